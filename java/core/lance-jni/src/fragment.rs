@@ -395,6 +395,7 @@ impl FromJObjectWithEnv<Fragment> for JObject<'_> {
             deletion_file,
             physical_rows: Some(physical_rows),
             row_id_meta,
+            partition_values: vec![],
         })
     }
 }
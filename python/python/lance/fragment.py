@@ -61,6 +61,9 @@ class FragmentMetadata:
         The deletion file, if any.
     row_id_meta : Optional[RowIdMeta]
         The row id metadata, if any.
+    partition_values : List[Optional[str]]
+        The partition values of the rows in this fragment, one for each field of
+        the dataset's partition spec. Empty if the dataset is not partitioned.
     """
 
     id: int
@@ -68,6 +71,7 @@ class FragmentMetadata:
     physical_rows: int
     deletion_file: Optional[DeletionFile] = None
     row_id_meta: Optional[RowIdMeta] = None
+    partition_values: List[Optional[str]] = field(default_factory=list)
 
     @property
     def num_deletions(self) -> int:
@@ -104,6 +108,7 @@ class FragmentMetadata:
             row_id_meta=(
                 self.row_id_meta.asdict() if self.row_id_meta is not None else None
             ),
+            partition_values=self.partition_values,
         )
 
     @staticmethod
@@ -124,6 +129,7 @@ class FragmentMetadata:
             physical_rows=json_data["physical_rows"],
             deletion_file=deletion_file,
             row_id_meta=row_id_meta,
+            partition_values=json_data.get("partition_values", []),
         )
 
 
@@ -608,6 +608,7 @@ impl FromPyObject<'_> for PyLance<Fragment> {
             deletion_file,
             physical_rows: ob.getattr("physical_rows")?.extract()?,
             row_id_meta,
+            partition_values: ob.getattr("partition_values")?.extract()?,
         }))
     }
 }
@@ -637,6 +638,7 @@ impl<'py> IntoPyObject<'py> for PyLance<&Fragment> {
             self.0.physical_rows,
             deletion_file,
             row_id_meta,
+            self.0.partition_values.clone(),
         ))
     }
 }
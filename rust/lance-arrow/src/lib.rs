@@ -739,11 +739,16 @@ fn project(struct_array: &StructArray, fields: &Fields) -> Result<StructArray> {
     for field in fields.iter() {
         if let Some(col) = struct_array.column_by_name(field.name()) {
             match field.data_type() {
-                // TODO handle list-of-struct
                 DataType::Struct(subfields) => {
                     let projected = project(col.as_struct(), subfields)?;
                     columns.push(Arc::new(projected));
                 }
+                DataType::List(item) => {
+                    columns.push(project_list(col.as_list::<i32>(), item)?);
+                }
+                DataType::LargeList(item) => {
+                    columns.push(project_list(col.as_list::<i64>(), item)?);
+                }
                 _ => {
                     columns.push(col.clone());
                 }
@@ -758,6 +763,38 @@ fn project(struct_array: &StructArray, fields: &Fields) -> Result<StructArray> {
     StructArray::try_new(fields.clone(), columns, None)
 }
 
+/// Project the items of a list (of structs) while keeping the offsets and validity
+fn project_list<O: OffsetSizeTrait>(
+    list_array: &GenericListArray<O>,
+    item: &FieldRef,
+) -> Result<ArrayRef> {
+    let values = list_array.values();
+    let values = match item.data_type() {
+        DataType::Struct(subfields) if subfields.is_empty() => {
+            Arc::new(project(values.as_struct(), subfields)?) as ArrayRef
+        }
+        DataType::Struct(subfields) => {
+            // The items of a list may be null so the validity needs to be kept
+            let struct_array = values.as_struct();
+            let projected = project(struct_array, subfields)?;
+            Arc::new(StructArray::try_new(
+                subfields.clone(),
+                projected.columns().to_vec(),
+                struct_array.nulls().cloned(),
+            )?)
+        }
+        DataType::List(inner) => project_list(values.as_list::<i32>(), inner)?,
+        DataType::LargeList(inner) => project_list(values.as_list::<i64>(), inner)?,
+        _ => values.clone(),
+    };
+    Ok(Arc::new(GenericListArray::<O>::try_new(
+        item.clone(),
+        list_array.offsets().clone(),
+        values,
+        list_array.nulls().cloned(),
+    )?))
+}
+
 fn lists_have_same_offsets_helper<T: OffsetSizeTrait>(left: &dyn Array, right: &dyn Array) -> bool {
     let left_list: &GenericListArray<T> = left.as_list();
     let right_list: &GenericListArray<T> = right.as_list();
@@ -1422,6 +1459,66 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_project_list_of_struct() {
+        let item_fields = Fields::from(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("price", DataType::Int32, true),
+        ]);
+        let items = StructArray::new(
+            item_fields.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+            ],
+            Some(vec![true, false, true].into()),
+        );
+        let list = ListArray::new(
+            Arc::new(Field::new("item", DataType::Struct(item_fields), true)),
+            OffsetBuffer::from_lengths([2, 0, 1]),
+            Arc::new(items),
+            Some(vec![true, false, true].into()),
+        );
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new(
+                "items",
+                list.data_type().clone(),
+                true,
+            )])),
+            vec![Arc::new(list)],
+        )
+        .unwrap();
+
+        let price_fields = Fields::from(vec![Field::new("price", DataType::Int32, true)]);
+        let projected_schema = Schema::new(vec![Field::new(
+            "items",
+            DataType::List(Arc::new(Field::new(
+                "item",
+                DataType::Struct(price_fields.clone()),
+                true,
+            ))),
+            true,
+        )]);
+        let projected = batch.project_by_schema(&projected_schema).unwrap();
+
+        let expected = ListArray::new(
+            Arc::new(Field::new(
+                "item",
+                DataType::Struct(price_fields.clone()),
+                true,
+            )),
+            OffsetBuffer::from_lengths([2, 0, 1]),
+            Arc::new(StructArray::new(
+                price_fields,
+                vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+                Some(vec![true, false, true].into()),
+            )),
+            Some(vec![true, false, true].into()),
+        );
+        assert_eq!(projected.schema().as_ref(), &projected_schema);
+        assert_eq!(projected.column(0).as_list::<i32>(), &expected);
+    }
+
     #[test]
     fn test_schema_project_by_schema() {
         let metadata = [("key".to_string(), "value".to_string())];
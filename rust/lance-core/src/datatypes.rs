@@ -7,7 +7,7 @@ use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 
 use arrow_array::ArrayRef;
-use arrow_schema::{DataType, Field as ArrowField, Fields, TimeUnit};
+use arrow_schema::{DataType, Field as ArrowField, Fields, TimeUnit, UnionMode};
 use deepsize::DeepSizeOf;
 use lance_arrow::bfloat16::{
     is_bfloat16_field, ARROW_EXT_META_KEY, ARROW_EXT_NAME_KEY, BFLOAT16_EXT_NAME,
@@ -15,12 +15,21 @@ use lance_arrow::bfloat16::{
 use snafu::location;
 
 mod field;
+mod json;
 mod schema;
 
 use crate::{Error, Result};
 pub use field::{
     Encoding, Field, NullabilityComparison, OnTypeMismatch, SchemaCompareOptions, StorageClass,
-    LANCE_STORAGE_CLASS_SCHEMA_META_KEY,
+    LANCE_STORAGE_CLASS_SCHEMA_META_KEY, LANCE_UNION_TYPE_ID_META_KEY, UNION_TYPE_IDS_FIELD_NAME,
+};
+pub use json::{
+    format_shredded_paths, infer_shredded_paths, is_json_field, is_json_shred_requested,
+    json_path_text, json_shredded_column, json_shredded_paths, json_storage_fields,
+    parse_json_path, parse_shredded_paths, shred_json, shredded_path_text, unshred_json,
+    ShreddedPath, DEFAULT_JSON_SHRED_MAX_PATHS, DEFAULT_JSON_SHRED_MIN_FREQUENCY, JSON_EXT_NAME,
+    JSON_SHREDDED_PATHS_META_KEY, JSON_SHRED_META_KEY, JSON_TYPED_VALUE_FIELD_NAME,
+    JSON_VALUE_FIELD_NAME, JSON_VARIANT_FIELD_NAME,
 };
 pub use schema::{OnMissing, Projectable, Projection, Schema};
 
@@ -32,6 +41,19 @@ pub const PACKED_STRUCT_META_KEY: &str = "lance-encoding:packed";
 pub const STRUCTURAL_ENCODING_META_KEY: &str = "lance-encoding:structural-encoding";
 pub const STRUCTURAL_ENCODING_MINIBLOCK: &str = "miniblock";
 pub const STRUCTURAL_ENCODING_FULLZIP: &str = "fullzip";
+/// Opts a vector (fixed size list of float32 or float64) field into lossy storage
+///
+/// The vectors are decoded back to the declared type but the items only approximate
+/// the written values.  Vectors with null items are always stored losslessly.
+pub const VECTOR_COMPRESSION_META_KEY: &str = "lance-encoding:vector-compression";
+/// Stores each item as an IEEE half precision float (2 bytes)
+pub const VECTOR_COMPRESSION_FLOAT16: &str = "float16";
+/// Stores each item as a bfloat16 (2 bytes)
+pub const VECTOR_COMPRESSION_BFLOAT16: &str = "bfloat16";
+/// Scales each item to a byte between the minimum and maximum item of the page
+pub const VECTOR_COMPRESSION_INT8: &str = "int8";
+/// Stores the sign of each item (1 bit), items decode to 1.0 or -1.0
+pub const VECTOR_COMPRESSION_BINARY: &str = "binary";
 
 lazy_static::lazy_static! {
     pub static ref BLOB_DESC_FIELDS: Fields =
@@ -67,6 +89,26 @@ impl LogicalType {
     fn is_struct(&self) -> bool {
         self.0 == "struct"
     }
+
+    fn is_map(&self) -> bool {
+        self.0 == "map" || self.0 == "map:keys_sorted"
+    }
+
+    fn is_map_keys_sorted(&self) -> bool {
+        self.0 == "map:keys_sorted"
+    }
+
+    fn is_union(&self) -> bool {
+        self.0 == "union:sparse" || self.0 == "union:dense"
+    }
+
+    fn union_mode(&self) -> UnionMode {
+        if self.0 == "union:dense" {
+            UnionMode::Dense
+        } else {
+            UnionMode::Sparse
+        }
+    }
 }
 
 impl From<&str> for LogicalType {
@@ -151,6 +193,15 @@ impl TryFrom<&DataType> for LogicalType {
                 DataType::Struct(_) => "large_list.struct".to_string(),
                 _ => "large_list".to_string(),
             },
+            DataType::Map(_, keys_sorted) => {
+                if *keys_sorted {
+                    "map:keys_sorted".to_string()
+                } else {
+                    "map".to_string()
+                }
+            }
+            DataType::Union(_, UnionMode::Sparse) => "union:sparse".to_string(),
+            DataType::Union(_, UnionMode::Dense) => "union:dense".to_string(),
             DataType::FixedSizeList(field, len) => {
                 if is_bfloat16_field(field) {
                     // Don't want to directly use `blfoat16`, in case a built-in type is added
@@ -18,14 +18,15 @@ use arrow_array::{
     },
     ArrayRef,
 };
-use arrow_schema::{DataType, Field as ArrowField};
+use arrow_schema::{DataType, Field as ArrowField, UnionFields};
 use deepsize::DeepSizeOf;
 use lance_arrow::{bfloat16::ARROW_EXT_NAME_KEY, *};
 use snafu::location;
 
 use super::{
+    is_json_field, json_shredded_paths, json_storage_fields,
     schema::{compare_fields, explain_fields_difference},
-    Dictionary, LogicalType, Projection,
+    Dictionary, LogicalType, Projection, JSON_SHREDDED_PATHS_META_KEY, JSON_VARIANT_FIELD_NAME,
 };
 use crate::{Error, Result};
 
@@ -39,6 +40,15 @@ pub const LANCE_STORAGE_CLASS_SCHEMA_META_KEY: &str = "lance-schema:storage-clas
 /// (3) The field must not be within a list type.
 pub const LANCE_UNENFORCED_PRIMARY_KEY: &str = "lance-schema:unenforced-primary-key";
 
+/// The name of the child field that stores the type ids of a union field.
+///
+/// Arrow unions don't have such a child but we need a field for the type ids
+/// column.  It is always the first child of a union field.
+pub const UNION_TYPE_IDS_FIELD_NAME: &str = "__type_ids";
+
+/// Field metadata key recording the type id of each child of a union field.
+pub const LANCE_UNION_TYPE_ID_META_KEY: &str = "lance-schema:union-type-id";
+
 #[derive(Debug, Default)]
 pub enum NullabilityComparison {
     // If the nullabilities don't match then the fields don't match
@@ -153,6 +163,15 @@ impl Field {
 
     /// Returns arrow data type.
     pub fn data_type(&self) -> DataType {
+        if self.is_shredded_json() {
+            // A shredded JSON field is only a string if all of it is loaded, a projection
+            // of some of the shredded paths is a struct of those paths.
+            return if self.child(JSON_VARIANT_FIELD_NAME).is_some() {
+                DataType::Utf8
+            } else {
+                DataType::Struct(self.children.iter().map(ArrowField::from).collect())
+            };
+        }
         match &self.logical_type {
             lt if lt.is_list() => DataType::List(Arc::new(ArrowField::from(&self.children[0]))),
             lt if lt.is_large_list() => {
@@ -161,6 +180,34 @@ impl Field {
             lt if lt.is_struct() => {
                 DataType::Struct(self.children.iter().map(ArrowField::from).collect())
             }
+            // Only the keys or only the values of a map is not a valid map, such a
+            // projection is read as a list of the entries instead.
+            lt if lt.is_map() && self.children[0].children.len() != 2 => {
+                DataType::List(Arc::new(ArrowField::from(&self.children[0])))
+            }
+            lt if lt.is_map() => DataType::Map(
+                Arc::new(ArrowField::from(&self.children[0])),
+                lt.is_map_keys_sorted(),
+            ),
+            lt if lt.is_union() => {
+                let (type_ids, fields): (Vec<_>, Vec<_>) = self
+                    .children
+                    .iter()
+                    .filter(|child| child.name != UNION_TYPE_IDS_FIELD_NAME)
+                    .enumerate()
+                    .map(|(idx, child)| {
+                        let type_id = child
+                            .metadata
+                            .get(LANCE_UNION_TYPE_ID_META_KEY)
+                            .and_then(|type_id| type_id.parse::<i8>().ok())
+                            .unwrap_or(idx as i8);
+                        let mut field = ArrowField::from(child);
+                        field.metadata_mut().remove(LANCE_UNION_TYPE_ID_META_KEY);
+                        (type_id, field)
+                    })
+                    .unzip();
+                DataType::Union(UnionFields::new(type_ids, fields), lt.union_mode())
+            }
             lt => DataType::try_from(lt).unwrap(),
         }
     }
@@ -170,6 +217,11 @@ impl Field {
             || self.children.iter().any(Self::has_dictionary_types)
     }
 
+    /// True if this is a JSON field whose documents are shredded into sub-columns
+    pub fn is_shredded_json(&self) -> bool {
+        !self.children.is_empty() && self.metadata.contains_key(JSON_SHREDDED_PATHS_META_KEY)
+    }
+
     pub fn is_default_storage(&self) -> bool {
         self.storage_class == StorageClass::Default
     }
@@ -251,11 +303,12 @@ impl Field {
     }
 
     pub fn apply_projection(&self, projection: &Projection) -> Option<Self> {
-        let children = self
-            .children
-            .iter()
-            .filter_map(|c| c.apply_projection(projection))
-            .collect::<Vec<_>>();
+        let children = self.with_union_type_ids(
+            self.children
+                .iter()
+                .filter_map(|c| c.apply_projection(projection))
+                .collect::<Vec<_>>(),
+        );
 
         // The following case is invalid:
         // - This is a nested field (has children)
@@ -464,15 +517,32 @@ impl Field {
                 let list_arr = arr.as_list::<i64>();
                 self.children[0].set_dictionary(list_arr.values());
             }
+            DataType::Map(_, _) => {
+                let entries: ArrayRef = Arc::new(arr.as_map().entries().clone());
+                self.children[0].set_dictionary(&entries);
+            }
             _ => {
                 // Field types that don't support dictionaries
             }
         }
     }
 
+    /// True if a column path continues through the items of this list
+    ///
+    /// The items of a list are not named in column paths, `tags.name` refers to the
+    /// `name` field of the items of a `tags` list of structs.  Naming the items
+    /// (e.g. `tags.item.name`) still works.
+    fn is_list_path(&self, name: &str) -> bool {
+        (self.logical_type.is_list() || self.logical_type.is_large_list())
+            && self.children.len() == 1
+            && self.children[0].name != name
+    }
+
     pub fn sub_field(&self, path_components: &[&str]) -> Option<&Self> {
         if path_components.is_empty() {
             Some(self)
+        } else if self.is_list_path(path_components[0]) {
+            self.children[0].sub_field(path_components)
         } else {
             let first = path_components[0];
             self.children
@@ -485,6 +555,8 @@ impl Field {
     pub fn sub_field_mut(&mut self, path_components: &[&str]) -> Option<&mut Self> {
         if path_components.is_empty() {
             Some(self)
+        } else if self.is_list_path(path_components[0]) {
+            self.children[0].sub_field_mut(path_components)
         } else {
             let first = path_components[0];
             self.children
@@ -511,6 +583,8 @@ impl Field {
         if path_components.is_empty() {
             // Project stops here, copy all the remaining children.
             f.children.clone_from(&self.children)
+        } else if self.is_list_path(path_components[0]) {
+            f.children.push(self.children[0].project(path_components)?);
         } else {
             let first = path_components[0];
             for c in self.children.as_slice() {
@@ -520,6 +594,7 @@ impl Field {
                     break;
                 }
             }
+            f.children = self.with_union_type_ids(f.children);
         }
         Ok(f)
     }
@@ -531,11 +606,12 @@ impl Field {
     ///
     /// Returns None if the field itself does not match the filter.
     pub fn project_by_filter<F: Fn(&Self) -> bool>(&self, filter: &F) -> Option<Self> {
-        let children = self
-            .children
-            .iter()
-            .filter_map(|c| c.project_by_filter(filter))
-            .collect::<Vec<_>>();
+        let children = self.with_union_type_ids(
+            self.children
+                .iter()
+                .filter_map(|c| c.project_by_filter(filter))
+                .collect::<Vec<_>>(),
+        );
         if !children.is_empty() || filter(self) {
             Some(Self {
                 children,
@@ -566,11 +642,12 @@ impl Field {
     /// If the ids are `[2]`, then this will include the parent `0` and the
     /// child `3`.
     pub(crate) fn project_by_ids(&self, ids: &[i32], include_all_children: bool) -> Option<Self> {
-        let children = self
-            .children
-            .iter()
-            .filter_map(|c| c.project_by_ids(ids, include_all_children))
-            .collect::<Vec<_>>();
+        let children = self.with_union_type_ids(
+            self.children
+                .iter()
+                .filter_map(|c| c.project_by_ids(ids, include_all_children))
+                .collect::<Vec<_>>(),
+        );
         if ids.contains(&self.id) && (children.is_empty() || include_all_children) {
             Some(self.clone())
         } else if !children.is_empty() {
@@ -583,6 +660,18 @@ impl Field {
         }
     }
 
+    /// A union needs its type ids to be decoded and so, if any children of a union
+    /// are projected, the type ids child is projected as well.
+    fn with_union_type_ids(&self, mut children: Vec<Self>) -> Vec<Self> {
+        if self.logical_type.is_union()
+            && !children.is_empty()
+            && children[0].name != UNION_TYPE_IDS_FIELD_NAME
+        {
+            children.insert(0, self.children[0].clone());
+        }
+        children
+    }
+
     /// Project by a field.
     ///
     pub fn project_by_field(&self, other: &Self, on_type_mismatch: OnTypeMismatch) -> Result<Self> {
@@ -597,6 +686,24 @@ impl Field {
         };
 
         match (self.data_type(), other.data_type()) {
+            _ if self.is_shredded_json() && other.is_shredded_json() => {
+                let mut fields = vec![];
+                for other_field in other.children.iter() {
+                    let Some(child) = self.child(&other_field.name) else {
+                        return Err(Error::Schema {
+                            message: format!(
+                                "Attempt to project non-existed field: {} on {}",
+                                other_field.name, self,
+                            ),
+                            location: location!(),
+                        });
+                    };
+                    fields.push(child.project_by_field(other_field, on_type_mismatch)?);
+                }
+                let mut cloned = self.clone();
+                cloned.children = fields;
+                Ok(cloned)
+            }
             (DataType::Boolean, DataType::Boolean) => Ok(self.clone()),
             (dt, other_dt)
                 if (dt.is_primitive() && other_dt.is_primitive())
@@ -613,7 +720,8 @@ impl Field {
                 }
                 Ok(self.clone())
             }
-            (DataType::Struct(_), DataType::Struct(_)) => {
+            (DataType::Struct(_), DataType::Struct(_))
+            | (DataType::Union(_, _), DataType::Union(_, _)) => {
                 let mut fields = vec![];
                 for other_field in other.children.iter() {
                     let Some(child) = self.child(&other_field.name) else {
@@ -632,7 +740,8 @@ impl Field {
                 Ok(cloned)
             }
             (DataType::List(_), DataType::List(_))
-            | (DataType::LargeList(_), DataType::LargeList(_)) => {
+            | (DataType::LargeList(_), DataType::LargeList(_))
+            | (DataType::Map(_, _), DataType::Map(_, _) | DataType::List(_)) => {
                 let projected =
                     self.children[0].project_by_field(&other.children[0], on_type_mismatch)?;
                 let mut cloned = self.clone();
@@ -676,6 +785,9 @@ impl Field {
         if split.is_empty() {
             return true;
         }
+        if self.is_list_path(split[0]) {
+            return self.children[0].resolve(split, fields);
+        }
         let first = split.pop_front().unwrap();
         if let Some(child) = self.children.iter().find(|c| c.name == first) {
             child.resolve(split, fields)
@@ -699,8 +811,13 @@ impl Field {
 
         if matches!(
             (&self_type, &other_type),
-            (DataType::Struct(_), DataType::Struct(_)) | (DataType::List(_), DataType::List(_))
-        ) {
+            (DataType::Struct(_), DataType::Struct(_))
+                | (DataType::List(_), DataType::List(_))
+                | (DataType::Map(_, _), DataType::Map(_, _) | DataType::List(_))
+                | (DataType::List(_), DataType::Map(_, _))
+                | (DataType::Union(_, _), DataType::Union(_, _))
+        ) || (self.is_shredded_json() && other.is_shredded_json())
+        {
             let children = self
                 .children
                 .iter()
@@ -796,7 +913,19 @@ impl Field {
     /// Merge the children of other field into this one.
     pub(super) fn merge(&mut self, other: &Self) -> Result<()> {
         match (self.data_type(), other.data_type()) {
-            (DataType::Struct(_), DataType::Struct(_)) => {
+            // A shredded JSON field may be a string or a struct, depending on how
+            // much of it is projected, but it is always merged like a struct
+            _ if self.is_shredded_json() && other.is_shredded_json() => {
+                for other_child in other.children.as_slice() {
+                    if let Some(field) = self.child_mut(&other_child.name) {
+                        field.merge(other_child)?;
+                    } else {
+                        self.children.push(other_child.clone());
+                    }
+                }
+            }
+            (DataType::Struct(_), DataType::Struct(_))
+            | (DataType::Union(_, _), DataType::Union(_, _)) => {
                 for other_child in other.children.as_slice() {
                     if let Some(field) = self.child_mut(&other_child.name) {
                         field.merge(other_child)?;
@@ -806,7 +935,9 @@ impl Field {
                 }
             }
             (DataType::List(_), DataType::List(_))
-            | (DataType::LargeList(_), DataType::LargeList(_)) => {
+            | (DataType::LargeList(_), DataType::LargeList(_))
+            | (DataType::Map(_, _), DataType::Map(_, _) | DataType::List(_))
+            | (DataType::List(_), DataType::Map(_, _)) => {
                 self.children[0].merge(&other.children[0])?;
             }
             (
@@ -953,6 +1084,27 @@ impl TryFrom<&ArrowField> for Field {
                 .collect::<Result<_>>()?,
             DataType::List(item) => vec![Self::try_from(item.as_ref())?],
             DataType::LargeList(item) => vec![Self::try_from(item.as_ref())?],
+            DataType::Map(entries, _) => vec![Self::try_from(entries.as_ref())?],
+            DataType::Union(fields, _) => {
+                let type_ids = ArrowField::new(UNION_TYPE_IDS_FIELD_NAME, DataType::Int8, false);
+                let mut children = vec![Self::try_from(&type_ids)?];
+                for (type_id, field) in fields.iter() {
+                    let mut child = Self::try_from(field.as_ref())?;
+                    child.metadata.insert(
+                        LANCE_UNION_TYPE_ID_META_KEY.to_string(),
+                        type_id.to_string(),
+                    );
+                    children.push(child);
+                }
+                children
+            }
+            DataType::Utf8 if is_json_field(field) => match json_shredded_paths(field)? {
+                Some(paths) => json_storage_fields(&paths)
+                    .iter()
+                    .map(|f| Self::try_from(f.as_ref()))
+                    .collect::<Result<_>>()?,
+                None => vec![],
+            },
             _ => vec![],
         };
         let storage_class = field
@@ -977,7 +1129,9 @@ impl TryFrom<&ArrowField> for Field {
                 dt if dt.is_binary_like() => Some(Encoding::VarBinary),
                 DataType::Dictionary(_, _) => Some(Encoding::Dictionary),
                 // Use plain encoder to store the offsets of list.
-                DataType::List(_) | DataType::LargeList(_) => Some(Encoding::Plain),
+                DataType::List(_) | DataType::LargeList(_) | DataType::Map(_, _) => {
+                    Some(Encoding::Plain)
+                }
                 _ => None,
             },
             metadata: field.metadata().clone(),
@@ -1020,7 +1174,9 @@ mod tests {
     use super::*;
 
     use arrow_array::{DictionaryArray, StringArray, UInt32Array};
-    use arrow_schema::{Fields, TimeUnit};
+    use arrow_schema::{Fields, TimeUnit, UnionMode};
+
+    use crate::datatypes::{ShreddedPath, JSON_EXT_NAME};
 
     #[test]
     fn arrow_field_to_field() {
@@ -1132,6 +1288,157 @@ mod tests {
         assert_eq!(ArrowField::from(&field), arrow_field);
     }
 
+    #[test]
+    fn test_map_type() {
+        let entries = ArrowField::new(
+            "entries",
+            DataType::Struct(Fields::from(vec![
+                ArrowField::new("keys", DataType::Utf8, false),
+                ArrowField::new("values", DataType::Utf8, true),
+            ])),
+            false,
+        );
+        for keys_sorted in [false, true] {
+            let arrow_field = ArrowField::new(
+                "attrs",
+                DataType::Map(Arc::new(entries.clone()), keys_sorted),
+                true,
+            );
+            let field = Field::try_from(&arrow_field).unwrap();
+            assert_eq!(
+                field.logical_type.0,
+                if keys_sorted {
+                    "map:keys_sorted"
+                } else {
+                    "map"
+                }
+            );
+            assert_eq!(field.children.len(), 1);
+            assert_eq!(field.children[0].children.len(), 2);
+            assert_eq!(&field.data_type(), arrow_field.data_type());
+            assert_eq!(ArrowField::from(&field), arrow_field);
+        }
+    }
+
+    #[test]
+    fn test_map_projection() {
+        let entries = ArrowField::new(
+            "entries",
+            DataType::Struct(Fields::from(vec![
+                ArrowField::new("keys", DataType::Utf8, false),
+                ArrowField::new("values", DataType::Int32, true),
+            ])),
+            false,
+        );
+        let arrow_field = ArrowField::new("attrs", DataType::Map(Arc::new(entries), false), true);
+        let field = Field::try_from(&arrow_field).unwrap();
+        let keys_id = field.children[0].children[0].id;
+
+        // Only the keys is read as a list of key structs
+        let keys = field.project_by_ids(&[keys_id], false).unwrap();
+        assert_eq!(
+            keys.data_type(),
+            DataType::List(Arc::new(ArrowField::new(
+                "entries",
+                DataType::Struct(Fields::from(vec![ArrowField::new(
+                    "keys",
+                    DataType::Utf8,
+                    false
+                )])),
+                false,
+            )))
+        );
+
+        // Both projections together are a map again
+        let mut merged = keys.clone();
+        merged.merge(&field).unwrap();
+        assert_eq!(&merged.data_type(), arrow_field.data_type());
+        assert_eq!(
+            field
+                .project_by_field(&keys, OnTypeMismatch::Error)
+                .unwrap(),
+            keys
+        );
+    }
+
+    #[test]
+    fn test_union_type() {
+        let union_fields = UnionFields::new(
+            vec![0, 3],
+            vec![
+                ArrowField::new("a", DataType::Int32, true),
+                ArrowField::new("b", DataType::Utf8, true),
+            ],
+        );
+        for (mode, logical_type) in [
+            (UnionMode::Sparse, "union:sparse"),
+            (UnionMode::Dense, "union:dense"),
+        ] {
+            let arrow_field =
+                ArrowField::new("u", DataType::Union(union_fields.clone(), mode), false);
+            let field = Field::try_from(&arrow_field).unwrap();
+            assert_eq!(field.logical_type.0, logical_type);
+            assert_eq!(field.children.len(), 3);
+            assert_eq!(field.children[0].name, UNION_TYPE_IDS_FIELD_NAME);
+            assert_eq!(field.children[0].data_type(), DataType::Int8);
+            assert_eq!(&field.data_type(), arrow_field.data_type());
+            assert_eq!(ArrowField::from(&field), arrow_field);
+
+            // Projecting a single child keeps the type ids and the child's type id
+            let projected = field.project(&["b"]).unwrap();
+            assert_eq!(projected.children.len(), 2);
+            assert_eq!(projected.children[0].name, UNION_TYPE_IDS_FIELD_NAME);
+            assert_eq!(
+                projected.data_type(),
+                DataType::Union(
+                    UnionFields::new(vec![3], vec![ArrowField::new("b", DataType::Utf8, true)]),
+                    mode
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_shredded_json_type() {
+        let arrow_field = ArrowField::new("payload", DataType::Utf8, true).with_metadata(
+            [
+                (ARROW_EXT_NAME_KEY.to_string(), JSON_EXT_NAME.to_string()),
+                (
+                    JSON_SHREDDED_PATHS_META_KEY.to_string(),
+                    "user.id:int64,score:double".to_string(),
+                ),
+            ]
+            .into(),
+        );
+        let field = Field::try_from(&arrow_field).unwrap();
+        assert!(field.is_shredded_json());
+        assert_eq!(field.logical_type.0, "string");
+        assert_eq!(field.children.len(), 3);
+        assert_eq!(field.children[0].name, JSON_VARIANT_FIELD_NAME);
+        assert_eq!(field.data_type(), DataType::Utf8);
+        assert_eq!(ArrowField::from(&field), arrow_field);
+
+        // Projecting a path gives a struct of just that path
+        let projected = field.project(&["user", "id"]).unwrap();
+        let storage = json_storage_fields(&[ShreddedPath::new(["user", "id"], DataType::Int64)]);
+        assert_eq!(
+            projected.data_type(),
+            DataType::Struct(storage.iter().skip(1).cloned().collect())
+        );
+
+        // Merging a path into the whole field keeps the whole field
+        let mut merged = field.project(&[]).unwrap();
+        merged.merge(&projected).unwrap();
+        assert_eq!(merged, field);
+
+        // Unshredded JSON is just a string
+        let plain = ArrowField::new("payload", DataType::Utf8, true)
+            .with_metadata([(ARROW_EXT_NAME_KEY.to_string(), JSON_EXT_NAME.to_string())].into());
+        let plain = Field::try_from(&plain).unwrap();
+        assert!(!plain.is_shredded_json());
+        assert!(plain.children.is_empty());
+    }
+
     #[test]
     fn test_project_by_field_null_type() {
         let f1: Field = ArrowField::new("a", DataType::Null, true)
@@ -0,0 +1,940 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! JSON fields
+//!
+//! A JSON field is a string field tagged with the [`JSON_EXT_NAME`] extension.
+//! When a JSON field is written to a 2.1 file the frequently occurring paths of the
+//! documents can be "shredded" into typed sub-columns.  Shredding is opt-in, either
+//! by listing the paths in the field metadata under [`JSON_SHREDDED_PATHS_META_KEY`]
+//! or by setting [`JSON_SHRED_META_KEY`] to `true` to pick the paths from the first
+//! batch of data.  A shredded field is stored as a struct:
+//!
+//! ```text
+//! payload: struct {
+//!   __variant: large_binary            // the documents without the shredded values
+//!   user: struct {
+//!     id: struct {                     // one struct per shredded path
+//!       typed_value: int64             // the value, if it has the shredded type
+//!       value: large_binary            // the JSON text of the value otherwise
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! The value of each shredded path is cut out of the document text and replaced by
+//! a placeholder, `\0<index of the path>\0`, which can't occur in valid JSON.  A
+//! value is only stored in `typed_value` if writing it back out gives the original
+//! text, otherwise its text is stored in `value`.  Reading the whole field puts the
+//! values back in place, so it returns the original documents byte for byte.
+//! Reading a single path only needs the two columns of that path.  Documents that
+//! are not valid JSON, or that repeat a key along a shredded path, are stored as
+//! they are and have no shredded values.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow_array::{
+    builder::{LargeBinaryBuilder, StringBuilder},
+    cast::AsArray,
+    types::{Float64Type, Int64Type},
+    Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray, StructArray,
+};
+use arrow_schema::{DataType, Field as ArrowField, Fields};
+use lance_arrow::bfloat16::ARROW_EXT_NAME_KEY;
+use serde_json::Value;
+use snafu::location;
+
+use crate::{Error, Result};
+
+/// The extension name that marks a string field as JSON
+pub const JSON_EXT_NAME: &str = "lance.json";
+/// The field metadata key that asks for the paths of a JSON field to be picked from the data
+///
+/// Only the value `true` turns shredding on.
+pub const JSON_SHRED_META_KEY: &str = "lance-encoding:json-shred";
+/// The field metadata key that lists the shredded paths of a JSON field
+///
+/// The value is a comma separated list of `path:type` pairs such as
+/// `user.id:int64,user.name:string`.
+pub const JSON_SHREDDED_PATHS_META_KEY: &str = "lance-encoding:json-shredded-paths";
+/// The name of the child that stores the documents without their shredded values
+pub const JSON_VARIANT_FIELD_NAME: &str = "__variant";
+/// The name of the child of a shredded path that stores values of the shredded type
+pub const JSON_TYPED_VALUE_FIELD_NAME: &str = "typed_value";
+/// The name of the child of a shredded path that stores values of any other type
+pub const JSON_VALUE_FIELD_NAME: &str = "value";
+
+/// The fraction of documents a path must appear in (with the same type) to be shredded
+pub const DEFAULT_JSON_SHRED_MIN_FREQUENCY: f64 = 0.5;
+/// The maximum number of paths that are shredded from a single JSON field
+pub const DEFAULT_JSON_SHRED_MAX_PATHS: usize = 32;
+
+/// A path of a JSON field that is stored in its own sub-column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShreddedPath {
+    /// The object keys leading to the value
+    pub path: Vec<String>,
+    /// The type of the shredded values, one of bool, int64, double or string
+    pub data_type: DataType,
+}
+
+impl ShreddedPath {
+    pub fn new(path: impl IntoIterator<Item = impl Into<String>>, data_type: DataType) -> Self {
+        Self {
+            path: path.into_iter().map(Into::into).collect(),
+            data_type,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self.data_type {
+            DataType::Boolean => "bool",
+            DataType::Int64 => "int64",
+            DataType::Float64 => "double",
+            DataType::Utf8 => "string",
+            _ => unreachable!("unsupported shredded type {}", self.data_type),
+        }
+    }
+}
+
+/// True if the arrow field is a string field with the JSON extension
+pub fn is_json_field(field: &ArrowField) -> bool {
+    field.data_type() == &DataType::Utf8
+        && field.metadata().get(ARROW_EXT_NAME_KEY).map(String::as_str) == Some(JSON_EXT_NAME)
+}
+
+/// True if the arrow field is a JSON field that asks for its paths to be picked from the data
+pub fn is_json_shred_requested(field: &ArrowField) -> bool {
+    is_json_field(field)
+        && field
+            .metadata()
+            .get(JSON_SHRED_META_KEY)
+            .map(String::as_str)
+            == Some("true")
+}
+
+/// The shredded paths of a JSON field, if the field has been shredded
+pub fn json_shredded_paths(field: &ArrowField) -> Result<Option<Vec<ShreddedPath>>> {
+    field
+        .metadata()
+        .get(JSON_SHREDDED_PATHS_META_KEY)
+        .map(|paths| parse_shredded_paths(paths))
+        .transpose()
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(['.', ',', ':', '[', ']', '$'])
+}
+
+/// Parse a JSON path of the form `$.a.b` into its keys
+///
+/// Only object keys are supported (no array indices or wildcards).
+pub fn parse_json_path(path: &str) -> Option<Vec<String>> {
+    let keys = path
+        .strip_prefix("$.")?
+        .split('.')
+        .map(String::from)
+        .collect::<Vec<_>>();
+    keys.iter().all(|key| is_valid_key(key)).then_some(keys)
+}
+
+/// Parse the value of the [`JSON_SHREDDED_PATHS_META_KEY`] metadata
+pub fn parse_shredded_paths(value: &str) -> Result<Vec<ShreddedPath>> {
+    let invalid = |reason: &str| {
+        Error::invalid_input(
+            format!("Invalid shredded JSON paths '{}': {}", value, reason),
+            location!(),
+        )
+    };
+    let mut paths: Vec<ShreddedPath> = Vec::new();
+    for entry in value.split(',').filter(|entry| !entry.is_empty()) {
+        let (path, type_name) = entry
+            .rsplit_once(':')
+            .ok_or_else(|| invalid("expected path:type"))?;
+        let data_type = match type_name {
+            "bool" => DataType::Boolean,
+            "int64" => DataType::Int64,
+            "double" => DataType::Float64,
+            "string" => DataType::Utf8,
+            _ => return Err(invalid(&format!("unsupported type {}", type_name))),
+        };
+        let path = path.split('.').map(String::from).collect::<Vec<_>>();
+        if !path.iter().all(|key| is_valid_key(key)) {
+            return Err(invalid(&format!("invalid path {}", path.join("."))));
+        }
+        // A shredded path can't be inside another shredded path
+        if paths.iter().any(|other| {
+            let len = other.path.len().min(path.len());
+            other.path[..len] == path[..len]
+        }) {
+            return Err(invalid(&format!("overlapping path {}", path.join("."))));
+        }
+        paths.push(ShreddedPath { path, data_type });
+    }
+    Ok(paths)
+}
+
+/// Format shredded paths as the value of the [`JSON_SHREDDED_PATHS_META_KEY`] metadata
+pub fn format_shredded_paths(paths: &[ShreddedPath]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("{}:{}", path.path.join("."), path.type_name()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn shredded_path_fields(data_type: &DataType) -> Fields {
+    Fields::from(vec![
+        ArrowField::new(JSON_TYPED_VALUE_FIELD_NAME, data_type.clone(), true),
+        ArrowField::new(JSON_VALUE_FIELD_NAME, DataType::LargeBinary, true),
+    ])
+}
+
+/// Group paths by their first key, in order of first appearance
+fn group_by_first_key<'a>(
+    paths: &[(&'a [String], &'a DataType)],
+) -> Vec<(&'a str, Vec<(&'a [String], &'a DataType)>)> {
+    let mut groups: Vec<(&str, Vec<_>)> = Vec::new();
+    for &(path, data_type) in paths {
+        let rest = (&path[1..], data_type);
+        if let Some((_, group)) = groups.iter_mut().find(|(key, _)| *key == path[0]) {
+            group.push(rest);
+        } else {
+            groups.push((path[0].as_str(), vec![rest]));
+        }
+    }
+    groups
+}
+
+fn path_fields(paths: &[(&[String], &DataType)]) -> Fields {
+    group_by_first_key(paths)
+        .into_iter()
+        .map(|(key, group)| {
+            let children = match group.as_slice() {
+                [(rest, data_type)] if rest.is_empty() => shredded_path_fields(data_type),
+                _ => path_fields(&group),
+            };
+            ArrowField::new(key, DataType::Struct(children), true)
+        })
+        .collect()
+}
+
+/// The fields of the struct that a shredded JSON field is stored as
+pub fn json_storage_fields(paths: &[ShreddedPath]) -> Fields {
+    let paths = paths
+        .iter()
+        .map(|path| (path.path.as_slice(), &path.data_type))
+        .collect::<Vec<_>>();
+    let path_fields = path_fields(&paths);
+    std::iter::once(Arc::new(ArrowField::new(
+        JSON_VARIANT_FIELD_NAME,
+        DataType::LargeBinary,
+        true,
+    )))
+    .chain(path_fields.iter().cloned())
+    .collect()
+}
+
+fn matches_type(value: &Value, data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Boolean => value.is_boolean(),
+        DataType::Int64 => value.is_i64(),
+        DataType::Float64 => value.is_f64(),
+        DataType::Utf8 => value.is_string(),
+        _ => false,
+    }
+}
+
+fn lookup<'a>(doc: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(doc, |value, key| value.as_object()?.get(key))
+}
+
+fn values_to_array(values: &[Option<Value>], data_type: &DataType) -> ArrayRef {
+    let values = values.iter().map(Option::as_ref);
+    match data_type {
+        DataType::Boolean => Arc::new(
+            values
+                .map(|value| value.and_then(Value::as_bool))
+                .collect::<BooleanArray>(),
+        ),
+        DataType::Int64 => Arc::new(
+            values
+                .map(|value| value.and_then(Value::as_i64))
+                .collect::<Int64Array>(),
+        ),
+        DataType::Float64 => Arc::new(
+            values
+                .map(|value| value.and_then(Value::as_f64))
+                .collect::<Float64Array>(),
+        ),
+        DataType::Utf8 => Arc::new(
+            values
+                .map(|value| value.and_then(Value::as_str))
+                .collect::<StringArray>(),
+        ),
+        _ => unreachable!("unsupported shredded type {}", data_type),
+    }
+}
+
+fn typed_value(array: &dyn Array, row: usize) -> Option<Value> {
+    if array.is_null(row) {
+        return None;
+    }
+    match array.data_type() {
+        DataType::Boolean => Some(Value::Bool(array.as_boolean().value(row))),
+        DataType::Int64 => Some(Value::from(array.as_primitive::<Int64Type>().value(row))),
+        DataType::Float64 => {
+            serde_json::Number::from_f64(array.as_primitive::<Float64Type>().value(row))
+                .map(Value::Number)
+        }
+        DataType::Utf8 => Some(Value::String(
+            array.as_string::<i32>().value(row).to_string(),
+        )),
+        data_type => unreachable!("unsupported shredded type {}", data_type),
+    }
+}
+
+/// The value of a shredded path in the given row
+fn shredded_value(column: &StructArray, row: usize) -> Result<Option<Value>> {
+    let typed = column
+        .column_by_name(JSON_TYPED_VALUE_FIELD_NAME)
+        .ok_or_else(missing_child)?;
+    if let Some(value) = typed_value(typed.as_ref(), row) {
+        return Ok(Some(value));
+    }
+    let fallback = column
+        .column_by_name(JSON_VALUE_FIELD_NAME)
+        .ok_or_else(missing_child)?
+        .as_binary::<i64>();
+    if fallback.is_null(row) {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::from_slice(fallback.value(row))?))
+    }
+}
+
+fn missing_child() -> Error {
+    Error::Internal {
+        message: "shredded JSON column is missing a child".to_string(),
+        location: location!(),
+    }
+}
+
+/// Find the struct that stores a shredded path
+///
+/// The storage may be the full storage struct or any projection of it.
+pub fn json_shredded_column<'a>(
+    storage: &'a StructArray,
+    path: &[String],
+) -> Option<&'a StructArray> {
+    path.iter().try_fold(storage, |array, key| {
+        array.column_by_name(key)?.as_struct_opt()
+    })
+}
+
+// Documents that are not valid JSON are treated as if they have no paths
+fn parse_document(doc: &str) -> Option<Value> {
+    serde_json::from_str(doc).ok()
+}
+
+/// Marks the start and end of the placeholder of a shredded value in the variant
+const PLACEHOLDER_DELIMITER: u8 = 0;
+
+/// Walks the text of a valid JSON document to find where values start and end
+struct JsonScanner<'a> {
+    doc: &'a [u8],
+    pos: usize,
+}
+
+impl JsonScanner<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        while self.doc.get(self.pos)?.is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        self.doc.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        (self.peek()? == byte).then(|| self.pos += 1)
+    }
+
+    fn string(&mut self) -> Option<Range<usize>> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        loop {
+            match *self.doc.get(self.pos)? {
+                b'"' => break,
+                b'\\' => self.pos += 2,
+                _ => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        Some(start..self.pos)
+    }
+
+    fn key(&mut self) -> Option<String> {
+        let span = self.string()?;
+        self.expect(b':')?;
+        serde_json::from_slice(&self.doc[span]).ok()
+    }
+
+    /// Skip over the next value
+    fn value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => self.string().map(|_| ()),
+            open @ (b'{' | b'[') => {
+                self.pos += 1;
+                let close = if open == b'{' { b'}' } else { b']' };
+                loop {
+                    match self.peek()? {
+                        byte if byte == close => break,
+                        b',' => self.pos += 1,
+                        _ if open == b'{' => {
+                            self.key()?;
+                            self.value()?;
+                        }
+                        _ => self.value()?,
+                    }
+                }
+                self.pos += 1;
+                Some(())
+            }
+            _ => {
+                while !matches!(
+                    self.doc.get(self.pos),
+                    None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+                Some(())
+            }
+        }
+    }
+
+    /// Find the spans of the shredded values in the object that starts at the
+    /// current position, `prefix` is the path of the object
+    fn shredded_spans(
+        &mut self,
+        paths: &[ShreddedPath],
+        prefix: &mut Vec<String>,
+        spans: &mut Vec<(usize, Range<usize>)>,
+    ) -> Option<()> {
+        self.expect(b'{')?;
+        let mut keys = Vec::new();
+        loop {
+            match self.peek()? {
+                b'}' => break,
+                b',' => self.pos += 1,
+                _ => {
+                    let key = self.key()?;
+                    // Only the last of a repeated key counts when the document is parsed
+                    if keys.contains(&key) {
+                        return None;
+                    }
+                    prefix.push(key.clone());
+                    keys.push(key);
+                    if let Some(idx) = paths.iter().position(|path| path.path == *prefix) {
+                        self.peek()?;
+                        let start = self.pos;
+                        self.value()?;
+                        spans.push((idx, start..self.pos));
+                    } else if self.peek()? == b'{'
+                        && paths.iter().any(|path| path.path.starts_with(prefix))
+                    {
+                        self.shredded_spans(paths, prefix, spans)?;
+                    } else {
+                        self.value()?;
+                    }
+                    prefix.pop();
+                }
+            }
+        }
+        self.pos += 1;
+        Some(())
+    }
+}
+
+/// The spans of the values of the shredded paths in the text of a valid JSON document
+///
+/// Returns `None` if the document repeats a key along a shredded path.
+fn shredded_spans(doc: &str, paths: &[ShreddedPath]) -> Option<Vec<(usize, Range<usize>)>> {
+    let mut scanner = JsonScanner {
+        doc: doc.as_bytes(),
+        pos: 0,
+    };
+    let mut spans = Vec::new();
+    if scanner.peek()? == b'{' {
+        scanner.shredded_spans(paths, &mut Vec::new(), &mut spans)?;
+    }
+    Some(spans)
+}
+
+/// Split JSON documents into the storage struct described by [`json_storage_fields`]
+///
+/// The values of the shredded paths are moved into their own columns, the rest
+/// of each document is stored in the variant column.
+pub fn shred_json(json: &StringArray, paths: &[ShreddedPath]) -> Result<StructArray> {
+    let mut variant = LargeBinaryBuilder::with_capacity(json.len(), json.value_data().len());
+    let mut typed = vec![Vec::with_capacity(json.len()); paths.len()];
+    let mut fallback = (0..paths.len())
+        .map(|_| LargeBinaryBuilder::with_capacity(json.len(), 0))
+        .collect::<Vec<_>>();
+    for doc in json.iter() {
+        let Some(doc) = doc else {
+            variant.append_null();
+            typed.iter_mut().for_each(|typed| typed.push(None));
+            fallback
+                .iter_mut()
+                .for_each(|fallback| fallback.append_null());
+            continue;
+        };
+        let mut spans = parse_document(doc)
+            .and_then(|parsed| Some((shredded_spans(doc, paths)?, parsed)))
+            .map_or_else(Vec::new, |(spans, parsed)| {
+                spans
+                    .into_iter()
+                    .filter_map(|(idx, span)| {
+                        let value = lookup(&parsed, &paths[idx].path)?.clone();
+                        Some((idx, span, value))
+                    })
+                    .collect()
+            });
+        spans.sort_by_key(|(_, span, _)| span.start);
+
+        let mut remainder = Vec::with_capacity(doc.len());
+        let mut values = vec![None; paths.len()];
+        let mut pos = 0;
+        for (idx, span, value) in spans {
+            remainder.extend_from_slice(&doc.as_bytes()[pos..span.start]);
+            remainder.push(PLACEHOLDER_DELIMITER);
+            remainder.extend_from_slice(idx.to_string().as_bytes());
+            remainder.push(PLACEHOLDER_DELIMITER);
+            pos = span.end;
+            values[idx] = Some((&doc[span], value));
+        }
+        remainder.extend_from_slice(&doc.as_bytes()[pos..]);
+        variant.append_value(remainder);
+
+        for (((path, value), typed), fallback) in
+            paths.iter().zip(values).zip(&mut typed).zip(&mut fallback)
+        {
+            match value {
+                // Only values that are written back out as they were can be typed
+                Some((text, value))
+                    if matches_type(&value, &path.data_type)
+                        && serde_json::to_string(&value)? == text =>
+                {
+                    typed.push(Some(value));
+                    fallback.append_null();
+                }
+                Some((text, _)) => {
+                    typed.push(None);
+                    fallback.append_value(text);
+                }
+                None => {
+                    typed.push(None);
+                    fallback.append_null();
+                }
+            }
+        }
+    }
+
+    let mut leaves = paths
+        .iter()
+        .zip(typed)
+        .zip(fallback)
+        .map(|((path, typed), mut fallback)| {
+            let leaf = StructArray::new(
+                shredded_path_fields(&path.data_type),
+                vec![
+                    values_to_array(&typed, &path.data_type),
+                    Arc::new(fallback.finish()),
+                ],
+                None,
+            );
+            (path.path.as_slice(), Arc::new(leaf) as ArrayRef)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let fields = json_storage_fields(paths);
+    let mut columns = vec![Arc::new(variant.finish()) as ArrayRef];
+    let mut prefix = Vec::new();
+    for field in fields.iter().skip(1) {
+        columns.push(assemble_column(field, &mut prefix, &mut leaves));
+    }
+    Ok(StructArray::try_new(fields, columns, None)?)
+}
+
+fn assemble_column(
+    field: &ArrowField,
+    prefix: &mut Vec<String>,
+    leaves: &mut BTreeMap<&[String], ArrayRef>,
+) -> ArrayRef {
+    prefix.push(field.name().clone());
+    let column = if let Some(leaf) = leaves.remove(prefix.as_slice()) {
+        leaf
+    } else {
+        let DataType::Struct(children) = field.data_type() else {
+            unreachable!()
+        };
+        let columns = children
+            .iter()
+            .map(|child| assemble_column(child, prefix, leaves))
+            .collect();
+        Arc::new(StructArray::new(children.clone(), columns, None))
+    };
+    prefix.pop();
+    column
+}
+
+/// The JSON text of a shredded value in the given row
+fn shredded_text(column: &StructArray, row: usize) -> Result<Option<Vec<u8>>> {
+    let typed = column
+        .column_by_name(JSON_TYPED_VALUE_FIELD_NAME)
+        .ok_or_else(missing_child)?;
+    if let Some(value) = typed_value(typed.as_ref(), row) {
+        return Ok(Some(serde_json::to_vec(&value)?));
+    }
+    let fallback = column
+        .column_by_name(JSON_VALUE_FIELD_NAME)
+        .ok_or_else(missing_child)?
+        .as_binary::<i64>();
+    Ok((!fallback.is_null(row)).then(|| fallback.value(row).to_vec()))
+}
+
+/// Put the shredded values of a document back in place of their placeholders
+fn fill_placeholders(remainder: &[u8], values: &[Option<Vec<u8>>]) -> Result<Vec<u8>> {
+    let corrupt = || Error::Internal {
+        message: "shredded JSON document has an invalid placeholder".to_string(),
+        location: location!(),
+    };
+    let mut doc = Vec::with_capacity(remainder.len());
+    let mut parts = remainder.split(|byte| *byte == PLACEHOLDER_DELIMITER);
+    doc.extend_from_slice(parts.next().unwrap_or_default());
+    while let Some(idx) = parts.next() {
+        let idx = std::str::from_utf8(idx)
+            .ok()
+            .and_then(|idx| idx.parse::<usize>().ok())
+            .ok_or_else(corrupt)?;
+        let value = values
+            .get(idx)
+            .and_then(Option::as_ref)
+            .ok_or_else(corrupt)?;
+        doc.extend_from_slice(value);
+        doc.extend_from_slice(parts.next().ok_or_else(corrupt)?);
+    }
+    Ok(doc)
+}
+
+/// Read the original JSON documents from the storage struct created by [`shred_json`]
+pub fn unshred_json(storage: &StructArray, paths: &[ShreddedPath]) -> Result<ArrayRef> {
+    let variant = storage
+        .column_by_name(JSON_VARIANT_FIELD_NAME)
+        .ok_or_else(missing_child)?
+        .as_binary::<i64>();
+    let columns = paths
+        .iter()
+        .map(|path| json_shredded_column(storage, &path.path).ok_or_else(missing_child))
+        .collect::<Result<Vec<_>>>()?;
+    let mut builder = StringBuilder::with_capacity(storage.len(), variant.value_data().len());
+    for (row, remainder) in variant.iter().enumerate() {
+        let Some(remainder) = remainder else {
+            builder.append_null();
+            continue;
+        };
+        let values = columns
+            .iter()
+            .map(|column| shredded_text(column, row))
+            .collect::<Result<Vec<_>>>()?;
+        // Documents without shredded values, including those that are not valid
+        // JSON, are stored as they are
+        let doc = if values.iter().all(Option::is_none) {
+            remainder.to_vec()
+        } else {
+            fill_placeholders(remainder, &values)?
+        };
+        builder.append_value(String::from_utf8(doc).map_err(|err| {
+            Error::invalid_input(
+                format!("Stored JSON document is not valid UTF-8: {}", err),
+                location!(),
+            )
+        })?);
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// The text of a JSON value, as returned by `json_get`
+///
+/// Strings are returned without quotes, `null` becomes a null and everything
+/// else is returned as JSON.
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// The text of the value at a path of each JSON document
+///
+/// Documents that are not valid JSON have no value, as they would if shredded.
+pub fn json_path_text(json: &StringArray, path: &[String]) -> Result<StringArray> {
+    Ok(json
+        .iter()
+        .map(|doc| {
+            let doc = parse_document(doc?)?;
+            lookup(&doc, path).and_then(value_text)
+        })
+        .collect())
+}
+
+/// The text of a shredded path, read from the struct of that path
+pub fn shredded_path_text(column: &StructArray) -> Result<StringArray> {
+    (0..column.len())
+        .map(|row| Ok(shredded_value(column, row)?.as_ref().and_then(value_text)))
+        .collect()
+}
+
+#[derive(Default)]
+struct PathStats {
+    bools: usize,
+    ints: usize,
+    floats: usize,
+    strings: usize,
+}
+
+impl PathStats {
+    fn record(&mut self, value: &Value) {
+        match value {
+            Value::Bool(_) => self.bools += 1,
+            Value::Number(number) if number.is_i64() => self.ints += 1,
+            Value::Number(number) if number.is_f64() => self.floats += 1,
+            Value::String(_) => self.strings += 1,
+            _ => {}
+        }
+    }
+
+    /// The most common type and the number of values with that type
+    ///
+    /// Ties go to the type listed first.
+    fn dominant_type(&self) -> (DataType, usize) {
+        [
+            (DataType::Int64, self.ints),
+            (DataType::Float64, self.floats),
+            (DataType::Boolean, self.bools),
+            (DataType::Utf8, self.strings),
+        ]
+        .into_iter()
+        .fold((DataType::Int64, 0), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+    }
+}
+
+fn collect_path_stats(
+    value: &Value,
+    prefix: &mut Vec<String>,
+    stats: &mut BTreeMap<Vec<String>, PathStats>,
+) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if !is_valid_key(key) {
+                    continue;
+                }
+                prefix.push(key.clone());
+                collect_path_stats(value, prefix, stats);
+                prefix.pop();
+            }
+        }
+        Value::Array(_) | Value::Null => {}
+        value => {
+            if !prefix.is_empty() {
+                stats.entry(prefix.clone()).or_default().record(value);
+            }
+        }
+    }
+}
+
+/// Pick the paths of a sample of JSON documents that are worth shredding
+///
+/// A path is shredded if it holds a scalar of the same type in at least
+/// `min_frequency` of the (valid) documents.  At most `max_paths` paths are
+/// picked, preferring the most frequent ones.
+pub fn infer_shredded_paths(
+    json: &StringArray,
+    min_frequency: f64,
+    max_paths: usize,
+) -> Vec<ShreddedPath> {
+    let mut stats = BTreeMap::new();
+    let mut num_docs = 0;
+    for doc in json.iter().flatten().filter_map(parse_document) {
+        num_docs += 1;
+        collect_path_stats(&doc, &mut Vec::new(), &mut stats);
+    }
+    if num_docs == 0 {
+        return Vec::new();
+    }
+
+    let mut candidates = stats
+        .into_iter()
+        .map(|(path, stats)| {
+            let (data_type, count) = stats.dominant_type();
+            (ShreddedPath { path, data_type }, count)
+        })
+        .filter(|(_, count)| *count as f64 / num_docs as f64 >= min_frequency)
+        .collect::<Vec<_>>();
+    // Most frequent first, ties are broken by path so the choice is deterministic
+    candidates.sort_by(|(a, a_count), (b, b_count)| {
+        b_count.cmp(a_count).then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut paths: Vec<ShreddedPath> = Vec::new();
+    for (candidate, _) in candidates {
+        if paths.len() == max_paths {
+            break;
+        }
+        // A key that holds a scalar in some documents and an object in others
+        // can only be shredded one way
+        let overlaps = paths.iter().any(|path| {
+            let len = path.path.len().min(candidate.path.len());
+            path.path[..len] == candidate.path[..len]
+        });
+        if !overlaps {
+            paths.push(candidate);
+        }
+    }
+    paths.sort_by(|a, b| a.path.cmp(&b.path));
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docs() -> StringArray {
+        StringArray::from(vec![
+            Some(r#"{"user": {"id": 1, "name": "a"}, "tags": ["x"]}"#),
+            None,
+            Some(r#"{"user": {"id": "two", "name": "b"}, "score": 1.5}"#),
+            Some(r#"{"user": {"name": "c", "admin": true}}"#),
+            Some(r#"[1, 2, 3]"#),
+            // Not valid JSON
+            Some(r#"{"user": {"id": 5"#),
+        ])
+    }
+
+    fn paths() -> Vec<ShreddedPath> {
+        vec![
+            ShreddedPath::new(["user", "id"], DataType::Int64),
+            ShreddedPath::new(["user", "name"], DataType::Utf8),
+            ShreddedPath::new(["score"], DataType::Float64),
+        ]
+    }
+
+    #[test]
+    fn test_shredded_paths_metadata() {
+        let value = format_shredded_paths(&paths());
+        assert_eq!(value, "user.id:int64,user.name:string,score:double");
+        assert_eq!(parse_shredded_paths(&value).unwrap(), paths());
+
+        assert!(parse_shredded_paths("user.id:int32").is_err());
+        assert!(parse_shredded_paths("user:string,user.id:int64").is_err());
+        assert!(parse_shredded_paths("user..id:int64").is_err());
+
+        assert_eq!(
+            parse_json_path("$.user.id"),
+            Some(vec!["user".to_string(), "id".to_string()])
+        );
+        assert_eq!(parse_json_path("user.id"), None);
+        assert_eq!(parse_json_path("$.user[0]"), None);
+    }
+
+    #[test]
+    fn test_shred_round_trip() {
+        let docs = docs();
+        let storage = shred_json(&docs, &paths()).unwrap();
+        assert_eq!(storage.fields(), &json_storage_fields(&paths()));
+
+        let ids = json_shredded_column(&storage, &paths()[0].path).unwrap();
+        assert_eq!(
+            ids.column(0).as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![Some(1), None, None, None, None, None])
+        );
+        // Values of another type are kept aside
+        assert_eq!(ids.column(1).null_count(), 5);
+
+        // Only the rest of the documents is kept in the variant
+        let variant = storage.column(0).as_binary::<i64>();
+        assert_eq!(
+            variant.value(0),
+            b"{\"user\": {\"id\": \x000\x00, \"name\": \x001\x00}, \"tags\": [\"x\"]}"
+        );
+        assert_eq!(variant.value(5), docs.value(5).as_bytes());
+
+        let round_trip = unshred_json(&storage, &paths()).unwrap();
+        assert_eq!(round_trip.as_string::<i32>(), &docs);
+
+        // Key order, whitespace and numbers that don't fit a double are kept as written
+        let docs = StringArray::from(vec![
+            r#"{"z": 1,  "a": {"id": 12345678901234567890123, "y": 0.10000000000000000001}}"#,
+            r#"{"z": 1.50, "a": {"id" : 7 }, "b": "\u0041"}"#,
+            // Only the last of a repeated key is seen when the document is parsed
+            r#"{"z": 1.0, "z": 2.0}"#,
+        ]);
+        let paths = vec![
+            ShreddedPath::new(["a", "id"], DataType::Int64),
+            ShreddedPath::new(["z"], DataType::Float64),
+        ];
+        let storage = shred_json(&docs, &paths).unwrap();
+        let ids = json_shredded_column(&storage, &paths[0].path).unwrap();
+        assert_eq!(
+            ids.column(0).as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![None, Some(7), None])
+        );
+        // 1.50 would be written back as 1.5, so it is kept as text
+        let z = json_shredded_column(&storage, &paths[1].path).unwrap();
+        assert_eq!(z.column(0).null_count(), 3);
+        assert_eq!(z.column(1).as_binary::<i64>().value(1), b"1.50");
+        let round_trip = unshred_json(&storage, &paths).unwrap();
+        assert_eq!(round_trip.as_string::<i32>(), &docs);
+    }
+
+    #[test]
+    fn test_path_text() {
+        let docs = docs();
+        let path = vec!["user".to_string(), "id".to_string()];
+        let expected = StringArray::from(vec![Some("1"), None, Some("two"), None, None, None]);
+        assert_eq!(json_path_text(&docs, &path).unwrap(), expected);
+
+        let storage = shred_json(&docs, &paths()).unwrap();
+        let column = json_shredded_column(&storage, &path).unwrap();
+        assert_eq!(shredded_path_text(column).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_infer_shredded_paths() {
+        // user.id is an int in one document and a string in another
+        let paths = infer_shredded_paths(&docs(), 0.5, 10);
+        assert_eq!(
+            paths,
+            vec![ShreddedPath::new(["user", "name"], DataType::Utf8)]
+        );
+
+        let paths = infer_shredded_paths(&docs(), 0.25, 2);
+        assert_eq!(
+            paths,
+            vec![
+                ShreddedPath::new(["score"], DataType::Float64),
+                ShreddedPath::new(["user", "name"], DataType::Utf8),
+            ]
+        );
+    }
+}
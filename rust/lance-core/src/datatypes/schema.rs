@@ -9,13 +9,17 @@ use std::{
     sync::Arc,
 };
 
-use arrow_array::RecordBatch;
-use arrow_schema::{Field as ArrowField, Schema as ArrowSchema};
+use arrow_array::{cast::AsArray, RecordBatch};
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
 use deepsize::DeepSizeOf;
 use lance_arrow::*;
 use snafu::location;
 
 use super::field::{Field, OnTypeMismatch, SchemaCompareOptions, StorageClass};
+use super::json::{
+    format_shredded_paths, infer_shredded_paths, is_json_shred_requested, json_storage_fields,
+    DEFAULT_JSON_SHRED_MAX_PATHS, DEFAULT_JSON_SHRED_MIN_FREQUENCY, JSON_SHREDDED_PATHS_META_KEY,
+};
 use crate::{Error, Result, ROW_ADDR, ROW_ADDR_FIELD, ROW_ID, ROW_ID_FIELD};
 
 /// Lance Schema.
@@ -450,6 +454,49 @@ impl Schema {
         None
     }
 
+    /// The column path of a field (e.g. `tags.name`), see [`Self::field`]
+    ///
+    /// The items of lists are not named in column paths unless they are the field.
+    pub fn field_path(&self, id: i32) -> Option<String> {
+        let ancestry = self.field_ancestry_by_id(id)?;
+        let (leaf, ancestors) = ancestry.split_last()?;
+        let mut path = Vec::with_capacity(ancestry.len());
+        for (idx, field) in ancestors.iter().enumerate() {
+            let is_list_item = idx > 0
+                && matches!(
+                    ancestors[idx - 1].data_type(),
+                    DataType::List(_) | DataType::LargeList(_)
+                );
+            if !is_list_item {
+                path.push(field.name.as_str());
+            }
+        }
+        path.push(leaf.name.as_str());
+        Some(path.join("."))
+    }
+
+    /// The data type of a (possibly nested) column when it is read on its own
+    ///
+    /// A field inside of a list is read as a list of the field's values, e.g. `tags.name`
+    /// is read as a `list<string>` if `tags` is a list of structs with a string `name`.
+    pub fn column_data_type(&self, column: &str) -> Option<DataType> {
+        let fields = self.resolve(column)?;
+        let (leaf, ancestors) = fields.split_last()?;
+        let mut data_type = leaf.data_type();
+        for field in ancestors.iter().rev() {
+            data_type = match field.data_type() {
+                DataType::List(item) => {
+                    DataType::List(Arc::new(ArrowField::new(item.name(), data_type, true)))
+                }
+                DataType::LargeList(item) => {
+                    DataType::LargeList(Arc::new(ArrowField::new(item.name(), data_type, true)))
+                }
+                _ => data_type,
+            };
+        }
+        Some(data_type)
+    }
+
     pub fn mut_field_by_id(&mut self, id: impl Into<i32>) -> Option<&mut Field> {
         let id = id.into();
         for field in self.fields.as_mut_slice() {
@@ -488,6 +535,44 @@ impl Schema {
         Ok(())
     }
 
+    /// Pick the paths to shred for top-level JSON fields that ask for shredding
+    ///
+    /// Only fields with [`super::JSON_SHRED_META_KEY`] set (and no explicit paths) are
+    /// shredded.  The paths are inferred from a sample batch of the data.  The new
+    /// sub-fields are assigned field ids.
+    pub fn shred_json_fields(&mut self, batch: &RecordBatch) -> Result<()> {
+        let mut shredded = false;
+        for field in self.fields.as_mut_slice() {
+            if field.is_shredded_json() || !is_json_shred_requested(&ArrowField::from(&*field)) {
+                continue;
+            }
+            let Some(column) = batch.column_by_name(&field.name) else {
+                continue;
+            };
+            let paths = infer_shredded_paths(
+                column.as_string::<i32>(),
+                DEFAULT_JSON_SHRED_MIN_FREQUENCY,
+                DEFAULT_JSON_SHRED_MAX_PATHS,
+            );
+            if paths.is_empty() {
+                continue;
+            }
+            field.metadata.insert(
+                JSON_SHREDDED_PATHS_META_KEY.to_string(),
+                format_shredded_paths(&paths),
+            );
+            field.children = json_storage_fields(&paths)
+                .iter()
+                .map(|f| Field::try_from(f.as_ref()))
+                .collect::<Result<_>>()?;
+            shredded = true;
+        }
+        if shredded {
+            self.set_field_id(None);
+        }
+        Ok(())
+    }
+
     /// Walk through the fields and assign a new field id to each field that does
     /// not have one (e.g. is set to -1)
     ///
@@ -626,10 +711,13 @@ impl TryFrom<&ArrowSchema> for Schema {
                         });
                     }
 
-                    if ancestor.logical_type.is_list() || ancestor.logical_type.is_large_list() {
+                    if ancestor.logical_type.is_list()
+                        || ancestor.logical_type.is_large_list()
+                        || ancestor.logical_type.is_map()
+                    {
                         return Err(Error::Schema {
                             message: format!(
-                                "Primary key column must not be in a list type: {}",
+                                "Primary key column must not be in a list type or map type: {}",
                                 ancestor
                             ),
                             location: location!(),
@@ -897,6 +985,15 @@ impl Projection {
 
         if let Some(fields) = self.base.schema().resolve(column) {
             self.field_ids.extend(fields.iter().map(|f| f.id));
+            // Asking for a shredded JSON field means asking for the whole document, even
+            // if some of its paths are projected on their own as well
+            if let Some(json) = fields.last().filter(|f| f.is_shredded_json()) {
+                let mut stack = json.children.iter().collect::<Vec<_>>();
+                while let Some(child) = stack.pop() {
+                    self.field_ids.insert(child.id);
+                    stack.extend(child.children.iter());
+                }
+            }
         } else if matches!(on_missing, OnMissing::Error) {
             return Err(Error::InvalidInput {
                 source: format!("Column {} does not exist", column).into(),
@@ -1288,6 +1385,54 @@ mod tests {
         assert_eq!(field.data_type(), DataType::Boolean);
     }
 
+    #[test]
+    fn test_list_of_struct_field_paths() {
+        let item_type = DataType::Struct(ArrowFields::from(vec![
+            ArrowField::new("name", DataType::Utf8, true),
+            ArrowField::new("price", DataType::Float64, true),
+        ]));
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "items",
+            DataType::List(Arc::new(ArrowField::new("item", item_type, true))),
+            true,
+        )]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+
+        // The items of the list do not need to be named in the path
+        let field = schema.field("items.price").unwrap();
+        assert_eq!(field.data_type(), DataType::Float64);
+        assert_eq!(schema.field("items.item.price").unwrap().id, field.id);
+        assert_eq!(schema.field_path(field.id).unwrap(), "items.price");
+        assert!(schema.field("items.missing").is_none());
+
+        let resolved = schema.resolve("items.price").unwrap();
+        assert_eq!(
+            resolved.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+            vec!["items", "item", "price"]
+        );
+        assert_eq!(
+            schema.column_data_type("items.price").unwrap(),
+            DataType::List(Arc::new(ArrowField::new("item", DataType::Float64, true)))
+        );
+
+        // Only the referenced leaf is projected
+        let projected = schema.project(&["items.price"]).unwrap();
+        let expected = ArrowSchema::new(vec![ArrowField::new(
+            "items",
+            DataType::List(Arc::new(ArrowField::new(
+                "item",
+                DataType::Struct(ArrowFields::from(vec![ArrowField::new(
+                    "price",
+                    DataType::Float64,
+                    true,
+                )])),
+                true,
+            ))),
+            true,
+        )]);
+        assert_eq!(ArrowSchema::from(&projected), expected);
+    }
+
     #[test]
     fn test_exclude_fields() {
         let arrow_schema = ArrowSchema::new(vec![
@@ -11,11 +11,13 @@ use crate::expr::safe_coerce_scalar;
 use crate::logical_expr::{coerce_filter_type_to_boolean, get_as_string_scalar_opt, resolve_expr};
 use crate::sql::{parse_sql_expr, parse_sql_filter};
 use arrow::compute::CastOptions;
-use arrow_array::ListArray;
-use arrow_buffer::OffsetBuffer;
+use arrow_array::{
+    cast::AsArray, make_array, Array, ArrayRef, GenericListArray, ListArray, OffsetSizeTrait,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
 use arrow_schema::{DataType as ArrowDataType, Field, SchemaRef, TimeUnit};
 use arrow_select::concat::concat;
-use datafusion::common::tree_node::{TreeNode, TreeNodeRecursion, TreeNodeVisitor};
+use datafusion::common::tree_node::{Transformed, TreeNode, TreeNodeRecursion, TreeNodeVisitor};
 use datafusion::common::DFSchema;
 use datafusion::config::ConfigOptions;
 use datafusion::error::Result as DFResult;
@@ -23,11 +25,12 @@ use datafusion::execution::config::SessionConfig;
 use datafusion::execution::context::SessionState;
 use datafusion::execution::runtime_env::RuntimeEnvBuilder;
 use datafusion::execution::session_state::SessionStateBuilder;
+use datafusion::functions_nested::expr_fn::{array_element, map_extract};
 use datafusion::logical_expr::expr::ScalarFunction;
 use datafusion::logical_expr::planner::{ExprPlanner, PlannerResult, RawFieldAccessExpr};
 use datafusion::logical_expr::{
-    AggregateUDF, ColumnarValue, GetFieldAccess, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl,
-    Signature, Volatility, WindowUDF,
+    AggregateUDF, ColumnarValue, ExprSchemable, GetFieldAccess, ReturnInfo, ReturnTypeArgs,
+    ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility, WindowUDF,
 };
 use datafusion::optimizer::simplify_expressions::SimplifyContext;
 use datafusion::sql::planner::{ContextProvider, ParserOptions, PlannerContext, SqlToRel};
@@ -44,9 +47,12 @@ use datafusion::{
     prelude::Expr,
     scalar::ScalarValue,
 };
-use datafusion_functions::core::getfield::GetFieldFunc;
+use datafusion_functions::core::{getfield::GetFieldFunc, union_extract};
 use lance_arrow::cast::cast_with_options;
-use lance_core::datatypes::Schema;
+use lance_core::datatypes::{
+    json_path_text, json_shredded_column, json_shredded_paths, parse_json_path, shredded_path_text,
+    Schema,
+};
 use lance_core::error::LanceOptionExt;
 use snafu::location;
 
@@ -151,6 +157,222 @@ impl ScalarUDFImpl for CastListF16Udf {
     }
 }
 
+const JSON_GET: &str = "json_get";
+const JSON_GET_SHREDDED: &str = "_json_get_shredded";
+
+/// `json_get(json, path)` returns the value at a JSON path (e.g. `$.user.id`) as a string
+///
+/// The planner replaces `json_get` with `_json_get_shredded` when the path is a shredded
+/// path of the column.  The only difference is that `_json_get_shredded` only needs the
+/// columns of that path to be loaded, so its input may be the JSON string or a struct
+/// with just the shredded path.
+#[derive(Debug, Clone)]
+struct JsonGetUdf {
+    name: &'static str,
+    signature: Signature,
+}
+
+impl JsonGetUdf {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonGetUdf {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[ArrowDataType]) -> DFResult<ArrowDataType> {
+        Ok(ArrowDataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, func_args: ScalarFunctionArgs) -> DFResult<ColumnarValue> {
+        let path = match &func_args.args[1] {
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(path))) => parse_json_path(path),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            datafusion::error::DataFusionError::Execution(format!(
+                "{} expects a literal JSON path such as '$.a.b'",
+                self.name
+            ))
+        })?;
+        let json = func_args.args[0].to_array(func_args.number_rows)?;
+        let text = match json.data_type() {
+            ArrowDataType::Utf8 => json_path_text(json.as_string::<i32>(), &path)?,
+            ArrowDataType::Struct(_) => {
+                let column = json_shredded_column(json.as_struct(), &path).ok_or_else(|| {
+                    datafusion::error::DataFusionError::Execution(format!(
+                        "{} input does not contain the shredded path {}",
+                        self.name,
+                        path.join(".")
+                    ))
+                })?;
+                shredded_path_text(column)?
+            }
+            data_type => {
+                return Err(datafusion::error::DataFusionError::Execution(format!(
+                    "{} expects a JSON string, got {}",
+                    self.name, data_type
+                )));
+            }
+        };
+        Ok(ColumnarValue::Array(Arc::new(text)))
+    }
+}
+
+/// Name of the function that the planner uses to access a field through a list
+pub const LIST_FIELD: &str = "_list_field";
+
+/// `_list_field(list, name)` returns, for each row, the list of the `name` fields of
+/// the list's struct items
+///
+/// This is how the planner resolves `tags.name` when `tags` is a list of structs.
+/// Lists of lists are handled as well, the result has the same list nesting as the
+/// input.
+#[derive(Debug, Clone)]
+struct ListFieldUdf {
+    signature: Signature,
+}
+
+impl ListFieldUdf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+
+    fn field_type(data_type: &ArrowDataType, name: &str) -> DFResult<ArrowDataType> {
+        match data_type {
+            ArrowDataType::List(item) => Ok(ArrowDataType::List(Arc::new(Field::new(
+                item.name(),
+                Self::field_type(item.data_type(), name)?,
+                true,
+            )))),
+            ArrowDataType::LargeList(item) => Ok(ArrowDataType::LargeList(Arc::new(Field::new(
+                item.name(),
+                Self::field_type(item.data_type(), name)?,
+                true,
+            )))),
+            ArrowDataType::Struct(fields) => fields
+                .find(name)
+                .map(|(_, field)| field.data_type().clone())
+                .ok_or_else(|| {
+                    datafusion::error::DataFusionError::Execution(format!(
+                        "{} could not find a field named {}",
+                        LIST_FIELD, name
+                    ))
+                }),
+            _ => Err(datafusion::error::DataFusionError::Execution(format!(
+                "{} expects a list of structs, got {}",
+                LIST_FIELD, data_type
+            ))),
+        }
+    }
+
+    fn extract_list<O: OffsetSizeTrait>(
+        list: &GenericListArray<O>,
+        name: &str,
+    ) -> DFResult<ArrayRef> {
+        let values = Self::extract(list.values(), name)?;
+        let (ArrowDataType::List(item) | ArrowDataType::LargeList(item)) = list.data_type() else {
+            unreachable!()
+        };
+        let item = Arc::new(Field::new(item.name(), values.data_type().clone(), true));
+        Ok(Arc::new(GenericListArray::<O>::try_new(
+            item,
+            list.offsets().clone(),
+            values,
+            list.nulls().cloned(),
+        )?))
+    }
+
+    fn extract(array: &ArrayRef, name: &str) -> DFResult<ArrayRef> {
+        match array.data_type() {
+            ArrowDataType::List(_) => Self::extract_list(array.as_list::<i32>(), name),
+            ArrowDataType::LargeList(_) => Self::extract_list(array.as_list::<i64>(), name),
+            ArrowDataType::Struct(_) => {
+                let struct_arr = array.as_struct();
+                let column = struct_arr.column_by_name(name).ok_or_else(|| {
+                    datafusion::error::DataFusionError::Execution(format!(
+                        "{} could not find a field named {}",
+                        LIST_FIELD, name
+                    ))
+                })?;
+                // The field of a null item is null
+                let nulls = NullBuffer::union(struct_arr.nulls(), column.nulls());
+                let data = column.to_data().into_builder().nulls(nulls).build()?;
+                Ok(make_array(data))
+            }
+            data_type => Err(datafusion::error::DataFusionError::Execution(format!(
+                "{} expects a list of structs, got {}",
+                LIST_FIELD, data_type
+            ))),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ListFieldUdf {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        LIST_FIELD
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[ArrowDataType]) -> DFResult<ArrowDataType> {
+        // The type depends on the field name, see return_type_from_args
+        Err(datafusion::error::DataFusionError::Internal(format!(
+            "{} return type requires the field name",
+            LIST_FIELD
+        )))
+    }
+
+    fn return_type_from_args(&self, args: ReturnTypeArgs) -> DFResult<ReturnInfo> {
+        let name = match args.scalar_arguments.get(1) {
+            Some(Some(ScalarValue::Utf8(Some(name)))) => name,
+            _ => {
+                return Err(datafusion::error::DataFusionError::Execution(format!(
+                    "{} expects a literal field name",
+                    LIST_FIELD
+                )));
+            }
+        };
+        Ok(ReturnInfo::new_nullable(Self::field_type(
+            &args.arg_types[0],
+            name,
+        )?))
+    }
+
+    fn invoke_with_args(&self, func_args: ScalarFunctionArgs) -> DFResult<ColumnarValue> {
+        let ColumnarValue::Scalar(ScalarValue::Utf8(Some(name))) = &func_args.args[1] else {
+            return Err(datafusion::error::DataFusionError::Execution(format!(
+                "{} expects a literal field name",
+                LIST_FIELD
+            )));
+        };
+        let list = func_args.args[0].to_array(func_args.number_rows)?;
+        Ok(ColumnarValue::Array(Self::extract(&list, name)?))
+    }
+}
+
 // Adapter that instructs datafusion how lance expects expressions to be interpreted
 struct LanceContextProvider {
     options: datafusion::config::ConfigOptions,
@@ -205,6 +427,13 @@ impl ContextProvider for LanceContextProvider {
             // TODO: cast should go thru CAST syntax instead of UDF
             // Going thru UDF makes it hard for the optimizer to find no-ops
             "_cast_list_f16" => Some(Arc::new(ScalarUDF::new_from_impl(CastListF16Udf::new()))),
+            JSON_GET => Some(Arc::new(ScalarUDF::new_from_impl(JsonGetUdf::new(
+                JSON_GET,
+            )))),
+            JSON_GET_SHREDDED => Some(Arc::new(ScalarUDF::new_from_impl(JsonGetUdf::new(
+                JSON_GET_SHREDDED,
+            )))),
+            LIST_FIELD => Some(Arc::new(ScalarUDF::new_from_impl(ListFieldUdf::new()))),
             _ => self.state.scalar_functions().get(f).cloned(),
         }
     }
@@ -248,20 +477,36 @@ impl Planner {
         }
     }
 
-    fn column(idents: &[Ident]) -> Expr {
+    fn column(&self, idents: &[Ident]) -> Expr {
+        let df_schema = DFSchema::try_from(self.schema.as_ref().clone()).ok();
         let mut column = col(&idents[0].value);
         for ident in &idents[1..] {
-            column = Expr::ScalarFunction(ScalarFunction {
-                args: vec![
-                    column,
-                    Expr::Literal(ScalarValue::Utf8(Some(ident.value.clone()))),
-                ],
-                func: Arc::new(ScalarUDF::new_from_impl(GetFieldFunc::default())),
-            });
+            let data_type = df_schema
+                .as_ref()
+                .and_then(|df_schema| column.get_type(df_schema).ok());
+            let name = Expr::Literal(ScalarValue::Utf8(Some(ident.value.clone())));
+            column = match data_type {
+                Some(ArrowDataType::Union(_, _)) => union_extract().call(vec![column, name]),
+                Some(ArrowDataType::List(_) | ArrowDataType::LargeList(_)) => {
+                    Self::list_field(column, name)
+                }
+                _ => Expr::ScalarFunction(ScalarFunction {
+                    args: vec![column, name],
+                    func: Arc::new(ScalarUDF::new_from_impl(GetFieldFunc::default())),
+                }),
+            };
         }
         column
     }
 
+    /// Access a field of the struct items of a list, see [`LIST_FIELD`]
+    fn list_field(list: Expr, name: Expr) -> Expr {
+        Expr::ScalarFunction(ScalarFunction::new_udf(
+            Arc::new(ScalarUDF::new_from_impl(ListFieldUdf::new())),
+            vec![list, name],
+        ))
+    }
+
     fn binary_op(&self, op: &BinaryOperator) -> Result<Operator> {
         Ok(match op {
             BinaryOperator::Plus => Operator::Plus,
@@ -422,7 +667,68 @@ impl Planner {
 
         let mut planner_context = PlannerContext::default();
         let schema = DFSchema::try_from(self.schema.as_ref().clone())?;
-        Ok(sql_to_rel.sql_to_expr(function, &schema, &mut planner_context)?)
+        let expr = sql_to_rel.sql_to_expr(function, &schema, &mut planner_context)?;
+        let expr = self.rewrite_list_fields(expr, &schema)?;
+        self.rewrite_shredded_json(expr)
+    }
+
+    /// Replace `get_field` on lists of structs with [`LIST_FIELD`]
+    ///
+    /// DataFusion plans `tags.name` in function arguments as a struct field access even
+    /// if `tags` is a list of structs.
+    fn rewrite_list_fields(&self, expr: Expr, schema: &DFSchema) -> Result<Expr> {
+        let rewritten = expr.transform_up(|expr| {
+            let Expr::ScalarFunction(func) = &expr else {
+                return Ok(Transformed::no(expr));
+            };
+            if func.name() != GetFieldFunc::default().name()
+                || !matches!(
+                    func.args[0].get_type(schema),
+                    Ok(ArrowDataType::List(_) | ArrowDataType::LargeList(_))
+                )
+            {
+                return Ok(Transformed::no(expr));
+            }
+            Ok(Transformed::yes(Self::list_field(
+                func.args[0].clone(),
+                func.args[1].clone(),
+            )))
+        })?;
+        Ok(rewritten.data)
+    }
+
+    /// Replace `json_get` on shredded JSON paths so that only the columns of the path
+    /// need to be loaded
+    fn rewrite_shredded_json(&self, expr: Expr) -> Result<Expr> {
+        let rewritten = expr.transform_up(|expr| {
+            let Expr::ScalarFunction(func) = &expr else {
+                return Ok(Transformed::no(expr));
+            };
+            if func.name() != JSON_GET {
+                return Ok(Transformed::no(expr));
+            }
+            let (Expr::Column(column), Some(path)) =
+                (&func.args[0], get_as_string_scalar_opt(&func.args[1]))
+            else {
+                return Ok(Transformed::no(expr));
+            };
+            let Ok(field) = self.schema.field_with_name(&column.name) else {
+                return Ok(Transformed::no(expr));
+            };
+            let shredded = json_shredded_paths(field)?.unwrap_or_default();
+            if !parse_json_path(path)
+                .is_some_and(|path| shredded.iter().any(|shredded| shredded.path == path))
+            {
+                return Ok(Transformed::no(expr));
+            }
+            Ok(Transformed::yes(Expr::ScalarFunction(
+                ScalarFunction::new_udf(
+                    Arc::new(ScalarUDF::new_from_impl(JsonGetUdf::new(JSON_GET_SHREDDED))),
+                    func.args.clone(),
+                ),
+            )))
+        })?;
+        Ok(rewritten.data)
     }
 
     fn parse_type(&self, data_type: &SQLDataType) -> Result<ArrowDataType> {
@@ -550,10 +856,10 @@ impl Planner {
                 } else if id.quote_style == Some('`') {
                     Ok(Expr::Column(Column::from_name(id.value.clone())))
                 } else {
-                    Ok(Self::column(vec![id.clone()].as_slice()))
+                    Ok(self.column(vec![id.clone()].as_slice()))
                 }
             }
-            SQLExpr::CompoundIdentifier(ids) => Ok(Self::column(ids.as_slice())),
+            SQLExpr::CompoundIdentifier(ids) => Ok(self.column(ids.as_slice())),
             SQLExpr::BinaryOp { left, op, right } => self.binary_expr(left, op, right),
             SQLExpr::UnaryOp { op, expr } => self.unary_expr(op, expr),
             SQLExpr::Value(value) => self.value(&value.value),
@@ -735,6 +1041,32 @@ impl Planner {
                         }
                     };
 
+                    // x['y'] on a map column is a key lookup rather than a struct field
+                    // access.  We plan it with map_extract so that the referenced column
+                    // is the map itself and not a (non-existent) child named `y`.
+                    if let GetFieldAccess::NamedStructField { name } = &field_access {
+                        let df_schema = DFSchema::try_from(self.schema.as_ref().clone())?;
+                        match expr.get_type(&df_schema)? {
+                            ArrowDataType::Map(_, _) => {
+                                expr = array_element(
+                                    map_extract(expr, Expr::Literal(name.clone())),
+                                    Expr::Literal(ScalarValue::Int64(Some(1))),
+                                );
+                                continue;
+                            }
+                            ArrowDataType::Union(_, _) => {
+                                expr =
+                                    union_extract().call(vec![expr, Expr::Literal(name.clone())]);
+                                continue;
+                            }
+                            ArrowDataType::List(_) | ArrowDataType::LargeList(_) => {
+                                expr = Self::list_field(expr, Expr::Literal(name.clone()));
+                                continue;
+                            }
+                            _ => {}
+                        }
+                    }
+
                     let field_access_expr = RawFieldAccessExpr { expr, field_access };
                     expr = self.plan_field_access(field_access_expr)?;
                 }
@@ -889,8 +1221,28 @@ impl TreeNodeVisitor<'_> for ColumnCapturingVisitor {
                 self.columns.insert(path);
                 self.current_path.clear();
             }
+            Expr::ScalarFunction(udf) if udf.name() == JSON_GET_SHREDDED => {
+                // Only the columns of the shredded path are needed
+                match get_as_string_scalar_opt(&udf.args[1]).and_then(parse_json_path) {
+                    Some(path) => {
+                        for key in path.into_iter().rev() {
+                            self.current_path.push_front(key);
+                        }
+                    }
+                    None => self.current_path.clear(),
+                }
+            }
+            Expr::ScalarFunction(udf) if udf.name() == "array_element" => {
+                // The element of a list has the same fields as the list items so the path
+                // continues into the list (the index itself is visited on its own)
+            }
             Expr::ScalarFunction(udf) => {
-                if udf.name() == GetFieldFunc::default().name() {
+                // Accessing a union child or the field of list items is treated like
+                // accessing a struct field so that only the needed children are loaded.
+                if udf.name() == GetFieldFunc::default().name()
+                    || udf.name() == union_extract().name()
+                    || udf.name() == LIST_FIELD
+                {
                     if let Some(name) = get_as_string_scalar_opt(&udf.args[1]) {
                         self.current_path.push_front(name.to_string())
                     } else {
@@ -918,8 +1270,9 @@ mod tests {
 
     use arrow::datatypes::Float64Type;
     use arrow_array::{
-        ArrayRef, BooleanArray, Float32Array, Int32Array, Int64Array, RecordBatch, StringArray,
-        StructArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+        builder::{MapBuilder, StringBuilder},
+        ArrayRef, BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array, RecordBatch,
+        StringArray, StructArray, TimestampMicrosecondArray, TimestampMillisecondArray,
         TimestampNanosecondArray, TimestampSecondArray,
     };
     use arrow_schema::{DataType, Fields, Schema};
@@ -928,6 +1281,11 @@ mod tests {
         prelude::{array_element, get_field},
     };
     use datafusion_functions::core::expr_ext::FieldAccessor;
+    use lance_arrow::bfloat16::ARROW_EXT_NAME_KEY;
+    use lance_core::datatypes::{
+        parse_shredded_paths, shred_json, JSON_EXT_NAME, JSON_SHREDDED_PATHS_META_KEY,
+    };
+    use std::collections::HashMap;
 
     #[test]
     fn test_parse_filter_simple() {
@@ -1096,10 +1454,167 @@ mod tests {
         let expr = planner.parse_expr("l[0]['f1']").unwrap();
         assert_eq!(expr, expected);
 
-        // FIXME: This should work, but sqlparser doesn't recognize anything
-        // after the period for some reason.
-        // let expr = planner.parse_expr("l[0].f1").unwrap();
-        // assert_eq!(expr, expected);
+        let expr = planner.parse_expr("l[0].f1").unwrap();
+        assert_eq!(expr, expected);
+        assert_eq!(Planner::column_names_in_expr(&expr), vec!["l.f1"]);
+    }
+
+    #[test]
+    fn test_list_of_struct_refs() {
+        let item_fields = Fields::from(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("price", DataType::Float64, true),
+        ]);
+        let items = ListArray::new(
+            Arc::new(Field::new(
+                "item",
+                DataType::Struct(item_fields.clone()),
+                true,
+            )),
+            OffsetBuffer::from_lengths([2, 1, 0]),
+            Arc::new(StructArray::new(
+                item_fields,
+                vec![
+                    Arc::new(StringArray::from(vec!["x", "y", "z"])),
+                    Arc::new(Float64Array::from(vec![12.0, 3.0, 5.0])),
+                ],
+                None,
+            )),
+            None,
+        );
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "items",
+            items.data_type().clone(),
+            true,
+        )]));
+        let planner = Planner::new(schema.clone());
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(items) as ArrayRef]).unwrap();
+
+        for (filter, column, expected) in [
+            (
+                "array_has(items.name, 'x')",
+                "items.name",
+                BooleanArray::from(vec![Some(true), Some(false), Some(false)]),
+            ),
+            (
+                "items[1].price > 10",
+                "items.price",
+                BooleanArray::from(vec![Some(true), Some(false), None]),
+            ),
+        ] {
+            let expr = planner.parse_filter(filter).unwrap();
+            // Only the referenced leaf of the list items needs to be loaded
+            assert_eq!(Planner::column_names_in_expr(&expr), vec![column]);
+
+            let expr = planner.optimize_expr(expr).unwrap();
+            let physical_expr = planner.create_physical_expr(&expr).unwrap();
+            let predicates = physical_expr.evaluate(&batch).unwrap();
+            assert_eq!(
+                predicates.into_array(batch.num_rows()).unwrap().as_ref(),
+                &expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_map_refs() {
+        let mut builder = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        builder.keys().append_value("k");
+        builder.values().append_value("v");
+        builder.append(true).unwrap();
+        builder.keys().append_value("k");
+        builder.values().append_value("w");
+        builder.keys().append_value("j");
+        builder.values().append_value("v");
+        builder.append(true).unwrap();
+        builder.append(true).unwrap();
+        builder.append(false).unwrap();
+        let attrs = builder.finish();
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "attrs",
+            attrs.data_type().clone(),
+            true,
+        )]));
+        let planner = Planner::new(schema.clone());
+
+        let expr = planner.parse_filter("attrs['k'] = 'v'").unwrap();
+        let expected = array_element(map_extract(col("attrs"), lit("k")), lit(1_i64)).eq(lit("v"));
+        assert_eq!(expr, expected);
+        assert_eq!(Planner::column_names_in_expr(&expr), vec!["attrs"]);
+
+        let physical_expr = planner.create_physical_expr(&expr).unwrap();
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(attrs) as ArrayRef]).unwrap();
+        let predicates = physical_expr.evaluate(&batch).unwrap();
+        assert_eq!(
+            predicates.into_array(0).unwrap().as_ref(),
+            &BooleanArray::from(vec![Some(true), Some(false), None, None])
+        );
+    }
+
+    #[test]
+    fn test_json_get() {
+        let metadata = HashMap::from([
+            (ARROW_EXT_NAME_KEY.to_string(), JSON_EXT_NAME.to_string()),
+            (
+                JSON_SHREDDED_PATHS_META_KEY.to_string(),
+                "user.id:int64".to_string(),
+            ),
+        ]);
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "payload",
+            DataType::Utf8,
+            true,
+        )
+        .with_metadata(metadata.clone())]));
+        let planner = Planner::new(schema.clone());
+
+        // Shredded paths only need the columns of the path, other paths need the documents
+        let expr = planner
+            .parse_filter("json_get(payload, '$.user.id') = 42")
+            .unwrap();
+        assert_eq!(
+            Planner::column_names_in_expr(&expr),
+            vec!["payload.user.id"]
+        );
+        let other = planner
+            .parse_filter("json_get(payload, '$.user.name') = 'a'")
+            .unwrap();
+        assert_eq!(Planner::column_names_in_expr(&other), vec!["payload"]);
+
+        let docs = StringArray::from(vec![
+            Some(r#"{"user": {"id": 42}}"#),
+            Some(r#"{"user": {"id": "42"}}"#),
+            Some(r#"{"user": {"id": 7}}"#),
+            Some(r#"{"user": {}}"#),
+            None,
+        ]);
+        let expected = BooleanArray::from(vec![Some(true), Some(true), Some(false), None, None]);
+        let expr = planner.optimize_expr(expr).unwrap();
+
+        // The filter works on the whole documents...
+        let physical_expr = planner.create_physical_expr(&expr).unwrap();
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(docs.clone()) as ArrayRef]).unwrap();
+        let predicates = physical_expr.evaluate(&batch).unwrap();
+        assert_eq!(predicates.into_array(0).unwrap().as_ref(), &expected);
+
+        // ...and on just the shredded path
+        let paths = parse_shredded_paths("user.id:int64").unwrap();
+        let storage = shred_json(&docs, &paths).unwrap();
+        let (fields, columns, _) = storage.into_parts();
+        let view = StructArray::new(fields[1..].into(), columns[1..].to_vec(), None);
+        let view_schema = Arc::new(Schema::new(vec![Field::new(
+            "payload",
+            view.data_type().clone(),
+            true,
+        )
+        .with_metadata(metadata)]));
+        let physical_expr = Planner::new(view_schema.clone())
+            .create_physical_expr(&expr)
+            .unwrap();
+        let batch = RecordBatch::try_new(view_schema, vec![Arc::new(view) as ArrayRef]).unwrap();
+        let predicates = physical_expr.evaluate(&batch).unwrap();
+        assert_eq!(predicates.into_array(0).unwrap().as_ref(), &expected);
     }
 
     #[test]
@@ -102,12 +102,33 @@ fn parse_statement(statement: &str) -> Result<Statement> {
         tokens.push(token);
     }
     tokens.push(prev_token);
+    let tokens = rewrite_subscript_field_access(tokens);
 
     Ok(Parser::new(&dialect)
         .with_tokens(tokens)
         .parse_statement()?)
 }
 
+/// sqlparser does not parse a field access that follows a subscript (`items[0].price`)
+/// so it is rewritten to the equivalent `items[0]['price']`.
+fn rewrite_subscript_field_access(tokens: Vec<Token>) -> Vec<Token> {
+    let mut rewritten: Vec<Token> = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        if let Token::Word(word) = &token {
+            let len = rewritten.len();
+            if len >= 2 && matches!(&rewritten[len - 2..], [Token::RBracket, Token::Period]) {
+                rewritten.pop();
+                rewritten.push(Token::LBracket);
+                rewritten.push(Token::SingleQuotedString(word.value.clone()));
+                rewritten.push(Token::RBracket);
+                continue;
+            }
+        }
+        rewritten.push(token);
+    }
+    rewritten
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -15,6 +15,7 @@
 
 use arrayref::{array_mut_ref, array_ref};
 use core::mem::size_of;
+use num_traits::{WrappingAdd, WrappingSub};
 use paste::paste;
 
 pub const FL_ORDER: [usize; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
@@ -1682,6 +1683,79 @@ pack_64!(pack_64_62, 62);
 pack_64!(pack_64_63, 63);
 pack_64!(pack_64_64, 64);
 
+/// The position, in the FastLanes "unified transposed layout", of the value at `idx`
+///
+/// Transposing 1024 values this way means each lane visited by [`BitPacking`] holds a
+/// contiguous run of the original values, so delta encoding can run lane by lane.
+#[inline]
+pub const fn transpose_index(idx: usize) -> usize {
+    let lane = idx % 16;
+    let order = (idx / 16) % 8;
+    let row = idx / 128;
+    (lane * 64) + (FL_ORDER[order] * 8) + row
+}
+
+/// The position of `row` in `lane`, in the order the bitpacking kernels visit values
+#[inline]
+pub const fn lane_index(row: usize, lane: usize) -> usize {
+    (FL_ORDER[row / 8] * 16) + ((row % 8) * 128) + lane
+}
+
+pub trait Transpose: FastLanes {
+    fn transpose(input: &[Self; 1024], output: &mut [Self; 1024]) {
+        for (idx, value) in output.iter_mut().enumerate() {
+            *value = input[transpose_index(idx)];
+        }
+    }
+
+    fn untranspose(input: &[Self; 1024], output: &mut [Self; 1024]) {
+        for (idx, value) in input.iter().enumerate() {
+            output[transpose_index(idx)] = *value;
+        }
+    }
+}
+
+impl Transpose for u8 {}
+impl Transpose for u16 {}
+impl Transpose for u32 {}
+impl Transpose for u64 {}
+
+/// Delta encoding of transposed values, each lane starts from its own base value
+pub trait Delta: FastLanes + WrappingAdd + WrappingSub {
+    /// `base` must have one value per lane
+    fn delta(input: &[Self; 1024], base: &[Self], output: &mut [Self; 1024]) {
+        debug_assert_eq!(base.len(), Self::LANES);
+        for (lane, base) in base.iter().enumerate() {
+            let mut prev = *base;
+            for row in 0..Self::T {
+                let idx = lane_index(row, lane);
+                let next = input[idx];
+                output[idx] = next.wrapping_sub(&prev);
+                prev = next;
+            }
+        }
+    }
+
+    /// `base` must have one value per lane
+    fn undelta(input: &[Self; 1024], base: &[Self], output: &mut [Self; 1024]) {
+        debug_assert_eq!(base.len(), Self::LANES);
+        for (lane, base) in base.iter().enumerate() {
+            let mut prev = *base;
+            for row in 0..Self::T {
+                let idx = lane_index(row, lane);
+                let next = input[idx].wrapping_add(&prev);
+                output[idx] = next;
+                prev = next;
+            }
+        }
+    }
+}
+
+impl Delta for u8 {}
+impl Delta for u16 {}
+impl Delta for u32 {}
+impl Delta for u64 {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1706,6 +1780,36 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_transpose_lanes_are_contiguous() {
+        // Each lane of the transposed layout holds a contiguous run of values
+        let input: [u32; 1024] = array::from_fn(|i| i as u32);
+        let mut transposed = [0; 1024];
+        u32::transpose(&input, &mut transposed);
+        for lane in 0..u32::LANES {
+            let values = (0..u32::T)
+                .map(|row| transposed[lane_index(row, lane)])
+                .collect::<Vec<_>>();
+            assert!(values.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        }
+
+        let mut untransposed = [0; 1024];
+        u32::untranspose(&transposed, &mut untransposed);
+        assert_eq!(input, untransposed);
+    }
+
+    #[test]
+    fn test_delta_round_trip() {
+        let mut rng = XorShift::new(42);
+        let input: [u64; 1024] = array::from_fn(|_| rng.next());
+        let base: [u64; 16] = array::from_fn(|_| rng.next());
+        let mut deltas = [0; 1024];
+        u64::delta(&input, &base, &mut deltas);
+        let mut output = [0; 1024];
+        u64::undelta(&deltas, &base, &mut output);
+        assert_eq!(input, output);
+    }
+
     // a macro version of this function generalize u8, u16, u32, u64 takes very long time for a test build, so I
     // write it for each type separately
     fn pack_unpack_u8(bit_width: usize) {
@@ -248,12 +248,19 @@ use crate::encodings::logical::primitive::{
 use crate::encodings::logical::r#struct::{
     SimpleStructDecoder, SimpleStructScheduler, StructuralStructDecoder, StructuralStructScheduler,
 };
+use crate::encodings::physical::alp::AlpMiniBlockDecompressor;
 use crate::encodings::physical::binary::{
     BinaryBlockDecompressor, BinaryMiniBlockDecompressor, VariableDecoder,
 };
 use crate::encodings::physical::bitpack_fastlanes::InlineBitpacking;
 use crate::encodings::physical::block_compress::CompressedBufferEncoder;
+use crate::encodings::physical::byte_stream_split::ByteStreamSplitMiniBlockDecompressor;
+use crate::encodings::physical::delta::{
+    DeltaMiniBlockDecompressor, FrameOfReferenceMiniBlockDecompressor,
+};
 use crate::encodings::physical::fsst::{FsstMiniBlockDecompressor, FsstPerValueDecompressor};
+use crate::encodings::physical::quantized_vector::QuantizedVectorDecompressor;
+use crate::encodings::physical::rle::RleMiniBlockDecompressor;
 use crate::encodings::physical::struct_encoding::PackedStructFixedWidthMiniBlockDecompressor;
 use crate::encodings::physical::value::{ConstantDecompressor, ValueDecompressor};
 use crate::encodings::physical::{ColumnBuffers, FileBuffers};
@@ -460,6 +467,19 @@ impl<'a> ColumnInfoIter<'a> {
 
 pub trait MiniBlockDecompressor: std::fmt::Debug + Send + Sync {
     fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock>;
+
+    /// Decompress only the values in `range` out of the `num_values` values in the chunk
+    ///
+    /// Returns `None` if the encoding can't do this any cheaper than decompressing the
+    /// entire chunk (the default), in which case [`Self::decompress`] is used instead.
+    fn decompress_range(
+        &self,
+        _data: &[LanceBuffer],
+        _num_values: u64,
+        _range: Range<u64>,
+    ) -> Result<Option<DataBlock>> {
+        Ok(None)
+    }
 }
 
 pub trait FixedPerValueDecompressor: std::fmt::Debug + Send + Sync {
@@ -517,6 +537,24 @@ impl DecompressorStrategy for CoreDecompressorStrategy {
             pb::array_encoding::ArrayEncoding::InlineBitpacking(description) => {
                 Ok(Box::new(InlineBitpacking::from_description(description)))
             }
+            pb::array_encoding::ArrayEncoding::Rle(description) => Ok(Box::new(
+                RleMiniBlockDecompressor::from_description(description),
+            )),
+            pb::array_encoding::ArrayEncoding::FrameOfReference(description) => Ok(Box::new(
+                FrameOfReferenceMiniBlockDecompressor::from_description(description),
+            )),
+            pb::array_encoding::ArrayEncoding::Delta(description) => Ok(Box::new(
+                DeltaMiniBlockDecompressor::from_description(description)?,
+            )),
+            pb::array_encoding::ArrayEncoding::Alp(description) => Ok(Box::new(
+                AlpMiniBlockDecompressor::from_description(description),
+            )),
+            pb::array_encoding::ArrayEncoding::ByteStreamSplit(description) => Ok(Box::new(
+                ByteStreamSplitMiniBlockDecompressor::from_description(description)?,
+            )),
+            pb::array_encoding::ArrayEncoding::QuantizedVector(description) => Ok(Box::new(
+                QuantizedVectorDecompressor::from_description(description)?,
+            )),
             pb::array_encoding::ArrayEncoding::Variable(_) => {
                 Ok(Box::new(BinaryMiniBlockDecompressor::default()))
             }
@@ -548,6 +586,9 @@ impl DecompressorStrategy for CoreDecompressorStrategy {
             pb::array_encoding::ArrayEncoding::FixedSizeList(fsl) => {
                 Ok(Box::new(ValueDecompressor::from_fsl(fsl)))
             }
+            pb::array_encoding::ArrayEncoding::QuantizedVector(description) => Ok(Box::new(
+                QuantizedVectorDecompressor::from_description(description)?,
+            )),
             _ => todo!("fixed-per-value decompressor for {:?}", description),
         }
     }
@@ -744,21 +785,10 @@ impl CoreFieldDecoderStrategy {
             offsets_column_buffers,
             self.validate_data,
         )) as Arc<dyn FieldScheduler>;
-        let items_field = match list_field.data_type() {
-            DataType::List(inner) => inner,
-            DataType::LargeList(inner) => inner,
-            _ => unreachable!(),
-        };
-        let offset_type = if matches!(list_field.data_type(), DataType::List(_)) {
-            DataType::Int32
-        } else {
-            DataType::Int64
-        };
         Ok(Box::new(ListFieldScheduler::new(
             inner,
             items_scheduler.into(),
-            items_field,
-            offset_type,
+            list_field.data_type(),
             null_offset_adjustments,
         )))
     }
@@ -780,6 +810,22 @@ impl CoreFieldDecoderStrategy {
         field: &Field,
         column_infos: &mut ColumnInfoIter,
     ) -> Result<Box<dyn StructuralFieldScheduler>> {
+        if field.is_shredded_json() {
+            // Shredded JSON is stored as a struct of the original documents and the
+            // shredded paths (this is a string if all of it is projected)
+            let mut child_schedulers = Vec::with_capacity(field.children.len());
+            for field in field.children.iter() {
+                let field_scheduler =
+                    self.create_structural_field_scheduler(field, column_infos)?;
+                child_schedulers.push(field_scheduler);
+            }
+
+            let fields = field.children.iter().map(ArrowField::from).collect();
+            return Ok(
+                Box::new(StructuralStructScheduler::new(child_schedulers, fields))
+                    as Box<dyn StructuralFieldScheduler>,
+            );
+        }
         let data_type = field.data_type();
         if Self::is_primitive(&data_type) {
             let column_info = column_infos.expect_next()?;
@@ -829,7 +875,20 @@ impl CoreFieldDecoderStrategy {
                 column_infos.next_top_level();
                 Ok(scheduler)
             }
-            DataType::List(_) | DataType::LargeList(_) => {
+            DataType::Dictionary(_, value_type)
+                if Self::is_primitive(value_type)
+                    || matches!(value_type.as_ref(), DataType::Binary | DataType::Utf8) =>
+            {
+                // Dictionaries are stored as values (possibly dictionary encoded)
+                let column_info = column_infos.expect_next()?;
+                let scheduler = Box::new(StructuralPrimitiveFieldScheduler::try_new(
+                    column_info.as_ref(),
+                    self.decompressor_strategy.as_ref(),
+                )?);
+                column_infos.next_top_level();
+                Ok(scheduler)
+            }
+            DataType::List(_) | DataType::LargeList(_) | DataType::Map(_, _) => {
                 let child = field
                     .children
                     .first()
@@ -839,6 +898,21 @@ impl CoreFieldDecoderStrategy {
                 Ok(Box::new(StructuralListScheduler::new(child_scheduler))
                     as Box<dyn StructuralFieldScheduler>)
             }
+            DataType::Union(_, _) => {
+                // Unions are stored as a struct of the type ids and the union children
+                let mut child_schedulers = Vec::with_capacity(field.children.len());
+                for field in field.children.iter() {
+                    let field_scheduler =
+                        self.create_structural_field_scheduler(field, column_infos)?;
+                    child_schedulers.push(field_scheduler);
+                }
+
+                let fields = field.children.iter().map(ArrowField::from).collect();
+                Ok(
+                    Box::new(StructuralStructScheduler::new(child_schedulers, fields))
+                        as Box<dyn StructuralFieldScheduler>,
+                )
+            }
             _ => todo!(),
         }
     }
@@ -935,7 +1009,7 @@ impl CoreFieldDecoderStrategy {
                     })
                 }
             }
-            DataType::List(_) | DataType::LargeList(_) => {
+            DataType::List(_) | DataType::LargeList(_) | DataType::Map(_, _) => {
                 let offsets_column = column_infos.expect_next()?.clone();
                 column_infos.next_top_level();
                 self.create_list_scheduler(field, column_infos, buffers, &offsets_column)
@@ -8,22 +8,27 @@ use arrow_array::{Array, ArrayRef, RecordBatch, UInt8Array};
 use arrow_schema::DataType;
 use bytes::{Bytes, BytesMut};
 use futures::future::BoxFuture;
+use lance_arrow::bfloat16::{is_bfloat16_field, ARROW_EXT_NAME_KEY, BFLOAT16_EXT_NAME};
 use lance_core::datatypes::{
-    Field, Schema, BLOB_DESC_FIELD, BLOB_META_KEY, COMPRESSION_LEVEL_META_KEY,
-    COMPRESSION_META_KEY, PACKED_STRUCT_LEGACY_META_KEY, PACKED_STRUCT_META_KEY,
+    parse_shredded_paths, Field, Schema, BLOB_DESC_FIELD, BLOB_META_KEY,
+    COMPRESSION_LEVEL_META_KEY, COMPRESSION_META_KEY, JSON_SHREDDED_PATHS_META_KEY,
+    PACKED_STRUCT_LEGACY_META_KEY, PACKED_STRUCT_META_KEY, VECTOR_COMPRESSION_META_KEY,
 };
 use lance_core::utils::bit::{is_pwr_two, pad_bytes_to};
 use lance_core::{Error, Result};
 use snafu::location;
 
 use crate::buffer::LanceBuffer;
-use crate::data::{DataBlock, FixedWidthDataBlock, VariableWidthBlock};
+use crate::data::{DataBlock, FixedSizeListBlock, FixedWidthDataBlock, VariableWidthBlock};
 use crate::decoder::PageEncoding;
 use crate::encodings::logical::blob::BlobFieldEncoder;
+use crate::encodings::logical::json::JsonStructuralEncoder;
 use crate::encodings::logical::list::ListStructuralEncoder;
 use crate::encodings::logical::primitive::PrimitiveStructuralEncoder;
 use crate::encodings::logical::r#struct::StructFieldEncoder;
 use crate::encodings::logical::r#struct::StructStructuralEncoder;
+use crate::encodings::logical::union::UnionStructuralEncoder;
+use crate::encodings::physical::alp::{self, AlpMiniBlockEncoder};
 use crate::encodings::physical::binary::{BinaryMiniBlockEncoder, VariableEncoder};
 use crate::encodings::physical::bitpack_fastlanes::BitpackedForNonNegArrayEncoder;
 use crate::encodings::physical::bitpack_fastlanes::{
@@ -32,11 +37,17 @@ use crate::encodings::physical::bitpack_fastlanes::{
 use crate::encodings::physical::block_compress::{
     CompressedBufferEncoder, CompressionConfig, CompressionScheme,
 };
+use crate::encodings::physical::byte_stream_split::{self, ByteStreamSplitMiniBlockEncoder};
+use crate::encodings::physical::delta::{
+    self, DeltaMiniBlockEncoder, FrameOfReferenceMiniBlockEncoder,
+};
 use crate::encodings::physical::dictionary::AlreadyDictionaryEncoder;
 use crate::encodings::physical::fsst::{
     FsstArrayEncoder, FsstMiniBlockEncoder, FsstPerValueEncoder,
 };
 use crate::encodings::physical::packed_struct::PackedStructEncoder;
+use crate::encodings::physical::quantized_vector::{QuantizedVectorEncoder, VectorCompression};
+use crate::encodings::physical::rle::{self, RleMiniBlockEncoder};
 use crate::encodings::physical::struct_encoding::PackedStructFixedWidthMiniBlockEncoder;
 use crate::format::ProtobufUtils;
 use crate::repdef::RepDefBuilder;
@@ -472,6 +483,171 @@ impl CoreArrayEncodingStrategy {
             && data_size > 4 * 1024 * 1024
     }
 
+    /// Whether run-length encoding is expected to be much smaller than the alternative,
+    /// which would take `size_bits`
+    fn prefer_rle(data: &DataBlock, bits_per_value: u64, size_bits: u64) -> bool {
+        if !rle::supports_bits_per_value(bits_per_value) {
+            return false;
+        }
+        let Some(run_count) = data.get_stat(Stat::RunCount) else {
+            return false;
+        };
+        let run_count = run_count.as_primitive::<UInt64Type>().value(0);
+        // Each run needs at most one value and a 12-bit run length.  The values are
+        // bitpacked too so this is pessimistic, but require a clear win anyway as the
+        // estimate ignores chunk overhead.
+        let rle_size_bits = run_count * (bits_per_value + 12);
+        rle_size_bits * 2 < size_bits
+    }
+
+    /// Picks frame-of-reference or delta encoding for integer and temporal data, if either is
+    /// expected to be clearly smaller than the alternative, which would take `size_bits`
+    fn delta_compressor(
+        field: &Field,
+        data: &FixedWidthDataBlock,
+        size_bits: u64,
+    ) -> Option<Box<dyn MiniBlockCompressor>> {
+        let data_type = field.data_type();
+        if !(data_type.is_integer() || data_type.is_temporal())
+            || !delta::supports_bits_per_value(data.bits_per_value)
+        {
+            return None;
+        }
+        let signed = !data_type.is_unsigned_integer();
+        // Ties go to the lowest order, which is the cheapest to decode
+        let (order, estimate_bits) = (0..=delta::MAX_DELTA_ORDER)
+            .filter_map(|order| {
+                delta::estimate_size_bits(data, order, signed).map(|bits| (order, bits))
+            })
+            .min_by_key(|(_, bits)| *bits)?;
+        // The alternative estimate ignores chunk headers, so require a clear win
+        if estimate_bits * 5 >= size_bits * 4 {
+            return None;
+        }
+        if order == 0 {
+            Some(Box::new(FrameOfReferenceMiniBlockEncoder::new(
+                data.bits_per_value,
+                signed,
+            )))
+        } else {
+            Some(Box::new(DeltaMiniBlockEncoder::new(
+                data.bits_per_value,
+                order,
+            )))
+        }
+    }
+
+    /// The compression applied to byte streams, zstd unless the field asks for a zstd level
+    fn byte_stream_compression(field: &Field) -> CompressionConfig {
+        Self::get_field_compression(&field.metadata)
+            .filter(|compression| compression.scheme == CompressionScheme::Zstd)
+            .unwrap_or(CompressionConfig::new(CompressionScheme::Zstd, None))
+    }
+
+    /// Picks ALP or byte stream split for floating point data (including bfloat16), if
+    /// either is expected to be clearly smaller than the alternative, which would take
+    /// `size_bits`
+    fn float_compressor(
+        field: &Field,
+        data: &FixedWidthDataBlock,
+        size_bits: u64,
+    ) -> Option<Box<dyn MiniBlockCompressor>> {
+        let is_bfloat16 = field
+            .metadata
+            .get(ARROW_EXT_NAME_KEY)
+            .is_some_and(|name| name == BFLOAT16_EXT_NAME);
+        if !(field.data_type().is_floating() || is_bfloat16)
+            || !byte_stream_split::supports_bits_per_value(data.bits_per_value)
+        {
+            return None;
+        }
+        let compression = Self::byte_stream_compression(field);
+        let bss_bits = byte_stream_split::estimate_size_bits(data, compression);
+        let alp_bits = alp::supports_bits_per_value(data.bits_per_value)
+            .then(|| alp::estimate_size_bits(data));
+        // Ties go to ALP, which is much cheaper to decode
+        if let Some(alp_bits) = alp_bits.filter(|alp_bits| *alp_bits <= bss_bits) {
+            return (alp_bits * 5 < size_bits * 4).then(|| {
+                Box::new(AlpMiniBlockEncoder::new(data.bits_per_value))
+                    as Box<dyn MiniBlockCompressor>
+            });
+        }
+        (bss_bits * 5 < size_bits * 4).then(|| {
+            Box::new(ByteStreamSplitMiniBlockEncoder::new(compression))
+                as Box<dyn MiniBlockCompressor>
+        })
+    }
+
+    /// Picks byte stream split for fixed size lists of floats (e.g. small vectors) if it is
+    /// expected to be clearly smaller than the uncompressed lists
+    fn float_list_compressor(
+        field: &Field,
+        data: &FixedSizeListBlock,
+    ) -> Option<Box<dyn MiniBlockCompressor>> {
+        let DataType::FixedSizeList(inner, dimension) = field.data_type() else {
+            return None;
+        };
+        // Lists with inner validity can't be split into streams
+        let DataBlock::FixedWidth(items) = data.child.as_ref() else {
+            return None;
+        };
+        if !(inner.data_type().is_floating() || is_bfloat16_field(&inner))
+            || !byte_stream_split::supports_bits_per_value(items.bits_per_value)
+            || !byte_stream_split::supports_bytes_per_row(
+                items.bits_per_value / 8 * dimension as u64,
+            )
+        {
+            return None;
+        }
+        let compression = Self::byte_stream_compression(field);
+        let bss_bits = byte_stream_split::estimate_size_bits(items, compression);
+        (bss_bits * 5 < items.data.len() as u64 * 8 * 4).then(|| {
+            Box::new(ByteStreamSplitMiniBlockEncoder::new(compression))
+                as Box<dyn MiniBlockCompressor>
+        })
+    }
+
+    /// The lossy vector compression requested in the field metadata, if any
+    ///
+    /// Lists with null items are always stored losslessly.
+    fn vector_compressor(
+        field: &Field,
+        data: &FixedSizeListBlock,
+    ) -> Result<Option<QuantizedVectorEncoder>> {
+        let Some(requested) = field.metadata.get(VECTOR_COMPRESSION_META_KEY) else {
+            return Ok(None);
+        };
+        let compression = requested.parse::<VectorCompression>()?;
+        let DataType::FixedSizeList(inner, _) = field.data_type() else {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "The field metadata key {} is only supported on fixed size lists, field {} is {}",
+                    VECTOR_COMPRESSION_META_KEY,
+                    field.name,
+                    field.data_type()
+                )
+                .into(),
+                location: location!(),
+            });
+        };
+        if !matches!(inner.data_type(), DataType::Float32 | DataType::Float64) {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "The field metadata key {} is only supported on lists of float32 or float64, field {} has items of type {}",
+                    VECTOR_COMPRESSION_META_KEY,
+                    field.name,
+                    inner.data_type()
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        if !matches!(data.child.as_ref(), DataBlock::FixedWidth(_)) {
+            return Ok(None);
+        }
+        Ok(Some(QuantizedVectorEncoder::new(compression)))
+    }
+
     fn get_field_compression(field_meta: &HashMap<String, String>) -> Option<CompressionConfig> {
         let compression = field_meta.get(COMPRESSION_META_KEY)?;
         let compression_scheme = compression.parse::<CompressionScheme>();
@@ -823,13 +999,30 @@ impl CompressionStrategy for CoreArrayEncodingStrategy {
                 // size might be smaller than the compressed size.
                 let too_small = bit_widths.len() == 1
                     && InlineBitpacking::min_size_bytes(bit_widths.value(0)) >= data.data_size();
-                if !has_all_zeros
+                let use_bitpacking = !has_all_zeros
                     && !too_small
                     && (fixed_width_data.bits_per_value == 8
                         || fixed_width_data.bits_per_value == 16
                         || fixed_width_data.bits_per_value == 32
-                        || fixed_width_data.bits_per_value == 64)
+                        || fixed_width_data.bits_per_value == 64);
+                let size_bits = if use_bitpacking {
+                    bit_widths.values().iter().map(|width| width * 1024).sum()
+                } else {
+                    data.data_size() * 8
+                };
+                if Self::prefer_rle(data, fixed_width_data.bits_per_value, size_bits) {
+                    Ok(Box::new(RleMiniBlockEncoder::new(
+                        fixed_width_data.bits_per_value,
+                    )))
+                } else if let Some(compressor) =
+                    Self::delta_compressor(field, fixed_width_data, size_bits)
                 {
+                    Ok(compressor)
+                } else if let Some(compressor) =
+                    Self::float_compressor(field, fixed_width_data, size_bits)
+                {
+                    Ok(compressor)
+                } else if use_bitpacking {
                     Ok(Box::new(InlineBitpacking::new(
                         fixed_width_data.bits_per_value,
                     )))
@@ -867,14 +1060,28 @@ impl CompressionStrategy for CoreArrayEncodingStrategy {
                 }
                 Ok(Box::new(PackedStructFixedWidthMiniBlockEncoder::default()))
             }
-            DataBlock::FixedSizeList(_) => {
+            DataBlock::FixedSizeList(fsl_data) => {
                 // Ideally we would compress the list items but this creates something of a challenge.
                 // We don't want to break lists across chunks and we need to worry about inner validity
                 // layers.  If we try and use a compression scheme then it is unlikely to respect these
                 // constraints.
                 //
-                // For now, we just don't compress.  In the future, we might want to consider a more
-                // sophisticated approach.
+                // The exception is byte stream split for lists of floats without inner validity, which
+                // chunks whole lists.  Otherwise, we just don't compress.  In the future, we might want
+                // to consider a more sophisticated approach.
+                //
+                // Lossy vector compression, if requested, also works on whole lists.
+                if let Some(compressor) = Self::vector_compressor(field, fsl_data)? {
+                    return Ok(Box::new(compressor));
+                }
+                if let Some(compression) = field.metadata.get(COMPRESSION_META_KEY) {
+                    if compression == "none" {
+                        return Ok(Box::new(ValueEncoder::default()));
+                    }
+                }
+                if let Some(compressor) = Self::float_list_compressor(field, fsl_data) {
+                    return Ok(compressor);
+                }
                 Ok(Box::new(ValueEncoder::default()))
             }
             _ => Err(Error::NotSupported {
@@ -890,12 +1097,17 @@ impl CompressionStrategy for CoreArrayEncodingStrategy {
 
     fn create_per_value(
         &self,
-        _field: &Field,
+        field: &Field,
         data: &DataBlock,
     ) -> Result<Box<dyn PerValueCompressor>> {
         match data {
             DataBlock::FixedWidth(_) => Ok(Box::new(ValueEncoder::default())),
-            DataBlock::FixedSizeList(_) => Ok(Box::new(ValueEncoder::default())),
+            DataBlock::FixedSizeList(fsl_data) => {
+                if let Some(compressor) = Self::vector_compressor(field, fsl_data)? {
+                    return Ok(Box::new(compressor));
+                }
+                Ok(Box::new(ValueEncoder::default()))
+            }
             DataBlock::VariableWidth(variable_width) => {
                 let max_len = variable_width.expect_single_stat::<UInt64Type>(Stat::MaxLength);
                 let data_size = variable_width.expect_single_stat::<UInt64Type>(Stat::DataSize);
@@ -1104,6 +1316,16 @@ impl FieldEncodingStrategy for CoreFieldEncodingStrategy {
         column_index: &mut ColumnIndexSequence,
         options: &EncodingOptions,
     ) -> Result<Box<dyn FieldEncoder>> {
+        if field.is_shredded_json() {
+            return Err(Error::NotSupported {
+                source: format!(
+                    "shredded JSON fields require file version 2.1 or later (field {})",
+                    field.name
+                )
+                .into(),
+                location: location!(),
+            });
+        }
         let data_type = field.data_type();
         if Self::is_primitive_type(&data_type) {
             let column_index = column_index.next_column_index(field.id as u32);
@@ -1129,7 +1351,7 @@ impl FieldEncodingStrategy for CoreFieldEncodingStrategy {
             }
         } else {
             match data_type {
-                DataType::List(_child) | DataType::LargeList(_child) => {
+                DataType::List(_child) | DataType::LargeList(_child) | DataType::Map(_child, _) => {
                     let list_idx = column_index.next_column_index(field.id as u32);
                     let inner_encoding = encoding_strategy_root.create_field_encoder(
                         encoding_strategy_root,
@@ -1198,6 +1420,14 @@ impl FieldEncodingStrategy for CoreFieldEncodingStrategy {
                         Err(Error::NotSupported { source: format!("cannot encode a dictionary column whose value type is a logical type ({})", value_type).into(), location: location!() })
                     }
                 }
+                DataType::Union(_, _) => Err(Error::NotSupported {
+                    source: format!(
+                        "union fields require file version 2.1 or later (field {})",
+                        field.name
+                    )
+                    .into(),
+                    location: location!(),
+                }),
                 _ => todo!("Implement encoding for field {}", field),
             }
         }
@@ -1267,7 +1497,27 @@ impl StructuralEncodingStrategy {
         root_field_metadata: &HashMap<String, String>,
     ) -> Result<Box<dyn FieldEncoder>> {
         let data_type = field.data_type();
-        if Self::is_primitive_type(&data_type) {
+        if field.is_shredded_json() {
+            let paths = parse_shredded_paths(&field.metadata[JSON_SHREDDED_PATHS_META_KEY])?;
+            let children_encoders = field
+                .children
+                .iter()
+                .map(|field| {
+                    self.do_create_field_encoder(
+                        _encoding_strategy_root,
+                        field,
+                        column_index,
+                        options,
+                        root_field_metadata,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Box::new(JsonStructuralEncoder::new(
+                options.keep_original_array,
+                paths,
+                children_encoders,
+            )))
+        } else if Self::is_primitive_type(&data_type) {
             Ok(Box::new(PrimitiveStructuralEncoder::try_new(
                 options,
                 self.compression_strategy.clone(),
@@ -1277,7 +1527,7 @@ impl StructuralEncodingStrategy {
             )?))
         } else {
             match data_type {
-                DataType::List(_) | DataType::LargeList(_) => {
+                DataType::List(_) | DataType::LargeList(_) | DataType::Map(_, _) => {
                     let child = field.children.first().expect("List should have a child");
                     let child_encoder = self.do_create_field_encoder(
                         _encoding_strategy_root,
@@ -1320,6 +1570,25 @@ impl StructuralEncodingStrategy {
                         )))
                     }
                 }
+                DataType::Union(_, _) => {
+                    let children_encoders = field
+                        .children
+                        .iter()
+                        .map(|field| {
+                            self.do_create_field_encoder(
+                                _encoding_strategy_root,
+                                field,
+                                column_index,
+                                options,
+                                root_field_metadata,
+                            )
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(Box::new(UnionStructuralEncoder::new(
+                        options.keep_original_array,
+                        children_encoders,
+                    )))
+                }
                 DataType::Dictionary(_, value_type) => {
                     // A dictionary of primitive is, itself, primitive
                     if Self::is_primitive_type(&value_type) {
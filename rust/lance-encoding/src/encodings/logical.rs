@@ -3,6 +3,8 @@
 
 pub mod binary;
 pub mod blob;
+pub mod json;
 pub mod list;
 pub mod primitive;
 pub mod r#struct;
+pub mod union;
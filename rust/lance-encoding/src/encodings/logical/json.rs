@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Shredded JSON fields
+//!
+//! The documents of a shredded JSON field are split into a struct of what is
+//! left of the documents and one struct per shredded path (see
+//! [`lance_core::datatypes::json_storage_fields`]) and then encoded as a struct.
+//! Projections of some of the shredded paths are plain structs and are decoded
+//! as such, a projection of the whole field reads back the original documents.
+
+use std::sync::Arc;
+
+use arrow_array::{cast::AsArray, ArrayRef};
+use arrow_schema::DataType;
+use futures::future::BoxFuture;
+use lance_core::{
+    datatypes::{json_storage_fields, shred_json, unshred_json, ShreddedPath},
+    Result,
+};
+
+use crate::{
+    decoder::{DecodedArray, LoadedPage, StructuralDecodeArrayTask, StructuralFieldDecoder},
+    encoder::{EncodeTask, EncodedColumn, FieldEncoder, OutOfLineBuffers},
+    repdef::RepDefBuilder,
+};
+
+use super::r#struct::{StructStructuralEncoder, StructuralStructDecoder};
+
+/// A structural encoder for shredded JSON fields
+pub struct JsonStructuralEncoder {
+    inner: StructStructuralEncoder,
+    paths: Vec<ShreddedPath>,
+}
+
+impl JsonStructuralEncoder {
+    /// Create a new encoder, there must be one child encoder per storage field
+    pub fn new(
+        keep_original_array: bool,
+        paths: Vec<ShreddedPath>,
+        children: Vec<Box<dyn FieldEncoder>>,
+    ) -> Self {
+        Self {
+            inner: StructStructuralEncoder::new(keep_original_array, children),
+            paths,
+        }
+    }
+}
+
+impl FieldEncoder for JsonStructuralEncoder {
+    fn maybe_encode(
+        &mut self,
+        array: ArrayRef,
+        external_buffers: &mut OutOfLineBuffers,
+        repdef: RepDefBuilder,
+        row_number: u64,
+        num_rows: u64,
+    ) -> Result<Vec<EncodeTask>> {
+        let struct_arr = shred_json(array.as_string::<i32>(), &self.paths)?;
+        self.inner.maybe_encode(
+            Arc::new(struct_arr),
+            external_buffers,
+            repdef,
+            row_number,
+            num_rows,
+        )
+    }
+
+    fn flush(&mut self, external_buffers: &mut OutOfLineBuffers) -> Result<Vec<EncodeTask>> {
+        self.inner.flush(external_buffers)
+    }
+
+    fn num_columns(&self) -> u32 {
+        self.inner.num_columns()
+    }
+
+    fn finish(
+        &mut self,
+        external_buffers: &mut OutOfLineBuffers,
+    ) -> BoxFuture<'_, Result<Vec<EncodedColumn>>> {
+        self.inner.finish(external_buffers)
+    }
+}
+
+/// A structural decoder for whole shredded JSON fields
+///
+/// The stored struct is decoded and then the original documents are rebuilt from
+/// it.  Scheduling is handled by a struct scheduler.
+#[derive(Debug)]
+pub struct StructuralJsonDecoder {
+    inner: StructuralStructDecoder,
+    paths: Arc<[ShreddedPath]>,
+}
+
+impl StructuralJsonDecoder {
+    pub fn new(paths: Vec<ShreddedPath>, should_validate: bool) -> Self {
+        let inner = StructuralStructDecoder::new(
+            json_storage_fields(&paths),
+            should_validate,
+            /*is_root=*/ false,
+        );
+        Self {
+            inner,
+            paths: paths.into(),
+        }
+    }
+}
+
+impl StructuralFieldDecoder for StructuralJsonDecoder {
+    fn accept_page(&mut self, child: LoadedPage) -> Result<()> {
+        self.inner.accept_page(child)
+    }
+
+    fn drain(&mut self, num_rows: u64) -> Result<Box<dyn StructuralDecodeArrayTask>> {
+        Ok(Box::new(StructuralJsonDecodeTask {
+            inner: self.inner.drain(num_rows)?,
+            paths: self.paths.clone(),
+        }))
+    }
+
+    fn data_type(&self) -> &DataType {
+        &DataType::Utf8
+    }
+}
+
+#[derive(Debug)]
+struct StructuralJsonDecodeTask {
+    inner: Box<dyn StructuralDecodeArrayTask>,
+    paths: Arc<[ShreddedPath]>,
+}
+
+impl StructuralDecodeArrayTask for StructuralJsonDecodeTask {
+    fn decode(self: Box<Self>) -> Result<DecodedArray> {
+        let DecodedArray { array, repdef } = self.inner.decode()?;
+        let array = unshred_json(array.as_struct(), &self.paths)?;
+        Ok(DecodedArray { array, repdef })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use arrow_array::StringArray;
+    use lance_arrow::bfloat16::ARROW_EXT_NAME_KEY;
+    use lance_core::datatypes::{JSON_EXT_NAME, JSON_SHREDDED_PATHS_META_KEY};
+
+    use crate::{
+        testing::{check_round_trip_encoding_of_data, TestCases},
+        version::LanceFileVersion,
+    };
+
+    #[test_log::test(tokio::test)]
+    async fn test_shredded_json() {
+        // The documents are read back exactly as written
+        let docs = StringArray::from(vec![
+            Some(r#"{"user": {"name": "a", "id": 1}, "score": 1.5}"#),
+            None,
+            Some(r#"{"user":{"id":"two","name":"b"}}"#),
+            Some(r#"{"tags":["x","y"],"user":{"name":"c","big":123456789012345678901234}}"#),
+            Some(r#"[1,2,3]"#),
+            Some(r#"{"user": "#),
+        ]);
+        let metadata = HashMap::from([
+            (ARROW_EXT_NAME_KEY.to_string(), JSON_EXT_NAME.to_string()),
+            (
+                JSON_SHREDDED_PATHS_META_KEY.to_string(),
+                "user.id:int64,user.name:string".to_string(),
+            ),
+        ]);
+
+        let test_cases = TestCases::default()
+            .with_range(0..2)
+            .with_range(1..4)
+            .with_indices(vec![0, 2, 4, 5])
+            .with_file_version(LanceFileVersion::V2_1);
+        check_round_trip_encoding_of_data(vec![Arc::new(docs)], &test_cases, metadata).await;
+    }
+}
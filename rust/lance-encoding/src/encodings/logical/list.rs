@@ -7,7 +7,8 @@ use arrow_array::{
     cast::AsArray,
     new_empty_array,
     types::{Int32Type, Int64Type, UInt64Type},
-    Array, ArrayRef, BooleanArray, Int32Array, Int64Array, LargeListArray, ListArray, UInt64Array,
+    Array, ArrayRef, BooleanArray, Int32Array, Int64Array, LargeListArray, ListArray, MapArray,
+    UInt64Array,
 };
 use arrow_buffer::{BooleanBuffer, BooleanBufferBuilder, Buffer, NullBuffer, OffsetBuffer};
 use arrow_schema::{DataType, Field, Fields};
@@ -538,15 +539,16 @@ impl ListFieldScheduler {
     pub fn new(
         offsets_scheduler: Arc<dyn FieldScheduler>,
         items_scheduler: Arc<dyn FieldScheduler>,
-        items_field: Arc<Field>,
-        // Should be int32 or int64
-        offset_type: DataType,
+        // Should be list, large list, or map
+        list_type: DataType,
         offset_page_info: Vec<OffsetPageInfo>,
     ) -> Self {
-        let list_type = match &offset_type {
-            DataType::Int32 => DataType::List(items_field.clone()),
-            DataType::Int64 => DataType::LargeList(items_field.clone()),
-            _ => panic!("Unexpected offset type {}", offset_type),
+        let (items_field, offset_type) = match &list_type {
+            DataType::List(items_field) | DataType::Map(items_field, _) => {
+                (items_field.clone(), DataType::Int32)
+            }
+            DataType::LargeList(items_field) => (items_field.clone(), DataType::Int64),
+            _ => panic!("Unexpected list type {}", list_type),
         };
         Self {
             offsets_scheduler,
@@ -616,6 +618,7 @@ struct ListDecodeTask {
     items: Option<Box<dyn DecodeArrayTask>>,
     items_field: Arc<Field>,
     offset_type: DataType,
+    data_type: DataType,
 }
 
 impl DecodeArrayTask for ListDecodeTask {
@@ -650,6 +653,16 @@ impl DecodeArrayTask for ListDecodeTask {
                 let offsets_i32 = offsets.as_primitive::<Int32Type>();
                 let offsets = OffsetBuffer::new(offsets_i32.values().clone());
 
+                if let DataType::Map(_, keys_sorted) = &self.data_type {
+                    return Ok(Arc::new(MapArray::try_new(
+                        self.items_field.clone(),
+                        offsets,
+                        items.as_struct().clone(),
+                        validity,
+                        *keys_sorted,
+                    )?));
+                }
+
                 Ok(Arc::new(ListArray::try_new(
                     self.items_field.clone(),
                     offsets,
@@ -794,6 +807,7 @@ impl LogicalPageDecoder for ListPageDecoder {
                 items_field: self.items_field.clone(),
                 items: item_decode,
                 offset_type: self.offset_type.clone(),
+                data_type: self.data_type.clone(),
             }) as Box<dyn DecodeArrayTask>,
         })
     }
@@ -1175,6 +1189,26 @@ impl ListFieldEncoder {
     }
 }
 
+/// Maps are stored as a list of key/value structs
+///
+/// The map-specific parts of the type (e.g. whether the keys are sorted) are not
+/// needed to encode the data.  They come from the schema when decoding.
+fn map_as_list(array: ArrayRef) -> ArrayRef {
+    if let Some(map_arr) = array.as_map_opt() {
+        let DataType::Map(entries_field, _) = map_arr.data_type() else {
+            unreachable!()
+        };
+        Arc::new(ListArray::new(
+            entries_field.clone(),
+            map_arr.offsets().clone(),
+            Arc::new(map_arr.entries().clone()),
+            map_arr.nulls().cloned(),
+        ))
+    } else {
+        array
+    }
+}
+
 impl FieldEncoder for ListFieldEncoder {
     fn maybe_encode(
         &mut self,
@@ -1184,6 +1218,7 @@ impl FieldEncoder for ListFieldEncoder {
         row_number: u64,
         num_rows: u64,
     ) -> Result<Vec<EncodeTask>> {
+        let array = map_as_list(array);
         // The list may have an offset / shorter length which means the underlying
         // values array could be longer than what we need to encode and so we need
         // to slice down to the region of interest.
@@ -1290,6 +1325,7 @@ impl FieldEncoder for ListStructuralEncoder {
         row_number: u64,
         num_rows: u64,
     ) -> Result<Vec<EncodeTask>> {
+        let array = map_as_list(array);
         let values = if let Some(list_arr) = array.as_list_opt::<i32>() {
             let has_garbage_values = if self.keep_original_array {
                 repdef.add_offsets(list_arr.offsets().clone(), array.nulls().cloned())
@@ -1336,6 +1372,13 @@ impl FieldEncoder for ListStructuralEncoder {
     }
 }
 
+/// Schedules the items of a list
+///
+/// In the structural encoding the offsets and validity of a list are stored in the
+/// repetition and definition levels of each leaf column so there is no list column to
+/// read.  The child scheduler is created from the projected field and so, if only some
+/// fields of a list of structs are projected (e.g. `tags.name`), only those leaf columns
+/// (and their repdef) are read.
 #[derive(Debug)]
 pub struct StructuralListScheduler {
     child: Box<dyn StructuralFieldScheduler>,
@@ -1457,6 +1500,20 @@ impl StructuralDecodeArrayTask for StructuralListDecodeTask {
                     repdef,
                 })
             }
+            DataType::Map(entries_field, keys_sorted) => {
+                let (offsets, validity) = repdef.unravel_offsets::<i32>()?;
+                let map_array = MapArray::try_new(
+                    entries_field.clone(),
+                    offsets,
+                    array.as_struct().clone(),
+                    validity,
+                    *keys_sorted,
+                )?;
+                Ok(DecodedArray {
+                    array: Arc::new(map_array),
+                    repdef,
+                })
+            }
             _ => panic!("List decoder did not have a list field"),
         }
     }
@@ -1469,9 +1526,9 @@ mod tests {
 
     use arrow::array::{Int64Builder, LargeListBuilder, StringBuilder};
     use arrow_array::{
-        builder::{Int32Builder, ListBuilder},
-        Array, ArrayRef, BooleanArray, DictionaryArray, LargeStringArray, ListArray, StructArray,
-        UInt64Array, UInt8Array,
+        builder::{Int32Builder, ListBuilder, MapBuilder},
+        Array, ArrayRef, BooleanArray, DictionaryArray, LargeStringArray, ListArray, MapArray,
+        StructArray, UInt64Array, UInt8Array,
     };
     use arrow_buffer::{BooleanBuffer, NullBuffer, OffsetBuffer, ScalarBuffer};
     use arrow_schema::{DataType, Field, Fields};
@@ -1611,6 +1668,35 @@ mod tests {
             .await;
     }
 
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_map(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+        #[values(false, true)] keys_sorted: bool,
+    ) {
+        let mut map_builder = MapBuilder::new(None, StringBuilder::new(), Int32Builder::new());
+        map_builder.keys().append_value("a");
+        map_builder.values().append_value(1);
+        map_builder.keys().append_value("b");
+        map_builder.values().append_null();
+        map_builder.append(true).unwrap();
+        map_builder.append(false).unwrap();
+        map_builder.append(true).unwrap();
+        map_builder.keys().append_value("c");
+        map_builder.values().append_value(3);
+        map_builder.append(true).unwrap();
+        let (entries_field, offsets, entries, nulls, _) = map_builder.finish().into_parts();
+        let map_array = MapArray::new(entries_field, offsets, entries, nulls, keys_sorted);
+
+        let test_cases = TestCases::default()
+            .with_range(0..2)
+            .with_range(1..4)
+            .with_indices(vec![0, 3])
+            .with_file_version(version);
+        check_round_trip_encoding_of_data(vec![Arc::new(map_array)], &test_cases, HashMap::new())
+            .await;
+    }
+
     #[rstest]
     #[test_log::test(tokio::test)]
     async fn test_simple_nested_list_ends_with_null(
@@ -7,7 +7,7 @@ use std::{
     fmt::Debug,
     iter,
     ops::Range,
-    sync::Arc,
+    sync::{Arc, Mutex},
     vec,
 };
 
@@ -22,6 +22,7 @@ use lance_core::{
     cache::{Context, DeepSizeOf},
     datatypes::{
         STRUCTURAL_ENCODING_FULLZIP, STRUCTURAL_ENCODING_META_KEY, STRUCTURAL_ENCODING_MINIBLOCK,
+        VECTOR_COMPRESSION_META_KEY,
     },
     error::Error,
     utils::bit::pad_bytes,
@@ -34,10 +35,10 @@ use crate::repdef::{
     build_control_word_iterator, CompositeRepDefUnraveler, ControlWordIterator, ControlWordParser,
     DefinitionInterpretation, RepDefSlicer,
 };
-use crate::statistics::{ComputeStat, GetStat, Stat};
+use crate::statistics::{compute_page_statistics, ComputeStat, GetStat, Stat};
 use crate::utils::bytepack::ByteUnpacker;
 use crate::{
-    data::{AllNullDataBlock, DataBlock, VariableWidthBlock},
+    data::{AllNullDataBlock, DataBlock, DictionaryDataBlock, VariableWidthBlock},
     utils::bytepack::BytepackedIntegerEncoder,
 };
 use crate::{
@@ -61,7 +62,10 @@ use crate::{
         ArrayEncodingStrategy, CompressionStrategy, EncodeTask, EncodedColumn, EncodedPage,
         EncodingOptions, FieldEncoder, MiniBlockChunk, MiniBlockCompressed, OutOfLineBuffers,
     },
-    encodings::physical::{decoder_from_array_encoding, ColumnBuffers, PageBuffers},
+    encodings::physical::{
+        decoder_from_array_encoding, quantized_vector::VectorCompression, ColumnBuffers,
+        PageBuffers,
+    },
     format::{pb, ProtobufUtils},
     repdef::{LevelBuffer, RepDefBuilder, RepDefUnraveler},
     EncodingsIo,
@@ -311,7 +315,9 @@ struct ChunkMeta {
 struct DecodedMiniBlockChunk {
     rep: Option<ScalarBuffer<u16>>,
     def: Option<ScalarBuffer<u16>>,
-    values: DataBlock,
+    /// The still compressed value buffers, these are decompressed once we know
+    /// which values are needed
+    value_buffers: Vec<LanceBuffer>,
 }
 
 /// A task to decode a one or more mini-blocks of data into an output batch
@@ -602,11 +608,7 @@ impl DecodeMiniBlockTask {
     }
 
     // Unserialize a miniblock into a collection of vectors
-    fn decode_miniblock_chunk(
-        &self,
-        buf: &LanceBuffer,
-        items_in_chunk: u64,
-    ) -> Result<DecodedMiniBlockChunk> {
+    fn decode_miniblock_chunk(&self, buf: &LanceBuffer) -> Result<DecodedMiniBlockChunk> {
         let mut offset = 0;
         let num_levels = u16::from_le_bytes([buf[offset], buf[offset + 1]]);
         offset += 2;
@@ -659,10 +661,6 @@ impl DecodeMiniBlockTask {
             })
             .collect::<Vec<_>>();
 
-        let values = self
-            .value_decompressor
-            .decompress(buffers, items_in_chunk)?;
-
         let rep = rep
             .map(|rep| {
                 Self::decode_levels(
@@ -682,7 +680,11 @@ impl DecodeMiniBlockTask {
             })
             .transpose()?;
 
-        Ok(DecodedMiniBlockChunk { rep, def, values })
+        Ok(DecodedMiniBlockChunk {
+            rep,
+            def,
+            value_buffers: buffers,
+        })
     }
 }
 
@@ -711,8 +713,11 @@ impl DecodePageTask for DecodeMiniBlockTask {
             // TODO: It's very possible that we have duplicate `buf` in self.instructions and we
             // don't want to decode the buf again and again on the same thread.
 
-            let DecodedMiniBlockChunk { rep, def, values } =
-                self.decode_miniblock_chunk(&chunk.data, chunk.items_in_chunk)?;
+            let DecodedMiniBlockChunk {
+                rep,
+                def,
+                value_buffers,
+            } = self.decode_miniblock_chunk(&chunk.data)?;
 
             // Our instructions tell us which rows we want to take from this chunk
             let row_range_start =
@@ -734,37 +739,47 @@ impl DecodePageTask for DecodeMiniBlockTask {
             Self::extend_levels(level_range.clone(), &mut repbuf, &rep, level_offset);
             Self::extend_levels(level_range.clone(), &mut defbuf, &def, level_offset);
             level_offset += (level_range.end - level_range.start) as usize;
-            data_builder.append(&values, item_range);
+
+            // If we only need some of the values then some encodings can avoid
+            // decompressing the entire chunk
+            let partial_values = if item_range.end - item_range.start < chunk.items_in_chunk {
+                self.value_decompressor.decompress_range(
+                    &value_buffers,
+                    chunk.items_in_chunk,
+                    item_range.clone(),
+                )?
+            } else {
+                None
+            };
+            if let Some(values) = partial_values {
+                let num_values = values.num_values();
+                data_builder.append(&values, 0..num_values);
+            } else {
+                let values = self
+                    .value_decompressor
+                    .decompress(value_buffers, chunk.items_in_chunk)?;
+                data_builder.append(&values, item_range);
+            }
         }
 
         let data = data_builder.finish();
 
         let unraveler = RepDefUnraveler::new(repbuf, defbuf, self.def_meaning.clone());
 
-        // if dictionary encoding is applied, do dictionary decode here.
-        if let Some(dictionary) = &self.dictionary_data {
-            // assume the indices are uniformly distributed.
-            let estimated_size_bytes = dictionary.data_size()
-                * (data.num_values() + dictionary.num_values() - 1)
-                / dictionary.num_values();
-            let mut data_builder = DataBlockBuilder::with_capacity_estimate(estimated_size_bytes);
-
-            // if dictionary encoding is applied, indices are of type `UInt8`
-            if let DataBlock::FixedWidth(mut fixed_width_data_block) = data {
-                let indices = fixed_width_data_block.data.borrow_to_typed_slice::<u8>();
-                let indices = indices.as_ref();
-
-                indices.iter().for_each(|&idx| {
-                    data_builder.append(dictionary, idx as u64..idx as u64 + 1);
-                });
-
-                let data = data_builder.finish();
-                return Ok(DecodedPage {
-                    data,
-                    repdef: unraveler,
-                });
-            }
-        }
+        // If dictionary encoding is applied then the values are indices into the dictionary,
+        // the dictionary is only applied once we know the output type
+        let data = if let Some(dictionary) = &self.dictionary_data {
+            DataBlock::Dictionary(DictionaryDataBlock {
+                indices: data.as_fixed_width().ok_or_else(|| Error::InvalidInput {
+                    source: "the indices of a dictionary encoded mini-block must be fixed width"
+                        .into(),
+                    location: location!(),
+                })?,
+                dictionary: Box::new(dictionary.try_clone()?),
+            })
+        } else {
+            data
+        };
 
         Ok(DecodedPage {
             data,
@@ -1158,6 +1173,84 @@ struct MiniBlockSchedulerDictionary {
     num_dictionary_items: u64,
 }
 
+impl MiniBlockSchedulerDictionary {
+    fn try_new(
+        dictionary_encoding: &pb::ArrayEncoding,
+        num_dictionary_items: u64,
+        dictionary_buf_position_and_size: (u64, u64),
+        decompressors: &dyn DecompressorStrategy,
+    ) -> Result<Self> {
+        let dictionary_data_alignment = match dictionary_encoding.array_encoding.as_ref().unwrap() {
+            pb::array_encoding::ArrayEncoding::Variable(_) => 4,
+            pb::array_encoding::ArrayEncoding::Flat(_) => 16,
+            _ => {
+                unreachable!("Currently only encodings `BinaryBlock` and `Flat` used for encoding MiniBlock dictionary.")
+            }
+        };
+        Ok(Self {
+            dictionary_decompressor: decompressors
+                .create_block_decompressor(dictionary_encoding)?
+                .into(),
+            dictionary_buf_position_and_size,
+            dictionary_data_alignment,
+            num_dictionary_items,
+        })
+    }
+
+    fn decode(&self, dictionary_data: bytes::Bytes) -> Result<DataBlock> {
+        let mut dictionary = self.dictionary_decompressor.decompress(
+            LanceBuffer::from_bytes(dictionary_data, self.dictionary_data_alignment),
+            self.num_dictionary_items,
+        )?;
+        // Switch to borrowed buffers so that every decoded batch can share them
+        dictionary.borrow_and_clone();
+        Ok(dictionary)
+    }
+}
+
+/// The dictionary shared by all of the mini-block pages in a column
+///
+/// The dictionary is loaded (once) by the first page that needs it
+#[derive(Debug)]
+struct ColumnDictionary {
+    dictionary: MiniBlockSchedulerDictionary,
+    loaded: tokio::sync::OnceCell<Arc<DataBlock>>,
+}
+
+impl ColumnDictionary {
+    fn try_new(
+        column_info: &ColumnInfo,
+        decompressors: &dyn DecompressorStrategy,
+    ) -> Result<Option<Arc<Self>>> {
+        let Some(pb::column_encoding::ColumnEncoding::Dictionary(column_dictionary)) =
+            column_info.encoding.column_encoding.as_ref()
+        else {
+            return Ok(None);
+        };
+        let dictionary = MiniBlockSchedulerDictionary::try_new(
+            column_dictionary.dictionary.as_ref().unwrap(),
+            column_dictionary.num_dictionary_items,
+            column_info.buffer_offsets_and_sizes[0],
+            decompressors,
+        )?;
+        Ok(Some(Arc::new(Self {
+            dictionary,
+            loaded: tokio::sync::OnceCell::new(),
+        })))
+    }
+
+    async fn load(&self, io: &Arc<dyn EncodingsIo>) -> Result<Arc<DataBlock>> {
+        self.loaded
+            .get_or_try_init(|| async {
+                let (position, size) = self.dictionary.dictionary_buf_position_and_size;
+                let dictionary_data = io.submit_single(position..position + size, 0).await?;
+                Ok(Arc::new(self.dictionary.decode(dictionary_data)?))
+            })
+            .await
+            .cloned()
+    }
+}
+
 #[derive(Debug)]
 struct RepIndexBlock {
     // The index of the first row that starts after the beginning of this block.  If the block
@@ -1232,16 +1325,21 @@ struct MiniBlockCacheableState {
     rep_index: RepetitionIndex,
     /// The dictionary for the page, if any
     dictionary: Option<Arc<DataBlock>>,
+    /// True if the dictionary is the column dictionary (and is not owned by the page)
+    is_column_dictionary: bool,
 }
 
 impl DeepSizeOf for MiniBlockCacheableState {
     fn deep_size_of_children(&self, context: &mut Context) -> usize {
-        self.rep_index.deep_size_of_children(context)
-            + self
-                .dictionary
+        let dictionary_size = if self.is_column_dictionary {
+            0
+        } else {
+            self.dictionary
                 .as_ref()
                 .map(|dict| dict.data_size() as usize)
                 .unwrap_or(0)
+        };
+        self.rep_index.deep_size_of_children(context) + dictionary_size
     }
 }
 
@@ -1290,6 +1388,7 @@ pub struct MiniBlockScheduler {
     value_decompressor: Arc<dyn MiniBlockDecompressor>,
     def_meaning: Arc<[DefinitionInterpretation]>,
     dictionary: Option<MiniBlockSchedulerDictionary>,
+    column_dictionary: Option<Arc<ColumnDictionary>>,
     // This is set after initialization
     page_meta: Option<Arc<MiniBlockCacheableState>>,
 }
@@ -1300,6 +1399,7 @@ impl MiniBlockScheduler {
         priority: u64,
         items_in_page: u64,
         layout: &pb::MiniBlockLayout,
+        column_dictionary: Option<&Arc<ColumnDictionary>>,
         decompressors: &dyn DecompressorStrategy,
     ) -> Result<Self> {
         let rep_decompressor = layout
@@ -1327,31 +1427,28 @@ impl MiniBlockScheduler {
             .collect::<Vec<_>>();
         let value_decompressor = decompressors
             .create_miniblock_decompressor(layout.value_compression.as_ref().unwrap())?;
-        let dictionary = if let Some(dictionary_encoding) = layout.dictionary.as_ref() {
-            let num_dictionary_items = layout.num_dictionary_items;
-            match dictionary_encoding.array_encoding.as_ref().unwrap() {
-                pb::array_encoding::ArrayEncoding::Variable(_) => {
-                    Some(MiniBlockSchedulerDictionary {
-                        dictionary_decompressor: decompressors
-                            .create_block_decompressor(dictionary_encoding)?
+        let dictionary = layout
+            .dictionary
+            .as_ref()
+            .map(|dictionary_encoding| {
+                MiniBlockSchedulerDictionary::try_new(
+                    dictionary_encoding,
+                    layout.num_dictionary_items,
+                    buffer_offsets_and_sizes[2],
+                    decompressors,
+                )
+            })
+            .transpose()?;
+        let column_dictionary = if layout.column_dictionary {
+            Some(
+                column_dictionary
+                    .ok_or_else(|| Error::InvalidInput {
+                        source: "mini-block page uses a column dictionary but the column has none"
                             .into(),
-                        dictionary_buf_position_and_size: buffer_offsets_and_sizes[2],
-                        dictionary_data_alignment: 4,
-                        num_dictionary_items,
-                    })
-                }
-                pb::array_encoding::ArrayEncoding::Flat(_) => Some(MiniBlockSchedulerDictionary {
-                    dictionary_decompressor: decompressors
-                        .create_block_decompressor(dictionary_encoding)?
-                        .into(),
-                    dictionary_buf_position_and_size: buffer_offsets_and_sizes[2],
-                    dictionary_data_alignment: 16,
-                    num_dictionary_items,
-                }),
-                _ => {
-                    unreachable!("Currently only encodings `BinaryBlock` and `Flat` used for encoding MiniBlock dictionary.")
-                }
-            }
+                        location: location!(),
+                    })?
+                    .clone(),
+            )
         } else {
             None
         };
@@ -1366,6 +1463,7 @@ impl MiniBlockScheduler {
             priority,
             items_in_page,
             dictionary,
+            column_dictionary,
             def_meaning: def_meaning.into(),
             page_meta: None,
         })
@@ -1648,6 +1746,7 @@ impl StructuralPageScheduler for MiniBlockScheduler {
             required_ranges.push(*rep_index_pos..*rep_index_pos + *rep_index_size);
         }
         let io_req = io.submit_request(required_ranges, 0);
+        let io = io.clone();
 
         async move {
             let mut buffers = io_req.await?.into_iter().fuse();
@@ -1716,20 +1815,17 @@ impl StructuralPageScheduler for MiniBlockScheduler {
                 chunk_meta,
                 rep_index: RepetitionIndex::decode(&rep_index),
                 dictionary: None,
+                is_column_dictionary: false,
             };
 
             // decode dictionary
-            if let Some(ref mut dictionary) = self.dictionary {
+            if let Some(ref dictionary) = self.dictionary {
                 let dictionary_data = dictionary_bytes.unwrap();
-                page_meta.dictionary =
-                    Some(Arc::new(dictionary.dictionary_decompressor.decompress(
-                        LanceBuffer::from_bytes(
-                            dictionary_data,
-                            dictionary.dictionary_data_alignment,
-                        ),
-                        dictionary.num_dictionary_items,
-                    )?));
-            };
+                page_meta.dictionary = Some(Arc::new(dictionary.decode(dictionary_data)?));
+            } else if let Some(ref column_dictionary) = self.column_dictionary {
+                page_meta.dictionary = Some(column_dictionary.load(&io).await?);
+                page_meta.is_column_dictionary = true;
+            }
             let page_meta = Arc::new(page_meta);
             self.page_meta = Some(page_meta.clone());
             Ok(page_meta as Arc<dyn CachedPageData>)
@@ -2780,6 +2876,7 @@ impl StructuralPrimitiveFieldScheduler {
         column_info: &ColumnInfo,
         decompressors: &dyn DecompressorStrategy,
     ) -> Result<Self> {
+        let column_dictionary = ColumnDictionary::try_new(column_info, decompressors)?;
         let page_schedulers = column_info
             .page_infos
             .iter()
@@ -2789,6 +2886,7 @@ impl StructuralPrimitiveFieldScheduler {
                     page_info,
                     page_index,
                     column_info.index as usize,
+                    column_dictionary.as_ref(),
                     decompressors,
                 )
             })
@@ -2803,6 +2901,7 @@ impl StructuralPrimitiveFieldScheduler {
         page_info: &PageInfo,
         page_index: usize,
         _column_index: usize,
+        column_dictionary: Option<&Arc<ColumnDictionary>>,
         decompressors: &dyn DecompressorStrategy,
     ) -> Result<PageInfoAndScheduler> {
         let scheduler: Box<dyn StructuralPageScheduler> =
@@ -2813,6 +2912,7 @@ impl StructuralPrimitiveFieldScheduler {
                         page_info.priority,
                         mini_block.num_items,
                         mini_block,
+                        column_dictionary,
                         decompressors,
                     )?)
                 }
@@ -3089,6 +3189,100 @@ pub struct StructuralCompositeDecodeArrayTask {
 }
 
 impl StructuralCompositeDecodeArrayTask {
+    // Dictionary encoded pages are returned as dictionary arrays (sharing the dictionary) if
+    // a dictionary was requested and the requested keys can index the whole dictionary.
+    // Otherwise they are materialized (and dictionary encoded again if requested).  Other
+    // pages are dictionary encoded if a dictionary was requested.
+    fn page_to_array(
+        data: DataBlock,
+        data_type: &DataType,
+        should_validate: bool,
+    ) -> Result<ArrayRef> {
+        match (data, data_type) {
+            (DataBlock::Dictionary(dictionary), DataType::Dictionary(key_type, value_type))
+                if Self::fits_key_type(dictionary.dictionary.num_values(), key_type) =>
+            {
+                let stored_type = DataType::Dictionary(
+                    Box::new(Self::index_type(&dictionary.indices)?),
+                    value_type.clone(),
+                );
+                let array = make_array(
+                    DataBlock::Dictionary(dictionary)
+                        .into_arrow(stored_type.clone(), should_validate)?,
+                );
+                if &stored_type == data_type {
+                    Ok(array)
+                } else {
+                    Ok(arrow_cast::cast(array.as_ref(), data_type)?)
+                }
+            }
+            (DataBlock::Dictionary(dictionary), DataType::Dictionary(_, value_type)) => {
+                let values = Self::materialize(dictionary, value_type, should_validate)?;
+                Ok(arrow_cast::cast(values.as_ref(), data_type)?)
+            }
+            (DataBlock::Dictionary(dictionary), _) => {
+                Self::materialize(dictionary, data_type, should_validate)
+            }
+            (data, DataType::Dictionary(_, value_type)) => {
+                let values =
+                    make_array(data.into_arrow(value_type.as_ref().clone(), should_validate)?);
+                Ok(arrow_cast::cast(values.as_ref(), data_type)?)
+            }
+            (data, _) => Ok(make_array(
+                data.into_arrow(data_type.clone(), should_validate)?,
+            )),
+        }
+    }
+
+    fn materialize(
+        dictionary: DictionaryDataBlock,
+        value_type: &DataType,
+        should_validate: bool,
+    ) -> Result<ArrayRef> {
+        let index_type = Self::index_type(&dictionary.indices)?;
+        let indices = make_array(
+            DataBlock::FixedWidth(dictionary.indices).into_arrow(index_type, should_validate)?,
+        );
+        let values = make_array(
+            dictionary
+                .dictionary
+                .into_arrow(value_type.clone(), should_validate)?,
+        );
+        Ok(arrow_select::take::take(
+            values.as_ref(),
+            indices.as_ref(),
+            None,
+        )?)
+    }
+
+    // True if every entry of a dictionary with `len` values can be referenced by a key of
+    // type `key_type`
+    fn fits_key_type(len: u64, key_type: &DataType) -> bool {
+        let max_len = match key_type {
+            DataType::Int8 => 1 << 7,
+            DataType::UInt8 => 1 << 8,
+            DataType::Int16 => 1 << 15,
+            DataType::UInt16 => 1 << 16,
+            DataType::Int32 => 1 << 31,
+            DataType::UInt32 => 1 << 32,
+            _ => u64::MAX,
+        };
+        len <= max_len
+    }
+
+    fn index_type(indices: &FixedWidthDataBlock) -> Result<DataType> {
+        match indices.bits_per_value {
+            8 => Ok(DataType::UInt8),
+            16 => Ok(DataType::UInt16),
+            32 => Ok(DataType::UInt32),
+            64 => Ok(DataType::UInt64),
+            bits => Err(Error::InvalidInput {
+                source: format!("dictionary indices with {} bits per value", bits).into(),
+                location: location!(),
+            }),
+        }
+    }
+
     fn restore_validity(
         array: Arc<dyn Array>,
         unraveler: &mut CompositeRepDefUnraveler,
@@ -3122,11 +3316,7 @@ impl StructuralDecodeArrayTask for StructuralCompositeDecodeArrayTask {
             let decoded = task.decode()?;
             unravelers.push(decoded.repdef);
 
-            let array = make_array(
-                decoded
-                    .data
-                    .into_arrow(self.data_type.clone(), self.should_validate)?,
-            );
+            let array = Self::page_to_array(decoded.data, &self.data_type, self.should_validate)?;
 
             arrays.push(array);
         }
@@ -3458,6 +3648,115 @@ struct SerializedFullZip {
 // the page" and "aligned to 8 bytes from the start of the file."
 const MINIBLOCK_ALIGNMENT: usize = 8;
 
+/// The dictionary of a dictionary encoded mini-block page
+enum MiniBlockDictionary {
+    /// The dictionary is stored in the page
+    Page(DataBlock),
+    /// The values are indices into the column dictionary
+    Column,
+}
+
+/// The largest column dictionary we will build (the indices are stored as u16)
+const MAX_COLUMN_DICTIONARY_ITEMS: usize = u16::MAX as usize + 1;
+/// The largest column dictionary we will build, in bytes
+///
+/// Readers load the entire dictionary when the column is first read
+const MAX_COLUMN_DICTIONARY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Builds a dictionary that is shared by all of the mini-block pages in a column
+///
+/// Pages add their values as they are encoded, indices handed out to earlier pages
+/// remain valid because the dictionary only grows.
+#[derive(Debug)]
+struct ColumnDictionaryBuilder {
+    indices: HashMap<Vec<u8>, u16>,
+    data: Vec<u8>,
+    offsets: Vec<u32>,
+}
+
+impl ColumnDictionaryBuilder {
+    fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            data: Vec::new(),
+            offsets: vec![0],
+        }
+    }
+
+    /// Converts a block of values into (u16) indices into the dictionary
+    ///
+    /// Returns None, and leaves the dictionary untouched, if the values would add
+    /// too many new entries (more than one per ten values) or the dictionary would
+    /// grow too large.
+    fn encode(&mut self, block: &mut VariableWidthBlock) -> Option<DataBlock> {
+        let offsets = block.offsets.borrow_to_typed_slice::<u32>();
+        let offsets = offsets.as_ref();
+        let values = offsets
+            .iter()
+            .tuple_windows()
+            .map(|(&start, &end)| &block.data[start as usize..end as usize]);
+
+        let new_values = values
+            .clone()
+            .filter(|value| !self.indices.contains_key(*value))
+            .unique()
+            .collect::<Vec<_>>();
+        let new_bytes = new_values.iter().map(|value| value.len()).sum::<usize>();
+        if new_values.len() * 10 > block.num_values as usize
+            || self.indices.len() + new_values.len() > MAX_COLUMN_DICTIONARY_ITEMS
+            || self.data.len() + new_bytes > MAX_COLUMN_DICTIONARY_BYTES
+        {
+            return None;
+        }
+
+        let indices = values
+            .map(|value| match self.indices.get(value) {
+                Some(idx) => *idx,
+                None => {
+                    let idx = self.indices.len() as u16;
+                    self.indices.insert(value.to_vec(), idx);
+                    self.data.extend_from_slice(value);
+                    self.offsets.push(self.data.len() as u32);
+                    idx
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut indices = DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::reinterpret_vec(indices),
+            bits_per_value: 16,
+            num_values: block.num_values,
+            block_info: BlockInfo::default(),
+        });
+        indices.compute_stat();
+        Some(indices)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    fn finish(self) -> DataBlock {
+        DataBlock::VariableWidth(VariableWidthBlock {
+            num_values: self.indices.len() as u64,
+            data: LanceBuffer::Owned(self.data),
+            offsets: LanceBuffer::reinterpret_vec(self.offsets),
+            bits_per_offset: 32,
+            block_info: BlockInfo::default(),
+        })
+    }
+}
+
+/// Whether or not a column uses a column dictionary
+///
+/// This is decided (based on the cardinality) when the first page is encoded
+#[derive(Debug, Default)]
+enum ColumnDictionaryState {
+    #[default]
+    Undecided,
+    Disabled,
+    Enabled(ColumnDictionaryBuilder),
+}
+
 /// An encoder for primitive (leaf) arrays
 ///
 /// This encoder is fairly complicated and follows a number of paths depending
@@ -3493,6 +3792,8 @@ pub struct PrimitiveStructuralEncoder {
     column_index: u32,
     field: Field,
     encoding_metadata: Arc<HashMap<String, String>>,
+    // Shared by the encode tasks of the column's pages
+    column_dictionary: Arc<Mutex<ColumnDictionaryState>>,
 }
 
 struct CompressedLevelsChunk {
@@ -3531,6 +3832,7 @@ impl PrimitiveStructuralEncoder {
             compression_strategy,
             field,
             encoding_metadata,
+            column_dictionary: Arc::new(Mutex::new(ColumnDictionaryState::default())),
         })
     }
 
@@ -3568,6 +3870,20 @@ impl PrimitiveStructuralEncoder {
         Self::is_narrow(data_block)
     }
 
+    /// Whether the field asks for lossy vector compression too wide for mini-blocks
+    ///
+    /// These pages are full-zipped even if mini-block was requested.
+    fn requires_fullzip(field: &Field, data_block: &DataBlock) -> bool {
+        let DataBlock::FixedSizeList(fsl) = data_block else {
+            return false;
+        };
+        field
+            .metadata
+            .get(VECTOR_COMPRESSION_META_KEY)
+            .and_then(|requested| requested.parse::<VectorCompression>().ok())
+            .is_some_and(|compression| !compression.supports_miniblock(fsl.dimension))
+    }
+
     fn prefers_fullzip(encoding_metadata: &HashMap<String, String>) -> bool {
         // Fullzip is the backup option so the only reason we wouldn't use it is if the
         // user specifically requested not to use it (in which case we're probably going
@@ -3851,6 +4167,16 @@ impl PrimitiveStructuralEncoder {
         })
     }
 
+    fn with_page_statistics(
+        mut page: EncodedPage,
+        statistics: Option<pb::PageStatistics>,
+    ) -> EncodedPage {
+        if let PageEncoding::Structural(layout) = &mut page.description {
+            layout.statistics = statistics;
+        }
+        page
+    }
+
     fn encode_simple_all_null(
         column_idx: u32,
         num_rows: u64,
@@ -3908,7 +4234,7 @@ impl PrimitiveStructuralEncoder {
         data: DataBlock,
         repdefs: Vec<RepDefBuilder>,
         row_number: u64,
-        dictionary_data: Option<DataBlock>,
+        dictionary: Option<MiniBlockDictionary>,
         num_rows: u64,
     ) -> Result<EncodedPage> {
         let repdef = RepDefBuilder::serialize(repdefs);
@@ -3977,65 +4303,51 @@ impl PrimitiveStructuralEncoder {
         data.push(serialized.metadata);
         data.push(serialized.data);
 
-        if let Some(dictionary_data) = dictionary_data {
-            let num_dictionary_items = dictionary_data.num_values();
-            // field in `create_block_compressor` is not used currently.
-            let dummy_dictionary_field = Field::new_arrow("", DataType::UInt16, false)?;
+        let (dictionary_encoding, column_dictionary) = match dictionary {
+            Some(MiniBlockDictionary::Page(dictionary_data)) => {
+                let num_dictionary_items = dictionary_data.num_values();
+                // field in `create_block_compressor` is not used currently.
+                let dummy_dictionary_field = Field::new_arrow("", DataType::UInt16, false)?;
 
-            let (compressor, dictionary_encoding) = compression_strategy
-                .create_block_compressor(&dummy_dictionary_field, &dictionary_data)?;
-            let dictionary_buffer = compressor.compress(dictionary_data)?;
+                let (compressor, dictionary_encoding) = compression_strategy
+                    .create_block_compressor(&dummy_dictionary_field, &dictionary_data)?;
+                let dictionary_buffer = compressor.compress(dictionary_data)?;
 
-            data.push(dictionary_buffer);
-            if let Some(rep_index) = rep_index {
-                data.push(rep_index);
+                data.push(dictionary_buffer);
+                (Some((dictionary_encoding, num_dictionary_items)), false)
             }
+            // The dictionary is written once, when the column is finished
+            Some(MiniBlockDictionary::Column) => (None, true),
+            None => (None, false),
+        };
 
-            let description = ProtobufUtils::miniblock_layout(
-                compressed_rep.map(|cr| cr.compression),
-                compressed_def.map(|cd| cd.compression),
-                value_encoding,
-                rep_index_depth,
-                serialized.num_buffers,
-                Some((dictionary_encoding, num_dictionary_items)),
-                &repdef.def_meaning,
-                num_items,
-            );
-            Ok(EncodedPage {
-                num_rows,
-                column_idx,
-                data,
-                description: PageEncoding::Structural(description),
-                row_number,
-            })
-        } else {
-            let description = ProtobufUtils::miniblock_layout(
-                compressed_rep.map(|cr| cr.compression),
-                compressed_def.map(|cd| cd.compression),
-                value_encoding,
-                rep_index_depth,
-                serialized.num_buffers,
-                None,
-                &repdef.def_meaning,
-                num_items,
-            );
-
-            if let Some(mut rep_index) = rep_index {
-                let view = rep_index.borrow_to_typed_slice::<u64>();
-                let total = view.chunks_exact(2).map(|c| c[0]).sum::<u64>();
-                debug_assert_eq!(total, num_rows);
+        let description = ProtobufUtils::miniblock_layout(
+            compressed_rep.map(|cr| cr.compression),
+            compressed_def.map(|cd| cd.compression),
+            value_encoding,
+            rep_index_depth,
+            serialized.num_buffers,
+            dictionary_encoding,
+            column_dictionary,
+            &repdef.def_meaning,
+            num_items,
+        );
 
-                data.push(rep_index);
-            }
+        if let Some(mut rep_index) = rep_index {
+            let view = rep_index.borrow_to_typed_slice::<u64>();
+            let total = view.chunks_exact(2).map(|c| c[0]).sum::<u64>();
+            debug_assert_eq!(total, num_rows);
 
-            Ok(EncodedPage {
-                num_rows,
-                column_idx,
-                data,
-                description: PageEncoding::Structural(description),
-                row_number,
-            })
+            data.push(rep_index);
         }
+
+        Ok(EncodedPage {
+            num_rows,
+            column_idx,
+            data,
+            description: PageEncoding::Structural(description),
+            row_number,
+        })
     }
 
     // For fixed-size data we encode < control word | data > for each value
@@ -4406,6 +4718,59 @@ impl PrimitiveStructuralEncoder {
         }
     }
 
+    // Encodes string / binary values as indices into the column dictionary
+    //
+    // Returns None if the column does not use a column dictionary or the values don't fit
+    // into it, in which case the page should be encoded some other way
+    fn column_dictionary_encode(
+        column_dictionary: &Mutex<ColumnDictionaryState>,
+        data_block: &mut DataBlock,
+        is_low_cardinality: bool,
+    ) -> Option<DataBlock> {
+        let DataBlock::VariableWidth(variable_width) = data_block else {
+            return None;
+        };
+        if variable_width.bits_per_offset != 32 {
+            return None;
+        }
+        let mut state = column_dictionary.lock().unwrap();
+        if matches!(*state, ColumnDictionaryState::Undecided) {
+            *state = if is_low_cardinality {
+                ColumnDictionaryState::Enabled(ColumnDictionaryBuilder::new())
+            } else {
+                ColumnDictionaryState::Disabled
+            };
+        }
+        match &mut *state {
+            ColumnDictionaryState::Enabled(builder) => builder.encode(variable_width),
+            _ => None,
+        }
+    }
+
+    // Writes out the column dictionary, if there is one, once all pages have been encoded
+    fn finish_column_dictionary(&self) -> Result<EncodedColumn> {
+        let state = std::mem::take(&mut *self.column_dictionary.lock().unwrap());
+        let ColumnDictionaryState::Enabled(builder) = state else {
+            return Ok(EncodedColumn::default());
+        };
+        if builder.is_empty() {
+            return Ok(EncodedColumn::default());
+        }
+        let dictionary = builder.finish();
+        let num_dictionary_items = dictionary.num_values();
+        // field in `create_block_compressor` is not used currently.
+        let dummy_dictionary_field = Field::new_arrow("", DataType::UInt16, false)?;
+        let (compressor, dictionary_encoding) = self
+            .compression_strategy
+            .create_block_compressor(&dummy_dictionary_field, &dictionary)?;
+        let dictionary_buffer = compressor.compress(dictionary)?;
+        Ok(EncodedColumn {
+            column_buffers: vec![dictionary_buffer],
+            encoding: ProtobufUtils::column_dictionary(dictionary_encoding, num_dictionary_items),
+            final_pages: Vec::new(),
+        })
+    }
+
     // Creates an encode task, consuming all buffered data
     fn do_flush(
         &mut self,
@@ -4418,6 +4783,7 @@ impl PrimitiveStructuralEncoder {
         let compression_strategy = self.compression_strategy.clone();
         let field = self.field.clone();
         let encoding_metadata = self.encoding_metadata.clone();
+        let column_dictionary = self.column_dictionary.clone();
         let task = spawn_cpu(move || {
             let num_values = arrays.iter().map(|arr| arr.len() as u64).sum();
             if num_values == 0 {
@@ -4430,8 +4796,13 @@ impl PrimitiveStructuralEncoder {
                 .iter()
                 .map(|arr| arr.logical_nulls().map(|n| n.null_count()).unwrap_or(0) as u64)
                 .sum::<u64>();
+            // Page statistics are only meaningful (to the reader) when each item is a row
+            let statistics = repdefs
+                .iter()
+                .all(|rd| rd.is_simple_validity())
+                .then(|| compute_page_statistics(&arrays));
 
-            if num_values == num_nulls {
+            let page = if num_values == num_nulls {
                 if repdefs.iter().all(|rd| rd.is_simple_validity()) {
                     log::debug!(
                         "Encoding column {} with {} items using simple-null layout",
@@ -4460,7 +4831,7 @@ impl PrimitiveStructuralEncoder {
                 }
 
                 // The top-level validity is encoded in repdef so we can remove it.
-                let data_block = data_block.remove_outer_validity();
+                let mut data_block = data_block.remove_outer_validity();
 
                 let dictionary_encoding_threshold: u64 = 100.max(data_block.num_values() / 4);
                 let cardinality =
@@ -4471,9 +4842,30 @@ impl PrimitiveStructuralEncoder {
                     };
 
                 // The triggering threshold for dictionary encoding can be further tuned.
-                if cardinality <= dictionary_encoding_threshold
-                    && data_block.num_values() >= 10 * cardinality
-                {
+                let is_low_cardinality = cardinality <= dictionary_encoding_threshold
+                    && data_block.num_values() >= 10 * cardinality;
+
+                if let Some(indices_data_block) = Self::column_dictionary_encode(
+                    &column_dictionary,
+                    &mut data_block,
+                    is_low_cardinality,
+                ) {
+                    log::debug!(
+                        "Encoding column {} with {} items using the column dictionary",
+                        column_idx,
+                        num_values
+                    );
+                    Self::encode_miniblock(
+                        column_idx,
+                        &field,
+                        compression_strategy.as_ref(),
+                        indices_data_block,
+                        repdefs,
+                        row_number,
+                        Some(MiniBlockDictionary::Column),
+                        num_rows,
+                    )
+                } else if is_low_cardinality {
                     let (indices_data_block, dictionary_data_block) =
                         Self::dictionary_encode(data_block, cardinality);
                     Self::encode_miniblock(
@@ -4483,10 +4875,12 @@ impl PrimitiveStructuralEncoder {
                         indices_data_block,
                         repdefs,
                         row_number,
-                        Some(dictionary_data_block),
+                        Some(MiniBlockDictionary::Page(dictionary_data_block)),
                         num_rows,
                     )
-                } else if Self::prefers_miniblock(&data_block, encoding_metadata.as_ref()) {
+                } else if !Self::requires_fullzip(&field, &data_block)
+                    && Self::prefers_miniblock(&data_block, encoding_metadata.as_ref())
+                {
                     log::debug!(
                         "Encoding column {} with {} items using mini-block layout",
                         column_idx,
@@ -4502,7 +4896,9 @@ impl PrimitiveStructuralEncoder {
                         None,
                         num_rows,
                     )
-                } else if Self::prefers_fullzip(encoding_metadata.as_ref()) {
+                } else if Self::requires_fullzip(&field, &data_block)
+                    || Self::prefers_fullzip(encoding_metadata.as_ref())
+                {
                     log::debug!(
                         "Encoding column {} with {} items using full-zip layout",
                         column_idx,
@@ -4520,7 +4916,8 @@ impl PrimitiveStructuralEncoder {
                 } else {
                     Err(Error::InvalidInput { source: format!("Cannot determine structural encoding for field {}.  This typically indicates an invalid value of the field metadata key {}", field.name, STRUCTURAL_ENCODING_META_KEY).into(), location: location!() })
                 }
-            }
+            };
+            page.map(|page| Self::with_page_statistics(page, statistics))
         })
         .boxed();
         Ok(vec![task])
@@ -4573,6 +4970,13 @@ impl FieldEncoder for PrimitiveStructuralEncoder {
         row_number: u64,
         num_rows: u64,
     ) -> Result<Vec<EncodeTask>> {
+        // Dictionary arrays are stored as values, low cardinality values will end up
+        // in a (page or column) dictionary again
+        let array = if let DataType::Dictionary(_, value_type) = array.data_type() {
+            arrow_cast::cast(array.as_ref(), value_type)?
+        } else {
+            array
+        };
         Self::extract_validity(
             array.as_ref(),
             &mut repdef,
@@ -4608,7 +5012,8 @@ impl FieldEncoder for PrimitiveStructuralEncoder {
         &mut self,
         _external_buffers: &mut OutOfLineBuffers,
     ) -> BoxFuture<'_, Result<Vec<crate::encoder::EncodedColumn>>> {
-        std::future::ready(Ok(vec![EncodedColumn::default()])).boxed()
+        let column = self.finish_column_dictionary();
+        std::future::ready(column.map(|column| vec![column])).boxed()
     }
 }
 
@@ -4617,14 +5022,20 @@ impl FieldEncoder for PrimitiveStructuralEncoder {
 mod tests {
     use std::{collections::VecDeque, sync::Arc};
 
-    use arrow_array::{ArrayRef, Int8Array, StringArray};
+    use arrow_array::{
+        cast::AsArray,
+        types::{Int8Type, UInt16Type},
+        Array, ArrayRef, Int8Array, StringArray, UInt16Array,
+    };
+    use arrow_schema::DataType;
 
     use crate::encodings::logical::primitive::{
         ChunkDrainInstructions, PrimitiveStructuralEncoder,
     };
 
     use super::{
-        ChunkInstructions, DataBlock, DecodeMiniBlockTask, PreambleAction, RepetitionIndex,
+        ChunkInstructions, DataBlock, DecodeMiniBlockTask, DictionaryDataBlock, PreambleAction,
+        RepetitionIndex, StructuralCompositeDecodeArrayTask,
     };
 
     #[test]
@@ -5233,4 +5644,57 @@ mod tests {
         assert!(!need_preamble);
         assert_eq!(skip_in_chunk, 0);
     }
+
+    #[test]
+    fn test_page_to_array_dictionary_key_type() {
+        // A column dictionary of 300 values, the page only uses a few of them
+        let values = (0..300).map(|i| format!("value-{}", i)).collect::<Vec<_>>();
+        let page = || {
+            DataBlock::Dictionary(DictionaryDataBlock {
+                indices: DataBlock::from_array(UInt16Array::from(vec![3, 299, 3, 150]))
+                    .as_fixed_width()
+                    .unwrap(),
+                dictionary: Box::new(DataBlock::from_array(StringArray::from(values.clone()))),
+            })
+        };
+        let expected = StringArray::from(vec!["value-3", "value-299", "value-3", "value-150"]);
+
+        // Keys that can index the whole dictionary share it
+        let data_type = DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::Utf8));
+        let array =
+            StructuralCompositeDecodeArrayTask::page_to_array(page(), &data_type, true).unwrap();
+        let dict = array.as_dictionary::<UInt16Type>();
+        assert_eq!(dict.values().len(), 300);
+        assert_eq!(
+            arrow_cast::cast(&array, &DataType::Utf8).unwrap().as_ref(),
+            &expected as &dyn Array
+        );
+
+        // Smaller keys get a dictionary of the values of the page
+        let data_type = DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8));
+        let array =
+            StructuralCompositeDecodeArrayTask::page_to_array(page(), &data_type, true).unwrap();
+        let dict = array.as_dictionary::<Int8Type>();
+        assert_eq!(dict.values().len(), 3);
+        assert_eq!(
+            arrow_cast::cast(&array, &DataType::Utf8).unwrap().as_ref(),
+            &expected as &dyn Array
+        );
+
+        // Indices that aren't byte aligned are invalid
+        let invalid = DataBlock::Dictionary(DictionaryDataBlock {
+            indices: DataBlock::from_array(UInt16Array::from(vec![3]))
+                .as_fixed_width()
+                .map(|mut indices| {
+                    indices.bits_per_value = 12;
+                    indices
+                })
+                .unwrap(),
+            dictionary: Box::new(DataBlock::from_array(StringArray::from(values))),
+        });
+        assert!(
+            StructuralCompositeDecodeArrayTask::page_to_array(invalid, &DataType::Utf8, true)
+                .is_err()
+        );
+    }
 }
@@ -28,11 +28,17 @@ use futures::{
 use itertools::Itertools;
 use lance_arrow::deepcopy::deep_copy_nulls;
 use lance_arrow::FieldExt;
-use lance_core::{Error, Result};
+use lance_core::{
+    datatypes::{is_json_field, json_shredded_paths},
+    Error, Result,
+};
 use log::trace;
 use snafu::location;
 
-use super::{list::StructuralListDecoder, primitive::StructuralPrimitiveFieldDecoder};
+use super::{
+    json::StructuralJsonDecoder, list::StructuralListDecoder,
+    primitive::StructuralPrimitiveFieldDecoder, union::StructuralUnionDecoder,
+};
 
 #[derive(Debug)]
 struct SchedulingJobWithStatus<'a> {
@@ -711,7 +717,9 @@ impl StructuralStructDecoder {
                     Box::new(Self::new(fields.clone(), should_validate, false))
                 }
             }
-            DataType::List(child_field) | DataType::LargeList(child_field) => {
+            DataType::List(child_field)
+            | DataType::LargeList(child_field)
+            | DataType::Map(child_field, _) => {
                 let child_decoder = Self::field_to_decoder(child_field, should_validate);
                 Box::new(StructuralListDecoder::new(
                     child_decoder,
@@ -720,8 +728,17 @@ impl StructuralStructDecoder {
             }
             DataType::RunEndEncoded(_, _) => todo!(),
             DataType::ListView(_) | DataType::LargeListView(_) => todo!(),
-            DataType::Map(_, _) => todo!(),
-            DataType::Union(_, _) => todo!(),
+            DataType::Union(_, _) => Box::new(StructuralUnionDecoder::new(
+                field.data_type().clone(),
+                should_validate,
+            )),
+            DataType::Utf8 if is_json_field(field) => {
+                // The paths were validated when the schema was loaded
+                match json_shredded_paths(field).expect("invalid shredded JSON paths") {
+                    Some(paths) => Box::new(StructuralJsonDecoder::new(paths, should_validate)),
+                    None => Box::new(StructuralPrimitiveFieldDecoder::new(field, should_validate)),
+                }
+            }
             _ => Box::new(StructuralPrimitiveFieldDecoder::new(field, should_validate)),
         }
     }
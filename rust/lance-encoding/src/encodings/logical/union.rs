@@ -0,0 +1,317 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Union fields
+//!
+//! A union is stored like a struct with one column for the type ids followed by
+//! one column per union child.  The children of a sparse union already have one
+//! value per row.  The children of a dense union are scattered out to one value
+//! per row (with nulls in the rows that belong to other children) so that all of
+//! the columns can be scheduled by row range, just like struct children.
+
+use std::sync::Arc;
+
+use arrow_array::{
+    cast::AsArray, types::Int8Type, Array, ArrayRef, BooleanArray, Int8Array, StructArray,
+    UInt32Array, UnionArray,
+};
+use arrow_buffer::ScalarBuffer;
+use arrow_schema::{DataType, Field, Fields, UnionFields, UnionMode};
+use futures::future::BoxFuture;
+use lance_core::{datatypes::UNION_TYPE_IDS_FIELD_NAME, Result};
+
+use crate::{
+    decoder::{DecodedArray, LoadedPage, StructuralDecodeArrayTask, StructuralFieldDecoder},
+    encoder::{EncodeTask, EncodedColumn, FieldEncoder, OutOfLineBuffers},
+    repdef::RepDefBuilder,
+};
+
+use super::r#struct::{StructStructuralEncoder, StructuralStructDecoder};
+
+/// The fields of the struct that a union is stored as
+///
+/// Every child is nullable because rows that belong to other children are null
+/// once a dense union has been scattered.
+fn union_storage_fields(union_fields: &UnionFields) -> Fields {
+    std::iter::once(Field::new(UNION_TYPE_IDS_FIELD_NAME, DataType::Int8, false))
+        .chain(
+            union_fields
+                .iter()
+                .map(|(_, field)| field.as_ref().clone().with_nullable(true)),
+        )
+        .collect()
+}
+
+fn union_to_struct(union_arr: &UnionArray) -> Result<StructArray> {
+    let DataType::Union(union_fields, mode) = union_arr.data_type() else {
+        unreachable!()
+    };
+    let type_ids = union_arr.type_ids();
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(union_fields.len() + 1);
+    columns.push(Arc::new(Int8Array::new(type_ids.clone(), None)));
+    for (type_id, _) in union_fields.iter() {
+        let child = union_arr.child(type_id);
+        let column = match (mode, union_arr.offsets()) {
+            (UnionMode::Dense, Some(offsets)) => {
+                let indices = type_ids
+                    .iter()
+                    .zip(offsets.iter())
+                    .map(|(row_type_id, offset)| {
+                        (*row_type_id == type_id).then_some(*offset as u32)
+                    })
+                    .collect::<UInt32Array>();
+                arrow_select::take::take(child.as_ref(), &indices, None)?
+            }
+            _ => child.clone(),
+        };
+        columns.push(column);
+    }
+    Ok(StructArray::try_new(
+        union_storage_fields(union_fields),
+        columns,
+        None,
+    )?)
+}
+
+fn struct_to_union(struct_arr: &StructArray, data_type: &DataType) -> Result<ArrayRef> {
+    let DataType::Union(union_fields, mode) = data_type else {
+        unreachable!()
+    };
+    let stored_type_ids = struct_arr.column(0).as_primitive::<Int8Type>().values();
+    let mut children = struct_arr.columns()[1..].to_vec();
+
+    // If only some of the children were projected then the rows that belong to the
+    // other children are assigned to the first projected child with a null value.
+    let (first_type_id, _) = union_fields
+        .iter()
+        .next()
+        .expect("union must have at least one child");
+    let not_projected = stored_type_ids
+        .iter()
+        .map(|type_id| {
+            !union_fields
+                .iter()
+                .any(|(projected, _)| projected == *type_id)
+        })
+        .collect::<BooleanArray>();
+    let type_ids = if not_projected.true_count() > 0 {
+        children[0] = arrow_select::nullif::nullif(children[0].as_ref(), &not_projected)?;
+        stored_type_ids
+            .iter()
+            .zip(not_projected.values().iter())
+            .map(|(type_id, not_projected)| {
+                if not_projected {
+                    first_type_id
+                } else {
+                    *type_id
+                }
+            })
+            .collect::<ScalarBuffer<i8>>()
+    } else {
+        stored_type_ids.clone()
+    };
+
+    let union_arr = match mode {
+        UnionMode::Sparse => UnionArray::try_new(union_fields.clone(), type_ids, None, children)?,
+        UnionMode::Dense => {
+            // Gather each child back down to the rows that belong to it
+            let mut next_offset = [0_i32; 128];
+            let offsets = type_ids
+                .iter()
+                .map(|type_id| {
+                    let offset = next_offset[*type_id as usize];
+                    next_offset[*type_id as usize] += 1;
+                    offset
+                })
+                .collect::<ScalarBuffer<i32>>();
+            let children = union_fields
+                .iter()
+                .zip(children.iter())
+                .map(|((type_id, _), child)| {
+                    let selected = type_ids
+                        .iter()
+                        .map(|row_type_id| *row_type_id == type_id)
+                        .collect::<BooleanArray>();
+                    Ok(arrow_select::filter::filter(child.as_ref(), &selected)?)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            UnionArray::try_new(union_fields.clone(), type_ids, Some(offsets), children)?
+        }
+    };
+    Ok(Arc::new(union_arr))
+}
+
+/// A structural encoder for union fields
+///
+/// The union is converted into a struct of the type ids and the (full length)
+/// children and then encoded as a struct.
+pub struct UnionStructuralEncoder {
+    inner: StructStructuralEncoder,
+}
+
+impl UnionStructuralEncoder {
+    /// Create a new encoder, the first child encoder must be the encoder for the type ids
+    pub fn new(keep_original_array: bool, children: Vec<Box<dyn FieldEncoder>>) -> Self {
+        Self {
+            inner: StructStructuralEncoder::new(keep_original_array, children),
+        }
+    }
+}
+
+impl FieldEncoder for UnionStructuralEncoder {
+    fn maybe_encode(
+        &mut self,
+        array: ArrayRef,
+        external_buffers: &mut OutOfLineBuffers,
+        repdef: RepDefBuilder,
+        row_number: u64,
+        num_rows: u64,
+    ) -> Result<Vec<EncodeTask>> {
+        let struct_arr = union_to_struct(array.as_union())?;
+        self.inner.maybe_encode(
+            Arc::new(struct_arr),
+            external_buffers,
+            repdef,
+            row_number,
+            num_rows,
+        )
+    }
+
+    fn flush(&mut self, external_buffers: &mut OutOfLineBuffers) -> Result<Vec<EncodeTask>> {
+        self.inner.flush(external_buffers)
+    }
+
+    fn num_columns(&self) -> u32 {
+        self.inner.num_columns()
+    }
+
+    fn finish(
+        &mut self,
+        external_buffers: &mut OutOfLineBuffers,
+    ) -> BoxFuture<'_, Result<Vec<EncodedColumn>>> {
+        self.inner.finish(external_buffers)
+    }
+}
+
+/// A structural decoder for union fields
+///
+/// The stored struct of type ids and children is decoded and then converted back
+/// into a union.  Scheduling is handled by a struct scheduler.
+#[derive(Debug)]
+pub struct StructuralUnionDecoder {
+    inner: StructuralStructDecoder,
+    data_type: DataType,
+}
+
+impl StructuralUnionDecoder {
+    pub fn new(data_type: DataType, should_validate: bool) -> Self {
+        let DataType::Union(union_fields, _) = &data_type else {
+            panic!("Union decoder created for non-union type {}", data_type)
+        };
+        let inner = StructuralStructDecoder::new(
+            union_storage_fields(union_fields),
+            should_validate,
+            /*is_root=*/ false,
+        );
+        Self { inner, data_type }
+    }
+}
+
+impl StructuralFieldDecoder for StructuralUnionDecoder {
+    fn accept_page(&mut self, child: LoadedPage) -> Result<()> {
+        self.inner.accept_page(child)
+    }
+
+    fn drain(&mut self, num_rows: u64) -> Result<Box<dyn StructuralDecodeArrayTask>> {
+        Ok(Box::new(StructuralUnionDecodeTask {
+            inner: self.inner.drain(num_rows)?,
+            data_type: self.data_type.clone(),
+        }))
+    }
+
+    fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+}
+
+#[derive(Debug)]
+struct StructuralUnionDecodeTask {
+    inner: Box<dyn StructuralDecodeArrayTask>,
+    data_type: DataType,
+}
+
+impl StructuralDecodeArrayTask for StructuralUnionDecodeTask {
+    fn decode(self: Box<Self>) -> Result<DecodedArray> {
+        let DecodedArray { array, repdef } = self.inner.decode()?;
+        let array = struct_to_union(array.as_struct(), &self.data_type)?;
+        Ok(DecodedArray { array, repdef })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use arrow_array::{ArrayRef, Int32Array, StringArray, UnionArray};
+    use arrow_buffer::ScalarBuffer;
+    use arrow_schema::{DataType, Field, UnionFields};
+
+    use crate::{
+        testing::{check_round_trip_encoding_of_data, TestCases},
+        version::LanceFileVersion,
+    };
+
+    fn union_fields() -> UnionFields {
+        UnionFields::new(
+            vec![0, 3],
+            vec![
+                Field::new("a", DataType::Int32, true),
+                Field::new("b", DataType::Utf8, true),
+            ],
+        )
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_sparse_union() {
+        let type_ids = ScalarBuffer::from(vec![0_i8, 3, 3, 0, 3]);
+        let children = vec![
+            Arc::new(Int32Array::from(vec![Some(1), None, None, Some(4), None])) as ArrayRef,
+            Arc::new(StringArray::from(vec![
+                None,
+                Some("b1"),
+                None,
+                None,
+                Some("b4"),
+            ])) as ArrayRef,
+        ];
+        let union_arr = UnionArray::try_new(union_fields(), type_ids, None, children).unwrap();
+
+        let test_cases = TestCases::default()
+            .with_range(0..2)
+            .with_range(1..4)
+            .with_indices(vec![0, 2, 4])
+            .with_file_version(LanceFileVersion::V2_1);
+        check_round_trip_encoding_of_data(vec![Arc::new(union_arr)], &test_cases, HashMap::new())
+            .await;
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_dense_union() {
+        let type_ids = ScalarBuffer::from(vec![0_i8, 3, 3, 0, 3]);
+        let offsets = ScalarBuffer::from(vec![0_i32, 0, 1, 1, 2]);
+        let children = vec![
+            Arc::new(Int32Array::from(vec![Some(1), Some(4)])) as ArrayRef,
+            Arc::new(StringArray::from(vec![Some("b1"), None, Some("b4")])) as ArrayRef,
+        ];
+        let union_arr =
+            UnionArray::try_new(union_fields(), type_ids, Some(offsets), children).unwrap();
+
+        let test_cases = TestCases::default()
+            .with_range(0..2)
+            .with_range(1..4)
+            .with_indices(vec![0, 2, 4])
+            .with_file_version(LanceFileVersion::V2_1);
+        check_round_trip_encoding_of_data(vec![Arc::new(union_arr)], &test_cases, HashMap::new())
+            .await;
+    }
+}
@@ -19,17 +19,22 @@ use crate::{
     format::pb::{self, PackedStruct},
 };
 
+pub mod alp;
 pub mod basic;
 pub mod binary;
 pub mod bitmap;
 pub mod bitpack;
 pub mod bitpack_fastlanes;
 pub mod block_compress;
+pub mod byte_stream_split;
+pub mod delta;
 pub mod dictionary;
 pub mod fixed_size_binary;
 pub mod fixed_size_list;
 pub mod fsst;
 pub mod packed_struct;
+pub mod quantized_vector;
+pub mod rle;
 pub mod struct_encoding;
 pub mod value;
 
@@ -0,0 +1,602 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Adaptive lossless floating point (ALP) compression for mini-blocks
+//!
+//! Many floating point columns hold decimal numbers (prices, sensor readings with a fixed
+//! precision).  ALP looks for an exponent `e` and a factor `f` such that `v * 10^e / 10^f`
+//! is an integer that converts back to exactly `v`.  The integers are then stored with
+//! frame-of-reference bitpacking.  Values that do not round trip (NaN, infinities, values
+//! with more precision than the rest) are stored as exceptions.
+//!
+//! See Afroozeh et al., "ALP: Adaptive Lossless floating-Point Compression" (SIGMOD 2024).
+//!
+//! Each chunk has two buffers:
+//!
+//! * The integers, encoded the same way as frame-of-reference chunks
+//! * `e`, `f`, the number of exceptions as a u16, the position of each exception as a u16,
+//!   and then the exceptions themselves
+//!
+//! Chunks with too many exceptions to be worth it store the values as-is, which is marked
+//! by an `e` of 255.
+
+use arrow_buffer::{ArrowNativeType, ToByteSlice};
+use lance_core::{Error, Result};
+use snafu::location;
+
+use crate::buffer::LanceBuffer;
+use crate::data::{BlockInfo, DataBlock, FixedWidthDataBlock};
+use crate::decoder::MiniBlockDecompressor;
+use crate::encoder::{
+    MiniBlockChunk, MiniBlockCompressed, MiniBlockCompressor, MAX_MINIBLOCK_BYTES,
+};
+use crate::encodings::physical::delta::{
+    decode_frame_of_reference, encode_frame_of_reference, Word,
+};
+use crate::format::{pb, ProtobufUtils};
+
+const LOG_ELEMS_PER_CHUNK: u8 = 10;
+const ELEMS_PER_CHUNK: usize = 1 << LOG_ELEMS_PER_CHUNK;
+
+/// Leave room for the padding between the two buffers of a chunk
+const MAX_CHUNK_BYTES: u64 = MAX_MINIBLOCK_BYTES - 16;
+
+/// Chunks that store the values as-is hold at most 4KiB of values
+const RAW_CHUNK_BYTES: usize = 4096;
+
+/// The exponent of a chunk that stores the values as-is
+const RAW_CHUNK: u8 = u8::MAX;
+
+/// The exponent, the factor and the number of exceptions
+const HEADER_BYTES: usize = 4;
+
+/// The number of (exponent, factor) combinations that are tried on each chunk
+const MAX_CANDIDATES: usize = 5;
+
+/// The number of values sampled from a page to find the candidates
+const SAMPLE_SIZE: usize = 256;
+
+const F10_F64: [f64; 19] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18,
+];
+const IF10_F64: [f64; 19] = [
+    1e-0, 1e-1, 1e-2, 1e-3, 1e-4, 1e-5, 1e-6, 1e-7, 1e-8, 1e-9, 1e-10, 1e-11, 1e-12, 1e-13, 1e-14,
+    1e-15, 1e-16, 1e-17, 1e-18,
+];
+const F10_F32: [f32; 11] = [1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10];
+const IF10_F32: [f32; 11] = [
+    1e-0, 1e-1, 1e-2, 1e-3, 1e-4, 1e-5, 1e-6, 1e-7, 1e-8, 1e-9, 1e-10,
+];
+
+/// Whether ALP supports values of the given width
+pub fn supports_bits_per_value(bits_per_value: u64) -> bool {
+    matches!(bits_per_value, 32 | 64)
+}
+
+trait AlpFloat: ArrowNativeType {
+    /// The word the integers are stored in
+    type Word: Word;
+    const MAX_EXPONENT: u8;
+
+    fn from_le_slice(bytes: &[u8]) -> Self;
+    /// The integer the value is encoded as, if it converts back to exactly the same value
+    fn encode(self, e: u8, f: u8) -> Option<i64>;
+    fn decode(value: Self::Word, e: u8, f: u8) -> Self;
+    fn to_word(value: i64) -> Self::Word;
+}
+
+macro_rules! impl_alp_float {
+    ($F:ty, $W:ty, $I:ty, $F10:ident, $IF10:ident, $limit:expr) => {
+        impl AlpFloat for $F {
+            type Word = $W;
+            const MAX_EXPONENT: u8 = ($F10.len() - 1) as u8;
+
+            fn from_le_slice(bytes: &[u8]) -> Self {
+                <$F>::from_le_bytes(bytes.try_into().unwrap())
+            }
+
+            fn encode(self, e: u8, f: u8) -> Option<i64> {
+                let scaled = self * $F10[e as usize] * $IF10[f as usize];
+                if scaled.is_nan() || scaled.abs() >= $limit {
+                    return None;
+                }
+                let value = scaled.round() as $I;
+                let decoded = Self::decode(value as $W, e, f);
+                (decoded.to_bits() == self.to_bits()).then_some(value as i64)
+            }
+
+            fn decode(value: $W, e: u8, f: u8) -> Self {
+                (value as $I) as $F * $F10[f as usize] * $IF10[e as usize]
+            }
+
+            fn to_word(value: i64) -> $W {
+                value as $W
+            }
+        }
+    };
+}
+
+impl_alp_float!(f64, u64, i64, F10_F64, IF10_F64, 4.611_686_018_427_388e18);
+impl_alp_float!(f32, u32, i32, F10_F32, IF10_F32, 1_073_741_824.0);
+
+/// The size of the values encoded with the given exponent and factor, excluding headers
+fn encoded_size_bits<F: AlpFloat>(values: &[F], e: u8, f: u8) -> u64 {
+    let mut min = i64::MAX;
+    let mut max = i64::MIN;
+    let mut num_exceptions = 0;
+    for value in values {
+        match value.encode(e, f) {
+            Some(encoded) => {
+                min = min.min(encoded);
+                max = max.max(encoded);
+            }
+            None => num_exceptions += 1,
+        }
+    }
+    let bit_width = if min > max {
+        0
+    } else {
+        128 - ((max as i128 - min as i128) as u128).leading_zeros() as u64
+    };
+    let bits_per_exception = 16 + 8 * std::mem::size_of::<F>() as u64;
+    values.len() as u64 * bit_width + num_exceptions * bits_per_exception
+}
+
+/// Up to `SAMPLE_SIZE` values, evenly spread over the input
+fn sample<F: AlpFloat>(values: &[F]) -> Vec<F> {
+    let step = values.len().div_ceil(SAMPLE_SIZE).max(1);
+    values.iter().step_by(step).copied().collect()
+}
+
+/// The best (exponent, factor) combinations for the sample, best first
+fn find_candidates<F: AlpFloat>(sample: &[F]) -> Vec<(u64, u8, u8)> {
+    let mut candidates = (0..=F::MAX_EXPONENT)
+        .flat_map(|e| (0..=e).map(move |f| (e, f)))
+        .map(|(e, f)| (encoded_size_bits(sample, e, f), e, f))
+        .collect::<Vec<_>>();
+    // Ties go to the smallest exponent
+    candidates.sort_unstable();
+    candidates.truncate(MAX_CANDIDATES);
+    candidates
+}
+
+/// Estimates the size, in bits, of the data encoded with ALP, from a sample of the values
+pub fn estimate_size_bits(data: &FixedWidthDataBlock) -> u64 {
+    match data.bits_per_value {
+        32 => estimate_size_bits_typed::<f32>(data),
+        64 => estimate_size_bits_typed::<f64>(data),
+        _ => unreachable!("ALP only supports 32 and 64 bit values"),
+    }
+}
+
+fn estimate_size_bits_typed<F: AlpFloat>(data: &FixedWidthDataBlock) -> u64 {
+    let bytes_per_value = std::mem::size_of::<F>();
+    let num_values = data.num_values as usize;
+    if num_values == 0 {
+        return 0;
+    }
+    let step = num_values.div_ceil(SAMPLE_SIZE).max(1);
+    let sample = (0..num_values)
+        .step_by(step)
+        .map(|idx| F::from_le_slice(&data.data[idx * bytes_per_value..][..bytes_per_value]))
+        .collect::<Vec<_>>();
+    let Some((sample_bits, _, _)) = find_candidates(&sample).first().copied() else {
+        return 0;
+    };
+    let num_chunks = num_values.div_ceil(ELEMS_PER_CHUNK) as u64;
+    let chunk_header_bits = (HEADER_BYTES + 2 * bytes_per_value) as u64 * 8;
+    sample_bits * num_values as u64 / sample.len() as u64 + num_chunks * chunk_header_bits
+}
+
+/// Encodes a chunk with the given exponent and factor, returning the two buffers, or
+/// `None` if the chunk would not fit in a mini-block
+fn encode_chunk<F: AlpFloat>(values: &[F], e: u8, f: u8) -> Option<(Vec<F::Word>, Vec<u8>)> {
+    let encoded = values.iter().map(|v| v.encode(e, f)).collect::<Vec<_>>();
+    // Exceptions (and padding) are replaced with a valid value so they don't widen the range
+    let fill = encoded.iter().flatten().next().copied().unwrap_or(0);
+
+    let mut ints = [F::Word::default(); ELEMS_PER_CHUNK];
+    let mut positions = Vec::new();
+    let mut exceptions = Vec::new();
+    for (idx, int) in ints.iter_mut().enumerate() {
+        match encoded.get(idx) {
+            Some(Some(value)) => *int = F::to_word(*value),
+            Some(None) => {
+                *int = F::to_word(fill);
+                positions.push(idx as u16);
+                exceptions.push(values[idx]);
+            }
+            None => *int = F::to_word(fill),
+        }
+    }
+    let mut words = Vec::new();
+    encode_frame_of_reference(&ints, true, &mut words);
+
+    let mut header = Vec::with_capacity(HEADER_BYTES + positions.len() * 2);
+    header.extend_from_slice(&[e, f]);
+    header.extend_from_slice(&(positions.len() as u16).to_le_bytes());
+    for position in positions {
+        header.extend_from_slice(&position.to_le_bytes());
+    }
+    header.extend_from_slice(exceptions.to_byte_slice());
+
+    let size = words.len() * std::mem::size_of::<F::Word>() + header.len();
+    (size as u64 <= MAX_CHUNK_BYTES).then_some((words, header))
+}
+
+/// Mini-block compressor that encodes decimal-like floating point values as integers
+#[derive(Debug)]
+pub struct AlpMiniBlockEncoder {
+    bits_per_value: u64,
+}
+
+impl AlpMiniBlockEncoder {
+    pub fn new(bits_per_value: u64) -> Self {
+        assert!(supports_bits_per_value(bits_per_value));
+        Self { bits_per_value }
+    }
+
+    fn chunk_data<F: AlpFloat>(data: &mut FixedWidthDataBlock) -> MiniBlockCompressed {
+        let values = data.data.borrow_to_typed_slice::<F>();
+        let candidates = find_candidates(&sample(&values));
+        let values_per_raw_chunk = RAW_CHUNK_BYTES / std::mem::size_of::<F>();
+
+        let mut int_data = Vec::new();
+        let mut header_data = Vec::new();
+        let mut chunks = Vec::with_capacity(values.len().div_ceil(ELEMS_PER_CHUNK));
+        for chunk in values.chunks(ELEMS_PER_CHUNK) {
+            let (_, e, f) = candidates
+                .iter()
+                .map(|(_, e, f)| (encoded_size_bits(chunk, *e, *f), *e, *f))
+                .min()
+                .unwrap();
+            let encoded = encode_chunk(chunk, e, f).filter(|(words, header)| {
+                words.len() * std::mem::size_of::<F::Word>() + header.len()
+                    < std::mem::size_of_val(chunk)
+            });
+            if let Some((words, header)) = encoded {
+                let int_bytes = words.to_byte_slice();
+                chunks.push(MiniBlockChunk {
+                    buffer_sizes: vec![int_bytes.len() as u16, header.len() as u16],
+                    log_num_values: LOG_ELEMS_PER_CHUNK,
+                });
+                int_data.extend_from_slice(int_bytes);
+                header_data.extend_from_slice(&header);
+            } else {
+                for raw in chunk.chunks(values_per_raw_chunk) {
+                    let raw_bytes = raw.to_byte_slice();
+                    chunks.push(MiniBlockChunk {
+                        buffer_sizes: vec![raw_bytes.len() as u16, 1],
+                        log_num_values: values_per_raw_chunk.trailing_zeros() as u8,
+                    });
+                    int_data.extend_from_slice(raw_bytes);
+                    header_data.push(RAW_CHUNK);
+                }
+            }
+        }
+        if let Some(last) = chunks.last_mut() {
+            last.log_num_values = 0;
+        }
+
+        MiniBlockCompressed {
+            data: vec![
+                LanceBuffer::Owned(int_data),
+                LanceBuffer::Owned(header_data),
+            ],
+            chunks,
+            num_values: data.num_values,
+        }
+    }
+}
+
+impl MiniBlockCompressor for AlpMiniBlockEncoder {
+    fn compress(&self, page: DataBlock) -> Result<(MiniBlockCompressed, pb::ArrayEncoding)> {
+        match page {
+            DataBlock::FixedWidth(mut data) if data.bits_per_value == self.bits_per_value => {
+                let compressed = match self.bits_per_value {
+                    32 => Self::chunk_data::<f32>(&mut data),
+                    64 => Self::chunk_data::<f64>(&mut data),
+                    _ => unreachable!(),
+                };
+                Ok((compressed, ProtobufUtils::alp(self.bits_per_value)))
+            }
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Cannot compress a data block of type {} with AlpMiniBlockEncoder",
+                    page.name()
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Decompressor for [`AlpMiniBlockEncoder`]
+#[derive(Debug)]
+pub struct AlpMiniBlockDecompressor {
+    bits_per_value: u64,
+}
+
+impl AlpMiniBlockDecompressor {
+    pub fn from_description(description: &pb::Alp) -> Self {
+        Self {
+            bits_per_value: description.bits_per_value,
+        }
+    }
+
+    fn unchunk<F: AlpFloat>(data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        let invalid = || Error::InvalidInput {
+            source: "an ALP mini-block is corrupt".into(),
+            location: location!(),
+        };
+        let [mut ints, header] = <[LanceBuffer; 2]>::try_from(data).map_err(|_| invalid())?;
+        let num_values = num_values as usize;
+        let bytes_per_value = std::mem::size_of::<F>();
+
+        if header.first() == Some(&RAW_CHUNK) {
+            if ints.len() != num_values * bytes_per_value {
+                return Err(invalid());
+            }
+            return Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+                data: ints,
+                bits_per_value: 8 * bytes_per_value as u64,
+                num_values: num_values as u64,
+                block_info: BlockInfo::new(),
+            }));
+        }
+
+        let [e, f, n0, n1, rest @ ..] = &header[..] else {
+            return Err(invalid());
+        };
+        let (e, f) = (*e, *f);
+        let num_exceptions = u16::from_le_bytes([*n0, *n1]) as usize;
+        if e > F::MAX_EXPONENT
+            || f > e
+            || num_values > ELEMS_PER_CHUNK
+            || rest.len() != num_exceptions * (2 + bytes_per_value)
+        {
+            return Err(invalid());
+        }
+        let (positions, exceptions) = rest.split_at(num_exceptions * 2);
+
+        let words = ints.borrow_to_typed_slice::<F::Word>();
+        let mut decoded = [F::Word::default(); ELEMS_PER_CHUNK];
+        decode_frame_of_reference(&words, &mut decoded)?;
+        let mut values = decoded[..num_values]
+            .iter()
+            .map(|value| F::decode(*value, e, f))
+            .collect::<Vec<_>>();
+        for (position, exception) in positions
+            .chunks_exact(2)
+            .zip(exceptions.chunks_exact(bytes_per_value))
+        {
+            let position = u16::from_le_bytes([position[0], position[1]]) as usize;
+            *values.get_mut(position).ok_or_else(invalid)? = F::from_le_slice(exception);
+        }
+
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::reinterpret_vec(values),
+            bits_per_value: 8 * bytes_per_value as u64,
+            num_values: num_values as u64,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
+impl MiniBlockDecompressor for AlpMiniBlockDecompressor {
+    fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        match self.bits_per_value {
+            32 => Self::unchunk::<f32>(data, num_values),
+            64 => Self::unchunk::<f64>(data, num_values),
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "ALP only supports 32 and 64 bit values, got {} bits per value",
+                    self.bits_per_value
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow_array::{Array, Float32Array, Float64Array};
+    use arrow_schema::DataType;
+    use lance_core::datatypes::Field;
+
+    use super::*;
+    use crate::encoder::{CompressionStrategy, CoreArrayEncodingStrategy};
+    use crate::testing::{
+        check_round_trip_encoding_of_data, miniblock_value_compression, TestCases,
+    };
+    use crate::version::LanceFileVersion;
+
+    fn round_trip<F: AlpFloat>(values: &[F]) -> MiniBlockCompressed {
+        let bits_per_value = 8 * std::mem::size_of::<F>() as u64;
+        let bytes = values.to_byte_slice().to_vec();
+        let block = FixedWidthDataBlock {
+            data: LanceBuffer::Owned(bytes.clone()),
+            bits_per_value,
+            num_values: values.len() as u64,
+            block_info: BlockInfo::new(),
+        };
+        let (compressed, encoding) = AlpMiniBlockEncoder::new(bits_per_value)
+            .compress(DataBlock::FixedWidth(block))
+            .unwrap();
+        let Some(pb::array_encoding::ArrayEncoding::Alp(description)) = encoding.array_encoding
+        else {
+            panic!("unexpected encoding {:?}", encoding);
+        };
+        let decompressor = AlpMiniBlockDecompressor::from_description(&description);
+
+        let mut offsets = [0; 2];
+        let mut values_seen = 0;
+        for chunk in &compressed.chunks {
+            let num_values = chunk.num_values(values_seen, compressed.num_values);
+            let buffers = (0..2)
+                .map(|idx| {
+                    let size = chunk.buffer_sizes[idx] as usize;
+                    let buffer = compressed.data[idx].slice_with_length(offsets[idx], size);
+                    offsets[idx] += size;
+                    buffer
+                })
+                .collect();
+            let decoded = decompressor
+                .decompress(buffers, num_values)
+                .unwrap()
+                .as_fixed_width()
+                .unwrap();
+            let start = values_seen as usize * std::mem::size_of::<F>();
+            let end = start + num_values as usize * std::mem::size_of::<F>();
+            assert_eq!(decoded.data.as_ref(), &bytes[start..end]);
+            values_seen += num_values;
+        }
+        assert_eq!(values_seen, values.len() as u64);
+        compressed
+    }
+
+    fn compressed_bytes(compressed: &MiniBlockCompressed) -> usize {
+        compressed.data.iter().map(|buffer| buffer.len()).sum()
+    }
+
+    fn is_raw(compressed: &MiniBlockCompressed) -> bool {
+        compressed.data[1].iter().all(|byte| *byte == RAW_CHUNK)
+    }
+
+    #[test]
+    fn test_alp_chunks() {
+        let mut prices = (0..3000_u64)
+            .map(|i| ((i * 7919) % 100_000) as f64 / 100.0)
+            .collect::<Vec<_>>();
+        let compressed = round_trip(&prices);
+        assert!(!is_raw(&compressed));
+        assert!(compressed_bytes(&compressed) * 3 < prices.len() * 8);
+
+        // Special values are exceptions
+        for (idx, special) in [
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            -0.0,
+            f64::MIN_POSITIVE,
+            1e300,
+            0.1 + 0.2,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            prices[idx * 400] = special;
+        }
+        let compressed = round_trip(&prices);
+        assert!(!is_raw(&compressed));
+
+        let prices = (0..3000_u32)
+            .map(|i| ((i * 7919) % 10_000) as f32 / 10.0)
+            .collect::<Vec<_>>();
+        let compressed = round_trip(&prices);
+        assert!(!is_raw(&compressed));
+        assert!(compressed_bytes(&compressed) * 3 < prices.len() * 4 * 2);
+
+        round_trip(&[1.5_f64]);
+        round_trip(&[-0.0_f32, f32::NAN]);
+    }
+
+    #[test]
+    fn test_alp_raw_chunks() {
+        // Random bits are not decimals, so the chunks store the values as-is
+        let random = (0..3000_u64)
+            .map(|i| f64::from_bits(i.wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+            .collect::<Vec<_>>();
+        assert!(is_raw(&round_trip(&random)));
+        let random = (0..3000_u32)
+            .map(|i| f32::from_bits(i.wrapping_mul(0x9E37_79B9)))
+            .collect::<Vec<_>>();
+        assert!(is_raw(&round_trip(&random)));
+    }
+
+    #[test]
+    fn test_alp_invalid_bits_per_value() {
+        let decompressor =
+            AlpMiniBlockDecompressor::from_description(&pb::Alp { bits_per_value: 16 });
+        let data = vec![
+            LanceBuffer::Owned(vec![0; 8]),
+            LanceBuffer::Owned(vec![0; 8]),
+        ];
+        assert!(matches!(
+            decompressor.decompress(data, 4),
+            Err(Error::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_alp_selected() {
+        let strategy = CoreArrayEncodingStrategy::default();
+        let compressor_for = |array: Arc<dyn Array>| {
+            let field = Field::new_arrow("", array.data_type().clone(), false).unwrap();
+            let block = DataBlock::from_array(array);
+            let compressor = strategy
+                .create_miniblock_compressor(&field, &block)
+                .unwrap();
+            format!("{:?}", compressor)
+        };
+
+        let prices = Float64Array::from_iter_values(
+            (0..10_000_u64).map(|i| ((i * 7919) % 100_000) as f64 / 100.0),
+        );
+        assert!(compressor_for(Arc::new(prices)).contains("Alp"));
+        let prices = Float32Array::from_iter_values(
+            (0..10_000_u32).map(|i| ((i * 7919) % 10_000) as f32 / 10.0),
+        );
+        assert!(compressor_for(Arc::new(prices)).contains("Alp"));
+        let random = Float64Array::from_iter_values(
+            (0..10_000_u64).map(|i| f64::from_bits(i.wrapping_mul(0x9E37_79B9_7F4A_7C15))),
+        );
+        let random = compressor_for(Arc::new(random));
+        assert!(!random.contains("Alp") && !random.contains("ByteStreamSplit"));
+    }
+
+    fn expect_alp() -> TestCases {
+        TestCases::default()
+            .with_file_version(LanceFileVersion::V2_1)
+            .with_verify_page_encoding(Arc::new(|encoding| {
+                let compression = miniblock_value_compression(encoding).unwrap();
+                assert!(matches!(
+                    compression.array_encoding,
+                    Some(pb::array_encoding::ArrayEncoding::Alp(_))
+                ));
+            }))
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_miniblock_alp() {
+        let arrays = vec![
+            Arc::new(Float64Array::from_iter_values(
+                (0..5000_u64).map(|i| ((i * 7919) % 100_000) as f64 / 100.0),
+            )) as Arc<dyn Array>,
+            Arc::new(Float32Array::from_iter_values(
+                (0..5000_u32).map(|i| ((i * 7919) % 10_000) as f32 / 10.0),
+            )),
+            Arc::new(Float64Array::from_iter((0..5000_u64).map(|i| {
+                (i % 100 != 0).then_some(((i * 7919) % 100_000) as f64 / 100.0)
+            }))),
+        ];
+        for array in arrays {
+            for test_cases in [
+                expect_alp(),
+                expect_alp().with_range(1000..1100),
+                expect_alp().with_range(1020..1030),
+                expect_alp().with_indices(vec![0, 1, 1023, 1024, 4999]),
+            ] {
+                check_round_trip_encoding_of_data(vec![array.clone()], &test_cases, HashMap::new())
+                    .await;
+            }
+        }
+    }
+}
@@ -845,7 +845,8 @@ impl BlockDecompressor for BinaryBlockDecompressor {
 pub mod tests {
     use arrow_array::{
         builder::{LargeStringBuilder, StringBuilder},
-        ArrayRef, StringArray,
+        types::Int32Type,
+        ArrayRef, DictionaryArray, StringArray,
     };
     use arrow_schema::{DataType, Field};
 
@@ -859,6 +860,8 @@ pub mod tests {
     use crate::{
         buffer::LanceBuffer,
         data::DataBlock,
+        decoder::PageEncoding,
+        format::pb,
         testing::{check_round_trip_encoding_of_data, check_round_trip_encoding_random, TestCases},
         version::LanceFileVersion,
     };
@@ -1136,4 +1139,85 @@ pub mod tests {
         let string_array = Arc::new(StringArray::from(repeated_strings)) as ArrayRef;
         check_round_trip_encoding_of_data(vec![string_array], &test_cases, HashMap::new()).await;
     }
+
+    fn uses_column_dictionary(encoding: &PageEncoding) -> bool {
+        match encoding {
+            PageEncoding::Structural(layout) => matches!(
+                layout.layout.as_ref(),
+                Some(pb::page_layout::Layout::MiniBlockLayout(mini_block))
+                    if mini_block.column_dictionary && mini_block.dictionary.is_none()
+            ),
+            PageEncoding::Legacy(_) => false,
+        }
+    }
+
+    fn low_cardinality_chunks(with_nulls: bool) -> Vec<ArrayRef> {
+        let strings = ["Hal Abelson", "Charles Babbage", "Vint Cerf", "Jim Gray"];
+        (0..4)
+            .map(|chunk| {
+                let values = (0..2000).map(|i| {
+                    if with_nulls && i % 7 == 0 {
+                        None
+                    } else {
+                        // Later chunks only use some of the values
+                        Some(strings[(i + chunk) % (strings.len() - chunk % 2)])
+                    }
+                });
+                Arc::new(StringArray::from_iter(values)) as ArrayRef
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_column_dictionary_encoding(#[values(true, false)] with_nulls: bool) {
+        let test_cases = TestCases::default()
+            .with_file_version(LanceFileVersion::V2_1)
+            .with_page_sizes(vec![4096])
+            .with_range(1500..6500)
+            .with_indices(vec![0, 1999, 2000, 7999])
+            .with_verify_encoding(Arc::new(|cols| {
+                assert_eq!(cols.len(), 1);
+                assert_eq!(cols[0].column_buffers.len(), 1);
+                assert!(matches!(
+                    cols[0].encoding.column_encoding.as_ref().unwrap(),
+                    pb::column_encoding::ColumnEncoding::Dictionary(dictionary)
+                        if dictionary.num_dictionary_items <= 5
+                ));
+            }))
+            .with_verify_page_encoding(Arc::new(|encoding| {
+                assert!(uses_column_dictionary(encoding));
+            }));
+        check_round_trip_encoding_of_data(
+            low_cardinality_chunks(with_nulls),
+            &test_cases,
+            HashMap::new(),
+        )
+        .await;
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_column_dictionary_as_dictionary_array() {
+        let test_cases = TestCases::default()
+            .with_file_version(LanceFileVersion::V2_1)
+            .with_page_sizes(vec![4096])
+            .with_range(1500..6500)
+            .with_indices(vec![0, 1999, 2000, 7999])
+            .with_verify_page_encoding(Arc::new(|encoding| {
+                assert!(uses_column_dictionary(encoding));
+            }));
+        let arrays = low_cardinality_chunks(true)
+            .into_iter()
+            .map(|arr| {
+                let dict = arrow_cast::cast(
+                    &arr,
+                    &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                )
+                .unwrap();
+                assert!(dict.as_any().is::<DictionaryArray<Int32Type>>());
+                dict
+            })
+            .collect::<Vec<_>>();
+        check_round_trip_encoding_of_data(arrays, &test_cases, HashMap::new()).await;
+    }
 }
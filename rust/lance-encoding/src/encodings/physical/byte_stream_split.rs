@@ -0,0 +1,548 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Byte stream split compression for mini-blocks
+//!
+//! Noisy floating point values (sensor readings, embeddings) barely compress with general
+//! purpose compressors because the low bytes of the mantissa look random.  The sign,
+//! exponent and high mantissa bytes are quite predictable though.  Splitting the values
+//! into one stream per byte position (all of the first bytes, then all of the second
+//! bytes, ...) puts the predictable bytes next to each other, and then zstd does much
+//! better.  This works for float16 and bfloat16 as well.
+//!
+//! Each chunk is a single buffer, a flag byte followed by the compressed streams.  Chunks
+//! that do not compress store the values as-is.
+//!
+//! Fixed size lists of floats (e.g. small vectors) are supported as long as there is no
+//! validity inside the lists.  A list is never split across chunks.
+
+use std::str::FromStr;
+
+use lance_core::{Error, Result};
+use snafu::location;
+
+use crate::buffer::LanceBuffer;
+use crate::data::{BlockInfo, DataBlock, FixedSizeListBlock, FixedWidthDataBlock};
+use crate::decoder::MiniBlockDecompressor;
+use crate::encoder::{
+    MiniBlockChunk, MiniBlockCompressed, MiniBlockCompressor, MAX_MINIBLOCK_VALUES,
+};
+use crate::encodings::physical::block_compress::{
+    BufferCompressor, CompressionConfig, CompressionScheme, GeneralBufferCompressor,
+};
+use crate::format::{pb, ProtobufUtils};
+
+/// Aim for chunks with 4KiB of values, like uncompressed mini-blocks
+const CHUNK_BYTES: u64 = 4096;
+
+/// The number of chunks compressed to estimate the compression ratio
+const NUM_SAMPLE_CHUNKS: usize = 4;
+
+const FLAG_RAW: u8 = 0;
+const FLAG_SPLIT_COMPRESSED: u8 = 1;
+
+/// Whether byte stream split supports values of the given width
+pub fn supports_bits_per_value(bits_per_value: u64) -> bool {
+    matches!(bits_per_value, 16 | 32 | 64)
+}
+
+/// The largest power-of-two number of rows that fits in a chunk, at least 2
+fn log_rows_per_chunk(bytes_per_row: u64) -> Option<u8> {
+    let max_rows = (CHUNK_BYTES / bytes_per_row).min(MAX_MINIBLOCK_VALUES);
+    (max_rows >= 2).then(|| max_rows.ilog2() as u8)
+}
+
+/// Whether rows of `bytes_per_row` bytes are small enough to be chunked
+pub fn supports_bytes_per_row(bytes_per_row: u64) -> bool {
+    log_rows_per_chunk(bytes_per_row).is_some()
+}
+
+/// Appends the streams of `values`: byte `k` of value `i` goes to `k * num_values + i`
+fn split_streams(values: &[u8], bytes_per_value: usize, out: &mut Vec<u8>) {
+    let num_values = values.len() / bytes_per_value;
+    let start = out.len();
+    out.resize(start + values.len(), 0);
+    let streams = &mut out[start..];
+    for (idx, value) in values.chunks_exact(bytes_per_value).enumerate() {
+        for (byte_idx, byte) in value.iter().enumerate() {
+            streams[byte_idx * num_values + idx] = *byte;
+        }
+    }
+}
+
+/// The inverse of [`split_streams`]
+fn join_streams(streams: &[u8], bytes_per_value: usize) -> Vec<u8> {
+    let num_values = streams.len() / bytes_per_value;
+    let mut values = vec![0; streams.len()];
+    for (byte_idx, stream) in streams.chunks_exact(num_values.max(1)).enumerate() {
+        for (idx, byte) in stream.iter().enumerate() {
+            values[idx * bytes_per_value + byte_idx] = *byte;
+        }
+    }
+    values
+}
+
+/// Appends the flag and the (compressed) values of a chunk to `out`
+fn encode_chunk(
+    compressor: &dyn BufferCompressor,
+    values: &[u8],
+    bytes_per_value: usize,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let mut streams = Vec::with_capacity(values.len());
+    split_streams(values, bytes_per_value, &mut streams);
+    let mut compressed = Vec::with_capacity(values.len());
+    compressor.compress(&streams, &mut compressed)?;
+    if compressed.len() < values.len() {
+        out.push(FLAG_SPLIT_COMPRESSED);
+        out.extend_from_slice(&compressed);
+    } else {
+        out.push(FLAG_RAW);
+        out.extend_from_slice(values);
+    }
+    Ok(())
+}
+
+/// Estimates the size, in bits, of the values encoded with byte stream split, by
+/// compressing a few evenly spaced chunks
+pub fn estimate_size_bits(data: &FixedWidthDataBlock, compression: CompressionConfig) -> u64 {
+    let bytes_per_value = (data.bits_per_value / 8) as usize;
+    let values_per_chunk = CHUNK_BYTES as usize / bytes_per_value;
+    let num_chunks = (data.num_values as usize).div_ceil(values_per_chunk);
+    if num_chunks == 0 {
+        return 0;
+    }
+    let compressor = GeneralBufferCompressor::get_compressor(compression);
+    let step = num_chunks.div_ceil(NUM_SAMPLE_CHUNKS).max(1);
+    let mut sample_bytes = 0;
+    let mut compressed_bytes = 0;
+    let mut out = Vec::new();
+    for chunk in data
+        .data
+        .chunks(values_per_chunk * bytes_per_value)
+        .step_by(step)
+    {
+        out.clear();
+        if encode_chunk(compressor.as_ref(), chunk, bytes_per_value, &mut out).is_err() {
+            return data.data.len() as u64 * 8;
+        }
+        sample_bytes += chunk.len() as u64;
+        compressed_bytes += out.len() as u64;
+    }
+    data.data.len() as u64 * compressed_bytes * 8 / sample_bytes
+}
+
+/// Mini-block compressor that splits floating point values into byte streams and then
+/// compresses the streams
+#[derive(Debug)]
+pub struct ByteStreamSplitMiniBlockEncoder {
+    compression: CompressionConfig,
+}
+
+impl ByteStreamSplitMiniBlockEncoder {
+    pub fn new(compression: CompressionConfig) -> Self {
+        Self { compression }
+    }
+
+    /// Chunks `data`, which holds `dimension` values per row
+    fn chunk_data(
+        &self,
+        data: FixedWidthDataBlock,
+        dimension: u64,
+    ) -> Result<(MiniBlockCompressed, pb::ArrayEncoding)> {
+        let bits_per_value = data.bits_per_value;
+        if !supports_bits_per_value(bits_per_value) {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "byte stream split does not support {} bit values",
+                    bits_per_value
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        let bytes_per_value = (bits_per_value / 8) as usize;
+        let bytes_per_row = bytes_per_value as u64 * dimension.max(1);
+        let log_rows = log_rows_per_chunk(bytes_per_row).ok_or_else(|| Error::InvalidInput {
+            source: format!(
+                "rows of {} bytes are too large for byte stream split",
+                bytes_per_row
+            )
+            .into(),
+            location: location!(),
+        })?;
+        let chunk_bytes = (bytes_per_row << log_rows) as usize;
+
+        let compressor = GeneralBufferCompressor::get_compressor(self.compression);
+        let num_rows = data.num_values / dimension.max(1);
+        let mut output = Vec::new();
+        let mut chunks = Vec::with_capacity(data.data.len().div_ceil(chunk_bytes));
+        for values in data.data.chunks(chunk_bytes) {
+            let start = output.len();
+            encode_chunk(compressor.as_ref(), values, bytes_per_value, &mut output)?;
+            chunks.push(MiniBlockChunk {
+                buffer_sizes: vec![(output.len() - start) as u16],
+                log_num_values: log_rows,
+            });
+        }
+        if let Some(last) = chunks.last_mut() {
+            last.log_num_values = 0;
+        }
+
+        Ok((
+            MiniBlockCompressed {
+                data: vec![LanceBuffer::Owned(output)],
+                chunks,
+                num_values: num_rows,
+            },
+            ProtobufUtils::byte_stream_split(
+                bits_per_value,
+                dimension,
+                &self.compression.scheme.to_string(),
+            ),
+        ))
+    }
+}
+
+impl MiniBlockCompressor for ByteStreamSplitMiniBlockEncoder {
+    fn compress(&self, page: DataBlock) -> Result<(MiniBlockCompressed, pb::ArrayEncoding)> {
+        match page {
+            DataBlock::FixedWidth(data) => self.chunk_data(data, 0),
+            DataBlock::FixedSizeList(FixedSizeListBlock { child, dimension }) => match *child {
+                DataBlock::FixedWidth(data) => self.chunk_data(data, dimension),
+                child => Err(Error::InvalidInput {
+                    source: format!(
+                        "Cannot compress a fixed size list of {} with ByteStreamSplitMiniBlockEncoder",
+                        child.name()
+                    )
+                    .into(),
+                    location: location!(),
+                }),
+            },
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Cannot compress a data block of type {} with ByteStreamSplitMiniBlockEncoder",
+                    page.name()
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Decompressor for [`ByteStreamSplitMiniBlockEncoder`]
+#[derive(Debug)]
+pub struct ByteStreamSplitMiniBlockDecompressor {
+    bits_per_value: u64,
+    /// The number of values in each fixed size list, 0 if the values are not in a list
+    dimension: u64,
+    compressor: Box<dyn BufferCompressor>,
+}
+
+impl ByteStreamSplitMiniBlockDecompressor {
+    pub fn from_description(description: &pb::ByteStreamSplit) -> Result<Self> {
+        let scheme = CompressionScheme::from_str(&description.compression)?;
+        Ok(Self {
+            bits_per_value: description.bits_per_value,
+            dimension: description.dimension,
+            compressor: GeneralBufferCompressor::get_compressor(CompressionConfig::new(
+                scheme, None,
+            )),
+        })
+    }
+}
+
+impl MiniBlockDecompressor for ByteStreamSplitMiniBlockDecompressor {
+    fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        assert_eq!(data.len(), 1);
+        let data = data.into_iter().next().unwrap();
+        let bytes_per_value = (self.bits_per_value / 8) as usize;
+        let num_items = num_values * self.dimension.max(1);
+        let num_bytes = num_items as usize * bytes_per_value;
+
+        let values = match data.split_first() {
+            Some((&FLAG_RAW, values)) => values.to_vec(),
+            Some((&FLAG_SPLIT_COMPRESSED, compressed)) => {
+                let mut streams = Vec::with_capacity(num_bytes);
+                self.compressor.decompress(compressed, &mut streams)?;
+                join_streams(&streams, bytes_per_value)
+            }
+            _ => {
+                return Err(Error::InvalidInput {
+                    source: "a byte stream split mini-block has an unknown flag".into(),
+                    location: location!(),
+                })
+            }
+        };
+        if values.len() != num_bytes {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "a byte stream split mini-block has {} bytes, expected {}",
+                    values.len(),
+                    num_bytes
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+
+        let items = DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::Owned(values),
+            bits_per_value: self.bits_per_value,
+            num_values: num_items,
+            block_info: BlockInfo::new(),
+        });
+        if self.dimension == 0 {
+            Ok(items)
+        } else {
+            Ok(DataBlock::FixedSizeList(FixedSizeListBlock {
+                child: Box::new(items),
+                dimension: self.dimension,
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow_array::{
+        make_array, Array, FixedSizeBinaryArray, FixedSizeListArray, Float32Array, Float64Array,
+    };
+    use arrow_buffer::{Buffer, ToByteSlice};
+    use arrow_data::ArrayData;
+    use arrow_schema::{DataType, Field as ArrowField};
+    use lance_arrow::bfloat16::{ARROW_EXT_NAME_KEY, BFLOAT16_EXT_NAME};
+    use lance_core::datatypes::{Field, COMPRESSION_META_KEY};
+
+    use super::*;
+    use crate::encoder::{CompressionStrategy, CoreArrayEncodingStrategy};
+    use crate::testing::{
+        check_round_trip_encoding_of_data, miniblock_value_compression, TestCases,
+    };
+    use crate::version::LanceFileVersion;
+
+    fn noise(idx: u32, bits: u32) -> u32 {
+        idx.wrapping_mul(0x9E37_79B9) >> (32 - bits)
+    }
+
+    /// Readings around 1000 with 12 bits of noise, which are not decimals
+    fn noisy_f32(num_values: u32) -> Float32Array {
+        Float32Array::from_iter_values(
+            (0..num_values).map(|i| 1000.0 + noise(i, 12) as f32 / 16384.0),
+        )
+    }
+
+    /// Values between 100 and 101 in steps of 1/16
+    fn noisy_f16(num_values: u32) -> Arc<dyn Array> {
+        let bits = (0..num_values)
+            .map(|i| 0x5640 + noise(i, 4) as u16)
+            .collect::<Vec<_>>();
+        let data = ArrayData::builder(DataType::Float16)
+            .len(num_values as usize)
+            .add_buffer(Buffer::from_vec(bits))
+            .build()
+            .unwrap();
+        make_array(data)
+    }
+
+    /// Values between 1 and 2 in steps of 1/128
+    fn noisy_bf16(num_values: u32) -> FixedSizeBinaryArray {
+        FixedSizeBinaryArray::try_from_iter(
+            (0..num_values).map(|i| (0x3F80 + noise(i, 7) as u16).to_le_bytes()),
+        )
+        .unwrap()
+    }
+
+    fn bf16_metadata() -> HashMap<String, String> {
+        HashMap::from([(
+            ARROW_EXT_NAME_KEY.to_string(),
+            BFLOAT16_EXT_NAME.to_string(),
+        )])
+    }
+
+    fn vectors(
+        items: Arc<dyn Array>,
+        dimension: i32,
+        metadata: HashMap<String, String>,
+    ) -> Arc<dyn Array> {
+        let item_field =
+            ArrowField::new("item", items.data_type().clone(), true).with_metadata(metadata);
+        Arc::new(FixedSizeListArray::new(
+            Arc::new(item_field),
+            dimension,
+            items,
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_streams_round_trip() {
+        let values = (0..=255_u8).collect::<Vec<_>>();
+        for bytes_per_value in [2, 4, 8] {
+            let mut streams = Vec::new();
+            split_streams(&values, bytes_per_value, &mut streams);
+            let num_values = values.len() / bytes_per_value;
+            assert_eq!(streams[1], values[bytes_per_value]);
+            assert_eq!(streams[num_values], values[1]);
+            assert_eq!(join_streams(&streams, bytes_per_value), values);
+        }
+    }
+
+    #[test]
+    fn test_byte_stream_split_chunks() {
+        let zstd = CompressionConfig::new(CompressionScheme::Zstd, None);
+        let encoder = ByteStreamSplitMiniBlockEncoder::new(zstd);
+        let values = noisy_f32(5000);
+        let items = || FixedWidthDataBlock {
+            data: LanceBuffer::Owned(values.values().to_byte_slice().to_vec()),
+            bits_per_value: 32,
+            num_values: 5000,
+            block_info: BlockInfo::new(),
+        };
+        let lists = DataBlock::FixedSizeList(FixedSizeListBlock {
+            child: Box::new(DataBlock::FixedWidth(items())),
+            dimension: 5,
+        });
+
+        for (block, dimension) in [(DataBlock::FixedWidth(items()), 0), (lists, 5)] {
+            let (compressed, encoding) = encoder.compress(block).unwrap();
+            let Some(pb::array_encoding::ArrayEncoding::ByteStreamSplit(description)) =
+                encoding.array_encoding
+            else {
+                panic!("unexpected encoding {:?}", encoding);
+            };
+            assert_eq!(description.dimension, dimension);
+            assert!(compressed.data[0].len() * 2 < 5000 * 4);
+
+            let decompressor =
+                ByteStreamSplitMiniBlockDecompressor::from_description(&description).unwrap();
+            let mut offset = 0;
+            let mut rows_seen = 0;
+            let mut decoded_values = Vec::new();
+            for chunk in &compressed.chunks {
+                let num_rows = chunk.num_values(rows_seen, compressed.num_values);
+                let size = chunk.buffer_sizes[0] as usize;
+                let buffer = compressed.data[0].slice_with_length(offset, size);
+                offset += size;
+                let decoded = match decompressor.decompress(vec![buffer], num_rows).unwrap() {
+                    DataBlock::FixedSizeList(list) => {
+                        assert_eq!(list.dimension, dimension);
+                        list.child.as_fixed_width().unwrap()
+                    }
+                    block => block.as_fixed_width().unwrap(),
+                };
+                decoded_values.extend_from_slice(&decoded.data);
+                rows_seen += num_rows;
+            }
+            assert_eq!(rows_seen, 5000 / dimension.max(1));
+            assert_eq!(decoded_values, values.values().to_byte_slice());
+        }
+    }
+
+    #[test]
+    fn test_byte_stream_split_selected() {
+        let strategy = CoreArrayEncodingStrategy::default();
+        let compressor_for = |array: Arc<dyn Array>, metadata: HashMap<String, String>| {
+            let field =
+                ArrowField::new("", array.data_type().clone(), false).with_metadata(metadata);
+            let field = Field::try_from(&field).unwrap();
+            let block = DataBlock::from_array(array);
+            let compressor = strategy
+                .create_miniblock_compressor(&field, &block)
+                .unwrap();
+            format!("{:?}", compressor)
+        };
+
+        for (array, metadata) in [
+            (
+                Arc::new(noisy_f32(10_000)) as Arc<dyn Array>,
+                HashMap::new(),
+            ),
+            (noisy_f16(10_000), HashMap::new()),
+            (Arc::new(noisy_bf16(10_000)), bf16_metadata()),
+            (
+                vectors(Arc::new(noisy_f32(10_000)), 4, HashMap::new()),
+                HashMap::new(),
+            ),
+            (
+                vectors(Arc::new(noisy_bf16(10_000)), 8, bf16_metadata()),
+                HashMap::new(),
+            ),
+        ] {
+            assert!(compressor_for(array.clone(), metadata).contains("ByteStreamSplit"));
+            let none = HashMap::from([(COMPRESSION_META_KEY.to_string(), "none".to_string())]);
+            assert!(compressor_for(array, none).contains("ValueEncoder"));
+        }
+
+        // Without the extension metadata these are just bytes
+        assert!(
+            !compressor_for(Arc::new(noisy_bf16(10_000)), HashMap::new())
+                .contains("ByteStreamSplit")
+        );
+        // Random floats don't compress
+        let random = Float64Array::from_iter_values(
+            (0..10_000_u64).map(|i| f64::from_bits(i.wrapping_mul(0x9E37_79B9_7F4A_7C15))),
+        );
+        assert!(!compressor_for(Arc::new(random), HashMap::new()).contains("ByteStreamSplit"));
+    }
+
+    fn expect_byte_stream_split() -> TestCases {
+        TestCases::default()
+            .with_file_version(LanceFileVersion::V2_1)
+            .with_verify_page_encoding(Arc::new(|encoding| {
+                let compression = miniblock_value_compression(encoding).unwrap();
+                assert!(matches!(
+                    compression.array_encoding,
+                    Some(pb::array_encoding::ArrayEncoding::ByteStreamSplit(_))
+                ));
+            }))
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_miniblock_byte_stream_split() {
+        let with_nulls = Float32Array::from_iter(
+            noisy_f32(5000)
+                .values()
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| (idx % 100 != 0).then_some(*value)),
+        );
+        let cases = vec![
+            (Arc::new(noisy_f32(5000)) as Arc<dyn Array>, HashMap::new()),
+            (Arc::new(with_nulls), HashMap::new()),
+            (noisy_f16(5000), HashMap::new()),
+            (Arc::new(noisy_bf16(5000)), bf16_metadata()),
+            (
+                vectors(Arc::new(noisy_f32(20_000)), 4, HashMap::new()),
+                HashMap::new(),
+            ),
+            (
+                vectors(noisy_f16(40_000), 8, HashMap::new()),
+                HashMap::new(),
+            ),
+            (
+                vectors(Arc::new(noisy_bf16(40_000)), 8, bf16_metadata()),
+                HashMap::new(),
+            ),
+        ];
+        for (array, metadata) in cases {
+            for test_cases in [
+                expect_byte_stream_split(),
+                expect_byte_stream_split().with_range(1000..1100),
+                expect_byte_stream_split().with_range(1020..1030),
+                expect_byte_stream_split().with_indices(vec![0, 1, 1023, 1024, 4999]),
+            ] {
+                check_round_trip_encoding_of_data(
+                    vec![array.clone()],
+                    &test_cases,
+                    metadata.clone(),
+                )
+                .await;
+            }
+        }
+    }
+}
@@ -0,0 +1,754 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Delta and frame-of-reference encodings for mini-blocks
+//!
+//! Row ids, sequence numbers and timestamps are usually close to each other, or increase
+//! steadily, but are large in absolute terms, so plain bitpacking barely helps.
+//!
+//! * Frame-of-reference subtracts the minimum of each chunk before bitpacking
+//! * Delta transposes each chunk into the FastLanes layout and replaces each value with
+//!   the difference from the previous value in its lane.  Delta-of-delta does this twice,
+//!   which turns regularly spaced values (e.g. a timestamp every second) into zeros.  The
+//!   residuals are then encoded with frame-of-reference.
+//!
+//! Chunks hold 1024 values (the last chunk is padded) stored as words of the uncompressed
+//! width: `[base of each lane, for each delta pass][reference][bit width][packed values]`
+
+use arrow_buffer::ArrowNativeType;
+use lance_core::{Error, Result};
+use snafu::location;
+
+use crate::buffer::LanceBuffer;
+use crate::compression_algo::fastlanes::{lane_index, BitPacking, Delta, FastLanes, Transpose};
+use crate::data::{BlockInfo, DataBlock, FixedWidthDataBlock};
+use crate::decoder::MiniBlockDecompressor;
+use crate::encoder::{
+    MiniBlockChunk, MiniBlockCompressed, MiniBlockCompressor, MAX_MINIBLOCK_BYTES,
+};
+use crate::format::{pb, ProtobufUtils};
+
+const LOG_ELEMS_PER_CHUNK: u8 = 10;
+const ELEMS_PER_CHUNK: usize = 1 << LOG_ELEMS_PER_CHUNK;
+
+/// The highest supported delta order (delta-of-delta)
+pub const MAX_DELTA_ORDER: u32 = 2;
+
+/// Whether delta and frame-of-reference encoding support values of the given width
+pub fn supports_bits_per_value(bits_per_value: u64) -> bool {
+    matches!(bits_per_value, 8 | 16 | 32 | 64)
+}
+
+/// An unsigned word that chunks are encoded in
+pub(crate) trait Word: FastLanes + BitPacking + Delta + Transpose + ArrowNativeType {
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! impl_word {
+    ($T:ty) => {
+        impl Word for $T {
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+        }
+    };
+}
+
+impl_word!(u8);
+impl_word!(u16);
+impl_word!(u32);
+impl_word!(u64);
+
+/// The value of `bits_per_value` bits, as a key that orders the same way as the value
+fn order_key(value: u64, bits_per_value: u64, signed: bool) -> i128 {
+    if signed {
+        let shift = 64 - bits_per_value;
+        (((value << shift) as i64) >> shift) as i128
+    } else {
+        value as i128
+    }
+}
+
+/// The number of bits needed to represent every value of a range of keys
+fn range_bit_width(min: i128, max: i128) -> u64 {
+    (128 - ((max - min) as u128).leading_zeros()) as u64
+}
+
+/// The size of a chunk in bytes, given the bit width of the residuals
+fn chunk_bytes(bits_per_value: u64, order: u32, bit_width: u64) -> u64 {
+    let lanes = 1024 / bits_per_value;
+    let header_words = order as u64 * lanes + 2;
+    header_words * bits_per_value / 8 + 128 * bit_width
+}
+
+/// Estimates the size, in bits, of the data encoded with frame-of-reference (`order` 0),
+/// delta (`order` 1) or delta-of-delta (`order` 2)
+///
+/// The estimate is never smaller than the actual size.  Returns `None` if some chunk would
+/// not fit in a mini-block.
+pub fn estimate_size_bits(data: &FixedWidthDataBlock, order: u32, signed: bool) -> Option<u64> {
+    let bits_per_value = data.bits_per_value;
+    debug_assert!(supports_bits_per_value(bits_per_value));
+    debug_assert!(order <= MAX_DELTA_ORDER);
+    let mask = u64::MAX >> (64 - bits_per_value);
+    // Residuals of delta encoding are always treated as signed
+    let signed = signed || order > 0;
+    let bytes_per_value = (bits_per_value / 8) as usize;
+
+    let mut total_bits = 0;
+    for chunk in data.data.chunks(ELEMS_PER_CHUNK * bytes_per_value) {
+        let mut residuals = chunk
+            .chunks_exact(bytes_per_value)
+            .map(|bytes| {
+                let mut value = [0_u8; 8];
+                value[..bytes_per_value].copy_from_slice(bytes);
+                u64::from_le_bytes(value)
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..order {
+            residuals = residuals
+                .windows(2)
+                .map(|pair| pair[1].wrapping_sub(pair[0]) & mask)
+                .collect();
+        }
+        // The first value of each lane has a residual of zero for delta-of-delta, the
+        // same is true of the padding when there are not enough values for a delta
+        if order == MAX_DELTA_ORDER || residuals.is_empty() {
+            residuals.push(0);
+        }
+        let (min, max) = residuals
+            .iter()
+            .fold((i128::MAX, i128::MIN), |(min, max), v| {
+                let key = order_key(*v, bits_per_value, signed);
+                (min.min(key), max.max(key))
+            });
+        let bytes = chunk_bytes(bits_per_value, order, range_bit_width(min, max));
+        if bytes > MAX_MINIBLOCK_BYTES {
+            return None;
+        }
+        total_bits += bytes * 8;
+    }
+    Some(total_bits)
+}
+
+/// Appends the reference, the bit width and the packed offsets from the reference
+pub(crate) fn encode_frame_of_reference<T: Word>(
+    values: &[T; 1024],
+    signed: bool,
+    out: &mut Vec<T>,
+) {
+    let bits_per_value = T::T as u64;
+    let key = |value: &T| order_key(value.to_u64(), bits_per_value, signed);
+    let reference = *values.iter().min_by_key(|v| key(v)).unwrap();
+    let max = *values.iter().max_by_key(|v| key(v)).unwrap();
+    let bit_width = range_bit_width(key(&reference), key(&max)) as usize;
+
+    let mut offsets = [T::default(); 1024];
+    for (offset, value) in offsets.iter_mut().zip(values) {
+        *offset = value.wrapping_sub(&reference);
+    }
+    out.push(reference);
+    out.push(T::from_usize(bit_width).unwrap());
+    let packed_len = 1024 * bit_width / T::T;
+    let start = out.len();
+    out.resize(start + packed_len, T::default());
+    unsafe {
+        BitPacking::unchecked_pack(bit_width, &offsets, &mut out[start..]);
+    }
+}
+
+/// Decodes the output of [`encode_frame_of_reference`]
+pub(crate) fn decode_frame_of_reference<T: Word>(data: &[T], out: &mut [T; 1024]) -> Result<()> {
+    let invalid = || Error::InvalidInput {
+        source: "a frame-of-reference mini-block is truncated".into(),
+        location: location!(),
+    };
+    let [reference, bit_width, packed @ ..] = data else {
+        return Err(invalid());
+    };
+    let bit_width = bit_width.as_usize();
+    let packed_len = 1024 * bit_width / T::T;
+    if bit_width > T::T || packed.len() < packed_len {
+        return Err(invalid());
+    }
+    unsafe {
+        BitPacking::unchecked_unpack(bit_width, &packed[..packed_len], out);
+    }
+    for value in out.iter_mut() {
+        *value = value.wrapping_add(reference);
+    }
+    Ok(())
+}
+
+/// Copies the values of a chunk, padding the last chunk with `pad`
+fn pad_chunk<T: Word>(values: &[T], pad: impl Fn(&[T]) -> T) -> [T; 1024] {
+    let mut chunk = [T::default(); 1024];
+    chunk[..values.len()].copy_from_slice(values);
+    for idx in values.len()..ELEMS_PER_CHUNK {
+        chunk[idx] = pad(&chunk[..idx]);
+    }
+    chunk
+}
+
+/// Splits the data into chunks of 1024 values and encodes each with `encode_chunk`
+fn chunk_data<T: Word>(
+    data: &mut FixedWidthDataBlock,
+    encode_chunk: impl Fn(&[T], &mut Vec<T>),
+) -> Result<MiniBlockCompressed> {
+    let values = data.data.borrow_to_typed_slice::<T>();
+    let mut output = Vec::new();
+    let mut chunks = Vec::with_capacity(values.len().div_ceil(ELEMS_PER_CHUNK));
+    for (i, chunk) in values.chunks(ELEMS_PER_CHUNK).enumerate() {
+        let start = output.len();
+        encode_chunk(chunk, &mut output);
+        let chunk_bytes = (output.len() - start) * std::mem::size_of::<T>();
+        if chunk_bytes as u64 > MAX_MINIBLOCK_BYTES {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "a delta encoded chunk of {} bytes does not fit in a mini-block",
+                    chunk_bytes
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        let is_last = (i + 1) * ELEMS_PER_CHUNK >= values.len();
+        chunks.push(MiniBlockChunk {
+            buffer_sizes: vec![chunk_bytes as u16],
+            log_num_values: if is_last { 0 } else { LOG_ELEMS_PER_CHUNK },
+        });
+    }
+    Ok(MiniBlockCompressed {
+        data: vec![LanceBuffer::reinterpret_vec(output)],
+        chunks,
+        num_values: data.num_values,
+    })
+}
+
+fn fixed_width_output<T: Word>(mut values: Vec<T>, num_values: u64) -> DataBlock {
+    values.truncate(num_values as usize);
+    DataBlock::FixedWidth(FixedWidthDataBlock {
+        data: LanceBuffer::reinterpret_vec(values),
+        bits_per_value: T::T as u64,
+        num_values,
+        block_info: BlockInfo::new(),
+    })
+}
+
+fn check_chunk_size(num_values: u64) -> Result<()> {
+    if num_values > ELEMS_PER_CHUNK as u64 {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "a delta encoded mini-block cannot hold {} values",
+                num_values
+            )
+            .into(),
+            location: location!(),
+        });
+    }
+    Ok(())
+}
+
+/// Mini-block compressor that bitpacks the offset of each value from the minimum of
+/// its chunk
+#[derive(Debug)]
+pub struct FrameOfReferenceMiniBlockEncoder {
+    bits_per_value: u64,
+    signed: bool,
+}
+
+impl FrameOfReferenceMiniBlockEncoder {
+    pub fn new(bits_per_value: u64, signed: bool) -> Self {
+        assert!(supports_bits_per_value(bits_per_value));
+        Self {
+            bits_per_value,
+            signed,
+        }
+    }
+
+    fn chunk_data<T: Word>(&self, data: &mut FixedWidthDataBlock) -> Result<MiniBlockCompressed> {
+        chunk_data::<T>(data, |values, out| {
+            let chunk = pad_chunk(values, |prev| prev[prev.len() - 1]);
+            encode_frame_of_reference(&chunk, self.signed, out);
+        })
+    }
+}
+
+impl MiniBlockCompressor for FrameOfReferenceMiniBlockEncoder {
+    fn compress(&self, page: DataBlock) -> Result<(MiniBlockCompressed, pb::ArrayEncoding)> {
+        match page {
+            DataBlock::FixedWidth(mut data) if data.bits_per_value == self.bits_per_value => {
+                let compressed = match self.bits_per_value {
+                    8 => self.chunk_data::<u8>(&mut data),
+                    16 => self.chunk_data::<u16>(&mut data),
+                    32 => self.chunk_data::<u32>(&mut data),
+                    64 => self.chunk_data::<u64>(&mut data),
+                    _ => unreachable!(),
+                }?;
+                Ok((
+                    compressed,
+                    ProtobufUtils::frame_of_reference(self.bits_per_value, self.signed),
+                ))
+            }
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Cannot compress a data block of type {} with FrameOfReferenceMiniBlockEncoder",
+                    page.name()
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Decompressor for [`FrameOfReferenceMiniBlockEncoder`]
+#[derive(Debug)]
+pub struct FrameOfReferenceMiniBlockDecompressor {
+    bits_per_value: u64,
+}
+
+impl FrameOfReferenceMiniBlockDecompressor {
+    pub fn from_description(description: &pb::FrameOfReference) -> Self {
+        Self {
+            bits_per_value: description.uncompressed_bits_per_value,
+        }
+    }
+
+    fn unchunk<T: Word>(mut data: LanceBuffer, num_values: u64) -> Result<DataBlock> {
+        check_chunk_size(num_values)?;
+        let words = data.borrow_to_typed_slice::<T>();
+        let mut values = vec![T::default(); ELEMS_PER_CHUNK];
+        decode_frame_of_reference(&words, values.as_mut_slice().try_into().unwrap())?;
+        Ok(fixed_width_output(values, num_values))
+    }
+}
+
+impl MiniBlockDecompressor for FrameOfReferenceMiniBlockDecompressor {
+    fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        assert_eq!(data.len(), 1);
+        let data = data.into_iter().next().unwrap();
+        match self.bits_per_value {
+            8 => Self::unchunk::<u8>(data, num_values),
+            16 => Self::unchunk::<u16>(data, num_values),
+            32 => Self::unchunk::<u32>(data, num_values),
+            64 => Self::unchunk::<u64>(data, num_values),
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Frame-of-reference word size must be 8, 16, 32, or 64, got {}",
+                    self.bits_per_value
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Mini-block compressor that stores the differences between consecutive values
+/// (`order` 1) or the differences between those differences (`order` 2)
+#[derive(Debug)]
+pub struct DeltaMiniBlockEncoder {
+    bits_per_value: u64,
+    order: u32,
+}
+
+impl DeltaMiniBlockEncoder {
+    pub fn new(bits_per_value: u64, order: u32) -> Self {
+        assert!(supports_bits_per_value(bits_per_value));
+        assert!((1..=MAX_DELTA_ORDER).contains(&order));
+        Self {
+            bits_per_value,
+            order,
+        }
+    }
+
+    fn encode_chunk<T: Word>(&self, values: &[T], out: &mut Vec<T>) {
+        // Pad by extending the last difference so the padding has the same residuals
+        // as the values before it
+        let chunk = pad_chunk(values, |prev| match prev {
+            [.., a, b] => b.wrapping_add(&b.wrapping_sub(a)),
+            [a] => *a,
+            [] => unreachable!(),
+        });
+        let mut residuals = [T::default(); 1024];
+        T::transpose(&chunk, &mut residuals);
+
+        let mut deltas = [T::default(); 1024];
+        for _ in 0..self.order {
+            // Pick the base so the first residual of each lane matches the second one
+            for lane in 0..T::LANES {
+                let first = residuals[lane_index(0, lane)];
+                let second = residuals[lane_index(1, lane)];
+                out.push(first.wrapping_add(&first).wrapping_sub(&second));
+            }
+            let base = &out[out.len() - T::LANES..];
+            T::delta(&residuals, base, &mut deltas);
+            residuals = deltas;
+        }
+        encode_frame_of_reference(&residuals, true, out);
+    }
+
+    fn chunk_data<T: Word>(&self, data: &mut FixedWidthDataBlock) -> Result<MiniBlockCompressed> {
+        chunk_data::<T>(data, |values, out| self.encode_chunk(values, out))
+    }
+}
+
+impl MiniBlockCompressor for DeltaMiniBlockEncoder {
+    fn compress(&self, page: DataBlock) -> Result<(MiniBlockCompressed, pb::ArrayEncoding)> {
+        match page {
+            DataBlock::FixedWidth(mut data) if data.bits_per_value == self.bits_per_value => {
+                let compressed = match self.bits_per_value {
+                    8 => self.chunk_data::<u8>(&mut data),
+                    16 => self.chunk_data::<u16>(&mut data),
+                    32 => self.chunk_data::<u32>(&mut data),
+                    64 => self.chunk_data::<u64>(&mut data),
+                    _ => unreachable!(),
+                }?;
+                Ok((
+                    compressed,
+                    ProtobufUtils::delta(self.bits_per_value, self.order),
+                ))
+            }
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Cannot compress a data block of type {} with DeltaMiniBlockEncoder",
+                    page.name()
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Decompressor for [`DeltaMiniBlockEncoder`]
+#[derive(Debug)]
+pub struct DeltaMiniBlockDecompressor {
+    bits_per_value: u64,
+    order: u32,
+}
+
+impl DeltaMiniBlockDecompressor {
+    pub fn from_description(description: &pb::Delta) -> Result<Self> {
+        if !(1..=MAX_DELTA_ORDER).contains(&description.order) {
+            return Err(Error::InvalidInput {
+                source: format!("unsupported delta order {}", description.order).into(),
+                location: location!(),
+            });
+        }
+        Ok(Self {
+            bits_per_value: description.uncompressed_bits_per_value,
+            order: description.order,
+        })
+    }
+
+    fn unchunk<T: Word>(&self, mut data: LanceBuffer, num_values: u64) -> Result<DataBlock> {
+        check_chunk_size(num_values)?;
+        let words = data.borrow_to_typed_slice::<T>();
+        let header_len = self.order as usize * T::LANES;
+        if words.len() < header_len {
+            return Err(Error::InvalidInput {
+                source: "a delta encoded mini-block is truncated".into(),
+                location: location!(),
+            });
+        }
+        let (bases, rest) = words.split_at(header_len);
+
+        let mut residuals = [T::default(); 1024];
+        decode_frame_of_reference(rest, &mut residuals)?;
+        let mut undeltas = [T::default(); 1024];
+        for base in bases.chunks_exact(T::LANES).rev() {
+            T::undelta(&residuals, base, &mut undeltas);
+            residuals = undeltas;
+        }
+        let mut values = vec![T::default(); ELEMS_PER_CHUNK];
+        T::untranspose(&residuals, values.as_mut_slice().try_into().unwrap());
+        Ok(fixed_width_output(values, num_values))
+    }
+}
+
+impl MiniBlockDecompressor for DeltaMiniBlockDecompressor {
+    fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        assert_eq!(data.len(), 1);
+        let data = data.into_iter().next().unwrap();
+        match self.bits_per_value {
+            8 => self.unchunk::<u8>(data, num_values),
+            16 => self.unchunk::<u16>(data, num_values),
+            32 => self.unchunk::<u32>(data, num_values),
+            64 => self.unchunk::<u64>(data, num_values),
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Delta word size must be 8, 16, 32, or 64, got {}",
+                    self.bits_per_value
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow_array::types::TimestampMicrosecondType;
+    use arrow_array::{
+        Array, Date32Array, Int16Array, Int64Array, Int8Array, PrimitiveArray, UInt32Array,
+    };
+    use arrow_schema::DataType;
+    use lance_core::datatypes::Field;
+
+    use super::*;
+    use crate::encoder::{CompressionStrategy, CoreArrayEncodingStrategy};
+    use crate::testing::{
+        check_round_trip_encoding_of_data, miniblock_value_compression, TestCases,
+    };
+    use crate::version::LanceFileVersion;
+
+    fn round_trip(compressor: &dyn MiniBlockCompressor, values: &[u64], bits_per_value: u64) {
+        let bytes_per_value = (bits_per_value / 8) as usize;
+        let data = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes()[..bytes_per_value].to_vec())
+            .collect::<Vec<_>>();
+        let block = FixedWidthDataBlock {
+            data: LanceBuffer::Owned(data.clone()),
+            bits_per_value,
+            num_values: values.len() as u64,
+            block_info: BlockInfo::new(),
+        };
+        let (compressed, encoding) = compressor.compress(DataBlock::FixedWidth(block)).unwrap();
+        let decompressor: Box<dyn MiniBlockDecompressor> = match encoding.array_encoding.unwrap() {
+            pb::array_encoding::ArrayEncoding::FrameOfReference(description) => Box::new(
+                FrameOfReferenceMiniBlockDecompressor::from_description(&description),
+            ),
+            pb::array_encoding::ArrayEncoding::Delta(description) => {
+                Box::new(DeltaMiniBlockDecompressor::from_description(&description).unwrap())
+            }
+            other => panic!("unexpected encoding {:?}", other),
+        };
+
+        let mut offset = 0;
+        let mut values_seen = 0;
+        for chunk in &compressed.chunks {
+            let num_values = chunk.num_values(values_seen, compressed.num_values);
+            let buffer =
+                compressed.data[0].slice_with_length(offset, chunk.buffer_sizes[0] as usize);
+            offset += chunk.buffer_sizes[0] as usize;
+            let decoded = decompressor
+                .decompress(vec![buffer], num_values)
+                .unwrap()
+                .as_fixed_width()
+                .unwrap();
+            let start = values_seen as usize * bytes_per_value;
+            assert_eq!(
+                decoded.data.as_ref(),
+                &data[start..start + num_values as usize * bytes_per_value]
+            );
+            values_seen += num_values;
+        }
+        assert_eq!(values_seen, values.len() as u64);
+    }
+
+    #[test]
+    fn test_delta_chunks() {
+        let ids = (0..5000_u64)
+            .map(|i| 1_000_000_000_000 + i)
+            .collect::<Vec<_>>();
+        let squares = (0..3000_u64).map(|i| i * i).collect::<Vec<_>>();
+        let wrapping = (0..2048_u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect::<Vec<_>>();
+        let negative = (0..1500_i64).map(|i| (-i * 3) as u64).collect::<Vec<_>>();
+        for bits_per_value in [8, 16, 32, 64] {
+            let mask = u64::MAX >> (64 - bits_per_value);
+            for values in [&ids, &squares, &wrapping, &negative] {
+                if bits_per_value == 64 && values == &wrapping {
+                    continue;
+                }
+                let values = values.iter().map(|v| v & mask).collect::<Vec<_>>();
+                for signed in [false, true] {
+                    let compressor = FrameOfReferenceMiniBlockEncoder::new(bits_per_value, signed);
+                    round_trip(&compressor, &values, bits_per_value);
+                }
+                for order in 1..=MAX_DELTA_ORDER {
+                    let compressor = DeltaMiniBlockEncoder::new(bits_per_value, order);
+                    round_trip(&compressor, &values, bits_per_value);
+                }
+            }
+            // Chunks with one or two values, or one too many
+            for values in [vec![7], vec![mask, 0], vec![3; 1025]] {
+                round_trip(
+                    &DeltaMiniBlockEncoder::new(bits_per_value, 2),
+                    &values,
+                    bits_per_value,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_delta_chunk_too_large() {
+        // Random 64-bit values need all 64 bits, which does not fit in a mini-block
+        let values = (0..2048_u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect::<Vec<_>>();
+        let block = || {
+            DataBlock::FixedWidth(FixedWidthDataBlock {
+                data: LanceBuffer::reinterpret_vec(values.clone()),
+                bits_per_value: 64,
+                num_values: 2048,
+                block_info: BlockInfo::new(),
+            })
+        };
+        assert!(FrameOfReferenceMiniBlockEncoder::new(64, false)
+            .compress(block())
+            .is_err());
+        assert!(DeltaMiniBlockEncoder::new(64, 1).compress(block()).is_err());
+    }
+
+    #[test]
+    fn test_delta_invalid_word_size() {
+        let data = || vec![LanceBuffer::Owned(vec![0; 64])];
+        let decompressor =
+            FrameOfReferenceMiniBlockDecompressor::from_description(&pb::FrameOfReference {
+                uncompressed_bits_per_value: 24,
+                signed: false,
+            });
+        assert!(matches!(
+            decompressor.decompress(data(), 4),
+            Err(Error::InvalidInput { .. })
+        ));
+        let decompressor = DeltaMiniBlockDecompressor::from_description(&pb::Delta {
+            uncompressed_bits_per_value: 24,
+            order: 1,
+        })
+        .unwrap();
+        assert!(matches!(
+            decompressor.decompress(data(), 4),
+            Err(Error::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_delta_estimate() {
+        let block = |values: Vec<i64>| {
+            let array = Arc::new(Int64Array::from(values)) as Arc<dyn Array>;
+            DataBlock::from_array(array).as_fixed_width().unwrap()
+        };
+
+        // Regularly spaced values have constant deltas and no delta-of-deltas
+        let ids = block((0..4096).map(|i| 1_000_000_000_000 + i * 10).collect());
+        let header_bits = 8 * 8 * (16 + 2);
+        assert_eq!(estimate_size_bits(&ids, 1, true), Some(4 * header_bits));
+        assert_eq!(
+            estimate_size_bits(&ids, 0, true),
+            Some(4 * (8 * 8 * 2 + 1024 * 14))
+        );
+
+        // Random values cannot be encoded in a mini-block
+        let random = block(
+            (0..4096_i64)
+                .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15_u64 as i64))
+                .collect(),
+        );
+        assert_eq!(estimate_size_bits(&random, 0, true), None);
+        assert_eq!(estimate_size_bits(&random, 2, true), None);
+    }
+
+    #[test]
+    fn test_delta_selected() {
+        let strategy = CoreArrayEncodingStrategy::default();
+        let field = Field::new_arrow("", DataType::Int64, false).unwrap();
+        let compressor_for = |values: Vec<i64>| {
+            let block = DataBlock::from_array(Arc::new(Int64Array::from(values)) as Arc<dyn Array>);
+            let compressor = strategy
+                .create_miniblock_compressor(&field, &block)
+                .unwrap();
+            format!("{:?}", compressor)
+        };
+
+        let ids = (0..10_000).map(|i| 1_000_000_000_000 + i).collect();
+        assert!(compressor_for(ids).contains("order: 1"));
+        let squares = (0..10_000).map(|i| i * i).collect();
+        assert!(compressor_for(squares).contains("order: 2"));
+        let clustered = (0..10_000)
+            .map(|i| 1_000_000_000_000 + (i * 7919) % 1000)
+            .collect();
+        assert!(compressor_for(clustered).contains("FrameOfReference"));
+        let random = (0..10_000_i64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15_u64 as i64))
+            .collect();
+        let random = compressor_for(random);
+        assert!(!random.contains("Delta") && !random.contains("FrameOfReference"));
+    }
+
+    fn expect_delta_or_frame_of_reference() -> TestCases {
+        TestCases::default()
+            .with_file_version(LanceFileVersion::V2_1)
+            .with_verify_page_encoding(Arc::new(|encoding| {
+                let compression = miniblock_value_compression(encoding).unwrap();
+                assert!(matches!(
+                    compression.array_encoding,
+                    Some(pb::array_encoding::ArrayEncoding::Delta(_))
+                        | Some(pb::array_encoding::ArrayEncoding::FrameOfReference(_))
+                ));
+            }))
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_miniblock_delta() {
+        let timestamps = PrimitiveArray::<TimestampMicrosecondType>::from_iter_values(
+            (0..2000_i64).map(|i| 1_700_000_000_000_000 + i * 1_000_000 + (i * 7919) % 1000),
+        );
+        let arrays = vec![
+            Arc::new(Int64Array::from_iter_values(
+                (0..2000).map(|i| 1_000_000_000_000 + i),
+            )) as Arc<dyn Array>,
+            Arc::new(timestamps),
+            Arc::new(Date32Array::from_iter_values(
+                (0..2000).map(|i| 19_000 + i / 2),
+            )),
+            Arc::new(UInt32Array::from_iter_values(
+                (0..2000).map(|i| u32::MAX - i * 3),
+            )),
+            Arc::new(Int16Array::from_iter_values(
+                (0..2000).map(|i| -20_000 + i * 5),
+            )),
+            Arc::new(Int8Array::from_iter_values(
+                (0..2000).map(|i| (i % 256) as u8 as i8),
+            )),
+        ];
+        // Few enough values that the 8-bit column is not dictionary encoded
+        for array in arrays {
+            for test_cases in [
+                expect_delta_or_frame_of_reference(),
+                expect_delta_or_frame_of_reference().with_range(1000..1100),
+                expect_delta_or_frame_of_reference().with_range(1020..1030),
+                expect_delta_or_frame_of_reference().with_indices(vec![0, 1, 1023, 1024, 1999]),
+            ] {
+                check_round_trip_encoding_of_data(vec![array.clone()], &test_cases, HashMap::new())
+                    .await;
+            }
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_miniblock_delta_with_nulls() {
+        let array = Arc::new(Int64Array::from_iter(
+            (0..5000).map(|i| (i % 100 != 0).then_some(i * 1000)),
+        )) as Arc<dyn Array>;
+        for test_cases in [
+            TestCases::default(),
+            TestCases::default().with_range(90..210),
+            TestCases::default().with_indices(vec![0, 100, 101, 4999]),
+        ] {
+            let test_cases = test_cases.with_file_version(LanceFileVersion::V2_1);
+            check_round_trip_encoding_of_data(vec![array.clone()], &test_cases, HashMap::new())
+                .await;
+        }
+    }
+}
@@ -0,0 +1,694 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Lossy compression of vectors (e.g. embeddings)
+//!
+//! This is opt-in with the [`VECTOR_COMPRESSION_META_KEY`] field metadata and only applies
+//! to fixed size lists of float32 or float64 without null items.  Every vector is stored
+//! in a fixed number of bytes:
+//!
+//! * `float16` / `bfloat16` - 2 bytes per item, items are rounded to the nearest
+//!   representable value
+//! * `int8` - 1 byte per item, items are scaled linearly from the range of the items in
+//!   the page to 0..=255 (the same scaling as the scalar quantizer of the vector indices),
+//!   so items decode to the nearest of 256 evenly spaced values
+//! * `binary` - 1 bit per item (the same bit order as the binary quantizer), set if the
+//!   item is positive, items decode to 1.0 or -1.0
+//!
+//! Decoding always gives back vectors of the declared type, the original values can not
+//! be recovered.  Columns that need exact values should keep a lossless copy.
+//!
+//! [`VECTOR_COMPRESSION_META_KEY`]: lance_core::datatypes::VECTOR_COMPRESSION_META_KEY
+
+use std::ops::Range;
+use std::str::FromStr;
+
+use arrow_buffer::ArrowNativeType;
+use half::{bf16, f16};
+use lance_arrow::{ArrowFloatType, FloatToArrayType};
+use lance_core::datatypes::{
+    VECTOR_COMPRESSION_BFLOAT16, VECTOR_COMPRESSION_BINARY, VECTOR_COMPRESSION_FLOAT16,
+    VECTOR_COMPRESSION_INT8, VECTOR_COMPRESSION_META_KEY,
+};
+use lance_core::{Error, Result};
+use num_traits::{AsPrimitive, Float, ToPrimitive};
+use snafu::location;
+
+use crate::buffer::LanceBuffer;
+use crate::data::{BlockInfo, DataBlock, FixedSizeListBlock, FixedWidthDataBlock};
+use crate::decoder::{FixedPerValueDecompressor, MiniBlockDecompressor};
+use crate::encoder::{
+    MiniBlockChunk, MiniBlockCompressed, MiniBlockCompressor, PerValueCompressor,
+    PerValueDataBlock, MAX_MINIBLOCK_VALUES,
+};
+use crate::format::{
+    pb::{self, VectorQuantization},
+    ProtobufUtils,
+};
+
+/// Aim for chunks with 4KiB of codes, like uncompressed mini-blocks
+const CHUNK_BYTES: u64 = 4096;
+
+/// The lossy compression requested for a vector field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorCompression {
+    Float16,
+    BFloat16,
+    Int8,
+    Binary,
+}
+
+impl VectorCompression {
+    fn quantization(&self) -> VectorQuantization {
+        match self {
+            Self::Float16 => VectorQuantization::Float16,
+            Self::BFloat16 => VectorQuantization::Bfloat16,
+            Self::Int8 => VectorQuantization::Int8,
+            Self::Binary => VectorQuantization::Binary,
+        }
+    }
+
+    fn from_quantization(quantization: VectorQuantization) -> Result<Self> {
+        match quantization {
+            VectorQuantization::Float16 => Ok(Self::Float16),
+            VectorQuantization::Bfloat16 => Ok(Self::BFloat16),
+            VectorQuantization::Int8 => Ok(Self::Int8),
+            VectorQuantization::Binary => Ok(Self::Binary),
+            VectorQuantization::Unspecified => Err(Error::InvalidInput {
+                source: "a quantized vector page does not specify the quantization".into(),
+                location: location!(),
+            }),
+        }
+    }
+
+    /// The number of bytes used to store a vector with `dimension` items
+    pub fn bytes_per_vector(&self, dimension: u64) -> u64 {
+        match self {
+            Self::Float16 | Self::BFloat16 => dimension * 2,
+            Self::Int8 => dimension,
+            Self::Binary => dimension.div_ceil(8),
+        }
+    }
+
+    /// Whether vectors of `dimension` items compress small enough for mini-block chunks
+    ///
+    /// Wider vectors have to be written with the full-zip layout.
+    pub fn supports_miniblock(&self, dimension: u64) -> bool {
+        log_vectors_per_chunk(self.bytes_per_vector(dimension)).is_some()
+    }
+}
+
+impl FromStr for VectorCompression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            VECTOR_COMPRESSION_FLOAT16 => Ok(Self::Float16),
+            VECTOR_COMPRESSION_BFLOAT16 => Ok(Self::BFloat16),
+            VECTOR_COMPRESSION_INT8 => Ok(Self::Int8),
+            VECTOR_COMPRESSION_BINARY => Ok(Self::Binary),
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Unknown value {} for the field metadata key {}, expected one of {}, {}, {} or {}",
+                    s,
+                    VECTOR_COMPRESSION_META_KEY,
+                    VECTOR_COMPRESSION_FLOAT16,
+                    VECTOR_COMPRESSION_BFLOAT16,
+                    VECTOR_COMPRESSION_INT8,
+                    VECTOR_COMPRESSION_BINARY
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Scales `values` linearly from `bounds` to 0..=255
+///
+/// Values outside of the bounds saturate.  Used both by the int8 vector compression and
+/// the scalar quantizer of the vector indices.
+pub fn scale_to_u8<T: ArrowFloatType>(values: &[T::Native], bounds: &Range<f64>) -> Vec<u8> {
+    if bounds.start == bounds.end {
+        return vec![0; values.len()];
+    }
+
+    let range = bounds.end - bounds.start;
+    values
+        .iter()
+        .map(|&v| {
+            let v = v.to_f64().unwrap();
+            let v = ((v - bounds.start) * 255.0 / range).round();
+            v as u8 // rust `as` performs saturating cast when casting float to int, so it's safe and expected here
+        })
+        .collect()
+}
+
+/// The range of the finite values, `0..0` if there are none
+fn finite_bounds<T: FloatToArrayType>(values: &[T]) -> Range<f64> {
+    values
+        .iter()
+        .map(|v| AsPrimitive::<f64>::as_(*v))
+        .filter(|v| v.is_finite())
+        .fold(None, |bounds: Option<Range<f64>>, v| match bounds {
+            Some(bounds) => Some(bounds.start.min(v)..bounds.end.max(v)),
+            None => Some(v..v),
+        })
+        .unwrap_or(0.0..0.0)
+}
+
+/// Appends the sign bits of each vector, a vector always starts on a new byte
+///
+/// Used both by the binary vector compression and the binary quantizer of the vector
+/// indices.
+pub fn pack_sign_bits<T: Float>(values: &[T], dimension: usize, out: &mut Vec<u8>) {
+    for vector in values.chunks_exact(dimension) {
+        for byte in vector.chunks(8) {
+            // Auto vectorized.
+            // Before changing this code, please check the assembly output.
+            let mut bits: u8 = 0;
+            for (idx, v) in byte.iter().enumerate() {
+                bits |= (v.is_sign_positive() as u8) << idx;
+            }
+            out.push(bits);
+        }
+    }
+}
+
+/// The largest power-of-two number of vectors that fits in a chunk, at least 2
+fn log_vectors_per_chunk(bytes_per_vector: u64) -> Option<u8> {
+    let max_vectors = (CHUNK_BYTES / bytes_per_vector.max(1)).min(MAX_MINIBLOCK_VALUES);
+    (max_vectors >= 2).then(|| max_vectors.ilog2() as u8)
+}
+
+/// Compressor that quantizes vectors, used for both mini-block and full-zip pages
+#[derive(Debug)]
+pub struct QuantizedVectorEncoder {
+    compression: VectorCompression,
+}
+
+impl QuantizedVectorEncoder {
+    pub fn new(compression: VectorCompression) -> Self {
+        Self { compression }
+    }
+
+    fn quantize_items<T: FloatToArrayType + ArrowNativeType>(
+        &self,
+        mut items: FixedWidthDataBlock,
+        dimension: u64,
+    ) -> (Vec<u8>, Range<f64>) {
+        let values = items.data.borrow_to_typed_slice::<T>();
+        let values = values.as_ref();
+        let num_bytes = (values.len() as u64 / dimension.max(1)
+            * self.compression.bytes_per_vector(dimension)) as usize;
+        let mut codes = Vec::with_capacity(num_bytes);
+        let mut bounds = 0.0..0.0;
+        match self.compression {
+            VectorCompression::Float16 => {
+                for v in values {
+                    codes.extend_from_slice(
+                        &f16::from_f64(AsPrimitive::<f64>::as_(*v)).to_le_bytes(),
+                    );
+                }
+            }
+            VectorCompression::BFloat16 => {
+                for v in values {
+                    codes.extend_from_slice(
+                        &bf16::from_f64(AsPrimitive::<f64>::as_(*v)).to_le_bytes(),
+                    );
+                }
+            }
+            VectorCompression::Int8 => {
+                bounds = finite_bounds(values);
+                codes = scale_to_u8::<T::ArrowType>(values, &bounds);
+            }
+            VectorCompression::Binary => {
+                pack_sign_bits(values, dimension as usize, &mut codes);
+            }
+        }
+        (codes, bounds)
+    }
+
+    /// Quantizes the vectors into one fixed width code per vector
+    fn quantize(&self, data: DataBlock) -> Result<(FixedWidthDataBlock, pb::ArrayEncoding)> {
+        let DataBlock::FixedSizeList(FixedSizeListBlock { child, dimension }) = data else {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "Cannot compress a data block of type {} with QuantizedVectorEncoder",
+                    data.name()
+                )
+                .into(),
+                location: location!(),
+            });
+        };
+        let items = match *child {
+            DataBlock::FixedWidth(items) if matches!(items.bits_per_value, 32 | 64) => items,
+            child => {
+                return Err(Error::InvalidInput {
+                    source: format!(
+                        "QuantizedVectorEncoder only supports lists of 32 or 64 bit floats without nulls, got {}",
+                        child.name()
+                    )
+                    .into(),
+                    location: location!(),
+                })
+            }
+        };
+        let bits_per_item = items.bits_per_value;
+        let num_vectors = items.num_values / dimension;
+        let (codes, bounds) = if bits_per_item == 32 {
+            self.quantize_items::<f32>(items, dimension)
+        } else {
+            self.quantize_items::<f64>(items, dimension)
+        };
+        let bytes_per_vector = self.compression.bytes_per_vector(dimension);
+        debug_assert_eq!(codes.len() as u64, num_vectors * bytes_per_vector);
+        Ok((
+            FixedWidthDataBlock {
+                data: LanceBuffer::Owned(codes),
+                bits_per_value: bytes_per_vector * 8,
+                num_values: num_vectors,
+                block_info: BlockInfo::new(),
+            },
+            ProtobufUtils::quantized_vector(
+                bits_per_item,
+                dimension,
+                self.compression.quantization(),
+                bounds.start,
+                bounds.end,
+            ),
+        ))
+    }
+}
+
+impl MiniBlockCompressor for QuantizedVectorEncoder {
+    fn compress(&self, page: DataBlock) -> Result<(MiniBlockCompressed, pb::ArrayEncoding)> {
+        let (codes, encoding) = self.quantize(page)?;
+        let bytes_per_vector = codes.bits_per_value / 8;
+        let Some(log_vectors) = log_vectors_per_chunk(bytes_per_vector) else {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "quantized vectors of {} bytes are too large for mini-block encoding",
+                    bytes_per_vector
+                )
+                .into(),
+                location: location!(),
+            });
+        };
+        let chunk_bytes = (bytes_per_vector << log_vectors) as usize;
+
+        let mut chunks = codes
+            .data
+            .chunks(chunk_bytes)
+            .map(|chunk| MiniBlockChunk {
+                buffer_sizes: vec![chunk.len() as u16],
+                log_num_values: log_vectors,
+            })
+            .collect::<Vec<_>>();
+        if let Some(last) = chunks.last_mut() {
+            last.log_num_values = 0;
+        }
+
+        Ok((
+            MiniBlockCompressed {
+                data: vec![codes.data],
+                chunks,
+                num_values: codes.num_values,
+            },
+            encoding,
+        ))
+    }
+}
+
+impl PerValueCompressor for QuantizedVectorEncoder {
+    fn compress(&self, data: DataBlock) -> Result<(PerValueDataBlock, pb::ArrayEncoding)> {
+        let (codes, encoding) = self.quantize(data)?;
+        Ok((PerValueDataBlock::Fixed(codes), encoding))
+    }
+}
+
+/// Decompressor for [`QuantizedVectorEncoder`], decodes vectors of the original item type
+#[derive(Debug)]
+pub struct QuantizedVectorDecompressor {
+    compression: VectorCompression,
+    bits_per_item: u64,
+    dimension: u64,
+    bounds: Range<f64>,
+}
+
+impl QuantizedVectorDecompressor {
+    pub fn from_description(description: &pb::QuantizedVector) -> Result<Self> {
+        if !matches!(description.bits_per_item, 32 | 64) || description.dimension == 0 {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "invalid quantized vector encoding, {} bit items with dimension {}",
+                    description.bits_per_item, description.dimension
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        Ok(Self {
+            compression: VectorCompression::from_quantization(description.quantization())?,
+            bits_per_item: description.bits_per_item,
+            dimension: description.dimension,
+            bounds: description.min_value..description.max_value,
+        })
+    }
+
+    fn dequantize(&self, codes: &[u8], num_vectors: u64) -> Result<DataBlock> {
+        let bytes_per_vector = self.compression.bytes_per_vector(self.dimension) as usize;
+        let expected_bytes = num_vectors as usize * bytes_per_vector;
+        if codes.len() < expected_bytes {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "a quantized vector buffer has {} bytes, expected {}",
+                    codes.len(),
+                    expected_bytes
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        let codes = &codes[..expected_bytes];
+        let dimension = self.dimension as usize;
+        let items: Box<dyn Iterator<Item = f64> + '_> = match self.compression {
+            VectorCompression::Float16 => Box::new(
+                codes
+                    .chunks_exact(2)
+                    .map(|code| f16::from_le_bytes([code[0], code[1]]).to_f64()),
+            ),
+            VectorCompression::BFloat16 => Box::new(
+                codes
+                    .chunks_exact(2)
+                    .map(|code| bf16::from_le_bytes([code[0], code[1]]).to_f64()),
+            ),
+            VectorCompression::Int8 => {
+                let step = (self.bounds.end - self.bounds.start) / 255.0;
+                Box::new(
+                    codes
+                        .iter()
+                        .map(move |code| self.bounds.start + *code as f64 * step),
+                )
+            }
+            VectorCompression::Binary => Box::new(
+                codes
+                    .chunks_exact(bytes_per_vector)
+                    .flat_map(move |vector| {
+                        (0..dimension).map(move |idx| (vector[idx / 8] >> (idx % 8)) & 1)
+                    })
+                    .map(|bit| if bit == 1 { 1.0 } else { -1.0 }),
+            ),
+        };
+        let data = if self.bits_per_item == 32 {
+            LanceBuffer::reinterpret_vec(items.map(|v| v as f32).collect::<Vec<_>>())
+        } else {
+            LanceBuffer::reinterpret_vec(items.collect::<Vec<_>>())
+        };
+        Ok(DataBlock::FixedSizeList(FixedSizeListBlock {
+            child: Box::new(DataBlock::FixedWidth(FixedWidthDataBlock {
+                data,
+                bits_per_value: self.bits_per_item,
+                num_values: num_vectors * self.dimension,
+                block_info: BlockInfo::new(),
+            })),
+            dimension: self.dimension,
+        }))
+    }
+}
+
+impl MiniBlockDecompressor for QuantizedVectorDecompressor {
+    fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        assert_eq!(data.len(), 1);
+        self.dequantize(&data[0], num_values)
+    }
+}
+
+impl FixedPerValueDecompressor for QuantizedVectorDecompressor {
+    fn decompress(&self, data: FixedWidthDataBlock, num_values: u64) -> Result<DataBlock> {
+        self.dequantize(&data.data, num_values)
+    }
+
+    fn bits_per_value(&self) -> u64 {
+        self.compression.bytes_per_vector(self.dimension) * 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow_array::{Array, FixedSizeListArray, Float32Array};
+    use arrow_buffer::{NullBuffer, ToByteSlice};
+    use arrow_schema::{DataType, Field as ArrowField};
+    use lance_core::datatypes::{
+        Field, STRUCTURAL_ENCODING_META_KEY, STRUCTURAL_ENCODING_MINIBLOCK,
+    };
+    use rstest::rstest;
+
+    use super::*;
+    use crate::decoder::PageEncoding;
+    use crate::encoder::{CompressionStrategy, CoreArrayEncodingStrategy};
+    use crate::testing::{check_round_trip_encoding_of_data, TestCases};
+    use crate::version::LanceFileVersion;
+
+    fn noise(idx: u32) -> f32 {
+        (idx.wrapping_mul(0x9E37_79B9) >> 8) as f32 / (1 << 23) as f32 - 1.0
+    }
+
+    fn fsl_block(values: &[f32], dimension: u64) -> DataBlock {
+        DataBlock::FixedSizeList(FixedSizeListBlock {
+            child: Box::new(DataBlock::FixedWidth(FixedWidthDataBlock {
+                data: LanceBuffer::Owned(values.to_byte_slice().to_vec()),
+                bits_per_value: 32,
+                num_values: values.len() as u64,
+                block_info: BlockInfo::new(),
+            })),
+            dimension,
+        })
+    }
+
+    #[rstest]
+    fn test_quantization_error(
+        #[values(
+            VectorCompression::Float16,
+            VectorCompression::BFloat16,
+            VectorCompression::Int8,
+            VectorCompression::Binary
+        )]
+        compression: VectorCompression,
+    ) {
+        let dimension = 20;
+        let values = (0..2000).map(noise).collect::<Vec<_>>();
+        let encoder = QuantizedVectorEncoder::new(compression);
+        let (compressed, encoding) =
+            MiniBlockCompressor::compress(&encoder, fsl_block(&values, dimension)).unwrap();
+        assert_eq!(compressed.num_values, 100);
+        assert_eq!(
+            compressed.data[0].len() as u64,
+            100 * compression.bytes_per_vector(dimension)
+        );
+        let Some(pb::array_encoding::ArrayEncoding::QuantizedVector(description)) =
+            encoding.array_encoding
+        else {
+            panic!("unexpected encoding {:?}", encoding);
+        };
+
+        let decompressor = QuantizedVectorDecompressor::from_description(&description).unwrap();
+        let decoded = MiniBlockDecompressor::decompress(
+            &decompressor,
+            vec![compressed.data[0].try_clone().unwrap()],
+            100,
+        )
+        .unwrap();
+        let DataBlock::FixedSizeList(decoded) = decoded else {
+            panic!("expected a fixed size list");
+        };
+        assert_eq!(decoded.dimension, dimension);
+        let mut items = decoded.child.as_fixed_width().unwrap();
+        let decoded_values = items.data.borrow_to_typed_slice::<f32>();
+        assert_eq!(decoded_values.len(), values.len());
+
+        let range = description.max_value - description.min_value;
+        for (value, decoded) in values.iter().zip(decoded_values.iter()) {
+            let error = (value - decoded).abs();
+            match compression {
+                VectorCompression::Float16 => assert!(error <= value.abs() / 1024.0 + 1e-7),
+                VectorCompression::BFloat16 => assert!(error <= value.abs() / 128.0),
+                VectorCompression::Int8 => assert!(error as f64 <= range / 510.0 + 1e-6),
+                VectorCompression::Binary => assert_eq!(*decoded, value.signum()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_vector_compression_selected() {
+        let strategy = CoreArrayEncodingStrategy::default();
+        let values = (0..2000).map(noise).collect::<Vec<_>>();
+        let field_with = |item_type: DataType, requested: &str| {
+            let field = ArrowField::new_fixed_size_list(
+                "vec",
+                ArrowField::new("item", item_type, true),
+                20,
+                true,
+            )
+            .with_metadata(HashMap::from([(
+                VECTOR_COMPRESSION_META_KEY.to_string(),
+                requested.to_string(),
+            )]));
+            Field::try_from(&field).unwrap()
+        };
+
+        let field = field_with(DataType::Float32, "INT8");
+        let compressor = strategy
+            .create_miniblock_compressor(&field, &fsl_block(&values, 20))
+            .unwrap();
+        assert!(format!("{:?}", compressor).contains("Int8"));
+        let compressor = strategy
+            .create_per_value(&field, &fsl_block(&values, 20))
+            .unwrap();
+        assert!(format!("{:?}", compressor).contains("Int8"));
+
+        // Unknown compressions and non-float items are rejected
+        let field = field_with(DataType::Float32, "int4");
+        assert!(strategy
+            .create_miniblock_compressor(&field, &fsl_block(&values, 20))
+            .is_err());
+        let field = field_with(DataType::Int32, "float16");
+        assert!(strategy
+            .create_per_value(&field, &fsl_block(&values, 20))
+            .is_err());
+    }
+
+    /// Vectors that every compression can store exactly
+    fn exact_vectors(compression: VectorCompression, dimension: i32) -> Arc<dyn Array> {
+        let num_rows = 1000;
+        let values = (0..num_rows * dimension)
+            .map(|idx| {
+                let (row, item) = (idx / dimension, idx % dimension);
+                match compression {
+                    VectorCompression::Float16 | VectorCompression::BFloat16 => {
+                        ((row + item) % 64 - 32) as f32
+                    }
+                    // Each vector has the full range so every page is scaled by exactly 1
+                    VectorCompression::Int8 => match item {
+                        0 => 0.0,
+                        1 => 255.0,
+                        _ => ((row * 7 + item) % 256) as f32,
+                    },
+                    VectorCompression::Binary => {
+                        if (row + item) % 3 == 0 {
+                            -1.0
+                        } else {
+                            1.0
+                        }
+                    }
+                }
+            })
+            .collect::<Float32Array>();
+        let validity = NullBuffer::from_iter((0..num_rows).map(|row| row % 7 != 3));
+        Arc::new(FixedSizeListArray::new(
+            Arc::new(ArrowField::new("item", DataType::Float32, true)),
+            dimension,
+            Arc::new(values),
+            Some(validity),
+        ))
+    }
+
+    fn value_compression(encoding: &PageEncoding) -> Option<&pb::ArrayEncoding> {
+        let PageEncoding::Structural(layout) = encoding else {
+            return None;
+        };
+        match layout.layout.as_ref()? {
+            pb::page_layout::Layout::MiniBlockLayout(mini_block) => {
+                mini_block.value_compression.as_ref()
+            }
+            pb::page_layout::Layout::FullZipLayout(full_zip) => full_zip.value_compression.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn test_vector_compression_round_trip(
+        #[values(
+            VectorCompression::Float16,
+            VectorCompression::BFloat16,
+            VectorCompression::Int8,
+            VectorCompression::Binary
+        )]
+        compression: VectorCompression,
+        // Narrow vectors are written as mini-blocks, wide vectors are full-zipped
+        #[values(16, 256)] dimension: i32,
+    ) {
+        let requested = match compression {
+            VectorCompression::Float16 => VECTOR_COMPRESSION_FLOAT16,
+            VectorCompression::BFloat16 => VECTOR_COMPRESSION_BFLOAT16,
+            VectorCompression::Int8 => VECTOR_COMPRESSION_INT8,
+            VectorCompression::Binary => VECTOR_COMPRESSION_BINARY,
+        };
+        let metadata = HashMap::from([(
+            VECTOR_COMPRESSION_META_KEY.to_string(),
+            requested.to_string(),
+        )]);
+        let test_cases = TestCases::default()
+            .with_range(0..10)
+            .with_range(300..700)
+            .with_indices(vec![0, 3, 511, 999])
+            .with_file_version(LanceFileVersion::V2_1)
+            .with_verify_page_encoding(Arc::new(move |encoding| {
+                let Some(pb::array_encoding::ArrayEncoding::QuantizedVector(description)) =
+                    value_compression(encoding).and_then(|c| c.array_encoding.as_ref())
+                else {
+                    panic!("expected a quantized vector page, got {:?}", encoding);
+                };
+                assert_eq!(description.quantization(), compression.quantization());
+                assert_eq!(description.dimension, dimension as u64);
+            }));
+        check_round_trip_encoding_of_data(
+            vec![exact_vectors(compression, dimension)],
+            &test_cases,
+            metadata,
+        )
+        .await;
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_wide_vectors_fall_back_to_full_zip() {
+        // Two float16 vectors of 2048 items don't fit in a chunk
+        let dimension = 2048;
+        assert!(!VectorCompression::Float16.supports_miniblock(dimension as u64));
+        assert!(VectorCompression::Int8.supports_miniblock(dimension as u64));
+
+        let metadata = HashMap::from([
+            (
+                VECTOR_COMPRESSION_META_KEY.to_string(),
+                VECTOR_COMPRESSION_FLOAT16.to_string(),
+            ),
+            (
+                STRUCTURAL_ENCODING_META_KEY.to_string(),
+                STRUCTURAL_ENCODING_MINIBLOCK.to_string(),
+            ),
+        ]);
+        let test_cases = TestCases::default()
+            .with_range(0..10)
+            .with_indices(vec![0, 3, 511, 999])
+            .with_file_version(LanceFileVersion::V2_1)
+            .with_verify_page_encoding(Arc::new(|encoding| {
+                let PageEncoding::Structural(layout) = encoding else {
+                    panic!("expected a structural page, got {:?}", encoding);
+                };
+                assert!(matches!(
+                    layout.layout,
+                    Some(pb::page_layout::Layout::FullZipLayout(_))
+                ));
+            }));
+        check_round_trip_encoding_of_data(
+            vec![exact_vectors(VectorCompression::Float16, dimension)],
+            &test_cases,
+            metadata,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,461 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Run-length encoding for mini-blocks
+//!
+//! Sorted or low-cardinality columns (status flags, partition keys, repeated timestamps)
+//! tend to contain long runs of identical values.  Each chunk is stored as two buffers:
+//!
+//! * The value of each run, bitpacked, preceded by a single byte with the bit width
+//! * The number of runs as a u16, a single byte with the bit width, and then the length
+//!   of each run (minus one), bitpacked
+//!
+//! Both bit widths are chosen per chunk.  A range of values can be decoded by walking
+//! the run lengths, without expanding the rest of the chunk.
+
+use std::ops::Range;
+
+use lance_core::{Error, Result};
+use snafu::location;
+
+use crate::buffer::LanceBuffer;
+use crate::data::{BlockInfo, DataBlock, FixedWidthDataBlock};
+use crate::decoder::MiniBlockDecompressor;
+use crate::encoder::{
+    MiniBlockChunk, MiniBlockCompressed, MiniBlockCompressor, MAX_MINIBLOCK_BYTES,
+    MAX_MINIBLOCK_VALUES,
+};
+use crate::format::pb;
+use crate::format::ProtobufUtils;
+
+/// The largest number of values in a chunk, as a power of two
+const LOG_MAX_VALUES_PER_CHUNK: u8 = MAX_MINIBLOCK_VALUES.trailing_zeros() as u8;
+
+/// Leave room for the padding between the two buffers of a chunk
+const MAX_CHUNK_BYTES: u64 = MAX_MINIBLOCK_BYTES - 16;
+
+/// The size of the header of the run lengths buffer (number of runs and bit width)
+const RUN_LENGTHS_HEADER_BYTES: usize = 3;
+
+/// Whether run-length encoding supports values of the given width
+pub fn supports_bits_per_value(bits_per_value: u64) -> bool {
+    matches!(bits_per_value, 8 | 16 | 32 | 64)
+}
+
+/// The number of bits needed to represent the value
+fn bit_width(value: u64) -> u8 {
+    (64 - value.leading_zeros()) as u8
+}
+
+/// Appends `bit_width` bits of each value to `out`, least significant bit first
+fn pack_bits(values: impl Iterator<Item = u64>, bit_width: u8, out: &mut Vec<u8>) {
+    let mut acc = 0_u128;
+    let mut acc_bits = 0_u32;
+    for value in values {
+        acc |= (value as u128) << acc_bits;
+        acc_bits += bit_width as u32;
+        while acc_bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    }
+    if acc_bits > 0 {
+        out.push(acc as u8);
+    }
+}
+
+/// Reads values that were written by [`pack_bits`]
+struct BitUnpacker<'a> {
+    data: &'a [u8],
+    bit_width: u8,
+}
+
+impl BitUnpacker<'_> {
+    fn get(&self, idx: usize) -> u64 {
+        if self.bit_width == 0 {
+            return 0;
+        }
+        let start_bit = idx * self.bit_width as usize;
+        let start_byte = start_bit / 8;
+        let end_byte = (start_bit + self.bit_width as usize).div_ceil(8);
+        let mut acc = 0_u128;
+        for (i, byte) in self.data[start_byte..end_byte].iter().enumerate() {
+            acc |= (*byte as u128) << (8 * i);
+        }
+        let mask = if self.bit_width == 64 {
+            u64::MAX
+        } else {
+            (1 << self.bit_width) - 1
+        };
+        (acc >> (start_bit % 8)) as u64 & mask
+    }
+}
+
+/// A chunk of runs, split into its two buffers
+struct RleChunk<'a> {
+    values: BitUnpacker<'a>,
+    run_lengths: BitUnpacker<'a>,
+    num_runs: usize,
+}
+
+impl<'a> RleChunk<'a> {
+    fn try_new(data: &'a [LanceBuffer]) -> Result<Self> {
+        if data.len() != 2 || data[0].is_empty() || data[1].len() < RUN_LENGTHS_HEADER_BYTES {
+            return Err(Error::InvalidInput {
+                source: "an RLE mini-block must have a values buffer and a run lengths buffer"
+                    .into(),
+                location: location!(),
+            });
+        }
+        let num_runs = u16::from_le_bytes([data[1][0], data[1][1]]) as usize;
+        Ok(Self {
+            values: BitUnpacker {
+                data: &data[0][1..],
+                bit_width: data[0][0],
+            },
+            run_lengths: BitUnpacker {
+                data: &data[1][RUN_LENGTHS_HEADER_BYTES..],
+                bit_width: data[1][2],
+            },
+            num_runs,
+        })
+    }
+
+    fn run_length(&self, run: usize) -> u64 {
+        self.run_lengths.get(run) + 1
+    }
+
+    /// Writes the values in `range` to `out`, each value takes `bytes_per_value` bytes
+    fn decode(&self, range: Range<u64>, bytes_per_value: usize, out: &mut Vec<u8>) {
+        let mut run_start = 0;
+        for run in 0..self.num_runs {
+            let run_end = run_start + self.run_length(run);
+            if run_end > range.start {
+                let num_values = run_end.min(range.end) - run_start.max(range.start);
+                let value = self.values.get(run).to_le_bytes();
+                for _ in 0..num_values {
+                    out.extend_from_slice(&value[..bytes_per_value]);
+                }
+            }
+            if run_end >= range.end {
+                break;
+            }
+            run_start = run_end;
+        }
+    }
+}
+
+/// Mini-block compressor that stores runs of identical values
+#[derive(Debug)]
+pub struct RleMiniBlockEncoder {
+    bits_per_value: u64,
+}
+
+impl RleMiniBlockEncoder {
+    pub fn new(bits_per_value: u64) -> Self {
+        assert!(supports_bits_per_value(bits_per_value));
+        Self { bits_per_value }
+    }
+
+    /// Splits the values into runs, a run never spans chunks
+    fn runs(values: &[u64]) -> Vec<(u64, u64)> {
+        let mut runs: Vec<(u64, u64)> = Vec::new();
+        for value in values {
+            match runs.last_mut() {
+                Some((last, len)) if last == value => *len += 1,
+                _ => runs.push((*value, 1)),
+            }
+        }
+        runs
+    }
+
+    /// Encodes a chunk, returning the two buffers
+    fn encode_chunk(values: &[u64]) -> (Vec<u8>, Vec<u8>) {
+        let runs = Self::runs(values);
+        let value_width = bit_width(runs.iter().map(|(value, _)| *value).max().unwrap_or(0));
+        let length_width = bit_width(runs.iter().map(|(_, len)| *len - 1).max().unwrap_or(0));
+
+        let mut value_buf = vec![value_width];
+        pack_bits(
+            runs.iter().map(|(value, _)| *value),
+            value_width,
+            &mut value_buf,
+        );
+
+        let mut length_buf = Vec::with_capacity(RUN_LENGTHS_HEADER_BYTES);
+        length_buf.extend_from_slice(&(runs.len() as u16).to_le_bytes());
+        length_buf.push(length_width);
+        pack_bits(
+            runs.iter().map(|(_, len)| *len - 1),
+            length_width,
+            &mut length_buf,
+        );
+
+        (value_buf, length_buf)
+    }
+
+    fn chunk_data(&self, data: FixedWidthDataBlock) -> MiniBlockCompressed {
+        let bytes_per_value = (self.bits_per_value / 8) as usize;
+        let values = data
+            .data
+            .chunks_exact(bytes_per_value)
+            .map(|bytes| {
+                let mut value = [0_u8; 8];
+                value[..bytes_per_value].copy_from_slice(bytes);
+                u64::from_le_bytes(value)
+            })
+            .collect::<Vec<_>>();
+
+        let mut value_data = Vec::new();
+        let mut length_data = Vec::new();
+        let mut chunks = Vec::new();
+        let mut offset = 0;
+        while offset < values.len() {
+            // Use the largest power-of-two chunk that fits, chunks with few runs can
+            // hold up to 4Ki values
+            let mut log_num_values = LOG_MAX_VALUES_PER_CHUNK;
+            let (num_values, (value_buf, length_buf)) = loop {
+                let num_values = (1_usize << log_num_values).min(values.len() - offset);
+                let encoded = Self::encode_chunk(&values[offset..offset + num_values]);
+                let size = (encoded.0.len() + encoded.1.len()) as u64;
+                // A chunk of 2 values always fits
+                if size <= MAX_CHUNK_BYTES || log_num_values == 1 {
+                    break (num_values, encoded);
+                }
+                log_num_values -= 1;
+            };
+            offset += num_values;
+            let is_last = offset == values.len();
+            chunks.push(MiniBlockChunk {
+                buffer_sizes: vec![value_buf.len() as u16, length_buf.len() as u16],
+                log_num_values: if is_last { 0 } else { log_num_values },
+            });
+            value_data.extend_from_slice(&value_buf);
+            length_data.extend_from_slice(&length_buf);
+        }
+
+        MiniBlockCompressed {
+            data: vec![
+                LanceBuffer::Owned(value_data),
+                LanceBuffer::Owned(length_data),
+            ],
+            chunks,
+            num_values: data.num_values,
+        }
+    }
+}
+
+impl MiniBlockCompressor for RleMiniBlockEncoder {
+    fn compress(&self, page: DataBlock) -> Result<(MiniBlockCompressed, pb::ArrayEncoding)> {
+        match page {
+            DataBlock::FixedWidth(data) if data.bits_per_value == self.bits_per_value => Ok((
+                self.chunk_data(data),
+                ProtobufUtils::rle(self.bits_per_value),
+            )),
+            _ => Err(Error::InvalidInput {
+                source: format!(
+                    "Cannot compress a data block of type {} with RleMiniBlockEncoder",
+                    page.name()
+                )
+                .into(),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Decompressor for [`RleMiniBlockEncoder`]
+#[derive(Debug)]
+pub struct RleMiniBlockDecompressor {
+    bits_per_value: u64,
+}
+
+impl RleMiniBlockDecompressor {
+    pub fn from_description(description: &pb::Rle) -> Self {
+        Self {
+            bits_per_value: description.uncompressed_bits_per_value,
+        }
+    }
+
+    fn decode_range(&self, data: &[LanceBuffer], range: Range<u64>) -> Result<DataBlock> {
+        let chunk = RleChunk::try_new(data)?;
+        let bytes_per_value = (self.bits_per_value / 8) as usize;
+        let num_values = range.end - range.start;
+        let mut out = Vec::with_capacity(num_values as usize * bytes_per_value);
+        chunk.decode(range, bytes_per_value, &mut out);
+        if out.len() != num_values as usize * bytes_per_value {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "an RLE mini-block had fewer values than the expected {}",
+                    num_values
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::Owned(out),
+            bits_per_value: self.bits_per_value,
+            num_values,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
+impl MiniBlockDecompressor for RleMiniBlockDecompressor {
+    fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        self.decode_range(&data, 0..num_values)
+    }
+
+    fn decompress_range(
+        &self,
+        data: &[LanceBuffer],
+        _num_values: u64,
+        range: Range<u64>,
+    ) -> Result<Option<DataBlock>> {
+        self.decode_range(data, range).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow_array::{Array, Int16Array, Int32Array, Int64Array, Int8Array, UInt64Array};
+    use arrow_schema::DataType;
+    use lance_core::datatypes::Field;
+
+    use super::*;
+    use crate::encoder::{CompressionStrategy, CoreArrayEncodingStrategy};
+    use crate::testing::{check_round_trip_encoding_of_data, TestCases};
+    use crate::version::LanceFileVersion;
+
+    fn round_trip(values: &[u64], bits_per_value: u64) {
+        let bytes_per_value = (bits_per_value / 8) as usize;
+        let data = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes()[..bytes_per_value].to_vec())
+            .collect::<Vec<_>>();
+        let block = FixedWidthDataBlock {
+            data: LanceBuffer::Owned(data.clone()),
+            bits_per_value,
+            num_values: values.len() as u64,
+            block_info: BlockInfo::new(),
+        };
+        let (compressed, encoding) = RleMiniBlockEncoder::new(bits_per_value)
+            .compress(DataBlock::FixedWidth(block))
+            .unwrap();
+        let pb::array_encoding::ArrayEncoding::Rle(description) = encoding.array_encoding.unwrap()
+        else {
+            panic!("expected RLE encoding");
+        };
+        let decompressor = RleMiniBlockDecompressor::from_description(&description);
+
+        // Walk the chunks, checking full and partial decoding of each
+        let mut value_offset = 0;
+        let mut length_offset = 0;
+        let mut values_seen = 0;
+        for chunk in &compressed.chunks {
+            assert!(chunk.buffer_sizes.iter().map(|s| *s as u64).sum::<u64>() <= MAX_CHUNK_BYTES);
+            let num_values = chunk.num_values(values_seen, compressed.num_values);
+            let buffers = vec![
+                compressed.data[0].slice_with_length(value_offset, chunk.buffer_sizes[0] as usize),
+                compressed.data[1].slice_with_length(length_offset, chunk.buffer_sizes[1] as usize),
+            ];
+            value_offset += chunk.buffer_sizes[0] as usize;
+            length_offset += chunk.buffer_sizes[1] as usize;
+
+            let start = values_seen as usize * bytes_per_value;
+            let expected = &data[start..start + num_values as usize * bytes_per_value];
+            let decoded = decompressor
+                .decompress(buffers.clone(), num_values)
+                .unwrap()
+                .as_fixed_width()
+                .unwrap();
+            assert_eq!(decoded.data.as_ref(), expected);
+
+            let range = num_values / 3..num_values / 2 + 1;
+            let decoded = decompressor
+                .decompress_range(&buffers, num_values, range.clone())
+                .unwrap()
+                .unwrap()
+                .as_fixed_width()
+                .unwrap();
+            assert_eq!(decoded.num_values, range.end - range.start);
+            assert_eq!(
+                decoded.data.as_ref(),
+                &expected
+                    [range.start as usize * bytes_per_value..range.end as usize * bytes_per_value]
+            );
+            values_seen += num_values;
+        }
+        assert_eq!(values_seen, values.len() as u64);
+    }
+
+    #[test]
+    fn test_rle_chunks() {
+        // Long runs fit in a few 4Ki chunks
+        let sorted = (0..10_000_u64).map(|i| i / 300).collect::<Vec<_>>();
+        round_trip(&sorted, 32);
+        // No runs at all, chunks shrink to stay under the size limit
+        let unique = (0..10_000_u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect::<Vec<_>>();
+        round_trip(&unique, 64);
+        // All zeros (zero bit width for the values)
+        round_trip(&[0; 5000], 8);
+        round_trip(&[u16::MAX as u64; 3], 16);
+        round_trip(&[7], 64);
+    }
+
+    #[test]
+    fn test_rle_selected_for_runs() {
+        let strategy = CoreArrayEncodingStrategy::default();
+        let field = Field::new_arrow("", DataType::Int32, false).unwrap();
+
+        let runs = Arc::new(Int32Array::from_iter_values((0..10_000).map(|i| i / 500)));
+        let block = DataBlock::from_array(runs as Arc<dyn Array>);
+        let compressor = strategy
+            .create_miniblock_compressor(&field, &block)
+            .unwrap();
+        assert!(format!("{:?}", compressor).contains("Rle"));
+
+        let no_runs = Arc::new(Int32Array::from_iter_values(0..10_000));
+        let block = DataBlock::from_array(no_runs as Arc<dyn Array>);
+        let compressor = strategy
+            .create_miniblock_compressor(&field, &block)
+            .unwrap();
+        assert!(!format!("{:?}", compressor).contains("Rle"));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_miniblock_rle() {
+        let arrays = vec![
+            Arc::new(Int8Array::from_iter_values(
+                (0..5000).map(|i| (i / 700) as i8),
+            )) as Arc<dyn Array>,
+            Arc::new(Int16Array::from_iter_values(
+                (0..5000).map(|i| (i / 100) as i16),
+            )),
+            Arc::new(Int32Array::from_iter(
+                (0..5000).map(|i| (i % 1000 != 0).then_some(i / 1000)),
+            )),
+            Arc::new(Int64Array::from_iter_values((0..5000).map(|i| -(i / 2000)))),
+            Arc::new(UInt64Array::from_iter_values((0..5000).map(|_| u64::MAX))),
+        ];
+        for array in arrays {
+            for test_cases in [
+                TestCases::default(),
+                TestCases::default().with_range(1000..1100),
+                TestCases::default().with_range(4090..4100),
+                TestCases::default().with_indices(vec![0, 1, 999, 2500, 4999]),
+            ] {
+                let test_cases = test_cases.with_file_version(LanceFileVersion::V2_1);
+                check_round_trip_encoding_of_data(vec![array.clone()], &test_cases, HashMap::new())
+                    .await;
+            }
+        }
+    }
+}
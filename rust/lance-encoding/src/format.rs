@@ -20,10 +20,11 @@ use pb::{
     full_zip_layout,
     nullable::{AllNull, NoNull, Nullability, SomeNull},
     page_layout::Layout,
-    AllNullLayout, ArrayEncoding, Binary, Bitpacked, BitpackedForNonNeg, Block, Dictionary,
-    FixedSizeBinary, FixedSizeList, Flat, Fsst, InlineBitpacking, MiniBlockLayout, Nullable,
-    OutOfLineBitpacking, PackedStruct, PackedStructFixedWidthMiniBlock, PageLayout, RepDefLayer,
-    Variable,
+    AllNullLayout, Alp, ArrayEncoding, Binary, Bitpacked, BitpackedForNonNeg, Block,
+    ByteStreamSplit, Delta, Dictionary, FixedSizeBinary, FixedSizeList, Flat, FrameOfReference,
+    Fsst, InlineBitpacking, MiniBlockLayout, Nullable, OutOfLineBitpacking, PackedStruct,
+    PackedStructFixedWidthMiniBlock, PageLayout, QuantizedVector, RepDefLayer, Rle, Variable,
+    VectorQuantization,
 };
 
 use crate::{
@@ -156,6 +157,64 @@ impl ProtobufUtils {
             })),
         }
     }
+    pub fn rle(uncompressed_bits_per_value: u64) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::Rle(Rle {
+                uncompressed_bits_per_value,
+            })),
+        }
+    }
+    pub fn frame_of_reference(uncompressed_bits_per_value: u64, signed: bool) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::FrameOfReference(FrameOfReference {
+                uncompressed_bits_per_value,
+                signed,
+            })),
+        }
+    }
+    pub fn delta(uncompressed_bits_per_value: u64, order: u32) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::Delta(Delta {
+                uncompressed_bits_per_value,
+                order,
+            })),
+        }
+    }
+    pub fn alp(bits_per_value: u64) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::Alp(Alp { bits_per_value })),
+        }
+    }
+    pub fn byte_stream_split(
+        bits_per_value: u64,
+        dimension: u64,
+        compression: &str,
+    ) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::ByteStreamSplit(ByteStreamSplit {
+                bits_per_value,
+                dimension,
+                compression: compression.to_string(),
+            })),
+        }
+    }
+    pub fn quantized_vector(
+        bits_per_item: u64,
+        dimension: u64,
+        quantization: VectorQuantization,
+        min_value: f64,
+        max_value: f64,
+    ) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::QuantizedVector(QuantizedVector {
+                bits_per_item,
+                dimension,
+                quantization: quantization as i32,
+                min_value,
+                max_value,
+            })),
+        }
+    }
     pub fn out_of_line_bitpacking(
         uncompressed_bits_per_value: u64,
         compressed_bits_per_value: u64,
@@ -294,6 +353,7 @@ impl ProtobufUtils {
         repetition_index_depth: u32,
         num_buffers: u64,
         dictionary_encoding: Option<(ArrayEncoding, u64)>,
+        column_dictionary: bool,
         def_meaning: &[DefinitionInterpretation],
         num_items: u64,
     ) -> PageLayout {
@@ -315,7 +375,9 @@ impl ProtobufUtils {
                     .map(|&def| Self::def_inter_to_repdef_layer(def))
                     .collect(),
                 num_items,
+                column_dictionary,
             })),
+            statistics: None,
         }
     }
 
@@ -341,6 +403,7 @@ impl ProtobufUtils {
                     .map(|&def| Self::def_inter_to_repdef_layer(def))
                     .collect(),
             })),
+            statistics: None,
         }
     }
 
@@ -392,10 +455,25 @@ impl ProtobufUtils {
                     .map(|&def| Self::def_inter_to_repdef_layer(def))
                     .collect(),
             })),
+            statistics: None,
         }
     }
 
     pub fn simple_all_null_layout() -> PageLayout {
         Self::all_null_layout(&[DefinitionInterpretation::NullableItem])
     }
+
+    pub fn column_dictionary(
+        dictionary: ArrayEncoding,
+        num_dictionary_items: u64,
+    ) -> pb::ColumnEncoding {
+        pb::ColumnEncoding {
+            column_encoding: Some(pb::column_encoding::ColumnEncoding::Dictionary(
+                pb::ColumnDictionary {
+                    dictionary: Some(dictionary),
+                    num_dictionary_items,
+                },
+            )),
+        }
+    }
 }
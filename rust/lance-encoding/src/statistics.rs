@@ -8,13 +8,28 @@ use std::{
 };
 
 use arrow::{array::AsArray, datatypes::UInt64Type};
-use arrow_array::{Array, ArrowPrimitiveType, UInt64Array};
+use arrow_arith::aggregate::{
+    max, max_binary, max_boolean, max_string, min, min_binary, min_boolean, min_string,
+};
+use arrow_array::{
+    downcast_primitive, make_array, Array, ArrayRef, ArrowNativeTypeOp, ArrowPrimitiveType,
+    BinaryArray, BooleanArray, LargeBinaryArray, LargeStringArray, OffsetSizeTrait, StringArray,
+    UInt64Array,
+};
+use arrow_buffer::{Buffer, ToByteSlice};
+use arrow_data::ArrayData;
+use arrow_schema::DataType;
 use hyperloglogplus::{HyperLogLog, HyperLogLogPlus};
+use lance_core::{Error, Result};
 use num_traits::PrimInt;
-
-use crate::data::{
-    AllNullDataBlock, DataBlock, DictionaryDataBlock, FixedSizeListBlock, FixedWidthDataBlock,
-    NullableDataBlock, OpaqueBlock, StructDataBlock, VariableWidthBlock,
+use snafu::location;
+
+use crate::{
+    data::{
+        AllNullDataBlock, DataBlock, DictionaryDataBlock, FixedSizeListBlock, FixedWidthDataBlock,
+        NullableDataBlock, OpaqueBlock, StructDataBlock, VariableWidthBlock,
+    },
+    format::pb,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -25,6 +40,8 @@ pub enum Stat {
     FixedSize,
     NullCount,
     MaxLength,
+    /// The number of runs of consecutive identical values
+    RunCount,
 }
 
 impl fmt::Debug for Stat {
@@ -36,6 +53,7 @@ impl fmt::Debug for Stat {
             Self::FixedSize => write!(f, "FixedSize"),
             Self::NullCount => write!(f, "NullCount"),
             Self::MaxLength => write!(f, "MaxLength"),
+            Self::RunCount => write!(f, "RunCount"),
         }
     }
 }
@@ -105,6 +123,8 @@ impl ComputeStat for FixedWidthDataBlock {
             None
         };
 
+        let run_count_array = self.run_count();
+
         let mut info = self.block_info.0.write().unwrap();
         info.insert(Stat::DataSize, data_size_array);
         info.insert(Stat::BitWidth, max_bit_widths);
@@ -112,6 +132,9 @@ impl ComputeStat for FixedWidthDataBlock {
         if let Some(cardinality_array) = cardidinality_array {
             info.insert(Stat::Cardinality, cardinality_array);
         }
+        if let Some(run_count_array) = run_count_array {
+            info.insert(Stat::RunCount, run_count_array);
+        }
     }
 }
 
@@ -359,6 +382,26 @@ impl FixedWidthDataBlock {
         }
     }
 
+    /// The number of runs of identical values, only computed for byte-aligned
+    /// values up to 64 bits wide
+    fn run_count(&mut self) -> Option<Arc<dyn Array>> {
+        fn count_runs<T: PartialEq>(slice: &[T]) -> u64 {
+            if slice.is_empty() {
+                return 0;
+            }
+            1 + slice.windows(2).filter(|pair| pair[0] != pair[1]).count() as u64
+        }
+
+        let run_count = match self.bits_per_value {
+            8 => count_runs(self.data.borrow_to_typed_slice::<u8>().as_ref()),
+            16 => count_runs(self.data.borrow_to_typed_slice::<u16>().as_ref()),
+            32 => count_runs(self.data.borrow_to_typed_slice::<u32>().as_ref()),
+            64 => count_runs(self.data.borrow_to_typed_slice::<u64>().as_ref()),
+            _ => return None,
+        };
+        Some(Arc::new(UInt64Array::from(vec![run_count])))
+    }
+
     fn cardinality(&mut self) -> Arc<dyn Array> {
         match self.bits_per_value {
             128 => {
@@ -424,20 +467,173 @@ impl ComputeStat for StructDataBlock {
     }
 }
 
+/// The largest min/max value (in bytes) that will be recorded in page statistics
+///
+/// Minimum values longer than this are truncated (a prefix is still a valid lower bound).  If
+/// the maximum value is longer than this then no bounds are recorded for the page.
+pub const MAX_PAGE_BOUND_BYTES: usize = 64;
+
+/// Computes the value statistics (null count and bounds) of a page made up of `arrays`
+///
+/// Bounds are only computed for primitive, boolean, string and binary arrays.  See
+/// [`decode_page_bound`] for the inverse operation.
+pub fn compute_page_statistics(arrays: &[ArrayRef]) -> pb::PageStatistics {
+    let null_count = arrays
+        .iter()
+        .map(|arr| arr.logical_null_count() as u64)
+        .sum();
+    let bounds = arrays
+        .first()
+        .and_then(|first| page_bounds(first.data_type(), arrays))
+        .map(|(min, max)| pb::page_statistics::Bounds { min, max });
+    pb::PageStatistics { null_count, bounds }
+}
+
+macro_rules! primitive_bounds_helper {
+    ($t:ty, $arrays:expr) => {
+        primitive_bounds::<$t>($arrays)
+    };
+}
+
+fn page_bounds(data_type: &DataType, arrays: &[ArrayRef]) -> Option<(Vec<u8>, Vec<u8>)> {
+    // Intervals do not have a meaningful total order
+    if matches!(data_type, DataType::Interval(_)) {
+        return None;
+    }
+    downcast_primitive! {
+        data_type => (primitive_bounds_helper, arrays),
+        DataType::Boolean => boolean_bounds(arrays),
+        DataType::Utf8 => string_bounds::<i32>(arrays),
+        DataType::LargeUtf8 => string_bounds::<i64>(arrays),
+        DataType::Binary => binary_bounds::<i32>(arrays),
+        DataType::LargeBinary => binary_bounds::<i64>(arrays),
+        _ => None,
+    }
+}
+
+fn primitive_bounds<T: ArrowPrimitiveType>(arrays: &[ArrayRef]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut bounds: Option<(T::Native, T::Native)> = None;
+    for arr in arrays {
+        let arr = arr.as_primitive::<T>();
+        let (Some(arr_min), Some(arr_max)) = (min(arr), max(arr)) else {
+            continue;
+        };
+        bounds = Some(match bounds {
+            Some((cur_min, cur_max)) => (
+                if arr_min.is_lt(cur_min) {
+                    arr_min
+                } else {
+                    cur_min
+                },
+                if arr_max.is_gt(cur_max) {
+                    arr_max
+                } else {
+                    cur_max
+                },
+            ),
+            None => (arr_min, arr_max),
+        });
+    }
+    bounds.map(|(min, max)| (min.to_byte_slice().to_vec(), max.to_byte_slice().to_vec()))
+}
+
+fn boolean_bounds(arrays: &[ArrayRef]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mins = arrays
+        .iter()
+        .filter_map(|arr| min_boolean(arr.as_boolean()));
+    let maxs = arrays
+        .iter()
+        .filter_map(|arr| max_boolean(arr.as_boolean()));
+    Some((vec![mins.min()? as u8], vec![maxs.max()? as u8]))
+}
+
+fn string_bounds<O: OffsetSizeTrait>(arrays: &[ArrayRef]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mins = arrays
+        .iter()
+        .filter_map(|arr| min_string(arr.as_string::<O>()));
+    let maxs = arrays
+        .iter()
+        .filter_map(|arr| max_string(arr.as_string::<O>()));
+    let (min, max) = (mins.min()?, maxs.max()?);
+    if max.len() > MAX_PAGE_BOUND_BYTES {
+        return None;
+    }
+    let mut min_len = min.len().min(MAX_PAGE_BOUND_BYTES);
+    while !min.is_char_boundary(min_len) {
+        min_len -= 1;
+    }
+    Some((min[..min_len].as_bytes().to_vec(), max.as_bytes().to_vec()))
+}
+
+fn binary_bounds<O: OffsetSizeTrait>(arrays: &[ArrayRef]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mins = arrays
+        .iter()
+        .filter_map(|arr| min_binary(arr.as_binary::<O>()));
+    let maxs = arrays
+        .iter()
+        .filter_map(|arr| max_binary(arr.as_binary::<O>()));
+    let (min, max) = (mins.min()?, maxs.max()?);
+    if max.len() > MAX_PAGE_BOUND_BYTES {
+        return None;
+    }
+    let min_len = min.len().min(MAX_PAGE_BOUND_BYTES);
+    Some((min[..min_len].to_vec(), max.to_vec()))
+}
+
+/// Decodes a min/max value recorded by [`compute_page_statistics`] into a single-element array
+pub fn decode_page_bound(bytes: &[u8], data_type: &DataType) -> Result<ArrayRef> {
+    let invalid_bound = || {
+        Error::invalid_input(
+            format!("invalid page bound for data type {}", data_type),
+            location!(),
+        )
+    };
+    let array: ArrayRef = match data_type {
+        DataType::Boolean => match bytes {
+            [value] => Arc::new(BooleanArray::from(vec![*value != 0])),
+            _ => return Err(invalid_bound()),
+        },
+        DataType::Utf8 | DataType::LargeUtf8 => {
+            let value = std::str::from_utf8(bytes).map_err(|_| invalid_bound())?;
+            if matches!(data_type, DataType::Utf8) {
+                Arc::new(StringArray::from(vec![value]))
+            } else {
+                Arc::new(LargeStringArray::from(vec![value]))
+            }
+        }
+        DataType::Binary => Arc::new(BinaryArray::from(vec![bytes])),
+        DataType::LargeBinary => Arc::new(LargeBinaryArray::from(vec![bytes])),
+        _ if data_type.is_primitive() => {
+            if data_type.primitive_width() != Some(bytes.len()) {
+                return Err(invalid_bound());
+            }
+            let data = ArrayData::builder(data_type.clone())
+                .len(1)
+                .add_buffer(Buffer::from(bytes))
+                .build()?;
+            make_array(data)
+        }
+        _ => return Err(invalid_bound()),
+    };
+    Ok(array)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use arrow_array::{
-        ArrayRef, Int16Array, Int32Array, Int64Array, Int8Array, LargeStringArray, StringArray,
-        UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+        ArrayRef, BooleanArray, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
+        LargeStringArray, StringArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
     };
     use arrow_schema::{DataType, Field};
     use lance_arrow::DataTypeExt;
     use lance_datagen::{array, ArrayGeneratorExt, RowCount, DEFAULT_SEED};
     use rand::SeedableRng;
 
-    use crate::statistics::{GetStat, Stat};
+    use crate::statistics::{
+        compute_page_statistics, decode_page_bound, GetStat, Stat, MAX_PAGE_BOUND_BYTES,
+    };
 
     use super::DataBlock;
 
@@ -998,4 +1194,85 @@ mod tests {
 
         assert_eq!(actual_max_length, expected_max_length);
     }
+
+    #[test]
+    fn test_run_count_stat() {
+        let block = DataBlock::from_array(Int32Array::from(vec![1, 1, 2, 2, 2, 1, 3]));
+        assert_eq!(block.expect_single_stat::<UInt64Type>(Stat::RunCount), 4);
+
+        let block = DataBlock::from_array(Int8Array::from(vec![5; 100]));
+        assert_eq!(block.expect_single_stat::<UInt64Type>(Stat::RunCount), 1);
+
+        let block = DataBlock::from_array(UInt64Array::from_iter_values(0..100));
+        assert_eq!(block.expect_single_stat::<UInt64Type>(Stat::RunCount), 100);
+
+        let block = DataBlock::from_array(StringArray::from(vec!["a", "a"]));
+        assert!(block.get_stat(Stat::RunCount).is_none());
+    }
+
+    fn decoded_bounds(arrays: &[ArrayRef]) -> Option<(ArrayRef, ArrayRef)> {
+        let data_type = arrays[0].data_type();
+        compute_page_statistics(arrays).bounds.map(|bounds| {
+            (
+                decode_page_bound(&bounds.min, data_type).unwrap(),
+                decode_page_bound(&bounds.max, data_type).unwrap(),
+            )
+        })
+    }
+
+    #[test]
+    fn test_page_statistics() {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![Some(5), None, Some(-3)])),
+            Arc::new(Int32Array::from(vec![None, None])),
+            Arc::new(Int32Array::from(vec![Some(17), Some(2)])),
+        ];
+        assert_eq!(compute_page_statistics(&arrays).null_count, 3);
+        let (min, max) = decoded_bounds(&arrays).unwrap();
+        assert_eq!(min.as_primitive::<Int32Type>().value(0), -3);
+        assert_eq!(max.as_primitive::<Int32Type>().value(0), 17);
+
+        let arrays: Vec<ArrayRef> = vec![Arc::new(Float64Array::from(vec![0.5, -1.5, 2.25]))];
+        let (min, max) = decoded_bounds(&arrays).unwrap();
+        assert_eq!(min.as_ref(), &Float64Array::from(vec![-1.5]) as &dyn Array);
+        assert_eq!(max.as_ref(), &Float64Array::from(vec![2.25]) as &dyn Array);
+
+        let arrays: Vec<ArrayRef> = vec![Arc::new(BooleanArray::from(vec![Some(true), None]))];
+        let (min, max) = decoded_bounds(&arrays).unwrap();
+        assert!(min.as_boolean().value(0));
+        assert!(max.as_boolean().value(0));
+
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["banana", "cherry"])),
+            Arc::new(StringArray::from(vec!["apple"])),
+        ];
+        let (min, max) = decoded_bounds(&arrays).unwrap();
+        assert_eq!(min.as_string::<i32>().value(0), "apple");
+        assert_eq!(max.as_string::<i32>().value(0), "cherry");
+
+        // All null pages have no bounds
+        let arrays: Vec<ArrayRef> = vec![Arc::new(Int64Array::from(vec![None, None]))];
+        let stats = compute_page_statistics(&arrays);
+        assert_eq!(stats.null_count, 2);
+        assert!(stats.bounds.is_none());
+    }
+
+    #[test]
+    fn test_page_statistics_long_values() {
+        // Long minimums are truncated on a character boundary
+        let long_min = format!("a{}", "é".repeat(MAX_PAGE_BOUND_BYTES));
+        let strings = StringArray::from(vec![long_min.as_str(), "z"]);
+        let arrays: Vec<ArrayRef> = vec![Arc::new(strings)];
+        let (min, max) = decoded_bounds(&arrays).unwrap();
+        assert_eq!(
+            min.as_string::<i32>().value(0),
+            &long_min[..MAX_PAGE_BOUND_BYTES - 1]
+        );
+        assert_eq!(max.as_string::<i32>().value(0), "z");
+
+        // Long maximums can't be truncated and so no bounds are recorded
+        let long_max = "z".repeat(MAX_PAGE_BOUND_BYTES + 1);
+        let arrays: Vec<ArrayRef> = vec![Arc::new(StringArray::from(vec!["a", long_max.as_str()]))];
+        assert!(compute_page_statistics(&arrays).bounds.is_none());
+    }
 }
@@ -23,12 +23,13 @@ use crate::{
     buffer::LanceBuffer,
     decoder::{
         create_decode_stream, ColumnInfo, DecodeBatchScheduler, DecoderMessage, DecoderPlugins,
-        FilterExpression, PageInfo,
+        FilterExpression, PageEncoding, PageInfo,
     },
     encoder::{
         default_encoding_strategy, ColumnIndexSequence, EncodedColumn, EncodedPage,
         EncodingOptions, FieldEncoder, OutOfLineBuffers, MIN_PAGE_BUFFER_ALIGNMENT,
     },
+    format::pb,
     repdef::RepDefBuilder,
     version::LanceFileVersion,
     EncodingsIo,
@@ -318,6 +319,20 @@ fn supports_nulls(data_type: &DataType) -> bool {
 }
 
 type EncodingVerificationFn = dyn Fn(&[EncodedColumn]);
+type PageEncodingVerificationFn = dyn Fn(&PageEncoding);
+
+/// The compression used for the values of a mini-block page, `None` for other pages
+pub fn miniblock_value_compression(encoding: &PageEncoding) -> Option<&pb::ArrayEncoding> {
+    match encoding {
+        PageEncoding::Structural(layout) => match layout.layout.as_ref()? {
+            pb::page_layout::Layout::MiniBlockLayout(mini_block) => {
+                mini_block.value_compression.as_ref()
+            }
+            _ => None,
+        },
+        PageEncoding::Legacy(_) => None,
+    }
+}
 
 // The default will just test the full read
 #[derive(Clone)]
@@ -330,6 +345,7 @@ pub struct TestCases {
     page_sizes: Vec<u64>,
     file_version: LanceFileVersion,
     verify_encoding: Option<Arc<EncodingVerificationFn>>,
+    verify_page_encoding: Option<Arc<PageEncodingVerificationFn>>,
 }
 
 impl Default for TestCases {
@@ -343,6 +359,7 @@ impl Default for TestCases {
             page_sizes: vec![4096, 1024 * 1024],
             file_version: LanceFileVersion::default(),
             verify_encoding: None,
+            verify_page_encoding: None,
         }
     }
 }
@@ -397,6 +414,23 @@ impl TestCases {
             verify_encoding(encoding);
         }
     }
+
+    /// Checks the encoding of every page that is written
+    pub fn with_verify_page_encoding(
+        mut self,
+        verify_page_encoding: Arc<PageEncodingVerificationFn>,
+    ) -> Self {
+        self.verify_page_encoding = Some(verify_page_encoding);
+        self
+    }
+
+    fn verify_page_encodings(&self, column_infos: &[Arc<ColumnInfo>]) {
+        if let Some(verify_page_encoding) = self.verify_page_encoding.as_ref() {
+            for page_info in column_infos.iter().flat_map(|info| info.page_infos.iter()) {
+                verify_page_encoding(&page_info.encoding);
+            }
+        }
+    }
 }
 
 /// Given specific data and test cases we check round trip encoding and decoding
@@ -564,6 +598,8 @@ async fn check_round_trip_encoding_inner(
         column_infos.push(Arc::new(column_info));
     }
 
+    test_cases.verify_page_encodings(&column_infos);
+
     let encoded_data = writer.encoded_data.freeze();
 
     let scheduler = Arc::new(SimulatedScheduler::new(encoded_data)) as Arc<dyn EncodingsIo>;
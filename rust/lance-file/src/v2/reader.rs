@@ -10,9 +10,10 @@ use std::{
 };
 
 use arrow_array::RecordBatchReader;
-use arrow_schema::Schema as ArrowSchema;
+use arrow_schema::{DataType, Schema as ArrowSchema};
 use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
 use bytes::{Bytes, BytesMut};
+use datafusion_common::ScalarValue;
 use deepsize::{Context, DeepSizeOf};
 use futures::{stream::BoxStream, Stream, StreamExt};
 use lance_encoding::{
@@ -22,6 +23,7 @@ use lance_encoding::{
         SchedulerDecoderConfig,
     },
     encoder::EncodedBatch,
+    statistics::decode_page_bound,
     version::LanceFileVersion,
     EncodingsIo,
 };
@@ -78,6 +80,20 @@ pub struct ColumnStatistics {
     pub size_bytes: u64,
 }
 
+/// Value statistics describing a single page of a column
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageStatistics {
+    /// The rows of the file covered by the page
+    pub rows: Range<u64>,
+    /// The number of null values in the page
+    pub null_count: u64,
+    /// The minimum and maximum non-null value in the page
+    ///
+    /// This will be `None` if all values are null or if the writer did not record bounds
+    /// (e.g. because the values were too large)
+    pub bounds: Option<(ScalarValue, ScalarValue)>,
+}
+
 // TODO: Caching
 #[derive(Debug)]
 pub struct CachedFileMetadata {
@@ -389,6 +405,61 @@ impl FileReader {
         }
     }
 
+    /// Returns the value statistics of each page in a column
+    ///
+    /// `data_type` must be the type of the column's field.  Page statistics are only recorded
+    /// by 2.1+ writers and only for columns that are not nested.  If any page in the column
+    /// is missing statistics then this returns `None`.
+    pub fn page_statistics(
+        &self,
+        column_index: u32,
+        data_type: &DataType,
+    ) -> Result<Option<Vec<PageStatistics>>> {
+        let column_info = self
+            .metadata
+            .column_infos
+            .get(column_index as usize)
+            .ok_or_else(|| {
+                Error::invalid_input(
+                    format!(
+                        "request for page statistics of column {} but there were only {} columns in the file",
+                        column_index,
+                        self.metadata.column_infos.len()
+                    ),
+                    location!(),
+                )
+            })?;
+        let mut page_stats = Vec::with_capacity(column_info.page_infos.len());
+        let mut row_offset = 0;
+        for page_info in column_info.page_infos.iter() {
+            let PageEncoding::Structural(layout) = &page_info.encoding else {
+                return Ok(None);
+            };
+            let Some(statistics) = &layout.statistics else {
+                return Ok(None);
+            };
+            let bounds = statistics
+                .bounds
+                .as_ref()
+                .map(|bounds| -> Result<_> {
+                    let min = decode_page_bound(&bounds.min, data_type)?;
+                    let max = decode_page_bound(&bounds.max, data_type)?;
+                    Ok((
+                        ScalarValue::try_from_array(&min, 0)?,
+                        ScalarValue::try_from_array(&max, 0)?,
+                    ))
+                })
+                .transpose()?;
+            page_stats.push(PageStatistics {
+                rows: row_offset..row_offset + page_info.num_rows,
+                null_count: statistics.null_count,
+                bounds,
+            });
+            row_offset += page_info.num_rows;
+        }
+        Ok(Some(page_stats))
+    }
+
     pub async fn read_global_buffer(&self, index: u32) -> Result<Bytes> {
         let buffer_desc = self.metadata.file_buffers.get(index as usize).ok_or_else(||Error::invalid_input(format!("request for global buffer at index {} but there were only {} global buffers in the file", index, self.metadata.file_buffers.len()), location!()))?;
         self.scheduler
@@ -1529,6 +1600,7 @@ pub mod tests {
     };
     use arrow_schema::{DataType, Field, Fields, Schema as ArrowSchema};
     use bytes::Bytes;
+    use datafusion_common::ScalarValue;
     use futures::{prelude::stream::TryStreamExt, StreamExt};
     use lance_arrow::RecordBatchExt;
     use lance_core::{datatypes::Schema, ArrowResult};
@@ -1981,6 +2053,68 @@ pub mod tests {
         assert_eq!(batches[0].num_rows(), total_rows);
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_page_statistics(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+    ) {
+        let fs = FsFixture::default();
+        let reader = gen()
+            .col("x", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(1000), BatchCount::from(10));
+        write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(version),
+                data_cache_bytes: Some(4096),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let page_stats = file_reader.page_statistics(0, &DataType::Int32).unwrap();
+        if version < LanceFileVersion::V2_1 {
+            assert!(page_stats.is_none());
+            return;
+        }
+        let page_stats = page_stats.unwrap();
+        assert!(page_stats.len() > 1);
+
+        // The values are the row offsets so each page's bounds should match its rows
+        let mut next_row = 0;
+        for page in page_stats {
+            assert_eq!(page.rows.start, next_row);
+            assert_eq!(page.null_count, 0);
+            assert_eq!(
+                page.bounds,
+                Some((
+                    ScalarValue::Int32(Some(page.rows.start as i32)),
+                    ScalarValue::Int32(Some(page.rows.end as i32 - 1))
+                ))
+            );
+            next_row = page.rows.end;
+        }
+        assert_eq!(next_row, 10000);
+
+        assert!(file_reader.page_statistics(1, &DataType::Int32).is_err());
+    }
+
     #[tokio::test]
     async fn test_blocking_take() {
         let fs = FsFixture::default();
@@ -11,12 +11,13 @@ use arrow::buffer::{OffsetBuffer, ScalarBuffer};
 use arrow_array::{ListArray, RecordBatch};
 use arrow_schema::{Field, Schema};
 use async_trait::async_trait;
+use datafusion::functions::regex::regexp_like;
 use datafusion::functions::string::contains::ContainsFunc;
 use datafusion::functions_array::array_has;
 use datafusion::physical_plan::SendableRecordBatchStream;
 use datafusion_common::{scalar::ScalarValue, Column};
 
-use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::expr::{Like, ScalarFunction};
 use datafusion_expr::Expr;
 use deepsize::DeepSizeOf;
 use inverted::query::{fill_fts_query_column, FtsQuery, FtsQueryNode, FtsSearchParams, MatchQuery};
@@ -28,16 +29,23 @@ use crate::metrics::MetricsCollector;
 use crate::{Index, IndexParams, IndexType};
 
 pub mod bitmap;
+pub mod bloom_filter;
 pub mod btree;
+pub mod composite;
 pub mod expression;
 pub mod flat;
 pub mod inverted;
 pub mod label_list;
 pub mod lance_format;
 pub mod ngram;
+mod row_blocks;
+pub mod zone_map;
 
 use crate::frag_reuse::FragReuseIndex;
+pub use bloom_filter::BloomFilterIndexParams;
 pub use inverted::tokenizer::InvertedIndexParams;
+pub use ngram::NGramIndexParams;
+pub use zone_map::ZoneMapIndexParams;
 
 pub const LANCE_SCALAR_INDEX: &str = "__lance_scalar_index";
 
@@ -48,6 +56,8 @@ pub enum ScalarIndexType {
     LabelList,
     NGram,
     Inverted,
+    BloomFilter,
+    ZoneMap,
 }
 
 impl TryFrom<IndexType> for ScalarIndexType {
@@ -60,6 +70,8 @@ impl TryFrom<IndexType> for ScalarIndexType {
             IndexType::LabelList => Ok(Self::LabelList),
             IndexType::NGram => Ok(Self::NGram),
             IndexType::Inverted => Ok(Self::Inverted),
+            IndexType::BloomFilter => Ok(Self::BloomFilter),
+            IndexType::ZoneMap => Ok(Self::ZoneMap),
             _ => Err(Error::InvalidInput {
                 source: format!("Index type {:?} is not a scalar index", value).into(),
                 location: location!(),
@@ -76,6 +88,8 @@ impl From<ScalarIndexType> for IndexType {
             ScalarIndexType::LabelList => Self::LabelList,
             ScalarIndexType::NGram => Self::NGram,
             ScalarIndexType::Inverted => Self::Inverted,
+            ScalarIndexType::BloomFilter => Self::BloomFilter,
+            ScalarIndexType::ZoneMap => Self::ZoneMap,
         }
     }
 }
@@ -106,6 +120,8 @@ impl IndexParams for ScalarIndexParams {
             Some(ScalarIndexType::LabelList) => IndexType::LabelList,
             Some(ScalarIndexType::Inverted) => IndexType::Inverted,
             Some(ScalarIndexType::NGram) => IndexType::NGram,
+            Some(ScalarIndexType::BloomFilter) => IndexType::BloomFilter,
+            Some(ScalarIndexType::ZoneMap) => IndexType::ZoneMap,
         }
     }
 
@@ -512,9 +528,14 @@ impl AnyQuery for LabelListQuery {
 pub enum TextQuery {
     /// Retrieve all row ids where the text contains the given string
     StringContains(String),
-    // TODO: In the future we should be able to do string-insensitive contains
-    // as well as partial matches (e.g. LIKE 'foo%') and potentially even
-    // some regular expressions
+    /// Retrieve all row ids where the text matches the given LIKE (or ILIKE) pattern
+    Like {
+        pattern: String,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+    },
+    /// Retrieve all row ids where the text matches the given regular expression
+    Regex(String),
 }
 
 impl AnyQuery for TextQuery {
@@ -535,6 +556,24 @@ impl AnyQuery for TextQuery {
                     Expr::Literal(ScalarValue::Utf8(Some(substr.clone()))),
                 ],
             }),
+            Self::Like {
+                pattern,
+                escape_char,
+                case_insensitive,
+            } => Expr::Like(Like::new(
+                false,
+                Box::new(Expr::Column(Column::new_unqualified(col))),
+                Box::new(Expr::Literal(ScalarValue::Utf8(Some(pattern.clone())))),
+                *escape_char,
+                *case_insensitive,
+            )),
+            Self::Regex(pattern) => Expr::ScalarFunction(ScalarFunction {
+                func: regexp_like(),
+                args: vec![
+                    Expr::Column(Column::new_unqualified(col)),
+                    Expr::Literal(ScalarValue::Utf8(Some(pattern.clone()))),
+                ],
+            }),
         }
     }
 
@@ -550,6 +589,65 @@ impl AnyQuery for TextQuery {
     }
 }
 
+/// A query that a composite (multi-column) btree index can satisfy
+///
+/// The leading columns of the index must equal `prefix` and, if there is a range, the
+/// next column must be in the range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeQuery {
+    /// The columns of the index, in index order
+    pub columns: Vec<String>,
+    /// The values of the leading columns
+    pub prefix: Vec<ScalarValue>,
+    /// The range of the column following the prefix
+    pub range: Option<(Bound<ScalarValue>, Bound<ScalarValue>)>,
+}
+
+impl CompositeQuery {
+    /// Convert the query into a range of the keys stored in the index
+    pub fn to_key_range(&self) -> Result<SargableQuery> {
+        composite::composite_key_range(&self.prefix, self.range.as_ref())
+    }
+}
+
+impl AnyQuery for CompositeQuery {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // The query spans several columns and so the column the index is registered under
+    // is ignored
+    fn format(&self, col: &str) -> String {
+        format!("{}", self.to_expr(col.to_string()))
+    }
+
+    fn to_expr(&self, _col: String) -> Expr {
+        let mut conjuncts = self
+            .columns
+            .iter()
+            .zip(self.prefix.iter())
+            .map(|(col, value)| {
+                Expr::Column(Column::new_unqualified(col)).eq(Expr::Literal(value.clone()))
+            })
+            .collect::<Vec<_>>();
+        if let Some((lower, upper)) = &self.range {
+            let col = self.columns[self.prefix.len()].clone();
+            conjuncts.push(SargableQuery::Range(lower.clone(), upper.clone()).to_expr(col));
+        }
+        conjuncts
+            .into_iter()
+            .reduce(Expr::and)
+            .unwrap_or(Expr::Literal(ScalarValue::Boolean(Some(true))))
+    }
+
+    fn dyn_eq(&self, other: &dyn AnyQuery) -> bool {
+        match other.as_any().downcast_ref::<Self>() {
+            Some(o) => self == o,
+            None => false,
+        }
+    }
+}
+
 /// The result of a search operation against a scalar index
 #[derive(Debug, PartialEq)]
 pub enum SearchResult {
@@ -619,4 +717,12 @@ pub trait ScalarIndex: Send + Sync + std::fmt::Debug + Index + DeepSizeOf {
         new_data: SendableRecordBatchStream,
         dest_store: &dyn IndexStore,
     ) -> Result<()>;
+
+    /// The smallest and largest non-null values in the index
+    ///
+    /// Returns None if the index is empty or can't tell without searching all of its data.
+    /// The values of rows that have since been deleted may still be taken into account.
+    async fn min_max(&self) -> Result<Option<(ScalarValue, ScalarValue)>> {
+        Ok(None)
+    }
 }
@@ -341,6 +341,19 @@ impl ScalarIndex for BitmapIndex {
 
         do_train_bitmap_index(new_data, state, dest_store).await
     }
+
+    async fn min_max(&self) -> Result<Option<(ScalarValue, ScalarValue)>> {
+        let mut values = self
+            .index_map
+            .iter()
+            .filter(|(key, bitmap)| !key.0.is_null() && !bitmap.is_empty())
+            .map(|(key, _)| &key.0);
+        let Some(min) = values.next() else {
+            return Ok(None);
+        };
+        let max = values.next_back().unwrap_or(min);
+        Ok(Some((min.clone(), max.clone())))
+    }
 }
 
 fn get_batch_from_arrays(
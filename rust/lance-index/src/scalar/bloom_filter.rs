@@ -0,0 +1,728 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Bloom filter index
+//!
+//! The rows are split into blocks of consecutive row ids (see `row_blocks`) and a
+//! bloom filter of the values is kept for each block.  An equality search returns the
+//! rows of every block whose filter may contain the value.
+//!
+//! This is much smaller than a btree or bitmap index for high-cardinality columns
+//! (e.g. ids or hashes) that are only ever searched for exact values.
+
+use std::{any::Any, collections::HashMap, f64::consts::LN_2, sync::Arc};
+
+use arrow_array::{
+    cast::AsArray,
+    new_empty_array,
+    types::{Float16Type, Float32Type, Float64Type},
+    Array, ArrayRef, ArrowPrimitiveType, BinaryArray,
+};
+use arrow_schema::{DataType, Field};
+use async_trait::async_trait;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion_common::ScalarValue;
+use deepsize::DeepSizeOf;
+use half::f16;
+use lance_core::{datatypes::LogicalType, error::LanceOptionExt, Error, Result};
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+use snafu::location;
+use tracing::instrument;
+
+use super::{
+    btree::TrainingSource,
+    row_blocks::{
+        block_row_ids, included_frags, read_row_blocks, remap_row_blocks, write_row_blocks,
+        RowBlock, RowBlockBuilder, SummaryBuilder,
+    },
+    AnyQuery, IndexStore, SargableQuery, ScalarIndex, SearchResult,
+};
+use crate::frag_reuse::FragReuseIndex;
+use crate::{metrics::MetricsCollector, Index, IndexParams, IndexType};
+
+pub const BLOOM_FILTER_FILENAME: &str = "bloom_filter.lance";
+
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.01;
+pub const DEFAULT_ROWS_PER_BLOCK: u64 = 8192;
+
+const PARAMS_META_KEY: &str = "params";
+const VALUE_TYPE_META_KEY: &str = "value_type";
+
+const FILTER_COL: &str = "filter";
+
+// A block of 8Ki rows takes ~10KiB at a 1% false positive rate
+const BLOCKS_PER_BATCH: usize = 1024;
+
+/// Parameters of a bloom filter index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BloomFilterIndexParams {
+    /// The probability that a block is searched for a value it does not contain
+    ///
+    /// Lower rates need more bits per value, 1% takes ~10 bits per value.
+    pub false_positive_rate: f64,
+
+    /// The maximum number of rows covered by a single bloom filter
+    ///
+    /// Smaller blocks return fewer rows to recheck but there are more filters to search.
+    pub rows_per_block: u64,
+}
+
+impl Default for BloomFilterIndexParams {
+    fn default() -> Self {
+        Self {
+            false_positive_rate: DEFAULT_FALSE_POSITIVE_RATE,
+            rows_per_block: DEFAULT_ROWS_PER_BLOCK,
+        }
+    }
+}
+
+impl BloomFilterIndexParams {
+    pub fn with_false_positive_rate(mut self, false_positive_rate: f64) -> Self {
+        self.false_positive_rate = false_positive_rate;
+        self
+    }
+
+    pub fn with_rows_per_block(mut self, rows_per_block: u64) -> Self {
+        self.rows_per_block = rows_per_block;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(self.false_positive_rate > 0.0 && self.false_positive_rate < 1.0) {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "The false positive rate of a bloom filter index must be between 0 and 1, got {}",
+                    self.false_positive_rate
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        if self.rows_per_block == 0 {
+            return Err(Error::InvalidInput {
+                source: "The rows per block of a bloom filter index must be greater than 0".into(),
+                location: location!(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The number of bits set per value, the optimal count is -log2(false positive rate)
+    fn num_hashes(&self) -> u32 {
+        (-self.false_positive_rate.log2()).ceil().max(1.0) as u32
+    }
+}
+
+impl IndexParams for BloomFilterIndexParams {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn index_type(&self) -> IndexType {
+        IndexType::BloomFilter
+    }
+
+    fn index_name(&self) -> &str {
+        "BLOOM_FILTER"
+    }
+}
+
+/// Hashes the bytes of a value
+///
+/// The hashes are persisted in the index so this must never change between versions
+/// or platforms.  This is FNV-1a followed by the murmur3 finalizer to spread the bits.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+/// Hashes each value of an array, null values have no hash
+fn hash_values(values: &dyn Array) -> Result<Vec<Option<u64>>> {
+    fn hash_all<'a>(values: impl Iterator<Item = Option<&'a [u8]>>) -> Vec<Option<u64>> {
+        values.map(|value| value.map(hash_bytes)).collect()
+    }
+
+    // Floats are hashed after mapping -0.0 to 0.0 and all NaNs to a single NaN so
+    // that values that compare equal have the same hash
+    fn hash_floats<T: ArrowPrimitiveType, B: AsRef<[u8]>>(
+        values: &dyn Array,
+        to_bytes: impl Fn(T::Native) -> B,
+    ) -> Vec<Option<u64>> {
+        values
+            .as_primitive::<T>()
+            .iter()
+            .map(|value| value.map(|value| hash_bytes(to_bytes(value).as_ref())))
+            .collect()
+    }
+
+    let hashes = match values.data_type() {
+        DataType::Utf8 => hash_all(
+            values
+                .as_string::<i32>()
+                .iter()
+                .map(|value| value.map(str::as_bytes)),
+        ),
+        DataType::LargeUtf8 => hash_all(
+            values
+                .as_string::<i64>()
+                .iter()
+                .map(|value| value.map(str::as_bytes)),
+        ),
+        DataType::Binary => hash_all(values.as_binary::<i32>().iter()),
+        DataType::LargeBinary => hash_all(values.as_binary::<i64>().iter()),
+        DataType::FixedSizeBinary(_) => hash_all(values.as_fixed_size_binary().iter()),
+        DataType::Float16 => hash_floats::<Float16Type, _>(values, |value| match value {
+            value if value.is_nan() => f16::NAN.to_le_bytes(),
+            value if value == f16::ZERO => f16::ZERO.to_le_bytes(),
+            value => value.to_le_bytes(),
+        }),
+        DataType::Float32 => hash_floats::<Float32Type, _>(values, |value| match value {
+            value if value.is_nan() => f32::NAN.to_le_bytes(),
+            value if value == 0.0 => 0.0_f32.to_le_bytes(),
+            value => value.to_le_bytes(),
+        }),
+        DataType::Float64 => hash_floats::<Float64Type, _>(values, |value| match value {
+            value if value.is_nan() => f64::NAN.to_le_bytes(),
+            value if value == 0.0 => 0.0_f64.to_le_bytes(),
+            value => value.to_le_bytes(),
+        }),
+        data_type if data_type.is_primitive() => {
+            let width = data_type.primitive_width().expect_ok()?;
+            let data = values.to_data();
+            let bytes = &data.buffers()[0].as_slice()[data.offset() * width..];
+            hash_all(
+                bytes
+                    .chunks_exact(width)
+                    .take(values.len())
+                    .enumerate()
+                    .map(|(idx, value)| values.is_valid(idx).then_some(value)),
+            )
+        }
+        data_type => {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "A bloom filter index cannot be created on a column of type {}",
+                    data_type
+                )
+                .into(),
+                location: location!(),
+            })
+        }
+    };
+    Ok(hashes)
+}
+
+/// The bits of a value are derived from its hash with double hashing
+fn bit_positions(num_bits: u64, hash: u64, num_hashes: u32) -> impl Iterator<Item = usize> {
+    let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+    (0..num_hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+}
+
+#[derive(Debug, Clone, PartialEq, DeepSizeOf)]
+struct BloomFilter {
+    words: Vec<u64>,
+}
+
+impl BloomFilter {
+    /// Create an empty filter sized for the given number of distinct values
+    fn new(num_values: usize, num_hashes: u32) -> Self {
+        // The optimal number of bits per value for k hashes is k / ln(2)
+        let num_bits = (num_values.max(1) as f64 * num_hashes as f64 / LN_2).ceil() as usize;
+        Self {
+            words: vec![0; num_bits.div_ceil(64)],
+        }
+    }
+
+    fn num_bits(&self) -> u64 {
+        self.words.len() as u64 * 64
+    }
+
+    fn insert(&mut self, hash: u64, num_hashes: u32) {
+        for pos in bit_positions(self.num_bits(), hash, num_hashes) {
+            self.words[pos / 64] |= 1_u64 << (pos % 64);
+        }
+    }
+
+    fn might_contain(&self, hash: u64, num_hashes: u32) -> bool {
+        bit_positions(self.num_bits(), hash, num_hashes)
+            .all(|pos| self.words[pos / 64] & (1_u64 << (pos % 64)) != 0)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.words
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            words: bytes
+                .chunks_exact(8)
+                .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+                .collect(),
+        }
+    }
+}
+
+/// The bloom filter of the values of the rows of a block
+type BloomBlock = RowBlock<BloomFilter>;
+
+/// Builds the bloom filter of the values of the block being built
+struct FilterBuilder {
+    num_hashes: u32,
+    hashes: Vec<u64>,
+}
+
+impl FilterBuilder {
+    fn new(params: &BloomFilterIndexParams) -> Self {
+        Self {
+            num_hashes: params.num_hashes(),
+            hashes: Vec::new(),
+        }
+    }
+}
+
+impl SummaryBuilder for FilterBuilder {
+    type Summary = BloomFilter;
+
+    fn update(&mut self, values: &ArrayRef) -> Result<()> {
+        self.hashes
+            .extend(hash_values(values.as_ref())?.into_iter().flatten());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<BloomFilter> {
+        self.hashes.sort_unstable();
+        self.hashes.dedup();
+        let mut filter = BloomFilter::new(self.hashes.len(), self.num_hashes);
+        for hash in self.hashes.drain(..) {
+            filter.insert(hash, self.num_hashes);
+        }
+        Ok(filter)
+    }
+}
+
+fn block_builder(params: &BloomFilterIndexParams) -> RowBlockBuilder<FilterBuilder> {
+    RowBlockBuilder::new(params.rows_per_block, FilterBuilder::new(params))
+}
+
+async fn write_bloom_filter_index(
+    blocks: &[BloomBlock],
+    value_type: &DataType,
+    params: &BloomFilterIndexParams,
+    index_store: &dyn IndexStore,
+) -> Result<()> {
+    let metadata = HashMap::from([
+        (PARAMS_META_KEY.to_string(), serde_json::to_string(params)?),
+        (
+            VALUE_TYPE_META_KEY.to_string(),
+            LogicalType::try_from(value_type)?.to_string(),
+        ),
+    ]);
+    write_row_blocks(
+        index_store,
+        BLOOM_FILTER_FILENAME,
+        blocks,
+        BLOCKS_PER_BATCH,
+        vec![Field::new(FILTER_COL, DataType::Binary, false)],
+        |blocks| {
+            let filters =
+                BinaryArray::from_iter_values(blocks.iter().map(|block| block.summary.to_bytes()));
+            Ok(vec![Arc::new(filters) as ArrayRef])
+        },
+        metadata,
+    )
+    .await
+}
+
+#[derive(Serialize)]
+struct BloomFilterStatistics {
+    num_blocks: usize,
+    false_positive_rate: f64,
+    rows_per_block: u64,
+}
+
+/// A scalar index that stores a bloom filter of the values of each block of rows
+///
+/// This index can only answer equality and IN queries and the results are inexact.
+#[derive(Debug)]
+pub struct BloomFilterIndex {
+    blocks: Vec<BloomBlock>,
+    value_type: DataType,
+    params: BloomFilterIndexParams,
+    num_hashes: u32,
+    fri: Option<Arc<FragReuseIndex>>,
+    store: Arc<dyn IndexStore>,
+}
+
+impl DeepSizeOf for BloomFilterIndex {
+    fn deep_size_of_children(&self, context: &mut deepsize::Context) -> usize {
+        self.blocks.deep_size_of_children(context) + self.store.deep_size_of_children(context)
+    }
+}
+
+impl BloomFilterIndex {
+    pub fn params(&self) -> &BloomFilterIndexParams {
+        &self.params
+    }
+
+    fn hash_query_values(&self, values: &[ScalarValue]) -> Result<Vec<u64>> {
+        // Nulls are never equal to anything
+        let values = values
+            .iter()
+            .filter(|value| !value.is_null())
+            .map(|value| value.cast_to(&self.value_type))
+            .collect::<datafusion_common::Result<Vec<_>>>()?;
+        if values.is_empty() {
+            return Ok(Vec::new());
+        }
+        let values = ScalarValue::iter_to_array(values)?;
+        Ok(hash_values(values.as_ref())?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Index for BloomFilterIndex {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_index(self: Arc<Self>) -> Arc<dyn Index> {
+        self
+    }
+
+    fn as_vector_index(self: Arc<Self>) -> Result<Arc<dyn crate::vector::VectorIndex>> {
+        Err(Error::NotSupported {
+            source: "BloomFilterIndex is not a vector index".into(),
+            location: location!(),
+        })
+    }
+
+    async fn prewarm(&self) -> Result<()> {
+        // All of the filters are loaded when the index is loaded
+        Ok(())
+    }
+
+    fn index_type(&self) -> IndexType {
+        IndexType::BloomFilter
+    }
+
+    fn statistics(&self) -> Result<serde_json::Value> {
+        let stats = BloomFilterStatistics {
+            num_blocks: self.blocks.len(),
+            false_positive_rate: self.params.false_positive_rate,
+            rows_per_block: self.params.rows_per_block,
+        };
+        serde_json::to_value(stats).map_err(|e| Error::Internal {
+            message: format!("failed to serialize bloom filter index statistics: {}", e),
+            location: location!(),
+        })
+    }
+
+    async fn calculate_included_frags(&self) -> Result<RoaringBitmap> {
+        Ok(included_frags(&self.blocks))
+    }
+}
+
+#[async_trait]
+impl ScalarIndex for BloomFilterIndex {
+    #[instrument(name = "bloom_filter_search", level = "debug", skip_all)]
+    async fn search(
+        &self,
+        query: &dyn AnyQuery,
+        metrics: &dyn MetricsCollector,
+    ) -> Result<SearchResult> {
+        let values = match query.as_any().downcast_ref::<SargableQuery>() {
+            Some(SargableQuery::Equals(value)) => vec![value.clone()],
+            Some(SargableQuery::IsIn(values)) => values.clone(),
+            _ => {
+                return Err(Error::NotSupported {
+                    source: "a bloom filter index can only answer equality queries".into(),
+                    location: location!(),
+                })
+            }
+        };
+        let hashes = self.hash_query_values(&values)?;
+
+        metrics.record_comparisons(self.blocks.len());
+        let blocks = self.blocks.iter().filter(|block| {
+            hashes
+                .iter()
+                .any(|hash| block.summary.might_contain(*hash, self.num_hashes))
+        });
+        Ok(SearchResult::AtMost(block_row_ids(
+            blocks,
+            self.fri.as_deref(),
+        )))
+    }
+
+    fn can_answer_exact(&self, _: &dyn AnyQuery) -> bool {
+        false
+    }
+
+    async fn load(
+        store: Arc<dyn IndexStore>,
+        fri: Option<Arc<FragReuseIndex>>,
+    ) -> Result<Arc<Self>> {
+        let reader = store.open_index_file(BLOOM_FILTER_FILENAME).await?;
+        let metadata = &reader.schema().metadata;
+        let params = serde_json::from_str::<BloomFilterIndexParams>(
+            metadata.get(PARAMS_META_KEY).expect_ok()?,
+        )?;
+        let value_type = DataType::try_from(&LogicalType::from(
+            metadata.get(VALUE_TYPE_META_KEY).expect_ok()?.as_str(),
+        ))?;
+
+        let blocks = read_row_blocks(reader.as_ref(), BLOCKS_PER_BATCH, |batch| {
+            let filters = batch
+                .column_by_name(FILTER_COL)
+                .expect_ok()?
+                .as_binary::<i32>();
+            Ok(filters
+                .iter()
+                .flatten()
+                .map(BloomFilter::from_bytes)
+                .collect())
+        })
+        .await?;
+
+        Ok(Arc::new(Self {
+            blocks,
+            value_type,
+            num_hashes: params.num_hashes(),
+            params,
+            fri,
+            store,
+        }))
+    }
+
+    /// Remap the row ids, creating a new remapped version of this index in `dest_store`
+    async fn remap(
+        &self,
+        mapping: &HashMap<u64, Option<u64>>,
+        dest_store: &dyn IndexStore,
+    ) -> Result<()> {
+        let blocks = remap_row_blocks(&self.blocks, mapping);
+        write_bloom_filter_index(&blocks, &self.value_type, &self.params, dest_store).await
+    }
+
+    /// Add the new data into the index, creating an updated version of the index in `dest_store`
+    async fn update(
+        &self,
+        new_data: SendableRecordBatchStream,
+        dest_store: &dyn IndexStore,
+    ) -> Result<()> {
+        let mut builder = block_builder(&self.params);
+        builder.add_stream(new_data).await?;
+        let blocks = self
+            .blocks
+            .iter()
+            .cloned()
+            .chain(builder.finish()?)
+            .collect::<Vec<_>>();
+        write_bloom_filter_index(&blocks, &self.value_type, &self.params, dest_store).await
+    }
+}
+
+pub async fn train_bloom_filter_index(
+    data_source: Box<dyn TrainingSource + Send>,
+    index_store: &dyn IndexStore,
+    params: BloomFilterIndexParams,
+) -> Result<()> {
+    params.validate()?;
+    let batches = data_source.scan_unordered_chunks(4096).await?;
+    let value_type = batches.schema().field(0).data_type().clone();
+    // Fail early if the values can't be hashed
+    hash_values(new_empty_array(&value_type).as_ref())?;
+
+    let mut builder = block_builder(&params);
+    builder.add_stream(batches).await?;
+    write_bloom_filter_index(&builder.finish()?, &value_type, &params, index_store).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Float32Array, Float64Array, Int32Array, RecordBatch, StringArray};
+    use datafusion_common::ScalarValue;
+    use lance_core::utils::address::RowAddress;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::metrics::NoOpMetricsCollector;
+    use crate::scalar::row_blocks::tests::{
+        batch_stream, fragments_batch, rewrite_mapping, row_id, search, test_store, training_source,
+    };
+
+    fn value(fragment_id: u32, offset: u32) -> ScalarValue {
+        ScalarValue::Utf8(Some(format!("{}-{}", fragment_id, offset)))
+    }
+
+    /// The value of each row is its address
+    fn batch(fragments: std::ops::Range<u32>) -> RecordBatch {
+        fragments_batch(fragments, |row_ids| {
+            Arc::new(StringArray::from_iter_values(row_ids.iter().map(
+                |row_id| {
+                    let addr = RowAddress::from(*row_id);
+                    format!("{}-{}", addr.fragment_id(), addr.row_offset())
+                },
+            )))
+        })
+    }
+
+    async fn train(
+        store: &Arc<dyn IndexStore>,
+        batch: RecordBatch,
+        params: BloomFilterIndexParams,
+    ) -> Arc<BloomFilterIndex> {
+        train_bloom_filter_index(training_source(batch).await, store.as_ref(), params)
+            .await
+            .unwrap();
+        BloomFilterIndex::load(store.clone(), None).await.unwrap()
+    }
+
+    #[test]
+    fn test_hash_is_stable() {
+        // These hashes are persisted and must never change
+        assert_eq!(hash_bytes(b"lance"), 0x621c_0762_c850_89bd);
+        let hashes = hash_values(&Int32Array::from(vec![Some(42), None])).unwrap();
+        assert_eq!(hashes, vec![Some(0xb8ac_a8f2_54d1_6bd2), None]);
+    }
+
+    #[test]
+    fn test_hash_floats() {
+        // Floats that compare equal hash the same, whatever their bits
+        let other_nan = f64::from_bits(f64::NAN.to_bits() ^ 1);
+        let hashes = hash_values(&Float64Array::from(vec![
+            0.0,
+            -0.0,
+            f64::NAN,
+            other_nan,
+            1.0,
+        ]))
+        .unwrap();
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(hashes[2], hashes[3]);
+        assert_ne!(hashes[0], hashes[4]);
+
+        let hashes =
+            hash_values(&Float32Array::from(vec![0.0, -0.0, f32::NAN, -f32::NAN])).unwrap();
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(hashes[2], hashes[3]);
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_search() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = BloomFilterIndexParams::default().with_rows_per_block(300);
+        let index = train(&store, batch(0..3), params.clone()).await;
+        assert_eq!(index.params(), &params);
+        assert_eq!(index.blocks.len(), 12);
+
+        let row_ids = search(index.as_ref(), &SargableQuery::Equals(value(1, 250))).await;
+        assert!(row_ids.contains(row_id(1, 250)));
+        // Only the block of the value matches
+        assert_eq!(row_ids.len(), Some(300));
+
+        let row_ids = search(
+            index.as_ref(),
+            &SargableQuery::IsIn(vec![value(0, 5), value(2, 999)]),
+        )
+        .await;
+        assert!(row_ids.contains(row_id(0, 5)));
+        assert!(row_ids.contains(row_id(2, 999)));
+        assert_eq!(row_ids.len(), Some(400));
+
+        let row_ids = search(
+            index.as_ref(),
+            &SargableQuery::Equals(ScalarValue::Utf8(None)),
+        )
+        .await;
+        assert!(row_ids.is_empty());
+
+        assert!(index
+            .search(&SargableQuery::IsNull(), &NoOpMetricsCollector)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_update() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = BloomFilterIndexParams::default().with_rows_per_block(500);
+        let index = train(&store, batch(0..2), params).await;
+        assert_eq!(index.blocks.len(), 4);
+
+        let updated_dir = tempdir().unwrap();
+        let updated_store = test_store(&updated_dir);
+        index
+            .update(batch_stream(batch(2..3)), updated_store.as_ref())
+            .await
+            .unwrap();
+        let updated_index = BloomFilterIndex::load(updated_store, None).await.unwrap();
+        assert_eq!(updated_index.blocks.len(), 6);
+
+        let row_ids = search(updated_index.as_ref(), &SargableQuery::Equals(value(2, 10))).await;
+        assert!(row_ids.contains(row_id(2, 10)));
+        let row_ids = search(updated_index.as_ref(), &SargableQuery::Equals(value(0, 10))).await;
+        assert!(row_ids.contains(row_id(0, 10)));
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_remap() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = BloomFilterIndexParams::default().with_rows_per_block(500);
+        let index = train(&store, batch(0..2), params).await;
+
+        let remapped_dir = tempdir().unwrap();
+        let remapped_store = test_store(&remapped_dir);
+        index
+            .remap(&rewrite_mapping(), remapped_store.as_ref())
+            .await
+            .unwrap();
+        let remapped_index = BloomFilterIndex::load(remapped_store, None).await.unwrap();
+        assert_eq!(remapped_index.blocks.len(), 4);
+        assert_eq!(
+            remapped_index.calculate_included_frags().await.unwrap(),
+            RoaringBitmap::from_iter([1, 5])
+        );
+
+        // The filters are moved with the rows
+        let row_ids = search(remapped_index.as_ref(), &SargableQuery::Equals(value(0, 3))).await;
+        assert!(row_ids.contains(row_id(5, 3)));
+        assert!(!row_ids.contains(row_id(0, 3)));
+        let row_ids = search(remapped_index.as_ref(), &SargableQuery::Equals(value(1, 3))).await;
+        assert!(row_ids.contains(row_id(1, 3)));
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_invalid_params() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = BloomFilterIndexParams::default().with_false_positive_rate(1.5);
+        assert!(train_bloom_filter_index(
+            training_source(batch(0..1)).await,
+            store.as_ref(),
+            params
+        )
+        .await
+        .is_err());
+    }
+}
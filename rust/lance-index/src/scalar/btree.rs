@@ -11,8 +11,8 @@ use std::{
 };
 
 use super::{
-    flat::FlatIndexMetadata, AnyQuery, IndexReader, IndexStore, IndexWriter, MetricsCollector,
-    SargableQuery, ScalarIndex, SearchResult,
+    flat::FlatIndexMetadata, AnyQuery, CompositeQuery, IndexReader, IndexStore, IndexWriter,
+    LabelListQuery, MetricsCollector, SargableQuery, ScalarIndex, SearchResult,
 };
 use crate::frag_reuse::FragReuseIndex;
 use crate::{Index, IndexType};
@@ -807,6 +807,45 @@ impl BTreeIndex {
         }
     }
 
+    async fn search_sargable(
+        &self,
+        query: &SargableQuery,
+        metrics: &dyn MetricsCollector,
+    ) -> Result<RowIdTreeMap> {
+        let pages = match query {
+            SargableQuery::Equals(val) => self
+                .page_lookup
+                .pages_eq(&OrderableScalarValue(val.clone())),
+            SargableQuery::Range(start, end) => self
+                .page_lookup
+                .pages_between((wrap_bound(start).as_ref(), wrap_bound(end).as_ref())),
+            SargableQuery::IsIn(values) => self
+                .page_lookup
+                .pages_in(values.iter().map(|val| OrderableScalarValue(val.clone()))),
+            SargableQuery::FullTextSearch(_) => return Err(Error::invalid_input(
+                "full text search is not supported for BTree index, build a inverted index for it",
+                location!(),
+            )),
+            SargableQuery::IsNull() => self.page_lookup.pages_null(),
+        };
+        let lazy_index_reader = LazyIndexReader::new(self.store.clone());
+        let page_tasks = pages
+            .into_iter()
+            .map(|page_index| {
+                self.search_page(query, page_index, lazy_index_reader.clone(), metrics)
+                    .boxed()
+            })
+            .collect::<Vec<_>>();
+        debug!("Searching {} btree pages", page_tasks.len());
+        let row_ids = stream::iter(page_tasks)
+            // I/O and compute mixed here but important case is index in cache so
+            // use compute intensive thread count
+            .buffered(get_num_compute_intensive_cpus())
+            .try_collect::<RowIdTreeMap>()
+            .await?;
+        Ok(row_ids)
+    }
+
     fn try_from_serialized(
         data: RecordBatch,
         store: Arc<dyn IndexStore>,
@@ -977,38 +1016,38 @@ impl ScalarIndex for BTreeIndex {
         query: &dyn AnyQuery,
         metrics: &dyn MetricsCollector,
     ) -> Result<SearchResult> {
+        // Composite indices store binary keys and so composite queries become key ranges
+        if let Some(composite) = query.as_any().downcast_ref::<CompositeQuery>() {
+            let key_range = composite.to_key_range()?;
+            let row_ids = self.search_sargable(&key_range, metrics).await?;
+            return Ok(SearchResult::Exact(row_ids));
+        }
+        // A btree on a field inside of a list (e.g. `tags.name`) maps each value to the rows
+        // whose lists contain the value and so it can answer label list queries
+        if let Some(labels) = query.as_any().downcast_ref::<LabelListQuery>() {
+            let row_ids = match labels {
+                LabelListQuery::HasAnyLabel(values) => {
+                    self.search_sargable(&SargableQuery::IsIn(values.clone()), metrics)
+                        .await?
+                }
+                LabelListQuery::HasAllLabels(values) => {
+                    let mut row_ids: Option<RowIdTreeMap> = None;
+                    for value in values {
+                        let matches = self
+                            .search_sargable(&SargableQuery::Equals(value.clone()), metrics)
+                            .await?;
+                        row_ids = Some(match row_ids {
+                            Some(row_ids) => row_ids & matches,
+                            None => matches,
+                        });
+                    }
+                    row_ids.unwrap_or_default()
+                }
+            };
+            return Ok(SearchResult::Exact(row_ids));
+        }
         let query = query.as_any().downcast_ref::<SargableQuery>().unwrap();
-        let pages = match query {
-            SargableQuery::Equals(val) => self
-                .page_lookup
-                .pages_eq(&OrderableScalarValue(val.clone())),
-            SargableQuery::Range(start, end) => self
-                .page_lookup
-                .pages_between((wrap_bound(start).as_ref(), wrap_bound(end).as_ref())),
-            SargableQuery::IsIn(values) => self
-                .page_lookup
-                .pages_in(values.iter().map(|val| OrderableScalarValue(val.clone()))),
-            SargableQuery::FullTextSearch(_) => return Err(Error::invalid_input(
-                "full text search is not supported for BTree index, build a inverted index for it",
-                location!(),
-            )),
-            SargableQuery::IsNull() => self.page_lookup.pages_null(),
-        };
-        let lazy_index_reader = LazyIndexReader::new(self.store.clone());
-        let page_tasks = pages
-            .into_iter()
-            .map(|page_index| {
-                self.search_page(query, page_index, lazy_index_reader.clone(), metrics)
-                    .boxed()
-            })
-            .collect::<Vec<_>>();
-        debug!("Searching {} btree pages", page_tasks.len());
-        let row_ids = stream::iter(page_tasks)
-            // I/O and compute mixed here but important case is index in cache so
-            // use compute intensive thread count
-            .buffered(get_num_compute_intensive_cpus())
-            .try_collect::<RowIdTreeMap>()
-            .await?;
+        let row_ids = self.search_sargable(query, metrics).await?;
         Ok(SearchResult::Exact(row_ids))
     }
 
@@ -1081,6 +1120,41 @@ impl ScalarIndex for BTreeIndex {
         )
         .await
     }
+
+    async fn min_max(&self) -> Result<Option<(ScalarValue, ScalarValue)>> {
+        // The keys of the tree are the minimum of each page.  Nulls sort first and so the
+        // smallest value is in the first page with a non-null max.
+        let Some((page_min, first_page)) = self
+            .page_lookup
+            .tree
+            .iter()
+            .flat_map(|(min, pages)| pages.iter().map(move |page| (min, page)))
+            .find(|(_, page)| !page.max.0.is_null())
+        else {
+            return Ok(None);
+        };
+        let min = if page_min.0.is_null() {
+            // The page starts with nulls and the smallest value follows them
+            let reader = self.store.open_index_file(BTREE_PAGES_NAME).await?;
+            let page = reader
+                .read_record_batch(first_page.page_number as u64, self.batch_size)
+                .await?;
+            let values = page.column(0);
+            ScalarValue::try_from_array(values, values.null_count())?
+        } else {
+            page_min.0.clone()
+        };
+        let max = self
+            .page_lookup
+            .tree
+            .values()
+            .flatten()
+            .map(|page| &page.max)
+            .filter(|max| !max.0.is_null())
+            .max()
+            .unwrap_or(&first_page.max);
+        Ok(Some((min, max.0.clone())))
+    }
 }
 
 struct BatchStats {
@@ -1387,8 +1461,8 @@ mod tests {
     use std::{collections::HashMap, sync::Arc};
 
     use arrow::datatypes::{Float32Type, Float64Type, Int32Type, UInt64Type};
-    use arrow_array::FixedSizeListArray;
-    use arrow_schema::DataType;
+    use arrow_array::{FixedSizeListArray, Int32Array, RecordBatch, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema};
     use datafusion::{
         execution::{SendableRecordBatchStream, TaskContext},
         physical_plan::{sorts::sort::SortExec, stream::RecordBatchStreamAdapter, ExecutionPlan},
@@ -1499,6 +1573,50 @@ mod tests {
         assert_eq!(original_data, remapped_data);
     }
 
+    #[tokio::test]
+    async fn test_min_max() {
+        let tmpdir = Arc::new(tempdir().unwrap());
+        let test_store = Arc::new(LanceIndexStore::new(
+            Arc::new(ObjectStore::local()),
+            Path::from_filesystem_path(tmpdir.path()).unwrap(),
+            FileMetadataCache::no_cache(),
+        ));
+
+        // The first page starts with nulls and so its minimum is null even though it has
+        // the smallest values
+        let values = Int32Array::from_iter((0..100).map(|i| (i >= 10).then_some(i + 90)));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("value", DataType::Int32, true),
+            Field::new("_rowid", DataType::UInt64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(values),
+                Arc::new(UInt64Array::from_iter_values(0..100)),
+            ],
+        )
+        .unwrap();
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema.clone(),
+            futures::stream::iter(vec![Ok(batch)]),
+        )) as SendableRecordBatchStream;
+        let stream = break_stream(stream, 64).map_err(DataFusionError::from);
+        let stream =
+            Box::pin(RecordBatchStreamAdapter::new(schema, stream)) as SendableRecordBatchStream;
+        let data_source = Box::new(MockTrainingSource::from(stream));
+        let sub_index_trainer = FlatIndexMetadata::new(DataType::Int32);
+        train_btree_index(data_source, &sub_index_trainer, test_store.as_ref(), 64)
+            .await
+            .unwrap();
+
+        let index = BTreeIndex::load(test_store, None).await.unwrap();
+        assert_eq!(
+            index.min_max().await.unwrap(),
+            Some((ScalarValue::Int32(Some(100)), ScalarValue::Int32(Some(189))))
+        );
+    }
+
     #[tokio::test]
     async fn test_nan_ordering() {
         let tmpdir = Arc::new(tempdir().unwrap());
@@ -0,0 +1,499 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Composite keys for btree indices on multiple columns
+//!
+//! A composite btree index is a regular btree index whose values are binary keys.  Each key
+//! encodes an ordered tuple of column values so that comparing two keys byte by byte gives
+//! the same order as comparing the tuples column by column (with nulls first).  This means
+//! equality on a prefix of the columns, followed by a range on the next column, is a single
+//! contiguous range of keys.
+//!
+//! Every value starts with a marker byte (nulls sort first).  Fixed width values are then
+//! written big-endian with the sign bit flipped and variable width values are written with
+//! every zero byte escaped and a terminator.  As a result no key is a prefix of another key
+//! unless the tuples share the same leading values.
+
+use std::ops::Bound;
+use std::sync::Arc;
+
+use arrow::datatypes::{
+    Date32Type, Date64Type, DurationMicrosecondType, DurationMillisecondType,
+    DurationNanosecondType, DurationSecondType, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, Time32MillisecondType, Time32SecondType, Time64MicrosecondType,
+    Time64NanosecondType, TimestampMicrosecondType, TimestampMillisecondType,
+    TimestampNanosecondType, TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{cast::AsArray, Array, ArrayRef, BinaryArray, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use async_trait::async_trait;
+use datafusion::physical_plan::{stream::RecordBatchStreamAdapter, SendableRecordBatchStream};
+use datafusion_common::ScalarValue;
+use futures::TryStreamExt;
+use lance_core::{Error, Result};
+use snafu::location;
+
+use super::btree::TrainingSource;
+use super::expression::binary_prefix_successor;
+use super::SargableQuery;
+
+const NULL_MARKER: u8 = 0;
+const VALUE_MARKER: u8 = 1;
+
+// Zero bytes in variable width values are written as [ESCAPE, ESCAPED_ZERO] and the value is
+// terminated by [ESCAPE, TERMINATOR].  The terminator sorts before any escaped zero or other
+// byte so shorter values sort before longer values that they are a prefix of.
+const ESCAPE: u8 = 0;
+const ESCAPED_ZERO: u8 = 0xFF;
+const TERMINATOR: u8 = 0;
+
+/// The name of the key column in batches of encoded keys
+pub const COMPOSITE_KEY_COL: &str = "value";
+
+/// Returns an error if values of the given type cannot be part of a composite key
+pub fn check_composite_key_type(data_type: &DataType) -> Result<()> {
+    match data_type {
+        DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Date32
+        | DataType::Date64
+        | DataType::Time32(_)
+        | DataType::Time64(_)
+        | DataType::Timestamp(_, _)
+        | DataType::Duration(_)
+        | DataType::Utf8
+        | DataType::LargeUtf8
+        | DataType::Binary
+        | DataType::LargeBinary => Ok(()),
+        _ => Err(Error::InvalidInput {
+            source: format!(
+                "A composite btree index cannot be created on a column of type {}",
+                data_type
+            )
+            .into(),
+            location: location!(),
+        }),
+    }
+}
+
+fn encode_with(array: &dyn Array, keys: &mut [Vec<u8>], encode: impl Fn(usize, &mut Vec<u8>)) {
+    for (idx, key) in keys.iter_mut().enumerate() {
+        if array.is_null(idx) {
+            key.push(NULL_MARKER);
+        } else {
+            key.push(VALUE_MARKER);
+            encode(idx, key);
+        }
+    }
+}
+
+fn encode_signed(value: i64, key: &mut Vec<u8>) {
+    key.extend_from_slice(&(value ^ i64::MIN).to_be_bytes());
+}
+
+fn encode_unsigned(value: u64, key: &mut Vec<u8>) {
+    key.extend_from_slice(&value.to_be_bytes());
+}
+
+// Matches the total order used by arrow (e.g. NaN sorts last) except that -0.0 is
+// encoded as 0.0 so that queries for either match both
+fn encode_float(value: f64, key: &mut Vec<u8>) {
+    let value = if value == 0.0 { 0.0 } else { value };
+    let bits = value.to_bits();
+    let bits = if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | (1 << 63)
+    };
+    key.extend_from_slice(&bits.to_be_bytes());
+}
+
+fn encode_bytes(value: &[u8], key: &mut Vec<u8>) {
+    for byte in value {
+        if *byte == 0 {
+            key.push(ESCAPE);
+            key.push(ESCAPED_ZERO);
+        } else {
+            key.push(*byte);
+        }
+    }
+    key.push(ESCAPE);
+    key.push(TERMINATOR);
+}
+
+macro_rules! encode_primitive {
+    ($array:expr, $keys:expr, $arrow_type:ty, $encode:ident, $as:ty) => {{
+        let values = $array.as_primitive::<$arrow_type>();
+        encode_with($array, $keys, |idx, key| {
+            $encode(values.value(idx) as $as, key)
+        })
+    }};
+}
+
+// Appends the encoding of each value in `array` to the corresponding key
+fn encode_column(array: &dyn Array, keys: &mut [Vec<u8>]) -> Result<()> {
+    debug_assert_eq!(array.len(), keys.len());
+    match array.data_type() {
+        DataType::Boolean => {
+            let values = array.as_boolean();
+            encode_with(array, keys, |idx, key| key.push(values.value(idx) as u8))
+        }
+        DataType::Int8 => encode_primitive!(array, keys, Int8Type, encode_signed, i64),
+        DataType::Int16 => encode_primitive!(array, keys, Int16Type, encode_signed, i64),
+        DataType::Int32 => encode_primitive!(array, keys, Int32Type, encode_signed, i64),
+        DataType::Int64 => encode_primitive!(array, keys, Int64Type, encode_signed, i64),
+        DataType::UInt8 => encode_primitive!(array, keys, UInt8Type, encode_unsigned, u64),
+        DataType::UInt16 => encode_primitive!(array, keys, UInt16Type, encode_unsigned, u64),
+        DataType::UInt32 => encode_primitive!(array, keys, UInt32Type, encode_unsigned, u64),
+        DataType::UInt64 => encode_primitive!(array, keys, UInt64Type, encode_unsigned, u64),
+        DataType::Float32 => encode_primitive!(array, keys, Float32Type, encode_float, f64),
+        DataType::Float64 => encode_primitive!(array, keys, Float64Type, encode_float, f64),
+        DataType::Date32 => encode_primitive!(array, keys, Date32Type, encode_signed, i64),
+        DataType::Date64 => encode_primitive!(array, keys, Date64Type, encode_signed, i64),
+        DataType::Time32(TimeUnit::Second) => {
+            encode_primitive!(array, keys, Time32SecondType, encode_signed, i64)
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            encode_primitive!(array, keys, Time32MillisecondType, encode_signed, i64)
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            encode_primitive!(array, keys, Time64MicrosecondType, encode_signed, i64)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            encode_primitive!(array, keys, Time64NanosecondType, encode_signed, i64)
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            encode_primitive!(array, keys, TimestampSecondType, encode_signed, i64)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            encode_primitive!(array, keys, TimestampMillisecondType, encode_signed, i64)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            encode_primitive!(array, keys, TimestampMicrosecondType, encode_signed, i64)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            encode_primitive!(array, keys, TimestampNanosecondType, encode_signed, i64)
+        }
+        DataType::Duration(TimeUnit::Second) => {
+            encode_primitive!(array, keys, DurationSecondType, encode_signed, i64)
+        }
+        DataType::Duration(TimeUnit::Millisecond) => {
+            encode_primitive!(array, keys, DurationMillisecondType, encode_signed, i64)
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            encode_primitive!(array, keys, DurationMicrosecondType, encode_signed, i64)
+        }
+        DataType::Duration(TimeUnit::Nanosecond) => {
+            encode_primitive!(array, keys, DurationNanosecondType, encode_signed, i64)
+        }
+        DataType::Utf8 => {
+            let values = array.as_string::<i32>();
+            encode_with(array, keys, |idx, key| {
+                encode_bytes(values.value(idx).as_bytes(), key)
+            })
+        }
+        DataType::LargeUtf8 => {
+            let values = array.as_string::<i64>();
+            encode_with(array, keys, |idx, key| {
+                encode_bytes(values.value(idx).as_bytes(), key)
+            })
+        }
+        DataType::Binary => {
+            let values = array.as_binary::<i32>();
+            encode_with(array, keys, |idx, key| encode_bytes(values.value(idx), key))
+        }
+        DataType::LargeBinary => {
+            let values = array.as_binary::<i64>();
+            encode_with(array, keys, |idx, key| encode_bytes(values.value(idx), key))
+        }
+        data_type => return check_composite_key_type(data_type),
+    }
+    Ok(())
+}
+
+/// Encodes each row of the given columns into a composite key
+pub fn encode_composite_keys(columns: &[ArrayRef]) -> Result<BinaryArray> {
+    let num_rows = columns.first().map(|col| col.len()).unwrap_or(0);
+    let mut keys = vec![Vec::new(); num_rows];
+    for column in columns {
+        encode_column(column.as_ref(), &mut keys)?;
+    }
+    Ok(BinaryArray::from_iter_values(keys))
+}
+
+// Encodes a tuple of values as the start of a composite key
+fn encode_key_prefix(values: &[ScalarValue]) -> Result<Vec<u8>> {
+    let mut keys = vec![Vec::new()];
+    for value in values {
+        encode_column(value.to_array()?.as_ref(), &mut keys)?;
+    }
+    Ok(keys.pop().unwrap())
+}
+
+/// Converts a composite query into a range of composite keys
+///
+/// The keys starting with the encoded `prefix` are exactly the rows where the leading columns
+/// equal `prefix`.  If there is a range on the next column then the bounds of the range are
+/// appended to the prefix.  Nulls in the range column are excluded, as they would be by SQL.
+pub fn composite_key_range(
+    prefix: &[ScalarValue],
+    range: Option<&(Bound<ScalarValue>, Bound<ScalarValue>)>,
+) -> Result<SargableQuery> {
+    let prefix = encode_key_prefix(prefix)?;
+    let key = |value: &ScalarValue| -> Result<Vec<u8>> {
+        let mut key = prefix.clone();
+        key.extend(encode_key_prefix(std::slice::from_ref(value))?);
+        Ok(key)
+    };
+    // Every encoded value starts with a marker byte that is not 0xFF and so any key containing
+    // a value has a successor
+    let successor = |key: Vec<u8>| binary_prefix_successor(&key).expect("key has a successor");
+    let binary = |key: Vec<u8>| ScalarValue::Binary(Some(key));
+    let prefix_end = binary_prefix_successor(&prefix)
+        .map(|end| Bound::Excluded(binary(end)))
+        .unwrap_or(Bound::Unbounded);
+
+    let Some((lower, upper)) = range else {
+        return Ok(SargableQuery::Range(
+            Bound::Included(binary(prefix)),
+            prefix_end,
+        ));
+    };
+    let lower = match lower {
+        Bound::Included(value) => Bound::Included(binary(key(value)?)),
+        Bound::Excluded(value) => Bound::Included(binary(successor(key(value)?))),
+        Bound::Unbounded => {
+            let mut first_value = prefix.clone();
+            first_value.push(VALUE_MARKER);
+            Bound::Included(binary(first_value))
+        }
+    };
+    let upper = match upper {
+        Bound::Included(value) => Bound::Excluded(binary(successor(key(value)?))),
+        Bound::Excluded(value) => Bound::Excluded(binary(key(value)?)),
+        Bound::Unbounded => prefix_end,
+    };
+    Ok(SargableQuery::Range(lower, upper))
+}
+
+/// Replaces the key columns of a batch with a single column of composite keys
+///
+/// The last column of the batch must be the row ids, every other column is part of the key
+pub fn encode_composite_batch(batch: &RecordBatch) -> Result<RecordBatch> {
+    let num_keys = batch.num_columns() - 1;
+    let keys = encode_composite_keys(&batch.columns()[..num_keys])?;
+    let row_ids = batch.column(num_keys).clone();
+    let schema = composite_key_schema(batch.schema().field(num_keys).clone());
+    Ok(RecordBatch::try_new(schema, vec![Arc::new(keys), row_ids])?)
+}
+
+fn composite_key_schema(row_id_field: Field) -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new(COMPOSITE_KEY_COL, DataType::Binary, false),
+        row_id_field,
+    ]))
+}
+
+/// Encodes a stream of key columns (followed by row ids) into a stream of composite keys
+///
+/// The order of the stream is preserved.  Sorting the input by each key column in turn (with
+/// nulls first) gives a stream sorted by key.
+pub fn encode_composite_stream(source: SendableRecordBatchStream) -> SendableRecordBatchStream {
+    let source_schema = source.schema();
+    let schema = composite_key_schema(
+        source_schema
+            .field(source_schema.fields().len() - 1)
+            .clone(),
+    );
+    let stream = source.and_then(|batch| {
+        std::future::ready(encode_composite_batch(&batch).map_err(|err| err.into()))
+    });
+    Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+}
+
+/// A training source that encodes the key columns of another source into composite keys
+pub struct CompositeKeyTrainingSource {
+    source: Box<dyn TrainingSource>,
+}
+
+impl CompositeKeyTrainingSource {
+    /// Wraps a source whose batches are the key columns followed by the row ids
+    pub fn new(source: Box<dyn TrainingSource>) -> Self {
+        Self { source }
+    }
+}
+
+#[async_trait]
+impl TrainingSource for CompositeKeyTrainingSource {
+    async fn scan_ordered_chunks(
+        self: Box<Self>,
+        chunk_size: u32,
+    ) -> Result<SendableRecordBatchStream> {
+        let source = self.source.scan_ordered_chunks(chunk_size).await?;
+        Ok(encode_composite_stream(source))
+    }
+
+    async fn scan_unordered_chunks(
+        self: Box<Self>,
+        chunk_size: u32,
+    ) -> Result<SendableRecordBatchStream> {
+        let source = self.source.scan_unordered_chunks(chunk_size).await?;
+        Ok(encode_composite_stream(source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Float64Array, Int32Array, StringArray};
+
+    use super::*;
+
+    fn keys(columns: Vec<ArrayRef>) -> Vec<Vec<u8>> {
+        encode_composite_keys(&columns)
+            .unwrap()
+            .iter()
+            .map(|key| key.unwrap().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_composite_key_order() {
+        // Tuples in sorted order (nulls first)
+        let first: ArrayRef = Arc::new(Int32Array::from(vec![
+            None,
+            Some(-5),
+            Some(-1),
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(7),
+        ]));
+        let second: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("z"),
+            None,
+            Some("b"),
+            None,
+            Some(""),
+            Some("a"),
+            Some("a\0"),
+            Some("ab"),
+            Some("a"),
+        ]));
+        let encoded = keys(vec![first, second]);
+        for pair in encoded.windows(2) {
+            assert!(pair[0] < pair[1], "{:?} >= {:?}", pair[0], pair[1]);
+        }
+
+        let floats: ArrayRef = Arc::new(Float64Array::from(vec![
+            f64::NEG_INFINITY,
+            -1.5,
+            -0.0,
+            0.0,
+            1e-10,
+            2.0,
+            f64::INFINITY,
+            f64::NAN,
+        ]));
+        let encoded = keys(vec![floats]);
+        for pair in encoded.windows(2) {
+            assert!(pair[0] < pair[1], "{:?} >= {:?}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_composite_key_range() {
+        let tenants: ArrayRef = Arc::new(Int32Array::from(vec![1, 1, 1, 1, 2, 2]));
+        let times: ArrayRef = Arc::new(Int32Array::from(vec![
+            None,
+            Some(10),
+            Some(20),
+            Some(30),
+            Some(10),
+            Some(20),
+        ]));
+        let encoded = keys(vec![tenants, times]);
+
+        let matches = |query: SargableQuery| -> Vec<usize> {
+            let SargableQuery::Range(lower, upper) = query else {
+                unreachable!()
+            };
+            let unwrap = |bound: Bound<ScalarValue>| match bound {
+                Bound::Included(ScalarValue::Binary(Some(key))) => Bound::Included(key),
+                Bound::Excluded(ScalarValue::Binary(Some(key))) => Bound::Excluded(key),
+                Bound::Unbounded => Bound::Unbounded,
+                _ => unreachable!(),
+            };
+            let range = (unwrap(lower), unwrap(upper));
+            encoded
+                .iter()
+                .enumerate()
+                .filter(|(_, key)| std::ops::RangeBounds::contains(&range, *key))
+                .map(|(idx, _)| idx)
+                .collect()
+        };
+        let int = |value: i32| ScalarValue::Int32(Some(value));
+
+        // Equality on the prefix
+        assert_eq!(
+            matches(composite_key_range(&[int(1)], None).unwrap()),
+            vec![0, 1, 2, 3]
+        );
+        // Equality on every column
+        assert_eq!(
+            matches(composite_key_range(&[int(2), int(20)], None).unwrap()),
+            vec![5]
+        );
+        // Ranges on the next column never match nulls
+        let range = (Bound::Unbounded, Bound::Excluded(int(30)));
+        assert_eq!(
+            matches(composite_key_range(&[int(1)], Some(&range)).unwrap()),
+            vec![1, 2]
+        );
+        let range = (Bound::Excluded(int(10)), Bound::Included(int(30)));
+        assert_eq!(
+            matches(composite_key_range(&[int(1)], Some(&range)).unwrap()),
+            vec![2, 3]
+        );
+        let range = (Bound::Included(int(20)), Bound::Unbounded);
+        assert_eq!(
+            matches(composite_key_range(&[int(1)], Some(&range)).unwrap()),
+            vec![2, 3]
+        );
+        // A range on the first column
+        let range = (Bound::Included(int(2)), Bound::Unbounded);
+        assert_eq!(
+            matches(composite_key_range(&[], Some(&range)).unwrap()),
+            vec![4, 5]
+        );
+
+        // -0.0 and 0.0 are the same key
+        let scores: ArrayRef = Arc::new(Float64Array::from(vec![-0.0, 0.0, 1.0]));
+        let encoded = keys(vec![scores]);
+        assert_eq!(encoded[0], encoded[1]);
+        let float = |value: f64| ScalarValue::Float64(Some(value));
+        let range = (Bound::Included(float(0.0)), Bound::Excluded(float(1.0)));
+        let SargableQuery::Range(Bound::Included(ScalarValue::Binary(Some(lower))), _) =
+            composite_key_range(&[], Some(&range)).unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(lower, encoded[0]);
+        let SargableQuery::Range(Bound::Included(ScalarValue::Binary(Some(prefix))), _) =
+            composite_key_range(&[float(-0.0)], None).unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(prefix, encoded[1]);
+    }
+}
@@ -9,17 +9,23 @@ use async_recursion::async_recursion;
 use async_trait::async_trait;
 use datafusion_common::ScalarValue;
 use datafusion_expr::{
-    expr::{InList, ScalarFunction},
+    expr::{InList, Like, ScalarFunction},
+    utils::split_conjunction,
     Between, BinaryExpr, Expr, Operator, ReturnTypeArgs, ScalarUDF,
 };
 
 use futures::join;
 use lance_core::{utils::mask::RowIdMask, Result};
-use lance_datafusion::{expr::safe_coerce_scalar, planner::Planner};
+use lance_datafusion::{
+    expr::safe_coerce_scalar,
+    logical_expr::get_as_string_scalar_opt,
+    planner::{Planner, LIST_FIELD},
+};
 use tracing::instrument;
 
 use super::{
-    AnyQuery, LabelListQuery, MetricsCollector, SargableQuery, ScalarIndex, SearchResult, TextQuery,
+    AnyQuery, CompositeQuery, LabelListQuery, MetricsCollector, SargableQuery, ScalarIndex,
+    SearchResult, TextQuery,
 };
 
 /// An indexed expression consists of a scalar index query with a post-scan filter
@@ -80,6 +86,31 @@ pub trait ScalarQueryParser: std::fmt::Debug + Send + Sync {
         func: &ScalarUDF,
         args: &[Expr],
     ) -> Option<IndexedExpression>;
+    /// Visit a LIKE (or ILIKE if `case_insensitive`) comparison with a pattern
+    ///
+    /// Most indices cannot answer pattern matches and so, by default, this returns None
+    fn visit_like(
+        &self,
+        _column: &str,
+        _data_type: &DataType,
+        _pattern: &str,
+        _escape_char: Option<char>,
+        _case_insensitive: bool,
+    ) -> Option<IndexedExpression> {
+        None
+    }
+    /// Visit a regular expression match (`~`, or `~*` if `case_insensitive`)
+    ///
+    /// Most indices cannot answer pattern matches and so, by default, this returns None
+    fn visit_regex(
+        &self,
+        _column: &str,
+        _data_type: &DataType,
+        _pattern: &str,
+        _case_insensitive: bool,
+    ) -> Option<IndexedExpression> {
+        None
+    }
 }
 
 /// A generic parser that wraps multiple scalar query parsers
@@ -151,18 +182,167 @@ impl ScalarQueryParser for MultiQueryParser {
             .iter()
             .find_map(|parser| parser.visit_scalar_function(column, data_type, func, args))
     }
+    fn visit_like(
+        &self,
+        column: &str,
+        data_type: &DataType,
+        pattern: &str,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+    ) -> Option<IndexedExpression> {
+        self.parsers.iter().find_map(|parser| {
+            parser.visit_like(column, data_type, pattern, escape_char, case_insensitive)
+        })
+    }
+    fn visit_regex(
+        &self,
+        column: &str,
+        data_type: &DataType,
+        pattern: &str,
+        case_insensitive: bool,
+    ) -> Option<IndexedExpression> {
+        self.parsers
+            .iter()
+            .find_map(|parser| parser.visit_regex(column, data_type, pattern, case_insensitive))
+    }
 }
 
 /// A parser for indices that handle SARGable queries
+///
+/// Indices that can only rule out rows (e.g. zone maps and bloom filters) use the same
+/// queries but mark the search as needing a recheck.
 #[derive(Debug)]
 pub struct SargableQueryParser {
     index_name: String,
+    needs_recheck: bool,
+    equality_only: bool,
 }
 
 impl SargableQueryParser {
     pub fn new(index_name: String) -> Self {
-        Self { index_name }
+        Self {
+            index_name,
+            needs_recheck: false,
+            equality_only: false,
+        }
+    }
+
+    /// The index returns a superset of the matching rows and the results must be rechecked
+    pub fn with_recheck(mut self) -> Self {
+        self.needs_recheck = true;
+        self
+    }
+
+    /// The index can only answer equality and IN queries (e.g. a bloom filter)
+    pub fn with_equality_only(mut self) -> Self {
+        self.equality_only = true;
+        self
+    }
+
+    fn index_query(&self, column: &str, query: SargableQuery) -> Option<IndexedExpression> {
+        if self.equality_only && !matches!(query, SargableQuery::Equals(_) | SargableQuery::IsIn(_))
+        {
+            return None;
+        }
+        Some(IndexedExpression {
+            scalar_query: Some(ScalarIndexExpr::Query(ScalarIndexSearch {
+                column: column.to_string(),
+                index_name: self.index_name.clone(),
+                query: Arc::new(query),
+                needs_recheck: self.needs_recheck,
+            })),
+            refine_expr: None,
+        })
+    }
+}
+
+// The smallest string that is greater than every string starting with `prefix`
+//
+// Returns None if there is no such string (e.g. the prefix is empty)
+fn string_prefix_successor(prefix: &str) -> Option<String> {
+    let mut chars = prefix.chars().collect::<Vec<_>>();
+    while let Some(last) = chars.pop() {
+        let next = match last {
+            // Skip over the surrogate range, which are not valid chars
+            '\u{D7FF}' => Some('\u{E000}'),
+            char::MAX => None,
+            _ => char::from_u32(last as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
+// The smallest binary value that is greater than every value starting with `prefix`
+//
+// Returns None if there is no such value (e.g. the prefix is empty or all 0xFF)
+pub(crate) fn binary_prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = prefix.to_vec();
+    while let Some(last) = bytes.pop() {
+        if last < u8::MAX {
+            bytes.push(last + 1);
+            return Some(bytes);
+        }
     }
+    None
+}
+
+/// Converts a prefix match into an equivalent range query
+///
+/// Strings and binary values are ordered byte by byte and so the values starting with a prefix
+/// are exactly those in the range `[prefix, successor)`.  If the prefix has no successor then
+/// the range has no upper bound.
+fn prefix_range(prefix: ScalarValue) -> Option<SargableQuery> {
+    let upper = match &prefix {
+        ScalarValue::Utf8(Some(prefix)) => {
+            string_prefix_successor(prefix).map(|succ| ScalarValue::Utf8(Some(succ)))
+        }
+        ScalarValue::LargeUtf8(Some(prefix)) => {
+            string_prefix_successor(prefix).map(|succ| ScalarValue::LargeUtf8(Some(succ)))
+        }
+        ScalarValue::Binary(Some(prefix)) => {
+            binary_prefix_successor(prefix).map(|succ| ScalarValue::Binary(Some(succ)))
+        }
+        ScalarValue::LargeBinary(Some(prefix)) => {
+            binary_prefix_successor(prefix).map(|succ| ScalarValue::LargeBinary(Some(succ)))
+        }
+        _ => return None,
+    };
+    let upper = upper.map(Bound::Excluded).unwrap_or(Bound::Unbounded);
+    Some(SargableQuery::Range(Bound::Included(prefix), upper))
+}
+
+/// The parts of a LIKE pattern that can be answered by an ordered index
+enum LikePrefix {
+    /// The pattern has no wildcards and must match exactly
+    Exact(String),
+    /// The pattern is a literal prefix followed only by `%` wildcards
+    Prefix(String),
+}
+
+fn parse_like_prefix(pattern: &str, escape_char: Option<char>) -> Option<LikePrefix> {
+    let escape_char = escape_char.unwrap_or('\\');
+    let mut literal = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == escape_char {
+            // A trailing escape character is invalid, leave it to the refine step to complain
+            literal.push(chars.next()?);
+        } else if c == '%' {
+            // Anything other than more % wildcards after the prefix would need a recheck
+            return chars
+                .all(|c| c == '%')
+                .then_some(LikePrefix::Prefix(literal));
+        } else if c == '_' {
+            return None;
+        } else {
+            literal.push(c);
+        }
+    }
+    Some(LikePrefix::Exact(literal))
 }
 
 impl ScalarQueryParser for SargableQueryParser {
@@ -174,36 +354,22 @@ impl ScalarQueryParser for SargableQueryParser {
     ) -> Option<IndexedExpression> {
         let query =
             SargableQuery::Range(Bound::Included(low.clone()), Bound::Included(high.clone()));
-        Some(IndexedExpression::index_query(
-            column.to_string(),
-            self.index_name.clone(),
-            Arc::new(query),
-        ))
+        self.index_query(column, query)
     }
 
     fn visit_in_list(&self, column: &str, in_list: &[ScalarValue]) -> Option<IndexedExpression> {
-        let query = SargableQuery::IsIn(in_list.to_vec());
-        Some(IndexedExpression::index_query(
-            column.to_string(),
-            self.index_name.clone(),
-            Arc::new(query),
-        ))
+        self.index_query(column, SargableQuery::IsIn(in_list.to_vec()))
     }
 
     fn visit_is_bool(&self, column: &str, value: bool) -> Option<IndexedExpression> {
-        Some(IndexedExpression::index_query(
-            column.to_string(),
-            self.index_name.clone(),
-            Arc::new(SargableQuery::Equals(ScalarValue::Boolean(Some(value)))),
-        ))
+        self.index_query(
+            column,
+            SargableQuery::Equals(ScalarValue::Boolean(Some(value))),
+        )
     }
 
     fn visit_is_null(&self, column: &str) -> Option<IndexedExpression> {
-        Some(IndexedExpression::index_query(
-            column.to_string(),
-            self.index_name.clone(),
-            Arc::new(SargableQuery::IsNull()),
-        ))
+        self.index_query(column, SargableQuery::IsNull())
     }
 
     fn visit_comparison(
@@ -222,25 +388,51 @@ impl ScalarQueryParser for SargableQueryParser {
                 SargableQuery::Range(Bound::Included(value.clone()), Bound::Unbounded)
             }
             Operator::Eq => SargableQuery::Equals(value.clone()),
+            // Equality-only indices can't rule out rows for a negation
+            Operator::NotEq if self.equality_only => return None,
             // This will be negated by the caller
             Operator::NotEq => SargableQuery::Equals(value.clone()),
             _ => unreachable!(),
         };
-        Some(IndexedExpression::index_query(
-            column.to_string(),
-            self.index_name.clone(),
-            Arc::new(query),
-        ))
+        self.index_query(column, query)
     }
 
     fn visit_scalar_function(
         &self,
-        _: &str,
-        _: &DataType,
-        _: &ScalarUDF,
-        _: &[Expr],
+        column: &str,
+        data_type: &DataType,
+        func: &ScalarUDF,
+        args: &[Expr],
     ) -> Option<IndexedExpression> {
-        None
+        if func.name() != "starts_with" || args.len() != 2 {
+            return None;
+        }
+        let prefix = maybe_scalar(&args[1], data_type)?;
+        self.index_query(column, prefix_range(prefix)?)
+    }
+
+    fn visit_like(
+        &self,
+        column: &str,
+        data_type: &DataType,
+        pattern: &str,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+    ) -> Option<IndexedExpression> {
+        // Case insensitive matches are not contiguous ranges
+        if case_insensitive {
+            return None;
+        }
+        let to_scalar = |value: String| match data_type {
+            DataType::Utf8 => Some(ScalarValue::Utf8(Some(value))),
+            DataType::LargeUtf8 => Some(ScalarValue::LargeUtf8(Some(value))),
+            _ => None,
+        };
+        let query = match parse_like_prefix(pattern, escape_char)? {
+            LikePrefix::Exact(value) => SargableQuery::Equals(to_scalar(value)?),
+            LikePrefix::Prefix(prefix) => prefix_range(to_scalar(prefix)?)?,
+        };
+        self.index_query(column, query)
     }
 }
 
@@ -297,6 +489,19 @@ impl ScalarQueryParser for LabelListQueryParser {
         if args.len() != 2 {
             return None;
         }
+        // array_has(col, label) is the same as array_has_any(col, [label])
+        if func.name() == "array_has" {
+            let DataType::List(item) = data_type else {
+                return None;
+            };
+            let label = maybe_scalar(&args[1], item.data_type())?;
+            let query = LabelListQuery::HasAnyLabel(vec![label]);
+            return Some(IndexedExpression::index_query(
+                column.to_string(),
+                self.index_name.clone(),
+                Arc::new(query),
+            ));
+        }
         let label_list = maybe_scalar(&args[1], data_type)?;
         if let ScalarValue::List(list_arr) = label_list {
             let list_values = list_arr.values();
@@ -383,16 +588,16 @@ impl ScalarQueryParser for TextQueryParser {
         let scalar = maybe_scalar(&args[1], data_type)?;
         match scalar {
             ScalarValue::Utf8(Some(scalar_str)) | ScalarValue::LargeUtf8(Some(scalar_str)) => {
-                if func.name() == "contains" {
-                    let query = TextQuery::StringContains(scalar_str);
-                    Some(IndexedExpression::index_query(
-                        column.to_string(),
-                        self.index_name.clone(),
-                        Arc::new(query),
-                    ))
-                } else {
-                    None
-                }
+                let query = match func.name() {
+                    "contains" => TextQuery::StringContains(scalar_str),
+                    "regexp_like" => TextQuery::Regex(scalar_str),
+                    _ => return None,
+                };
+                Some(IndexedExpression::index_query(
+                    column.to_string(),
+                    self.index_name.clone(),
+                    Arc::new(query),
+                ))
             }
             _ => {
                 // If the scalar is not a string, we cannot handle it
@@ -400,6 +605,46 @@ impl ScalarQueryParser for TextQueryParser {
             }
         }
     }
+
+    fn visit_like(
+        &self,
+        column: &str,
+        _: &DataType,
+        pattern: &str,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+    ) -> Option<IndexedExpression> {
+        let query = TextQuery::Like {
+            pattern: pattern.to_string(),
+            escape_char,
+            case_insensitive,
+        };
+        Some(IndexedExpression::index_query(
+            column.to_string(),
+            self.index_name.clone(),
+            Arc::new(query),
+        ))
+    }
+
+    fn visit_regex(
+        &self,
+        column: &str,
+        _: &DataType,
+        pattern: &str,
+        case_insensitive: bool,
+    ) -> Option<IndexedExpression> {
+        // The flag is part of the pattern so the recheck matches the same way
+        let pattern = if case_insensitive {
+            format!("(?i){}", pattern)
+        } else {
+            pattern.to_string()
+        };
+        Some(IndexedExpression::index_query(
+            column.to_string(),
+            self.index_name.clone(),
+            Arc::new(TextQuery::Regex(pattern)),
+        ))
+    }
 }
 
 /// A parser for indices that handle queries with the contains_tokens function
@@ -492,6 +737,7 @@ impl IndexedExpression {
                 column,
                 index_name,
                 query,
+                needs_recheck: false,
             })),
             refine_expr: None,
         }
@@ -624,12 +870,16 @@ pub struct ScalarIndexSearch {
     pub index_name: String,
     /// The query to search for
     pub query: Arc<dyn AnyQuery>,
+    /// True if the index can only rule out rows and the results must be rechecked, even
+    /// if the query itself is exact
+    pub needs_recheck: bool,
 }
 
 impl PartialEq for ScalarIndexSearch {
     fn eq(&self, other: &Self) -> bool {
         self.column == other.column
             && self.index_name == other.index_name
+            && self.needs_recheck == other.needs_recheck
             && self.query.as_ref().eq(other.query.as_ref())
     }
 }
@@ -827,26 +1077,34 @@ impl ScalarIndexExpr {
         match self {
             Self::Not(inner) => inner.needs_recheck(),
             Self::And(lhs, rhs) | Self::Or(lhs, rhs) => lhs.needs_recheck() || rhs.needs_recheck(),
-            Self::Query(search) => search.query.needs_recheck(),
+            Self::Query(search) => search.needs_recheck || search.query.needs_recheck(),
         }
     }
 }
 
 // Extract a column from the expression, if it is a column, or None
-fn maybe_column(expr: &Expr) -> Option<&str> {
+//
+// Nested fields are returned as their column path (e.g. `st.s1`, or `tags.name` for the
+// `name` field of the items of a `tags` list)
+fn maybe_column(expr: &Expr) -> Option<String> {
     match expr {
-        Expr::Column(col) => Some(&col.name),
+        Expr::Column(col) => Some(col.name.clone()),
+        Expr::ScalarFunction(udf) if udf.name() == "get_field" || udf.name() == LIST_FIELD => {
+            let parent = maybe_column(&udf.args[0])?;
+            let name = get_as_string_scalar_opt(&udf.args[1])?;
+            Some(format!("{}.{}", parent, name))
+        }
         _ => None,
     }
 }
 
 // Extract a column from the expression, if it is a column, and we have an index for that column, or None
-fn maybe_indexed_column<'a, 'b>(
-    expr: &'a Expr,
+fn maybe_indexed_column<'b>(
+    expr: &Expr,
     index_info: &'b dyn IndexInformationProvider,
-) -> Option<(&'a str, &'b DataType, &'b dyn ScalarQueryParser)> {
+) -> Option<(String, &'b DataType, &'b dyn ScalarQueryParser)> {
     let col = maybe_column(expr)?;
-    let data_type = index_info.get_index(col);
+    let data_type = index_info.get_index(&col);
     data_type.map(|(ty, parser)| (col, ty, parser))
 }
 
@@ -920,7 +1178,7 @@ fn visit_between(
     let low = maybe_scalar(&between.low, col_type)?;
     let high = maybe_scalar(&between.high, col_type)?;
 
-    let indexed_expr = query_parser.visit_between(column, &low, &high)?;
+    let indexed_expr = query_parser.visit_between(&column, &low, &high)?;
 
     if between.negated {
         indexed_expr.maybe_not()
@@ -936,7 +1194,7 @@ fn visit_in_list(
     let (column, col_type, query_parser) = maybe_indexed_column(&in_list.expr, index_info)?;
     let values = maybe_scalar_list(&in_list.list, col_type)?;
 
-    let indexed_expr = query_parser.visit_in_list(column, &values)?;
+    let indexed_expr = query_parser.visit_in_list(&column, &values)?;
 
     if in_list.negated {
         indexed_expr.maybe_not()
@@ -954,7 +1212,7 @@ fn visit_is_bool(
     if *col_type != DataType::Boolean {
         None
     } else {
-        query_parser.visit_is_bool(column, value)
+        query_parser.visit_is_bool(&column, value)
     }
 }
 
@@ -967,7 +1225,7 @@ fn visit_column(
     if *col_type != DataType::Boolean {
         None
     } else {
-        query_parser.visit_is_bool(column, true)
+        query_parser.visit_is_bool(&column, true)
     }
 }
 
@@ -977,7 +1235,7 @@ fn visit_is_null(
     negated: bool,
 ) -> Option<IndexedExpression> {
     let (column, _, query_parser) = maybe_indexed_column(expr, index_info)?;
-    let indexed_expr = query_parser.visit_is_null(column)?;
+    let indexed_expr = query_parser.visit_is_null(&column)?;
     if negated {
         indexed_expr.maybe_not()
     } else {
@@ -985,24 +1243,67 @@ fn visit_is_null(
     }
 }
 
+fn visit_like(like: &Like, index_info: &dyn IndexInformationProvider) -> Option<IndexedExpression> {
+    let (column, col_type, query_parser) = maybe_indexed_column(&like.expr, index_info)?;
+    let pattern = match maybe_scalar(&like.pattern, col_type)? {
+        ScalarValue::Utf8(Some(pattern)) | ScalarValue::LargeUtf8(Some(pattern)) => pattern,
+        _ => return None,
+    };
+    let indexed_expr = query_parser.visit_like(
+        &column,
+        col_type,
+        &pattern,
+        like.escape_char,
+        like.case_insensitive,
+    )?;
+    if like.negated {
+        indexed_expr.maybe_not()
+    } else {
+        Some(indexed_expr)
+    }
+}
+
+fn visit_regex(
+    expr: &BinaryExpr,
+    index_info: &dyn IndexInformationProvider,
+) -> Option<IndexedExpression> {
+    let (column, col_type, query_parser) = maybe_indexed_column(&expr.left, index_info)?;
+    let pattern = match maybe_scalar(&expr.right, col_type)? {
+        ScalarValue::Utf8(Some(pattern)) | ScalarValue::LargeUtf8(Some(pattern)) => pattern,
+        _ => return None,
+    };
+    let case_insensitive = matches!(expr.op, Operator::RegexIMatch | Operator::RegexNotIMatch);
+    query_parser.visit_regex(&column, col_type, &pattern, case_insensitive)
+}
+
 fn visit_not(expr: &Expr, index_info: &dyn IndexInformationProvider) -> Option<IndexedExpression> {
     let node = visit_node(expr, index_info)?;
     node.maybe_not()
 }
 
+// Split a comparison into its column side, operator, and value side
+//
+// Datafusion's query simplifier will usually put the column on the left but filters that
+// are not simplified may be written as `value <op> col` and so those are swapped (e.g.
+// `5 < aisle` becomes `aisle > 5`)
+fn comparison_operands(expr: &BinaryExpr) -> Option<(&Expr, Operator, &Expr)> {
+    if maybe_column(&expr.left).is_some() {
+        Some((&expr.left, expr.op, &expr.right))
+    } else if maybe_column(&expr.right).is_some() {
+        Some((&expr.right, expr.op.swap()?, &expr.left))
+    } else {
+        None
+    }
+}
+
 fn visit_comparison(
     expr: &BinaryExpr,
     index_info: &dyn IndexInformationProvider,
 ) -> Option<IndexedExpression> {
-    let left_col = maybe_indexed_column(&expr.left, index_info);
-    if let Some((column, col_type, query_parser)) = left_col {
-        let scalar = maybe_scalar(&expr.right, col_type)?;
-        query_parser.visit_comparison(column, &scalar, &expr.op)
-    } else {
-        // Datafusion's query simplifier will canonicalize expressions and so we shouldn't reach this case.  If, for some reason, we
-        // do reach this case we can handle it in the future by inverting expr.op and swapping the left and right sides
-        None
-    }
+    let (column, op, value) = comparison_operands(expr)?;
+    let (column, col_type, query_parser) = maybe_indexed_column(column, index_info)?;
+    let scalar = maybe_scalar(value, col_type)?;
+    query_parser.visit_comparison(&column, &scalar, &op)
 }
 
 fn maybe_between(expr: &BinaryExpr) -> Option<Between> {
@@ -1065,6 +1366,19 @@ fn maybe_between(expr: &BinaryExpr) -> Option<Between> {
 fn visit_and(
     expr: &BinaryExpr,
     index_info: &dyn IndexInformationProvider,
+) -> Option<IndexedExpression> {
+    // The composite indices are matched against the whole conjunction, once
+    let mut conjuncts = split_conjunction(&expr.left);
+    conjuncts.extend(split_conjunction(&expr.right));
+    if let Some(indexed_expr) = visit_composite(&conjuncts, index_info) {
+        return Some(indexed_expr);
+    }
+    visit_conjunction(expr, index_info)
+}
+
+fn visit_conjunction(
+    expr: &BinaryExpr,
+    index_info: &dyn IndexInformationProvider,
 ) -> Option<IndexedExpression> {
     // Many scalar indices can efficiently handle a BETWEEN query as a single search and this
     // can be much more efficient than two separate range queries.  As an optimization we check
@@ -1077,8 +1391,14 @@ fn visit_and(
         return visit_between(&between, index_info);
     }
 
-    let left = visit_node(&expr.left, index_info);
-    let right = visit_node(&expr.right, index_info);
+    let visit_conjunct = |conjunct: &Expr| match conjunct {
+        Expr::BinaryExpr(binary) if binary.op == Operator::And => {
+            visit_conjunction(binary, index_info)
+        }
+        _ => visit_node(conjunct, index_info),
+    };
+    let left = visit_conjunct(&expr.left);
+    let right = visit_conjunct(&expr.right);
     match (left, right) {
         (Some(left), Some(right)) => Some(left.and(right)),
         (Some(left), None) => Some(left.refine((*expr.right).clone())),
@@ -1116,6 +1436,10 @@ fn visit_binary_expr(
         }
         // visit_comparison will maybe create an Eq query which we negate
         Operator::NotEq => visit_comparison(expr, index_info).and_then(|node| node.maybe_not()),
+        Operator::RegexMatch | Operator::RegexIMatch => visit_regex(expr, index_info),
+        Operator::RegexNotMatch | Operator::RegexNotIMatch => {
+            visit_regex(expr, index_info).and_then(|node| node.maybe_not())
+        }
         Operator::And => visit_and(expr, index_info),
         Operator::Or => visit_or(expr, index_info),
         _ => None,
@@ -1130,7 +1454,169 @@ fn visit_scalar_fn(
         return None;
     }
     let (col, data_type, query_parser) = maybe_indexed_column(&scalar_fn.args[0], index_info)?;
-    query_parser.visit_scalar_function(col, data_type, &scalar_fn.func, &scalar_fn.args)
+    query_parser.visit_scalar_function(&col, data_type, &scalar_fn.func, &scalar_fn.args)
+}
+
+// The conjuncts of a filter that a composite index can satisfy
+struct CompositeMatch<'a> {
+    index: &'a CompositeIndexInfo,
+    prefix: Vec<ScalarValue>,
+    range: Option<(Bound<ScalarValue>, Bound<ScalarValue>)>,
+    // The positions of the conjuncts satisfied by the index
+    used: Vec<usize>,
+}
+
+// Extract `col = value` from a conjunct, if it is an equality on `col` with a non-null value
+fn maybe_composite_eq(conjunct: &Expr, col: &str, data_type: &DataType) -> Option<ScalarValue> {
+    let Expr::BinaryExpr(binary) = conjunct else {
+        return None;
+    };
+    let (column, op, value) = comparison_operands(binary)?;
+    if op != Operator::Eq || maybe_column(column)? != col {
+        return None;
+    }
+    maybe_scalar(value, data_type).filter(|value| !value.is_null())
+}
+
+// Extract the (lower, upper) bounds that a conjunct places on `col`, if it is a range on `col`
+fn maybe_composite_range(
+    conjunct: &Expr,
+    col: &str,
+    data_type: &DataType,
+) -> Option<(Bound<ScalarValue>, Bound<ScalarValue>)> {
+    let scalar = |expr: &Expr| maybe_scalar(expr, data_type).filter(|value| !value.is_null());
+    match conjunct {
+        Expr::BinaryExpr(binary) => {
+            let (column, op, value) = comparison_operands(binary)?;
+            if maybe_column(column)? != col {
+                return None;
+            }
+            let value = scalar(value)?;
+            match op {
+                Operator::Lt => Some((Bound::Unbounded, Bound::Excluded(value))),
+                Operator::LtEq => Some((Bound::Unbounded, Bound::Included(value))),
+                Operator::Gt => Some((Bound::Excluded(value), Bound::Unbounded)),
+                Operator::GtEq => Some((Bound::Included(value), Bound::Unbounded)),
+                _ => None,
+            }
+        }
+        Expr::Between(between) if !between.negated => {
+            if maybe_column(&between.expr)? != col {
+                return None;
+            }
+            Some((
+                Bound::Included(scalar(&between.low)?),
+                Bound::Included(scalar(&between.high)?),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// Match the conjuncts against a composite index
+//
+// Each column of the index, in order, is matched against an equality.  The first column
+// without an equality can then be matched against (at most) one lower and one upper bound.
+fn match_composite<'a>(
+    conjuncts: &[&Expr],
+    index: &'a CompositeIndexInfo,
+) -> Option<CompositeMatch<'a>> {
+    let mut prefix = Vec::new();
+    let mut used = Vec::new();
+    let mut range = None;
+    for (col, data_type) in &index.columns {
+        let eq = conjuncts.iter().enumerate().find_map(|(idx, conjunct)| {
+            maybe_composite_eq(conjunct, col, data_type).map(|value| (idx, value))
+        });
+        if let Some((idx, value)) = eq {
+            prefix.push(value);
+            used.push(idx);
+            continue;
+        }
+        let (mut lower, mut upper) = (Bound::Unbounded, Bound::Unbounded);
+        for (idx, conjunct) in conjuncts.iter().enumerate() {
+            let Some((new_lower, new_upper)) = maybe_composite_range(conjunct, col, data_type)
+            else {
+                continue;
+            };
+            let lower_free = matches!(lower, Bound::Unbounded);
+            let upper_free = matches!(upper, Bound::Unbounded);
+            let lower_ok = lower_free || matches!(new_lower, Bound::Unbounded);
+            let upper_ok = upper_free || matches!(new_upper, Bound::Unbounded);
+            if lower_ok && upper_ok {
+                if !matches!(new_lower, Bound::Unbounded) {
+                    lower = new_lower;
+                }
+                if !matches!(new_upper, Bound::Unbounded) {
+                    upper = new_upper;
+                }
+                used.push(idx);
+            }
+        }
+        if !matches!((&lower, &upper), (Bound::Unbounded, Bound::Unbounded)) {
+            range = Some((lower, upper));
+        }
+        break;
+    }
+    if used.is_empty() {
+        None
+    } else {
+        Some(CompositeMatch {
+            index,
+            prefix,
+            range,
+            used,
+        })
+    }
+}
+
+// Try and satisfy the conjuncts of a conjunction (or a lone filter) with a composite index
+//
+// The index that satisfies the most conjuncts is used and the remaining conjuncts are
+// handled as usual.  If a composite index would only satisfy a single conjunct on a column
+// that has its own index then the composite index is not used.
+fn visit_composite(
+    conjuncts: &[&Expr],
+    index_info: &dyn IndexInformationProvider,
+) -> Option<IndexedExpression> {
+    let indices = index_info.composite_indices();
+    if indices.is_empty() {
+        return None;
+    }
+    let best = indices
+        .iter()
+        .filter_map(|index| match_composite(conjuncts, index))
+        .filter(|matched| {
+            matched.used.len() > 1 || index_info.get_index(&matched.index.columns[0].0).is_none()
+        })
+        .max_by_key(|matched| matched.used.len())?;
+
+    let columns = best
+        .index
+        .columns
+        .iter()
+        .map(|(col, _)| col.clone())
+        .collect::<Vec<_>>();
+    let query = CompositeQuery {
+        columns: columns.clone(),
+        prefix: best.prefix,
+        range: best.range,
+    };
+    let mut indexed_expr = IndexedExpression::index_query(
+        columns[0].clone(),
+        best.index.index_name.clone(),
+        Arc::new(query),
+    );
+    for (idx, conjunct) in conjuncts.iter().enumerate() {
+        if best.used.contains(&idx) {
+            continue;
+        }
+        indexed_expr = match visit_node(conjunct, index_info) {
+            Some(other) => indexed_expr.and(other),
+            None => indexed_expr.refine((*conjunct).clone()),
+        };
+    }
+    Some(indexed_expr)
 }
 
 fn visit_node(expr: &Expr, index_info: &dyn IndexInformationProvider) -> Option<IndexedExpression> {
@@ -1142,6 +1628,7 @@ fn visit_node(expr: &Expr, index_info: &dyn IndexInformationProvider) -> Option<
         Expr::IsTrue(expr) => visit_is_bool(expr.as_ref(), index_info, true),
         Expr::IsNull(expr) => visit_is_null(expr.as_ref(), index_info, false),
         Expr::IsNotNull(expr) => visit_is_null(expr.as_ref(), index_info, true),
+        Expr::Like(like) => visit_like(like, index_info),
         Expr::Not(expr) => visit_not(expr.as_ref(), index_info),
         Expr::BinaryExpr(binary_expr) => visit_binary_expr(binary_expr, index_info),
         Expr::ScalarFunction(scalar_fn) => visit_scalar_fn(scalar_fn, index_info),
@@ -1154,6 +1641,20 @@ pub trait IndexInformationProvider {
     /// Check if an index exists for `col` and, if so, return the data type of col
     /// as well as a query parser that can parse queries for that column
     fn get_index(&self, col: &str) -> Option<(&DataType, &dyn ScalarQueryParser)>;
+
+    /// The indices that span more than one column
+    fn composite_indices(&self) -> &[CompositeIndexInfo] {
+        &[]
+    }
+}
+
+/// A btree index on more than one column
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeIndexInfo {
+    /// The name of the index
+    pub index_name: String,
+    /// The columns of the index (and their data types), in index order
+    pub columns: Vec<(String, DataType)>,
 }
 
 /// Attempt to split a filter expression into a search of scalar indexes and an
@@ -1162,7 +1663,12 @@ pub fn apply_scalar_indices(
     expr: Expr,
     index_info: &dyn IndexInformationProvider,
 ) -> IndexedExpression {
-    visit_node(&expr, index_info).unwrap_or(IndexedExpression::refine_only(expr))
+    // Conjunctions are matched against the composite indices by visit_and
+    let indexed_expr = match &expr {
+        Expr::BinaryExpr(binary) if binary.op == Operator::And => visit_node(&expr, index_info),
+        _ => visit_composite(&[&expr], index_info).or_else(|| visit_node(&expr, index_info)),
+    };
+    indexed_expr.unwrap_or(IndexedExpression::refine_only(expr))
 }
 
 #[derive(Clone, Default, Debug)]
@@ -1275,6 +1781,7 @@ mod tests {
 
     struct MockIndexInfoProvider {
         indexed_columns: HashMap<String, ColInfo>,
+        composite_indices: Vec<CompositeIndexInfo>,
     }
 
     impl MockIndexInfoProvider {
@@ -1285,6 +1792,7 @@ mod tests {
                         .into_iter()
                         .map(|(s, ty)| (s.to_string(), ty)),
                 ),
+                composite_indices: Vec::new(),
             }
         }
     }
@@ -1295,6 +1803,10 @@ mod tests {
                 .get(col)
                 .map(|col_info| (&col_info.data_type, col_info.parser.as_ref()))
         }
+
+        fn composite_indices(&self) -> &[CompositeIndexInfo] {
+            &self.composite_indices
+        }
     }
 
     fn check(
@@ -1558,10 +2070,16 @@ mod tests {
                 Bound::Unbounded,
             ),
         );
-        // In the future we can handle this case if we need to.  For
-        // now let's make sure we don't accidentally do the wrong thing
-        // (we were getting this backwards in the past)
-        check_no_index(&index_info, "10 > aisle");
+        // The operands are swapped, make sure we don't get this backwards
+        check_simple(
+            &index_info,
+            "10 > aisle",
+            "aisle",
+            SargableQuery::Range(
+                Bound::Unbounded,
+                Bound::Excluded(ScalarValue::UInt32(Some(10))),
+            ),
+        );
         check_simple(
             &index_info,
             "aisle >= 10",
@@ -1588,6 +2106,7 @@ mod tests {
             column: "aisle".to_string(),
             index_name: "aisle_idx".to_string(),
             query: Arc::new(SargableQuery::Equals(ScalarValue::UInt32(Some(10)))),
+            needs_recheck: false,
         }));
         let right = Box::new(ScalarIndexExpr::Query(ScalarIndexSearch {
             column: "color".to_string(),
@@ -1595,6 +2114,7 @@ mod tests {
             query: Arc::new(SargableQuery::Equals(ScalarValue::Utf8(Some(
                 "blue".to_string(),
             )))),
+            needs_recheck: false,
         }));
         check(
             &index_info,
@@ -1645,4 +2165,427 @@ mod tests {
         // Non-normalized arithmetic (can use expression simplification)
         check_no_index(&index_info, "aisle + 3 < 10")
     }
+
+    #[test]
+    fn test_nested_expressions() {
+        let tag_fields = vec![Field::new("name", DataType::Utf8, true)];
+        let schema = Schema::new(vec![
+            Field::new(
+                "st",
+                DataType::Struct(vec![Field::new("s1", DataType::Utf8, true)].into()),
+                true,
+            ),
+            Field::new(
+                "tags",
+                DataType::List(Arc::new(Field::new(
+                    "item",
+                    DataType::Struct(tag_fields.into()),
+                    true,
+                ))),
+                true,
+            ),
+        ]);
+        let planner = Planner::new(Arc::new(schema));
+        let index_info = MockIndexInfoProvider::new(vec![
+            (
+                "st.s1",
+                ColInfo::new(
+                    DataType::Utf8,
+                    Box::new(SargableQueryParser::new("s1_idx".to_string())),
+                ),
+            ),
+            (
+                "tags.name",
+                ColInfo::new(
+                    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+                    Box::new(LabelListQueryParser::new("tags_idx".to_string())),
+                ),
+            ),
+        ]);
+
+        let expr = planner.parse_filter("st.s1 = 'x'").unwrap();
+        assert_eq!(
+            apply_scalar_indices(expr, &index_info),
+            IndexedExpression::index_query(
+                "st.s1".to_string(),
+                "s1_idx".to_string(),
+                Arc::new(SargableQuery::Equals(ScalarValue::Utf8(Some(
+                    "x".to_string()
+                )))),
+            )
+        );
+
+        let expr = planner.parse_filter("array_has(tags.name, 'x')").unwrap();
+        assert_eq!(
+            apply_scalar_indices(expr, &index_info),
+            IndexedExpression::index_query(
+                "tags.name".to_string(),
+                "tags_idx".to_string(),
+                Arc::new(LabelListQuery::HasAnyLabel(vec![ScalarValue::Utf8(Some(
+                    "x".to_string()
+                ))])),
+            )
+        );
+    }
+
+    #[test]
+    fn test_bloom_filter_expressions() {
+        let index_info = MockIndexInfoProvider::new(vec![(
+            "color",
+            ColInfo::new(
+                DataType::Utf8,
+                Box::new(
+                    SargableQueryParser::new("color_idx".to_string())
+                        .with_recheck()
+                        .with_equality_only(),
+                ),
+            ),
+        )]);
+
+        let expected = |query: SargableQuery| {
+            let expected = IndexedExpression {
+                scalar_query: Some(ScalarIndexExpr::Query(ScalarIndexSearch {
+                    column: "color".to_string(),
+                    index_name: "color_idx".to_string(),
+                    query: Arc::new(query),
+                    needs_recheck: true,
+                })),
+                refine_expr: None,
+            };
+            assert!(expected.scalar_query.as_ref().unwrap().needs_recheck());
+            Some(expected)
+        };
+        check(
+            &index_info,
+            "color = 'blue'",
+            expected(SargableQuery::Equals(ScalarValue::Utf8(Some(
+                "blue".to_string(),
+            )))),
+        );
+        check(
+            &index_info,
+            "color IN ('blue', 'red')",
+            expected(SargableQuery::IsIn(vec![
+                ScalarValue::Utf8(Some("blue".to_string())),
+                ScalarValue::Utf8(Some("red".to_string())),
+            ])),
+        );
+        // Bloom filters can't answer range queries
+        check_no_index(&index_info, "color > 'blue'");
+        check_no_index(&index_info, "color != 'blue'");
+        check_no_index(&index_info, "color LIKE 'bl%'");
+        check_no_index(&index_info, "color IS NULL");
+    }
+
+    #[test]
+    fn test_prefix_range() {
+        let utf8 = |value: &str| ScalarValue::Utf8(Some(value.to_string()));
+        let binary = |value: &[u8]| ScalarValue::Binary(Some(value.to_vec()));
+
+        let range = |lower: ScalarValue, upper: Option<ScalarValue>| {
+            Some(SargableQuery::Range(
+                Bound::Included(lower),
+                upper.map(Bound::Excluded).unwrap_or(Bound::Unbounded),
+            ))
+        };
+        assert_eq!(
+            prefix_range(utf8("abc")),
+            range(utf8("abc"), Some(utf8("abd")))
+        );
+        // The empty prefix matches everything
+        assert_eq!(prefix_range(utf8("")), range(utf8(""), None));
+        // The max char cannot be incremented so we carry into the previous char
+        assert_eq!(
+            prefix_range(utf8("a\u{10FFFF}")),
+            range(utf8("a\u{10FFFF}"), Some(utf8("b")))
+        );
+        assert_eq!(
+            prefix_range(utf8("\u{10FFFF}")),
+            range(utf8("\u{10FFFF}"), None)
+        );
+        assert_eq!(
+            prefix_range(utf8("\u{D7FF}")),
+            range(utf8("\u{D7FF}"), Some(utf8("\u{E000}")))
+        );
+
+        assert_eq!(
+            prefix_range(binary(&[1, 2])),
+            range(binary(&[1, 2]), Some(binary(&[1, 3])))
+        );
+        assert_eq!(
+            prefix_range(binary(&[1, 0xFF])),
+            range(binary(&[1, 0xFF]), Some(binary(&[2])))
+        );
+        assert_eq!(prefix_range(binary(&[0xFF])), range(binary(&[0xFF]), None));
+
+        assert_eq!(prefix_range(ScalarValue::Int32(Some(1))), None);
+    }
+
+    #[test]
+    fn test_prefix_expressions() {
+        let index_info = MockIndexInfoProvider::new(vec![(
+            "color",
+            ColInfo::new(
+                DataType::Utf8,
+                Box::new(SargableQueryParser::new("color_idx".to_string())),
+            ),
+        )]);
+
+        let utf8 = |value: &str| ScalarValue::Utf8(Some(value.to_string()));
+        let prefix = |lower: &str, upper: &str| {
+            SargableQuery::Range(Bound::Included(utf8(lower)), Bound::Excluded(utf8(upper)))
+        };
+
+        check_simple(
+            &index_info,
+            "starts_with(color, 'ab')",
+            "color",
+            prefix("ab", "ac"),
+        );
+        check_simple(&index_info, "color LIKE 'ab%'", "color", prefix("ab", "ac"));
+        check_simple(
+            &index_info,
+            "color LIKE 'ab%%'",
+            "color",
+            prefix("ab", "ac"),
+        );
+        check_simple(
+            &index_info,
+            "color LIKE 'ab!%%' ESCAPE '!'",
+            "color",
+            prefix("ab%", "ab&"),
+        );
+        check_simple(
+            &index_info,
+            "color LIKE '%'",
+            "color",
+            SargableQuery::Range(Bound::Included(utf8("")), Bound::Unbounded),
+        );
+        check_simple(
+            &index_info,
+            "color LIKE 'abc'",
+            "color",
+            SargableQuery::Equals(utf8("abc")),
+        );
+        check_simple_negated(
+            &index_info,
+            "color NOT LIKE 'ab%'",
+            "color",
+            prefix("ab", "ac"),
+        );
+        // These patterns are not contiguous ranges
+        check_no_index(&index_info, "color LIKE 'a_c%'");
+        check_no_index(&index_info, "color LIKE 'ab%c'");
+        check_no_index(&index_info, "color ILIKE 'ab%'");
+    }
+
+    #[test]
+    fn test_text_expressions() {
+        let index_info = MockIndexInfoProvider::new(vec![(
+            "color",
+            ColInfo::new(
+                DataType::Utf8,
+                Box::new(TextQueryParser::new("color_idx".to_string())),
+            ),
+        )]);
+
+        let expected = |query: TextQuery| {
+            Some(IndexedExpression::index_query(
+                "color".to_string(),
+                "color_idx".to_string(),
+                Arc::new(query),
+            ))
+        };
+        check(
+            &index_info,
+            "contains(color, 'blue')",
+            expected(TextQuery::StringContains("blue".to_string())),
+        );
+        check(
+            &index_info,
+            "color LIKE 'abc%def'",
+            expected(TextQuery::Like {
+                pattern: "abc%def".to_string(),
+                escape_char: None,
+                case_insensitive: false,
+            }),
+        );
+        check(
+            &index_info,
+            "color ILIKE '%blue%'",
+            expected(TextQuery::Like {
+                pattern: "%blue%".to_string(),
+                escape_char: None,
+                case_insensitive: true,
+            }),
+        );
+        check(
+            &index_info,
+            "color NOT LIKE 'abc%'",
+            expected(TextQuery::Like {
+                pattern: "abc%".to_string(),
+                escape_char: None,
+                case_insensitive: false,
+            })
+            .unwrap()
+            .maybe_not(),
+        );
+        check(
+            &index_info,
+            "regexp_like(color, 'bl(ue|ack)')",
+            expected(TextQuery::Regex("bl(ue|ack)".to_string())),
+        );
+        check(
+            &index_info,
+            "color ~ 'bl(ue|ack)'",
+            expected(TextQuery::Regex("bl(ue|ack)".to_string())),
+        );
+        check(
+            &index_info,
+            "color ~* 'blue'",
+            expected(TextQuery::Regex("(?i)blue".to_string())),
+        );
+        check(
+            &index_info,
+            "color !~ 'blue'",
+            expected(TextQuery::Regex("blue".to_string()))
+                .unwrap()
+                .maybe_not(),
+        );
+        // The pattern must be a literal
+        check_no_index(&index_info, "color LIKE color");
+    }
+
+    #[test]
+    fn test_zone_map_expressions() {
+        let index_info = MockIndexInfoProvider::new(vec![(
+            "size",
+            ColInfo::new(
+                DataType::Int32,
+                Box::new(SargableQueryParser::new("size_idx".to_string()).with_recheck()),
+            ),
+        )]);
+
+        let expected = |query: SargableQuery| {
+            let expected = IndexedExpression {
+                scalar_query: Some(ScalarIndexExpr::Query(ScalarIndexSearch {
+                    column: "size".to_string(),
+                    index_name: "size_idx".to_string(),
+                    query: Arc::new(query),
+                    needs_recheck: true,
+                })),
+                refine_expr: None,
+            };
+            assert!(expected.scalar_query.as_ref().unwrap().needs_recheck());
+            Some(expected)
+        };
+        check(
+            &index_info,
+            "size BETWEEN 5 AND 10",
+            expected(SargableQuery::Range(
+                Bound::Included(ScalarValue::Int32(Some(5))),
+                Bound::Included(ScalarValue::Int32(Some(10))),
+            )),
+        );
+        check(
+            &index_info,
+            "size < 5",
+            expected(SargableQuery::Range(
+                Bound::Unbounded,
+                Bound::Excluded(ScalarValue::Int32(Some(5))),
+            )),
+        );
+        check(
+            &index_info,
+            "size = 5",
+            expected(SargableQuery::Equals(ScalarValue::Int32(Some(5)))),
+        );
+        check(
+            &index_info,
+            "size IS NULL",
+            expected(SargableQuery::IsNull()),
+        );
+    }
+
+    #[test]
+    fn test_composite_expressions() {
+        let mut index_info = MockIndexInfoProvider::new(vec![(
+            "color",
+            ColInfo::new(
+                DataType::Utf8,
+                Box::new(SargableQueryParser::new("color_idx".to_string())),
+            ),
+        )]);
+        index_info.composite_indices = vec![CompositeIndexInfo {
+            index_name: "color_aisle_idx".to_string(),
+            columns: vec![
+                ("color".to_string(), DataType::Utf8),
+                ("aisle".to_string(), DataType::UInt32),
+            ],
+        }];
+        let composite = |prefix: Vec<ScalarValue>, range| {
+            IndexedExpression::index_query(
+                "color".to_string(),
+                "color_aisle_idx".to_string(),
+                Arc::new(CompositeQuery {
+                    columns: vec!["color".to_string(), "aisle".to_string()],
+                    prefix,
+                    range,
+                }),
+            )
+        };
+        let blue = || ScalarValue::Utf8(Some("blue".to_string()));
+        let aisle = |value: u32| ScalarValue::UInt32(Some(value));
+
+        // Equality on the prefix and a range on the next column
+        check(
+            &index_info,
+            "color = 'blue' AND aisle > 5 AND aisle <= 10",
+            Some(composite(
+                vec![blue()],
+                Some((Bound::Excluded(aisle(5)), Bound::Included(aisle(10)))),
+            )),
+        );
+        check(
+            &index_info,
+            "aisle BETWEEN 5 AND 10 AND color = 'blue'",
+            Some(composite(
+                vec![blue()],
+                Some((Bound::Included(aisle(5)), Bound::Included(aisle(10)))),
+            )),
+        );
+        // The column may be on either side of a comparison
+        check(
+            &index_info,
+            "5 < aisle AND 'blue' = color AND 10 >= aisle",
+            Some(composite(
+                vec![blue()],
+                Some((Bound::Excluded(aisle(5)), Bound::Included(aisle(10)))),
+            )),
+        );
+        // Equality on every column, other conjuncts are refined
+        let refine = Expr::Column(Column::new_unqualified("size")).gt(datafusion_expr::lit(30_i64));
+        check(
+            &index_info,
+            "aisle = 3 AND size > 30 AND color = 'blue'",
+            Some(composite(vec![blue(), aisle(3)], None).refine(refine)),
+        );
+        // A single conjunct prefers the single column index
+        check_simple(
+            &index_info,
+            "color = 'blue'",
+            "color",
+            SargableQuery::Equals(blue()),
+        );
+        check_simple(
+            &index_info,
+            "'blue' = color",
+            "color",
+            SargableQuery::Equals(blue()),
+        );
+        // The leading column of the index must be constrained
+        check_no_index(&index_info, "aisle = 3");
+        // The index cannot satisfy part of a disjunction
+        check_no_index(&index_info, "color = 'blue' OR aisle = 3");
+    }
 }
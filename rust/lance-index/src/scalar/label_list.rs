@@ -328,7 +328,10 @@ impl TrainingSource for UnnestTrainingSource {
     }
 }
 
-fn unnest_chunks(
+/// Flattens a stream whose first column is a list into a stream of the list items
+///
+/// The remaining columns (e.g. row ids) are repeated for each item of the list
+pub fn unnest_chunks(
     source: Pin<Box<dyn RecordBatchStream + Send>>,
 ) -> Result<SendableRecordBatchStream> {
     let unnest_schema = unnest_schema(source.schema().as_ref());
@@ -3,7 +3,7 @@
 
 use std::any::Any;
 use std::collections::BTreeMap;
-use std::iter::once;
+use std::iter::{once, Peekable};
 use std::time::Instant;
 use std::{collections::HashMap, sync::Arc};
 
@@ -17,7 +17,7 @@ use crate::frag_reuse::FragReuseIndex;
 use crate::metrics::NoOpMetricsCollector;
 use crate::scalar::inverted::CACHE_SIZE;
 use crate::vector::VectorIndex;
-use crate::{Index, IndexType};
+use crate::{Index, IndexParams, IndexType};
 use arrow::array::{AsArray, UInt32Builder};
 use arrow::datatypes::{UInt32Type, UInt64Type};
 use arrow_array::{BinaryArray, RecordBatch, UInt32Array};
@@ -39,7 +39,7 @@ use log::info;
 use moka::future::Cache;
 use object_store::path::Path;
 use roaring::{RoaringBitmap, RoaringTreemap};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use snafu::location;
 use tantivy::tokenizer::TextAnalyzer;
 use tempfile::{tempdir, TempDir};
@@ -48,6 +48,7 @@ use tracing::instrument;
 const TOKENS_COL: &str = "tokens";
 const POSTING_LIST_COL: &str = "posting_list";
 const POSTINGS_FILENAME: &str = "ngram_postings.lance";
+const PARAMS_META_KEY: &str = "params";
 
 lazy_static::lazy_static! {
     pub static ref TOKENS_FIELD: Field = Field::new(TOKENS_COL, DataType::UInt32, true);
@@ -57,14 +58,22 @@ lazy_static::lazy_static! {
         .filter(tantivy::tokenizer::LowerCaser)
         .filter(tantivy::tokenizer::AsciiFoldingFilter)
         .build();
-    /// Currently we ALWAYS use trigrams with ascii folding and lower casing.  We may want to make this configurable in the future.
+    pub static ref CASE_SENSITIVE_TEXT_PREPPER: TextAnalyzer = TextAnalyzer::builder(tantivy::tokenizer::RawTokenizer::default())
+        .filter(tantivy::tokenizer::AsciiFoldingFilter)
+        .build();
+    /// Currently we ALWAYS use trigrams with ascii folding.  Lower casing is controlled by [`NGramIndexParams`].
     pub static ref NGRAM_TOKENIZER: TextAnalyzer = TextAnalyzer::builder(tantivy::tokenizer::NgramTokenizer::all_ngrams(3, 3).unwrap())
         .filter(tantivy::tokenizer::AlphaNumOnlyFilter)
         .build();
 }
 
 // Helper function to apply a function to each token in a text
-fn tokenize_visitor(tokenizer: &TextAnalyzer, text: &str, mut visitor: impl FnMut(&String)) {
+fn tokenize_visitor(
+    prepper: &TextAnalyzer,
+    tokenizer: &TextAnalyzer,
+    text: &str,
+    mut visitor: impl FnMut(&String),
+) {
     // The token_stream method is mutable.  As far as I can tell this is to enforce exclusivity and not
     // true mutability.  For example, the object returned by `token_stream` has thread-local state but
     // it is reset each time `token_stream` is called.
@@ -72,7 +81,7 @@ fn tokenize_visitor(tokenizer: &TextAnalyzer, text: &str, mut visitor: impl FnMu
     // However, I don't see this documented anywhere and I'm not sure about relying on it.  For now, we
     // make a clone as that seems to be the safer option.  All the tokenizers we use here should be trivially
     // cloneable (although it requires a heap allocation so may be worth investigating in the future)
-    let mut prepper = TEXT_PREPPER.clone();
+    let mut prepper = prepper.clone();
     let mut tokenizer = tokenizer.clone();
     let mut raw_stream = prepper.token_stream(text);
     while raw_stream.advance() {
@@ -84,15 +93,16 @@ fn tokenize_visitor(tokenizer: &TextAnalyzer, text: &str, mut visitor: impl FnMu
 }
 
 const ALPHA_SPAN: usize = 37;
-const MAX_TOKEN: usize = ALPHA_SPAN.pow(2) + ALPHA_SPAN;
+const CASE_SENSITIVE_ALPHA_SPAN: usize = 63;
 const MIN_TOKEN: usize = 0;
 const NGRAM_N: usize = 3;
 
 // Convert an ngram (string) to a token (u32).  This helps avoid heap allocations
 // and it makes it easier to partition the tokens for shuffling
 //
-// There are 36 alphanumeric values and we add 1 for the NULL token giving us 37^3
-// potential tokens.
+// There are 36 lower case alphanumeric values and we add 1 for the NULL token giving us 37^3
+// potential tokens.  Case sensitive indices add 26 upper case values giving us 63^3 potential
+// tokens.  Lower case ngrams map to the same token in both cases.
 //
 // "" => 0
 // "?" => 37^2 * ?
@@ -108,24 +118,209 @@ const NGRAM_N: usize = 3;
 //
 // NOTE: Today we hard-code trigrams and we do not include 1-grams or 2-grams so this
 // function is more general than it needs to be...just in case.
-fn ngram_to_token(ngram: &str, ngram_length: usize) -> u32 {
+fn ngram_to_token(ngram: &str, ngram_length: usize, alpha_span: usize) -> u32 {
     let mut token = 0;
     // Empty string will get 0
     for (idx, byte) in ngram.bytes().enumerate() {
-        let pos = if byte <= b'9' {
-            byte - b'0'
-        } else if byte <= b'z' {
-            byte - b'a' + 10
-        } else {
-            unreachable!()
+        let pos = match byte {
+            b'0'..=b'9' => byte - b'0',
+            b'a'..=b'z' => byte - b'a' + 10,
+            b'A'..=b'Z' => byte - b'A' + 36,
+            _ => unreachable!(),
         } + 1;
-        debug_assert!(pos < ALPHA_SPAN as u8);
-        let mult = ALPHA_SPAN.pow(ngram_length as u32 - idx as u32 - 1) as u32;
+        debug_assert!(pos < alpha_span as u8);
+        let mult = alpha_span.pow(ngram_length as u32 - idx as u32 - 1) as u32;
         token += pos as u32 * mult;
     }
     token
 }
 
+/// Parameters of an ngram index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NGramIndexParams {
+    /// Whether text is lower cased before it is split into ngrams
+    ///
+    /// A lower cased index can answer both case sensitive and case insensitive (ILIKE)
+    /// searches.  A case sensitive index is more selective for case sensitive searches
+    /// but cannot help with case insensitive searches.
+    pub lower_case: bool,
+}
+
+impl Default for NGramIndexParams {
+    fn default() -> Self {
+        Self { lower_case: true }
+    }
+}
+
+impl NGramIndexParams {
+    pub fn with_lower_case(mut self, lower_case: bool) -> Self {
+        self.lower_case = lower_case;
+        self
+    }
+}
+
+impl IndexParams for NGramIndexParams {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn index_type(&self) -> IndexType {
+        IndexType::NGram
+    }
+
+    fn index_name(&self) -> &str {
+        "NGRAM"
+    }
+}
+
+/// Splits text into ngram tokens according to the index parameters
+#[derive(Clone)]
+struct NGramTokenizer {
+    prepper: TextAnalyzer,
+    tokenizer: TextAnalyzer,
+    alpha_span: usize,
+}
+
+impl NGramTokenizer {
+    fn new(params: &NGramIndexParams) -> Self {
+        if params.lower_case {
+            Self {
+                prepper: TEXT_PREPPER.clone(),
+                tokenizer: NGRAM_TOKENIZER.clone(),
+                alpha_span: ALPHA_SPAN,
+            }
+        } else {
+            Self {
+                prepper: CASE_SENSITIVE_TEXT_PREPPER.clone(),
+                tokenizer: NGRAM_TOKENIZER.clone(),
+                alpha_span: CASE_SENSITIVE_ALPHA_SPAN,
+            }
+        }
+    }
+
+    fn max_token(&self) -> usize {
+        self.alpha_span.pow(2) + self.alpha_span
+    }
+
+    fn visit_tokens(&self, text: &str, mut visitor: impl FnMut(u32)) {
+        tokenize_visitor(&self.prepper, &self.tokenizer, text, |ngram| {
+            visitor(ngram_to_token(ngram, NGRAM_N, self.alpha_span))
+        });
+    }
+}
+
+// Splits a LIKE pattern into the literal runs between wildcards.  Every one of these
+// runs must appear in a matching string.
+fn like_literals(pattern: &str, escape_char: Option<char>) -> Vec<String> {
+    let escape_char = escape_char.unwrap_or('\\');
+    let mut literals = Vec::new();
+    let mut current = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == escape_char {
+            if let Some(escaped) = chars.next() {
+                current.push(escaped);
+            }
+        } else if c == '%' || c == '_' {
+            literals.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+    }
+    literals.push(current);
+    literals.retain(|literal| !literal.is_empty());
+    literals
+}
+
+// Skips past the closing `]` of a character class (the opening `[` has already been
+// consumed).  A `]` at the start of the class (e.g. `[]a]` or `[^]a]`) is a literal and
+// nested classes (e.g. `[[:alpha:]]`) are respected.
+fn skip_regex_class<I: Iterator<Item = char>>(chars: &mut Peekable<I>) {
+    chars.next_if_eq(&'^');
+    chars.next_if_eq(&']');
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => skip_regex_class(chars),
+            ']' => return,
+            _ => {}
+        }
+    }
+}
+
+// Skips past the closing `)` of a group (the opening `(` has already been consumed).
+// Escaped characters and character classes are skipped and nesting is respected.
+fn skip_regex_group<I: Iterator<Item = char>>(chars: &mut Peekable<I>) {
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '(' => skip_regex_group(chars),
+            '[' => skip_regex_class(chars),
+            ')' => return,
+            _ => {}
+        }
+    }
+}
+
+// Extracts literal runs that must appear in any string matching a regular expression
+//
+// This is deliberately simple and conservative.  Groups, classes, and anything else that
+// is not a plain literal are skipped.  Patterns with flags or top-level alternation yield
+// no literals at all.
+fn regex_literals(pattern: &str) -> Vec<String> {
+    if pattern.contains("(?") {
+        return Vec::new();
+    }
+    let mut literals = Vec::new();
+    let mut current = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                // Hex and unicode escapes are not worth decoding
+                Some('x' | 'p' | 'P' | 'u' | 'U') => return Vec::new(),
+                // Character classes and assertions (e.g. \d, \b)
+                Some(escaped) if escaped.is_ascii_alphanumeric() => {
+                    literals.push(std::mem::take(&mut current));
+                }
+                Some(escaped) => current.push(escaped),
+                None => {}
+            },
+            '|' => return Vec::new(),
+            '(' => {
+                literals.push(std::mem::take(&mut current));
+                skip_regex_group(&mut chars);
+            }
+            '[' => {
+                literals.push(std::mem::take(&mut current));
+                skip_regex_class(&mut chars);
+            }
+            // The preceding character may not appear at all
+            '?' | '*' => {
+                current.pop();
+                literals.push(std::mem::take(&mut current));
+            }
+            '{' => {
+                current.pop();
+                literals.push(std::mem::take(&mut current));
+                // Repetition counts can't contain a `}`
+                chars.find(|c| *c == '}');
+            }
+            '+' | '.' | '^' | '$' => {
+                literals.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    literals.push(current);
+    literals.retain(|literal| !literal.is_empty());
+    literals
+}
+
 /// Basic stats about an ngram index
 #[derive(Serialize)]
 struct NGramStatistics {
@@ -241,7 +436,8 @@ pub struct NGramIndex {
     /// example, a stemming tokenizer would not work well because "dozing" would stem to "doze" and if the
     /// search term is "zing" it would not match.  As a result, this tokenizer is not as configurable as the
     /// tokenizers used in an inverted index.
-    tokenizer: TextAnalyzer,
+    tokenizer: NGramTokenizer,
+    params: NGramIndexParams,
     io_parallelism: usize,
     /// The store that owns the index
     store: Arc<dyn IndexStore>,
@@ -267,9 +463,14 @@ impl NGramIndex {
         store: Arc<dyn IndexStore>,
         fri: Option<Arc<FragReuseIndex>>,
     ) -> Result<Self> {
-        let tokens = store.open_index_file(POSTINGS_FILENAME).await?;
-        let tokens = tokens
-            .read_range(0..tokens.num_rows(), Some(&[TOKENS_COL]))
+        let reader = store.open_index_file(POSTINGS_FILENAME).await?;
+        // Indices created before the parameters were introduced are always lower cased
+        let params = match reader.schema().metadata.get(PARAMS_META_KEY) {
+            Some(params) => serde_json::from_str::<NGramIndexParams>(params)?,
+            None => NGramIndexParams::default(),
+        };
+        let tokens = reader
+            .read_range(0..reader.num_rows(), Some(&[TOKENS_COL]))
             .await?;
 
         let tokens_map = HashMap::from_iter(
@@ -284,7 +485,7 @@ impl NGramIndex {
         );
 
         let posting_reader = Arc::new(NGramPostingListReader {
-            reader: store.open_index_file(POSTINGS_FILENAME).await?,
+            reader,
             cache: Cache::builder()
                 .max_capacity(*CACHE_SIZE as u64)
                 .weigher(|_, posting: &Arc<NGramPostingList>| posting.deep_size_of() as u32)
@@ -296,7 +497,8 @@ impl NGramIndex {
             io_parallelism: store.io_parallelism(),
             tokens: tokens_map,
             list_reader: posting_reader,
-            tokenizer: NGRAM_TOKENIZER.clone(),
+            tokenizer: NGramTokenizer::new(&params),
+            params,
             store,
         })
     }
@@ -410,41 +612,53 @@ impl ScalarIndex for NGramIndex {
                     source: "Query is not a TextQuery".into(),
                     location: location!(),
                 })?;
-        match query {
-            TextQuery::StringContains(substr) => {
-                if substr.len() < NGRAM_N {
-                    // We know nothing on short searches, need to recheck all
-                    return Ok(SearchResult::AtLeast(RowIdTreeMap::new()));
-                }
+        let (literals, case_insensitive) = match query {
+            TextQuery::StringContains(substr) => (vec![substr.clone()], false),
+            TextQuery::Like {
+                pattern,
+                escape_char,
+                case_insensitive,
+            } => (like_literals(pattern, *escape_char), *case_insensitive),
+            TextQuery::Regex(pattern) => (regex_literals(pattern), false),
+        };
+        if case_insensitive && !self.params.lower_case {
+            // A case sensitive index knows nothing about case insensitive searches
+            return Ok(SearchResult::AtLeast(RowIdTreeMap::new()));
+        }
 
-                let mut row_offsets = Vec::with_capacity(substr.len() * 3);
-                let mut missing = false;
-                tokenize_visitor(&self.tokenizer, substr, |ngram| {
-                    let token = ngram_to_token(ngram, NGRAM_N);
-                    if let Some(row_offset) = self.tokens.get(&token) {
-                        row_offsets.push(*row_offset);
-                    } else {
-                        missing = true;
-                    }
-                });
-                // At least one token was missing, so we know there are zero results
-                if missing {
-                    return Ok(SearchResult::Exact(RowIdTreeMap::new()));
+        let mut row_offsets = Vec::new();
+        let mut missing = false;
+        for literal in &literals {
+            self.tokenizer.visit_tokens(literal, |token| {
+                if let Some(row_offset) = self.tokens.get(&token) {
+                    row_offsets.push(*row_offset);
+                } else {
+                    missing = true;
                 }
-                let posting_lists = futures::stream::iter(
-                    row_offsets
-                        .into_iter()
-                        .map(|row_offset| self.list_reader.ngram_list(row_offset, metrics)),
-                )
-                .buffer_unordered(self.io_parallelism)
-                .try_collect::<Vec<_>>()
-                .await?;
-                metrics.record_comparisons(posting_lists.len());
-                let list_refs = posting_lists.iter().map(|list| list.as_ref());
-                let row_ids = NGramPostingList::intersect(list_refs);
-                Ok(SearchResult::AtMost(RowIdTreeMap::from(row_ids)))
-            }
+            });
+        }
+        // At least one token was missing, so we know there are zero results
+        if missing {
+            return Ok(SearchResult::Exact(RowIdTreeMap::new()));
         }
+        // We know nothing on short searches, need to recheck all
+        if row_offsets.is_empty() {
+            return Ok(SearchResult::AtLeast(RowIdTreeMap::new()));
+        }
+        row_offsets.sort_unstable();
+        row_offsets.dedup();
+        let posting_lists = futures::stream::iter(
+            row_offsets
+                .into_iter()
+                .map(|row_offset| self.list_reader.ngram_list(row_offset, metrics)),
+        )
+        .buffer_unordered(self.io_parallelism)
+        .try_collect::<Vec<_>>()
+        .await?;
+        metrics.record_comparisons(posting_lists.len());
+        let list_refs = posting_lists.iter().map(|list| list.as_ref());
+        let row_ids = NGramPostingList::intersect(list_refs);
+        Ok(SearchResult::AtMost(RowIdTreeMap::from(row_ids)))
     }
 
     fn can_answer_exact(&self, _: &dyn AnyQuery) -> bool {
@@ -479,7 +693,9 @@ impl ScalarIndex for NGramIndex {
             offset += BATCH_SIZE;
         }
 
-        writer.finish().await
+        writer
+            .finish_with_metadata(params_metadata(&self.params)?)
+            .await
     }
 
     async fn update(
@@ -487,7 +703,8 @@ impl ScalarIndex for NGramIndex {
         new_data: SendableRecordBatchStream,
         dest_store: &dyn IndexStore,
     ) -> Result<()> {
-        let mut builder = NGramIndexBuilder::try_new(NGramIndexBuilderOptions::default())?;
+        let mut builder = NGramIndexBuilder::try_new(NGramIndexBuilderOptions::default())?
+            .with_params(self.params.clone());
         let spill_files = builder.train(new_data).await?;
 
         builder
@@ -613,7 +830,8 @@ impl NGramIndexBuildState {
 /// Once all the data is processed we spill all the parititons to disk and then we merge the
 /// spill files into a single index file.
 pub struct NGramIndexBuilder {
-    tokenizer: TextAnalyzer,
+    tokenizer: NGramTokenizer,
+    params: NGramIndexParams,
     options: NGramIndexBuilderOptions,
     tmpdir: Arc<TempDir>,
     spill_store: Arc<dyn IndexStore>,
@@ -636,6 +854,7 @@ impl NGramIndexBuilder {
         bitmaps.push(RoaringTreemap::new());
         Self {
             tokenizer: self.tokenizer.clone(),
+            params: self.params.clone(),
             state: NGramIndexBuildState::starting(),
             tmpdir: self.tmpdir.clone(),
             spill_store: self.spill_store.clone(),
@@ -647,7 +866,8 @@ impl NGramIndexBuilder {
     }
 
     fn from_state(state: NGramIndexBuildState, options: NGramIndexBuilderOptions) -> Result<Self> {
-        let tokenizer = NGRAM_TOKENIZER.clone();
+        let params = NGramIndexParams::default();
+        let tokenizer = NGramTokenizer::new(&params);
 
         let tmpdir = Arc::new(tempdir()?);
         let spill_store = Arc::new(LanceIndexStore::new(
@@ -658,6 +878,7 @@ impl NGramIndexBuilder {
 
         Ok(Self {
             tokenizer,
+            params,
             state,
             tmpdir,
             spill_store,
@@ -668,6 +889,13 @@ impl NGramIndexBuilder {
         })
     }
 
+    /// Sets the parameters of the index being built
+    pub fn with_params(mut self, params: NGramIndexParams) -> Self {
+        self.tokenizer = NGramTokenizer::new(&params);
+        self.params = params;
+        self
+    }
+
     fn validate_schema(schema: &Schema) -> Result<()> {
         if schema.fields().len() != 2 {
             return Err(Error::InvalidInput {
@@ -777,7 +1005,7 @@ impl NGramIndexBuilder {
     }
 
     fn tokenize_and_partition(
-        tokenizer: &TextAnalyzer,
+        tokenizer: &NGramTokenizer,
         batch: RecordBatch,
         num_workers: usize,
     ) -> Vec<Vec<(u32, u64)>> {
@@ -785,11 +1013,10 @@ impl NGramIndexBuilder {
         let row_id_col = batch.column(1).as_primitive::<UInt64Type>();
         // Guessing 1000 tokens per row to at least avoid some of the earlier allocations
         let mut partitions = vec![Vec::with_capacity(batch.num_rows() * 1000); num_workers];
-        let divisor = (MAX_TOKEN - MIN_TOKEN) / num_workers;
+        let divisor = (tokenizer.max_token() - MIN_TOKEN) / num_workers;
         for (text, row_id) in text_iter.zip(row_id_col.values()) {
             if let Some(text) = text {
-                tokenize_visitor(tokenizer, text, |token| {
-                    let token = ngram_to_token(token, NGRAM_N);
+                tokenizer.visit_tokens(text, |token| {
                     let partition_id = (token as usize).saturating_sub(MIN_TOKEN) / divisor;
                     partitions[partition_id % num_workers].push((token, *row_id));
                 });
@@ -1133,7 +1360,9 @@ impl NGramIndexBuilder {
                 let mut writer = store
                     .new_index_file(POSTINGS_FILENAME, POSTINGS_SCHEMA.clone())
                     .await?;
-                writer.finish().await?;
+                writer
+                    .finish_with_metadata(params_metadata(&self.params)?)
+                    .await?;
             }
             return Ok(());
         }
@@ -1159,16 +1388,27 @@ impl NGramIndexBuilder {
             offset += batch_size;
         }
 
-        writer.finish().await
+        writer
+            .finish_with_metadata(params_metadata(&self.params)?)
+            .await
     }
 }
 
+fn params_metadata(params: &NGramIndexParams) -> Result<HashMap<String, String>> {
+    Ok(HashMap::from([(
+        PARAMS_META_KEY.to_string(),
+        serde_json::to_string(params)?,
+    )]))
+}
+
 pub async fn train_ngram_index(
     data_source: Box<dyn TrainingSource + Send>,
     index_store: &dyn IndexStore,
+    params: NGramIndexParams,
 ) -> Result<()> {
     let batches_source = data_source.scan_unordered_chunks(4096).await?;
-    let mut builder = NGramIndexBuilder::try_new(NGramIndexBuilderOptions::default())?;
+    let mut builder =
+        NGramIndexBuilder::try_new(NGramIndexBuilderOptions::default())?.with_params(params);
 
     let spill_files = builder.train(batches_source).await?;
 
@@ -1201,15 +1441,20 @@ mod tests {
     use crate::metrics::NoOpMetricsCollector;
     use crate::scalar::{
         lance_format::LanceIndexStore,
-        ngram::{NGramIndex, NGramIndexBuilder, NGramIndexBuilderOptions},
+        ngram::{NGramIndex, NGramIndexBuilder, NGramIndexBuilderOptions, NGramIndexParams},
         ScalarIndex, SearchResult, TextQuery,
     };
 
-    use super::{ngram_to_token, tokenize_visitor, NGRAM_TOKENIZER};
+    use super::{
+        like_literals, ngram_to_token, regex_literals, tokenize_visitor, ALPHA_SPAN,
+        NGRAM_TOKENIZER, TEXT_PREPPER,
+    };
 
     fn collect_tokens(analyzer: &TextAnalyzer, text: &str) -> Vec<String> {
         let mut tokens = Vec::with_capacity(text.len() * 3);
-        tokenize_visitor(analyzer, text, |token| tokens.push(token.to_owned()));
+        tokenize_visitor(&TEXT_PREPPER, analyzer, text, |token| {
+            tokens.push(token.to_owned())
+        });
         tokens
     }
 
@@ -1278,7 +1523,7 @@ mod tests {
     }
 
     async fn get_posting_list_for_trigram(index: &NGramIndex, trigram: &str) -> Vec<u64> {
-        let token = ngram_to_token(trigram, 3);
+        let token = ngram_to_token(trigram, 3, ALPHA_SPAN);
         let row_offset = index.tokens[&token];
         let list = index
             .list_reader
@@ -1397,6 +1642,126 @@ mod tests {
         assert_eq!(expected, res);
     }
 
+    #[test]
+    fn test_pattern_literals() {
+        assert_eq!(like_literals("abc", None), vec!["abc"]);
+        assert_eq!(like_literals("%ab_cd%ef", None), vec!["ab", "cd", "ef"]);
+        assert_eq!(like_literals("100\\%%", None), vec!["100%"]);
+        assert_eq!(like_literals("a!_b%", Some('!')), vec!["a_b"]);
+        assert!(like_literals("%_%", None).is_empty());
+
+        assert_eq!(regex_literals("^abc$"), vec!["abc"]);
+        assert_eq!(regex_literals("ab+cd?e"), vec!["ab", "c", "e"]);
+        assert_eq!(regex_literals("foo(bar)*baz"), vec!["foo", "baz"]);
+        assert_eq!(regex_literals("[a-z]+ing\\.com"), vec!["ing.com"]);
+        assert_eq!(regex_literals("abc{2,3}\\d+xyz"), vec!["ab", "xyz"]);
+        assert!(regex_literals("cat|dog").is_empty());
+        assert!(regex_literals("(?i)cat").is_empty());
+        assert!(regex_literals("\\x41bc").is_empty());
+        // A `]` at the start of a class is part of the class
+        assert_eq!(regex_literals("[]a]bc"), vec!["bc"]);
+        assert_eq!(regex_literals("x[^]a]yz"), vec!["x", "yz"]);
+        assert_eq!(regex_literals("[[:alpha:]]+ab"), vec!["ab"]);
+        assert_eq!(regex_literals("a(b[)])cd"), vec!["a", "cd"]);
+    }
+
+    fn text_data(values: &[&str]) -> SendableRecordBatchStream {
+        let data = StringArray::from_iter_values(values);
+        let row_ids = UInt64Array::from_iter_values((0..data.len()).map(|i| i as u64));
+        let schema = test_data_schema();
+        let data =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(data), Arc::new(row_ids)]).unwrap();
+        Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            stream::once(std::future::ready(Ok(data))),
+        ))
+    }
+
+    fn like(pattern: &str, case_insensitive: bool) -> TextQuery {
+        TextQuery::Like {
+            pattern: pattern.to_string(),
+            escape_char: None,
+            case_insensitive,
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_ngram_index_patterns() {
+        let data = text_data(&["cat", "dog", "cat dog", "dog cat", "rhino", "rhinos nose"]);
+        let builder = NGramIndexBuilder::try_new(NGramIndexBuilderOptions::default()).unwrap();
+        let (index, _tmpdir) = do_train(builder, data).await;
+        assert!(index.params.lower_case);
+
+        let search = |query: TextQuery| {
+            let index = &index;
+            async move { index.search(&query, &NoOpMetricsCollector).await.unwrap() }
+        };
+
+        // Every literal run must be present
+        assert_eq!(
+            search(like("cat%dog", false)).await,
+            SearchResult::AtMost(RowIdTreeMap::from_iter([2, 3]))
+        );
+        // Case insensitive searches can use a lower cased index
+        assert_eq!(
+            search(like("%RHINO%", true)).await,
+            SearchResult::AtMost(RowIdTreeMap::from_iter([4, 5]))
+        );
+        // Missing literal
+        assert_eq!(
+            search(like("%cow%", false)).await,
+            SearchResult::Exact(RowIdTreeMap::new())
+        );
+        // Only wildcards and short literals, don't know anything
+        assert_eq!(
+            search(like("c_t%", false)).await,
+            SearchResult::AtLeast(RowIdTreeMap::new())
+        );
+
+        assert_eq!(
+            search(TextQuery::Regex("^rhi+nos? nose$".to_string())).await,
+            SearchResult::AtMost(RowIdTreeMap::from_iter([5]))
+        );
+        assert_eq!(
+            search(TextQuery::Regex("cat|cow".to_string())).await,
+            SearchResult::AtLeast(RowIdTreeMap::new())
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_case_sensitive_ngram_index() {
+        let data = text_data(&["Cat", "cat", "CAT", "Cat dog"]);
+        let params = NGramIndexParams::default().with_lower_case(false);
+        let builder = NGramIndexBuilder::try_new(NGramIndexBuilderOptions::default())
+            .unwrap()
+            .with_params(params.clone());
+        let (index, _tmpdir) = do_train(builder, data).await;
+        assert_eq!(index.params, params);
+
+        let search = |query: TextQuery| {
+            let index = &index;
+            async move { index.search(&query, &NoOpMetricsCollector).await.unwrap() }
+        };
+
+        assert_eq!(
+            search(TextQuery::StringContains("Cat".to_string())).await,
+            SearchResult::AtMost(RowIdTreeMap::from_iter([0, 3]))
+        );
+        assert_eq!(
+            search(like("CAT%", false)).await,
+            SearchResult::AtMost(RowIdTreeMap::from_iter([2]))
+        );
+        assert_eq!(
+            search(like("%cAt%", false)).await,
+            SearchResult::Exact(RowIdTreeMap::new())
+        );
+        // A case sensitive index cannot help with case insensitive searches
+        assert_eq!(
+            search(like("%cat%", true)).await,
+            SearchResult::AtLeast(RowIdTreeMap::new())
+        );
+    }
+
     fn test_data_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![
             Field::new("values", DataType::Utf8, true),
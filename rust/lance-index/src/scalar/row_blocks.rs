@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Blocks of consecutive rows
+//!
+//! Zone map and bloom filter indices split the rows into blocks of consecutive row ids
+//! and keep a summary of the values of each block.  A block never covers rows from more
+//! than one fragment.  A search returns the rows of every block whose summary may match
+//! the query and so the results always need to be rechecked.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use arrow_array::{cast::AsArray, types::UInt64Type, ArrayRef, RecordBatch, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use datafusion::physical_plan::SendableRecordBatchStream;
+use deepsize::DeepSizeOf;
+use futures::TryStreamExt;
+use lance_core::{
+    error::LanceOptionExt,
+    utils::{address::RowAddress, mask::RowIdTreeMap},
+    Result,
+};
+use roaring::RoaringBitmap;
+
+use super::{IndexReader, IndexStore};
+use crate::frag_reuse::FragReuseIndex;
+
+const START_COL: &str = "start";
+const END_COL: &str = "end";
+
+/// Summarizes the values of the block being built
+pub trait SummaryBuilder {
+    type Summary;
+
+    /// Add the values of some of the rows of the block
+    fn update(&mut self, values: &ArrayRef) -> Result<()>;
+
+    /// The summary of the values added since the last call to finish
+    fn finish(&mut self) -> Result<Self::Summary>;
+}
+
+/// The summary of the values of the rows with ids in `start..end`
+#[derive(Debug, Clone, DeepSizeOf)]
+pub struct RowBlock<S> {
+    pub start: u64,
+    pub end: u64,
+    pub summary: S,
+}
+
+/// Splits a stream of (value, row id) batches into blocks
+pub struct RowBlockBuilder<B: SummaryBuilder> {
+    rows_per_block: u64,
+    summary: B,
+    blocks: Vec<RowBlock<B::Summary>>,
+    // The row id range and row count of the block being built
+    range: Option<(u64, u64)>,
+    num_rows: u64,
+}
+
+impl<B: SummaryBuilder> RowBlockBuilder<B> {
+    pub fn new(rows_per_block: u64, summary: B) -> Self {
+        Self {
+            rows_per_block,
+            summary,
+            blocks: Vec::new(),
+            range: None,
+            num_rows: 0,
+        }
+    }
+
+    pub async fn add_stream(&mut self, mut batches: SendableRecordBatchStream) -> Result<()> {
+        while let Some(batch) = batches.try_next().await? {
+            debug_assert_eq!(batch.num_columns(), 2);
+            let row_ids = batch.column(1).as_primitive::<UInt64Type>();
+            self.add(batch.column(0), row_ids.values())?;
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, values: &ArrayRef, row_ids: &[u64]) -> Result<()> {
+        let mut offset = 0;
+        while offset < row_ids.len() {
+            if let Some((start, _)) = self.range {
+                if self.num_rows == self.rows_per_block
+                    || RowAddress::from(start).fragment_id()
+                        != RowAddress::from(row_ids[offset]).fragment_id()
+                {
+                    self.flush()?;
+                }
+            }
+            let (start, end) = self
+                .range
+                .get_or_insert((row_ids[offset], row_ids[offset] + 1));
+
+            // The run of rows that belong to the current block
+            let fragment_id = RowAddress::from(*start).fragment_id();
+            let max_len = (self.rows_per_block - self.num_rows) as usize;
+            let len = row_ids[offset..]
+                .iter()
+                .take(max_len)
+                .take_while(|row_id| RowAddress::from(**row_id).fragment_id() == fragment_id)
+                .count();
+            let run = &row_ids[offset..offset + len];
+            *start = (*start).min(*run.iter().min().unwrap());
+            *end = (*end).max(*run.iter().max().unwrap() + 1);
+
+            self.summary.update(&values.slice(offset, len))?;
+            self.num_rows += len as u64;
+            offset += len;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some((start, end)) = self.range.take() {
+            self.blocks.push(RowBlock {
+                start,
+                end,
+                summary: self.summary.finish()?,
+            });
+            self.num_rows = 0;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<Vec<RowBlock<B::Summary>>> {
+        self.flush()?;
+        Ok(self.blocks)
+    }
+}
+
+/// Writes the blocks to an index file, the row range columns are followed by the
+/// summary columns
+pub async fn write_row_blocks<S>(
+    index_store: &dyn IndexStore,
+    filename: &str,
+    blocks: &[RowBlock<S>],
+    blocks_per_batch: usize,
+    summary_fields: Vec<Field>,
+    summary_columns: impl Fn(&[RowBlock<S>]) -> Result<Vec<ArrayRef>>,
+    metadata: HashMap<String, String>,
+) -> Result<()> {
+    let schema = Arc::new(Schema::new(
+        [
+            Field::new(START_COL, DataType::UInt64, false),
+            Field::new(END_COL, DataType::UInt64, false),
+        ]
+        .into_iter()
+        .chain(summary_fields)
+        .collect::<Vec<_>>(),
+    ));
+    let mut writer = index_store.new_index_file(filename, schema.clone()).await?;
+    for blocks in blocks.chunks(blocks_per_batch) {
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from_iter_values(
+                blocks.iter().map(|block| block.start),
+            )),
+            Arc::new(UInt64Array::from_iter_values(
+                blocks.iter().map(|block| block.end),
+            )),
+        ];
+        columns.extend(summary_columns(blocks)?);
+        writer
+            .write_record_batch(RecordBatch::try_new(schema.clone(), columns)?)
+            .await?;
+    }
+    writer.finish_with_metadata(metadata).await
+}
+
+/// Reads the blocks written by [`write_row_blocks`]
+pub async fn read_row_blocks<S>(
+    reader: &dyn IndexReader,
+    blocks_per_batch: usize,
+    read_summaries: impl Fn(&RecordBatch) -> Result<Vec<S>>,
+) -> Result<Vec<RowBlock<S>>> {
+    let num_blocks = reader.num_rows();
+    let mut blocks = Vec::with_capacity(num_blocks);
+    for start in (0..num_blocks).step_by(blocks_per_batch) {
+        let end = (start + blocks_per_batch).min(num_blocks);
+        let batch = reader.read_range(start..end, None).await?;
+        let starts = batch
+            .column_by_name(START_COL)
+            .expect_ok()?
+            .as_primitive::<UInt64Type>();
+        let ends = batch
+            .column_by_name(END_COL)
+            .expect_ok()?
+            .as_primitive::<UInt64Type>();
+        let summaries = read_summaries(&batch)?;
+        blocks.extend(
+            starts
+                .values()
+                .iter()
+                .zip(ends.values())
+                .zip(summaries)
+                .map(|((start, end), summary)| RowBlock {
+                    start: *start,
+                    end: *end,
+                    summary,
+                }),
+        );
+    }
+    Ok(blocks)
+}
+
+/// Remaps the row ids of the blocks
+///
+/// The rows of a block that were moved get a copy of its summary for each fragment
+/// they were moved to.  The block itself is kept unless all of its rows were moved.
+pub fn remap_row_blocks<S: Clone>(
+    blocks: &[RowBlock<S>],
+    mapping: &HashMap<u64, Option<u64>>,
+) -> Vec<RowBlock<S>> {
+    let mut mapping = mapping
+        .iter()
+        .map(|(old, new)| (*old, *new))
+        .collect::<Vec<_>>();
+    mapping.sort_unstable();
+
+    let mut remapped = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let lower = mapping.partition_point(|(old, _)| *old < block.start);
+        let upper = mapping.partition_point(|(old, _)| *old < block.end);
+        let moved = &mapping[lower..upper];
+        if (moved.len() as u64) < block.end - block.start {
+            remapped.push(block.clone());
+        }
+
+        let mut new_ranges = BTreeMap::<u32, (u64, u64)>::new();
+        for new in moved.iter().filter_map(|(_, new)| *new) {
+            let (start, end) = new_ranges
+                .entry(RowAddress::from(new).fragment_id())
+                .or_insert((new, new + 1));
+            *start = (*start).min(new);
+            *end = (*end).max(new + 1);
+        }
+        remapped.extend(new_ranges.into_values().map(|(start, end)| RowBlock {
+            start,
+            end,
+            summary: block.summary.clone(),
+        }));
+    }
+    remapped
+}
+
+/// The row ids of the given blocks, remapped if rows were moved since the index was built
+pub fn block_row_ids<'a, S: 'a>(
+    blocks: impl IntoIterator<Item = &'a RowBlock<S>>,
+    fri: Option<&FragReuseIndex>,
+) -> RowIdTreeMap {
+    let mut row_ids = RowIdTreeMap::new();
+    for block in blocks {
+        row_ids.insert_range(block.start..block.end);
+    }
+    match fri {
+        Some(fri) => fri.remap_row_ids_tree_map(&row_ids),
+        None => row_ids,
+    }
+}
+
+/// The fragments covered by the blocks
+pub fn included_frags<S>(blocks: &[RowBlock<S>]) -> RoaringBitmap {
+    blocks
+        .iter()
+        .map(|block| RowAddress::from(block.start).fragment_id())
+        .collect()
+}
+
+/// A test fixture shared by the indices built on row blocks
+#[cfg(test)]
+pub mod tests {
+    use std::{collections::HashMap, ops::Range, sync::Arc};
+
+    use arrow_array::{
+        cast::AsArray, types::UInt64Type, ArrayRef, Int32Array, RecordBatch, RecordBatchIterator,
+        UInt64Array,
+    };
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::physical_plan::SendableRecordBatchStream;
+    use lance_core::{
+        cache::FileMetadataCache,
+        utils::{address::RowAddress, mask::RowIdTreeMap},
+        Result,
+    };
+    use lance_io::object_store::ObjectStore;
+    use object_store::path::Path;
+    use roaring::RoaringBitmap;
+    use tempfile::TempDir;
+
+    use super::{block_row_ids, included_frags, remap_row_blocks, RowBlockBuilder, SummaryBuilder};
+    use crate::metrics::NoOpMetricsCollector;
+    use crate::scalar::lance_format::{tests::MockTrainingSource, LanceIndexStore};
+    use crate::scalar::{AnyQuery, IndexStore, ScalarIndex, SearchResult};
+
+    pub fn test_store(tmpdir: &TempDir) -> Arc<dyn IndexStore> {
+        Arc::new(LanceIndexStore::new(
+            Arc::new(ObjectStore::local()),
+            Path::from_filesystem_path(tmpdir.path()).unwrap(),
+            FileMetadataCache::no_cache(),
+        ))
+    }
+
+    pub fn row_id(fragment_id: u32, offset: u32) -> u64 {
+        RowAddress::new_from_parts(fragment_id, offset).into()
+    }
+
+    /// A (values, row ids) batch with 1000 rows per fragment, the value of each row is
+    /// computed from its fragment id and offset
+    pub fn fragments_batch(
+        fragments: Range<u32>,
+        values: impl Fn(&[u64]) -> ArrayRef,
+    ) -> RecordBatch {
+        let row_ids = fragments
+            .flat_map(|fragment_id| (0..1000).map(move |offset| row_id(fragment_id, offset)))
+            .collect::<Vec<_>>();
+        let values = values(&row_ids);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("values", values.data_type().clone(), true),
+            Field::new("row_ids", DataType::UInt64, false),
+        ]));
+        RecordBatch::try_new(schema, vec![values, Arc::new(UInt64Array::from(row_ids))]).unwrap()
+    }
+
+    pub async fn training_source(batch: RecordBatch) -> Box<MockTrainingSource> {
+        let schema = batch.schema();
+        let data = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        Box::new(MockTrainingSource::new(data).await)
+    }
+
+    pub fn batch_stream(batch: RecordBatch) -> SendableRecordBatchStream {
+        let schema = batch.schema();
+        let data = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        lance_datafusion::utils::reader_to_stream(Box::new(data))
+    }
+
+    /// Fragment 0 is rewritten as fragment 5 without row 7
+    pub fn rewrite_mapping() -> HashMap<u64, Option<u64>> {
+        (0..1000)
+            .map(|offset| {
+                let new = (offset != 7).then(|| row_id(5, offset));
+                (row_id(0, offset), new)
+            })
+            .collect()
+    }
+
+    pub async fn search(index: &dyn ScalarIndex, query: &dyn AnyQuery) -> RowIdTreeMap {
+        match index.search(query, &NoOpMetricsCollector).await.unwrap() {
+            SearchResult::AtMost(row_ids) => row_ids,
+            result => panic!("expected an AtMost result but got {:?}", result),
+        }
+    }
+
+    /// Counts the rows of a block
+    struct RowCount(u64);
+
+    impl SummaryBuilder for RowCount {
+        type Summary = u64;
+
+        fn update(&mut self, values: &ArrayRef) -> Result<()> {
+            self.0 += values.len() as u64;
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<u64> {
+            Ok(std::mem::take(&mut self.0))
+        }
+    }
+
+    #[test]
+    fn test_row_block_builder() {
+        let batch = fragments_batch(0..3, |row_ids| {
+            Arc::new(Int32Array::from_iter_values(0..row_ids.len() as i32))
+        });
+        let row_ids = batch.column(1).as_primitive::<UInt64Type>().values();
+        let mut builder = RowBlockBuilder::new(300, RowCount(0));
+        // Blocks continue across batches
+        builder
+            .add(&batch.column(0).slice(0, 150), &row_ids[..150])
+            .unwrap();
+        builder
+            .add(&batch.column(0).slice(150, 2850), &row_ids[150..])
+            .unwrap();
+        let blocks = builder.finish().unwrap();
+
+        // Blocks are split at fragment boundaries
+        assert_eq!(blocks.len(), 12);
+        assert_eq!(
+            blocks.iter().map(|block| block.summary).collect::<Vec<_>>(),
+            [300_u64, 300, 300, 100].repeat(3)
+        );
+        for block in &blocks {
+            assert_eq!(block.end - block.start, block.summary);
+            assert_eq!(
+                RowAddress::from(block.start).fragment_id(),
+                RowAddress::from(block.end - 1).fragment_id()
+            );
+        }
+        assert_eq!(included_frags(&blocks), RoaringBitmap::from_iter([0, 1, 2]));
+        assert_eq!(block_row_ids(&blocks[..2], None).len(), Some(600));
+    }
+
+    #[test]
+    fn test_remap_row_blocks() {
+        let batch = fragments_batch(0..2, |row_ids| {
+            Arc::new(Int32Array::from_iter_values(0..row_ids.len() as i32))
+        });
+        let row_ids = batch.column(1).as_primitive::<UInt64Type>();
+        let mut builder = RowBlockBuilder::new(500, RowCount(0));
+        builder.add(batch.column(0), row_ids.values()).unwrap();
+        let blocks = remap_row_blocks(&builder.finish().unwrap(), &rewrite_mapping());
+
+        // The blocks of fragment 0 are moved to fragment 5 with their summaries
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(included_frags(&blocks), RoaringBitmap::from_iter([1, 5]));
+        let moved = blocks
+            .iter()
+            .filter(|block| RowAddress::from(block.start).fragment_id() == 5)
+            .map(|block| (block.start, block.end, block.summary))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            moved,
+            vec![
+                (row_id(5, 0), row_id(5, 500), 500),
+                (row_id(5, 500), row_id(5, 1000), 500)
+            ]
+        );
+    }
+}
@@ -0,0 +1,637 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Zone map index
+//!
+//! The rows are split into zones of consecutive row ids (see `row_blocks`) and
+//! the min, max and null count of the values are kept for each zone.  A search returns
+//! the rows of every zone whose statistics may satisfy the query.
+//!
+//! Unlike the zone maps written by the file encoders this index can be built on any
+//! existing dataset and is kept up to date by `optimize_indices`.
+
+use std::{any::Any, collections::HashMap, ops::Bound, sync::Arc};
+
+use arrow_array::{cast::AsArray, types::UInt64Type, Array, ArrayRef, UInt64Array};
+use arrow_schema::{DataType, Field};
+use async_trait::async_trait;
+use datafusion::functions_aggregate::min_max::{MaxAccumulator, MinAccumulator};
+use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion_common::ScalarValue;
+use datafusion_expr::Accumulator;
+use deepsize::DeepSizeOf;
+use lance_core::{error::LanceOptionExt, Error, Result};
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+use snafu::location;
+use tracing::instrument;
+
+use super::{
+    btree::{OrderableScalarValue, TrainingSource},
+    row_blocks::{
+        block_row_ids, included_frags, read_row_blocks, remap_row_blocks, write_row_blocks,
+        RowBlock, RowBlockBuilder, SummaryBuilder,
+    },
+    AnyQuery, IndexStore, SargableQuery, ScalarIndex, SearchResult,
+};
+use crate::frag_reuse::FragReuseIndex;
+use crate::{metrics::MetricsCollector, Index, IndexParams, IndexType};
+
+pub const ZONE_MAP_FILENAME: &str = "zone_map.lance";
+
+pub const DEFAULT_ROWS_PER_ZONE: u64 = 8192;
+
+const PARAMS_META_KEY: &str = "params";
+
+const MIN_COL: &str = "min";
+const MAX_COL: &str = "max";
+const NULL_COUNT_COL: &str = "null_count";
+
+const ZONES_PER_BATCH: usize = 4096;
+
+/// Parameters of a zone map index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZoneMapIndexParams {
+    /// The maximum number of rows covered by a single zone
+    ///
+    /// Smaller zones return fewer rows to recheck but there are more zones to search.
+    pub rows_per_zone: u64,
+}
+
+impl Default for ZoneMapIndexParams {
+    fn default() -> Self {
+        Self {
+            rows_per_zone: DEFAULT_ROWS_PER_ZONE,
+        }
+    }
+}
+
+impl ZoneMapIndexParams {
+    pub fn with_rows_per_zone(mut self, rows_per_zone: u64) -> Self {
+        self.rows_per_zone = rows_per_zone;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.rows_per_zone == 0 {
+            return Err(Error::InvalidInput {
+                source: "The rows per zone of a zone map index must be greater than 0".into(),
+                location: location!(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl IndexParams for ZoneMapIndexParams {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn index_type(&self) -> IndexType {
+        IndexType::ZoneMap
+    }
+
+    fn index_name(&self) -> &str {
+        "ZONE_MAP"
+    }
+}
+
+fn check_value_type(value_type: &DataType) -> Result<()> {
+    if value_type.is_nested() || matches!(value_type, DataType::Dictionary(..)) {
+        return Err(Error::InvalidInput {
+            source: format!(
+                "A zone map index cannot be created on a column of type {}",
+                value_type
+            )
+            .into(),
+            location: location!(),
+        });
+    }
+    Ok(())
+}
+
+/// The statistics of the values of the rows of a zone
+///
+/// The min and max are null if all of the values are null
+#[derive(Debug, Clone, DeepSizeOf)]
+struct ZoneStats {
+    min: OrderableScalarValue,
+    max: OrderableScalarValue,
+    null_count: u64,
+}
+
+type Zone = RowBlock<ZoneStats>;
+
+impl ZoneStats {
+    fn has_values(&self) -> bool {
+        !self.min.0.is_null()
+    }
+
+    fn may_equal(&self, value: &OrderableScalarValue) -> bool {
+        // Nulls are never equal to anything
+        self.has_values() && !value.0.is_null() && &self.min <= value && value <= &self.max
+    }
+
+    fn may_be_in_range(
+        &self,
+        lower: &Bound<OrderableScalarValue>,
+        upper: &Bound<OrderableScalarValue>,
+    ) -> bool {
+        if !self.has_values() {
+            return false;
+        }
+        let above_lower = match lower {
+            Bound::Unbounded => true,
+            Bound::Included(lower) => !lower.0.is_null() && &self.max >= lower,
+            Bound::Excluded(lower) => !lower.0.is_null() && &self.max > lower,
+        };
+        let below_upper = match upper {
+            Bound::Unbounded => true,
+            Bound::Included(upper) => !upper.0.is_null() && &self.min <= upper,
+            Bound::Excluded(upper) => !upper.0.is_null() && &self.min < upper,
+        };
+        above_lower && below_upper
+    }
+}
+
+/// Computes the statistics of the zone being built
+struct ZoneStatsBuilder {
+    value_type: DataType,
+    null_count: u64,
+    min: MinAccumulator,
+    max: MaxAccumulator,
+}
+
+impl ZoneStatsBuilder {
+    fn try_new(value_type: DataType) -> Result<Self> {
+        Ok(Self {
+            null_count: 0,
+            min: MinAccumulator::try_new(&value_type)?,
+            max: MaxAccumulator::try_new(&value_type)?,
+            value_type,
+        })
+    }
+}
+
+impl SummaryBuilder for ZoneStatsBuilder {
+    type Summary = ZoneStats;
+
+    fn update(&mut self, values: &ArrayRef) -> Result<()> {
+        self.min.update_batch(&[values.clone()])?;
+        self.max.update_batch(&[values.clone()])?;
+        self.null_count += values.null_count() as u64;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<ZoneStats> {
+        let next = Self::try_new(self.value_type.clone())?;
+        let mut zone = std::mem::replace(self, next);
+        Ok(ZoneStats {
+            min: OrderableScalarValue(zone.min.evaluate()?),
+            max: OrderableScalarValue(zone.max.evaluate()?),
+            null_count: zone.null_count,
+        })
+    }
+}
+
+fn zone_builder(
+    params: &ZoneMapIndexParams,
+    value_type: DataType,
+) -> Result<RowBlockBuilder<ZoneStatsBuilder>> {
+    Ok(RowBlockBuilder::new(
+        params.rows_per_zone,
+        ZoneStatsBuilder::try_new(value_type)?,
+    ))
+}
+
+async fn write_zone_map_index(
+    zones: &[Zone],
+    value_type: &DataType,
+    params: &ZoneMapIndexParams,
+    index_store: &dyn IndexStore,
+) -> Result<()> {
+    let metadata = HashMap::from([(PARAMS_META_KEY.to_string(), serde_json::to_string(params)?)]);
+    write_row_blocks(
+        index_store,
+        ZONE_MAP_FILENAME,
+        zones,
+        ZONES_PER_BATCH,
+        vec![
+            Field::new(MIN_COL, value_type.clone(), true),
+            Field::new(MAX_COL, value_type.clone(), true),
+            Field::new(NULL_COUNT_COL, DataType::UInt64, false),
+        ],
+        |zones| {
+            Ok(vec![
+                ScalarValue::iter_to_array(zones.iter().map(|zone| zone.summary.min.0.clone()))?,
+                ScalarValue::iter_to_array(zones.iter().map(|zone| zone.summary.max.0.clone()))?,
+                Arc::new(UInt64Array::from_iter_values(
+                    zones.iter().map(|zone| zone.summary.null_count),
+                )),
+            ])
+        },
+        metadata,
+    )
+    .await
+}
+
+#[derive(Serialize)]
+struct ZoneMapStatistics {
+    num_zones: usize,
+    rows_per_zone: u64,
+}
+
+/// A scalar index that stores the min, max and null count of each zone of rows
+///
+/// This index can answer the same queries as a btree index, except for full text
+/// search, but the results are inexact.
+#[derive(Debug)]
+pub struct ZoneMapIndex {
+    zones: Vec<Zone>,
+    value_type: DataType,
+    params: ZoneMapIndexParams,
+    fri: Option<Arc<FragReuseIndex>>,
+    store: Arc<dyn IndexStore>,
+}
+
+impl DeepSizeOf for ZoneMapIndex {
+    fn deep_size_of_children(&self, context: &mut deepsize::Context) -> usize {
+        self.zones.deep_size_of_children(context) + self.store.deep_size_of_children(context)
+    }
+}
+
+impl ZoneMapIndex {
+    pub fn params(&self) -> &ZoneMapIndexParams {
+        &self.params
+    }
+
+    fn cast_value(&self, value: &ScalarValue) -> Result<OrderableScalarValue> {
+        Ok(OrderableScalarValue(value.cast_to(&self.value_type)?))
+    }
+
+    fn cast_bound(&self, bound: &Bound<ScalarValue>) -> Result<Bound<OrderableScalarValue>> {
+        Ok(match bound {
+            Bound::Unbounded => Bound::Unbounded,
+            Bound::Included(value) => Bound::Included(self.cast_value(value)?),
+            Bound::Excluded(value) => Bound::Excluded(self.cast_value(value)?),
+        })
+    }
+
+    /// Returns a predicate that is true for every zone that may contain matching rows
+    fn zone_filter(&self, query: &SargableQuery) -> Result<Box<dyn Fn(&ZoneStats) -> bool>> {
+        Ok(match query {
+            SargableQuery::Equals(value) => {
+                let value = self.cast_value(value)?;
+                Box::new(move |zone: &ZoneStats| zone.may_equal(&value))
+            }
+            SargableQuery::IsIn(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.cast_value(value))
+                    .collect::<Result<Vec<_>>>()?;
+                Box::new(move |zone: &ZoneStats| values.iter().any(|value| zone.may_equal(value)))
+            }
+            SargableQuery::Range(lower, upper) => {
+                let (lower, upper) = (self.cast_bound(lower)?, self.cast_bound(upper)?);
+                Box::new(move |zone: &ZoneStats| zone.may_be_in_range(&lower, &upper))
+            }
+            SargableQuery::IsNull() => Box::new(|zone: &ZoneStats| zone.null_count > 0),
+            SargableQuery::FullTextSearch(_) => {
+                return Err(Error::NotSupported {
+                    source: "full text search is not supported for zone map indices".into(),
+                    location: location!(),
+                })
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl Index for ZoneMapIndex {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_index(self: Arc<Self>) -> Arc<dyn Index> {
+        self
+    }
+
+    fn as_vector_index(self: Arc<Self>) -> Result<Arc<dyn crate::vector::VectorIndex>> {
+        Err(Error::NotSupported {
+            source: "ZoneMapIndex is not a vector index".into(),
+            location: location!(),
+        })
+    }
+
+    async fn prewarm(&self) -> Result<()> {
+        // All of the zones are loaded when the index is loaded
+        Ok(())
+    }
+
+    fn index_type(&self) -> IndexType {
+        IndexType::ZoneMap
+    }
+
+    fn statistics(&self) -> Result<serde_json::Value> {
+        let stats = ZoneMapStatistics {
+            num_zones: self.zones.len(),
+            rows_per_zone: self.params.rows_per_zone,
+        };
+        serde_json::to_value(stats).map_err(|e| Error::Internal {
+            message: format!("failed to serialize zone map index statistics: {}", e),
+            location: location!(),
+        })
+    }
+
+    async fn calculate_included_frags(&self) -> Result<RoaringBitmap> {
+        Ok(included_frags(&self.zones))
+    }
+}
+
+#[async_trait]
+impl ScalarIndex for ZoneMapIndex {
+    #[instrument(name = "zone_map_search", level = "debug", skip_all)]
+    async fn search(
+        &self,
+        query: &dyn AnyQuery,
+        metrics: &dyn MetricsCollector,
+    ) -> Result<SearchResult> {
+        let Some(query) = query.as_any().downcast_ref::<SargableQuery>() else {
+            return Err(Error::NotSupported {
+                source: "a zone map index can only answer range and equality queries".into(),
+                location: location!(),
+            });
+        };
+        let may_match = self.zone_filter(query)?;
+
+        metrics.record_comparisons(self.zones.len());
+        let zones = self.zones.iter().filter(|zone| may_match(&zone.summary));
+        Ok(SearchResult::AtMost(block_row_ids(
+            zones,
+            self.fri.as_deref(),
+        )))
+    }
+
+    fn can_answer_exact(&self, _: &dyn AnyQuery) -> bool {
+        false
+    }
+
+    async fn load(
+        store: Arc<dyn IndexStore>,
+        fri: Option<Arc<FragReuseIndex>>,
+    ) -> Result<Arc<Self>> {
+        let reader = store.open_index_file(ZONE_MAP_FILENAME).await?;
+        let params = serde_json::from_str::<ZoneMapIndexParams>(
+            reader.schema().metadata.get(PARAMS_META_KEY).expect_ok()?,
+        )?;
+        let value_type = reader.schema().field(MIN_COL).expect_ok()?.data_type();
+
+        let zones = read_row_blocks(reader.as_ref(), ZONES_PER_BATCH, |batch| {
+            let mins = batch.column_by_name(MIN_COL).expect_ok()?;
+            let maxs = batch.column_by_name(MAX_COL).expect_ok()?;
+            let null_counts = batch
+                .column_by_name(NULL_COUNT_COL)
+                .expect_ok()?
+                .as_primitive::<UInt64Type>();
+            (0..batch.num_rows())
+                .map(|idx| {
+                    Ok(ZoneStats {
+                        min: OrderableScalarValue(ScalarValue::try_from_array(mins, idx)?),
+                        max: OrderableScalarValue(ScalarValue::try_from_array(maxs, idx)?),
+                        null_count: null_counts.value(idx),
+                    })
+                })
+                .collect()
+        })
+        .await?;
+
+        Ok(Arc::new(Self {
+            zones,
+            value_type,
+            params,
+            fri,
+            store,
+        }))
+    }
+
+    /// Remap the row ids, creating a new remapped version of this index in `dest_store`
+    async fn remap(
+        &self,
+        mapping: &HashMap<u64, Option<u64>>,
+        dest_store: &dyn IndexStore,
+    ) -> Result<()> {
+        let zones = remap_row_blocks(&self.zones, mapping);
+        write_zone_map_index(&zones, &self.value_type, &self.params, dest_store).await
+    }
+
+    /// Add the new data into the index, creating an updated version of the index in `dest_store`
+    async fn update(
+        &self,
+        new_data: SendableRecordBatchStream,
+        dest_store: &dyn IndexStore,
+    ) -> Result<()> {
+        let mut builder = zone_builder(&self.params, self.value_type.clone())?;
+        builder.add_stream(new_data).await?;
+        let zones = self
+            .zones
+            .iter()
+            .cloned()
+            .chain(builder.finish()?)
+            .collect::<Vec<_>>();
+        write_zone_map_index(&zones, &self.value_type, &self.params, dest_store).await
+    }
+}
+
+pub async fn train_zone_map_index(
+    data_source: Box<dyn TrainingSource + Send>,
+    index_store: &dyn IndexStore,
+    params: ZoneMapIndexParams,
+) -> Result<()> {
+    params.validate()?;
+    let batches = data_source.scan_unordered_chunks(4096).await?;
+    let value_type = batches.schema().field(0).data_type().clone();
+    check_value_type(&value_type)?;
+
+    let mut builder = zone_builder(&params, value_type.clone())?;
+    builder.add_stream(batches).await?;
+    write_zone_map_index(&builder.finish()?, &value_type, &params, index_store).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, RecordBatch};
+    use datafusion_common::ScalarValue;
+    use lance_core::utils::address::RowAddress;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::metrics::NoOpMetricsCollector;
+    use crate::scalar::row_blocks::tests::{
+        batch_stream, fragments_batch, rewrite_mapping, row_id, search, test_store, training_source,
+    };
+    use crate::scalar::TextQuery;
+
+    fn value(value: i32) -> ScalarValue {
+        ScalarValue::Int32(Some(value))
+    }
+
+    /// The value of each row is fragment_id * 1000 + offset except that every 100th row
+    /// (starting at offset 50) is null
+    fn batch(fragments: std::ops::Range<u32>) -> RecordBatch {
+        fragments_batch(fragments, |row_ids| {
+            Arc::new(Int32Array::from_iter(row_ids.iter().map(|row_id| {
+                let addr = RowAddress::from(*row_id);
+                (addr.row_offset() % 100 != 50)
+                    .then_some((addr.fragment_id() * 1000 + addr.row_offset()) as i32)
+            })))
+        })
+    }
+
+    async fn train(
+        store: &Arc<dyn IndexStore>,
+        batch: RecordBatch,
+        params: ZoneMapIndexParams,
+    ) -> Arc<ZoneMapIndex> {
+        train_zone_map_index(training_source(batch).await, store.as_ref(), params)
+            .await
+            .unwrap();
+        ZoneMapIndex::load(store.clone(), None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_zone_map_search() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = ZoneMapIndexParams::default().with_rows_per_zone(300);
+        let index = train(&store, batch(0..3), params.clone()).await;
+        assert_eq!(index.params(), &params);
+        assert_eq!(index.zones.len(), 12);
+        assert_eq!(index.zones[0].summary.min.0, value(0));
+        assert_eq!(index.zones[0].summary.max.0, value(299));
+        assert_eq!(index.zones[0].summary.null_count, 3);
+
+        let row_ids = search(index.as_ref(), &SargableQuery::Equals(value(1250))).await;
+        assert!(row_ids.contains(row_id(1, 250)));
+        // Only the zone of the value matches
+        assert_eq!(row_ids.len(), Some(300));
+
+        let row_ids = search(
+            index.as_ref(),
+            &SargableQuery::IsIn(vec![value(5), value(2950)]),
+        )
+        .await;
+        assert!(row_ids.contains(row_id(0, 5)));
+        assert!(row_ids.contains(row_id(2, 950)));
+        assert_eq!(row_ids.len(), Some(400));
+
+        let row_ids = search(
+            index.as_ref(),
+            &SargableQuery::Range(Bound::Included(value(1250)), Bound::Excluded(value(1700))),
+        )
+        .await;
+        assert_eq!(row_ids.len(), Some(900));
+
+        // The max of the first zone of fragment 1 is 1299
+        let row_ids = search(
+            index.as_ref(),
+            &SargableQuery::Range(Bound::Excluded(value(1299)), Bound::Unbounded),
+        )
+        .await;
+        assert!(!row_ids.contains(row_id(1, 0)));
+        assert_eq!(row_ids.len(), Some(1700));
+
+        let row_ids = search(index.as_ref(), &SargableQuery::IsNull()).await;
+        assert_eq!(row_ids.len(), Some(3000));
+
+        let row_ids = search(
+            index.as_ref(),
+            &SargableQuery::Equals(ScalarValue::Int32(None)),
+        )
+        .await;
+        assert!(row_ids.is_empty());
+
+        // Values are cast to the type of the column
+        let row_ids = search(
+            index.as_ref(),
+            &SargableQuery::Equals(ScalarValue::Int64(Some(2010))),
+        )
+        .await;
+        assert!(row_ids.contains(row_id(2, 10)));
+        assert_eq!(row_ids.len(), Some(300));
+
+        assert!(index
+            .search(
+                &TextQuery::StringContains("x".to_string()),
+                &NoOpMetricsCollector
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_zone_map_update() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = ZoneMapIndexParams::default().with_rows_per_zone(400);
+        let index = train(&store, batch(0..2), params).await;
+        assert_eq!(index.zones.len(), 6);
+
+        let updated_dir = tempdir().unwrap();
+        let updated_store = test_store(&updated_dir);
+        index
+            .update(batch_stream(batch(2..3)), updated_store.as_ref())
+            .await
+            .unwrap();
+        let updated_index = ZoneMapIndex::load(updated_store, None).await.unwrap();
+        assert_eq!(updated_index.zones.len(), 9);
+
+        let row_ids = search(updated_index.as_ref(), &SargableQuery::Equals(value(2500))).await;
+        assert!(row_ids.contains(row_id(2, 500)));
+        assert_eq!(row_ids.len(), Some(400));
+        let row_ids = search(updated_index.as_ref(), &SargableQuery::Equals(value(10))).await;
+        assert!(row_ids.contains(row_id(0, 10)));
+    }
+
+    #[tokio::test]
+    async fn test_zone_map_remap() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = ZoneMapIndexParams::default().with_rows_per_zone(500);
+        let index = train(&store, batch(0..2), params).await;
+
+        let remapped_dir = tempdir().unwrap();
+        let remapped_store = test_store(&remapped_dir);
+        index
+            .remap(&rewrite_mapping(), remapped_store.as_ref())
+            .await
+            .unwrap();
+        let remapped_index = ZoneMapIndex::load(remapped_store, None).await.unwrap();
+        assert_eq!(remapped_index.zones.len(), 4);
+        assert_eq!(
+            remapped_index.calculate_included_frags().await.unwrap(),
+            RoaringBitmap::from_iter([1, 5])
+        );
+
+        // The statistics are moved with the rows
+        let row_ids = search(remapped_index.as_ref(), &SargableQuery::Equals(value(3))).await;
+        assert!(row_ids.contains(row_id(5, 3)));
+        assert!(!row_ids.contains(row_id(0, 3)));
+        let row_ids = search(remapped_index.as_ref(), &SargableQuery::Equals(value(1003))).await;
+        assert!(row_ids.contains(row_id(1, 3)));
+    }
+
+    #[tokio::test]
+    async fn test_zone_map_invalid_params() {
+        let tmpdir = tempdir().unwrap();
+        let store = test_store(&tmpdir);
+        let params = ZoneMapIndexParams::default().with_rows_per_zone(0);
+        assert!(
+            train_zone_map_index(training_source(batch(0..1)).await, store.as_ref(), params)
+                .await
+                .is_err()
+        );
+    }
+}
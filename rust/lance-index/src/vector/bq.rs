@@ -3,13 +3,12 @@
 
 //! Binary Quantization (BQ)
 
-use std::iter::once;
 use std::sync::Arc;
 
 use arrow_array::types::Float32Type;
 use arrow_array::{cast::AsArray, Array, ArrayRef, UInt8Array};
 use lance_core::{Error, Result};
-use num_traits::Float;
+use lance_encoding::encodings::physical::quantized_vector::pack_sign_bits;
 use snafu::location;
 
 #[derive(Clone, Default)]
@@ -40,45 +39,19 @@ impl BinaryQuantization {
                 location: location!(),
             })?;
         let dim = fsl.value_length() as usize;
-        let code = data
-            .values()
-            .chunks_exact(dim)
-            .flat_map(binary_quantization)
-            .collect::<Vec<_>>();
+        let mut code = Vec::with_capacity(fsl.len() * dim.div_ceil(8));
+        pack_sign_bits(data.values(), dim, &mut code);
 
         Ok(Arc::new(UInt8Array::from(code)))
     }
 }
 
-/// Binary quantization.
-///
-/// Use the sign bit of the float vector to represent the binary vector.
-fn binary_quantization<T: Float>(data: &[T]) -> impl Iterator<Item = u8> + '_ {
-    let iter = data.chunks_exact(8);
-    iter.clone()
-        .map(|c| {
-            // Auto vectorized.
-            // Before changing this code, please check the assembly output.
-            let mut bits: u8 = 0;
-            c.iter().enumerate().for_each(|(idx, v)| {
-                bits |= (v.is_sign_positive() as u8) << idx;
-            });
-            bits
-        })
-        .chain(once(0).map(move |_| {
-            let mut bits: u8 = 0;
-            iter.remainder().iter().enumerate().for_each(|(idx, v)| {
-                bits |= (v.is_sign_positive() as u8) << idx;
-            });
-            bits
-        }))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use half::{bf16, f16};
+    use num_traits::Float;
 
     fn test_bq<T: Float>() {
         let data: Vec<T> = [1.0, -1.0, 1.0, -5.0, -7.0, -1.0, 1.0, -1.0, -0.2, 1.2, 3.2]
@@ -86,7 +59,8 @@ mod tests {
             .map(|&v| T::from(v).unwrap())
             .collect();
         let expected = vec![0b01000101, 0b00000110];
-        let result = binary_quantization(&data).collect::<Vec<_>>();
+        let mut result = Vec::new();
+        pack_sign_bits(&data, data.len(), &mut result);
         assert_eq!(result, expected);
     }
 
@@ -1,3 +1,323 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! CAGRA graph implementation.
+//!
+//! CAGRA is a proximity graph where every node has the same number of neighbors.
+//! The graph is built by pruning an approximate kNN graph, and searched with a
+//! beam search over a fixed size internal top-k buffer.
+//!
+//! Ootomo, H., et al. (2024). CAGRA: Highly Parallel Graph Construction and
+//! Approximate Nearest Neighbor Search for GPUs.
+//!
+
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+use arrow::array::AsArray;
+use arrow::datatypes::UInt32Type;
+use arrow_array::{FixedSizeListArray, UInt32Array};
+use arrow_schema::{DataType, Field};
+use deepsize::DeepSizeOf;
+use lance_arrow::FixedSizeListArrayExt;
+use lance_core::{Error, Result};
+use snafu::location;
+
+use super::graph::{OrderedFloat, OrderedNode, Visited};
+use super::storage::DistCalculator;
+use super::Query;
+
 pub mod builder;
-pub use builder::CagraBuildParams;
-pub use builder::build_cagra_index;
\ No newline at end of file
+
+pub use builder::{build_cagra_graph, CagraBuildAlgo, CagraBuildParams};
+
+pub const CAGRA_TYPE: &str = "CAGRA";
+pub const CAGRA_NEIGHBORS_COL: &str = "__neighbors";
+/// The schema metadata key of the global buffer position of the
+/// [crate::pb::VectorIndex] proto, which has a single `Cagra` stage.
+pub const CAGRA_METADATA_KEY: &str = "lance:cagra";
+
+/// Default size of the internal top-k buffer.
+const DEFAULT_ITOPK_SIZE: usize = 64;
+
+/// The field of the fixed-degree neighbor lists.
+pub fn neighbors_field(degree: usize) -> Field {
+    Field::new(
+        CAGRA_NEIGHBORS_COL,
+        DataType::FixedSizeList(
+            Arc::new(Field::new("item", DataType::UInt32, true)),
+            degree as i32,
+        ),
+        false,
+    )
+}
+
+/// Parameters of searching CAGRA graph.
+#[derive(Debug, Clone)]
+pub struct CagraSearchParams {
+    /// Size of the internal top-k buffer, the larger the better recall.
+    pub itopk_size: usize,
+
+    /// Number of nodes in the buffer to expand in each iteration.
+    pub search_width: usize,
+
+    /// Maximum number of iterations, `0` means searching until
+    /// all nodes in the buffer are expanded.
+    pub max_iterations: usize,
+}
+
+impl Default for CagraSearchParams {
+    fn default() -> Self {
+        Self {
+            itopk_size: DEFAULT_ITOPK_SIZE,
+            search_width: 1,
+            max_iterations: 0,
+        }
+    }
+}
+
+impl From<&Query> for CagraSearchParams {
+    fn from(query: &Query) -> Self {
+        Self {
+            itopk_size: query.ef.unwrap_or(DEFAULT_ITOPK_SIZE).max(query.k),
+            ..Default::default()
+        }
+    }
+}
+
+/// CAGRA graph, the neighbors of node `i` are
+/// `neighbors[i * degree..(i + 1) * degree]`.
+#[derive(Debug, Clone, DeepSizeOf)]
+pub struct CagraGraph {
+    degree: usize,
+    neighbors: Vec<u32>,
+}
+
+impl CagraGraph {
+    pub fn try_new(degree: usize, neighbors: Vec<u32>) -> Result<Self> {
+        if degree == 0 || neighbors.len() % degree != 0 {
+            return Err(Error::Index {
+                message: format!(
+                    "invalid CAGRA graph: {} neighbors with degree {}",
+                    neighbors.len(),
+                    degree
+                ),
+                location: location!(),
+            });
+        }
+        Ok(Self { degree, neighbors })
+    }
+
+    /// Load the graph from the neighbors column.
+    pub fn try_from_array(neighbors: &FixedSizeListArray) -> Result<Self> {
+        let degree = neighbors.value_length() as usize;
+        let values = neighbors.values().as_primitive::<UInt32Type>();
+        Self::try_new(degree, values.values().to_vec())
+    }
+
+    /// The neighbors column.
+    pub fn to_array(&self) -> Result<FixedSizeListArray> {
+        Ok(FixedSizeListArray::try_new_from_values(
+            UInt32Array::from(self.neighbors.clone()),
+            self.degree as i32,
+        )?)
+    }
+
+    /// Number of neighbors of each node.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Number of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.neighbors.len() / self.degree
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.neighbors.is_empty()
+    }
+
+    #[inline]
+    pub fn neighbors(&self, id: u32) -> &[u32] {
+        let start = id as usize * self.degree;
+        &self.neighbors[start..start + self.degree]
+    }
+
+    /// Search the graph for the k nearest neighbors.
+    ///
+    /// The search starts from nodes sampled evenly from the graph, then in each
+    /// iteration expands the `search_width` closest unexpanded nodes of the
+    /// internal top-k buffer, until all nodes in the buffer are expanded.
+    ///
+    /// Nodes not in `bitset` are still traversed but not returned.
+    ///
+    /// Returns an ascending sorted list of at most `k` nodes.
+    pub fn search(
+        &self,
+        dist_calc: &impl DistCalculator,
+        k: usize,
+        params: &CagraSearchParams,
+        bitset: Option<&Visited>,
+        visited: &mut Visited,
+    ) -> Vec<OrderedNode> {
+        if self.is_empty() || k == 0 {
+            return vec![];
+        }
+        let itopk_size = params.itopk_size.max(k);
+        let search_width = params.search_width.max(1);
+        let max_iterations = match params.max_iterations {
+            0 => usize::MAX,
+            n => n,
+        };
+
+        // (node, expanded)
+        let mut buffer: Vec<(OrderedNode, bool)> =
+            Vec::with_capacity(itopk_size + search_width * self.degree);
+        let mut results = BinaryHeap::with_capacity(k);
+        let visit = |id: u32,
+                     buffer: &mut Vec<(OrderedNode, bool)>,
+                     results: &mut BinaryHeap<OrderedNode>| {
+            let dist = OrderedFloat(dist_calc.distance(id));
+            if bitset.map(|bitset| bitset.contains(id)).unwrap_or(true) {
+                if results.len() < k {
+                    results.push(OrderedNode::new(id, dist));
+                } else if dist < results.peek().unwrap().dist {
+                    results.pop();
+                    results.push(OrderedNode::new(id, dist));
+                }
+            }
+            buffer.push((OrderedNode::new(id, dist), false));
+        };
+
+        let num_rows = self.len();
+        let num_seeds = itopk_size.max(search_width * self.degree).min(num_rows);
+        for i in 0..num_seeds {
+            let id = (i * num_rows / num_seeds) as u32;
+            visited.insert(id);
+            visit(id, &mut buffer, &mut results);
+        }
+        buffer.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        buffer.truncate(itopk_size);
+
+        let mut parents = Vec::with_capacity(search_width);
+        for _ in 0..max_iterations {
+            parents.clear();
+            for (node, expanded) in buffer.iter_mut().filter(|(_, expanded)| !expanded) {
+                *expanded = true;
+                parents.push(node.id);
+                if parents.len() == search_width {
+                    break;
+                }
+            }
+            if parents.is_empty() {
+                break;
+            }
+
+            for parent in parents.iter() {
+                for &neighbor in self.neighbors(*parent) {
+                    if visited.contains(neighbor) {
+                        continue;
+                    }
+                    visited.insert(neighbor);
+                    visit(neighbor, &mut buffer, &mut results);
+                }
+            }
+            buffer.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+            buffer.truncate(itopk_size);
+        }
+
+        results.into_sorted_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::{Array, ArrayRef};
+    use lance_linalg::distance::DistanceType;
+    use lance_testing::datagen::generate_random_array;
+
+    use crate::vector::flat::storage::FlatFloatStorage;
+    use crate::vector::graph::VisitedGenerator;
+    use crate::vector::storage::VectorStore;
+
+    #[test]
+    fn test_search_recall() {
+        let num_rows = 2000;
+        let dim = 16;
+        let k = 10;
+        let vectors = FixedSizeListArray::try_new_from_values(
+            generate_random_array(num_rows * dim),
+            dim as i32,
+        )
+        .unwrap();
+        let params = CagraBuildParams {
+            cagra_intermediate_graph_degree: 32,
+            cagra_graph_degree: 16,
+            ..Default::default()
+        };
+        let graph = build_cagra_graph(&vectors, &params).unwrap();
+        let storage = FlatFloatStorage::new(vectors.clone(), DistanceType::L2);
+
+        let mut visited_generator = VisitedGenerator::new(num_rows);
+        let mut bitset_generator = VisitedGenerator::new(num_rows);
+        let mut num_hits = 0;
+        let num_queries = 20;
+        for i in 0..num_queries {
+            let query: ArrayRef = vectors.value(i * 7);
+            let dist_calc = storage.dist_calculator(query.clone());
+            let mut ground_truth = (0..num_rows as u32)
+                .map(|id| OrderedNode::new(id, dist_calc.distance(id).into()))
+                .collect::<Vec<_>>();
+            ground_truth.sort_unstable();
+            let ground_truth = ground_truth[..k].iter().map(|n| n.id).collect::<Vec<_>>();
+
+            let mut visited = visited_generator.generate(num_rows);
+            let results = graph.search(
+                &dist_calc,
+                k,
+                &CagraSearchParams::default(),
+                None,
+                &mut visited,
+            );
+            assert_eq!(results.len(), k);
+            assert!(results.windows(2).all(|w| w[0].dist <= w[1].dist));
+            num_hits += results
+                .iter()
+                .filter(|n| ground_truth.contains(&n.id))
+                .count();
+
+            // only even nodes pass the filter
+            let mut bitset = bitset_generator.generate(num_rows);
+            for id in (0..num_rows as u32).step_by(2) {
+                bitset.insert(id);
+            }
+            drop(visited);
+            let mut visited = visited_generator.generate(num_rows);
+            let results = graph.search(
+                &dist_calc,
+                k,
+                &CagraSearchParams::default(),
+                Some(&bitset),
+                &mut visited,
+            );
+            assert_eq!(results.len(), k);
+            assert!(results.iter().all(|n| n.id % 2 == 0));
+        }
+        let recall = num_hits as f32 / (num_queries * k) as f32;
+        assert!(recall >= 0.9, "recall: {}", recall);
+    }
+
+    #[test]
+    fn test_graph_round_trip() {
+        let graph = CagraGraph::try_new(2, vec![1, 2, 0, 2, 0, 1]).unwrap();
+        let array = graph.to_array().unwrap();
+        assert_eq!(array.len(), 3);
+        let loaded = CagraGraph::try_from_array(&array).unwrap();
+        assert_eq!(loaded.degree(), 2);
+        assert_eq!(loaded.neighbors(1), &[0, 2]);
+
+        assert!(CagraGraph::try_new(2, vec![1, 2, 0]).is_err());
+    }
+}
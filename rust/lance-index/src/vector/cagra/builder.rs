@@ -1,26 +1,105 @@
-use crate::Result;
-use pyo3::prelude::*;
-use pyo3::types::PyList;
-use pyo3::types::PyDict;
-use snafu::location;
-use crate::Error;
-use arrow_array::{Array, PrimitiveArray, FixedSizeListArray};
-use std::sync::Arc;
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Builder of CAGRA graph.
+//!
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use arrow::array::AsArray;
 use arrow::datatypes::Float32Type;
-use pyo3::types::IntoPyDict;
-use arrow_array::Int64Array;
-use arrow_array::StringArray;
-use pyo3::Py;
+use arrow_array::{Array, FixedSizeListArray};
+use arrow_schema::DataType;
+use lance_arrow::FixedSizeListArrayExt;
+use lance_core::{Error, Result};
+use lance_linalg::distance::{DistanceFunc, DistanceType};
+use lance_linalg::kmeans::{compute_partitions_arrow_array, kmeans_find_partitions};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use snafu::location;
+
+use super::CagraGraph;
+use crate::pb;
+use crate::vector::kmeans::train_kmeans;
+
+/// Maximum number of NN-descent iterations.
+const NN_DESCENT_MAX_ITERATIONS: usize = 20;
+
+/// NN-descent stops early once fewer than `delta * num_rows * k` neighbor
+/// lists were updated in one iteration.
+const NN_DESCENT_DELTA: f64 = 0.001;
+
+/// Maximum number of new / old candidates joined per node in one NN-descent
+/// iteration, this bounds the cost of one iteration to `O(n * C^2)`.
+const NN_DESCENT_MAX_CANDIDATES: usize = 50;
 
-// Parameters for building product quantizer.
+/// With IVF seeding, partitions are probed until at least
+/// `IVF_SEED_CANDIDATE_FACTOR * k` candidates are collected for a vector.
+const IVF_SEED_CANDIDATE_FACTOR: usize = 4;
+
+/// Below this number of rows the exact kNN graph is computed.
+const EXHAUSTIVE_KNN_THRESHOLD: usize = 4096;
+
+const RANDOM_SEED: u64 = 42;
+
+/// The algorithm used to build the initial kNN graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CagraBuildAlgo {
+    /// NN-descent, refines a random graph by joining the neighbors of neighbors.
+    NnDescent,
+    /// Candidates are seeded from the closest IVF partitions of each vector,
+    /// then ranked by the exact distance.
+    ///
+    /// Also accepted as `ivf_pq`, the name of the GPU algorithm, but the vectors
+    /// are not product quantized.
+    Ivf,
+}
+
+impl std::fmt::Display for CagraBuildAlgo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NnDescent => write!(f, "nn_descent"),
+            Self::Ivf => write!(f, "ivf"),
+        }
+    }
+}
+
+impl TryFrom<&str> for CagraBuildAlgo {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "nn_descent" => Ok(Self::NnDescent),
+            "ivf" | "ivf_pq" => Ok(Self::Ivf),
+            _ => Err(Error::Index {
+                message: format!(
+                    "unknown CAGRA build algorithm {}, expected 'nn_descent' or 'ivf'",
+                    s
+                ),
+                location: location!(),
+            }),
+        }
+    }
+}
+
+/// Parameters of building CAGRA graph.
 #[derive(Debug, Clone)]
 pub struct CagraBuildParams {
-    /// cagra build algorithm
+    /// The distance metric, accepts `sqeuclidean` and `inner_product` as well as
+    /// the names supported by [DistanceType].
     pub cagra_metric: String,
+
+    /// Number of neighbors of each node in the kNN graph before pruning.
     pub cagra_intermediate_graph_degree: u32,
+
+    /// Number of neighbors of each node in the final graph.
     pub cagra_graph_degree: u32,
-    pub cagra_build_algo: String,
 
+    /// The algorithm to build the kNN graph, `nn_descent` or `ivf`.
+    pub cagra_build_algo: String,
 }
 
 impl Default for CagraBuildParams {
@@ -29,104 +108,575 @@ impl Default for CagraBuildParams {
             cagra_metric: "sqeuclidean".to_string(),
             cagra_intermediate_graph_degree: 128,
             cagra_graph_degree: 64,
-            cagra_build_algo: "ivf_pq".to_string(),
+            cagra_build_algo: "ivf".to_string(),
         }
     }
 }
 
 impl CagraBuildParams {
-    fn iter(&self, py: Python<'_>) -> Py<PyDict> {
-        let param_vec = vec![
-            ("metric", self.cagra_metric.to_string()),
-            ("intermediate_graph_degree", self.cagra_intermediate_graph_degree.to_string()),
-            ("graph_degree", self.cagra_graph_degree.to_string()),
-            ("algo", self.cagra_build_algo.to_string()),
-        ];
+    /// The distance type to build the graph with.
+    pub fn distance_type(&self) -> Result<DistanceType> {
+        match self.cagra_metric.to_lowercase().as_str() {
+            "sqeuclidean" => Ok(DistanceType::L2),
+            "inner_product" => Ok(DistanceType::Dot),
+            "hamming" => Err(Error::Index {
+                message: "CAGRA does not support hamming distance".to_string(),
+                location: location!(),
+            }),
+            metric => Ok(DistanceType::try_from(metric)?),
+        }
+    }
+
+    pub fn build_algo(&self) -> Result<CagraBuildAlgo> {
+        CagraBuildAlgo::try_from(self.cagra_build_algo.as_str())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.cagra_graph_degree == 0 {
+            return Err(Error::Index {
+                message: "CAGRA graph_degree must be greater than 0".to_string(),
+                location: location!(),
+            });
+        }
+        if self.cagra_intermediate_graph_degree < self.cagra_graph_degree {
+            return Err(Error::Index {
+                message: format!(
+                    "CAGRA intermediate_graph_degree ({}) must be greater than or equal to graph_degree ({})",
+                    self.cagra_intermediate_graph_degree, self.cagra_graph_degree
+                ),
+                location: location!(),
+            });
+        }
+        Ok(())
+    }
+}
 
-        return param_vec.into_py_dict(py).expect("Failed to convert to PyDict").into();
+impl From<&pb::Cagra> for CagraBuildParams {
+    fn from(proto: &pb::Cagra) -> Self {
+        Self {
+            cagra_metric: proto.metric.clone(),
+            cagra_intermediate_graph_degree: proto.intermediate_graph_degree,
+            cagra_graph_degree: proto.graph_degree,
+            cagra_build_algo: proto.build_algo.clone(),
+        }
     }
 }
 
-fn print_type<T>(_: &T) { 
-    println!("{:?}", std::any::type_name::<T>());
+impl From<&CagraBuildParams> for pb::Cagra {
+    fn from(params: &CagraBuildParams) -> Self {
+        Self {
+            build_algo: params.cagra_build_algo.clone(),
+            metric: params.cagra_metric.clone(),
+            graph_degree: params.cagra_graph_degree,
+            intermediate_graph_degree: params.cagra_intermediate_graph_degree,
+        }
+    }
 }
 
-fn data_array_to_pylist(py: Python<'_>, array: &Arc<dyn arrow_array::Array>) -> Py<PyList> {
-    //eprintln!("{:?}", print_type(&array));
+/// Build the CAGRA graph over the vectors.
+///
+/// The vectors must be a [FixedSizeListArray] of `f32` without nulls, the id of
+/// each node in the graph is its offset in the array.
+pub fn build_cagra_graph(
+    vectors: &FixedSizeListArray,
+    params: &CagraBuildParams,
+) -> Result<CagraGraph> {
+    params.validate()?;
+    let distance_type = params.distance_type()?;
+    let build_algo = params.build_algo()?;
+    if vectors.value_type() != DataType::Float32 {
+        return Err(Error::Index {
+            message: format!(
+                "CAGRA graph can only be built on float32 vectors, got {}",
+                vectors.value_type()
+            ),
+            location: location!(),
+        });
+    }
+
+    let num_rows = vectors.len();
+    let degree = params.cagra_graph_degree as usize;
+    if num_rows <= 1 {
+        // a single node points to itself so every node has the same degree.
+        let degree = degree.min(num_rows.max(1));
+        return CagraGraph::try_new(degree, vec![0; num_rows * degree]);
+    }
 
-    if let Some(list_array) = array.as_any().downcast_ref::<FixedSizeListArray>() {
-        let py_outer_list = PyList::empty(py);
+    let dim = vectors.value_length() as usize;
+    let data = vectors.values().as_primitive::<Float32Type>();
+    let data = VectorSlices {
+        data: data.values(),
+        dim,
+        distance_fn: distance_type.func(),
+    };
+
+    let degree = degree.min(num_rows - 1);
+    let k = (params.cagra_intermediate_graph_degree as usize).min(num_rows - 1);
+    log::info!(
+        "Building CAGRA graph: num={}, intermediate_graph_degree={}, graph_degree={}, build_algo={}, distance_type={}",
+        num_rows,
+        k,
+        degree,
+        build_algo,
+        distance_type,
+    );
+
+    let knn = if num_rows <= EXHAUSTIVE_KNN_THRESHOLD {
+        exhaustive_knn_graph(&data, k)
+    } else {
+        match build_algo {
+            CagraBuildAlgo::NnDescent => nn_descent_knn_graph(&data, k),
+            CagraBuildAlgo::Ivf => ivf_knn_graph(vectors, &data, k)?,
+        }
+    };
 
-        for i in 0..list_array.len() {
-            let subarray = list_array.value(i);
+    let pruned = prune_knn_graph(&knn, k, degree);
+    let neighbors = merge_reverse_edges(&pruned, degree);
+    CagraGraph::try_new(degree, neighbors)
+}
+
+struct VectorSlices<'a> {
+    data: &'a [f32],
+    dim: usize,
+    distance_fn: DistanceFunc<f32>,
+}
+
+impl VectorSlices<'_> {
+    fn len(&self) -> usize {
+        self.data.len() / self.dim
+    }
+
+    #[inline]
+    fn vector(&self, id: u32) -> &[f32] {
+        let start = id as usize * self.dim;
+        &self.data[start..start + self.dim]
+    }
+
+    #[inline]
+    fn distance(&self, a: u32, b: u32) -> f32 {
+        (self.distance_fn)(self.vector(a), self.vector(b))
+    }
+}
+
+/// The neighbors of one node, sorted by distance in ascending order.
+#[derive(Debug, Clone, Default)]
+struct NeighborList {
+    // (distance, id, is_new)
+    neighbors: Vec<(f32, u32, bool)>,
+}
+
+impl NeighborList {
+    /// Insert a neighbor if it's closer than the farthest one,
+    /// returns true if the list was updated.
+    fn insert(&mut self, id: u32, dist: f32, capacity: usize) -> bool {
+        if self.neighbors.len() >= capacity
+            && self
+                .neighbors
+                .last()
+                .map(|(d, _, _)| dist >= *d)
+                .unwrap_or(false)
+        {
+            return false;
+        }
+        if self.neighbors.iter().any(|(_, n, _)| *n == id) {
+            return false;
+        }
+        let pos = self
+            .neighbors
+            .partition_point(|(d, _, _)| d.total_cmp(&dist).is_le());
+        self.neighbors.insert(pos, (dist, id, true));
+        self.neighbors.truncate(capacity);
+        true
+    }
 
-            if let Some(float_subarray) = subarray.as_any().downcast_ref::<PrimitiveArray<Float32Type>>(){
-                let py_inner_list = PyList::new(py, float_subarray).unwrap();
-                py_outer_list.append(py_inner_list).unwrap();
-            } else {
-                py_outer_list.append(PyList::empty(py)).unwrap();
+    fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.neighbors.iter().map(|(_, id, _)| *id)
+    }
+}
+
+/// Flatten the neighbor lists into `num_rows * k` ids.
+fn flatten(lists: Vec<NeighborList>, k: usize) -> Vec<u32> {
+    let mut ids = Vec::with_capacity(lists.len() * k);
+    for list in lists {
+        debug_assert_eq!(list.neighbors.len(), k);
+        ids.extend(list.ids());
+    }
+    ids
+}
+
+fn exhaustive_knn_graph(data: &VectorSlices, k: usize) -> Vec<u32> {
+    let num_rows = data.len();
+    let lists = (0..num_rows as u32)
+        .into_par_iter()
+        .map(|id| {
+            let mut list = NeighborList::default();
+            for other in (0..num_rows as u32).filter(|other| *other != id) {
+                list.insert(other, data.distance(id, other), k);
+            }
+            list
+        })
+        .collect::<Vec<_>>();
+    flatten(lists, k)
+}
+
+/// Build an approximate kNN graph with NN-descent.
+///
+/// Dong, W., Moses, C., & Li, K. (2011). Efficient k-nearest neighbor graph
+/// construction for generic similarity measures.
+fn nn_descent_knn_graph(data: &VectorSlices, k: usize) -> Vec<u32> {
+    let num_rows = data.len();
+    let mut rng = SmallRng::seed_from_u64(RANDOM_SEED);
+    let lists = (0..num_rows as u32)
+        .map(|id| {
+            let mut list = NeighborList::default();
+            while list.neighbors.len() < k {
+                let other = rng.gen_range(0..num_rows as u32);
+                if other != id {
+                    list.insert(other, data.distance(id, other), k);
+                }
+            }
+            Mutex::new(list)
+        })
+        .collect::<Vec<_>>();
+
+    let max_candidates = k.min(NN_DESCENT_MAX_CANDIDATES);
+    for iteration in 0..NN_DESCENT_MAX_ITERATIONS {
+        // split the neighbors into new and old ones, the new ones are marked as old
+        // once they are sampled to join.
+        let mut new_candidates = vec![Vec::new(); num_rows];
+        let mut old_candidates = vec![Vec::new(); num_rows];
+        for (id, list) in lists.iter().enumerate() {
+            let mut list = list.lock().unwrap();
+            for (_, neighbor, is_new) in list.neighbors.iter_mut() {
+                if *is_new {
+                    if new_candidates[id].len() < max_candidates {
+                        new_candidates[id].push(*neighbor);
+                        *is_new = false;
+                    }
+                } else if old_candidates[id].len() < max_candidates {
+                    old_candidates[id].push(*neighbor);
+                }
             }
         }
-        return py_outer_list.into();
-    } else {
-        return PyList::empty(py).into();
+
+        // add reverse neighbors
+        let mut new_reverse = vec![Vec::new(); num_rows];
+        let mut old_reverse = vec![Vec::new(); num_rows];
+        for id in 0..num_rows {
+            for &neighbor in new_candidates[id].iter() {
+                new_reverse[neighbor as usize].push(id as u32);
+            }
+            for &neighbor in old_candidates[id].iter() {
+                old_reverse[neighbor as usize].push(id as u32);
+            }
+        }
+        for (candidates, reverse) in new_candidates
+            .iter_mut()
+            .zip(new_reverse.iter())
+            .chain(old_candidates.iter_mut().zip(old_reverse.iter()))
+        {
+            for &id in reverse.iter().take(max_candidates) {
+                if !candidates.contains(&id) {
+                    candidates.push(id);
+                }
+            }
+        }
+
+        // local join
+        let num_updates = (0..num_rows)
+            .into_par_iter()
+            .map(|id| {
+                let new = &new_candidates[id];
+                let old = &old_candidates[id];
+                let mut updates = 0;
+                for (i, &a) in new.iter().enumerate() {
+                    for &b in new[i + 1..].iter().chain(old.iter()) {
+                        if a == b {
+                            continue;
+                        }
+                        let dist = data.distance(a, b);
+                        if lists[a as usize].lock().unwrap().insert(b, dist, k) {
+                            updates += 1;
+                        }
+                        if lists[b as usize].lock().unwrap().insert(a, dist, k) {
+                            updates += 1;
+                        }
+                    }
+                }
+                updates
+            })
+            .sum::<usize>();
+
+        log::debug!(
+            "NN-descent iteration {}: {} neighbor updates",
+            iteration,
+            num_updates
+        );
+        if (num_updates as f64) <= NN_DESCENT_DELTA * (num_rows * k) as f64 {
+            break;
+        }
     }
+
+    flatten(
+        lists
+            .into_iter()
+            .map(|list| list.into_inner().unwrap())
+            .collect(),
+        k,
+    )
 }
 
-fn id_array_to_pylist(py: Python<'_>, array: &Arc<dyn arrow_array::Array>) -> Py<PyList> {
-    let mut id_values = PyList::empty(py);
-    if let Some(string_array) = array.as_any().downcast_ref::<StringArray>() {
-        for i in 0..string_array.len() {
-            let val = string_array.value(i);
-            match val.parse::<i64>() {
-                Ok(parsed) =>  id_values.append(parsed).unwrap(),
-                Err(err) => {
-                    eprintln!("Failed to parse '{}' as i64 at index {}: {}", val, i, err);
-                    continue;
+/// Build an approximate kNN graph by searching the closest IVF partitions of
+/// every vector.
+///
+/// The partitions are only used to find candidates, so they are trained with L2
+/// regardless of the distance type, candidates are then ranked by the exact distance.
+fn ivf_knn_graph(vectors: &FixedSizeListArray, data: &VectorSlices, k: usize) -> Result<Vec<u32>> {
+    let num_rows = data.len();
+    // A NaN or null vector has no partition and thus no neighbors
+    let invalid_vector = |id: usize| Error::InvalidInput {
+        source: format!(
+            "Vector {} is null or has NaN values, it has no IVF partition",
+            id
+        )
+        .into(),
+        location: location!(),
+    };
+    if let Some(id) = (0..num_rows)
+        .find(|id| vectors.is_null(*id) || data.vector(*id as u32).iter().any(|v| v.is_nan()))
+    {
+        return Err(invalid_vector(id));
+    }
+
+    let num_partitions = ((num_rows as f64).sqrt() as usize).max(1);
+    let kmeans = train_kmeans::<Float32Type>(
+        None,
+        vectors.values().as_primitive::<Float32Type>(),
+        data.dim,
+        num_partitions,
+        50,
+        1,
+        DistanceType::L2,
+        256,
+    )?;
+    let centroids = FixedSizeListArray::try_new_from_values(
+        kmeans.centroids.as_primitive::<Float32Type>().clone(),
+        data.dim as i32,
+    )?;
+    let (partition_ids, _) = compute_partitions_arrow_array(&centroids, vectors, DistanceType::L2)?;
+    let mut partitions = vec![Vec::new(); num_partitions];
+    for (id, part_id) in partition_ids.into_iter().enumerate() {
+        let part_id = part_id.ok_or_else(|| invalid_vector(id))?;
+        partitions[part_id as usize].push(id as u32);
+    }
+
+    let centroid_values = centroids.values().as_primitive::<Float32Type>();
+    let num_candidates = (IVF_SEED_CANDIDATE_FACTOR * k).min(num_rows - 1);
+    let lists = (0..num_rows as u32)
+        .into_par_iter()
+        .map(|id| {
+            let probes = kmeans_find_partitions(
+                centroid_values.values(),
+                data.vector(id),
+                num_partitions,
+                DistanceType::L2,
+            )?;
+            let mut list = NeighborList::default();
+            let mut visited = 0;
+            for part_id in probes.values().iter() {
+                for &other in partitions[*part_id as usize].iter() {
+                    if other != id {
+                        list.insert(other, data.distance(id, other), k);
+                        visited += 1;
+                    }
+                }
+                if visited >= num_candidates {
+                    break;
                 }
             }
+            Ok(list)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(flatten(lists, k))
+}
+
+/// Rank-based pruning of the kNN graph.
+///
+/// For the edge from `x` to its `j`-th neighbor `y`, a detourable route is a
+/// 2-hop path `x -> z -> y` where `z` is the `i`-th neighbor of `x` and `y` is the
+/// `r`-th neighbor of `z`, with `max(i, r) < j`. The edges with the fewest
+/// detourable routes are kept, in the order of the original rank.
+fn prune_knn_graph(knn: &[u32], k: usize, degree: usize) -> Vec<u32> {
+    let num_rows = knn.len() / k;
+    (0..num_rows)
+        .into_par_iter()
+        .flat_map_iter(|id| {
+            let neighbors = &knn[id * k..(id + 1) * k];
+            let rank: HashMap<u32, usize> = neighbors
+                .iter()
+                .enumerate()
+                .map(|(rank, neighbor)| (*neighbor, rank))
+                .collect();
+
+            let mut detours = vec![0_usize; k];
+            for (i, &z) in neighbors.iter().enumerate() {
+                let z_neighbors = &knn[z as usize * k..(z as usize + 1) * k];
+                for (r, y) in z_neighbors.iter().enumerate() {
+                    if let Some(&j) = rank.get(y) {
+                        if i.max(r) < j {
+                            detours[j] += 1;
+                        }
+                    }
+                }
+            }
+
+            let mut selected = (0..k).collect::<Vec<_>>();
+            selected.sort_by_key(|&j| (detours[j], j));
+            selected.truncate(degree);
+            selected.sort_unstable();
+            selected.into_iter().map(move |j| neighbors[j])
+        })
+        .collect()
+}
+
+/// Merge the pruned graph with its reverse edges.
+///
+/// Half of the neighbors come from the pruned graph, the rest are filled with
+/// the reverse edges (ordered by the rank of the edge), and then the remaining
+/// pruned neighbors.
+fn merge_reverse_edges(pruned: &[u32], degree: usize) -> Vec<u32> {
+    let num_rows = pruned.len() / degree;
+    let mut reverse = vec![Vec::new(); num_rows];
+    for rank in 0..degree {
+        for id in 0..num_rows {
+            let neighbor = pruned[id * degree + rank];
+            if reverse[neighbor as usize].len() < degree {
+                reverse[neighbor as usize].push(id as u32);
+            }
         }
     }
-    return id_values.into();
+
+    let num_forward = degree.div_ceil(2);
+    (0..num_rows)
+        .into_par_iter()
+        .flat_map_iter(|id| {
+            let forward = &pruned[id * degree..(id + 1) * degree];
+            let mut neighbors = Vec::with_capacity(degree);
+            neighbors.extend_from_slice(&forward[..num_forward]);
+            for candidate in reverse[id].iter().chain(forward[num_forward..].iter()) {
+                if neighbors.len() == degree {
+                    break;
+                }
+                if !neighbors.contains(candidate) {
+                    neighbors.push(*candidate);
+                }
+            }
+            neighbors
+        })
+        .collect()
 }
 
-pub async fn build_cagra_index(
-    data: &Arc<dyn arrow_array::Array>,
-    ids: &Arc<dyn arrow_array::Array>,
-    cagra_params: &CagraBuildParams
-) -> Result<()> {
-    Python::with_gil(|py| {
-        let module = PyModule::import(py, "lance.cagra").map_err(|e| Error::Index {
-            message: format!("Failed to import lance.cagra: {}", e),
-            location: location!(),
-        })?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let function = module.getattr("build_cagra_index").map_err(|e| Error::Index {
-            message: format!("Failed to get attribute {}", e),
-            location: location!(),
-        })?;
-
-        let data_result = data_array_to_pylist(py, data);
-        // if(data_result.is_empty()) {
-        //     eprintln!("Data not transformed properly");
-        // }
-        // let data_list = match data_result {
-        //     Ok(obj) => obj,
-        //     Err(e) => return Err(Error::Index {
-        //         message: format!("Failed to call inner function: {}", e),
-        //         location: location!(),
-        //     }),
-        // };
-
-        let cagra_params_vec = cagra_params.iter(py);
-        let ids_pylist = id_array_to_pylist(py, ids);
-
-        function.call1((data_result, ids_pylist, cagra_params_vec)).map_err(|e| Error::Index {
-            message: format!("Failed to call function {}", e),
-            location: location!(),
-        })?;
+    use lance_arrow::FixedSizeListArrayExt;
+    use lance_testing::datagen::generate_random_array;
 
-        Ok(())
-    })
+    fn build(num_rows: usize, build_algo: &str) -> CagraGraph {
+        let dim = 16;
+        let vectors = FixedSizeListArray::try_new_from_values(
+            generate_random_array(num_rows * dim),
+            dim as i32,
+        )
+        .unwrap();
+        let params = CagraBuildParams {
+            cagra_intermediate_graph_degree: 32,
+            cagra_graph_degree: 16,
+            cagra_build_algo: build_algo.to_string(),
+            ..Default::default()
+        };
+        build_cagra_graph(&vectors, &params).unwrap()
+    }
 
-}
\ No newline at end of file
+    fn assert_fixed_degree(graph: &CagraGraph) {
+        for id in 0..graph.len() as u32 {
+            let neighbors = graph.neighbors(id);
+            assert_eq!(neighbors.len(), graph.degree());
+            assert!(!neighbors.contains(&id), "node {} points to itself", id);
+            let mut unique = neighbors.to_vec();
+            unique.sort_unstable();
+            unique.dedup();
+            assert_eq!(unique.len(), graph.degree());
+        }
+    }
+
+    #[test]
+    fn test_build_small_graph() {
+        let graph = build(100, "nn_descent");
+        assert_eq!(graph.len(), 100);
+        assert_eq!(graph.degree(), 16);
+        assert_fixed_degree(&graph);
+
+        // fewer rows than the degree
+        let graph = build(5, "ivf");
+        assert_eq!(graph.degree(), 4);
+        assert_fixed_degree(&graph);
+    }
+
+    #[test]
+    fn test_build_nn_descent() {
+        let graph = build(5000, "nn_descent");
+        assert_eq!(graph.len(), 5000);
+        assert_fixed_degree(&graph);
+    }
+
+    #[test]
+    fn test_build_ivf() {
+        let graph = build(5000, "ivf");
+        assert_eq!(graph.len(), 5000);
+        assert_fixed_degree(&graph);
+
+        // the name of the GPU algorithm is an alias
+        assert_eq!(
+            CagraBuildAlgo::try_from("ivf_pq").unwrap(),
+            CagraBuildAlgo::Ivf
+        );
+    }
+
+    #[test]
+    fn test_build_ivf_with_nan() {
+        let dim = 16;
+        let mut values = generate_random_array(5000 * dim).values().to_vec();
+        values[100 * dim] = f32::NAN;
+        let vectors = FixedSizeListArray::try_new_from_values(
+            arrow_array::Float32Array::from(values),
+            dim as i32,
+        )
+        .unwrap();
+        let params = CagraBuildParams {
+            cagra_build_algo: "ivf".to_string(),
+            ..Default::default()
+        };
+        let result = build_cagra_graph(&vectors, &params);
+        assert!(matches!(result, Err(Error::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let vectors =
+            FixedSizeListArray::try_new_from_values(generate_random_array(16), 4).unwrap();
+        let params = CagraBuildParams {
+            cagra_intermediate_graph_degree: 8,
+            cagra_graph_degree: 16,
+            ..Default::default()
+        };
+        assert!(build_cagra_graph(&vectors, &params).is_err());
+
+        let params = CagraBuildParams {
+            cagra_build_algo: "unknown".to_string(),
+            ..Default::default()
+        };
+        assert!(build_cagra_graph(&vectors, &params).is_err());
+    }
+}
@@ -80,6 +80,10 @@ impl FlatFloatStorage {
     pub fn vector(&self, id: u32) -> ArrayRef {
         self.vectors.value(id as usize)
     }
+
+    pub fn dim(&self) -> usize {
+        self.vectors.value_length() as usize
+    }
 }
 
 impl VectorStore for FlatFloatStorage {
@@ -13,6 +13,7 @@ use deepsize::DeepSizeOf;
 use itertools::Itertools;
 use lance_arrow::*;
 use lance_core::{Error, Result};
+pub(crate) use lance_encoding::encodings::physical::quantized_vector::scale_to_u8;
 use lance_linalg::distance::DistanceType;
 use num_traits::*;
 use snafu::location;
@@ -261,22 +262,6 @@ impl Quantization for ScalarQuantizer {
     }
 }
 
-pub(crate) fn scale_to_u8<T: ArrowFloatType>(values: &[T::Native], bounds: &Range<f64>) -> Vec<u8> {
-    if bounds.start == bounds.end {
-        return vec![0; values.len()];
-    }
-
-    let range = bounds.end - bounds.start;
-    values
-        .iter()
-        .map(|&v| {
-            let v = v.to_f64().unwrap();
-            let v = ((v - bounds.start) * 255.0 / range).round();
-            v as u8 // rust `as` performs saturating cast when casting float to int, so it's safe and expected here
-        })
-        .collect_vec()
-}
-
 pub(crate) fn inverse_scalar_dist(
     values: impl Iterator<Item = f32>,
     bounds: &Range<f64>,
@@ -272,6 +272,11 @@ pub struct Fragment {
     /// unknown. This is only optional for legacy reasons. All new tables should
     /// have this set.
     pub physical_rows: Option<usize>,
+
+    /// The partition values of the rows in this fragment, one for each field of
+    /// the dataset's partition spec.  Empty if the dataset is not partitioned.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partition_values: Vec<Option<String>>,
 }
 
 impl Fragment {
@@ -282,6 +287,7 @@ impl Fragment {
             deletion_file: None,
             row_id_meta: None,
             physical_rows: None,
+            partition_values: vec![],
         }
     }
 
@@ -319,6 +325,7 @@ impl Fragment {
             deletion_file: None,
             physical_rows,
             row_id_meta: None,
+            partition_values: vec![],
         }
     }
 
@@ -437,6 +444,11 @@ impl TryFrom<pb::DataFragment> for Fragment {
             deletion_file: p.deletion_file.map(DeletionFile::try_from).transpose()?,
             row_id_meta: p.row_id_sequence.map(RowIdMeta::try_from).transpose()?,
             physical_rows,
+            partition_values: p
+                .partition_values
+                .into_iter()
+                .map(|value| value.value)
+                .collect(),
         })
     }
 }
@@ -473,6 +485,13 @@ impl From<&Fragment> for pb::DataFragment {
             deletion_file,
             row_id_sequence,
             physical_rows: f.physical_rows.unwrap_or_default() as u64,
+            partition_values: f
+                .partition_values
+                .iter()
+                .map(|value| pb::PartitionValue {
+                    value: value.clone(),
+                })
+                .collect(),
         }
     }
 }
@@ -751,6 +751,7 @@ mod tests {
                 deletion_file: None,
                 row_id_meta: None,
                 physical_rows: None,
+                partition_values: vec![],
             },
             Fragment {
                 id: 1,
@@ -761,6 +762,7 @@ mod tests {
                 deletion_file: None,
                 row_id_meta: None,
                 physical_rows: None,
+                partition_values: vec![],
             },
         ];
 
@@ -48,12 +48,15 @@ use take::row_indices_to_row_addresses;
 use tracing::{info, instrument};
 
 mod blob;
+mod branch_merge;
 pub mod builder;
 pub mod cleanup;
+mod diff;
 pub mod fragment;
 mod hash_joiner;
 pub mod index;
 pub mod optimize;
+mod partition;
 pub mod progress;
 pub mod refs;
 pub(crate) mod rowids;
@@ -69,7 +72,7 @@ mod write;
 use self::builder::DatasetBuilder;
 use self::cleanup::RemovalStats;
 use self::fragment::FileFragment;
-use self::refs::Tags;
+use self::refs::{Branches, Tags};
 use self::scanner::{DatasetRecordBatchStream, Scanner};
 use self::transaction::{Operation, Transaction};
 use self::write::write_fragments_internal;
@@ -84,9 +87,12 @@ use crate::session::Session;
 use crate::utils::temporal::{timestamp_to_nanos, utc_now, SystemTime};
 use crate::{Error, Result};
 pub use blob::BlobFile;
+pub use branch_merge::{MergeConflict, MergeConflictKind, MergeReport};
+pub use diff::{ChangeType, CHANGE_TYPE_COLUMN, COMMIT_VERSION_COLUMN};
 use hash_joiner::HashJoiner;
 pub use lance_core::ROW_ID;
 use lance_table::feature_flags::{apply_feature_flags, can_read_dataset};
+pub use partition::{PartitionField, PartitionSpec, PartitionTransform};
 pub use schema_evolution::{
     BatchInfo, BatchUDF, ColumnAlteration, NewColumnTransform, UDFCheckpointStore,
 };
@@ -126,6 +132,9 @@ pub struct Dataset {
     pub(crate) manifest_location: ManifestLocation,
     pub(crate) session: Arc<Session>,
     pub tags: Tags,
+    pub branches: Branches,
+    /// The branch that is checked out, or `None` for the main branch.
+    pub(crate) branch: Option<String>,
 }
 
 impl std::fmt::Debug for Dataset {
@@ -134,6 +143,7 @@ impl std::fmt::Debug for Dataset {
             .field("uri", &self.uri)
             .field("base", &self.base)
             .field("version", &self.manifest.version)
+            .field("branch", &self.branch)
             .field("cache_num_items", &self.session.approx_num_items())
             .finish()
     }
@@ -318,8 +328,69 @@ impl Dataset {
     pub async fn checkout_version(&self, version: impl Into<refs::Ref>) -> Result<Self> {
         let ref_: refs::Ref = version.into();
         match ref_ {
-            refs::Ref::Version(version) => self.checkout_by_version_number(version).await,
+            refs::Ref::Version(version) => {
+                self.checkout_by_version_number(self.branch.clone(), version)
+                    .await
+            }
             refs::Ref::Tag(tag) => self.checkout_by_tag(tag.as_str()).await,
+            refs::Ref::Branch(branch) => self.checkout_branch(branch.as_str()).await,
+        }
+    }
+
+    /// Check out the latest version of a branch.
+    ///
+    /// Subsequent commits to the returned dataset are written to the branch and
+    /// do not affect the main branch. Pass [`refs::MAIN_BRANCH`] to return to the
+    /// main branch.
+    pub async fn checkout_branch(&self, branch: &str) -> Result<Self> {
+        let branch = if branch == refs::MAIN_BRANCH {
+            None
+        } else {
+            // Validates that the branch exists
+            self.branches.get(branch).await?;
+            Some(branch.to_string())
+        };
+        let manifest_base = self.branch_manifest_base(branch.as_deref());
+        let manifest_location = self
+            .commit_handler
+            .resolve_latest_location(&manifest_base, &self.object_store)
+            .await?;
+        let manifest = Self::load_manifest(
+            self.object_store.as_ref(),
+            &manifest_location,
+            &manifest_base,
+            self.session.as_ref(),
+        )
+        .await?;
+        Self::checkout_manifest(
+            self.object_store.clone(),
+            self.base.clone(),
+            self.uri.clone(),
+            Arc::new(manifest),
+            manifest_location,
+            self.session.clone(),
+            self.commit_handler.clone(),
+            branch,
+        )
+    }
+
+    /// The name of the checked out branch, or `None` for the main branch.
+    pub fn branch(&self) -> Option<&str> {
+        self.branch.as_deref()
+    }
+
+    /// The root directory of the manifests of the checked out branch.
+    ///
+    /// Data, deletion, index and transaction files are always stored relative to
+    /// [`Self::base`], only the manifests of a branch live under their own prefix.
+    pub(crate) fn manifest_base(&self) -> Path {
+        self.branch_manifest_base(self.branch.as_deref())
+    }
+
+    fn branch_manifest_base(&self, branch: Option<&str>) -> Path {
+        match branch {
+            Some(branch) => refs::branch_path(&self.base, branch),
+            None => self.base.clone(),
         }
     }
 
@@ -344,44 +415,50 @@ impl Dataset {
             })
     }
 
-    async fn checkout_by_version_number(&self, version: u64) -> Result<Self> {
-        let base_path = self.base.clone();
+    async fn checkout_by_version_number(
+        &self,
+        branch: Option<String>,
+        version: u64,
+    ) -> Result<Self> {
+        let manifest_base = self.branch_manifest_base(branch.as_deref());
         let manifest_location = self
             .commit_handler
-            .resolve_version_location(&base_path, version, &self.object_store.inner)
+            .resolve_version_location(&manifest_base, version, &self.object_store.inner)
             .await?;
 
-        if self.already_checked_out(&manifest_location) {
+        if self.branch == branch && self.already_checked_out(&manifest_location) {
             return Ok(self.clone());
         }
 
         let manifest = Self::load_manifest(
             self.object_store.as_ref(),
             &manifest_location,
-            &base_path,
+            &manifest_base,
             self.session.as_ref(),
         )
         .await?;
         Self::checkout_manifest(
             self.object_store.clone(),
-            base_path,
+            self.base.clone(),
             self.uri.clone(),
             Arc::new(manifest),
             manifest_location,
             self.session.clone(),
             self.commit_handler.clone(),
+            branch,
         )
     }
 
     async fn checkout_by_tag(&self, tag: &str) -> Result<Self> {
+        // Tags always point at versions of the main branch
         let version = self.tags.get_version(tag).await?;
-        self.checkout_by_version_number(version).await
+        self.checkout_by_version_number(None, version).await
     }
 
     async fn load_manifest(
         object_store: &ObjectStore,
         manifest_location: &ManifestLocation,
-        base_path: &Path,
+        manifest_base: &Path,
         session: &Session,
     ) -> Result<Manifest> {
         let object_reader = if let Some(size) = manifest_location.size {
@@ -458,7 +535,7 @@ impl Dataset {
                     .map(Index::try_from)
                     .collect::<Result<Vec<_>>>()?;
                 session.index_cache.insert_metadata(
-                    base_path.as_ref(),
+                    manifest_base.as_ref(),
                     manifest_location.version,
                     Arc::new(indices),
                 );
@@ -479,12 +556,18 @@ impl Dataset {
         manifest_location: ManifestLocation,
         session: Arc<Session>,
         commit_handler: Arc<dyn CommitHandler>,
+        branch: Option<String>,
     ) -> Result<Self> {
         let tags = Tags::new(
             object_store.clone(),
             commit_handler.clone(),
             base_path.clone(),
         );
+        let branches = Branches::new(
+            object_store.clone(),
+            commit_handler.clone(),
+            base_path.clone(),
+        );
         Ok(Self {
             object_store,
             base: base_path,
@@ -494,6 +577,8 @@ impl Dataset {
             commit_handler,
             session,
             tags,
+            branches,
+            branch,
         })
     }
 
@@ -577,6 +662,7 @@ impl Dataset {
                 blob_manifest_location,
                 self.session.clone(),
                 self.commit_handler.clone(),
+                None,
             )?;
             Ok(Some(Arc::new(blobs_dataset)))
         } else {
@@ -595,7 +681,7 @@ impl Dataset {
     pub async fn latest_manifest(&self) -> Result<(Arc<Manifest>, ManifestLocation)> {
         let location = self
             .commit_handler
-            .resolve_latest_location(&self.base, &self.object_store)
+            .resolve_latest_location(&self.manifest_base(), &self.object_store)
             .await?;
 
         // Check if manifest is in cache before reading from storage
@@ -656,6 +742,41 @@ impl Dataset {
         Ok(())
     }
 
+    /// Merge the changes committed on `branch` into the checked out branch.
+    ///
+    /// `branch` must have been created from the checked out branch. Every
+    /// transaction committed on `branch` is replayed, in order, on top of the
+    /// latest version, rebasing it over the transactions committed here since the
+    /// branch was created. If any of them conflict nothing is committed and the
+    /// conflicts are returned in the [`MergeReport`]. If committing one of them
+    /// fails, the transactions that were already replayed are undone by restoring
+    /// the version checked out before the merge.
+    ///
+    /// All versions of both branches since the branch was created must still
+    /// exist, so the merge must happen before they are cleaned up.
+    pub async fn merge_branch(&mut self, branch: &str) -> Result<MergeReport> {
+        branch_merge::merge_branch(self, branch).await
+    }
+
+    /// Stream the rows that changed between versions `from` and `to`.
+    ///
+    /// Every changed row is returned with its `_rowid`, a [`CHANGE_TYPE_COLUMN`]
+    /// column holding its [`ChangeType`] and a [`COMMIT_VERSION_COLUMN`] column
+    /// holding the version that changed it. Updated rows are returned twice, as
+    /// an `update_preimage` and an `update_postimage`. Inserts and deletes made by
+    /// an update (e.g. a merge insert) can only be told apart from updates when
+    /// the dataset uses stable row ids.
+    ///
+    /// The changes are read from the versions in between, so this fails if any of
+    /// them have been removed by [`Self::cleanup_old_versions`].
+    pub async fn diff(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> Result<datafusion::physical_plan::SendableRecordBatchStream> {
+        diff::diff(self, from, to).await
+    }
+
     /// Removes old versions of the dataset from disk
     ///
     /// This function will remove all versions of the dataset that are older than the provided
@@ -855,6 +976,12 @@ impl Dataset {
         self.manifest.config.iter()
     }
 
+    /// How the dataset is partitioned into fragments, if it was written with a
+    /// partition spec (see [`WriteParams::partition_spec`])
+    pub fn partition_spec(&self) -> Result<Option<PartitionSpec>> {
+        PartitionSpec::from_manifest(&self.manifest)
+    }
+
     /// Create a Scanner to scan the dataset.
     pub fn scan(&self) -> Scanner {
         Scanner::new(Arc::new(self.clone()))
@@ -1081,7 +1208,7 @@ impl Dataset {
     pub async fn versions(&self) -> Result<Vec<Version>> {
         let mut versions: Vec<Version> = self
             .commit_handler
-            .list_manifest_locations(&self.base, &self.object_store, false)
+            .list_manifest_locations(&self.manifest_base(), &self.object_store, false)
             .try_filter_map(|location| async move {
                 match read_manifest(&self.object_store, &location.path, location.size).await {
                     Ok(manifest) => Ok(Some(Version::from(&manifest))),
@@ -1103,7 +1230,7 @@ impl Dataset {
     pub async fn latest_version_id(&self) -> Result<u64> {
         Ok(self
             .commit_handler
-            .resolve_latest_location(&self.base, &self.object_store)
+            .resolve_latest_location(&self.manifest_base(), &self.object_store)
             .await?
             .version)
     }
@@ -1457,7 +1584,7 @@ impl Dataset {
     /// # tokio::runtime::Runtime::new().unwrap().block_on(fut);
     /// ```
     pub async fn migrate_manifest_paths_v2(&mut self) -> Result<()> {
-        migrate_scheme_to_v2(self.object_store(), &self.base).await?;
+        migrate_scheme_to_v2(self.object_store(), &self.manifest_base()).await?;
         // We need to re-open.
         let latest_version = self.latest_version_id().await?;
         *self = self.checkout_version(latest_version).await?;
@@ -1477,7 +1604,7 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
     let latest_version = dataset.manifest.version;
     let locations = dataset
         .commit_handler
-        .list_manifest_locations(&dataset.base, dataset.object_store(), true)
+        .list_manifest_locations(&dataset.manifest_base(), dataset.object_store(), true)
         .try_take_while(move |location| {
             futures::future::ready(Ok(location.version > latest_version))
         });
@@ -1498,7 +1625,7 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
                         Dataset::load_manifest(
                             dataset.object_store(),
                             &location,
-                            &dataset.base,
+                            &dataset.manifest_base(),
                             dataset.session.as_ref(),
                         )
                     })
@@ -1515,7 +1642,8 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
         .try_buffer_unordered(io_parallelism / 2);
     let transactions = manifests
         .map_ok(move |(manifest, location)| async move {
-            let cache_path = transaction_file_cache_path(&dataset.base, manifest.version);
+            let cache_path =
+                transaction_file_cache_path(&dataset.manifest_base(), manifest.version);
             let manifest_copy = manifest.clone();
             let transaction = dataset
                 .session
@@ -1529,6 +1657,7 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
                         location,
                         dataset.session(),
                         dataset.commit_handler.clone(),
+                        dataset.branch.clone(),
                     )?;
                     let object_store = dataset_version.object_store();
                     let path = dataset_version
@@ -1563,6 +1692,7 @@ pub(crate) fn load_new_transactions(dataset: &Dataset) -> NewTransactionResult<'
                 location,
                 dataset.session(),
                 dataset.commit_handler.clone(),
+                dataset.branch.clone(),
             )
         } else {
             // If we didn't get the latest manifest, we can still return the dataset
@@ -4143,6 +4273,484 @@ mod tests {
         assert_eq!(dataset.manifest.version, 1);
     }
 
+    #[tokio::test]
+    async fn test_branch() {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::UInt32,
+            false,
+        )]));
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+
+        let batch = |range: std::ops::Range<u32>| {
+            let data = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(UInt32Array::from_iter_values(range))],
+            );
+            RecordBatchIterator::new(vec![data.unwrap()].into_iter().map(Ok), schema.clone())
+        };
+
+        let mut dataset = Dataset::write(batch(0..100), test_uri, None).await.unwrap();
+        dataset.delete("i >= 50").await.unwrap();
+        assert_eq!(dataset.manifest.version, 2);
+        dataset.tags.create("tag1", 1).await.unwrap();
+
+        assert_eq!(dataset.branches.list().await.unwrap().len(), 0);
+
+        let bad_branch_creation = dataset.branches.create("main", 1).await;
+        assert_eq!(
+            bad_branch_creation.err().unwrap().to_string(),
+            "Ref is invalid: Branch name main is reserved"
+        );
+
+        let bad_branch_creation = dataset.branches.create("dev", 3).await;
+        assert_eq!(
+            bad_branch_creation.err().unwrap().to_string(),
+            "Version not found error: version 3 does not exist"
+        );
+
+        dataset.branches.create("dev", "tag1").await.unwrap();
+        dataset.branches.create("fix", 2).await.unwrap();
+
+        let another_bad_branch_creation = dataset.branches.create("dev", 1).await;
+        assert_eq!(
+            another_bad_branch_creation.err().unwrap().to_string(),
+            "Ref conflict error: branch dev already exists"
+        );
+
+        let branches = dataset.branches.list().await.unwrap();
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches["dev"].parent_version, 1);
+        assert_eq!(branches["dev"].parent_branch, None);
+
+        // Commits to a branch do not touch main
+        let mut dev = dataset
+            .checkout_version(refs::Ref::Branch("dev".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(dev.branch(), Some("dev"));
+        assert_eq!(dev.manifest.version, 1);
+        assert_eq!(dev.count_rows(None).await.unwrap(), 100);
+
+        dev = Dataset::write(
+            batch(100..110),
+            WriteDestination::Dataset(Arc::new(dev)),
+            Some(WriteParams {
+                mode: WriteMode::Append,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dev.branch(), Some("dev"));
+        assert_eq!(dev.manifest.version, 2);
+        dev.delete("i < 10").await.unwrap();
+        assert_eq!(dev.manifest.version, 3);
+        assert_eq!(dev.count_rows(None).await.unwrap(), 100);
+        assert_eq!(dev.latest_version_id().await.unwrap(), 3);
+        assert_eq!(dev.versions().await.unwrap().len(), 3);
+
+        dataset.checkout_latest().await.unwrap();
+        assert_eq!(dataset.manifest.version, 2);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 50);
+
+        // Versions are resolved relative to the checked out branch
+        let dev_v2 = dev.checkout_version(2).await.unwrap();
+        assert_eq!(dev_v2.branch(), Some("dev"));
+        assert_eq!(dev_v2.count_rows(None).await.unwrap(), 110);
+
+        // Tags are resolved against the main branch
+        dataset.tags.create("tag2", 2).await.unwrap();
+        let main_v2 = dev.checkout_version("tag2").await.unwrap();
+        assert_eq!(main_v2.branch(), None);
+        assert_eq!(main_v2.manifest.version, 2);
+        assert_eq!(main_v2.count_rows(None).await.unwrap(), 50);
+
+        let reopened = DatasetBuilder::from_uri(test_uri)
+            .with_branch("dev")
+            .load()
+            .await
+            .unwrap();
+        assert_eq!(reopened.branch(), Some("dev"));
+        assert_eq!(reopened.manifest.version, 3);
+
+        let main = dev.checkout_branch(refs::MAIN_BRANCH).await.unwrap();
+        assert_eq!(main.branch(), None);
+        assert_eq!(main.manifest.version, 2);
+
+        // Branch from a branch
+        dataset
+            .branches
+            .create("dev2", refs::Ref::Branch("dev".to_string()))
+            .await
+            .unwrap();
+        let dev2 = dataset.checkout_branch("dev2").await.unwrap();
+        assert_eq!(dev2.manifest.version, 3);
+        assert_eq!(dev2.count_rows(None).await.unwrap(), 100);
+
+        let bad_branch_deletion = dataset.branches.delete("dev").await;
+        assert!(bad_branch_deletion.is_err());
+
+        dataset.branches.delete("dev2").await.unwrap();
+        dataset.branches.delete("dev").await.unwrap();
+        assert_eq!(dataset.branches.list().await.unwrap().len(), 1);
+
+        let bad_checkout = dataset.checkout_branch("dev").await;
+        assert_eq!(
+            bad_checkout.err().unwrap().to_string(),
+            "Ref not found error: branch dev does not exist"
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_map_column(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+    ) {
+        use arrow_array::builder::{MapBuilder, StringBuilder};
+
+        let mut builder = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        for i in 0..10 {
+            builder.keys().append_value("k");
+            builder
+                .values()
+                .append_value(if i % 2 == 0 { "v" } else { "w" });
+            if i % 3 == 0 {
+                builder.keys().append_value("j");
+                builder.values().append_null();
+            }
+            builder.append(i != 9).unwrap();
+        }
+        let attrs = builder.finish();
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, false),
+            ArrowField::new("attrs", attrs.data_type().clone(), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..10)),
+                Arc::new(attrs.clone()),
+            ],
+        )
+        .unwrap();
+
+        let dataset = Dataset::write(
+            RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone()),
+            "memory://test",
+            Some(WriteParams {
+                data_storage_version: Some(version),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dataset.scan().try_into_batch().await.unwrap(), batch);
+
+        let mut scanner = dataset.scan();
+        scanner.filter("attrs['k'] = 'v'").unwrap();
+        let filtered = scanner.try_into_batch().await.unwrap();
+        assert_eq!(
+            filtered["id"].as_primitive::<Int32Type>().values().to_vec(),
+            vec![0, 2, 4, 6, 8]
+        );
+
+        // Reading only the keys gives a list of the entries with just the keys
+        let projection = dataset.schema().project(&["attrs.entries.keys"]).unwrap();
+        let keys = dataset.take(&[0, 1], projection).await.unwrap();
+        let keys = keys["attrs"].as_list::<i32>();
+        assert_eq!(keys.value_length(0), 2);
+        assert_eq!(keys.value_length(1), 1);
+        assert_eq!(
+            keys.value(0)
+                .as_struct()
+                .column_by_name("keys")
+                .unwrap()
+                .as_string::<i32>(),
+            &StringArray::from(vec!["k", "j"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_branch() {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::UInt32,
+            false,
+        )]));
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+
+        let batch = |range: std::ops::Range<u32>| {
+            let data = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(UInt32Array::from_iter_values(range))],
+            );
+            RecordBatchIterator::new(vec![data.unwrap()].into_iter().map(Ok), schema.clone())
+        };
+        let append_params = Some(WriteParams {
+            mode: WriteMode::Append,
+            ..Default::default()
+        });
+
+        let mut dataset = Dataset::write(batch(0..100), test_uri, None).await.unwrap();
+        dataset.branches.create("dev", 1).await.unwrap();
+
+        // Diverge both histories with non-overlapping changes
+        let mut dev = dataset.checkout_branch("dev").await.unwrap();
+        dev = Dataset::write(
+            batch(100..110),
+            WriteDestination::Dataset(Arc::new(dev)),
+            append_params.clone(),
+        )
+        .await
+        .unwrap();
+        dev.delete("i < 10 or i = 105").await.unwrap();
+
+        dataset = Dataset::write(
+            batch(200..205),
+            WriteDestination::Dataset(Arc::new(dataset)),
+            append_params.clone(),
+        )
+        .await
+        .unwrap();
+        dataset.delete("i >= 50 and i < 60").await.unwrap();
+        assert_eq!(dataset.manifest.version, 3);
+
+        let report = dataset.merge_branch("dev").await.unwrap();
+        assert!(!report.has_conflicts());
+        assert_eq!(report.merged_versions, vec![2, 3]);
+        assert_eq!(dataset.manifest.version, 5);
+        assert_eq!(dataset.branch(), None);
+        assert_eq!(
+            dataset.count_rows(None).await.unwrap(),
+            100 - 10 - 10 + 9 + 5
+        );
+        assert_eq!(
+            dataset
+                .count_rows(Some("i >= 100 and i < 110".to_string()))
+                .await
+                .unwrap(),
+            9
+        );
+        assert_eq!(
+            dataset
+                .count_rows(Some("i >= 200".to_string()))
+                .await
+                .unwrap(),
+            5
+        );
+
+        let already_merged = dataset.merge_branch("dev").await;
+        assert!(already_merged.is_err());
+
+        // Overlapping deletes are reported and nothing is committed
+        dataset.branches.create("dev2", 5).await.unwrap();
+        let mut dev2 = dataset.checkout_branch("dev2").await.unwrap();
+        dev2.delete("i = 70").await.unwrap();
+        dataset.delete("i >= 65 and i < 75").await.unwrap();
+        assert_eq!(dataset.manifest.version, 6);
+
+        let report = dataset.merge_branch("dev2").await.unwrap();
+        assert_eq!(report.merged_versions.len(), 0);
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.source_version, 6);
+        assert_eq!(conflict.source_operation, "Delete");
+        assert_eq!(conflict.target_version, Some(6));
+        assert!(matches!(conflict.kind, MergeConflictKind::Rows { .. }));
+        assert_eq!(dataset.manifest.version, 6);
+    }
+
+    #[tokio::test]
+    async fn test_merge_branch_restores_target_on_failure() {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::UInt32,
+            false,
+        )]));
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+
+        let batch = |range: std::ops::Range<u32>| {
+            let data = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(UInt32Array::from_iter_values(range))],
+            );
+            RecordBatchIterator::new(vec![data.unwrap()].into_iter().map(Ok), schema.clone())
+        };
+        let append_params = Some(WriteParams {
+            mode: WriteMode::Append,
+            ..Default::default()
+        });
+
+        let mut dataset = Dataset::write(batch(0..100), test_uri, None).await.unwrap();
+        dataset.branches.create("dev", 1).await.unwrap();
+
+        let mut dev = dataset.checkout_branch("dev").await.unwrap();
+        dev = Dataset::write(
+            batch(100..110),
+            WriteDestination::Dataset(Arc::new(dev)),
+            append_params.clone(),
+        )
+        .await
+        .unwrap();
+        dev.delete("i = 105").await.unwrap();
+
+        dataset = Dataset::write(
+            batch(200..205),
+            WriteDestination::Dataset(Arc::new(dataset)),
+            append_params.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dataset.manifest.version, 2);
+
+        // Hide the deletion file of the branch so the delete fails to replay
+        // after the append was already committed
+        let deletions_dir = test_dir.path().join("_deletions");
+        let hidden_dir = test_dir.path().join("_hidden_deletions");
+        std::fs::rename(&deletions_dir, &hidden_dir).unwrap();
+
+        let failed = dataset.merge_branch("dev").await;
+        assert!(failed.is_err());
+        assert_eq!(dataset.manifest.version, 4);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 105);
+        assert!(dataset
+            .branches
+            .get("dev")
+            .await
+            .unwrap()
+            .merged_version
+            .is_none());
+
+        std::fs::rename(&hidden_dir, &deletions_dir).unwrap();
+        let report = dataset.merge_branch("dev").await.unwrap();
+        assert!(!report.has_conflicts());
+        assert_eq!(report.merged_versions, vec![2, 3]);
+        assert_eq!(dataset.manifest.version, 6);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 114);
+    }
+
+    #[tokio::test]
+    async fn test_diff() {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            DataType::UInt32,
+            false,
+        )]));
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+
+        let batch = |range: std::ops::Range<u32>| {
+            let data = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(UInt32Array::from_iter_values(range))],
+            );
+            RecordBatchIterator::new(vec![data.unwrap()].into_iter().map(Ok), schema.clone())
+        };
+
+        let mut dataset = Dataset::write(
+            batch(0..10),
+            test_uri,
+            Some(WriteParams {
+                enable_move_stable_row_ids: true,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        dataset = Dataset::write(
+            batch(10..15),
+            WriteDestination::Dataset(Arc::new(dataset)),
+            Some(WriteParams {
+                mode: WriteMode::Append,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        dataset.delete("i < 2").await.unwrap();
+        let dataset = UpdateBuilder::new(Arc::new(dataset))
+            .update_where("i = 5")
+            .unwrap()
+            .set("i", "105")
+            .unwrap()
+            .build()
+            .unwrap()
+            .execute()
+            .await
+            .unwrap()
+            .new_dataset;
+        assert_eq!(dataset.manifest.version, 4);
+
+        let batches = dataset
+            .diff(1, 4)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let changes = concat_batches(&batches[0].schema(), &batches).unwrap();
+        let values = changes["i"].as_primitive::<arrow::datatypes::UInt32Type>();
+        let change_types = changes[CHANGE_TYPE_COLUMN].as_string::<i32>();
+        let commit_versions = changes[COMMIT_VERSION_COLUMN].as_primitive::<UInt64Type>();
+        let row_ids = changes[ROW_ID].as_primitive::<UInt64Type>();
+        let mut rows = (0..changes.num_rows())
+            .map(|i| {
+                (
+                    commit_versions.value(i),
+                    change_types.value(i),
+                    values.value(i),
+                    row_ids.value(i),
+                )
+            })
+            .collect::<Vec<_>>();
+        rows.sort();
+
+        let mut expected = (10..15)
+            .map(|i| (2, "insert", i, i as u64))
+            .chain([(3, "delete", 0, 0), (3, "delete", 1, 1)])
+            .chain([
+                (4, "update_postimage", 105, 5),
+                (4, "update_preimage", 5, 5),
+            ])
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(rows, expected);
+
+        // Nothing changed in an empty range
+        let batches = dataset
+            .diff(4, 4)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert!(batches.is_empty());
+
+        assert!(dataset.diff(3, 2).await.is_err());
+
+        // The history of a removed version cannot be read
+        let manifest_path = dataset
+            .manifest_location
+            .naming_scheme
+            .manifest_path(&dataset.base, 2);
+        dataset.object_store.delete(&manifest_path).await.unwrap();
+        let dataset = Dataset::open(test_uri).await.unwrap();
+        let err = dataset.diff(1, 4).await.err().unwrap();
+        assert!(
+            matches!(err, Error::VersionNotFound { .. }),
+            "unexpected error: {}",
+            err
+        );
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_search_empty(
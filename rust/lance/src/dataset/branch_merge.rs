@@ -0,0 +1,585 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Merging a branch back into the branch it was created from.
+//!
+//! The transactions committed on the source branch since it was created are
+//! replayed, in order, on top of the target. Before anything is committed each
+//! of them is checked against every transaction the target committed over the
+//! same period, using the same [`TransactionRebase`] logic that resolves
+//! concurrent commits. Compatible appends, deletes, updates and index builds
+//! are rebased; true conflicts are collected into a [`MergeReport`].
+//!
+//! The replayed transactions are committed one at a time. If one of them fails
+//! after others were already committed, the target is restored to the version
+//! it had before the merge, so the merge can be retried once the cause is fixed.
+//! Transactions of the target that were undone by a restore are ignored when
+//! looking for conflicts.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use lance_core::utils::mask::RowIdTreeMap;
+use lance_table::format::Fragment;
+use lance_table::io::deletion::deletion_file_path;
+use roaring::RoaringBitmap;
+use snafu::location;
+
+use super::fragment::FileFragment;
+use super::transaction::{Operation, Transaction};
+use crate::io::commit::conflict_resolver::TransactionRebase;
+use crate::io::deletion::read_dataset_deletion_file;
+use crate::{Dataset, Error, Result};
+
+/// The number of conflicting row addresses kept in a [`MergeConflictKind::Rows`].
+const MAX_SAMPLE_ROWS: usize = 5;
+
+/// The outcome of [`Dataset::merge_branch`].
+#[derive(Debug, Clone, Default)]
+pub struct MergeReport {
+    /// Versions of the source branch whose transactions were replayed, in order.
+    pub merged_versions: Vec<u64>,
+    /// Conflicts that prevented the merge. If this is non-empty then nothing
+    /// was committed to the target.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeReport {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// A transaction of the source branch that cannot be replayed on the target.
+#[derive(Debug, Clone)]
+pub struct MergeConflict {
+    /// The version of the source branch that committed the transaction.
+    pub source_version: u64,
+    /// The name of the source operation, e.g. `Delete`.
+    pub source_operation: String,
+    /// The version of the target that committed the conflicting transaction,
+    /// if the conflict is with a single transaction of the target.
+    pub target_version: Option<u64>,
+    /// The name of the conflicting target operation.
+    pub target_operation: Option<String>,
+    pub kind: MergeConflictKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeConflictKind {
+    /// Both branches deleted or updated the same rows.
+    Rows {
+        /// Up to a few of the conflicting row addresses.
+        sample_row_addresses: Vec<u64>,
+    },
+    /// Both branches modified the same fragments in a way that cannot be
+    /// rebased, for example by rewriting their data files.
+    Fragments,
+    /// One of the branches changed the schema or replaced the data in a way that
+    /// is incompatible with the other branch.
+    Schema,
+    /// The source branch built an index over fragments whose ids change when
+    /// they are replayed on the target.
+    Index { name: String },
+}
+
+/// A transaction of the source branch along with the state it was applied to.
+struct SourceCommit {
+    version: u64,
+    transaction: Transaction,
+    /// The source branch before the transaction
+    before: Dataset,
+    /// The source branch after the transaction
+    after: Dataset,
+    /// Rows of pre-existing fragments that the transaction deleted or updated
+    affected_rows: Option<RowIdTreeMap>,
+}
+
+/// A transaction committed to the target since the branch was created.
+struct TargetCommit {
+    version: u64,
+    transaction: Transaction,
+    /// Rows of pre-existing fragments that the transaction deleted or updated
+    deleted_rows: RowIdTreeMap,
+}
+
+pub(super) async fn merge_branch(dataset: &mut Dataset, branch: &str) -> Result<MergeReport> {
+    let branch_contents = dataset.branches.get(branch).await?;
+    if branch_contents.parent_branch.as_deref() != dataset.branch() {
+        return Err(Error::invalid_input(
+            format!(
+                "branch {} was not created from the checked out branch {}",
+                branch,
+                dataset.branch().unwrap_or(super::refs::MAIN_BRANCH)
+            ),
+            location!(),
+        ));
+    }
+    if let Some(merged_version) = branch_contents.merged_version {
+        return Err(Error::invalid_input(
+            format!(
+                "branch {} was already merged at version {}",
+                branch, merged_version
+            ),
+            location!(),
+        ));
+    }
+
+    dataset.checkout_latest().await?;
+    let source = dataset.checkout_branch(branch).await?;
+
+    // The first version of the branch is a copy of the version it was created from,
+    // so it is the common ancestor of both histories.
+    let base = source
+        .checkout_version(branch_contents.parent_version)
+        .await?;
+    let base_fragment_ids = base
+        .fragments()
+        .iter()
+        .map(|fragment| fragment.id)
+        .collect::<HashSet<_>>();
+
+    let source_commits = load_source_commits(&base, &source, &base_fragment_ids).await?;
+    let target_commits = load_target_commits(&base, dataset, &base_fragment_ids).await?;
+
+    // Fragments created on the source branch are given new ids when they are
+    // replayed. They are assigned in the same order as on the branch, starting
+    // after the last fragment id of the target.
+    let source_next_id = next_fragment_id(&base);
+    let target_next_id = next_fragment_id(dataset);
+    let predicted_fragment_map = source_commits
+        .iter()
+        .flat_map(|commit| commit.after.fragments().iter().map(|f| f.id))
+        .filter(|id| *id >= source_next_id)
+        .map(|id| (id, target_next_id + (id - source_next_id)))
+        .collect::<HashMap<_, _>>();
+
+    let mut report = MergeReport::default();
+    for commit in &source_commits {
+        if let Some(conflict) =
+            check_source_commit(commit, &target_commits, &predicted_fragment_map).await?
+        {
+            report.conflicts.push(conflict);
+        }
+    }
+    if report.has_conflicts() {
+        return Ok(report);
+    }
+
+    let start_version = dataset.manifest.version;
+    match replay_source_commits(dataset, source_commits, &target_commits).await {
+        Ok(merged_versions) => report.merged_versions = merged_versions,
+        Err(err) => {
+            if dataset.manifest.version != start_version {
+                *dataset = dataset.checkout_version(start_version).await?;
+                dataset.restore().await?;
+            }
+            return Err(err);
+        }
+    }
+
+    dataset
+        .branches
+        .record_merge(branch, dataset.manifest.version)
+        .await?;
+
+    Ok(report)
+}
+
+/// Commit the transactions of the source branch on top of the target, returning
+/// the source versions that were replayed.
+async fn replay_source_commits(
+    dataset: &mut Dataset,
+    source_commits: Vec<SourceCommit>,
+    target_commits: &[TargetCommit],
+) -> Result<Vec<u64>> {
+    let mut merged_versions = Vec::with_capacity(source_commits.len());
+    let mut fragment_map = HashMap::new();
+    for commit in source_commits {
+        let mut transaction = Transaction {
+            read_version: commit.before.manifest.version,
+            ..commit.transaction
+        };
+        relocate_deletion_files(dataset, &transaction.operation, &fragment_map).await?;
+        remap_fragment_ids(&mut transaction.operation, &fragment_map);
+
+        let mut rebase =
+            TransactionRebase::try_new(&commit.before, transaction, commit.affected_rows.as_ref())
+                .await?;
+        for target_commit in target_commits {
+            rebase.check_txn(&target_commit.transaction, target_commit.version)?;
+        }
+        let transaction = rebase.finish(dataset).await?;
+        let transaction = Transaction::new(
+            dataset.manifest.version,
+            transaction.operation,
+            transaction.blobs_op,
+            transaction.tag,
+        );
+
+        let target_before = dataset.manifest.clone();
+        dataset
+            .apply_commit(transaction, &Default::default(), &Default::default())
+            .await?;
+
+        let source_new = new_fragment_ids(commit.before.fragments(), commit.after.fragments());
+        let target_new = new_fragment_ids(&target_before.fragments, dataset.fragments());
+        if source_new.len() != target_new.len() {
+            return Err(Error::Internal {
+                message: format!(
+                    "replaying version {} created {} fragments on the target but {} on the source branch",
+                    commit.version,
+                    target_new.len(),
+                    source_new.len()
+                ),
+                location: location!(),
+            });
+        }
+        fragment_map.extend(source_new.into_iter().zip(target_new));
+        merged_versions.push(commit.version);
+    }
+    Ok(merged_versions)
+}
+
+async fn load_source_commits(
+    base: &Dataset,
+    source: &Dataset,
+    base_fragment_ids: &HashSet<u64>,
+) -> Result<Vec<SourceCommit>> {
+    let mut commits = Vec::new();
+    let mut before = base.clone();
+    for version in base.manifest.version + 1..=source.manifest.version {
+        let after = source.checkout_version(version).await?;
+        let transaction = read_transaction(&after).await?;
+        let affected_rows = match &transaction.operation {
+            Operation::Delete { .. } | Operation::Update { .. } => {
+                Some(deleted_rows(&before, &after, base_fragment_ids).await?)
+            }
+            _ => None,
+        };
+        commits.push(SourceCommit {
+            version,
+            transaction,
+            before,
+            after: after.clone(),
+            affected_rows,
+        });
+        before = after;
+    }
+    Ok(commits)
+}
+
+async fn load_target_commits(
+    base: &Dataset,
+    target: &Dataset,
+    base_fragment_ids: &HashSet<u64>,
+) -> Result<Vec<TargetCommit>> {
+    let mut commits: Vec<TargetCommit> = Vec::new();
+    let mut before = base.clone();
+    for version in base.manifest.version + 1..=target.manifest.version {
+        let after = target.checkout_version(version).await?;
+        let transaction = read_transaction(&after).await?;
+        if let Operation::Restore {
+            version: restored_version,
+        } = transaction.operation
+        {
+            if restored_version >= base.manifest.version {
+                // The transactions committed after the restored version were
+                // undone, only the ones still in effect can conflict
+                commits.retain(|commit| commit.version <= restored_version);
+                before = after;
+                continue;
+            }
+        }
+        let deleted_rows = deleted_rows(&before, &after, base_fragment_ids).await?;
+        commits.push(TargetCommit {
+            version,
+            transaction,
+            deleted_rows,
+        });
+        before = after;
+    }
+    Ok(commits)
+}
+
+async fn read_transaction(dataset: &Dataset) -> Result<Transaction> {
+    dataset
+        .read_transaction()
+        .await?
+        .ok_or_else(|| Error::NotSupported {
+            source: format!(
+                "version {} does not have a transaction file and cannot be merged",
+                dataset.manifest.version
+            )
+            .into(),
+            location: location!(),
+        })
+}
+
+/// Check a transaction of the source branch against every transaction of the target.
+async fn check_source_commit(
+    commit: &SourceCommit,
+    target_commits: &[TargetCommit],
+    fragment_map: &HashMap<u64, u64>,
+) -> Result<Option<MergeConflict>> {
+    let source_operation = &commit.transaction.operation;
+    let conflict = |target: Option<&TargetCommit>, kind, message| MergeConflict {
+        source_version: commit.version,
+        source_operation: source_operation.name().to_string(),
+        target_version: target.map(|target| target.version),
+        target_operation: target.map(|target| target.transaction.operation.name().to_string()),
+        kind,
+        message,
+    };
+
+    if let Operation::CreateIndex { new_indices, .. } = source_operation {
+        for index in new_indices {
+            let moved = index.fragment_bitmap.as_ref().is_some_and(|bitmap| {
+                bitmap.iter().any(|id| {
+                    fragment_map
+                        .get(&(id as u64))
+                        .is_some_and(|new_id| *new_id != id as u64)
+                })
+            });
+            if moved {
+                return Ok(Some(conflict(
+                    None,
+                    MergeConflictKind::Index {
+                        name: index.name.clone(),
+                    },
+                    format!(
+                        "index {} covers fragments that are renumbered by the merge, rebuild it after merging",
+                        index.name
+                    ),
+                )));
+            }
+        }
+    }
+
+    let mut transaction = Transaction {
+        read_version: commit.before.manifest.version,
+        ..commit.transaction.clone()
+    };
+    remap_fragment_ids(&mut transaction.operation, fragment_map);
+    let mut rebase =
+        TransactionRebase::try_new(&commit.before, transaction, commit.affected_rows.as_ref())
+            .await?;
+
+    for target in target_commits {
+        match rebase.check_txn(&target.transaction, target.version) {
+            Ok(()) => {}
+            Err(err @ Error::CommitConflict { .. })
+            | Err(err @ Error::RetryableCommitConflict { .. }) => {
+                let kind = if changes_schema(source_operation)
+                    || changes_schema(&target.transaction.operation)
+                {
+                    MergeConflictKind::Schema
+                } else {
+                    MergeConflictKind::Fragments
+                };
+                return Ok(Some(conflict(Some(target), kind, err.to_string())));
+            }
+            Err(err) => return Err(err),
+        }
+
+        if let Some(affected_rows) = &commit.affected_rows {
+            let overlap = affected_rows.clone() & target.deleted_rows.clone();
+            if !overlap.is_empty() {
+                let sample_row_addresses: Vec<u64> = overlap
+                    .row_ids()
+                    .map(|rows| rows.take(MAX_SAMPLE_ROWS).map(u64::from).collect())
+                    .unwrap_or_default();
+                let message = format!(
+                    "both branches modified rows at addresses {:?}",
+                    sample_row_addresses
+                );
+                return Ok(Some(conflict(
+                    Some(target),
+                    MergeConflictKind::Rows {
+                        sample_row_addresses,
+                    },
+                    message,
+                )));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn changes_schema(operation: &Operation) -> bool {
+    matches!(
+        operation,
+        Operation::Overwrite { .. }
+            | Operation::Restore { .. }
+            | Operation::Merge { .. }
+            | Operation::Project { .. }
+    )
+}
+
+fn next_fragment_id(dataset: &Dataset) -> u64 {
+    dataset
+        .manifest
+        .max_fragment_id()
+        .map(|id| id + 1)
+        .unwrap_or(0)
+}
+
+/// Ids of the fragments in `after` that are not in `before`, in ascending order.
+fn new_fragment_ids(before: &[Fragment], after: &[Fragment]) -> Vec<u64> {
+    let before = before.iter().map(|f| f.id).collect::<HashSet<_>>();
+    let mut new_ids = after
+        .iter()
+        .map(|f| f.id)
+        .filter(|id| !before.contains(id))
+        .collect::<Vec<_>>();
+    new_ids.sort_unstable();
+    new_ids
+}
+
+/// Rewrite the ids of fragments that were created on the source branch to the
+/// ids they were given on the target.
+fn remap_fragment_ids(operation: &mut Operation, fragment_map: &HashMap<u64, u64>) {
+    let remap = |id: &mut u64| {
+        if let Some(new_id) = fragment_map.get(id) {
+            *id = *new_id;
+        }
+    };
+    match operation {
+        Operation::Delete {
+            updated_fragments,
+            deleted_fragment_ids,
+            ..
+        }
+        | Operation::Update {
+            updated_fragments,
+            removed_fragment_ids: deleted_fragment_ids,
+            ..
+        } => {
+            updated_fragments.iter_mut().for_each(|f| remap(&mut f.id));
+            deleted_fragment_ids.iter_mut().for_each(remap);
+        }
+        Operation::Rewrite { groups, .. } => groups
+            .iter_mut()
+            .flat_map(|group| group.old_fragments.iter_mut())
+            .for_each(|f| remap(&mut f.id)),
+        Operation::DataReplacement { replacements } => replacements
+            .iter_mut()
+            .for_each(|replacement| remap(&mut replacement.0)),
+        Operation::Merge { fragments, .. } => fragments.iter_mut().for_each(|f| remap(&mut f.id)),
+        Operation::CreateIndex { new_indices, .. } => {
+            for index in new_indices {
+                if let Some(bitmap) = &mut index.fragment_bitmap {
+                    *bitmap = bitmap
+                        .iter()
+                        .map(|id| {
+                            fragment_map
+                                .get(&(id as u64))
+                                .map_or(id, |new_id| *new_id as u32)
+                        })
+                        .collect();
+                }
+            }
+        }
+        Operation::Append { .. }
+        | Operation::Overwrite { .. }
+        | Operation::Restore { .. }
+        | Operation::ReserveFragments { .. }
+        | Operation::Project { .. }
+        | Operation::UpdateConfig { .. } => {}
+    }
+}
+
+/// Deletion files are named after their fragment, so copy the deletion files of
+/// renumbered fragments to their new name.
+async fn relocate_deletion_files(
+    dataset: &Dataset,
+    operation: &Operation,
+    fragment_map: &HashMap<u64, u64>,
+) -> Result<()> {
+    let updated_fragments = match operation {
+        Operation::Delete {
+            updated_fragments, ..
+        }
+        | Operation::Update {
+            updated_fragments, ..
+        } => updated_fragments,
+        _ => return Ok(()),
+    };
+    for fragment in updated_fragments {
+        if let (Some(new_id), Some(deletion_file)) =
+            (fragment_map.get(&fragment.id), &fragment.deletion_file)
+        {
+            dataset
+                .object_store()
+                .copy(
+                    &deletion_file_path(&dataset.base, fragment.id, deletion_file),
+                    &deletion_file_path(&dataset.base, *new_id, deletion_file),
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Rows of `before` that are deleted in `after`, either through its deletion
+/// file or because the whole fragment was removed.
+///
+/// Only the fragments in `fragment_ids` are considered.
+async fn deleted_rows(
+    before: &Dataset,
+    after: &Dataset,
+    fragment_ids: &HashSet<u64>,
+) -> Result<RowIdTreeMap> {
+    let after_fragments = after
+        .fragments()
+        .iter()
+        .map(|fragment| (fragment.id, fragment))
+        .collect::<HashMap<_, _>>();
+
+    let mut deleted = RowIdTreeMap::new();
+    for fragment in before
+        .fragments()
+        .iter()
+        .filter(|fragment| fragment_ids.contains(&fragment.id))
+    {
+        let rows = match after_fragments.get(&fragment.id) {
+            Some(after_fragment) if after_fragment.deletion_file == fragment.deletion_file => {
+                continue;
+            }
+            Some(after_fragment) => {
+                deletion_bitmap(after, after_fragment).await?
+                    - deletion_bitmap(before, fragment).await?
+            }
+            None => {
+                let physical_rows = match fragment.physical_rows {
+                    Some(physical_rows) => physical_rows,
+                    None => {
+                        FileFragment::new(Arc::new(before.clone()), fragment.clone())
+                            .physical_rows()
+                            .await?
+                    }
+                };
+                let mut rows = RoaringBitmap::new();
+                rows.insert_range(0..physical_rows as u32);
+                rows - deletion_bitmap(before, fragment).await?
+            }
+        };
+        if !rows.is_empty() {
+            deleted.insert_bitmap(fragment.id as u32, rows);
+        }
+    }
+    Ok(deleted)
+}
+
+async fn deletion_bitmap(dataset: &Dataset, fragment: &Fragment) -> Result<RoaringBitmap> {
+    match &fragment.deletion_file {
+        Some(deletion_file) => {
+            let deletion_vector =
+                read_dataset_deletion_file(dataset, fragment.id, deletion_file).await?;
+            Ok(RoaringBitmap::from(deletion_vector.as_ref()))
+        }
+        None => Ok(RoaringBitmap::new()),
+    }
+}
@@ -16,7 +16,7 @@ use snafu::location;
 use tracing::instrument;
 use url::Url;
 
-use super::refs::{Ref, Tags};
+use super::refs::{branch_path, Branches, Ref, Tags, MAIN_BRANCH};
 use super::{ReadParams, WriteParams, DEFAULT_INDEX_CACHE_SIZE, DEFAULT_METADATA_CACHE_SIZE};
 use crate::{
     error::{Error, Result},
@@ -91,6 +91,12 @@ impl DatasetBuilder {
         self
     }
 
+    /// Sets `version` for the builder to the latest version of a branch
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.version = Some(Ref::Branch(branch.to_string()));
+        self
+    }
+
     pub fn with_commit_handler(mut self, commit_handler: Arc<dyn CommitHandler>) -> Self {
         self.commit_handler = Some(commit_handler);
         self
@@ -269,6 +275,7 @@ impl DatasetBuilder {
         };
 
         let mut version: Option<u64> = None;
+        let mut branch: Option<String> = None;
         let cloned_ref = self.version.clone();
         let table_uri = self.table_uri.clone();
 
@@ -289,12 +296,29 @@ impl DatasetBuilder {
                     );
                     Some(tags.get_version(t.as_str()).await?)
                 }
+                Ref::Branch(b) if b == MAIN_BRANCH => None,
+                Ref::Branch(b) => {
+                    let branches = Branches::new(
+                        object_store.clone(),
+                        commit_handler.clone(),
+                        base_path.clone(),
+                    );
+                    // Validates that the branch exists
+                    branches.get(b.as_str()).await?;
+                    branch = Some(b);
+                    None
+                }
             }
         }
 
+        let manifest_base = match &branch {
+            Some(branch) => branch_path(&base_path, branch),
+            None => base_path.clone(),
+        };
+
         let (manifest, location) = if let Some(mut manifest) = manifest {
             let location = commit_handler
-                .resolve_version_location(&base_path, manifest.version, &object_store.inner)
+                .resolve_version_location(&manifest_base, manifest.version, &object_store.inner)
                 .await?;
             if manifest.schema.has_dictionary_types() {
                 let reader = object_store.open(&location.path).await?;
@@ -305,11 +329,11 @@ impl DatasetBuilder {
             let manifest_location = match version {
                 Some(version) => {
                     commit_handler
-                        .resolve_version_location(&base_path, version, &object_store.inner)
+                        .resolve_version_location(&manifest_base, version, &object_store.inner)
                         .await?
                 }
                 None => commit_handler
-                    .resolve_latest_location(&base_path, &object_store)
+                    .resolve_latest_location(&manifest_base, &object_store)
                     .await
                     .map_err(|e| Error::DatasetNotFound {
                         source: Box::new(e),
@@ -321,7 +345,7 @@ impl DatasetBuilder {
             let manifest = Dataset::load_manifest(
                 &object_store,
                 &manifest_location,
-                &base_path,
+                &manifest_base,
                 session.as_ref(),
             )
             .await?;
@@ -336,6 +360,7 @@ impl DatasetBuilder {
             location,
             session,
             commit_handler,
+            branch,
         )
     }
 }
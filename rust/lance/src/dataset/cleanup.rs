@@ -9,7 +9,9 @@
 //! The following types of files may be deleted by the cleanup function:
 //!
 //! * Old manifest files - If a manifest file is older than the threshold
-//!   and is not the latest manifest then it will be deleted.
+//!   and is not the latest manifest then it will be deleted.  Each branch
+//!   is treated as its own line of history, so the latest manifest of every
+//!   branch is kept along with all of the files it references.
 //! * Unreferenced data files - If a data file is not referenced by any
 //!   fragment in a valid manifest file then it will be deleted.
 //! * Unreferenced delete files - If a delete file is not referenced by
@@ -61,7 +63,7 @@ use tracing::{info, instrument, Span};
 
 use crate::{utils::temporal::utc_now, Dataset};
 
-use super::refs::TagContents;
+use super::refs::{branch_path, TagContents};
 
 #[derive(Clone, Debug, Default)]
 struct ReferencedFiles {
@@ -110,6 +112,17 @@ struct CleanupInspection {
     tagged_old_versions: HashSet<u64>,
 }
 
+/// A line of history whose manifests are inspected by the cleanup task.
+#[derive(Clone, Debug)]
+struct Lineage {
+    /// The directory the manifests of this lineage are stored under
+    manifest_base: Path,
+    /// Manifests at or above this version are always kept
+    latest_version: u64,
+    /// Whether this is the main branch, the only lineage tags can refer to
+    is_main: bool,
+}
+
 /// If a file cannot be verified then it will only be deleted if it is at least
 /// this many days old.
 const UNVERIFIED_THRESHOLD_DAYS: i64 = 7;
@@ -143,7 +156,9 @@ impl<'a> CleanupTask<'a> {
             .map(|tag_content| tag_content.version)
             .collect();
 
-        let inspection = self.process_manifests(&tagged_versions).await?;
+        let lineages = self.lineages().await?;
+
+        let inspection = self.process_manifests(&lineages, &tagged_versions).await?;
 
         if self.error_if_old_versions_tagged && !inspection.tagged_old_versions.is_empty() {
             return Err(tagged_old_versions_cleanup_error(
@@ -155,25 +170,60 @@ impl<'a> CleanupTask<'a> {
         self.delete_unreferenced_files(inspection).await
     }
 
+    /// Every line of history in the dataset: the main branch followed by each
+    /// named branch.
+    async fn lineages(&self) -> Result<Vec<Lineage>> {
+        let mut lineages = Vec::new();
+        for branch in
+            std::iter::once(None).chain(self.dataset.branches.list().await?.into_keys().map(Some))
+        {
+            let manifest_base = match &branch {
+                Some(branch) => branch_path(&self.dataset.base, branch),
+                None => self.dataset.base.clone(),
+            };
+            // The checked out version is used for the checked out branch so that
+            // manifests committed since we started are never removed.
+            let latest_version = if branch.as_deref() == self.dataset.branch() {
+                self.dataset.version().version
+            } else {
+                self.dataset
+                    .commit_handler
+                    .resolve_latest_location(&manifest_base, &self.dataset.object_store)
+                    .await?
+                    .version
+            };
+            lineages.push(Lineage {
+                manifest_base,
+                latest_version,
+                is_main: branch.is_none(),
+            });
+        }
+        Ok(lineages)
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn process_manifests(
         &'a self,
+        lineages: &[Lineage],
         tagged_versions: &HashSet<u64>,
     ) -> Result<CleanupInspection> {
         let inspection = Mutex::new(CleanupInspection::default());
-        self.dataset
-            .commit_handler
-            .list_manifest_locations(&self.dataset.base, &self.dataset.object_store, false)
-            .try_for_each_concurrent(self.dataset.object_store.io_parallelism(), |location| {
-                self.process_manifest_file(location, &inspection, tagged_versions)
-            })
-            .await?;
+        for lineage in lineages {
+            self.dataset
+                .commit_handler
+                .list_manifest_locations(&lineage.manifest_base, &self.dataset.object_store, false)
+                .try_for_each_concurrent(self.dataset.object_store.io_parallelism(), |location| {
+                    self.process_manifest_file(location, lineage, &inspection, tagged_versions)
+                })
+                .await?;
+        }
         Ok(inspection.into_inner().unwrap())
     }
 
     async fn process_manifest_file(
         &self,
         location: ManifestLocation,
+        lineage: &Lineage,
         inspection: &Mutex<CleanupInspection>,
         tagged_versions: &HashSet<u64>,
     ) -> Result<()> {
@@ -185,13 +235,14 @@ impl<'a> CleanupTask<'a> {
 
         let manifest =
             read_manifest(&self.dataset.object_store, &location.path, location.size).await?;
-        let dataset_version = self.dataset.version().version;
 
-        // Don't delete the latest version, even if it is old. Don't delete tagged versions,
-        // regardless of age. Don't delete manifests if their version is newer than the dataset
-        // version.  These are either in-progress or newly added since we started.
-        let is_latest = dataset_version <= manifest.version;
-        let is_tagged = tagged_versions.contains(&manifest.version);
+        // Don't delete the latest version of any branch, even if it is old. Don't delete
+        // tagged versions, regardless of age. Don't delete manifests if their version is
+        // newer than the latest version.  These are either in-progress or newly added
+        // since we started.
+        let is_latest = lineage.latest_version <= manifest.version;
+        // Tags always refer to versions of the main branch
+        let is_tagged = lineage.is_main && tagged_versions.contains(&manifest.version);
         let in_working_set = is_latest || manifest.timestamp() >= self.before || is_tagged;
         let indexes =
             read_manifest_indexes(&self.dataset.object_store, &location, &manifest).await?;
@@ -1010,6 +1061,57 @@ mod tests {
         assert_eq!(removed.old_versions, 1);
     }
 
+    #[tokio::test]
+    async fn cleanup_around_branches() {
+        // The latest version of every branch is a retention root, even if it
+        // is older than the cleanup threshold.
+        let fixture = MockDatasetFixture::try_new().unwrap();
+        fixture.create_some_data().await.unwrap();
+
+        let mut dataset = *(fixture.open().await.unwrap());
+        dataset.branches.create("dev", 1).await.unwrap();
+        let num_rows = fixture.count_rows().await.unwrap();
+
+        fixture.overwrite_some_data().await.unwrap();
+        fixture.overwrite_some_data().await.unwrap();
+
+        fixture
+            .clock
+            .set_system_time(TimeDelta::try_days(10).unwrap());
+
+        let before_count = fixture.count_files().await.unwrap();
+
+        let removed = fixture
+            .run_cleanup(utc_now() - TimeDelta::try_days(8).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(removed.old_versions, 2);
+
+        // Only the data of version 2 is unreferenced, version 1 is the branch head
+        let after_count = fixture.count_files().await.unwrap();
+        assert_eq!(after_count.num_data_files, before_count.num_data_files - 1);
+        assert_eq!(
+            after_count.num_manifest_files,
+            before_count.num_manifest_files - 2
+        );
+
+        let dev = fixture
+            .open()
+            .await
+            .unwrap()
+            .checkout_branch("dev")
+            .await
+            .unwrap();
+        assert_eq!(dev.count_rows(None).await.unwrap(), num_rows);
+
+        dataset.branches.delete("dev").await.unwrap();
+        let removed = fixture
+            .run_cleanup(utc_now() - TimeDelta::try_days(8).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(removed.old_versions, 0);
+    }
+
     #[tokio::test]
     async fn auto_cleanup_old_versions() {
         // Every n commits, all versions older than T should be deleted.
@@ -0,0 +1,443 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Row-level changes between two versions of a dataset (a "change data feed").
+//!
+//! The changes are reconstructed from the transaction file of every version in
+//! the range along with the deletion files it wrote. An update is written as a
+//! deletion of the old rows and an insertion of new ones, so with stable row ids
+//! the two halves are paired up by row id into pre- and post-images.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::{stream, StreamExt, TryStreamExt};
+use lance_arrow::RecordBatchExt;
+use lance_core::{Error, Result, ROW_ID_FIELD};
+use lance_table::format::Fragment;
+use roaring::RoaringBitmap;
+use snafu::location;
+
+use super::fragment::FileFragment;
+use super::rowids::load_row_id_sequence;
+use super::transaction::Operation;
+use crate::Dataset;
+
+/// The column describing how a row changed.
+pub const CHANGE_TYPE_COLUMN: &str = "_change_type";
+/// The column holding the version that committed a change.
+pub const COMMIT_VERSION_COLUMN: &str = "_commit_version";
+
+/// How a row changed, the value of the [`CHANGE_TYPE_COLUMN`] column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeType {
+    Insert,
+    /// The value of an updated row before the update.
+    UpdatePreimage,
+    /// The value of an updated row after the update.
+    UpdatePostimage,
+    Delete,
+}
+
+impl ChangeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::UpdatePreimage => "update_preimage",
+            Self::UpdatePostimage => "update_postimage",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+impl std::fmt::Display for ChangeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Rows of a single fragment that changed in a single version.
+struct FragmentChange {
+    /// The version of the dataset the rows are read from
+    dataset: Arc<Dataset>,
+    fragment: Fragment,
+    /// Physical offsets of the rows within the fragment
+    offsets: RoaringBitmap,
+    change_type: ChangeType,
+    commit_version: u64,
+}
+
+pub(super) async fn diff(
+    dataset: &Dataset,
+    from: u64,
+    to: u64,
+) -> Result<SendableRecordBatchStream> {
+    if from > to {
+        return Err(Error::invalid_input(
+            format!(
+                "cannot diff from version {} to the earlier version {}",
+                from, to
+            ),
+            location!(),
+        ));
+    }
+
+    let mut previous = Arc::new(checkout_history(dataset, from).await?);
+    let schema = previous.schema().clone();
+    let output_schema = Arc::new(ArrowSchema::new(
+        ArrowSchema::from(&schema)
+            .fields()
+            .iter()
+            .cloned()
+            .chain([
+                Arc::new(ROW_ID_FIELD.clone()),
+                Arc::new(ArrowField::new(CHANGE_TYPE_COLUMN, DataType::Utf8, false)),
+                Arc::new(ArrowField::new(
+                    COMMIT_VERSION_COLUMN,
+                    DataType::UInt64,
+                    false,
+                )),
+            ])
+            .collect::<Vec<_>>(),
+    ));
+
+    let mut changes = Vec::new();
+    for version in from + 1..=to {
+        let current = Arc::new(checkout_history(dataset, version).await?);
+        if current.schema() != &schema {
+            return Err(Error::NotSupported {
+                source: format!(
+                    "the schema changed at version {}, row-level changes can only be computed between versions with the same schema",
+                    version
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        let transaction = current
+            .read_transaction()
+            .await?
+            .ok_or_else(|| history_unavailable(version, "it does not have a transaction file"))?;
+        changes.extend(version_changes(&previous, &current, &transaction.operation).await?);
+        previous = current;
+    }
+
+    let batches = stream::iter(changes)
+        .map(|change| async move { read_change(change).await })
+        .buffered(dataset.object_store().io_parallelism())
+        .try_filter(|batch| futures::future::ready(batch.num_rows() > 0))
+        .map_err(datafusion::error::DataFusionError::from);
+    Ok(Box::pin(RecordBatchStreamAdapter::new(
+        output_schema,
+        batches,
+    )))
+}
+
+fn history_unavailable(version: u64, reason: &str) -> Error {
+    Error::VersionNotFound {
+        message: format!(
+            "the changes of version {} cannot be computed because {}, it may have been removed by cleanup_old_versions",
+            version, reason
+        ),
+    }
+}
+
+async fn checkout_history(dataset: &Dataset, version: u64) -> Result<Dataset> {
+    dataset
+        .checkout_version(version)
+        .await
+        .map_err(|err| match err {
+            Error::NotFound { .. } | Error::DatasetNotFound { .. } => {
+                history_unavailable(version, "its manifest no longer exists")
+            }
+            err => err,
+        })
+}
+
+/// Work out which rows changed between two consecutive versions.
+async fn version_changes(
+    previous: &Arc<Dataset>,
+    current: &Arc<Dataset>,
+    operation: &Operation,
+) -> Result<Vec<FragmentChange>> {
+    let commit_version = current.manifest.version;
+    let change =
+        |dataset: &Arc<Dataset>, fragment: &Fragment, offsets, change_type| FragmentChange {
+            dataset: dataset.clone(),
+            fragment: fragment.clone(),
+            offsets,
+            change_type,
+            commit_version,
+        };
+
+    let previous_fragments = previous
+        .fragments()
+        .iter()
+        .map(|fragment| (fragment.id, fragment))
+        .collect::<HashMap<_, _>>();
+    let current_fragments = current
+        .fragments()
+        .iter()
+        .map(|fragment| (fragment.id, fragment))
+        .collect::<HashMap<_, _>>();
+
+    let mut changes = Vec::new();
+    match operation {
+        Operation::Append { .. } => {
+            for fragment in current.fragments().iter() {
+                if !previous_fragments.contains_key(&fragment.id) {
+                    let offsets = live_rows(current, fragment).await?;
+                    changes.push(change(current, fragment, offsets, ChangeType::Insert));
+                }
+            }
+        }
+        Operation::Delete { .. } => {
+            for fragment in previous.fragments().iter() {
+                let offsets = match current_fragments.get(&fragment.id) {
+                    Some(current_fragment)
+                        if current_fragment.deletion_file == fragment.deletion_file =>
+                    {
+                        continue
+                    }
+                    Some(current_fragment) => {
+                        deleted_rows(current, current_fragment).await?
+                            - deleted_rows(previous, fragment).await?
+                    }
+                    None => live_rows(previous, fragment).await?,
+                };
+                changes.push(change(previous, fragment, offsets, ChangeType::Delete));
+            }
+        }
+        Operation::Update { .. } => {
+            let mut preimages = Vec::new();
+            let mut postimages = Vec::new();
+            for fragment in previous.fragments().iter() {
+                match current_fragments.get(&fragment.id) {
+                    Some(current_fragment) if current_fragment.files != fragment.files => {
+                        // Columns were rewritten in place, every row may have changed.
+                        preimages.push((fragment, live_rows(previous, fragment).await?));
+                        postimages.push((
+                            *current_fragment,
+                            live_rows(current, current_fragment).await?,
+                        ));
+                    }
+                    Some(current_fragment)
+                        if current_fragment.deletion_file == fragment.deletion_file => {}
+                    Some(current_fragment) => preimages.push((
+                        fragment,
+                        deleted_rows(current, current_fragment).await?
+                            - deleted_rows(previous, fragment).await?,
+                    )),
+                    None => preimages.push((fragment, live_rows(previous, fragment).await?)),
+                }
+            }
+            for fragment in current.fragments().iter() {
+                if !previous_fragments.contains_key(&fragment.id) {
+                    postimages.push((fragment, live_rows(current, fragment).await?));
+                }
+            }
+
+            if current.manifest.uses_move_stable_row_ids() {
+                // Rows that kept their row id were updated, the others were
+                // deleted or inserted (e.g. by a merge insert).
+                let preimage_ids = row_ids_of(previous, &preimages).await?;
+                let postimage_ids = row_ids_of(current, &postimages).await?;
+                let updated_ids = preimage_ids
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .collect::<HashSet<_>>()
+                    .intersection(&postimage_ids.iter().flatten().copied().collect())
+                    .copied()
+                    .collect::<HashSet<_>>();
+                for ((fragment, offsets), ids) in preimages.into_iter().zip(preimage_ids) {
+                    let (updated, deleted) = partition_offsets(&offsets, &ids, &updated_ids);
+                    changes.push(change(
+                        previous,
+                        fragment,
+                        updated,
+                        ChangeType::UpdatePreimage,
+                    ));
+                    changes.push(change(previous, fragment, deleted, ChangeType::Delete));
+                }
+                for ((fragment, offsets), ids) in postimages.into_iter().zip(postimage_ids) {
+                    let (updated, inserted) = partition_offsets(&offsets, &ids, &updated_ids);
+                    changes.push(change(
+                        current,
+                        fragment,
+                        updated,
+                        ChangeType::UpdatePostimage,
+                    ));
+                    changes.push(change(current, fragment, inserted, ChangeType::Insert));
+                }
+            } else {
+                // Without stable row ids there is nothing to pair the rows with.
+                for (fragment, offsets) in preimages {
+                    changes.push(change(
+                        previous,
+                        fragment,
+                        offsets,
+                        ChangeType::UpdatePreimage,
+                    ));
+                }
+                for (fragment, offsets) in postimages {
+                    changes.push(change(
+                        current,
+                        fragment,
+                        offsets,
+                        ChangeType::UpdatePostimage,
+                    ));
+                }
+            }
+        }
+        Operation::Overwrite { .. } | Operation::Restore { .. } => {
+            for fragment in previous.fragments().iter() {
+                let offsets = live_rows(previous, fragment).await?;
+                changes.push(change(previous, fragment, offsets, ChangeType::Delete));
+            }
+            for fragment in current.fragments().iter() {
+                let offsets = live_rows(current, fragment).await?;
+                changes.push(change(current, fragment, offsets, ChangeType::Insert));
+            }
+        }
+        // These move rows around or only change metadata.
+        Operation::Rewrite { .. }
+        | Operation::CreateIndex { .. }
+        | Operation::ReserveFragments { .. }
+        | Operation::UpdateConfig { .. } => {}
+        Operation::Merge { .. } | Operation::Project { .. } | Operation::DataReplacement { .. } => {
+            return Err(Error::NotSupported {
+                source: format!(
+                    "row-level changes cannot be computed across the {} operation at version {}",
+                    operation, commit_version
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+    }
+    changes.retain(|change| !change.offsets.is_empty());
+    Ok(changes)
+}
+
+/// Physical offsets of the rows of `fragment` that are deleted in `dataset`.
+async fn deleted_rows(dataset: &Arc<Dataset>, fragment: &Fragment) -> Result<RoaringBitmap> {
+    let deletion_vector = FileFragment::new(dataset.clone(), fragment.clone())
+        .get_deletion_vector()
+        .await?;
+    Ok(deletion_vector
+        .map(|deletion_vector| RoaringBitmap::from(deletion_vector.as_ref()))
+        .unwrap_or_default())
+}
+
+/// Physical offsets of the rows of `fragment` that are not deleted in `dataset`.
+async fn live_rows(dataset: &Arc<Dataset>, fragment: &Fragment) -> Result<RoaringBitmap> {
+    let physical_rows = match fragment.physical_rows {
+        Some(physical_rows) => physical_rows,
+        None => {
+            FileFragment::new(dataset.clone(), fragment.clone())
+                .physical_rows()
+                .await?
+        }
+    };
+    let mut rows = RoaringBitmap::new();
+    rows.insert_range(0..physical_rows as u32);
+    Ok(rows - deleted_rows(dataset, fragment).await?)
+}
+
+/// The row ids of the rows at `offsets`, in the order of the offsets.
+async fn row_ids(
+    dataset: &Dataset,
+    fragment: &Fragment,
+    offsets: &RoaringBitmap,
+) -> Result<Vec<u64>> {
+    if dataset.manifest.uses_move_stable_row_ids() {
+        let sequence = load_row_id_sequence(dataset, fragment).await?;
+        offsets
+            .iter()
+            .map(|offset| {
+                sequence
+                    .get(offset as usize)
+                    .ok_or_else(|| Error::Internal {
+                        message: format!(
+                            "row id sequence of fragment {} has no row at offset {}",
+                            fragment.id, offset
+                        ),
+                        location: location!(),
+                    })
+            })
+            .collect()
+    } else {
+        Ok(offsets
+            .iter()
+            .map(|offset| (fragment.id << 32) | offset as u64)
+            .collect())
+    }
+}
+
+async fn row_ids_of(
+    dataset: &Dataset,
+    rows: &[(&Fragment, RoaringBitmap)],
+) -> Result<Vec<Vec<u64>>> {
+    let mut ids = Vec::with_capacity(rows.len());
+    for (fragment, offsets) in rows {
+        ids.push(row_ids(dataset, fragment, offsets).await?);
+    }
+    Ok(ids)
+}
+
+/// Split `offsets` into those whose row id is in `matched` and the rest.
+fn partition_offsets(
+    offsets: &RoaringBitmap,
+    ids: &[u64],
+    matched: &HashSet<u64>,
+) -> (RoaringBitmap, RoaringBitmap) {
+    let mut in_matched = RoaringBitmap::new();
+    let mut rest = RoaringBitmap::new();
+    for (offset, id) in offsets.iter().zip(ids) {
+        if matched.contains(id) {
+            in_matched.insert(offset);
+        } else {
+            rest.insert(offset);
+        }
+    }
+    (in_matched, rest)
+}
+
+async fn read_change(change: FragmentChange) -> Result<RecordBatch> {
+    let offsets = change.offsets.iter().collect::<Vec<_>>();
+    let row_ids = row_ids(&change.dataset, &change.fragment, &change.offsets).await?;
+
+    // The offsets are physical, so read them from the fragment as if none of its
+    // rows were deleted.
+    let fragment = Fragment {
+        deletion_file: None,
+        ..change.fragment
+    };
+    let batch = FileFragment::new(change.dataset.clone(), fragment)
+        .take(&offsets, change.dataset.schema())
+        .await?;
+
+    let num_rows = batch.num_rows();
+    batch
+        .try_with_column(
+            ROW_ID_FIELD.clone(),
+            Arc::new(UInt64Array::from(row_ids)) as ArrayRef,
+        )?
+        .try_with_column(
+            ArrowField::new(CHANGE_TYPE_COLUMN, DataType::Utf8, false),
+            Arc::new(StringArray::from(vec![
+                change.change_type.as_str();
+                num_rows
+            ])),
+        )?
+        .try_with_column(
+            ArrowField::new(COMMIT_VERSION_COLUMN, DataType::UInt64, false),
+            Arc::new(UInt64Array::from(vec![change.commit_version; num_rows])),
+        )
+        .map_err(Error::from)
+}
@@ -24,12 +24,17 @@ use lance_core::datatypes::{OnMissing, OnTypeMismatch, SchemaCompareOptions};
 use lance_core::utils::deletion::DeletionVector;
 use lance_core::utils::tokio::get_num_compute_intensive_cpus;
 use lance_core::utils::tracing::StreamTracingExt;
-use lance_core::{datatypes::Schema, Error, Result};
+use lance_core::{
+    datatypes::{Field, Schema},
+    Error, Result,
+};
 use lance_core::{ROW_ADDR, ROW_ADDR_FIELD, ROW_ID, ROW_ID_FIELD};
 use lance_datafusion::utils::StreamingWriteSource;
 use lance_encoding::decoder::DecoderPlugins;
 use lance_file::reader::{read_batch, FileReader};
-use lance_file::v2::reader::{CachedFileMetadata, FileReaderOptions, ReaderProjection};
+use lance_file::v2::reader::{
+    CachedFileMetadata, FileReaderOptions, PageStatistics, ReaderProjection,
+};
 use lance_file::v2::LanceEncodingsIo;
 use lance_file::version::LanceFileVersion;
 use lance_file::{determine_file_version, v2};
@@ -111,6 +116,12 @@ pub trait GenericFileReader: std::fmt::Debug + Send + Sync {
     /// Update storage statistics (ignored by v1 reader)
     fn update_storage_stats(&self, field_stats: &mut HashMap<u32, FieldStatistics>);
 
+    /// Return the value statistics of each page of a field
+    ///
+    /// Returns `None` if the file does not have page statistics for the field (v1 files
+    /// never do)
+    fn page_statistics(&self, field: &Field) -> Result<Option<Vec<PageStatistics>>>;
+
     // Helper functions to fallback to the legacy implementation while we
     // slowly migrate functionality over to the generic reader
 
@@ -270,6 +281,11 @@ impl GenericFileReader for V1Reader {
         // No-op for v1 files
     }
 
+    fn page_statistics(&self, _field: &Field) -> Result<Option<Vec<PageStatistics>>> {
+        // v1 files store page statistics differently, see `legacy_read_page_stats`
+        Ok(None)
+    }
+
     fn clone_box(&self) -> Box<dyn GenericFileReader> {
         Box::new(self.clone())
     }
@@ -460,6 +476,13 @@ mod v2_adapter {
             }
         }
 
+        fn page_statistics(&self, field: &Field) -> Result<Option<Vec<PageStatistics>>> {
+            let Some(column_idx) = self.field_id_to_column_idx.get(&(field.id as u32)) else {
+                return Ok(None);
+            };
+            self.reader.page_statistics(*column_idx, &field.data_type())
+        }
+
         fn projection(&self) -> &Arc<Schema> {
             &self.projection
         }
@@ -570,6 +593,15 @@ impl GenericFileReader for NullReader {
         // No-op for null reader
     }
 
+    fn page_statistics(&self, _field: &Field) -> Result<Option<Vec<PageStatistics>>> {
+        // Every value is null so there is a single page with no bounds
+        Ok(Some(vec![PageStatistics {
+            rows: 0..self.num_rows as u64,
+            null_count: self.num_rows as u64,
+            bounds: None,
+        }]))
+    }
+
     fn projection(&self) -> &Arc<Schema> {
         &self.schema
     }
@@ -2171,6 +2203,18 @@ impl FragmentReader {
         self.do_read_range(range, batch_size, false)
     }
 
+    /// Returns the value statistics of each page of a field
+    ///
+    /// Page rows are physical offsets into the fragment (i.e. deleted rows are not skipped).
+    /// Returns `None` if the field is not part of the reader's projection or the data file
+    /// does not have page statistics for the field.
+    pub fn page_statistics(&self, field: &Field) -> Result<Option<Vec<PageStatistics>>> {
+        self.readers
+            .iter()
+            .find(|reader| reader.projection().field_by_id(field.id).is_some())
+            .map_or(Ok(None), |reader| reader.page_statistics(field))
+    }
+
     pub fn read_all(&self, batch_size: u32) -> Result<ReadBatchFutStream> {
         self.new_read_impl(ReadBatchParams::RangeFull, batch_size, move |reader| {
             reader.read_all_tasks(batch_size, reader.projection().clone())
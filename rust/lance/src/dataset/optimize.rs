@@ -23,6 +23,9 @@
 //! that fragment will be remapped.  However, we cannot combine indexed fragments
 //! with unindexed fragments.
 //!
+//! Compaction can also cluster the rows it rewrites, by sorting them or ordering
+//! them along a space filling curve.  See [CompactionOptions::clustering].
+//!
 //! ```rust
 //! # use std::sync::Arc;
 //! # use tokio::runtime::Runtime;
@@ -88,27 +91,36 @@ use std::sync::{Arc, RwLock};
 use crate::io::commit::{commit_transaction, migrate_fragments};
 use crate::Dataset;
 use crate::Result;
+use arrow::compute::concat_batches;
+use arrow_array::cast::AsArray;
+use arrow_array::types::UInt64Type;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::SendableRecordBatchStream;
 use futures::{StreamExt, TryStreamExt};
 use lance_core::utils::tokio::get_num_compute_intensive_cpus;
+use lance_core::{Error, ROW_ID};
 use lance_index::frag_reuse::FragReuseGroup;
 use lance_index::DatasetIndexExt;
 use lance_table::format::{Fragment, RowIdMeta};
+use lance_table::rowids::RowIdSequence;
 use roaring::{RoaringBitmap, RoaringTreemap};
 use serde::{Deserialize, Serialize};
+use snafu::location;
 
 use super::fragment::FileFragment;
 use super::index::DatasetIndexRemapperOptions;
 use super::rowids::load_row_id_sequences;
+use super::scanner::Scanner;
 use super::transaction::{Operation, RewriteGroup, RewrittenIndex, Transaction};
 use super::utils::make_rowid_capture_stream;
 use super::{write_fragments_internal, WriteMode, WriteParams};
 
+pub mod clustering;
 pub mod remapping;
 
 use crate::index::frag_reuse::build_new_frag_reuse_index;
 use crate::io::deletion::read_dataset_deletion_file;
+pub use clustering::ClusteringSpec;
 pub use remapping::{IgnoreRemap, IndexRemapper, IndexRemapperOptions, RemappedIndex};
 
 /// Options to be passed to [compact_files].
@@ -152,6 +164,13 @@ pub struct CompactionOptions {
     /// not be remapped during this compaction operation. Instead, the fragment reuse index
     /// is updated and will be used to perform remapping later.
     pub defer_index_remap: bool,
+    /// How to order the rows of the rewritten fragments.  If not specified, rows
+    /// keep the order they had in the input fragments.
+    ///
+    /// The rows of each compaction task are clustered independently, and all of
+    /// them are held in memory while doing so.  Clustering can't be combined with
+    /// [Self::defer_index_remap] unless the dataset uses move-stable row ids.
+    pub clustering: Option<ClusteringSpec>,
 }
 
 impl Default for CompactionOptions {
@@ -166,6 +185,7 @@ impl Default for CompactionOptions {
             max_bytes_per_file: None,
             batch_size: None,
             defer_index_remap: false,
+            clustering: None,
         }
     }
 }
@@ -529,15 +549,18 @@ pub async fn plan_compaction(
             }
             (Some(candidacy), Some(bin)) => {
                 // We cannot mix "indexed" and "non-indexed" fragments and so we only consider
-                // the existing bin if it contains the same indices
-                if bin.indices == indices {
+                // the existing bin if it contains the same indices.  Fragments of different
+                // partitions are not mixed either.
+                if bin.indices == indices
+                    && bin.fragments[0].partition_values == fragment.partition_values
+                {
                     // Add to current bin
                     bin.fragments.push(fragment);
                     bin.pos_range.end += 1;
                     bin.candidacy.push(candidacy);
                     bin.row_counts.push(metrics.num_rows());
                 } else {
-                    // Index set is different.  Complete previous bin and start new one
+                    // Index set or partition is different.  Complete previous bin and start new one
                     candidate_bins.push(current_bin.take().unwrap());
                     current_bin = Some(CandidateBin {
                         fragments: vec![fragment],
@@ -670,6 +693,16 @@ async fn rewrite_files(
         .sum::<u64>();
     // If we aren't using move-stable row ids, then we need to remap indices.
     let needs_remapping = !dataset.manifest.uses_move_stable_row_ids();
+    if let Some(clustering) = &options.clustering {
+        clustering.validate(dataset.schema())?;
+        if needs_remapping && options.defer_index_remap {
+            // The fragment reuse index assumes rows keep their order
+            return Err(Error::NotSupported {
+                source: "clustering during compaction can't be combined with deferred index remapping unless the dataset uses move-stable row ids".into(),
+                location: location!(),
+            });
+        }
+    }
     let mut scanner = dataset.scan();
     if let Some(batch_size) = options.batch_size {
         scanner.batch_size(batch_size);
@@ -685,7 +718,13 @@ async fn rewrite_files(
     scanner
         .with_fragments(fragments.clone())
         .scan_in_order(true);
-    let (row_ids, reader) = if needs_remapping {
+    // The original row ids of the rows, in the order they are written
+    let mut clustered_row_ids = None;
+    let (row_ids, reader) = if let Some(clustering) = &options.clustering {
+        let (row_ids, data) = read_clustered(&mut scanner, clustering).await?;
+        clustered_row_ids = Some(row_ids);
+        (None, data)
+    } else if needs_remapping {
         let row_ids = Arc::new(RwLock::new(RoaringTreemap::new()));
         scanner.with_row_id();
         let data = SendableRecordBatchStream::from(scanner.try_into_stream().await?);
@@ -736,10 +775,44 @@ async fn rewrite_files(
     // We should not be rewriting any blob data
     assert!(new_fragments.blob.is_none());
     let mut new_fragments = new_fragments.default.0;
+    // Tasks only contain fragments of a single partition
+    for new_fragment in &mut new_fragments {
+        new_fragment
+            .partition_values
+            .clone_from(&task.fragments[0].partition_values);
+    }
 
     log::info!("Compaction task {}: file written", task_id);
 
-    let (row_id_map, changed_row_addrs) = if let Some(row_ids) = row_ids {
+    let (row_id_map, changed_row_addrs) = if let Some(clustered_row_ids) = clustered_row_ids {
+        if needs_remapping {
+            log::info!(
+                "Compaction task {}: reserving fragment ids and mapping clustered row ids",
+                task_id
+            );
+            reserve_fragment_ids(&dataset, new_fragments.iter_mut()).await?;
+            let row_id_map = remapping::transpose_clustered_row_ids(
+                &clustered_row_ids,
+                &fragments,
+                &new_fragments,
+            );
+            (Some(row_id_map), None)
+        } else {
+            log::info!(
+                "Compaction task {}: assigning clustered stable row ids",
+                task_id
+            );
+            assign_stable_row_ids(&mut new_fragments, &clustered_row_ids);
+            if options.defer_index_remap {
+                let no_addrs = RoaringTreemap::new();
+                let mut serialized_no_addrs = Vec::with_capacity(no_addrs.serialized_size());
+                no_addrs.serialize_into(&mut serialized_no_addrs)?;
+                (None, Some(serialized_no_addrs))
+            } else {
+                (Some(HashMap::new()), None)
+            }
+        }
+    } else if let Some(row_ids) = row_ids {
         let row_ids = Arc::try_unwrap(row_ids)
             .expect("Row ids lock still owned")
             .into_inner()
@@ -797,6 +870,43 @@ async fn rewrite_files(
     })
 }
 
+/// Read all the rows of a task and reorder them according to the clustering spec.
+///
+/// Returns the row ids the rows had before, in their new order, and the reordered rows.
+async fn read_clustered(
+    scanner: &mut Scanner,
+    clustering: &ClusteringSpec,
+) -> Result<(Vec<u64>, SendableRecordBatchStream)> {
+    scanner.with_row_id();
+    let stream = scanner.try_into_stream().await?;
+    let schema = stream.schema();
+    let batches = stream.try_collect::<Vec<_>>().await?;
+    let batch = concat_batches(&schema, &batches)?;
+    let batch = clustering::cluster_batch(clustering, &batch)?;
+    let (batch, row_ids) = clustering::split_row_ids(batch, ROW_ID);
+    let row_ids = row_ids.as_primitive::<UInt64Type>().values().to_vec();
+    let data = futures::stream::iter(vec![Ok(batch.clone())]);
+    Ok((
+        row_ids,
+        Box::pin(RecordBatchStreamAdapter::new(batch.schema(), data)),
+    ))
+}
+
+/// Give the new fragments the stable row ids of their rows, which were reordered
+/// while being rewritten.
+fn assign_stable_row_ids(new_fragments: &mut [Fragment], row_ids: &[u64]) {
+    let mut offset = 0;
+    for fragment in new_fragments {
+        let num_rows = fragment.physical_rows.unwrap();
+        let sequence = RowIdSequence::from(&row_ids[offset..offset + num_rows]);
+        offset += num_rows;
+        // TODO: if large enough, serialize to separate file
+        let serialized = lance_table::rowids::write_row_ids(&sequence);
+        fragment.row_id_meta = Some(RowIdMeta::Inline(serialized));
+    }
+    debug_assert_eq!(offset, row_ids.len());
+}
+
 async fn rechunk_stable_row_ids(
     dataset: &Dataset,
     new_fragments: &mut [Fragment],
@@ -1016,6 +1126,7 @@ mod tests {
             deletion_file: None,
             row_id_meta: None,
             physical_rows: Some(0),
+            partition_values: vec![],
         };
         let single_bin = CandidateBin {
             fragments: vec![fragment.clone()],
@@ -1770,6 +1881,100 @@ mod tests {
         assert_eq!(before_scalar_result, after_scalar_result);
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_compact_clustered(#[values(false, true)] use_stable_row_ids: bool) {
+        // x is a permutation of 0..1000, y records the original position
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("x", DataType::Int64, false),
+            Field::new("y", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(
+                    (0..1000).map(|i| (i * 7919) % 1000),
+                )),
+                Arc::new(Int64Array::from_iter_values(0..1000)),
+            ],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut dataset = Dataset::write(
+            reader,
+            "memory://test/table",
+            Some(WriteParams {
+                enable_move_stable_row_ids: use_stable_row_ids,
+                max_rows_per_file: 100,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        dataset.delete("y < 50").await.unwrap();
+        dataset
+            .create_index(
+                &["x"],
+                IndexType::Scalar,
+                Some("scalar".into()),
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+
+        if !use_stable_row_ids {
+            let options = CompactionOptions {
+                clustering: Some(ClusteringSpec::Sort(vec!["x".into()])),
+                defer_index_remap: true,
+                ..Default::default()
+            };
+            let result = compact_files(&mut dataset, options, None).await;
+            assert!(matches!(result, Err(Error::NotSupported { .. })));
+        }
+
+        let options = CompactionOptions {
+            clustering: Some(ClusteringSpec::Sort(vec!["x".into()])),
+            ..Default::default()
+        };
+        let metrics = compact_files(&mut dataset, options, None).await.unwrap();
+        assert_eq!(metrics.fragments_removed, 10);
+        assert_eq!(metrics.fragments_added, 1);
+
+        let data = dataset.scan().try_into_batch().await.unwrap();
+        let xs = data["x"].as_primitive::<Int64Type>();
+        let ys = data["y"].as_primitive::<Int64Type>();
+        assert_eq!(data.num_rows(), 950);
+        assert!(xs.values().windows(2).all(|pair| pair[0] < pair[1]));
+        for (x, y) in xs.values().iter().zip(ys.values()) {
+            assert_eq!((y * 7919) % 1000, *x);
+        }
+
+        if !use_stable_row_ids {
+            // The index was remapped to the new fragment
+            let indices = dataset.load_indices().await.unwrap();
+            let new_fragment_id = dataset.get_fragments()[0].id() as u32;
+            assert!(indices[0]
+                .fragment_bitmap
+                .as_ref()
+                .unwrap()
+                .contains(new_fragment_id));
+        }
+
+        // The index still finds the right rows, and none of the deleted ones
+        for (x, expected) in [(7919 * 600 % 1000, Some(600)), (7919 * 10 % 1000, None)] {
+            let mut scanner = dataset.scan();
+            scanner
+                .filter(&format!("x = {}", x))
+                .unwrap()
+                .project(&["y"])
+                .unwrap();
+            let result = scanner.try_into_batch().await.unwrap();
+            let ys = result["y"].as_primitive::<Int64Type>();
+            assert_eq!(ys.values().first().copied(), expected);
+        }
+    }
+
     #[tokio::test]
     async fn test_defer_index_remap() {
         let mut data_gen = BatchGenerator::new()
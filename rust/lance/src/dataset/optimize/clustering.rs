@@ -0,0 +1,361 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Clustering rows while compacting.
+//!
+//! By default compaction concatenates the rows of the input fragments in the order
+//! they were written.  With a [`ClusteringSpec`] the rows of each compaction task
+//! are reordered first, so rows with similar values end up in the same pages and
+//! fragments.  This keeps the min / max statistics of each page narrow, which is
+//! what makes zone map pruning effective.
+//!
+//! Sorting clusters well on the first column but poorly on the others.  The
+//! space filling curves (Z-order and Hilbert) interleave the columns so that
+//! filters on any of them benefit.
+
+use arrow_array::{Array, ArrayRef, RecordBatch, UInt32Array};
+use arrow_row::{RowConverter, Rows, SortField};
+use lance_core::datatypes::Schema;
+use serde::{Deserialize, Serialize};
+use snafu::location;
+
+use crate::{Error, Result};
+
+/// The maximum number of columns a space filling curve can be built from.  Each
+/// column gets `64 / n` bits of the curve key.
+const MAX_CURVE_COLUMNS: usize = 16;
+
+/// How to order rows when they are rewritten by compaction.
+///
+/// Only top-level columns can be used.  Nulls are ordered before all other values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClusteringSpec {
+    /// Sort the rows by the given columns, in ascending order
+    Sort(Vec<String>),
+    /// Order the rows along a Z-order (Morton) curve over the given columns
+    ZOrder(Vec<String>),
+    /// Order the rows along a Hilbert curve over the given columns.  This clusters
+    /// slightly better than Z-order as consecutive cells are always adjacent.
+    Hilbert(Vec<String>),
+}
+
+impl ClusteringSpec {
+    pub fn columns(&self) -> &[String] {
+        match self {
+            Self::Sort(columns) | Self::ZOrder(columns) | Self::Hilbert(columns) => columns,
+        }
+    }
+
+    /// Check the spec can be applied to a dataset with the given schema
+    pub fn validate(&self, schema: &Schema) -> Result<()> {
+        let columns = self.columns();
+        if columns.is_empty() {
+            return Err(Error::invalid_input(
+                "clustering requires at least one column",
+                location!(),
+            ));
+        }
+        if !matches!(self, Self::Sort(_)) && columns.len() > MAX_CURVE_COLUMNS {
+            return Err(Error::invalid_input(
+                format!(
+                    "space filling curves support at most {} columns, got {}",
+                    MAX_CURVE_COLUMNS,
+                    columns.len()
+                ),
+                location!(),
+            ));
+        }
+        for column in columns {
+            if schema.fields.iter().all(|field| &field.name != column) {
+                return Err(Error::invalid_input(
+                    format!(
+                        "clustering column '{}' is not a top-level column of the dataset",
+                        column
+                    ),
+                    location!(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The order the rows of the batch should be written in
+    pub(super) fn sort_indices(&self, batch: &RecordBatch) -> Result<UInt32Array> {
+        let columns = self
+            .columns()
+            .iter()
+            .map(|name| {
+                batch.column_by_name(name).cloned().ok_or_else(|| {
+                    Error::invalid_input(
+                        format!("clustering column '{}' not found", name),
+                        location!(),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut indices = (0..batch.num_rows() as u32).collect::<Vec<_>>();
+        match self {
+            Self::Sort(_) => {
+                let rows = convert_rows(&columns)?;
+                indices.sort_by(|a, b| rows.row(*a as usize).cmp(&rows.row(*b as usize)));
+            }
+            Self::ZOrder(_) | Self::Hilbert(_) => {
+                let bits = (64 / columns.len() as u32).min(32);
+                let coordinates = columns
+                    .iter()
+                    .map(|column| quantized_ranks(column, bits))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut point = vec![0; columns.len()];
+                let keys = (0..batch.num_rows())
+                    .map(|row| {
+                        for (coordinate, values) in point.iter_mut().zip(&coordinates) {
+                            *coordinate = values[row];
+                        }
+                        if matches!(self, Self::Hilbert(_)) {
+                            hilbert_transpose(&mut point, bits);
+                        }
+                        interleave_bits(&point, bits)
+                    })
+                    .collect::<Vec<_>>();
+                indices.sort_by_key(|row| keys[*row as usize]);
+            }
+        }
+        Ok(UInt32Array::from(indices))
+    }
+}
+
+fn convert_rows(columns: &[ArrayRef]) -> Result<Rows> {
+    let converter = RowConverter::new(
+        columns
+            .iter()
+            .map(|column| SortField::new(column.data_type().clone()))
+            .collect(),
+    )?;
+    Ok(converter.convert_columns(columns)?)
+}
+
+/// Replace each value with its rank among the distinct values of the column,
+/// scaled to fill `bits` bits.
+///
+/// Using ranks instead of the values themselves spreads skewed distributions
+/// evenly over the curve and works for any orderable type.
+fn quantized_ranks(column: &ArrayRef, bits: u32) -> Result<Vec<u32>> {
+    let rows = convert_rows(&[column.clone()])?;
+    let mut order = (0..column.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| rows.row(*a).cmp(&rows.row(*b)));
+
+    let mut ranks = vec![0_u64; column.len()];
+    let mut rank = 0;
+    for (i, row) in order.iter().enumerate() {
+        if i > 0 && rows.row(order[i - 1]) != rows.row(*row) {
+            rank += 1;
+        }
+        ranks[*row] = rank;
+    }
+    let num_distinct = rank as u128 + 1;
+    Ok(ranks
+        .into_iter()
+        .map(|rank| ((rank as u128 * (1_u128 << bits)) / num_distinct) as u32)
+        .collect())
+}
+
+/// Build a key from the bits of each coordinate, most significant bits first.
+fn interleave_bits(point: &[u32], bits: u32) -> u64 {
+    let mut key = 0_u64;
+    for bit in (0..bits).rev() {
+        for coordinate in point {
+            key = (key << 1) | ((coordinate >> bit) & 1) as u64;
+        }
+    }
+    key
+}
+
+/// Convert a point into the "transposed" Hilbert index, using Skilling's
+/// algorithm.  Interleaving the bits of the result gives the Hilbert index.
+///
+/// See J. Skilling, "Programming the Hilbert curve", AIP Conf. Proc. 707 (2004).
+#[allow(clippy::needless_range_loop)]
+fn hilbert_transpose(point: &mut [u32], bits: u32) {
+    let n = point.len();
+    let m = 1_u32 << (bits - 1);
+
+    // Inverse undo
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if point[i] & q != 0 {
+                point[0] ^= p;
+            } else {
+                let t = (point[0] ^ point[i]) & p;
+                point[0] ^= t;
+                point[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode
+    for i in 1..n {
+        point[i] ^= point[i - 1];
+    }
+    let mut t = 0;
+    let mut q = m;
+    while q > 1 {
+        if point[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for coordinate in point.iter_mut() {
+        *coordinate ^= t;
+    }
+}
+
+/// Split the row id column off a batch of rows read with row ids
+pub(super) fn split_row_ids(
+    mut batch: RecordBatch,
+    row_id_column: &str,
+) -> (RecordBatch, ArrayRef) {
+    let idx = batch
+        .schema()
+        .index_of(row_id_column)
+        .expect("row ids were requested");
+    let row_ids = batch.remove_column(idx);
+    (batch, row_ids)
+}
+
+/// Reorder the rows of a batch according to the spec
+pub(super) fn cluster_batch(spec: &ClusteringSpec, batch: &RecordBatch) -> Result<RecordBatch> {
+    let indices = spec.sort_indices(batch)?;
+    Ok(arrow_select::take::take_record_batch(batch, &indices)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, StringArray};
+    use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+
+    fn grid(size: i32) -> RecordBatch {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("x", DataType::Int32, false),
+            ArrowField::new("y", DataType::Int32, false),
+            ArrowField::new("name", DataType::Utf8, true),
+        ]));
+        let points = (0..size)
+            .flat_map(|x| (0..size).map(move |y| (x, y)))
+            .collect::<Vec<_>>();
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from_iter_values(points.iter().map(|p| p.0))),
+                Arc::new(Int32Array::from_iter_values(points.iter().map(|p| p.1))),
+                Arc::new(StringArray::from_iter(
+                    points
+                        .iter()
+                        .map(|p| (p.0 != 0).then(|| (p.1 * 10).to_string())),
+                )),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn points(spec: &ClusteringSpec, batch: &RecordBatch) -> Vec<(i32, i32)> {
+        let batch = cluster_batch(spec, batch).unwrap();
+        let xs = batch
+            .column_by_name("x")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        let ys = batch
+            .column_by_name("y")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        xs.values()
+            .iter()
+            .copied()
+            .zip(ys.values().iter().copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = Schema::try_from(grid(1).schema().as_ref()).unwrap();
+        assert!(ClusteringSpec::Sort(vec!["x".into()])
+            .validate(&schema)
+            .is_ok());
+        assert!(ClusteringSpec::ZOrder(vec![]).validate(&schema).is_err());
+        assert!(ClusteringSpec::Hilbert(vec!["x".into(), "z".into()])
+            .validate(&schema)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sort() {
+        let batch = grid(3);
+        let sorted = cluster_batch(
+            &ClusteringSpec::Sort(vec!["name".into(), "x".into()]),
+            &batch,
+        )
+        .unwrap();
+        let names = sorted
+            .column_by_name("name")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        // Nulls first, then strings in lexicographic order
+        assert_eq!(names.null_count(), 3);
+        assert!(names.iter().take(3).all(|name| name.is_none()));
+        assert_eq!(
+            names.iter().skip(3).flatten().collect::<Vec<_>>(),
+            vec!["0", "0", "10", "10", "20", "20"]
+        );
+    }
+
+    #[test]
+    fn test_z_order() {
+        let batch = grid(4);
+        let points = points(
+            &ClusteringSpec::ZOrder(vec!["x".into(), "y".into()]),
+            &batch,
+        );
+        assert_eq!(
+            &points[..8],
+            &[
+                (0, 0),
+                (0, 1),
+                (1, 0),
+                (1, 1),
+                (0, 2),
+                (0, 3),
+                (1, 2),
+                (1, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hilbert() {
+        let batch = grid(8);
+        let points = points(
+            &ClusteringSpec::Hilbert(vec!["x".into(), "y".into()]),
+            &batch,
+        );
+        assert_eq!(points.len(), 64);
+        assert_eq!(points[0], (0, 0));
+        // Consecutive points on a Hilbert curve are always neighbors
+        for pair in points.windows(2) {
+            let distance = (pair[0].0 - pair[1].0).abs() + (pair[0].1 - pair[1].1).abs();
+            assert_eq!(distance, 1, "{:?}", pair);
+        }
+    }
+}
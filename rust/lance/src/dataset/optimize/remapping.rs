@@ -185,6 +185,37 @@ pub fn transpose_row_ids_from_digest(
     mapping
 }
 
+/// Like [transpose_row_ids], but for rows that were reordered while being
+/// rewritten.  `row_ids` are the original row ids, in the order the rows were
+/// written to the new fragments.
+pub fn transpose_clustered_row_ids(
+    row_ids: &[u64],
+    old_fragments: &[Fragment],
+    new_fragments: &[Fragment],
+) -> HashMap<u64, Option<u64>> {
+    let old_frag_digests: Vec<FragDigest> = old_fragments.iter().map(|frag| frag.into()).collect();
+    let new_ids = new_fragments.iter().flat_map(|frag| {
+        (0..frag.physical_rows.unwrap_or_default() as u32).map(|offset| {
+            Some(u64::from(RowAddress::new_from_parts(
+                frag.id as u32,
+                offset,
+            )))
+        })
+    });
+    let expected_size = row_ids.len()
+        + old_frag_digests
+            .iter()
+            .map(|frag| frag.num_deleted_rows)
+            .sum::<usize>();
+    let mut mapping: HashMap<u64, Option<u64>> = HashMap::with_capacity(expected_size);
+    mapping.extend(row_ids.iter().copied().zip(new_ids));
+    let sorted_row_ids = RoaringTreemap::from_iter(row_ids.iter().copied());
+    MissingIds::new(sorted_row_ids.into_iter(), &old_frag_digests).for_each(|id| {
+        mapping.insert(id, None);
+    });
+    mapping
+}
+
 /// Remap a given index using the fragment reuse index if possible.
 /// If the frag reuse index does not exist, the operation fails with [Error::NotSupported]
 /// If the frag reuse index exists but is empty, the operation succeeds without a commit.
@@ -0,0 +1,827 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Partitioned dataset layout
+//!
+//! A dataset can be written with a [`PartitionSpec`].  Every fragment of a partitioned
+//! dataset only holds rows that share the same partition values, and those values are
+//! recorded in the fragment metadata.  Scans can then skip whole fragments whose
+//! partition values can't satisfy the filter, without reading any data.
+//!
+//! The spec is recorded in the manifest config as a comma separated list of
+//! `<field id>:<transform>` (e.g. `3:identity,5:bucket[16]`), so it survives column
+//! renames.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::compute::cast;
+use arrow::util::display::array_value_to_string;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Date32Type, Int64Type};
+use arrow_array::{Array, ArrayRef, Int32Array, Int64Array, RecordBatch, StringArray, UInt32Array};
+use arrow_row::{RowConverter, SortField};
+use arrow_schema::DataType;
+use arrow_select::take::take_record_batch;
+use chrono::{Datelike, NaiveDate};
+use datafusion::logical_expr::{Between, BinaryExpr, Expr, Operator};
+use datafusion::scalar::ScalarValue;
+use datafusion_expr::expr::InList;
+use datafusion_expr::utils::split_conjunction;
+use lance_core::datatypes::Schema;
+use lance_table::format::{Fragment, Manifest};
+use snafu::location;
+
+use crate::{Error, Result};
+
+/// The manifest config key that the partition spec is stored under
+pub(crate) const PARTITION_SPEC_CONFIG_KEY: &str = "lance.partition_spec";
+
+/// The number of days between 0001-01-01 (CE) and 1970-01-01
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// How the partition value of a row is derived from the value of the source column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionTransform {
+    /// The value itself
+    Identity,
+    /// A hash of the value, modulo the number of buckets
+    Bucket(u32),
+    /// Integers rounded down to a multiple of the width, or strings truncated to the
+    /// width (in characters)
+    Truncate(u32),
+    /// The day of a date or timestamp, as the number of days since the unix epoch
+    Day,
+    /// The month of a date or timestamp, as the number of months since the unix epoch
+    Month,
+}
+
+impl Display for PartitionTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity => write!(f, "identity"),
+            Self::Bucket(num_buckets) => write!(f, "bucket[{}]", num_buckets),
+            Self::Truncate(width) => write!(f, "truncate[{}]", width),
+            Self::Day => write!(f, "day"),
+            Self::Month => write!(f, "month"),
+        }
+    }
+}
+
+impl FromStr for PartitionTransform {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse_arg = |name: &str| -> Result<u32> {
+            let arg = s
+                .strip_prefix(name)
+                .and_then(|s| s.strip_prefix('['))
+                .and_then(|s| s.strip_suffix(']'))
+                .and_then(|s| s.parse::<u32>().ok())
+                .filter(|arg| *arg > 0);
+            arg.ok_or_else(|| {
+                Error::invalid_input(format!("Invalid partition transform: {}", s), location!())
+            })
+        };
+        match s {
+            "identity" => Ok(Self::Identity),
+            "day" => Ok(Self::Day),
+            "month" => Ok(Self::Month),
+            _ if s.starts_with("bucket") => Ok(Self::Bucket(parse_arg("bucket")?)),
+            _ if s.starts_with("truncate") => Ok(Self::Truncate(parse_arg("truncate")?)),
+            _ => Err(Error::invalid_input(
+                format!("Invalid partition transform: {}", s),
+                location!(),
+            )),
+        }
+    }
+}
+
+impl PartitionTransform {
+    /// The type of the partition values, or an error if the transform can't be
+    /// applied to values of `source_type`
+    fn output_type(&self, source_type: &DataType) -> Result<DataType> {
+        let is_string = matches!(source_type, DataType::Utf8 | DataType::LargeUtf8);
+        let is_temporal = matches!(
+            source_type,
+            DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _)
+        );
+        let output_type = match self {
+            Self::Identity
+                if source_type.is_integer()
+                    || is_string
+                    || is_temporal
+                    || *source_type == DataType::Boolean =>
+            {
+                Some(source_type.clone())
+            }
+            Self::Bucket(_) if source_type.is_integer() || is_string || is_temporal => {
+                Some(DataType::Int32)
+            }
+            Self::Truncate(_) if source_type.is_integer() => Some(DataType::Int64),
+            Self::Truncate(_) if is_string => Some(DataType::Utf8),
+            Self::Day | Self::Month if is_temporal => Some(DataType::Int32),
+            _ => None,
+        };
+        output_type.ok_or_else(|| {
+            Error::invalid_input(
+                format!(
+                    "Partition transform {} does not support type {}",
+                    self, source_type
+                ),
+                location!(),
+            )
+        })
+    }
+
+    /// Whether `a <= b` implies `transform(a) <= transform(b)`
+    fn preserves_order(&self) -> bool {
+        !matches!(self, Self::Bucket(_))
+    }
+
+    fn apply(&self, values: &ArrayRef) -> Result<ArrayRef> {
+        let is_string = matches!(values.data_type(), DataType::Utf8 | DataType::LargeUtf8);
+        match self {
+            Self::Identity => Ok(values.clone()),
+            Self::Bucket(num_buckets) => {
+                let num_buckets = *num_buckets as u64;
+                let buckets: Int32Array = if is_string {
+                    let values = cast(values, &DataType::Utf8)?;
+                    values
+                        .as_string::<i32>()
+                        .iter()
+                        .map(|v| v.map(|v| (fnv1a(v.as_bytes()) % num_buckets) as i32))
+                        .collect()
+                } else {
+                    let values = cast(&days_or_values(values)?, &DataType::Int64)?;
+                    values
+                        .as_primitive::<Int64Type>()
+                        .iter()
+                        .map(|v| v.map(|v| (fnv1a(&v.to_le_bytes()) % num_buckets) as i32))
+                        .collect()
+                };
+                Ok(Arc::new(buckets))
+            }
+            Self::Truncate(width) => {
+                if is_string {
+                    let values = cast(values, &DataType::Utf8)?;
+                    let truncated: StringArray = values
+                        .as_string::<i32>()
+                        .iter()
+                        .map(|v| v.map(|v| v.chars().take(*width as usize).collect::<String>()))
+                        .collect();
+                    Ok(Arc::new(truncated))
+                } else {
+                    let width = *width as i64;
+                    let values = cast(values, &DataType::Int64)?;
+                    let truncated: Int64Array = values
+                        .as_primitive::<Int64Type>()
+                        .iter()
+                        .map(|v| v.map(|v| v - v.rem_euclid(width)))
+                        .collect();
+                    Ok(Arc::new(truncated))
+                }
+            }
+            Self::Day => Ok(cast(&days_or_values(values)?, &DataType::Int32)?),
+            Self::Month => {
+                let days = days_or_values(values)?;
+                let months: Int32Array = days
+                    .as_primitive::<Date32Type>()
+                    .iter()
+                    .map(|days| {
+                        let date =
+                            NaiveDate::from_num_days_from_ce_opt(days? + UNIX_EPOCH_DAYS_FROM_CE)?;
+                        Some((date.year() - 1970) * 12 + date.month0() as i32)
+                    })
+                    .collect();
+                Ok(Arc::new(months))
+            }
+        }
+    }
+}
+
+/// Dates and timestamps as days since the unix epoch, other values unchanged
+fn days_or_values(values: &ArrayRef) -> Result<ArrayRef> {
+    match values.data_type() {
+        DataType::Date64 | DataType::Timestamp(_, _) => Ok(cast(values, &DataType::Date32)?),
+        _ => Ok(values.clone()),
+    }
+}
+
+/// 64-bit FNV-1a, used for bucketing because the result must never change between releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A column of the dataset and the transform that derives partition values from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionField {
+    pub column: String,
+    pub transform: PartitionTransform,
+}
+
+impl PartitionField {
+    pub fn new(column: impl Into<String>, transform: PartitionTransform) -> Self {
+        Self {
+            column: column.into(),
+            transform,
+        }
+    }
+
+    pub fn identity(column: impl Into<String>) -> Self {
+        Self::new(column, PartitionTransform::Identity)
+    }
+
+    pub fn bucket(column: impl Into<String>, num_buckets: u32) -> Self {
+        Self::new(column, PartitionTransform::Bucket(num_buckets))
+    }
+
+    pub fn truncate(column: impl Into<String>, width: u32) -> Self {
+        Self::new(column, PartitionTransform::Truncate(width))
+    }
+
+    pub fn day(column: impl Into<String>) -> Self {
+        Self::new(column, PartitionTransform::Day)
+    }
+
+    pub fn month(column: impl Into<String>) -> Self {
+        Self::new(column, PartitionTransform::Month)
+    }
+
+    fn output_type(&self, schema: &Schema) -> Result<DataType> {
+        let field = schema
+            .fields
+            .iter()
+            .find(|f| f.name == self.column)
+            .ok_or_else(|| {
+                Error::invalid_input(
+                    format!(
+                        "Partition column {} is not a top-level column of the schema",
+                        self.column
+                    ),
+                    location!(),
+                )
+            })?;
+        self.transform.output_type(&field.data_type())
+    }
+}
+
+/// How the rows of a dataset are partitioned into fragments
+///
+/// Each fragment holds the rows that have the same value for every field of the spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionSpec {
+    pub fields: Vec<PartitionField>,
+}
+
+impl PartitionSpec {
+    pub fn new(fields: Vec<PartitionField>) -> Self {
+        Self { fields }
+    }
+
+    /// Load the partition spec of a dataset version, if it is partitioned
+    pub(crate) fn from_manifest(manifest: &Manifest) -> Result<Option<Self>> {
+        let Some(value) = manifest.config.get(PARTITION_SPEC_CONFIG_KEY) else {
+            return Ok(None);
+        };
+        let fields = value
+            .split(',')
+            .map(|field| {
+                let invalid = || {
+                    Error::corrupt_file(
+                        "_versions".into(),
+                        format!("Invalid partition spec in manifest: {}", value),
+                        location!(),
+                    )
+                };
+                let (field_id, transform) = field.split_once(':').ok_or_else(invalid)?;
+                let field_id = field_id.parse::<i32>().map_err(|_| invalid())?;
+                let column = manifest
+                    .schema
+                    .fields
+                    .iter()
+                    .find(|f| f.id == field_id)
+                    .ok_or_else(invalid)?;
+                Ok(PartitionField::new(&column.name, transform.parse()?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(Self::new(fields)))
+    }
+
+    /// The value to store in the manifest config, see [`PARTITION_SPEC_CONFIG_KEY`]
+    pub(crate) fn to_config_value(&self, schema: &Schema) -> Result<String> {
+        self.validate(schema)?;
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| {
+                let field_id = schema.field_id(&field.column)?;
+                Ok(format!("{}:{}", field_id, field.transform))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(fields.join(","))
+    }
+
+    /// Check that every field is a column of the schema that its transform supports
+    pub(crate) fn validate(&self, schema: &Schema) -> Result<()> {
+        if self.fields.is_empty() {
+            return Err(Error::invalid_input(
+                "A partition spec must have at least one field",
+                location!(),
+            ));
+        }
+        for field in &self.fields {
+            field.output_type(schema)?;
+        }
+        Ok(())
+    }
+
+    /// Split a batch into one batch per partition, along with the partition values
+    pub(crate) fn split_batch(
+        &self,
+        batch: &RecordBatch,
+    ) -> Result<Vec<(Vec<Option<String>>, RecordBatch)>> {
+        let partition_values = self
+            .fields
+            .iter()
+            .map(|field| {
+                let column = batch.column_by_name(&field.column).ok_or_else(|| {
+                    Error::invalid_input(
+                        format!("Partition column {} is missing from the data", field.column),
+                        location!(),
+                    )
+                })?;
+                field.transform.apply(column)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let converter = RowConverter::new(
+            partition_values
+                .iter()
+                .map(|values| SortField::new(values.data_type().clone()))
+                .collect(),
+        )?;
+        let rows = converter.convert_columns(&partition_values)?;
+        let mut group_of_row = HashMap::new();
+        let mut groups: Vec<Vec<u32>> = Vec::new();
+        for (row_idx, row) in rows.iter().enumerate() {
+            let group = *group_of_row.entry(row).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(row_idx as u32);
+        }
+
+        groups
+            .into_iter()
+            .map(|indices| {
+                let values = partition_values
+                    .iter()
+                    .map(|values| {
+                        let idx = indices[0] as usize;
+                        if values.is_null(idx) {
+                            Ok(None)
+                        } else {
+                            Ok(Some(array_value_to_string(values, idx)?))
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let batch = if indices.len() == batch.num_rows() {
+                    batch.clone()
+                } else {
+                    take_record_batch(batch, &UInt32Array::from(indices))?
+                };
+                Ok((values, batch))
+            })
+            .collect()
+    }
+
+    /// The partition values of a fragment after some of its rows were rewritten
+    ///
+    /// `updated` holds the new values of the rewritten rows, possibly for only some
+    /// of the columns.  If they move rows to another partition then the fragment no
+    /// longer holds a single partition, so its values are cleared and it is never
+    /// pruned.
+    pub(crate) fn updated_partition_values(
+        &self,
+        current: &[Option<String>],
+        updated: &[RecordBatch],
+        replaces_all_rows: bool,
+    ) -> Result<Vec<Option<String>>> {
+        let Some(update_schema) = updated.first().map(|batch| batch.schema()) else {
+            return Ok(current.to_vec());
+        };
+        let num_updated_fields = self
+            .fields
+            .iter()
+            .filter(|field| update_schema.column_with_name(&field.column).is_some())
+            .count();
+        if num_updated_fields == 0 {
+            return Ok(current.to_vec());
+        }
+        if num_updated_fields < self.fields.len() {
+            return Ok(vec![]);
+        }
+
+        let mut new_values = None;
+        for batch in updated {
+            for (values, _) in self.split_batch(batch)? {
+                match &new_values {
+                    None => new_values = Some(values),
+                    Some(new_values) if *new_values == values => {}
+                    Some(_) => return Ok(vec![]),
+                }
+            }
+        }
+        match new_values {
+            None => Ok(current.to_vec()),
+            Some(values) if replaces_all_rows || values == current => Ok(values),
+            Some(_) => Ok(vec![]),
+        }
+    }
+
+    /// Remove the fragments that can't contain any rows matching `filter`
+    ///
+    /// Only the top-level conjuncts of the filter that compare a partition column with
+    /// literals (`=`, `<`, `<=`, `>`, `>=`, `IN` and `BETWEEN`) are used.  Fragments
+    /// without partition values are always kept.
+    pub(crate) fn prune(
+        &self,
+        schema: &Schema,
+        filter: &Expr,
+        fragments: &[Fragment],
+    ) -> Vec<Fragment> {
+        let constraints = split_conjunction(filter)
+            .into_iter()
+            .flat_map(|expr| self.constraints(schema, expr))
+            .collect::<Vec<_>>();
+        fragments
+            .iter()
+            .filter(|fragment| {
+                fragment.partition_values.len() != self.fields.len()
+                    || constraints.iter().all(|constraint| {
+                        constraint
+                            .may_match(fragment.partition_values[constraint.field_idx].as_deref())
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The constraints that a predicate puts on the partition values
+    fn constraints(&self, schema: &Schema, expr: &Expr) -> Vec<PartitionConstraint> {
+        let comparisons = match expr {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                match (
+                    column_name(left),
+                    literal(right),
+                    column_name(right),
+                    literal(left),
+                ) {
+                    (Some(column), Some(value), _, _) => vec![(column, *op, vec![value])],
+                    (_, _, Some(column), Some(value)) => match op.swap() {
+                        Some(op) => vec![(column, op, vec![value])],
+                        None => vec![],
+                    },
+                    _ => vec![],
+                }
+            }
+            Expr::InList(InList {
+                expr,
+                list,
+                negated: false,
+            }) => match (
+                column_name(expr),
+                list.iter().map(literal).collect::<Option<Vec<_>>>(),
+            ) {
+                (Some(column), Some(values)) => vec![(column, Operator::Eq, values)],
+                _ => vec![],
+            },
+            Expr::Between(Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            }) => match (column_name(expr), literal(low), literal(high)) {
+                (Some(column), Some(low), Some(high)) => vec![
+                    (column, Operator::GtEq, vec![low]),
+                    (column, Operator::LtEq, vec![high]),
+                ],
+                _ => vec![],
+            },
+            _ => vec![],
+        };
+
+        let mut constraints = Vec::new();
+        for (column, op, values) in comparisons {
+            for (field_idx, field) in self.fields.iter().enumerate() {
+                if field.column != column {
+                    continue;
+                }
+                let Some(constraint) =
+                    PartitionConstraint::try_new(schema, field_idx, field, op, &values)
+                else {
+                    continue;
+                };
+                constraints.push(constraint);
+            }
+        }
+        constraints
+    }
+}
+
+fn column_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Column(column) => Some(&column.name),
+        _ => None,
+    }
+}
+
+fn literal(expr: &Expr) -> Option<ScalarValue> {
+    match expr {
+        Expr::Literal(value) => Some(value.clone()),
+        Expr::Cast(cast) => match cast.expr.as_ref() {
+            Expr::Literal(value) => value.cast_to(&cast.data_type).ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A condition that the partition values of a fragment must meet for the fragment
+/// to contain matching rows
+struct PartitionConstraint {
+    field_idx: usize,
+    output_type: DataType,
+    op: Operator,
+    /// The transformed literals, the constraint is met if any of them match
+    values: Vec<ScalarValue>,
+}
+
+impl PartitionConstraint {
+    fn try_new(
+        schema: &Schema,
+        field_idx: usize,
+        field: &PartitionField,
+        op: Operator,
+        values: &[ScalarValue],
+    ) -> Option<Self> {
+        let source_type = schema.field(&field.column)?.data_type();
+        let output_type = field.output_type(schema).ok()?;
+        let op = match op {
+            Operator::Eq => Operator::Eq,
+            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+                if field.transform.preserves_order() =>
+            {
+                // Many values share a partition value, so only the identity keeps the
+                // comparison strict
+                match (field.transform, op) {
+                    (PartitionTransform::Identity, op) => op,
+                    (_, Operator::Lt | Operator::LtEq) => Operator::LtEq,
+                    _ => Operator::GtEq,
+                }
+            }
+            _ => return None,
+        };
+        let values = values
+            .iter()
+            .map(|value| {
+                let value = value.cast_to(&source_type).ok()?;
+                let transformed = field.transform.apply(&value.to_array().ok()?).ok()?;
+                ScalarValue::try_from_array(&transformed, 0).ok()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            field_idx,
+            output_type,
+            op,
+            values,
+        })
+    }
+
+    fn may_match(&self, partition_value: Option<&str>) -> bool {
+        // Comparisons with null are never true
+        let Some(partition_value) = partition_value else {
+            return false;
+        };
+        let Ok(partition_value) =
+            ScalarValue::try_from_string(partition_value.to_string(), &self.output_type)
+        else {
+            return true;
+        };
+        self.values.iter().any(|value| {
+            if value.is_null() {
+                return false;
+            }
+            let Some(ordering) = partition_value.partial_cmp(value) else {
+                return true;
+            };
+            match self.op {
+                Operator::Eq => ordering.is_eq(),
+                Operator::Lt => ordering.is_lt(),
+                Operator::LtEq => ordering.is_le(),
+                Operator::Gt => ordering.is_gt(),
+                Operator::GtEq => ordering.is_ge(),
+                _ => true,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::{Date32Array, Int32Array, StringArray};
+    use arrow_schema::{Field as ArrowField, Schema as ArrowSchema};
+    use datafusion::logical_expr::{col, lit};
+
+    fn test_schema() -> Schema {
+        Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, false),
+            ArrowField::new("region", DataType::Utf8, true),
+            ArrowField::new("date", DataType::Date32, false),
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_transform_round_trip() {
+        for transform in [
+            PartitionTransform::Identity,
+            PartitionTransform::Bucket(16),
+            PartitionTransform::Truncate(4),
+            PartitionTransform::Day,
+            PartitionTransform::Month,
+        ] {
+            assert_eq!(
+                transform.to_string().parse::<PartitionTransform>().unwrap(),
+                transform
+            );
+        }
+        assert!("bucket[0]".parse::<PartitionTransform>().is_err());
+        assert!("bucket".parse::<PartitionTransform>().is_err());
+        assert!("year".parse::<PartitionTransform>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = test_schema();
+        assert!(PartitionSpec::new(vec![PartitionField::day("date")])
+            .validate(&schema)
+            .is_ok());
+        assert!(PartitionSpec::new(vec![PartitionField::day("region")])
+            .validate(&schema)
+            .is_err());
+        assert!(
+            PartitionSpec::new(vec![PartitionField::identity("missing")])
+                .validate(&schema)
+                .is_err()
+        );
+        assert!(PartitionSpec::new(vec![]).validate(&schema).is_err());
+    }
+
+    #[test]
+    fn test_split_batch() {
+        let schema = test_schema();
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::from(&schema)),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 12, 5, 17, -3])),
+                Arc::new(StringArray::from(vec![
+                    Some("us-east"),
+                    Some("eu-west"),
+                    Some("us-west"),
+                    None,
+                    Some("eu-central"),
+                ])),
+                // 2024-01-01, 2024-01-31, 2024-02-01, ...
+                Arc::new(Date32Array::from(vec![19723, 19753, 19754, 19754, 19723])),
+            ],
+        )
+        .unwrap();
+
+        let spec = PartitionSpec::new(vec![
+            PartitionField::truncate("region", 2),
+            PartitionField::month("date"),
+        ]);
+        let partitions = spec.split_batch(&batch).unwrap();
+        let partitions = partitions
+            .iter()
+            .map(|(values, batch)| {
+                let ids = batch["id"].as_primitive::<arrow_array::types::Int32Type>();
+                (values.clone(), ids.values().to_vec())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            partitions,
+            vec![
+                (
+                    vec![Some("us".to_string()), Some("648".to_string())],
+                    vec![1]
+                ),
+                (
+                    vec![Some("eu".to_string()), Some("648".to_string())],
+                    vec![12, -3]
+                ),
+                (
+                    vec![Some("us".to_string()), Some("649".to_string())],
+                    vec![5]
+                ),
+                (vec![None, Some("649".to_string())], vec![17]),
+            ]
+        );
+
+        let spec = PartitionSpec::new(vec![PartitionField::truncate("id", 10)]);
+        let values = spec
+            .split_batch(&batch)
+            .unwrap()
+            .into_iter()
+            .map(|(values, _)| values[0].clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["0", "10", "-10"]);
+
+        let spec = PartitionSpec::new(vec![PartitionField::bucket("id", 4)]);
+        for (values, batch) in spec.split_batch(&batch).unwrap() {
+            let bucket = values[0].as_ref().unwrap().parse::<u32>().unwrap();
+            assert!(bucket < 4);
+            let ids = batch["id"].as_primitive::<arrow_array::types::Int32Type>();
+            for id in ids.values() {
+                assert_eq!(fnv1a(&(*id as i64).to_le_bytes()) % 4, bucket as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_prune() {
+        let schema = test_schema();
+        let spec = PartitionSpec::new(vec![
+            PartitionField::identity("region"),
+            PartitionField::day("date"),
+            PartitionField::bucket("id", 8),
+        ]);
+        let bucket_of = |id: i64| (fnv1a(&id.to_le_bytes()) % 8).to_string();
+        let fragment = |id: u64, region: Option<&str>, day: i32, bucket: String| {
+            let mut fragment = Fragment::new(id);
+            fragment.partition_values = vec![
+                region.map(String::from),
+                Some(day.to_string()),
+                Some(bucket),
+            ];
+            fragment
+        };
+        let fragments = vec![
+            fragment(0, Some("us"), 19723, bucket_of(1)),
+            fragment(1, Some("eu"), 19723, bucket_of(2)),
+            fragment(2, Some("us"), 19724, bucket_of(3)),
+            fragment(3, None, 19725, bucket_of(4)),
+            // Written before the dataset was partitioned
+            Fragment::new(4),
+        ];
+        let prune = |filter: Expr| {
+            spec.prune(&schema, &filter, &fragments)
+                .iter()
+                .map(|f| f.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(prune(col("region").eq(lit("us"))), vec![0, 2, 4]);
+        assert_eq!(prune(lit("eu").eq(col("region"))), vec![1, 4]);
+        assert_eq!(
+            prune(col("region").in_list(vec![lit("eu"), lit("ap")], false)),
+            vec![1, 4]
+        );
+        // 2024-01-02
+        let date = |days: i32| lit(ScalarValue::Date32(Some(days)));
+        assert_eq!(prune(col("date").gt_eq(date(19724))), vec![2, 3, 4]);
+        assert_eq!(prune(col("date").lt(date(19724))), vec![0, 1, 4]);
+        assert_eq!(
+            prune(col("date").between(date(19724), date(19724))),
+            vec![2, 4]
+        );
+        assert_eq!(
+            prune(col("region").eq(lit("us")).and(col("date").eq(date(19723)))),
+            vec![0, 4]
+        );
+        // Other fragments may share the bucket of id 3
+        let same_bucket = fragments
+            .iter()
+            .filter(|f| {
+                f.partition_values.is_empty() || f.partition_values[2] == Some(bucket_of(3))
+            })
+            .map(|f| f.id)
+            .collect::<Vec<_>>();
+        assert!(same_bucket.contains(&2));
+        assert_eq!(prune(col("id").eq(lit(3))), same_bucket);
+        // Bucketing doesn't preserve order and OR is not used
+        assert_eq!(prune(col("id").lt(lit(3))), vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            prune(col("region").eq(lit("us")).or(col("region").eq(lit("eu")))),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+}
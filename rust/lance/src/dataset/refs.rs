@@ -11,15 +11,23 @@ use object_store::path::Path;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::dataset::{write_manifest_file, ManifestWriteConfig};
+use crate::utils::temporal::utc_now;
 use crate::{Error, Result};
+use lance_table::io::manifest::{read_manifest, read_manifest_indexes};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
+/// The name of the implicit branch that holds the dataset's primary history.
+pub const MAIN_BRANCH: &str = "main";
+
 /// Lance Ref
 #[derive(Debug, Clone)]
 pub enum Ref {
     Version(u64),
     Tag(String),
+    /// The latest version on a branch
+    Branch(String),
 }
 
 impl From<u64> for Ref {
@@ -252,6 +260,299 @@ impl TagContents {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct Branches {
+    object_store: Arc<ObjectStore>,
+    commit_handler: Arc<dyn CommitHandler>,
+    base: Path,
+}
+
+impl Branches {
+    pub fn new(
+        object_store: Arc<ObjectStore>,
+        commit_handler: Arc<dyn CommitHandler>,
+        base: Path,
+    ) -> Self {
+        Self {
+            object_store,
+            commit_handler,
+            base,
+        }
+    }
+
+    async fn fetch_branches(&self) -> Result<Vec<(String, BranchContents)>> {
+        let base_path = base_branches_path(&self.base);
+        let branch_files = self.object_store().read_dir(base_path).await?;
+
+        let branch_names: Vec<String> = branch_files
+            .iter()
+            .filter_map(|name| name.strip_suffix(".json"))
+            .map(|name| name.to_string())
+            .collect_vec();
+
+        futures::stream::iter(branch_names)
+            .map(|branch_name| async move {
+                let contents = BranchContents::from_path(
+                    &branch_contents_path(&self.base, &branch_name),
+                    self.object_store(),
+                )
+                .await?;
+                Ok((branch_name, contents))
+            })
+            .buffer_unordered(10)
+            .try_collect()
+            .await
+    }
+
+    pub async fn list(&self) -> Result<HashMap<String, BranchContents>> {
+        self.fetch_branches()
+            .await
+            .map(|branches| branches.into_iter().collect())
+    }
+
+    pub async fn get(&self, branch: &str) -> Result<BranchContents> {
+        check_valid_branch(branch)?;
+
+        let branch_file = branch_contents_path(&self.base, branch);
+
+        if !self.object_store().exists(&branch_file).await? {
+            return Err(Error::RefNotFound {
+                message: format!("branch {} does not exist", branch),
+            });
+        }
+
+        BranchContents::from_path(&branch_file, self.object_store()).await
+    }
+
+    /// Get the version number of the latest commit on the branch.
+    pub async fn get_version(&self, branch: &str) -> Result<u64> {
+        self.get(branch).await?;
+        Ok(self
+            .commit_handler
+            .resolve_latest_location(&branch_path(&self.base, branch), &self.object_store)
+            .await?
+            .version)
+    }
+
+    /// Create a new branch whose history starts at `from`.
+    ///
+    /// `from` may be a version of the main branch, a tag, or the latest version
+    /// of another branch. The manifest of that version is committed as the first
+    /// version of the new branch, so the branch shares all of its data files
+    /// with its parent.
+    pub async fn create(&mut self, branch: &str, from: impl Into<Ref>) -> Result<()> {
+        check_valid_branch(branch)?;
+
+        let branch_file = branch_contents_path(&self.base, branch);
+
+        if self.object_store().exists(&branch_file).await? {
+            return Err(Error::RefConflict {
+                message: format!("branch {} already exists", branch),
+            });
+        }
+
+        let (parent_branch, parent_version) = match from.into() {
+            Ref::Version(version) => (None, version),
+            Ref::Tag(tag) => {
+                let tags = Tags::new(
+                    self.object_store.clone(),
+                    self.commit_handler.clone(),
+                    self.base.clone(),
+                );
+                (None, tags.get_version(&tag).await?)
+            }
+            Ref::Branch(parent) if parent == MAIN_BRANCH => {
+                let location = self
+                    .commit_handler
+                    .resolve_latest_location(&self.base, &self.object_store)
+                    .await?;
+                (None, location.version)
+            }
+            Ref::Branch(parent) => {
+                let version = self.get_version(&parent).await?;
+                (Some(parent), version)
+            }
+        };
+
+        let parent_base = match &parent_branch {
+            Some(parent) => branch_path(&self.base, parent),
+            None => self.base.clone(),
+        };
+        let parent_location = self
+            .commit_handler
+            .resolve_version_location(&parent_base, parent_version, &self.object_store.inner)
+            .await?;
+
+        if !self.object_store().exists(&parent_location.path).await? {
+            return Err(Error::VersionNotFound {
+                message: format!("version {} does not exist", parent_version),
+            });
+        }
+
+        let mut manifest = read_manifest(
+            self.object_store(),
+            &parent_location.path,
+            parent_location.size,
+        )
+        .await?;
+        let indices =
+            read_manifest_indexes(self.object_store(), &parent_location, &manifest).await?;
+
+        // The first manifest on the branch is a copy of the parent version, written
+        // with the same naming scheme under the branch's own prefix.
+        write_manifest_file(
+            self.object_store(),
+            self.commit_handler.as_ref(),
+            &branch_path(&self.base, branch),
+            &mut manifest,
+            if indices.is_empty() {
+                None
+            } else {
+                Some(indices)
+            },
+            &ManifestWriteConfig::default(),
+            parent_location.naming_scheme,
+        )
+        .await?;
+
+        let branch_contents = BranchContents {
+            parent_branch,
+            parent_version,
+            create_at: utc_now().timestamp() as u64,
+            merged_version: None,
+        };
+
+        self.object_store()
+            .put(
+                &branch_file,
+                serde_json::to_string_pretty(&branch_contents)?.as_bytes(),
+            )
+            .await
+            .map(|_| ())
+    }
+
+    /// Delete a branch and all of the manifests committed to it.
+    ///
+    /// Data files written on the branch are left in place and will be removed
+    /// by the next call to `cleanup_old_versions`.
+    pub async fn delete(&mut self, branch: &str) -> Result<()> {
+        check_valid_branch(branch)?;
+
+        let branch_file = branch_contents_path(&self.base, branch);
+
+        if !self.object_store().exists(&branch_file).await? {
+            return Err(Error::RefNotFound {
+                message: format!("branch {} does not exist", branch),
+            });
+        }
+
+        let children = self
+            .list()
+            .await?
+            .into_iter()
+            .filter(|(_, contents)| contents.parent_branch.as_deref() == Some(branch))
+            .map(|(name, _)| name)
+            .sorted()
+            .collect_vec();
+        if !children.is_empty() {
+            return Err(Error::RefConflict {
+                message: format!(
+                    "branch {} cannot be deleted while branches {:?} are based on it",
+                    branch, children
+                ),
+            });
+        }
+
+        self.commit_handler
+            .delete(&branch_path(&self.base, branch))
+            .await?;
+        self.object_store()
+            .remove_dir_all(branch_path(&self.base, branch))
+            .await?;
+        self.object_store().delete(&branch_file).await
+    }
+
+    /// Record that the branch was merged into its parent, producing `version`.
+    pub(crate) async fn record_merge(&self, branch: &str, version: u64) -> Result<()> {
+        let mut branch_contents = self.get(branch).await?;
+        branch_contents.merged_version = Some(version);
+
+        self.object_store()
+            .put(
+                &branch_contents_path(&self.base, branch),
+                serde_json::to_string_pretty(&branch_contents)?.as_bytes(),
+            )
+            .await
+            .map(|_| ())
+    }
+
+    pub(crate) fn object_store(&self) -> &ObjectStore {
+        &self.object_store
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchContents {
+    /// The branch this branch was created from, or `None` for the main branch.
+    pub parent_branch: Option<String>,
+    /// The version of the parent branch this branch was created from.
+    pub parent_version: u64,
+    /// Creation time, in seconds since the Unix epoch.
+    pub create_at: u64,
+    /// The version of the parent branch created by merging this branch into it,
+    /// if the branch has been merged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_version: Option<u64>,
+}
+
+pub fn base_branches_path(base_path: &Path) -> Path {
+    base_path.child("_refs").child("branches")
+}
+
+/// The root under which the manifests of a branch are stored.
+pub fn branch_path(base_path: &Path, branch: &str) -> Path {
+    base_branches_path(base_path).child(branch)
+}
+
+pub fn branch_contents_path(base_path: &Path, branch: &str) -> Path {
+    base_branches_path(base_path).child(format!("{}.json", branch))
+}
+
+impl BranchContents {
+    pub async fn from_path(path: &Path, object_store: &ObjectStore) -> Result<Self> {
+        let branch_reader = object_store.open(path).await?;
+        let branch_bytes = branch_reader
+            .get_range(Range {
+                start: 0,
+                end: branch_reader.size().await?,
+            })
+            .await?;
+        Ok(serde_json::from_str(
+            String::from_utf8(branch_bytes.to_vec()).unwrap().as_str(),
+        )?)
+    }
+}
+
+fn check_valid_branch(branch: &str) -> Result<()> {
+    check_valid_ref(branch)?;
+
+    if branch == MAIN_BRANCH {
+        return Err(Error::InvalidRef {
+            message: format!("Branch name {} is reserved", MAIN_BRANCH),
+        });
+    }
+
+    // Branch metadata is stored as `<branch>.json` alongside the branch directories
+    if branch.ends_with(".json") {
+        return Err(Error::InvalidRef {
+            message: "Branch name cannot end with .json".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 pub fn check_valid_ref(s: &str) -> Result<()> {
     if s.is_empty() {
         return Err(Error::InvalidRef {
@@ -346,4 +647,12 @@ mod tests {
             "Ref is invalid: Ref"
         );
     }
+
+    #[rstest]
+    fn test_err_branch(#[values("main", "feature.json", "ref.lock")] branch: &str) {
+        assert_contains!(
+            check_valid_branch(branch).err().unwrap().to_string(),
+            "Ref is invalid: "
+        );
+    }
 }
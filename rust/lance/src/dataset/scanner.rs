@@ -6,18 +6,16 @@ use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
-use pyo3::prelude::*;
-use pyo3::types::PyModule;
 
 use arrow::array::AsArray;
-use arrow_array::{Array, Float32Array, Int64Array, RecordBatch};
+use arrow_array::{Array, ArrayRef, FixedSizeListArray, Float32Array, Int64Array, RecordBatch};
 use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema, SchemaRef, SortOptions};
 use arrow_select::concat::concat_batches;
 use async_recursion::async_recursion;
 use datafusion::common::{DFSchema, SchemaExt};
 use datafusion::functions_aggregate;
 use datafusion::functions_aggregate::count::count_udaf;
-use datafusion::logical_expr::{col, lit, Expr};
+use datafusion::logical_expr::{col, lit, AggregateUDF, Expr};
 use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
 use datafusion::physical_plan::expressions;
@@ -34,7 +32,10 @@ use datafusion::physical_plan::{
 };
 use datafusion::scalar::ScalarValue;
 use datafusion_expr::execution_props::ExecutionProps;
-use datafusion_physical_expr::{aggregate::AggregateExprBuilder, expressions::Column};
+use datafusion_physical_expr::{
+    aggregate::{AggregateExprBuilder, AggregateFunctionExpr},
+    expressions::Column,
+};
 use datafusion_physical_expr::{create_physical_expr, LexOrdering, Partitioning, PhysicalExpr};
 use datafusion_physical_plan::{empty::EmptyExec, joins::HashJoinExec};
 use futures::future::BoxFuture;
@@ -47,6 +48,7 @@ use lance_core::utils::tokio::get_num_compute_intensive_cpus;
 use lance_core::{ROW_ADDR, ROW_ADDR_FIELD, ROW_ID, ROW_ID_FIELD};
 use lance_datafusion::exec::{analyze_plan, execute_plan, LanceExecutionOptions};
 use lance_datafusion::projection::ProjectionPlan;
+use lance_encoding::statistics::MAX_PAGE_BOUND_BYTES;
 use lance_index::scalar::expression::PlannerIndexExt;
 use lance_index::scalar::inverted::query::{
     fill_fts_query_column, FtsQuery, FtsSearchParams, MatchQuery,
@@ -63,6 +65,7 @@ use lance_table::format::{Fragment, Index};
 use roaring::RoaringBitmap;
 use tracing::{info_span, instrument, Span};
 
+use super::fragment::FragReadConfig;
 use super::Dataset;
 use crate::index::scalar::detect_scalar_index_type;
 use crate::index::vector::utils::{get_vector_dim, get_vector_type};
@@ -70,26 +73,24 @@ use crate::index::DatasetIndexInternalExt;
 use crate::io::exec::fts::{BoostQueryExec, FlatMatchQueryExec, MatchQueryExec, PhraseQueryExec};
 use crate::io::exec::knn::MultivectorScoringExec;
 use crate::io::exec::scalar_index::{MaterializeIndexExec, ScalarIndexExec};
-use crate::io::exec::{get_physical_optimizer, LanceFilterExec, LanceScanConfig};
 use crate::io::exec::{
-    knn::new_knn_exec, project, AddRowAddrExec, FilterPlan, KNNVectorDistanceExec,
-    LancePushdownScanExec, LanceScanExec, Planner, PreFilterSource, ScanConfig, TakeExec,
+    get_physical_optimizer, HybridFusionExec, LanceFilterExec, LanceScanConfig,
+    PrecomputedAggregateExec,
+};
+use crate::io::exec::{
+    knn::{new_batch_knn_exec, new_knn_exec},
+    project, AddRowAddrExec, FilterPlan, KNNVectorDistanceExec, LancePushdownScanExec,
+    LanceScanExec, Planner, PreFilterSource, ScanConfig, TakeExec,
 };
 use crate::{datatypes::Schema, io::exec::fts::BooleanQueryExec};
 use crate::{Error, Result};
 use snafu::location;
 
-use prost::Message;
-use lance_table::format::pb::CagraIndexMetaDataDetails;
-
+pub use crate::io::exec::{Reranker, QUERY_INDEX_COLUMN, RELEVANCE_SCORE_COL};
 pub use lance_datafusion::exec::{ExecutionStatsCallback, ExecutionSummaryCounts};
 #[cfg(feature = "substrait")]
 use lance_datafusion::substrait::parse_substrait;
 
-use arrow_schema::Field as OtherField;
-use arrow_array::ArrayRef;
-use arrow_array::StringArray;
-
 pub(crate) const BATCH_SIZE_FALLBACK: usize = 8192;
 // For backwards compatibility / historical reasons we re-calculate the default batch size
 // on each call
@@ -160,6 +161,144 @@ impl ColumnOrdering {
     }
 }
 
+/// An aggregate function that can be computed by [`Scanner::aggregate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+impl AggregateFunction {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Avg => "avg",
+        }
+    }
+
+    fn udaf(&self) -> Arc<AggregateUDF> {
+        match self {
+            Self::Count => count_udaf(),
+            Self::Sum => functions_aggregate::sum::sum_udaf(),
+            Self::Min => functions_aggregate::min_max::min_udaf(),
+            Self::Max => functions_aggregate::min_max::max_udaf(),
+            Self::Avg => functions_aggregate::average::avg_udaf(),
+        }
+    }
+}
+
+/// A single aggregate computed by [`Scanner::aggregate`]
+#[derive(Debug, Clone)]
+pub struct AggregateExpr {
+    pub function: AggregateFunction,
+    /// The column to aggregate, None for `COUNT(*)`
+    pub column: Option<String>,
+    /// The name of the output column, defaults to e.g. `sum(x)`
+    pub alias: Option<String>,
+}
+
+impl AggregateExpr {
+    fn new(function: AggregateFunction, column: Option<String>) -> Self {
+        Self {
+            function,
+            column,
+            alias: None,
+        }
+    }
+
+    /// The number of rows
+    pub fn count_star() -> Self {
+        Self::new(AggregateFunction::Count, None)
+    }
+
+    /// The number of non-null values in `column`
+    pub fn count(column: impl Into<String>) -> Self {
+        Self::new(AggregateFunction::Count, Some(column.into()))
+    }
+
+    pub fn sum(column: impl Into<String>) -> Self {
+        Self::new(AggregateFunction::Sum, Some(column.into()))
+    }
+
+    pub fn min(column: impl Into<String>) -> Self {
+        Self::new(AggregateFunction::Min, Some(column.into()))
+    }
+
+    pub fn max(column: impl Into<String>) -> Self {
+        Self::new(AggregateFunction::Max, Some(column.into()))
+    }
+
+    pub fn avg(column: impl Into<String>) -> Self {
+        Self::new(AggregateFunction::Avg, Some(column.into()))
+    }
+
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// The name of the output column
+    pub fn output_name(&self) -> String {
+        self.alias.clone().unwrap_or_else(|| {
+            format!(
+                "{}({})",
+                self.function.name(),
+                self.column.as_deref().unwrap_or("*")
+            )
+        })
+    }
+}
+
+/// Column statistics gathered from the page statistics of every fragment
+struct ColumnPageStats {
+    non_null_count: u64,
+    /// The min and max non-null values (null if every value is null), None if unknown
+    bounds: Option<(ScalarValue, ScalarValue)>,
+}
+
+/// True if a page minimum may have been truncated to a prefix of the actual minimum
+fn is_truncated_page_bound(bound: &ScalarValue) -> bool {
+    let len = match bound {
+        ScalarValue::Utf8(Some(value)) | ScalarValue::LargeUtf8(Some(value)) => value.len(),
+        ScalarValue::Binary(Some(value)) | ScalarValue::LargeBinary(Some(value)) => value.len(),
+        _ => return false,
+    };
+    len >= MAX_PAGE_BOUND_BYTES
+}
+
+/// The aggregation requested by [`Scanner::aggregate`]
+struct Aggregation {
+    group_by: Vec<String>,
+    aggregates: Vec<AggregateExpr>,
+}
+
+fn aggregate_exprs(
+    aggregates: &[AggregateExpr],
+    schema: &SchemaRef,
+) -> Result<Vec<Arc<AggregateFunctionExpr>>> {
+    aggregates
+        .iter()
+        .map(|aggregate| {
+            let input = match &aggregate.column {
+                Some(column) => expressions::col(column, schema.as_ref())?,
+                // Datafusion interprets COUNT(*) as COUNT(1)
+                None => Arc::new(Literal::new(ScalarValue::UInt8(Some(1)))),
+            };
+            let expr = AggregateExprBuilder::new(aggregate.function.udaf(), vec![input])
+                .schema(schema.clone())
+                .alias(aggregate.output_name())
+                .build()?;
+            Ok(Arc::new(expr))
+        })
+        .collect()
+}
+
 /// Materialization style for the scanner
 ///
 /// This only affects columns that are not used in a filter
@@ -313,6 +452,9 @@ pub struct Scanner {
 
     nearest: Option<Query>,
 
+    /// The query vectors of a batched nearest neighbor search, see [`Self::nearest_batch`]
+    nearest_batch: Option<Vec<ArrayRef>>,
+
     /// If false, do not use any scalar indices for the scan
     ///
     /// This can be used to pick a more efficient plan for certain queries where
@@ -358,6 +500,12 @@ pub struct Scanner {
     /// Mainly, if the result is returned strictly according to the batch_size,
     /// batching and waiting are required, and the performance will decrease.
     strict_batch_size: bool,
+
+    /// If set, the aggregates to compute over the scanned rows
+    aggregation: Option<Aggregation>,
+
+    /// How the results are ranked when there is both a nearest and a full text query
+    reranker: Reranker,
 }
 
 fn escape_column_name(name: &str) -> String {
@@ -389,6 +537,7 @@ impl Scanner {
             offset: None,
             ordering: None,
             nearest: None,
+            nearest_batch: None,
             use_stats: true,
             with_row_id: false,
             with_row_address: false,
@@ -399,6 +548,8 @@ impl Scanner {
             include_deleted_rows: false,
             scan_stats_callback: None,
             strict_batch_size: false,
+            aggregation: None,
+            reranker: Reranker::default(),
         }
     }
 
@@ -565,6 +716,20 @@ impl Scanner {
         Ok(self)
     }
 
+    /// Set how results are ranked in a hybrid search.
+    ///
+    /// A hybrid search is run when both [`Self::nearest`] and [`Self::full_text_search`]
+    /// are set.  Both searches are run with the same filter and their results are fused
+    /// into a single ranking, which limit / offset are applied to.  The results have the
+    /// `_distance` of the vector search, the `_score` of the text search (either of which
+    /// is null if the row wasn't found by that search) and the fused `_relevance_score`.
+    ///
+    /// Defaults to reciprocal rank fusion.
+    pub fn reranker(&mut self, reranker: Reranker) -> &mut Self {
+        self.reranker = reranker;
+        self
+    }
+
     /// Set a filter using a Substrait ExtendedExpression message
     ///
     /// The message must contain exactly one expression and that expression
@@ -820,6 +985,49 @@ impl Scanner {
             metric_type: MetricType::L2,
             use_index: true,
         });
+        self.nearest_batch = None;
+        Ok(self)
+    }
+
+    /// Find the k-nearest neighbors of each of the vectors in `queries`.
+    ///
+    /// The queries are searched together: each index partition is loaded once for all
+    /// of the queries that probe it and the prefilter is only evaluated once.  Each
+    /// result row has a `query_index` column with the position of its query in `queries`,
+    /// and the results are ordered by `query_index` and then by distance.
+    ///
+    /// The column must have a vector index.  The search options (e.g. [Self::nprobs],
+    /// [Self::distance_metric]) apply to all of the queries, but each query only searches
+    /// [Self::minimum_nprobes] partitions and refinement is not supported.
+    pub fn nearest_batch(
+        &mut self,
+        column: &str,
+        queries: &FixedSizeListArray,
+        k: usize,
+    ) -> Result<&mut Self> {
+        if queries.is_empty() {
+            return Err(Error::invalid_input(
+                "Batched nearest neighbor search must have at least one query".to_string(),
+                location!(),
+            ));
+        }
+        let (vector_type, _) = get_vector_type(self.dataset.schema(), column)?;
+        if matches!(vector_type, DataType::List(_)) {
+            return Err(Error::invalid_input(
+                format!(
+                    "Batched nearest neighbor search is not supported on multivector column {}",
+                    column
+                ),
+                location!(),
+            ));
+        }
+
+        let mut keys = Vec::with_capacity(queries.len());
+        for i in 0..queries.len() {
+            self.nearest(column, &queries.value(i), k)?;
+            keys.push(self.nearest.as_ref().unwrap().key.clone());
+        }
+        self.nearest_batch = Some(keys);
         Ok(self)
     }
 
@@ -954,6 +1162,73 @@ impl Scanner {
         Ok(self)
     }
 
+    /// Compute aggregates over the scanned rows, grouped by the `group_by` columns.
+    ///
+    /// The output has one column for each group by column followed by one column for each
+    /// aggregate, and one row per group (exactly one row if `group_by` is empty).  The
+    /// aggregates are computed after any filter, search or limit has been applied.  This
+    /// replaces the projection with the columns the aggregates need.
+    ///
+    /// If there is no filter, search, limit or grouping then `COUNT(*)` is answered from
+    /// the fragment metadata and `MIN` / `MAX` from a btree or bitmap index on the column
+    /// (as long as it covers every fragment) without scanning any data.  Otherwise `MIN`,
+    /// `MAX` and `COUNT(column)` are answered from the page statistics of 2.1+ files.
+    /// Indices and page statistics are not used once rows have been deleted.  Use
+    /// [`Self::explain_plan`] to see which aggregates were.
+    pub fn aggregate(
+        &mut self,
+        group_by: &[impl AsRef<str>],
+        aggregates: Vec<AggregateExpr>,
+    ) -> Result<&mut Self> {
+        if aggregates.is_empty() {
+            return Err(Error::invalid_input(
+                "at least one aggregate must be given",
+                location!(),
+            ));
+        }
+        if let Some(aggregate) = aggregates.iter().find(|aggregate| {
+            aggregate.column.is_none() && aggregate.function != AggregateFunction::Count
+        }) {
+            return Err(Error::invalid_input(
+                format!("{} requires a column", aggregate.function.name()),
+                location!(),
+            ));
+        }
+
+        let group_by = group_by
+            .iter()
+            .map(|column| column.as_ref().to_string())
+            .collect::<Vec<_>>();
+        let mut columns: Vec<&str> = Vec::new();
+        for column in group_by.iter().map(String::as_str).chain(
+            aggregates
+                .iter()
+                .filter_map(|aggregate| aggregate.column.as_deref()),
+        ) {
+            self.dataset
+                .schema()
+                .field(column)
+                .ok_or(Error::invalid_input(
+                    format!("Column {} not found", column),
+                    location!(),
+                ))?;
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        self.project(&columns)?;
+        if columns.is_empty() {
+            // COUNT(*) on its own still needs something to scan
+            self.with_row_id = true;
+        }
+
+        self.aggregation = Some(Aggregation {
+            group_by,
+            aggregates,
+        });
+        Ok(self)
+    }
+
     /// Set whether to use the index if available
     pub fn use_index(&mut self, use_index: bool) -> &mut Self {
         if let Some(q) = self.nearest.as_mut() {
@@ -1006,9 +1281,17 @@ impl Scanner {
         }
     }
 
+    fn is_hybrid_search(&self) -> bool {
+        self.nearest.is_some() && self.full_text_query.is_some()
+    }
+
     fn get_extra_columns(&self, force_row_id: bool) -> Vec<ArrowField> {
         let mut extra_columns = vec![];
 
+        if self.nearest_batch.is_some() {
+            extra_columns.push(ArrowField::new(QUERY_INDEX_COLUMN, DataType::UInt32, false));
+        }
+
         if self.nearest.as_ref().is_some() {
             extra_columns.push(ArrowField::new(DIST_COL, DataType::Float32, true));
         };
@@ -1017,6 +1300,14 @@ impl Scanner {
             extra_columns.push(ArrowField::new(SCORE_COL, DataType::Float32, true));
         }
 
+        if self.is_hybrid_search() {
+            extra_columns.push(ArrowField::new(
+                RELEVANCE_SCORE_COL,
+                DataType::Float32,
+                false,
+            ));
+        }
+
         if self.with_row_id || force_row_id {
             extra_columns.push(ROW_ID_FIELD.clone());
         }
@@ -1082,6 +1373,15 @@ impl Scanner {
                 .as_ref(),
         );
 
+        if self.nearest_batch.is_some()
+            && output_expr
+                .iter()
+                .all(|(_, name)| name != QUERY_INDEX_COLUMN)
+        {
+            let query_index_expr = expressions::col(QUERY_INDEX_COLUMN, &physical_schema)?;
+            output_expr.push((query_index_expr, QUERY_INDEX_COLUMN.to_string()));
+        }
+
         // distance goes before the row_id column
         if self.nearest.is_some() && output_expr.iter().all(|(_, name)| name != DIST_COL) {
             let vector_expr = expressions::col(DIST_COL, &physical_schema)?;
@@ -1093,6 +1393,15 @@ impl Scanner {
             output_expr.push((score_expr, SCORE_COL.to_string()));
         }
 
+        if self.is_hybrid_search()
+            && output_expr
+                .iter()
+                .all(|(_, name)| name != RELEVANCE_SCORE_COL)
+        {
+            let relevance_expr = expressions::col(RELEVANCE_SCORE_COL, &physical_schema)?;
+            output_expr.push((relevance_expr, RELEVANCE_SCORE_COL.to_string()));
+        }
+
         if self.with_row_id && output_expr.iter().all(|(_, name)| name != ROW_ID) {
             let row_id_expr = expressions::col(ROW_ID, &physical_schema)?;
             output_expr.push((row_id_expr, ROW_ID.to_string()));
@@ -1285,7 +1594,7 @@ impl Scanner {
             });
         }
 
-        Ok(self
+        let projection = self
             .dataset
             .empty_projection()
             // Start with the desired schema
@@ -1293,7 +1602,32 @@ impl Scanner {
             // Subtract columns that are expensive
             .subtract_predicate(|f| !self.is_early_field(f))
             // Add back columns that we need for filtering
-            .union_schema(&filter_schema))
+            .union_schema(&filter_schema);
+
+        // A shredded JSON field that is only partially loaded early is a struct of its
+        // shredded paths and can't be completed by a later take, so load all of it early
+        let partial_json = desired_schema
+            .fields
+            .iter()
+            .filter(|f| {
+                if !f.is_shredded_json() {
+                    return false;
+                }
+                let mut ids = Vec::new();
+                let mut stack = f.children.iter().collect::<Vec<_>>();
+                while let Some(child) = stack.pop() {
+                    ids.push(child.id);
+                    stack.extend(child.children.iter());
+                }
+                let loaded = ids
+                    .iter()
+                    .filter(|id| projection.contains_field_id(**id))
+                    .count();
+                loaded > 0 && loaded < ids.len()
+            })
+            .map(|f| f.name.clone())
+            .collect::<Vec<_>>();
+        projection.union_columns(partial_json, OnMissing::Error)
     }
 
     /// Create [`ExecutionPlan`] for Scan.
@@ -1379,6 +1713,12 @@ impl Scanner {
             });
         }
 
+        if let Some(aggregation) = &self.aggregation {
+            if let Some(plan) = self.precomputed_aggregate(aggregation).await? {
+                return Ok(plan);
+            }
+        }
+
         // Scalar indices are only used when prefiltering
         let use_scalar_index = self.use_scalar_index && (self.prefilter || self.nearest.is_none());
 
@@ -1442,35 +1782,6 @@ impl Scanner {
         };
         let mut use_limit_node = true;
 
-        // Check if it's a cagra index
-        if let Some(n) = &self.nearest {
-            let vector_col_name = &n.column;
-            let indicies_loaded = self.dataset.load_indices().await?;
-
-            let index_opt = indicies_loaded.iter().find(|idx| {
-                idx.fields.iter().any(|field| {
-                    self.dataset.schema().field_by_id(*field).unwrap().name == *vector_col_name
-                })
-            });
-            
-            if let Some(index) = index_opt {
-                if let Some(any) = &index.index_details {
-                    if any.type_url.contains("Cagra") {
-
-                        let cagra_schema = Arc::new(ArrowSchema::new(vec![
-                            OtherField::new("cagra_marker", DataType::Utf8, false),
-                        ]));
-
-                        let empty_exec = Arc::new(EmptyExec::new(cagra_schema));
-                        let exec: Arc<dyn ExecutionPlan> = empty_exec;
-
-                        return Ok(exec);
-                    }
-                }
-            }
-
-        }
-
         // Stage 1: source (either an (K|A)NN search, full text search or or a (full|indexed) scan)
         let mut plan: Arc<dyn ExecutionPlan> = match (&self.nearest, &self.full_text_query) {
             (Some(_), None) => {
@@ -1591,11 +1902,35 @@ impl Scanner {
                     }
                 }
             }
-            _ => {
-                return Err(Error::InvalidInput {
-                    source: "Cannot have both nearest and full text search".into(),
-                    location: location!(),
-                })
+            (Some(_), Some(query)) => {
+                if self.include_deleted_rows {
+                    return Err(Error::InvalidInput {
+                        source: "Cannot include deleted rows in a hybrid search".into(),
+                        location: location!(),
+                    });
+                }
+                if self.nearest_batch.is_some() {
+                    return Err(Error::NotSupported {
+                        source: "Batched nearest neighbor search cannot be combined with a full text search".into(),
+                        location: location!(),
+                    });
+                }
+
+                // The source is both searches, fused into a single ranking
+                let (knn, fts) = if self.prefilter {
+                    // If we are prefiltering then both searches take care of the filter
+                    let knn = self.knn(&filter_plan).await?;
+                    let fts = self.fts(&filter_plan, query).await?;
+                    filter_plan = FilterPlan::default();
+                    (knn, fts)
+                } else {
+                    filter_plan.make_refine_only();
+                    (
+                        self.knn(&FilterPlan::default()).await?,
+                        self.fts(&FilterPlan::default(), query).await?,
+                    )
+                };
+                Arc::new(HybridFusionExec::new(knn, fts, self.reranker))
             }
         };
 
@@ -1640,11 +1975,14 @@ impl Scanner {
                 .union_columns(ordering_columns, OnMissing::Error)?;
             // We haven't loaded the sort column yet so take it now
             plan = self.take(plan, projection_with_ordering)?;
+            // The ordering columns may be nested fields (e.g. `st.s1`)
+            let planner = Planner::new(plan.schema());
             let col_exprs = ordering
                 .iter()
                 .map(|col| {
+                    let expr = planner.parse_expr(&escape_column_name(&col.column_name))?;
                     Ok(PhysicalSortExpr {
-                        expr: expressions::col(&col.column_name, plan.schema().as_ref())?,
+                        expr: planner.create_physical_expr(&expr)?,
                         options: SortOptions {
                             descending: !col.ascending,
                             nulls_first: col.nulls_first,
@@ -1678,6 +2016,11 @@ impl Scanner {
         // Stage 7: final projection
         plan = Arc::new(DFProjectionExec::try_new(self.output_expr()?, plan)?);
 
+        // Stage 8: aggregate
+        if let Some(aggregation) = &self.aggregation {
+            plan = Self::aggregate_node(plan, aggregation)?;
+        }
+
         let optimizer = get_physical_optimizer();
         let options = Default::default();
         for rule in optimizer.rules {
@@ -1687,6 +2030,202 @@ impl Scanner {
         Ok(plan)
     }
 
+    fn aggregate_node(
+        plan: Arc<dyn ExecutionPlan>,
+        aggregation: &Aggregation,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = plan.schema();
+        let group_by = aggregation
+            .group_by
+            .iter()
+            .map(|column| Ok((expressions::col(column, schema.as_ref())?, column.clone())))
+            .collect::<Result<Vec<_>>>()?;
+        let aggregates = aggregate_exprs(&aggregation.aggregates, &schema)?;
+        let filters = vec![None; aggregates.len()];
+        Ok(Arc::new(AggregateExec::try_new(
+            AggregateMode::Single,
+            PhysicalGroupBy::new_single(group_by),
+            aggregates,
+            filters,
+            plan,
+            schema,
+        )?))
+    }
+
+    /// Answer the aggregation from metadata and indices, without scanning, if possible.
+    async fn precomputed_aggregate(
+        &self,
+        aggregation: &Aggregation,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        if !aggregation.group_by.is_empty()
+            || self.filter.is_some()
+            || self.full_text_query.is_some()
+            || self.nearest.is_some()
+            || self.limit.is_some()
+            || self.offset.is_some()
+            || self.fragments.is_some()
+            || self.include_deleted_rows
+            || !self.use_stats
+        {
+            return Ok(None);
+        }
+
+        // Indices still hold the values of deleted rows
+        let has_deletions = self
+            .dataset
+            .fragments()
+            .iter()
+            .any(|fragment| fragment.deletion_file.is_some());
+
+        let mut num_rows = None;
+        let mut values = Vec::with_capacity(aggregation.aggregates.len());
+        let mut sources = Vec::with_capacity(aggregation.aggregates.len());
+        for aggregate in &aggregation.aggregates {
+            let field = aggregate
+                .column
+                .as_deref()
+                .and_then(|column| self.dataset.schema().field(column));
+            match (aggregate.function, field) {
+                (AggregateFunction::Count, None) => {}
+                (AggregateFunction::Count, Some(field)) if !field.nullable => {}
+                // Page statistics include the values of deleted rows
+                (AggregateFunction::Count, Some(field)) if !has_deletions => {
+                    let Some(stats) = self.page_statistics_aggregate(field).await? else {
+                        return Ok(None);
+                    };
+                    values.push(ScalarValue::Int64(Some(stats.non_null_count as i64)));
+                    sources.push("page_statistics".to_string());
+                    continue;
+                }
+                (AggregateFunction::Min | AggregateFunction::Max, Some(field))
+                    if !has_deletions =>
+                {
+                    let column = aggregate.column.as_deref().unwrap();
+                    let (min, max) =
+                        if let Some((index_name, min, max)) = self.index_min_max(column).await? {
+                            sources.push(format!("index({})", index_name));
+                            (min, max)
+                        } else if let Some((min, max)) = self
+                            .page_statistics_aggregate(field)
+                            .await?
+                            .and_then(|stats| stats.bounds)
+                        {
+                            sources.push("page_statistics".to_string());
+                            (min, max)
+                        } else {
+                            return Ok(None);
+                        };
+                    values.push(if aggregate.function == AggregateFunction::Min {
+                        min
+                    } else {
+                        max
+                    });
+                    continue;
+                }
+                _ => return Ok(None),
+            }
+            if num_rows.is_none() {
+                num_rows = Some(self.dataset.count_all_rows().await? as i64);
+            }
+            values.push(ScalarValue::Int64(num_rows));
+            sources.push("metadata".to_string());
+        }
+
+        let input_schema = Arc::new(ArrowSchema::from(self.dataset.schema()));
+        let exprs = aggregate_exprs(&aggregation.aggregates, &input_schema)?;
+        let fields = exprs.iter().map(|expr| expr.field()).collect::<Vec<_>>();
+        let columns = values
+            .into_iter()
+            .zip(&fields)
+            .map(|(value, field)| Ok(value.cast_to(field.data_type())?.to_array()?))
+            .collect::<Result<Vec<_>>>()?;
+        let batch = RecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), columns)?;
+        Ok(Some(Arc::new(PrecomputedAggregateExec::new(
+            batch, sources,
+        ))))
+    }
+
+    /// The name, min and max of an index on `column` that covers every fragment
+    async fn index_min_max(
+        &self,
+        column: &str,
+    ) -> Result<Option<(String, ScalarValue, ScalarValue)>> {
+        let Some(index) = self
+            .dataset
+            .load_scalar_index(ScalarIndexCriteria::default().for_column(column))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let covers_all_fragments = index.fragment_bitmap.as_ref().is_some_and(|bitmap| {
+            self.dataset
+                .fragments()
+                .iter()
+                .all(|fragment| bitmap.contains(fragment.id as u32))
+        });
+        if !covers_all_fragments {
+            return Ok(None);
+        }
+        let scalar_index = self
+            .dataset
+            .open_scalar_index(column, &index.uuid.to_string(), &NoOpMetricsCollector)
+            .await?;
+        Ok(scalar_index
+            .min_max()
+            .await?
+            .map(|(min, max)| (index.name.clone(), min, max)))
+    }
+
+    /// The number of non-null values and the min and max of a field from page statistics
+    ///
+    /// Returns None if any page of any fragment does not have statistics.  Page statistics
+    /// are only recorded by 2.1+ writers and only for columns that are not nested.
+    async fn page_statistics_aggregate(&self, field: &Field) -> Result<Option<ColumnPageStats>> {
+        if field.data_type().is_nested() {
+            return Ok(None);
+        }
+        let projection = self.dataset.schema().project_by_ids(&[field.id], true);
+        let null = ScalarValue::try_from(&field.data_type())?;
+        let mut stats = ColumnPageStats {
+            non_null_count: 0,
+            bounds: Some((null.clone(), null)),
+        };
+        for fragment in self.dataset.get_fragments() {
+            let reader = fragment
+                .open(&projection, FragReadConfig::default())
+                .await?;
+            let Some(pages) = reader.page_statistics(field)? else {
+                return Ok(None);
+            };
+            for page in pages {
+                let num_values = page.rows.end - page.rows.start - page.null_count;
+                stats.non_null_count += num_values;
+                if num_values == 0 {
+                    continue;
+                }
+                stats.bounds = match (stats.bounds, page.bounds) {
+                    // Long strings and binaries have their minimum truncated to a prefix
+                    (_, Some((min, _))) if is_truncated_page_bound(&min) => None,
+                    (Some((cur_min, cur_max)), Some((min, max))) => Some((
+                        if cur_min.is_null() || min < cur_min {
+                            min
+                        } else {
+                            cur_min
+                        },
+                        if cur_max.is_null() || max > cur_max {
+                            max
+                        } else {
+                            cur_max
+                        },
+                    )),
+                    // The writer did not record bounds (e.g. because the values were too large)
+                    _ => None,
+                };
+            }
+        }
+        Ok(Some(stats))
+    }
+
     async fn fragments_covered_by_fts_leaf(
         &self,
         column: &str,
@@ -1809,7 +2348,15 @@ impl Scanner {
         let columns = query.columns();
         let mut params = query.params();
         if params.limit.is_none() {
-            params = params.with_limit(self.limit.map(|l| l as usize));
+            let limit = match &self.nearest {
+                // In a hybrid search limit / offset apply to the fused results, so fetch at
+                // least as many candidates as that needs
+                Some(q) => {
+                    Some(q.k.max((self.limit.unwrap_or(0) + self.offset.unwrap_or(0)) as usize))
+                }
+                None => self.limit.map(|l| l as usize),
+            };
+            params = params.with_limit(limit);
         }
         let query = if columns.is_empty() {
             // the field is not specified,
@@ -2183,6 +2730,10 @@ impl Scanner {
             Arc::new(vec![])
         };
         if let Some(index) = indices.iter().find(|i| i.fields.contains(&column_id)) {
+            if let Some(keys) = self.nearest_batch.as_ref() {
+                return self.batch_knn(q, keys, index, filter_plan).await;
+            }
+
             // There is an index built for the column.
             // We will use the index.
             if matches!(q.refine_factor, Some(0)) {
@@ -2228,6 +2779,15 @@ impl Scanner {
             }
 
             Ok(knn_node)
+        } else if self.nearest_batch.is_some() {
+            Err(Error::NotSupported {
+                source: format!(
+                    "Batched nearest neighbor search requires a vector index on column {}",
+                    q.column
+                )
+                .into(),
+                location: location!(),
+            })
         } else {
             // No index found. use flat search.
             let mut columns = vec![q.column.clone()];
@@ -2257,6 +2817,50 @@ impl Scanner {
         }
     }
 
+    /// Search the vector index for a batch of queries, see [`Self::nearest_batch`]
+    async fn batch_knn(
+        &self,
+        q: &Query,
+        keys: &[ArrayRef],
+        index: &Index,
+        filter_plan: &FilterPlan,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if q.refine_factor.is_some() {
+            return Err(Error::NotSupported {
+                source: "Batched nearest neighbor search does not support refine".into(),
+                location: location!(),
+            });
+        }
+        if !self.fast_search
+            && !self
+                .dataset
+                .unindexed_fragments(&index.name)
+                .await?
+                .is_empty()
+        {
+            return Err(Error::NotSupported {
+                source: format!(
+                    "Batched nearest neighbor search requires the index {} to cover all of the data, optimize the index or enable fast_search",
+                    index.name
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+
+        let deltas = self.dataset.load_indices_by_name(&index.name).await?;
+        let prefilter_source = self
+            .prefilter_source(filter_plan, self.get_indexed_frags(&deltas))
+            .await?;
+        new_batch_knn_exec(
+            self.dataset.clone(),
+            &deltas,
+            q,
+            keys.to_vec(),
+            prefilter_source,
+        )
+    }
+
     /// Combine ANN results with KNN results for data appended after index creation
     async fn knn_combined(
         &self,
@@ -2265,52 +2869,6 @@ impl Scanner {
         mut knn_node: Arc<dyn ExecutionPlan>,
         filter_plan: &FilterPlan,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        // (NOTE): Attempting to add desearlization logic for cagra binary file
-        //deserialize the proto index.index_details, figure out the index or message then reroute it to cagra path
-        // if let Some(any) = &index.index_details {
-        //     if any.type_url.contains("Cagra") {
-
-        //         let details: CagraIndexMetaDataDetails = CagraIndexMetaDataDetails::decode(&*any.value)?;
-        //         eprintln!("details are {:?}", details);
-
-        //         // let field = Field::new_arrow("_name", LanceDataType::)
-        //         // let empty_schema = Arc::new(Schema::new(vec![
-        //         //     Field::new("_name", "Cagra".to_string(), false)
-        //         // ]));
-        //         let empty_schema = Arc::new(ArrowSchema::empty());
-        //         let empty_exec = Arc::new(EmptyExec::new(empty_schema));
-        //         let exec: Arc<dyn ExecutionPlan> = empty_exec;
-
-        //         // Python::with_gil::<_, std::result::Result<(), lance_core::Error>>(|py| {
-        //         //     let module = PyModule::import(py, "lance.cagra").map_err(|e| Error::Index {
-        //         //         message: format!("Failed to import lance.cagra: {}", e),
-        //         //         location: location!(),
-        //         //     })?;
-
-        //         //     let function = module.getattr("search_cagra").map_err(|e| Error::Index {
-        //         //         message: format!("Failed to get attribute {}", e),
-        //         //         location: location!(),
-        //         //     })?;
-
-        //         //     let query_arr = q.key.as_any().downcast_ref::<Float32Array>().unwrap();
-        //         //     let mut query_vec = Vec::new();
-        //         //     for i in 0..query_arr.len() {
-        //         //         query_vec.push(query_arr.value(i));
-        //         //     }
-
-        //         //     // TODO: Remove hard code of k
-        //         //     let result = function.call1((query_vec, "/workspace/cagra_index.bin", 384)).map_err(|e| Error::Index {
-        //         //         message: format!("Failed to call function {}", e),
-        //         //         location: location!(),
-        //         //     })?;
-
-        //         //     Ok(())
-        //         // });
-        //         return Ok(exec);
-        //     }
-        // } 
-
-
         // Check if we've created new versions since the index was built.
         let unindexed_fragments = self.dataset.unindexed_fragments(&index.name).await?;
         if !unindexed_fragments.is_empty() {
@@ -2426,16 +2984,12 @@ impl Scanner {
         index_expr: &ScalarIndexExpr,
     ) -> Result<(Vec<Fragment>, Vec<Fragment>)> {
         // Figure out which fragments are covered by ALL of the indices we are using
-        let fragments = if let Some(fragment) = self.fragments.as_ref() {
-            fragment.clone()
-        } else {
-            (**self.dataset.fragments()).clone()
-        };
+        let fragments = self.fragments_to_scan();
 
         let covered_frags = self.fragments_covered_by_index_query(index_expr).await?;
         let mut relevant_frags = Vec::with_capacity(fragments.len());
         let mut missing_frags = Vec::with_capacity(fragments.len());
-        for fragment in fragments {
+        for fragment in fragments.iter().cloned() {
             if covered_frags.contains(fragment.id as u32) {
                 relevant_frags.push(fragment);
             } else {
@@ -2593,6 +3147,49 @@ impl Scanner {
         }
     }
 
+    /// The fragments to scan, without the fragments of a partitioned dataset whose
+    /// partition values rule out every row matching the filter
+    fn fragments_to_scan(&self) -> Arc<Vec<Fragment>> {
+        let fragments = if let Some(fragment) = self.fragments.as_ref() {
+            Arc::new(fragment.clone())
+        } else {
+            self.dataset.fragments().clone()
+        };
+        match self.prune_partitions(&fragments) {
+            Ok(Some(pruned)) => Arc::new(pruned),
+            Ok(None) => fragments,
+            Err(err) => {
+                log::warn!(
+                    "Failed to prune partitions, scanning all fragments: {}",
+                    err
+                );
+                fragments
+            }
+        }
+    }
+
+    fn prune_partitions(&self, fragments: &[Fragment]) -> Result<Option<Vec<Fragment>>> {
+        let Some(filter) = self.filter.as_ref() else {
+            return Ok(None);
+        };
+        // A postfilter is applied to the search results, the search still needs every row
+        if (self.nearest.is_some() || self.full_text_query.is_some()) && !self.prefilter {
+            return Ok(None);
+        }
+        let Some(partition_spec) = self.dataset.partition_spec()? else {
+            return Ok(None);
+        };
+        let filter_schema = self.scan_input_schema()?;
+        let filter = filter.to_datafusion(self.dataset.schema(), filter_schema.as_ref())?;
+        let planner = Planner::new(Arc::new(filter_schema.as_ref().into()));
+        let filter = planner.optimize_expr(filter)?;
+        Ok(Some(partition_spec.prune(
+            self.dataset.schema(),
+            &filter,
+            fragments,
+        )))
+    }
+
     fn get_io_buffer_size(&self) -> u64 {
         self.io_buffer_size.unwrap_or(*DEFAULT_IO_BUFFER_SIZE)
     }
@@ -2609,11 +3206,7 @@ impl Scanner {
         range: Option<Range<u64>>,
         projection: Arc<Schema>,
     ) -> Arc<dyn ExecutionPlan> {
-        let fragments = if let Some(fragment) = self.fragments.as_ref() {
-            Arc::new(fragment.clone())
-        } else {
-            self.dataset.fragments().clone()
-        };
+        let fragments = self.fragments_to_scan();
         let ordered = if self.ordering.is_some() || self.nearest.is_some() {
             // If we are sorting the results there is no need to scan in order
             false
@@ -2678,11 +3271,7 @@ impl Scanner {
             ordered_output: self.ordered,
         };
 
-        let fragments = if let Some(fragment) = self.fragments.as_ref() {
-            Arc::new(fragment.clone())
-        } else {
-            self.dataset.fragments().clone()
-        };
+        let fragments = self.fragments_to_scan();
 
         Ok(Arc::new(LancePushdownScanExec::try_new(
             self.dataset.clone(),
@@ -3219,7 +3808,7 @@ mod test {
     use arrow::array::as_primitive_array;
     use arrow::datatypes::Int32Type;
     use arrow_array::cast::AsArray;
-    use arrow_array::types::{Float32Type, UInt64Type};
+    use arrow_array::types::{Float32Type, Float64Type, Int64Type, UInt32Type, UInt64Type};
     use arrow_array::{
         ArrayRef, FixedSizeListArray, Float16Array, Int32Array, LargeStringArray, PrimitiveArray,
         RecordBatchIterator, StringArray, StructArray,
@@ -3460,6 +4049,83 @@ mod test {
         assert_eq!(expected_i, actual_i);
     }
 
+    #[tokio::test]
+    async fn test_nearest_batch() {
+        let mut test_ds = TestVectorDataset::new(LanceFileVersion::Stable, false)
+            .await
+            .unwrap();
+        let dataset = &test_ds.dataset;
+        let queries = FixedSizeListArray::try_new_from_values(
+            (0..96).map(|v| (v + 32) as f32).collect::<Float32Array>(),
+            32,
+        )
+        .unwrap();
+
+        // A vector index is required
+        let err = dataset
+            .scan()
+            .nearest_batch("vec", &queries, 5)
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NotSupported { .. }));
+
+        test_ds.make_vector_index().await.unwrap();
+        let dataset = &test_ds.dataset;
+
+        let mut scan = dataset.scan();
+        scan.nearest_batch("vec", &queries, 5).unwrap().nprobs(2);
+        let plan = scan.explain_plan(false).await.unwrap();
+        assert!(plan.contains("ANNSubIndex: name=idx, k=5, deltas=1, queries=3"));
+
+        let batch = scan.try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 15);
+        assert_eq!(
+            batch.schema().field_with_name(QUERY_INDEX_COLUMN).unwrap(),
+            &ArrowField::new(QUERY_INDEX_COLUMN, DataType::UInt32, false)
+        );
+        let query_index = batch[QUERY_INDEX_COLUMN].as_primitive::<UInt32Type>();
+        let distances = batch[DIST_COL].as_primitive::<Float32Type>();
+
+        // Each query has the same results as searching for it on its own
+        for i in 0..3 {
+            let expected = dataset
+                .scan()
+                .nearest("vec", &queries.value(i), 5)
+                .unwrap()
+                .nprobs(2)
+                .try_into_batch()
+                .await
+                .unwrap();
+            let expected = expected[DIST_COL].as_primitive::<Float32Type>();
+            let actual = (0..batch.num_rows())
+                .filter(|row| query_index.value(*row) == i as u32)
+                .map(|row| distances.value(row))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected.values().to_vec());
+        }
+
+        // The prefilter applies to all of the queries
+        let batch = dataset
+            .scan()
+            .nearest_batch("vec", &queries, 5)
+            .unwrap()
+            .nprobs(2)
+            .prefilter(true)
+            .filter("i < 100")
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch.num_rows(), 15);
+        assert!(batch["i"]
+            .as_primitive::<Int32Type>()
+            .values()
+            .iter()
+            .all(|i| *i < 100));
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_knn_with_new_data(
@@ -3610,6 +4276,70 @@ mod test {
         assert!(actual_i.is_subset(&close_i));
     }
 
+    #[tokio::test]
+    async fn test_hybrid_search() {
+        let mut test_ds = TestVectorDataset::new(LanceFileVersion::Stable, false)
+            .await
+            .unwrap();
+        test_ds.make_fts_index().await.unwrap();
+        let dataset = &test_ds.dataset;
+
+        // Rows 5, 85, 165, ... all have this vector and only row 85 matches the text
+        let key: Float32Array = (5 * 32..6 * 32).map(|v| v as f32).collect();
+        let search = |reranker: Reranker, filter: Option<&str>| {
+            let mut scan = dataset.scan();
+            scan.project(&["i"]).unwrap();
+            scan.nearest("vec", &key, 5).unwrap();
+            scan.full_text_search(FullTextSearchQuery::new("85".to_owned()))
+                .unwrap();
+            scan.reranker(reranker);
+            scan.limit(Some(3), None).unwrap();
+            if let Some(filter) = filter {
+                scan.filter(filter).unwrap();
+                scan.prefilter(true);
+            }
+            scan
+        };
+
+        let scan = search(Reranker::default(), None);
+        let plan = scan.explain_plan(false).await.unwrap();
+        assert!(
+            plan.contains("HybridFusion: reranker=rrf(k=60)"),
+            "{}",
+            plan
+        );
+        let batch = scan.try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        let ids = batch["i"].as_primitive::<Int32Type>();
+        let distances = batch[DIST_COL].as_primitive::<Float32Type>();
+        let scores = batch[SCORE_COL].as_primitive::<Float32Type>();
+        let relevance = batch[RELEVANCE_SCORE_COL].as_primitive::<Float32Type>();
+        // Found by both searches
+        assert_eq!(ids.value(0), 85);
+        assert_eq!(distances.value(0), 0.0);
+        assert!(scores.is_valid(0));
+        // Its vector rank depends on how the ties are broken
+        assert!(relevance.value(0) >= 1.0 / 61.0 + 1.0 / 65.0);
+        // Only found by the vector search
+        for row in 1..3 {
+            assert_eq!(ids.value(row) % 80, 5);
+            assert!(scores.is_null(row));
+            assert!(relevance.value(row) <= relevance.value(row - 1));
+        }
+
+        let batch = search(Reranker::Linear { vector_weight: 0.5 }, Some("i < 100"))
+            .try_into_batch()
+            .await
+            .unwrap();
+        let ids = batch["i"].as_primitive::<Int32Type>();
+        let relevance = batch[RELEVANCE_SCORE_COL].as_primitive::<Float32Type>();
+        assert_eq!(ids.value(0), 85);
+        assert_eq!(relevance.value(0), 1.0);
+        assert_eq!(ids.value(1), 5);
+        assert_eq!(relevance.value(1), 0.5);
+        assert!(ids.values().iter().all(|i| *i < 100));
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_knn_filter_new_data(
@@ -4611,6 +5341,266 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_partition_pruning() {
+        use crate::dataset::{PartitionField, PartitionSpec};
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new("region", DataType::Utf8, false),
+        ]));
+        let make_batch = |range: std::ops::Range<i32>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from_iter_values(range.clone())),
+                    Arc::new(StringArray::from_iter_values(
+                        range.map(|i| ["us", "eu", "ap"][i as usize % 3]),
+                    )),
+                ],
+            )
+            .unwrap()
+        };
+
+        let spec = PartitionSpec::new(vec![
+            PartitionField::identity("region"),
+            PartitionField::truncate("i", 50),
+        ]);
+        let reader = RecordBatchIterator::new(vec![Ok(make_batch(0..100))], schema.clone());
+        let mut dataset = Dataset::write(
+            reader,
+            "memory://test",
+            Some(WriteParams {
+                partition_spec: Some(spec.clone()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dataset.partition_spec().unwrap(), Some(spec));
+        assert_eq!(dataset.get_fragments().len(), 6);
+        assert!(dataset
+            .get_fragments()
+            .iter()
+            .all(|frag| frag.metadata().partition_values.len() == 2));
+
+        // Appends pick up the spec from the dataset
+        let reader = RecordBatchIterator::new(vec![Ok(make_batch(100..130))], schema.clone());
+        dataset.append(reader, None).await.unwrap();
+        assert_eq!(dataset.get_fragments().len(), 9);
+
+        for (filter, num_fragments, num_rows) in [
+            ("region = 'us'", 3, 44),
+            ("region = 'us' AND i >= 100", 1, 10),
+            ("i < 50", 6, 50),
+            ("i < 49", 3, 49),
+            ("region = 'us' OR i < 10", 9, 50),
+        ] {
+            let mut scanner = dataset.scan();
+            scanner.filter(filter).unwrap();
+            assert_eq!(
+                scanner.fragments_to_scan().len(),
+                num_fragments,
+                "filter: {}",
+                filter
+            );
+            let batch = scanner.try_into_batch().await.unwrap();
+            assert_eq!(batch.num_rows(), num_rows, "filter: {}", filter);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregate() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new("category", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..100)),
+                Arc::new(Int32Array::from_iter_values((0..100).map(|i| i % 4))),
+            ],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut dataset = Dataset::write(reader, "memory://test", None).await.unwrap();
+
+        let aggregate = |dataset: &Dataset, filter: Option<&str>| {
+            let mut scanner = dataset.scan();
+            if let Some(filter) = filter {
+                scanner.filter(filter).unwrap();
+            }
+            scanner
+                .aggregate(
+                    &[] as &[&str],
+                    vec![
+                        AggregateExpr::count_star(),
+                        AggregateExpr::min("i"),
+                        AggregateExpr::max("i").with_alias("largest"),
+                    ],
+                )
+                .unwrap();
+            scanner
+        };
+        let check = |batch: RecordBatch, count: i64, min: i32, max: i32| {
+            assert_eq!(batch.num_rows(), 1);
+            assert_eq!(batch.schema().field(2).name(), "largest");
+            assert_eq!(
+                batch["count(*)"].as_primitive::<Int64Type>().value(0),
+                count
+            );
+            assert_eq!(batch["min(i)"].as_primitive::<Int32Type>().value(0), min);
+            assert_eq!(batch["largest"].as_primitive::<Int32Type>().value(0), max);
+        };
+
+        // Without an index every aggregate is computed from a scan
+        let scanner = aggregate(&dataset, None);
+        assert!(scanner
+            .explain_plan(false)
+            .await
+            .unwrap()
+            .contains("AggregateExec"));
+        check(scanner.try_into_batch().await.unwrap(), 100, 0, 99);
+
+        let batch = dataset
+            .scan()
+            .aggregate(
+                &["category"],
+                vec![
+                    AggregateExpr::count_star(),
+                    AggregateExpr::sum("i"),
+                    AggregateExpr::avg("i"),
+                ],
+            )
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch.num_rows(), 4);
+        let categories = batch["category"].as_primitive::<Int32Type>();
+        let sums = batch["sum(i)"].as_primitive::<Int64Type>();
+        let avgs = batch["avg(i)"].as_primitive::<Float64Type>();
+        for row in 0..4 {
+            let category = categories.value(row) as i64;
+            assert_eq!(batch["count(*)"].as_primitive::<Int64Type>().value(row), 25);
+            // category + (category + 4) + ... + (category + 96)
+            assert_eq!(sums.value(row), 25 * category + 4 * 300);
+            assert_eq!(avgs.value(row), (25 * category + 4 * 300) as f64 / 25.0);
+        }
+
+        // With an index that covers everything nothing needs to be scanned
+        dataset
+            .create_index(
+                &["i"],
+                IndexType::BTree,
+                None,
+                &ScalarIndexParams::default(),
+                true,
+            )
+            .await
+            .unwrap();
+        let scanner = aggregate(&dataset, None);
+        let plan = scanner.explain_plan(false).await.unwrap();
+        assert!(plan.contains("PrecomputedAggregate"), "{}", plan);
+        assert!(plan.contains("count(*)=metadata"), "{}", plan);
+        assert!(plan.contains("largest=index(i_idx)"), "{}", plan);
+        check(scanner.try_into_batch().await.unwrap(), 100, 0, 99);
+
+        // A filter must be applied to the rows
+        let scanner = aggregate(&dataset, Some("i >= 10 and i < 20"));
+        assert!(!scanner
+            .explain_plan(false)
+            .await
+            .unwrap()
+            .contains("PrecomputedAggregate"));
+        check(scanner.try_into_batch().await.unwrap(), 10, 10, 19);
+
+        // The index still has the deleted values
+        dataset.delete("i = 99").await.unwrap();
+        let scanner = aggregate(&dataset, None);
+        assert!(!scanner
+            .explain_plan(false)
+            .await
+            .unwrap()
+            .contains("PrecomputedAggregate"));
+        check(scanner.try_into_batch().await.unwrap(), 99, 0, 98);
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_page_statistics() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("x", DataType::Int32, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter(
+                    (0..100).map(|i| (i % 10 != 0).then_some(i)),
+                )),
+                // Long strings have their page minimums truncated
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|i| format!("{:0>100}", i)),
+                )),
+            ],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let mut dataset = Dataset::write(
+            reader,
+            "memory://test",
+            Some(WriteParams {
+                max_rows_per_file: 50,
+                data_storage_version: Some(LanceFileVersion::V2_1),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        let aggregate = |dataset: &Dataset, aggregates: Vec<AggregateExpr>| {
+            let mut scanner = dataset.scan();
+            scanner.aggregate(&[] as &[&str], aggregates).unwrap();
+            scanner
+        };
+
+        let scanner = aggregate(
+            &dataset,
+            vec![
+                AggregateExpr::count("x"),
+                AggregateExpr::min("x"),
+                AggregateExpr::max("x"),
+            ],
+        );
+        let plan = scanner.explain_plan(false).await.unwrap();
+        assert!(plan.contains("count(x)=page_statistics"), "{}", plan);
+        assert!(plan.contains("min(x)=page_statistics"), "{}", plan);
+        assert!(plan.contains("max(x)=page_statistics"), "{}", plan);
+        let batch = scanner.try_into_batch().await.unwrap();
+        assert_eq!(batch["count(x)"].as_primitive::<Int64Type>().value(0), 90);
+        assert_eq!(batch["min(x)"].as_primitive::<Int32Type>().value(0), 1);
+        assert_eq!(batch["max(x)"].as_primitive::<Int32Type>().value(0), 99);
+
+        // The statistics can't give the minimum of the long strings
+        let scanner = aggregate(&dataset, vec![AggregateExpr::min("s")]);
+        let plan = scanner.explain_plan(false).await.unwrap();
+        assert!(!plan.contains("PrecomputedAggregate"), "{}", plan);
+        let batch = scanner.try_into_batch().await.unwrap();
+        assert_eq!(
+            batch["min(s)"].as_string::<i32>().value(0),
+            format!("{:0>100}", 0)
+        );
+
+        // Page statistics still hold the values of deleted rows
+        dataset.delete("x = 99").await.unwrap();
+        let scanner = aggregate(&dataset, vec![AggregateExpr::max("x")]);
+        let plan = scanner.explain_plan(false).await.unwrap();
+        assert!(!plan.contains("PrecomputedAggregate"), "{}", plan);
+        let batch = scanner.try_into_batch().await.unwrap();
+        assert_eq!(batch["max(x)"].as_primitive::<Int32Type>().value(0), 98);
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_dynamic_projection(
@@ -5576,6 +6566,423 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_project_union() {
+        use arrow_array::{Array, UnionArray};
+        use arrow_buffer::ScalarBuffer;
+        use arrow_schema::{UnionFields, UnionMode};
+
+        let union_fields = UnionFields::new(
+            vec![0, 1],
+            vec![
+                ArrowField::new("a", DataType::Int32, true),
+                ArrowField::new("b", DataType::Utf8, true),
+            ],
+        );
+        let payload = UnionArray::try_new(
+            union_fields.clone(),
+            ScalarBuffer::from(vec![0_i8, 1, 1, 0]),
+            Some(ScalarBuffer::from(vec![0_i32, 0, 1, 1])),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 4])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["b1", "b2"])) as ArrayRef,
+            ],
+        )
+        .unwrap();
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "payload",
+            DataType::Union(union_fields, UnionMode::Dense),
+            false,
+        )]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(payload)]).unwrap();
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let write_params = WriteParams {
+            data_storage_version: Some(LanceFileVersion::V2_1),
+            ..Default::default()
+        };
+        let batches = RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone());
+        let dataset = Dataset::write(batches, test_uri, Some(write_params))
+            .await
+            .unwrap();
+
+        let scanned = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(scanned, batch);
+
+        let scanned = dataset
+            .scan()
+            .project(&["payload.b"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(
+            scanned.column_by_name("payload.b").unwrap().as_ref(),
+            &StringArray::from(vec![None, Some("b1"), Some("b2"), None]) as &dyn Array
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_of_struct_filter() {
+        use arrow_array::ListArray;
+        use arrow_buffer::OffsetBuffer;
+
+        let name_field = Arc::new(ArrowField::new("name", DataType::Utf8, true));
+        let tags_item = Arc::new(ArrowField::new(
+            "item",
+            DataType::Struct(vec![name_field.clone()].into()),
+            true,
+        ));
+        let names = StringArray::from(vec!["x", "y", "y", "z", "x", "w"]);
+        let tags = ListArray::new(
+            tags_item.clone(),
+            OffsetBuffer::from_lengths([2, 1, 0, 3]),
+            Arc::new(StructArray::new(
+                vec![name_field.clone()].into(),
+                vec![Arc::new(names)],
+                None,
+            )),
+            None,
+        );
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, false),
+            ArrowField::new("tags", DataType::List(tags_item.clone()), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![0, 1, 2, 3])), Arc::new(tags)],
+        )
+        .unwrap();
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let write_params = WriteParams {
+            data_storage_version: Some(LanceFileVersion::V2_1),
+            ..Default::default()
+        };
+        let batches = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+        let mut dataset = Dataset::write(batches, test_uri, Some(write_params))
+            .await
+            .unwrap();
+
+        let query_ids = |dataset: Dataset| async move {
+            let batch = dataset
+                .scan()
+                .filter("array_has(tags.name, 'x')")
+                .unwrap()
+                .project(&["id"])
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap();
+            batch
+                .column_by_name("id")
+                .unwrap()
+                .as_primitive::<Int32Type>()
+                .values()
+                .to_vec()
+        };
+        assert_eq!(query_ids(dataset.clone()).await, vec![0, 3]);
+
+        dataset
+            .create_index(
+                &["tags.name"],
+                IndexType::LabelList,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+        let plan = dataset
+            .scan()
+            .filter("array_has(tags.name, 'x')")
+            .unwrap()
+            .explain_plan(false)
+            .await
+            .unwrap();
+        assert!(plan.contains("MaterializeIndex"), "{}", plan);
+        assert_eq!(query_ids(dataset).await, vec![0, 3]);
+
+        // A btree on a nested leaf of a list indexes the values of the lists
+        let mut dataset = Dataset::open(test_uri).await.unwrap();
+        dataset
+            .create_index(
+                &["tags.name"],
+                IndexType::BTree,
+                None,
+                &ScalarIndexParams::default(),
+                true,
+            )
+            .await
+            .unwrap();
+        let indices = dataset.load_indices().await.unwrap();
+        assert_eq!(indices.len(), 1);
+        assert!(indices[0]
+            .index_details
+            .as_ref()
+            .unwrap()
+            .type_url
+            .ends_with("BTreeIndexDetails"));
+        let plan = dataset
+            .scan()
+            .filter("array_has(tags.name, 'x')")
+            .unwrap()
+            .explain_plan(false)
+            .await
+            .unwrap();
+        assert!(plan.contains("MaterializeIndex"), "{}", plan);
+        assert_eq!(query_ids(dataset.clone()).await, vec![0, 3]);
+
+        // New rows are indexed after the index is optimized
+        let names = StringArray::from(vec!["x", "v"]);
+        let tags = ListArray::new(
+            tags_item.clone(),
+            OffsetBuffer::from_lengths([1, 1]),
+            Arc::new(StructArray::new(
+                vec![name_field.clone()].into(),
+                vec![Arc::new(names)],
+                None,
+            )),
+            None,
+        );
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![4, 5])), Arc::new(tags)],
+        )
+        .unwrap();
+        dataset
+            .append(RecordBatchIterator::new(vec![Ok(batch)], schema), None)
+            .await
+            .unwrap();
+        dataset.optimize_indices(&Default::default()).await.unwrap();
+        let mut scan = dataset.scan();
+        scan.filter("array_has(tags.name, 'x')").unwrap();
+        let plan = scan.explain_plan(false).await.unwrap();
+        assert!(!plan.contains("LanceScan"), "{}", plan);
+        assert_eq!(query_ids(dataset).await, vec![0, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_list_of_struct_filter_reads_leaf() {
+        use arrow_array::ListArray;
+        use arrow_buffer::OffsetBuffer;
+
+        // Each tag has a small name and a large payload, filtering on the name should not
+        // read the payloads
+        let num_rows = 1000;
+        let item_fields = vec![
+            ArrowField::new("name", DataType::Utf8, true),
+            ArrowField::new("payload", DataType::Utf8, true),
+        ];
+        let names = StringArray::from_iter_values((0..num_rows * 2).map(|i| format!("n{}", i % 7)));
+        let payloads =
+            StringArray::from_iter_values((0..num_rows * 2).map(|i| format!("{:0>1024}", i)));
+        let tags_item = Arc::new(ArrowField::new(
+            "item",
+            DataType::Struct(item_fields.clone().into()),
+            true,
+        ));
+        let tags = ListArray::new(
+            tags_item.clone(),
+            OffsetBuffer::from_lengths(std::iter::repeat_n(2, num_rows)),
+            Arc::new(StructArray::new(
+                item_fields.into(),
+                vec![Arc::new(names), Arc::new(payloads)],
+                None,
+            )),
+            None,
+        );
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, false),
+            ArrowField::new("tags", DataType::List(tags_item), true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..num_rows as i32)),
+                Arc::new(tags),
+            ],
+        )
+        .unwrap();
+
+        let (io_stats_wrapper, io_stats) = IoTrackingStore::new_wrapper();
+        let dataset = Dataset::write(
+            RecordBatchIterator::new(vec![Ok(batch)], schema),
+            "memory://test",
+            Some(WriteParams {
+                store_params: Some(ObjectStoreParams {
+                    object_store_wrapper: Some(io_stats_wrapper),
+                    ..Default::default()
+                }),
+                data_storage_version: Some(LanceFileVersion::V2_1),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        let get_bytes = || io_stats.lock().unwrap().read_bytes;
+
+        let start_bytes = get_bytes();
+        dataset
+            .scan()
+            .project(&["tags.payload"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        let payload_bytes = get_bytes() - start_bytes;
+
+        let start_bytes = get_bytes();
+        let batch = dataset
+            .scan()
+            .filter("array_has(tags.name, 'n3')")
+            .unwrap()
+            .project(&["id"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        let filter_bytes = get_bytes() - start_bytes;
+
+        // Row i has the tags 2i and 2i + 1
+        let expected = (0..num_rows as i32)
+            .filter(|i| (2 * i) % 7 == 3 || (2 * i + 1) % 7 == 3)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            batch["id"].as_primitive::<Int32Type>().values().to_vec(),
+            expected
+        );
+        assert!(
+            filter_bytes * 10 < payload_bytes,
+            "filter read {} bytes, payloads are {} bytes",
+            filter_bytes,
+            payload_bytes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shredded_json_filter() {
+        use arrow_array::Array;
+        use lance_arrow::bfloat16::ARROW_EXT_NAME_KEY;
+        use lance_core::datatypes::{
+            JSON_EXT_NAME, JSON_SHREDDED_PATHS_META_KEY, JSON_SHRED_META_KEY,
+        };
+
+        let json_schema = |shred: bool| {
+            let mut metadata =
+                HashMap::from([(ARROW_EXT_NAME_KEY.to_string(), JSON_EXT_NAME.to_string())]);
+            if shred {
+                metadata.insert(JSON_SHRED_META_KEY.to_string(), "true".to_string());
+            }
+            Arc::new(ArrowSchema::new(vec![
+                ArrowField::new("id", DataType::Int32, false),
+                ArrowField::new("payload", DataType::Utf8, true).with_metadata(metadata),
+            ]))
+        };
+        // The documents are read back exactly as written, even if they are not valid JSON
+        let docs = StringArray::from(vec![
+            r#"{"user": {"name": "a", "id": 0}}"#,
+            r#"{"user":{"id":1}}"#,
+            r#"{"user":{"id":"2"}}"#,
+            r#"{"tags":[1],"user":{"id":2,"name":"d"}}"#,
+            r#"{"user":"#,
+        ]);
+        let write = |schema: Arc<ArrowSchema>, test_uri: String| {
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(vec![0, 1, 2, 3, 5])),
+                    Arc::new(docs.clone()),
+                ],
+            )
+            .unwrap();
+            let write_params = WriteParams {
+                data_storage_version: Some(LanceFileVersion::V2_1),
+                ..Default::default()
+            };
+            async move {
+                let batches = RecordBatchIterator::new(vec![Ok(batch)], schema);
+                Dataset::write(batches, &test_uri, Some(write_params))
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Shredding is opt-in
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let dataset = write(json_schema(false), test_uri.to_string()).await;
+        let payload = dataset.schema().field("payload").unwrap();
+        assert!(!payload.is_shredded_json());
+
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let schema = json_schema(true);
+        let dataset = write(schema.clone(), test_uri.to_string()).await;
+
+        // The shredded paths are picked from the data
+        let payload = dataset.schema().field("payload").unwrap();
+        assert_eq!(
+            payload.metadata[JSON_SHREDDED_PATHS_META_KEY],
+            "user.id:int64,user.name:string"
+        );
+
+        let scanned = dataset.scan().try_into_batch().await.unwrap();
+        assert_eq!(
+            scanned.column_by_name("payload").unwrap().as_ref(),
+            &docs as &dyn Array
+        );
+
+        // Appends are shredded the same way
+        let append = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![4])),
+                Arc::new(StringArray::from(vec![r#"{"user":{"id":2}}"#])),
+            ],
+        )
+        .unwrap();
+        let write_params = WriteParams {
+            mode: WriteMode::Append,
+            ..Default::default()
+        };
+        let batches = RecordBatchIterator::new(vec![Ok(append)], schema.clone());
+        let dataset = Dataset::write(batches, test_uri, Some(write_params))
+            .await
+            .unwrap();
+
+        let scanned = dataset
+            .scan()
+            .filter("json_get(payload, '$.user.id') = 2")
+            .unwrap()
+            .project(&["id"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(
+            scanned.column_by_name("id").unwrap().as_ref(),
+            &Int32Array::from(vec![2, 3, 4]) as &dyn Array
+        );
+
+        // Loading the documents as well as filtering on a shredded path
+        let scanned = dataset
+            .scan()
+            .filter("json_get(payload, '$.user.id') = 1")
+            .unwrap()
+            .project(&["payload"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(
+            scanned.column_by_name("payload").unwrap().as_ref(),
+            &StringArray::from(vec![r#"{"user":{"id":1}}"#]) as &dyn Array
+        );
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_plans(
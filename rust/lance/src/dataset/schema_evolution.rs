@@ -511,6 +511,7 @@ pub(super) async fn alter_columns(
         }
 
         if let Some(data_type) = &alteration.data_type {
+            check_not_partition_column(dataset, field_src, &alteration.path, "cast")?;
             if !(lance_arrow::cast::can_cast_types(&field_src.data_type(), data_type)
                 && is_upcast_downcast(&field_src.data_type(), data_type))
             {
@@ -653,6 +654,7 @@ pub(super) async fn drop_columns(dataset: &mut Dataset, columns: &[&str]) -> Res
                     location: location!(),
                 });
             }
+            check_not_partition_column(dataset, field, col, "dropped")?;
         } else {
             return Err(Error::invalid_input(
                 format!("Column {} does not exist in the dataset", col),
@@ -685,6 +687,35 @@ pub(super) async fn drop_columns(dataset: &mut Dataset, columns: &[&str]) -> Res
     Ok(())
 }
 
+/// The partition spec and the partition values of the fragments refer to the
+/// partition columns by field id, so they can be renamed but not replaced.
+fn check_not_partition_column(
+    dataset: &Dataset,
+    field: &Field,
+    path: &str,
+    action: &str,
+) -> Result<()> {
+    let Some(partition_spec) = dataset.partition_spec()? else {
+        return Ok(());
+    };
+    let is_partition_column = partition_spec.fields.iter().any(|partition_field| {
+        dataset
+            .schema()
+            .field(&partition_field.column)
+            .is_some_and(|partition_column| partition_column.id == field.id)
+    });
+    if is_partition_column {
+        return Err(Error::invalid_input(
+            format!(
+                "Column \"{}\" is a partition column and cannot be {}",
+                path, action
+            ),
+            location!(),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use std::sync::Mutex;
@@ -965,6 +996,7 @@ mod test {
                         deletion_file: None,
                         row_id_meta: None,
                         physical_rows: Some(50),
+                        partition_values: vec![],
                     }))
                 } else {
                     Ok(None)
@@ -1606,6 +1638,53 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_partition_column_evolution() -> Result<()> {
+        use crate::dataset::{PartitionField, PartitionSpec};
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, false),
+            ArrowField::new("part", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..10)),
+                Arc::new(Int32Array::from_iter_values((0..10).map(|i| i % 2))),
+            ],
+        )?;
+        let mut dataset = Dataset::write(
+            RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone()),
+            "memory://test",
+            Some(WriteParams {
+                partition_spec: Some(PartitionSpec::new(vec![PartitionField::identity("part")])),
+                ..Default::default()
+            }),
+        )
+        .await?;
+
+        let res = dataset.drop_columns(&["part"]).await;
+        assert!(matches!(res, Err(Error::InvalidInput { .. })));
+        let res = dataset
+            .alter_columns(&[ColumnAlteration::new("part".into()).cast_to(DataType::Int64)])
+            .await;
+        assert!(matches!(res, Err(Error::InvalidInput { .. })));
+        assert_eq!(dataset.version().version, 1);
+
+        // Renaming keeps the field id, so the spec follows the column
+        dataset
+            .alter_columns(&[ColumnAlteration::new("part".into()).rename("p".into())])
+            .await?;
+        dataset.drop_columns(&["i"]).await?;
+        assert_eq!(
+            dataset.partition_spec()?,
+            Some(PartitionSpec::new(vec![PartitionField::identity("p")]))
+        );
+        assert_eq!(dataset.count_rows(Some("p = 1".into())).await?, 5);
+
+        Ok(())
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_drop_add_columns(
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
+use std::collections::HashMap;
 use std::num::NonZero;
 use std::sync::Arc;
 
@@ -35,6 +36,7 @@ use crate::session::Session;
 use crate::Dataset;
 
 use super::blob::BlobStreamExt;
+use super::partition::PartitionSpec;
 use super::progress::{NoopFragmentWriteProgress, WriteFragmentProgress};
 use super::transaction::Transaction;
 use super::DATA_DIR;
@@ -200,6 +202,14 @@ pub struct WriteParams {
     /// to set lance.auto_cleanup.interval and lance.auto_cleanup.older_than.
     /// Both parameters must be set to invoke autocleaning.
     pub auto_cleanup: Option<AutoCleanupParams>,
+
+    /// If set, rows are written to fragments by partition so that every fragment
+    /// holds rows with the same partition values.
+    ///
+    /// The spec is recorded in the manifest when the dataset is created or overwritten
+    /// and later inserts use the recorded spec.  If this is `None` when overwriting a
+    /// partitioned dataset then the existing spec is kept.
+    pub partition_spec: Option<PartitionSpec>,
 }
 
 impl Default for WriteParams {
@@ -219,6 +229,7 @@ impl Default for WriteParams {
             enable_v2_manifest_paths: false,
             session: None,
             auto_cleanup: Some(AutoCleanupParams::default()),
+            partition_spec: None,
         }
     }
 }
@@ -243,6 +254,32 @@ impl WriteParams {
             .map(|s| s.store_registry())
             .unwrap_or_default()
     }
+
+    /// The partition spec to write with, given the dataset being written to (if any)
+    pub(crate) fn resolve_partition_spec(
+        &self,
+        dataset: Option<&Dataset>,
+    ) -> Result<Option<PartitionSpec>> {
+        let Some(dataset) = dataset else {
+            return Ok(self.partition_spec.clone());
+        };
+        let existing = dataset.partition_spec()?;
+        match self.mode {
+            WriteMode::Overwrite => Ok(self.partition_spec.clone().or(existing)),
+            WriteMode::Append | WriteMode::Create => {
+                if self.partition_spec.is_some() && self.partition_spec != existing {
+                    return Err(Error::invalid_input(
+                        format!(
+                            "Cannot append with partition spec {:?}, the dataset is partitioned by {:?}",
+                            self.partition_spec, existing
+                        ),
+                        location!(),
+                    ));
+                }
+                Ok(existing)
+            }
+        }
+    }
 }
 
 /// Writes the given data to the dataset and returns fragments.
@@ -329,6 +366,68 @@ pub async fn do_write_fragments(
     Ok(fragments)
 }
 
+/// Like [`do_write_fragments`] but each fragment only holds rows of one partition.
+///
+/// Rows are buffered per partition until there are enough for a full file, either
+/// by number of rows or by size.
+#[allow(clippy::too_many_arguments)]
+async fn do_write_partitioned_fragments(
+    object_store: Arc<ObjectStore>,
+    base_dir: &Path,
+    schema: &Schema,
+    mut data: SendableRecordBatchStream,
+    params: WriteParams,
+    storage_version: LanceFileVersion,
+    partition_spec: &PartitionSpec,
+) -> Result<Vec<Fragment>> {
+    let arrow_schema = data.schema();
+    let write_partition = |partition_values: Vec<Option<String>>, batches: Vec<RecordBatch>| {
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            arrow_schema.clone(),
+            futures::stream::iter(batches.into_iter().map(Ok)),
+        ));
+        let fut = do_write_fragments(
+            object_store.clone(),
+            base_dir,
+            schema,
+            stream,
+            params.clone(),
+            storage_version,
+        );
+        async move {
+            let mut fragments = fut.await?;
+            for fragment in &mut fragments {
+                fragment.partition_values = partition_values.clone();
+            }
+            Ok::<_, Error>(fragments)
+        }
+    };
+
+    // The buffered batches of each partition, along with their number of rows and bytes
+    let mut buffered = HashMap::<Vec<Option<String>>, (Vec<RecordBatch>, usize, usize)>::new();
+    let mut fragments = Vec::new();
+    while let Some(batch) = data.try_next().await? {
+        for (partition_values, batch) in partition_spec.split_batch(&batch)? {
+            let (batches, num_rows, num_bytes) =
+                buffered.entry(partition_values.clone()).or_default();
+            *num_rows += batch.num_rows();
+            *num_bytes += batch.get_array_memory_size();
+            batches.push(batch);
+            if *num_rows >= params.max_rows_per_file || *num_bytes >= params.max_bytes_per_file {
+                let (batches, _, _) = buffered.remove(&partition_values).unwrap();
+                fragments.extend(write_partition(partition_values, batches).await?);
+            }
+        }
+    }
+
+    let mut remaining = buffered.into_iter().collect::<Vec<_>>();
+    remaining.sort_by(|a, b| a.0.cmp(&b.0));
+    for (partition_values, (batches, _, _)) in remaining {
+        fragments.extend(write_partition(partition_values, batches).await?);
+    }
+    Ok(fragments)
+}
+
 pub struct WrittenFragments {
     /// The fragments written to the dataset (and the schema)
     pub default: (Vec<Fragment>, Schema),
@@ -433,15 +532,43 @@ pub async fn write_fragments_internal(
         ));
     }
 
+    let partition_spec = params.partition_spec.clone();
+    if let Some(partition_spec) = &partition_spec {
+        partition_spec.validate(&schema)?;
+        if blob_data.is_some() {
+            return Err(Error::NotSupported {
+                source: "Partitioned datasets do not support the blob storage class".into(),
+                location: location!(),
+            });
+        }
+    }
+
     let frag_schema = schema.retain_storage_class(StorageClass::Default);
-    let fragments_fut = do_write_fragments(
-        object_store.clone(),
-        base_dir,
-        &frag_schema,
-        data,
-        params,
-        storage_version,
-    );
+    let data_object_store = object_store.clone();
+    let fragments_fut = async {
+        if let Some(partition_spec) = &partition_spec {
+            do_write_partitioned_fragments(
+                data_object_store.clone(),
+                base_dir,
+                &frag_schema,
+                data,
+                params,
+                storage_version,
+                partition_spec,
+            )
+            .await
+        } else {
+            do_write_fragments(
+                data_object_store.clone(),
+                base_dir,
+                &frag_schema,
+                data,
+                params,
+                storage_version,
+            )
+            .await
+        }
+    };
 
     let (default, blob) = if let Some(blob_data) = blob_data {
         let blob_schema = schema.retain_storage_class(StorageClass::Blob);
@@ -908,6 +1035,62 @@ mod tests {
         assert_eq!(fragments.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_partitioned_file_size() {
+        use crate::dataset::PartitionField;
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("part", DataType::Int32, false),
+            ArrowField::new("value", DataType::Int32, false),
+        ]));
+        let batches = (0..10)
+            .map(|_| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from_iter_values((0..100).map(|i| i % 2))),
+                        Arc::new(Int32Array::from_iter_values(0..100)),
+                    ],
+                )
+                .map_err(DataFusionError::from)
+            })
+            .collect::<Vec<_>>();
+        let data_stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema.clone(),
+            futures::stream::iter(batches),
+        ));
+
+        let write_params = WriteParams {
+            max_rows_per_file: 1024 * 1024, // Won't be limited by this
+            max_bytes_per_file: 2 * 1024,
+            mode: WriteMode::Create,
+            partition_spec: Some(PartitionSpec::new(vec![PartitionField::identity("part")])),
+            ..Default::default()
+        };
+        let written = write_fragments_internal(
+            None,
+            Arc::new(ObjectStore::memory()),
+            &Path::from("test"),
+            Schema::try_from(schema.as_ref()).unwrap(),
+            data_stream,
+            write_params,
+        )
+        .await
+        .unwrap();
+
+        // Both partitions are flushed several times instead of being buffered whole
+        let fragments = written.default.0;
+        assert!(fragments.len() > 2);
+        assert!(fragments
+            .iter()
+            .all(|fragment| fragment.partition_values.len() == 1));
+        let num_rows = fragments
+            .iter()
+            .map(|fragment| fragment.physical_rows.unwrap())
+            .sum::<usize>();
+        assert_eq!(num_rows, 1000);
+    }
+
     #[tokio::test]
     async fn test_file_write_version() {
         let schema = Arc::new(ArrowSchema::new(vec![arrow::datatypes::Field::new(
@@ -16,7 +16,7 @@ use crate::{
     dataset::{
         builder::DatasetBuilder,
         commit_detached_transaction, commit_new_dataset, commit_transaction,
-        refs::Tags,
+        refs::{Branches, Tags},
         transaction::{Operation, Transaction},
         ManifestWriteConfig, ReadParams,
     },
@@ -323,6 +323,11 @@ impl<'a> CommitBuilder<'a> {
             commit_handler.clone(),
             base_path.clone(),
         );
+        let branches = Branches::new(
+            object_store.clone(),
+            commit_handler.clone(),
+            base_path.clone(),
+        );
 
         match &self.dest {
             WriteDestination::Dataset(dataset) => Ok(Dataset {
@@ -340,6 +345,8 @@ impl<'a> CommitBuilder<'a> {
                 session,
                 commit_handler,
                 tags,
+                branches,
+                branch: None,
             }),
         }
     }
@@ -446,6 +453,7 @@ mod tests {
             deletion_file: None,
             row_id_meta: None,
             physical_rows: Some(10),
+            partition_values: vec![],
         }
     }
 
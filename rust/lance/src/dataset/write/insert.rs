@@ -7,6 +7,8 @@ use std::sync::Arc;
 use arrow_array::RecordBatch;
 use arrow_array::RecordBatchIterator;
 use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use futures::StreamExt;
 use humantime::format_duration;
 use lance_core::datatypes::NullabilityComparison;
 use lance_core::datatypes::Schema;
@@ -20,6 +22,7 @@ use object_store::path::Path;
 use snafu::location;
 
 use crate::dataset::builder::DatasetBuilder;
+use crate::dataset::partition::PARTITION_SPEC_CONFIG_KEY;
 use crate::dataset::transaction::Operation;
 use crate::dataset::transaction::Transaction;
 use crate::dataset::write::write_fragments_internal;
@@ -183,13 +186,26 @@ impl<'a> InsertBuilder<'a> {
 
         self.validate_write(&mut context, &schema)?;
 
+        // Appends keep the shredded JSON paths of the dataset, new schemas pick
+        // their own from the data (for the JSON fields that opt in)
+        let (stream, schema) = if !matches!(context.params.mode, WriteMode::Append)
+            && context.storage_version >= LanceFileVersion::V2_1
+        {
+            shred_json_fields(stream, schema).await?
+        } else {
+            (stream, schema)
+        };
+
+        let mut params = context.params.clone();
+        params.partition_spec = params.resolve_partition_spec(context.dest.dataset())?;
+
         let written_frags = write_fragments_internal(
             context.dest.dataset(),
             context.object_store.clone(),
             &context.base_path,
             schema.clone(),
             stream,
-            context.params.clone(),
+            params,
         )
         .await?;
 
@@ -203,10 +219,22 @@ impl<'a> InsertBuilder<'a> {
         written_frags: WrittenFragments,
         context: &WriteContext<'_>,
     ) -> Result<Transaction> {
+        // The partition spec is recorded by field id, so it is rewritten for every new schema
+        let partition_spec_config = context
+            .params
+            .resolve_partition_spec(context.dest.dataset())?
+            .map(|spec| {
+                Ok::<_, Error>((
+                    PARTITION_SPEC_CONFIG_KEY.to_string(),
+                    spec.to_config_value(&schema)?,
+                ))
+            })
+            .transpose()?;
+
         let operation = match context.params.mode {
             WriteMode::Create => {
                 // Fetch auto_cleanup params from context
-                let config_upsert_values = match context.params.auto_cleanup.as_ref() {
+                let mut config_upsert_values = match context.params.auto_cleanup.as_ref() {
                     Some(auto_cleanup_params) => {
                         let mut upsert_values = HashMap::new();
 
@@ -234,6 +262,11 @@ impl<'a> InsertBuilder<'a> {
                     }
                     None => None,
                 };
+                if let Some((key, value)) = partition_spec_config {
+                    config_upsert_values
+                        .get_or_insert_with(HashMap::new)
+                        .insert(key, value);
+                }
                 Operation::Overwrite {
                     // Use the full schema, not the written schema
                     schema,
@@ -245,7 +278,8 @@ impl<'a> InsertBuilder<'a> {
                 // Use the full schema, not the written schema
                 schema,
                 fragments: written_frags.default.0,
-                config_upsert_values: None,
+                config_upsert_values: partition_spec_config
+                    .map(|(key, value)| HashMap::from([(key, value)])),
             },
             WriteMode::Append => Operation::Append {
                 fragments: written_frags.default.0,
@@ -428,6 +462,24 @@ impl<'a> InsertBuilder<'a> {
     }
 }
 
+/// Pick the paths to shred for any JSON fields that ask for shredding
+///
+/// The paths are inferred from the first batch of data.
+async fn shred_json_fields(
+    stream: SendableRecordBatchStream,
+    mut schema: Schema,
+) -> Result<(SendableRecordBatchStream, Schema)> {
+    let arrow_schema = stream.schema();
+    let mut stream = Box::pin(stream.peekable());
+    if let Some(Ok(batch)) = stream.as_mut().peek().await {
+        schema.shred_json_fields(batch)?;
+    }
+    Ok((
+        Box::pin(RecordBatchStreamAdapter::new(arrow_schema, stream)),
+        schema,
+    ))
+}
+
 #[derive(Debug)]
 struct WriteContext<'a> {
     params: WriteParams,
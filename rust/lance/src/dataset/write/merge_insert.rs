@@ -747,7 +747,17 @@ impl MergeInsertJob {
                 )?;
 
                 let updated_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
-                if Some(updated_rows) == metadata.physical_rows {
+                let replaces_all_rows = Some(updated_rows) == metadata.physical_rows;
+                // The update may move rows to another partition
+                let partition_values = match dataset.partition_spec()? {
+                    Some(partition_spec) => partition_spec.updated_partition_values(
+                        &metadata.partition_values,
+                        &batches,
+                        replaces_all_rows,
+                    )?,
+                    None => metadata.partition_values.clone(),
+                };
+                if replaces_all_rows {
                     // All rows have been updated and there are no deletions. So we
                     // don't need to merge in existing values.
                     // Also, because we already sorted by row address, the rows
@@ -802,6 +812,7 @@ impl MergeInsertJob {
                     let (_num_rows, data_file) = writer.finish().await?;
 
                     metadata.files.push(data_file);
+                    metadata.partition_values = partition_values;
                     updated_fragments.lock().unwrap().push(metadata);
                 } else {
                     // TODO: we could skip scanning row addresses we don't need.
@@ -886,7 +897,8 @@ impl MergeInsertJob {
                         updater.update(updated_batch).await?;
                     }
 
-                    let updated_fragment = updater.finish().await?;
+                    let mut updated_fragment = updater.finish().await?;
+                    updated_fragment.partition_values = partition_values;
                     updated_fragments.lock().unwrap().push(updated_fragment);
                 }
                 Ok(reservation_size)
@@ -2803,4 +2815,80 @@ mod tests {
             values
         );
     }
+
+    #[tokio::test]
+    async fn test_merge_insert_updates_partition_values() {
+        use crate::dataset::{PartitionField, PartitionSpec};
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("region", DataType::Utf8, false),
+            Field::new("value", DataType::UInt32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from_iter_values(0..30)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..30).map(|i| ["us", "eu", "ap"][i % 3]),
+                )),
+                Arc::new(UInt32Array::from_iter_values(0..30)),
+            ],
+        )
+        .unwrap();
+        let spec = PartitionSpec::new(vec![PartitionField::identity("region")]);
+        let dataset = Dataset::write(
+            RecordBatchIterator::new([Ok(batch)], schema.clone()),
+            "memory://test",
+            Some(WriteParams {
+                partition_spec: Some(spec),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dataset.get_fragments().len(), 3);
+
+        // Only update some of the columns, so the fragments are updated in place
+        let update_schema = Arc::new(schema.project(&[0, 1]).unwrap());
+        let new_data = RecordBatch::try_new(
+            update_schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![0])),
+                Arc::new(StringArray::from(vec!["eu"])),
+            ],
+        )
+        .unwrap();
+        let (dataset, stats) = MergeInsertBuilder::try_new(Arc::new(dataset), vec!["id".into()])
+            .unwrap()
+            .when_matched(WhenMatched::UpdateAll)
+            .when_not_matched(WhenNotMatched::DoNothing)
+            .try_build()
+            .unwrap()
+            .execute_reader(Box::new(RecordBatchIterator::new(
+                [Ok(new_data)],
+                update_schema,
+            )))
+            .await
+            .unwrap();
+        assert_eq!(stats.num_updated_rows, 1);
+
+        // The fragment now holds rows of two partitions
+        let partition_values = dataset
+            .get_fragments()
+            .iter()
+            .map(|frag| frag.metadata().partition_values.clone())
+            .collect::<Vec<_>>();
+        assert!(partition_values.contains(&vec![]));
+        assert!(partition_values.contains(&vec![Some("eu".to_string())]));
+
+        for (filter, num_rows) in [("region = 'eu'", 11), ("region = 'us'", 9)] {
+            assert_eq!(
+                dataset.count_rows(Some(filter.to_string())).await.unwrap(),
+                num_rows,
+                "filter: {}",
+                filter
+            );
+        }
+    }
 }
@@ -14,6 +14,7 @@ use datafusion::execution::SendableRecordBatchStream;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use futures::{stream, StreamExt, TryStreamExt};
 use itertools::Itertools;
+use lance_core::error::LanceOptionExt;
 use lance_core::utils::address::RowAddress;
 use lance_core::utils::parse::str_is_truthy;
 use lance_core::utils::tracing::{
@@ -25,11 +26,13 @@ use lance_file::v2::reader::FileReaderOptions;
 use lance_index::frag_reuse::{FragReuseIndex, FRAG_REUSE_INDEX_NAME};
 use lance_index::pb::index::Implementation;
 use lance_index::scalar::expression::{
-    FtsQueryParser, IndexInformationProvider, LabelListQueryParser, MultiQueryParser,
-    SargableQueryParser, ScalarQueryParser, TextQueryParser,
+    CompositeIndexInfo, FtsQueryParser, IndexInformationProvider, LabelListQueryParser,
+    MultiQueryParser, SargableQueryParser, ScalarQueryParser, TextQueryParser,
 };
 use lance_index::scalar::lance_format::LanceIndexStore;
-use lance_index::scalar::{ScalarIndex, ScalarIndexType};
+use lance_index::scalar::{
+    BloomFilterIndexParams, NGramIndexParams, ScalarIndex, ScalarIndexType, ZoneMapIndexParams,
+};
 use lance_index::vector::flat::index::{FlatBinQuantizer, FlatIndex, FlatQuantizer};
 use lance_index::vector::hnsw::HNSW;
 use lance_index::vector::pq::ProductQuantizer;
@@ -58,15 +61,17 @@ use lance_table::format::{Fragment, SelfDescribingFileReader};
 use lance_table::io::manifest::read_manifest_indexes;
 use roaring::RoaringBitmap;
 use scalar::{
-    build_inverted_index, detect_scalar_index_type, index_matches_criteria, infer_index_type,
-    inverted_index_details, TrainingRequest,
+    bloom_filter_index_details, build_bloom_filter_index, build_composite_btree_index,
+    build_inverted_index, build_ngram_index, build_zone_map_index, detect_scalar_index_type,
+    index_matches_criteria, infer_index_type, inverted_index_details, ngram_index_details,
+    zone_map_index_details, TrainingRequest,
 };
 use serde_json::json;
 use snafu::location;
 use tracing::{info, instrument};
 use uuid::Uuid;
-use vector::ivf::v2::IVFIndex;
 use vector::cagra::CagraIndex;
+use vector::ivf::v2::IVFIndex;
 use vector::utils::get_vector_type;
 
 pub(crate) mod append;
@@ -121,7 +126,9 @@ pub(crate) async fn remap_index(
             location: location!(),
         })?;
 
-    if matched.fields.len() > 1 {
+    // Composite btree indices store a single column of keys and can be remapped like any
+    // other btree index
+    if matched.fields.len() > 1 && infer_index_type(matched) != Some(IndexType::BTree) {
         return Err(Error::Index {
             message: "Remapping indices with multiple fields is not supported".to_string(),
             location: location!(),
@@ -150,11 +157,12 @@ pub(crate) async fn remap_index(
         .expect("An index existed with no fields");
 
     let field = dataset.schema().field_by_id(*field).unwrap();
+    let column = dataset.schema().field_path(field.id).expect_ok()?;
 
     let new_id = Uuid::new_v4();
 
     let generic = dataset
-        .open_generic_index(&field.name, &index_id.to_string(), &NoOpMetricsCollector)
+        .open_generic_index(&column, &index_id.to_string(), &NoOpMetricsCollector)
         .await?;
 
     match generic.index_type() {
@@ -162,7 +170,7 @@ pub(crate) async fn remap_index(
             let new_store = LanceIndexStore::from_dataset(dataset, &new_id.to_string());
 
             let scalar_index = dataset
-                .open_scalar_index(&field.name, &index_id.to_string(), &NoOpMetricsCollector)
+                .open_scalar_index(&column, &index_id.to_string(), &NoOpMetricsCollector)
                 .await?;
 
             match scalar_index.index_type() {
@@ -182,7 +190,7 @@ pub(crate) async fn remap_index(
                         );
                         let training_request = Box::new(TrainingRequest::new(
                             Arc::new(dataset.clone()),
-                            field.name.clone(),
+                            column.clone(),
                         ));
                         train_inverted_index(
                             training_request,
@@ -222,6 +230,7 @@ pub(crate) async fn remap_index(
 #[derive(Debug)]
 pub struct ScalarIndexInfo {
     indexed_columns: HashMap<String, (DataType, Box<MultiQueryParser>)>,
+    composite_indices: Vec<CompositeIndexInfo>,
 }
 
 impl IndexInformationProvider for ScalarIndexInfo {
@@ -230,6 +239,10 @@ impl IndexInformationProvider for ScalarIndexInfo {
             .get(col)
             .map(|(ty, parser)| (ty, parser.as_ref() as &dyn ScalarQueryParser))
     }
+
+    fn composite_indices(&self) -> &[CompositeIndexInfo] {
+        &self.composite_indices
+    }
 }
 
 async fn open_index_proto(reader: &dyn Reader) -> Result<pb::Index> {
@@ -251,15 +264,6 @@ fn vector_index_details() -> prost_types::Any {
     prost_types::Any::from_msg(&details).unwrap()
 }
 
-fn cagra_index_details(name: &str, column: &str) -> prost_types::Any {
-    let details = lance_table::format::pb::CagraIndexMetaDataDetails {
-        name: name.to_string(),
-        column: column.to_string(),
-        index_type: "CAGRA".to_string()
-    };
-    return prost_types::Any::from_msg(&details).unwrap()
-}
-
 #[async_trait]
 impl DatasetIndexExt for Dataset {
     #[instrument(skip_all)]
@@ -271,26 +275,50 @@ impl DatasetIndexExt for Dataset {
         params: &dyn IndexParams,
         replace: bool,
     ) -> Result<()> {
-        if columns.len() != 1 {
+        if columns.is_empty() {
             return Err(Error::Index {
-                message: "Only support building index on 1 column at the moment".to_string(),
+                message: "CreateIndex: at least one column must be specified".to_string(),
                 location: location!(),
             });
         }
+        // Only btree indices can be built on more than one column (a composite index)
+        if columns.len() > 1 {
+            let is_btree = match (index_type, params.index_name()) {
+                (IndexType::BTree, LANCE_SCALAR_INDEX) => true,
+                (IndexType::Scalar, LANCE_SCALAR_INDEX) => params
+                    .as_any()
+                    .downcast_ref::<ScalarIndexParams>()
+                    .map(|params| {
+                        matches!(params.force_index_type, None | Some(ScalarIndexType::BTree))
+                    })
+                    .unwrap_or(false),
+                _ => false,
+            };
+            if !is_btree {
+                return Err(Error::Index {
+                    message: "Only BTree indices can be built on more than 1 column".to_string(),
+                    location: location!(),
+                });
+            }
+        }
         let column = columns[0];
-        let Some(field) = self.schema().field(column) else {
-            return Err(Error::Index {
-                message: format!("CreateIndex: column '{column}' does not exist"),
-                location: location!(),
-            });
-        };
+        let mut field_ids = Vec::with_capacity(columns.len());
+        for column in columns {
+            let Some(field) = self.schema().field(column) else {
+                return Err(Error::Index {
+                    message: format!("CreateIndex: column '{column}' does not exist"),
+                    location: location!(),
+                });
+            };
+            field_ids.push(field.id);
+        }
 
         // Load indices from the disk.
         let indices = self.load_indices().await?;
         let fri = self.open_frag_reuse_index(&NoOpMetricsCollector).await?;
-        let index_name = name.unwrap_or(format!("{column}_idx"));
+        let index_name = name.unwrap_or(format!("{}_idx", columns.join("_")));
         if let Some(idx) = indices.iter().find(|i| i.name == index_name) {
-            if idx.fields == [field.id] && !replace {
+            if idx.fields == field_ids && !replace {
                 return Err(Error::Index {
                     message: format!(
                         "Index name '{index_name} already exists, \
@@ -299,7 +327,7 @@ impl DatasetIndexExt for Dataset {
                     location: location!(),
                 });
             };
-            if idx.fields != [field.id] {
+            if idx.fields != field_ids {
                 return Err(Error::Index {
                     message: format!(
                         "Index name '{index_name} already exists with different fields, \
@@ -312,12 +340,17 @@ impl DatasetIndexExt for Dataset {
 
         let index_id = Uuid::new_v4();
         let index_details = match (index_type, params.index_name()) {
+            (IndexType::BTree | IndexType::Scalar, LANCE_SCALAR_INDEX) if columns.len() > 1 => {
+                build_composite_btree_index(self, columns, &index_id.to_string()).await?
+            }
             (
                 IndexType::Bitmap
                 | IndexType::BTree
                 | IndexType::Inverted
                 | IndexType::NGram
-                | IndexType::LabelList,
+                | IndexType::LabelList
+                | IndexType::BloomFilter
+                | IndexType::ZoneMap,
                 LANCE_SCALAR_INDEX,
             ) => {
                 let params = ScalarIndexParams::new(index_type.try_into()?);
@@ -347,6 +380,44 @@ impl DatasetIndexExt for Dataset {
                 build_inverted_index(self, column, &index_id.to_string(), inverted_params).await?;
                 inverted_index_details()
             }
+            (IndexType::BloomFilter, _) => {
+                let bloom_filter_params = params
+                    .as_any()
+                    .downcast_ref::<BloomFilterIndexParams>()
+                    .ok_or_else(|| Error::Index {
+                        message: "Bloom filter index type must take a BloomFilterIndexParams"
+                            .to_string(),
+                        location: location!(),
+                    })?;
+
+                build_bloom_filter_index(self, column, &index_id.to_string(), bloom_filter_params)
+                    .await?;
+                bloom_filter_index_details()
+            }
+            (IndexType::ZoneMap, _) => {
+                let zone_map_params = params
+                    .as_any()
+                    .downcast_ref::<ZoneMapIndexParams>()
+                    .ok_or_else(|| Error::Index {
+                        message: "Zone map index type must take a ZoneMapIndexParams".to_string(),
+                        location: location!(),
+                    })?;
+
+                build_zone_map_index(self, column, &index_id.to_string(), zone_map_params).await?;
+                zone_map_index_details()
+            }
+            (IndexType::NGram, _) => {
+                let ngram_params = params
+                    .as_any()
+                    .downcast_ref::<NGramIndexParams>()
+                    .ok_or_else(|| Error::Index {
+                        message: "NGram index type must take a NGramIndexParams".to_string(),
+                        location: location!(),
+                    })?;
+
+                build_ngram_index(self, column, &index_id.to_string(), ngram_params).await?;
+                ngram_index_details()
+            }
             (IndexType::Vector, LANCE_VECTOR_INDEX) => {
                 // Vector index params.
                 let vec_params = params
@@ -367,14 +438,7 @@ impl DatasetIndexExt for Dataset {
                     fri,
                 ))
                 .await?;
-
-                let index_details = if vec_params.cagra_params.is_some() {
-                    cagra_index_details(&index_name, column)
-
-                } else { 
-                    vector_index_details()
-                };
-                index_details
+                vector_index_details()
             }
             // Can't use if let Some(...) here because it's not stable yet.
             // TODO: fix after https://github.com/rust-lang/rust/issues/51114
@@ -422,15 +486,13 @@ impl DatasetIndexExt for Dataset {
         let new_idx = IndexMetadata {
             uuid: index_id,
             name: index_name,
-            fields: vec![field.id],
+            fields: field_ids,
             dataset_version: self.manifest.version,
             fragment_bitmap: Some(self.get_fragments().iter().map(|f| f.id() as u32).collect()),
             index_details: Some(index_details),
             index_version: index_type.version(),
         };
 
-        eprintln!("New Index {:?}", new_idx);
-
         let transaction = Transaction::new(
             self.manifest.version,
             Operation::CreateIndex {
@@ -493,7 +555,7 @@ impl DatasetIndexExt for Dataset {
         let indices = match self
             .session
             .index_cache
-            .get_metadata(self.base.as_ref(), self.version().version)
+            .get_metadata(self.manifest_base().as_ref(), self.version().version)
         {
             Some(indices) => indices,
             None => {
@@ -505,7 +567,7 @@ impl DatasetIndexExt for Dataset {
                 .await?;
                 let loaded_indices = Arc::new(loaded_indices);
                 self.session.index_cache.insert_metadata(
-                    self.base.as_ref(),
+                    self.manifest_base().as_ref(),
                     self.version().version,
                     loaded_indices.clone(),
                 );
@@ -1066,7 +1128,6 @@ impl DatasetIndexInternalExt for Dataset {
 
                 info!(target: TRACE_IO_EVENTS, index_uuid=uuid, type=IO_TYPE_OPEN_VECTOR, version="0.3", index_type=index_metadata.index_type);
 
-                eprintln!("Metadata index type {:?}", index_metadata.index_type);
                 match index_metadata.index_type.as_str() {
                     "IVF_FLAT" => match element_type {
                         DataType::Float16 | DataType::Float32 | DataType::Float64 => {
@@ -1135,12 +1196,16 @@ impl DatasetIndexInternalExt for Dataset {
                         .await?;
                         Ok(Arc::new(ivf) as Arc<dyn VectorIndex>)
                     }
-                    
+
                     "CAGRA" => {
-                        let cagra = CagraIndex::new(
-                            "temp".to_string(),
-                            "temp".to_string()
-                        );
+                        let cagra = CagraIndex::open(
+                            self.object_store.clone(),
+                            self.indices_dir(),
+                            uuid.to_owned(),
+                            &self.session.file_metadata_cache,
+                            fri,
+                        )
+                        .await?;
                         Ok(Arc::new(cagra) as Arc<dyn VectorIndex>)
                     }
 
@@ -1197,6 +1262,32 @@ impl DatasetIndexInternalExt for Dataset {
     async fn scalar_index_info(&self) -> Result<ScalarIndexInfo> {
         let indices = self.load_indices().await?;
         let schema = self.schema();
+
+        // Btree indices on more than one column are composite indices
+        let mut composite_indices: Vec<CompositeIndexInfo> = Vec::new();
+        for index in indices
+            .iter()
+            .filter(|idx| idx.fields.len() > 1 && infer_index_type(idx) == Some(IndexType::BTree))
+        {
+            // Each delta of an index has its own entry
+            if composite_indices
+                .iter()
+                .any(|info| info.index_name == index.name)
+            {
+                continue;
+            }
+            let mut columns = Vec::with_capacity(index.fields.len());
+            for field_id in &index.fields {
+                let column = schema.field_path(*field_id).expect_ok()?;
+                let data_type = schema.column_data_type(&column).expect_ok()?;
+                columns.push((column, data_type));
+            }
+            composite_indices.push(CompositeIndexInfo {
+                index_name: index.name.clone(),
+                columns,
+            });
+        }
+
         let mut indexed_fields = Vec::new();
         for index in indices.iter().filter(|idx| {
             let idx_schema = schema.project_by_ids(idx.fields.as_slice(), true);
@@ -1215,14 +1306,38 @@ impl DatasetIndexInternalExt for Dataset {
                 location: location!(),
             })?;
 
-            let query_parser = match field.data_type() {
+            // Nested fields are referred to by their path (e.g. `tags.name`) and fields inside
+            // of lists are indexed as lists of their values
+            let column = schema.field_path(field.id).expect_ok()?;
+            let data_type = schema.column_data_type(&column).expect_ok()?;
+
+            let query_parser = match data_type {
                 DataType::List(_) => Box::new(LabelListQueryParser::new(index.name.clone()))
                     as Box<dyn ScalarQueryParser>,
+                // Bloom filters can only rule out rows for equality queries
+                _ if infer_index_type(index) == Some(IndexType::BloomFilter) => Box::new(
+                    SargableQueryParser::new(index.name.clone())
+                        .with_recheck()
+                        .with_equality_only(),
+                )
+                    as Box<dyn ScalarQueryParser>,
+                // Zone maps can only rule out zones of rows
+                _ if infer_index_type(index) == Some(IndexType::ZoneMap) => {
+                    Box::new(SargableQueryParser::new(index.name.clone()).with_recheck())
+                        as Box<dyn ScalarQueryParser>
+                }
                 DataType::Utf8 | DataType::LargeUtf8 => {
                     let index_type =
-                        detect_scalar_index_type(self, index, &field.name, self.session.as_ref())
+                        detect_scalar_index_type(self, index, &column, self.session.as_ref())
                             .await?;
                     match index_type {
+                        // The results of these indices are rechecked with a filter that
+                        // can only refer to top level columns
+                        ScalarIndexType::NGram | ScalarIndexType::Inverted
+                            if column.contains('.') =>
+                        {
+                            continue
+                        }
                         ScalarIndexType::BTree | ScalarIndexType::Bitmap => {
                             Box::new(SargableQueryParser::new(index.name.clone()))
                                 as Box<dyn ScalarQueryParser>
@@ -1242,7 +1357,7 @@ impl DatasetIndexInternalExt for Dataset {
                     as Box<dyn ScalarQueryParser>,
             };
 
-            indexed_fields.push((field.name.clone(), (field.data_type(), query_parser)));
+            indexed_fields.push((column, (data_type, query_parser)));
         }
         let mut index_info_map = HashMap::with_capacity(indexed_fields.len());
         for indexed_field in indexed_fields {
@@ -1267,6 +1382,7 @@ impl DatasetIndexInternalExt for Dataset {
         }
         Ok(ScalarIndexInfo {
             indexed_columns: index_info_map,
+            composite_indices,
         })
     }
 
@@ -1341,7 +1457,9 @@ mod tests {
 
     use arrow::array::AsArray;
     use arrow::datatypes::{Float32Type, Int32Type};
-    use arrow_array::{FixedSizeListArray, RecordBatch, RecordBatchIterator, StringArray};
+    use arrow_array::{
+        FixedSizeListArray, Int32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray,
+    };
     use arrow_schema::{Field, Schema};
     use lance_arrow::*;
     use lance_datagen::r#gen;
@@ -2087,6 +2205,317 @@ mod tests {
         assert_eq!(index.index_type(), IndexType::Bitmap);
     }
 
+    #[tokio::test]
+    async fn test_create_bloom_filter_index() {
+        let test_dir = tempdir().unwrap();
+        let field = Field::new("key", DataType::Utf8, false);
+        let schema = Arc::new(Schema::new(vec![field]));
+        let array = StringArray::from_iter_values((0..1000).map(|i| format!("key-{}", i)));
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+        let reader = RecordBatchIterator::new(
+            vec![record_batch.clone()].into_iter().map(Ok),
+            schema.clone(),
+        );
+
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = Dataset::write(reader, test_uri, None).await.unwrap();
+        let params = BloomFilterIndexParams::default().with_rows_per_block(100);
+        dataset
+            .create_index(&["key"], IndexType::BloomFilter, None, &params, false)
+            .await
+            .unwrap();
+        let indices = dataset.load_indices().await.unwrap();
+        let index = dataset
+            .open_generic_index("key", &indices[0].uuid.to_string(), &NoOpMetricsCollector)
+            .await
+            .unwrap();
+        assert_eq!(index.index_type(), IndexType::BloomFilter);
+
+        // The index is inexact so the filter must still be applied
+        let plan = dataset
+            .scan()
+            .filter("key = 'key-123'")
+            .unwrap()
+            .explain_plan(true)
+            .await
+            .unwrap();
+        assert!(plan.contains("MaterializeIndex"), "{}", plan);
+        let batch = dataset
+            .scan()
+            .filter("key IN ('key-123', 'key-999', 'missing')")
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch.num_rows(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_btree_index_prefix() {
+        let test_dir = tempdir().unwrap();
+        let field = Field::new("key", DataType::Utf8, false);
+        let schema = Arc::new(Schema::new(vec![field]));
+        let array = StringArray::from_iter_values((0..1000).map(|i| format!("Key{}", i)));
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+        let reader = RecordBatchIterator::new(
+            vec![record_batch.clone()].into_iter().map(Ok),
+            schema.clone(),
+        );
+
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = Dataset::write(reader, test_uri, None).await.unwrap();
+        dataset
+            .create_index(
+                &["key"],
+                IndexType::BTree,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+
+        for (filter, expected) in [
+            ("key LIKE 'Key12%'", 11),
+            ("starts_with(key, 'Key99')", 11),
+            ("key NOT LIKE 'Key1%'", 889),
+            ("key LIKE 'Key5'", 1),
+        ] {
+            let plan = dataset
+                .scan()
+                .filter(filter)
+                .unwrap()
+                .explain_plan(true)
+                .await
+                .unwrap();
+            assert!(plan.contains("MaterializeIndex"), "{}", plan);
+            let batch = dataset
+                .scan()
+                .filter(filter)
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap();
+            assert_eq!(batch.num_rows(), expected, "{}", filter);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ngram_index_patterns() {
+        let test_dir = tempdir().unwrap();
+        let field = Field::new("key", DataType::Utf8, false);
+        let schema = Arc::new(Schema::new(vec![field]));
+        let array = StringArray::from_iter_values((0..1000).map(|i| format!("Key{}", i)));
+        let record_batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+        let reader = RecordBatchIterator::new(
+            vec![record_batch.clone()].into_iter().map(Ok),
+            schema.clone(),
+        );
+
+        let test_uri = test_dir.path().to_str().unwrap();
+        let mut dataset = Dataset::write(reader, test_uri, None).await.unwrap();
+        let params = NGramIndexParams::default().with_lower_case(false);
+        dataset
+            .create_index(&["key"], IndexType::NGram, None, &params, false)
+            .await
+            .unwrap();
+
+        let plan = dataset
+            .scan()
+            .filter("key LIKE 'Key12%'")
+            .unwrap()
+            .explain_plan(true)
+            .await
+            .unwrap();
+        assert!(plan.contains("MaterializeIndex"), "{}", plan);
+
+        for (filter, expected) in [
+            ("key LIKE 'Key12%'", 11),
+            ("key LIKE 'key12%'", 0),
+            // Case insensitive searches are not helped by a case sensitive index but must still work
+            ("key ILIKE 'key12%'", 11),
+            ("regexp_like(key, '^Key99.$')", 10),
+        ] {
+            let batch = dataset
+                .scan()
+                .filter(filter)
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap();
+            assert_eq!(batch.num_rows(), expected, "{}", filter);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zone_map_index() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let data = gen()
+            .col("x", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(1000), BatchCount::from(1));
+        let mut dataset = Dataset::write(data, test_uri, None).await.unwrap();
+        let params = ZoneMapIndexParams::default().with_rows_per_zone(100);
+        dataset
+            .create_index(
+                &["x"],
+                IndexType::ZoneMap,
+                Some("x_idx".into()),
+                &params,
+                false,
+            )
+            .await
+            .unwrap();
+
+        async fn count_rows(dataset: &Dataset, filter: &str) -> usize {
+            let plan = dataset
+                .scan()
+                .filter(filter)
+                .unwrap()
+                .explain_plan(true)
+                .await
+                .unwrap();
+            assert!(plan.contains("MaterializeIndex"), "{}", plan);
+            dataset
+                .scan()
+                .filter(filter)
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap()
+                .num_rows()
+        }
+
+        // The zones are rechecked so the results are exact
+        assert_eq!(count_rows(&dataset, "x >= 250 AND x < 260").await, 10);
+        assert_eq!(count_rows(&dataset, "x IN (5, 500, 5000)").await, 2);
+
+        // Appended rows are added to the index by optimize_indices
+        let data = gen()
+            .col("x", array::step_custom::<Int32Type>(1000, 1))
+            .into_reader_rows(RowCount::from(200), BatchCount::from(1));
+        dataset.append(data, None).await.unwrap();
+        dataset
+            .optimize_indices(&OptimizeOptions::append())
+            .await
+            .unwrap();
+        let stats: serde_json::Value =
+            serde_json::from_str(&dataset.index_statistics("x_idx").await.unwrap()).unwrap();
+        assert_eq!(stats["num_unindexed_rows"], 0);
+        assert_eq!(count_rows(&dataset, "x > 1100").await, 99);
+
+        // The zones are remapped to the compacted fragment
+        dataset.delete("x < 100").await.unwrap();
+        compact_files(&mut dataset, CompactionOptions::default(), None)
+            .await
+            .unwrap();
+        let stats: serde_json::Value =
+            serde_json::from_str(&dataset.index_statistics("x_idx").await.unwrap()).unwrap();
+        assert_eq!(stats["num_unindexed_rows"], 0);
+        assert_eq!(count_rows(&dataset, "x > 1100").await, 99);
+        assert_eq!(count_rows(&dataset, "x BETWEEN 50 AND 150").await, 51);
+    }
+
+    #[tokio::test]
+    async fn test_composite_btree_index() {
+        let test_dir = tempdir().unwrap();
+        let test_uri = test_dir.path().to_str().unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("tenant_id", DataType::Int32, false),
+            Field::new("event_time", DataType::Int64, false),
+        ]));
+        let events = |range: std::ops::Range<i64>| {
+            let tenants = Int32Array::from_iter_values(range.clone().map(|i| (i % 10) as i32));
+            let times = Int64Array::from_iter_values(range);
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(tenants), Arc::new(times)])
+                    .unwrap();
+            RecordBatchIterator::new(vec![Ok(batch)], schema.clone())
+        };
+        let mut dataset = Dataset::write(events(0..1000), test_uri, None)
+            .await
+            .unwrap();
+
+        // Only btree indices can span more than one column
+        assert!(dataset
+            .create_index(
+                &["tenant_id", "event_time"],
+                IndexType::Bitmap,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .is_err());
+        dataset
+            .create_index(
+                &["tenant_id", "event_time"],
+                IndexType::BTree,
+                None,
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+        let indices = dataset.load_indices().await.unwrap();
+        assert_eq!(indices[0].name, "tenant_id_event_time_idx");
+        assert_eq!(indices[0].fields.len(), 2);
+
+        async fn count_rows(dataset: &Dataset, filter: &str) -> usize {
+            let plan = dataset
+                .scan()
+                .filter(filter)
+                .unwrap()
+                .explain_plan(true)
+                .await
+                .unwrap();
+            assert!(plan.contains("MaterializeIndex"), "{}", plan);
+            dataset
+                .scan()
+                .filter(filter)
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap()
+                .num_rows()
+        }
+
+        let range = "tenant_id = 3 AND event_time >= 100 AND event_time < 300";
+        assert_eq!(count_rows(&dataset, range).await, 20);
+        assert_eq!(
+            count_rows(&dataset, "event_time = 503 AND tenant_id = 3").await,
+            1
+        );
+        assert_eq!(count_rows(&dataset, "tenant_id = 3").await, 100);
+        assert_eq!(count_rows(&dataset, "tenant_id > 7").await, 200);
+
+        // Appended rows are added to the index by optimize_indices
+        dataset.append(events(1000..1200), None).await.unwrap();
+        dataset
+            .optimize_indices(&OptimizeOptions::append())
+            .await
+            .unwrap();
+        let stats: serde_json::Value = serde_json::from_str(
+            &dataset
+                .index_statistics("tenant_id_event_time_idx")
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stats["num_unindexed_rows"], 0);
+        assert_eq!(
+            count_rows(&dataset, "tenant_id = 3 AND event_time >= 900").await,
+            30
+        );
+
+        // The keys are remapped to the compacted fragment
+        dataset.delete("event_time < 150").await.unwrap();
+        compact_files(&mut dataset, CompactionOptions::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(count_rows(&dataset, range).await, 15);
+    }
+
     #[tokio::test]
     async fn test_load_indices() {
         let session = Arc::new(Session::default());
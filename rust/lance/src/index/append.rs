@@ -3,8 +3,11 @@
 
 use std::sync::Arc;
 
-use lance_core::{Error, Result};
+use arrow_schema::DataType;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use lance_core::{error::LanceOptionExt, Error, Result};
 use lance_index::optimize::OptimizeOptions;
+use lance_index::scalar::composite::encode_composite_stream;
 use lance_index::scalar::lance_format::LanceIndexStore;
 use lance_index::IndexType;
 use lance_index::{metrics::NoOpMetricsCollector, scalar::inverted::InvertedIndex};
@@ -13,6 +16,7 @@ use roaring::RoaringBitmap;
 use snafu::location;
 use uuid::Uuid;
 
+use super::scalar::sorted_list_values;
 use super::vector::ivf::optimize_vector_indices;
 use super::DatasetIndexInternalExt;
 use crate::dataset::index::LanceIndexStoreExt;
@@ -58,11 +62,19 @@ pub async fn merge_indices<'a>(
             ),
             location: location!(),
         })?;
+    // Scalar indices can be on nested fields which are referred to by their path
+    let column_path = dataset.schema().field_path(column.id).expect_ok()?;
+    // Composite btree indices are keyed on more than one column
+    let key_paths = old_indices[0]
+        .fields
+        .iter()
+        .map(|field_id| dataset.schema().field_path(*field_id).expect_ok())
+        .collect::<Result<Vec<_>>>()?;
 
     let mut indices = Vec::with_capacity(old_indices.len());
     for idx in old_indices {
         let index = dataset
-            .open_generic_index(&column.name, &idx.uuid.to_string(), &NoOpMetricsCollector)
+            .open_generic_index(&column_path, &idx.uuid.to_string(), &NoOpMetricsCollector)
             .await?;
         indices.push(index);
     }
@@ -94,7 +106,7 @@ pub async fn merge_indices<'a>(
 
             let index = dataset
                 .open_scalar_index(
-                    &column.name,
+                    &column_path,
                     &old_indices[0].uuid.to_string(),
                     &NoOpMetricsCollector,
                 )
@@ -117,24 +129,45 @@ pub async fn merge_indices<'a>(
                 _ => false,
             };
 
+            // A btree on a field inside of a list (e.g. `tags.name`) is sorted by the values of
+            // the lists after they have been flattened
+            let is_list_values = index.index_type() == IndexType::BTree
+                && dataset.schema().column_data_type(&column_path).as_ref()
+                    != Some(&column.data_type());
+
             let mut scanner = dataset.scan();
             let orodering = match index.index_type() {
-                IndexType::Inverted => None,
-                _ => Some(vec![ColumnOrdering::asc_nulls_first(column.name.clone())]),
+                _ if is_list_values => None,
+                // Bloom filters and zone maps are built over blocks of consecutive rows
+                IndexType::Inverted | IndexType::BloomFilter | IndexType::ZoneMap => None,
+                _ => Some(
+                    key_paths
+                        .iter()
+                        .map(|path| ColumnOrdering::asc_nulls_first(path.clone()))
+                        .collect(),
+                ),
             };
             scanner
                 .with_row_id()
                 .order_by(orodering)?
-                .project(&[&column.name])?;
+                .project(&key_paths)?;
             if !need_full_data {
                 scanner.with_fragments(unindexed);
             }
-            let new_data_stream = scanner.try_into_stream().await?;
+            let mut new_data_stream: SendableRecordBatchStream =
+                scanner.try_into_stream().await?.into();
+            if key_paths.len() > 1 {
+                new_data_stream = encode_composite_stream(new_data_stream);
+            } else if is_list_values {
+                let use_spilling =
+                    !matches!(column.data_type(), DataType::Utf8 | DataType::LargeUtf8);
+                new_data_stream = sorted_list_values(new_data_stream, use_spilling)?;
+            }
 
             let new_uuid = Uuid::new_v4();
 
             let new_store = LanceIndexStore::from_dataset(&dataset, &new_uuid.to_string());
-            index.update(new_data_stream.into(), &new_store).await?;
+            index.update(new_data_stream, &new_store).await?;
 
             Ok((new_uuid, 1))
         }
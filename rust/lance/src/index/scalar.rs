@@ -12,21 +12,30 @@ use crate::{
     dataset::{index::LanceIndexStoreExt, scanner::ColumnOrdering},
     Dataset,
 };
-use arrow_schema::DataType;
+use arrow_schema::{DataType, SortOptions};
 use async_trait::async_trait;
+use datafusion::physical_plan::sorts::sort::SortExec;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::SendableRecordBatchStream;
+use datafusion_physical_expr::{expressions::Column, LexOrdering, PhysicalSortExpr};
 use futures::TryStreamExt;
 use lance_core::datatypes::Field;
 use lance_core::{Error, Result};
-use lance_datafusion::{chunker::chunk_concat_stream, exec::LanceExecutionOptions};
+use lance_datafusion::{
+    chunker::chunk_concat_stream,
+    exec::{execute_plan, LanceExecutionOptions, OneShotExec},
+};
 use lance_index::metrics::MetricsCollector;
 use lance_index::scalar::{
-    btree::DEFAULT_BTREE_BATCH_SIZE, inverted::tokenizer::InvertedIndexParams,
+    bloom_filter::{train_bloom_filter_index, BloomFilterIndex, BloomFilterIndexParams},
+    btree::DEFAULT_BTREE_BATCH_SIZE,
+    composite::{check_composite_key_type, CompositeKeyTrainingSource},
+    inverted::tokenizer::InvertedIndexParams,
+    zone_map::{train_zone_map_index, ZoneMapIndex, ZoneMapIndexParams},
 };
 use lance_index::scalar::{
     inverted::METADATA_FILE,
-    ngram::{train_ngram_index, NGramIndex},
+    ngram::{train_ngram_index, NGramIndex, NGramIndexParams},
 };
 use lance_index::ScalarIndexCriteria;
 use lance_index::{
@@ -35,7 +44,7 @@ use lance_index::{
         btree::{train_btree_index, BTreeIndex, TrainingSource},
         flat::FlatIndexMetadata,
         inverted::{train_inverted_index, InvertedIndex, INVERT_LIST_FILE},
-        label_list::{train_label_list_index, LabelListIndex},
+        label_list::{train_label_list_index, unnest_chunks, LabelListIndex},
         lance_format::LanceIndexStore,
         ScalarIndex, ScalarIndexParams, ScalarIndexType,
     },
@@ -51,7 +60,9 @@ const TRAINING_UPDATE_FREQ: usize = 1000000;
 
 pub(crate) struct TrainingRequest {
     dataset: Arc<Dataset>,
-    column: String,
+    // More than one column is only used for composite indices, each batch will contain
+    // the columns (in order) followed by the row ids
+    columns: Vec<String>,
 }
 
 #[async_trait]
@@ -73,7 +84,14 @@ impl TrainingSource for TrainingRequest {
 
 impl TrainingRequest {
     pub fn new(dataset: Arc<Dataset>, column: String) -> Self {
-        Self { dataset, column }
+        Self {
+            dataset,
+            columns: vec![column],
+        }
+    }
+
+    pub fn new_composite(dataset: Arc<Dataset>, columns: Vec<String>) -> Self {
+        Self { dataset, columns }
     }
 
     async fn scan_chunks(
@@ -85,33 +103,41 @@ impl TrainingRequest {
 
         let mut scan = self.dataset.scan();
 
-        let column_field =
-            self.dataset
+        let mut use_spilling = true;
+        for column in &self.columns {
+            let column_field = self
+                .dataset
                 .schema()
-                .field(&self.column)
+                .field(column)
                 .ok_or(Error::InvalidInput {
-                    source: format!("No column with name {}", self.column).into(),
+                    source: format!("No column with name {}", column).into(),
                     location: location!(),
                 })?;
 
-        // Datafusion currently has bugs with spilling on string columns
-        // See https://github.com/apache/datafusion/issues/10073
-        //
-        // One we upgrade we can remove this
-        let use_spilling = !matches!(
-            column_field.data_type(),
-            DataType::Utf8 | DataType::LargeUtf8
-        );
+            // Datafusion currently has bugs with spilling on string columns
+            // See https://github.com/apache/datafusion/issues/10073
+            //
+            // One we upgrade we can remove this
+            use_spilling &= !matches!(
+                column_field.data_type(),
+                DataType::Utf8 | DataType::LargeUtf8
+            );
+        }
 
         let ordering = match sort {
-            true => Some(vec![ColumnOrdering::asc_nulls_first(self.column.clone())]),
+            true => Some(
+                self.columns
+                    .iter()
+                    .map(|column| ColumnOrdering::asc_nulls_first(column.clone()))
+                    .collect(),
+            ),
             false => None,
         };
 
         let scan = scan
             .with_row_id()
             .order_by(ordering)?
-            .project(&[&self.column])?;
+            .project(&self.columns)?;
 
         let batches = scan
             .try_into_dfstream(LanceExecutionOptions {
@@ -127,7 +153,8 @@ impl TrainingRequest {
         let training_uuid = uuid::Uuid::new_v4().to_string();
         info!(
             "Starting index training job with id {} on column {}",
-            training_uuid, self.column
+            training_uuid,
+            self.columns.join(", ")
         );
         info!("Training index (job_id={}): 0/{}", training_uuid, num_rows);
         let batches = batches.map_ok(move |batch| {
@@ -146,6 +173,64 @@ impl TrainingRequest {
     }
 }
 
+/// Flattens a stream of lists of values into a stream of the values, sorted nulls first
+///
+/// This is used to index a field inside of a list (e.g. `tags.name`) with a btree.  Each
+/// value is paired with the row id of the row that contains it.
+pub(crate) fn sorted_list_values(
+    source: SendableRecordBatchStream,
+    use_spilling: bool,
+) -> Result<SendableRecordBatchStream> {
+    let values = unnest_chunks(source)?;
+    let sort_expr = PhysicalSortExpr {
+        expr: Arc::new(Column::new(values.schema().field(0).name(), 0)),
+        options: SortOptions {
+            descending: false,
+            nulls_first: true,
+        },
+    };
+    let sort = SortExec::new(
+        LexOrdering::new(vec![sort_expr]),
+        Arc::new(OneShotExec::new(values)),
+    );
+    execute_plan(
+        Arc::new(sort),
+        LanceExecutionOptions {
+            use_spilling,
+            ..Default::default()
+        },
+    )
+}
+
+/// A training source for the values of a field inside of a list (e.g. `tags.name`)
+///
+/// The rows can't be ordered by the values in the scan (each row has a list of values) so
+/// the values are flattened and sorted after the scan.
+struct ListValuesTrainingSource {
+    source: Box<TrainingRequest>,
+    use_spilling: bool,
+}
+
+#[async_trait]
+impl TrainingSource for ListValuesTrainingSource {
+    async fn scan_ordered_chunks(
+        self: Box<Self>,
+        chunk_size: u32,
+    ) -> Result<SendableRecordBatchStream> {
+        let source = self.source.scan_unordered_chunks(chunk_size).await?;
+        let values = sorted_list_values(source, self.use_spilling)?;
+        Ok(chunk_concat_stream(values, chunk_size as usize))
+    }
+
+    async fn scan_unordered_chunks(
+        self: Box<Self>,
+        chunk_size: u32,
+    ) -> Result<SendableRecordBatchStream> {
+        let source = self.source.scan_unordered_chunks(chunk_size).await?;
+        unnest_chunks(source)
+    }
+}
+
 /// A trait used by the planner to determine how it can use a scalar index
 //
 // This may go away at some point but the scanner is a weak spot if we want
@@ -171,11 +256,21 @@ fn label_list_index_details() -> prost_types::Any {
     prost_types::Any::from_msg(&details).unwrap()
 }
 
-fn ngram_index_details() -> prost_types::Any {
+pub(super) fn ngram_index_details() -> prost_types::Any {
     let details = lance_table::format::pb::NGramIndexDetails {};
     prost_types::Any::from_msg(&details).unwrap()
 }
 
+pub(super) fn bloom_filter_index_details() -> prost_types::Any {
+    let details = lance_table::format::pb::BloomFilterIndexDetails {};
+    prost_types::Any::from_msg(&details).unwrap()
+}
+
+pub(super) fn zone_map_index_details() -> prost_types::Any {
+    let details = lance_table::format::pb::ZoneMapIndexDetails {};
+    prost_types::Any::from_msg(&details).unwrap()
+}
+
 pub(super) fn inverted_index_details() -> prost_types::Any {
     let details = lance_table::format::pb::InvertedIndexDetails::default();
     prost_types::Any::from_msg(&details).unwrap()
@@ -211,6 +306,18 @@ impl ScalarIndexDetails for lance_table::format::pb::NGramIndexDetails {
     }
 }
 
+impl ScalarIndexDetails for lance_table::format::pb::BloomFilterIndexDetails {
+    fn get_type(&self) -> ScalarIndexType {
+        ScalarIndexType::BloomFilter
+    }
+}
+
+impl ScalarIndexDetails for lance_table::format::pb::ZoneMapIndexDetails {
+    fn get_type(&self) -> ScalarIndexType {
+        ScalarIndexType::ZoneMap
+    }
+}
+
 fn get_scalar_index_details(
     details: &prost_types::Any,
 ) -> Result<Option<Box<dyn ScalarIndexDetails>>> {
@@ -234,6 +341,14 @@ fn get_scalar_index_details(
         Ok(Some(Box::new(
             details.to_msg::<lance_table::format::pb::NGramIndexDetails>()?,
         )))
+    } else if details.type_url.ends_with("BloomFilterIndexDetails") {
+        Ok(Some(Box::new(
+            details.to_msg::<lance_table::format::pb::BloomFilterIndexDetails>()?,
+        )))
+    } else if details.type_url.ends_with("ZoneMapIndexDetails") {
+        Ok(Some(Box::new(
+            details.to_msg::<lance_table::format::pb::ZoneMapIndexDetails>()?,
+        )))
     } else {
         Ok(None)
     }
@@ -259,42 +374,60 @@ pub(super) async fn build_scalar_index(
     uuid: &str,
     params: &ScalarIndexParams,
 ) -> Result<prost_types::Any> {
-    let training_request = Box::new(TrainingRequest {
-        dataset: Arc::new(dataset.clone()),
-        column: column.to_string(),
-    });
+    let training_request = Box::new(TrainingRequest::new(
+        Arc::new(dataset.clone()),
+        column.to_string(),
+    ));
     let field = dataset.schema().field(column).ok_or(Error::InvalidInput {
         source: format!("No column with name {}", column).into(),
         location: location!(),
     })?;
 
+    // A field inside of a list (e.g. `tags.name`) is read as a list of its values
+    let data_type = dataset
+        .schema()
+        .column_data_type(column)
+        .unwrap_or_else(|| field.data_type());
+
     // Check if LabelList index is being created on a non-List or non-LargeList type
     if matches!(params.force_index_type, Some(ScalarIndexType::LabelList))
-        && !matches!(
-            field.data_type(),
-            DataType::List(_) | DataType::LargeList(_)
-        )
+        && !matches!(data_type, DataType::List(_) | DataType::LargeList(_))
     {
         return Err(Error::InvalidInput {
             source: format!(
                 "LabelList index can only be created on List or LargeList type columns. Column '{}' has type {:?}",
                 column,
-                field.data_type()
+                data_type
             )
             .into(),
             location: location!(),
         });
     }
 
+    // A btree on a field inside of a list indexes the values of the lists, each value maps
+    // to the row that contains it
+    let is_list_values = data_type != field.data_type();
+
     // In theory it should be possible to create a btree/bitmap index on a nested field but
     // performance would be poor and I'm not sure we want to allow that unless there is a need.
-    if !matches!(params.force_index_type, Some(ScalarIndexType::LabelList))
-        && field.data_type().is_nested()
-    {
-        return Err(Error::InvalidInput {
-            source: "A scalar index can only be created on a non-nested field.".into(),
-            location: location!(),
-        });
+    if !matches!(params.force_index_type, Some(ScalarIndexType::LabelList)) {
+        if is_list_values && !matches!(params.force_index_type, None | Some(ScalarIndexType::BTree))
+        {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "Column '{}' is inside of a list and can only be indexed with a BTree or LabelList index",
+                    column
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        if field.data_type().is_nested() {
+            return Err(Error::InvalidInput {
+                source: "A scalar index can only be created on a non-nested field.".into(),
+                location: location!(),
+            });
+        }
     }
     let index_store = LanceIndexStore::from_dataset(dataset, uuid);
     match params.force_index_type {
@@ -316,15 +449,43 @@ pub(super) async fn build_scalar_index(
             Ok(inverted_index_details())
         }
         Some(ScalarIndexType::NGram) => {
-            if field.data_type() != DataType::Utf8 && field.data_type() != DataType::LargeUtf8 {
-                return Err(Error::InvalidInput {
-                    source: "NGram index can only be created on Utf8/LargeUtf8 type columns".into(),
-                    location: location!(),
-                });
-            }
-            train_ngram_index(training_request, &index_store).await?;
+            build_ngram_index(dataset, column, uuid, &NGramIndexParams::default()).await?;
             Ok(ngram_index_details())
         }
+        Some(ScalarIndexType::BloomFilter) => {
+            train_bloom_filter_index(
+                training_request,
+                &index_store,
+                BloomFilterIndexParams::default(),
+            )
+            .await?;
+            Ok(bloom_filter_index_details())
+        }
+        Some(ScalarIndexType::ZoneMap) => {
+            train_zone_map_index(
+                training_request,
+                &index_store,
+                ZoneMapIndexParams::default(),
+            )
+            .await?;
+            Ok(zone_map_index_details())
+        }
+        _ if is_list_values => {
+            let flat_index_trainer = FlatIndexMetadata::new(field.data_type());
+            let use_spilling = !matches!(field.data_type(), DataType::Utf8 | DataType::LargeUtf8);
+            let list_values = Box::new(ListValuesTrainingSource {
+                source: training_request,
+                use_spilling,
+            });
+            train_btree_index(
+                list_values,
+                &flat_index_trainer,
+                &index_store,
+                DEFAULT_BTREE_BATCH_SIZE as u32,
+            )
+            .await?;
+            Ok(btree_index_details())
+        }
         _ => {
             let flat_index_trainer = FlatIndexMetadata::new(field.data_type());
             train_btree_index(
@@ -339,6 +500,105 @@ pub(super) async fn build_scalar_index(
     }
 }
 
+/// Build a composite BTree index on more than one column
+///
+/// The index is a btree index on binary keys that encode the values of the columns
+#[instrument(level = "debug", skip_all)]
+pub(super) async fn build_composite_btree_index(
+    dataset: &Dataset,
+    columns: &[&str],
+    uuid: &str,
+) -> Result<prost_types::Any> {
+    for column in columns {
+        let field = dataset.schema().field(column).ok_or(Error::InvalidInput {
+            source: format!("No column with name {}", column).into(),
+            location: location!(),
+        })?;
+        if dataset.schema().column_data_type(column) != Some(field.data_type()) {
+            return Err(Error::InvalidInput {
+                source: format!(
+                    "Column '{}' is inside of a list and cannot be part of a composite index",
+                    column
+                )
+                .into(),
+                location: location!(),
+            });
+        }
+        check_composite_key_type(&field.data_type())?;
+    }
+    let training_request = Box::new(TrainingRequest::new_composite(
+        Arc::new(dataset.clone()),
+        columns.iter().map(|column| column.to_string()).collect(),
+    ));
+    let index_store = LanceIndexStore::from_dataset(dataset, uuid);
+    train_btree_index(
+        Box::new(CompositeKeyTrainingSource::new(training_request)),
+        &FlatIndexMetadata::new(DataType::Binary),
+        &index_store,
+        DEFAULT_BTREE_BATCH_SIZE as u32,
+    )
+    .await?;
+    Ok(btree_index_details())
+}
+
+/// Build a Bloom Filter Index
+#[instrument(level = "debug", skip_all)]
+pub(super) async fn build_bloom_filter_index(
+    dataset: &Dataset,
+    column: &str,
+    uuid: &str,
+    params: &BloomFilterIndexParams,
+) -> Result<()> {
+    let training_request = Box::new(TrainingRequest::new(
+        Arc::new(dataset.clone()),
+        column.to_string(),
+    ));
+    let index_store = LanceIndexStore::from_dataset(dataset, uuid);
+    train_bloom_filter_index(training_request, &index_store, params.clone()).await
+}
+
+/// Build an NGram Index
+#[instrument(level = "debug", skip_all)]
+pub(super) async fn build_ngram_index(
+    dataset: &Dataset,
+    column: &str,
+    uuid: &str,
+    params: &NGramIndexParams,
+) -> Result<()> {
+    let field = dataset.schema().field(column).ok_or(Error::InvalidInput {
+        source: format!("No column with name {}", column).into(),
+        location: location!(),
+    })?;
+    if field.data_type() != DataType::Utf8 && field.data_type() != DataType::LargeUtf8 {
+        return Err(Error::InvalidInput {
+            source: "NGram index can only be created on Utf8/LargeUtf8 type columns".into(),
+            location: location!(),
+        });
+    }
+    let training_request = Box::new(TrainingRequest::new(
+        Arc::new(dataset.clone()),
+        column.to_string(),
+    ));
+    let index_store = LanceIndexStore::from_dataset(dataset, uuid);
+    train_ngram_index(training_request, &index_store, params.clone()).await
+}
+
+/// Build a Zone Map Index
+#[instrument(level = "debug", skip_all)]
+pub(super) async fn build_zone_map_index(
+    dataset: &Dataset,
+    column: &str,
+    uuid: &str,
+    params: &ZoneMapIndexParams,
+) -> Result<()> {
+    let training_request = Box::new(TrainingRequest::new(
+        Arc::new(dataset.clone()),
+        column.to_string(),
+    ));
+    let index_store = LanceIndexStore::from_dataset(dataset, uuid);
+    train_zone_map_index(training_request, &index_store, params.clone()).await
+}
+
 /// Build a Scalar Index
 #[instrument(level = "debug", skip_all)]
 pub(super) async fn build_inverted_index(
@@ -347,10 +607,10 @@ pub(super) async fn build_inverted_index(
     uuid: &str,
     params: &InvertedIndexParams,
 ) -> Result<()> {
-    let training_request = Box::new(TrainingRequest {
-        dataset: Arc::new(dataset.clone()),
-        column: column.to_string(),
-    });
+    let training_request = Box::new(TrainingRequest::new(
+        Arc::new(dataset.clone()),
+        column.to_string(),
+    ));
     let index_store = LanceIndexStore::from_dataset(dataset, uuid);
     train_inverted_index(training_request, &index_store, params.clone()).await
 }
@@ -386,6 +646,14 @@ pub async fn open_scalar_index(
             let btree_index = BTreeIndex::load(index_store, fri).await?;
             Ok(btree_index as Arc<dyn ScalarIndex>)
         }
+        ScalarIndexType::BloomFilter => {
+            let bloom_filter_index = BloomFilterIndex::load(index_store, fri).await?;
+            Ok(bloom_filter_index as Arc<dyn ScalarIndex>)
+        }
+        ScalarIndexType::ZoneMap => {
+            let zone_map_index = ZoneMapIndex::load(index_store, fri).await?;
+            Ok(zone_map_index as Arc<dyn ScalarIndex>)
+        }
     }
 }
 
@@ -406,7 +674,12 @@ async fn infer_scalar_index_type(
     let bitmap_page_lookup = index_dir.child(BITMAP_LOOKUP_NAME);
     let inverted_list_lookup = index_dir.child(METADATA_FILE);
     let legacy_inverted_list_lookup = index_dir.child(INVERT_LIST_FILE);
-    let index_type = if let DataType::List(_) = col.data_type() {
+    // A field inside of a list is indexed as a list of its values
+    let data_type = dataset
+        .schema()
+        .column_data_type(column)
+        .unwrap_or_else(|| col.data_type());
+    let index_type = if let DataType::List(_) = data_type {
         ScalarIndexType::LabelList
     } else if dataset.object_store.exists(&bitmap_page_lookup).await? {
         ScalarIndexType::Bitmap
@@ -507,11 +780,14 @@ pub fn index_matches_criteria(
             if index_type != expected_type {
                 return Ok(false);
             }
-            // We should not use FTS / NGram indices for exact equality queries
+            // We should not use FTS / NGram / bloom filter / zone map indices for exact equality queries
             // (i.e. merge insert with a join on the indexed column)
             if criteria.supports_exact_equality {
                 match index_type {
-                    ScalarIndexType::Inverted | ScalarIndexType::NGram => {
+                    ScalarIndexType::Inverted
+                    | ScalarIndexType::NGram
+                    | ScalarIndexType::BloomFilter
+                    | ScalarIndexType::ZoneMap => {
                         return Ok(false);
                     }
                     _ => {}
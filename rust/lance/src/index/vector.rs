@@ -8,10 +8,10 @@ use std::sync::Arc;
 use std::{any::Any, collections::HashMap};
 
 pub mod builder;
+pub mod cagra;
 pub mod ivf;
 pub mod pq;
 pub mod utils;
-pub mod cagra;
 
 #[cfg(test)]
 mod fixture_test;
@@ -22,12 +22,12 @@ use builder::IvfIndexBuilder;
 use lance_file::reader::FileReader;
 use lance_index::frag_reuse::FragReuseIndex;
 use lance_index::metrics::NoOpMetricsCollector;
+use lance_index::vector::cagra::CagraBuildParams;
 use lance_index::vector::flat::index::{FlatBinQuantizer, FlatIndex, FlatQuantizer};
 use lance_index::vector::hnsw::HNSW;
 use lance_index::vector::ivf::storage::IvfModel;
 use lance_index::vector::pq::ProductQuantizer;
 use lance_index::vector::v3::shuffler::IvfShuffler;
-use lance_index::vector::cagra::CagraBuildParams;
 use lance_index::vector::{
     hnsw::{
         builder::HnswBuildParams,
@@ -73,7 +73,6 @@ pub enum IndexFileVersion {
     V3,
 }
 
-// (TODO): May be nice to convert this into an enum to have one for the ivfpq stuff and one for cagra
 /// The parameters to build vector index.
 #[derive(Debug, Clone)]
 pub struct VectorIndexParams {
@@ -84,9 +83,6 @@ pub struct VectorIndexParams {
 
     /// The version of the index file.
     pub version: IndexFileVersion,
-
-    // If running cagra you will include params here 
-    pub cagra_params: Option<CagraBuildParams>,
 }
 
 impl VectorIndexParams {
@@ -102,7 +98,6 @@ impl VectorIndexParams {
             stages,
             metric_type,
             version: IndexFileVersion::V3,
-            cagra_params: None
         }
     }
 
@@ -136,7 +131,6 @@ impl VectorIndexParams {
             stages,
             metric_type,
             version: IndexFileVersion::V3,
-            cagra_params: None
         }
     }
 
@@ -151,7 +145,6 @@ impl VectorIndexParams {
             stages,
             metric_type,
             version: IndexFileVersion::V3,
-            cagra_params: None
         }
     }
 
@@ -172,7 +165,6 @@ impl VectorIndexParams {
             stages,
             metric_type,
             version: IndexFileVersion::V3,
-            cagra_params: None
         }
     }
 
@@ -193,22 +185,53 @@ impl VectorIndexParams {
             stages,
             metric_type,
             version: IndexFileVersion::V3,
-            cagra_params: None
         }
     }
 
+    /// Create index parameters for `CAGRA` index.
+    ///
+    /// Parameters
+    ///
+    ///  - `cagra_metric`: how to compute distance, i.e., `sqeuclidean`, `inner_product` or `cosine`.
+    ///  - `cagra_intermediate_graph_degree`: the degree of the kNN graph before pruning.
+    ///  - `cagra_graph_degree`: the degree of the final graph.
+    ///  - `cagra_build_algo`: how to build the kNN graph, i.e., `ivf` or `nn_descent`.
     pub fn cagra(
         cagra_metric: String,
         cagra_intermediate_graph_degree: u32,
         cagra_graph_degree: u32,
         cagra_build_algo: String,
-    ) -> Self {
+    ) -> Result<Self> {
+        let cagra_params = CagraBuildParams {
+            cagra_metric,
+            cagra_intermediate_graph_degree,
+            cagra_graph_degree,
+            cagra_build_algo,
+        };
+        let metric_type = cagra_params.distance_type()?;
+        Ok(Self::with_cagra_params(metric_type, cagra_params))
+    }
 
+    /// Create index parameters with `CAGRA` parameters.
+    ///
+    /// The graph is built with `metric_type`, which replaces the `cagra_metric` of `cagra`.
+    pub fn with_cagra_params(metric_type: MetricType, cagra: CagraBuildParams) -> Self {
+        let cagra = CagraBuildParams {
+            cagra_metric: metric_type.to_string(),
+            ..cagra
+        };
         Self {
-            stages: vec![], // unused
-            metric_type: MetricType::L2, // unused
-            version: IndexFileVersion::V3, // unused
-            cagra_params: Some(CagraBuildParams { cagra_metric, cagra_intermediate_graph_degree, cagra_graph_degree, cagra_build_algo }),
+            stages: vec![StageParams::Cagra(cagra)],
+            metric_type,
+            version: IndexFileVersion::V3,
+        }
+    }
+
+    /// Returns the CAGRA build parameters if this is a `CAGRA` index.
+    pub fn cagra_params(&self) -> Option<&CagraBuildParams> {
+        match self.stages.as_slice() {
+            [StageParams::Cagra(params)] => Some(params),
+            _ => None,
         }
     }
 }
@@ -253,10 +276,6 @@ fn is_ivf_hnsw(stages: &[StageParams]) -> bool {
     matches!(&stages[0], StageParams::Ivf(_)) && matches!(&stages[1], StageParams::Hnsw(_))
 }
 
-fn print_type<T>(_: &T) { 
-    println!("{:?}", std::any::type_name::<T>());
-}
-
 /// Build a Vector Index
 #[instrument(level = "debug", skip(dataset))]
 pub(crate) async fn build_vector_index(
@@ -267,34 +286,9 @@ pub(crate) async fn build_vector_index(
     params: &VectorIndexParams,
     fri: Option<Arc<FragReuseIndex>>,
 ) -> Result<()> {
-    if let Some(cagra_params) = &params.cagra_params {
-        // Getting the one embedding column of data         
-        let mut scanner = dataset.scan();
-        scanner.project(&[column])?;
-        scanner.with_row_id();
-        let batch = scanner.try_into_batch().await?;
-
-        let data = batch.column_by_name(column).ok_or(Error::Index {
-            message: format!("Column {} does not exist in returned batch", column),
-            location: location!(),
-        })?;
-
-        //Getting id column from data
-        let mut id_scanner = dataset.scan();
-        id_scanner.project(&["id"])?;
-        id_scanner.with_row_id();
-        let id_batch = id_scanner.try_into_batch().await?;
-
-        let ids = id_batch.column_by_name("id").ok_or(Error::Index {
-            message: format!("Column {} does not exist in returned batch", column),
-            location: location!(),
-        })?;
-
-        // Calls Builder.rs file under lance-index and calls python to create index
-        let _ = lance_index::vector::cagra::build_cagra_index(data, ids, cagra_params).await;
-
-        // Call cagra module in this create to save the index
-        return cagra::save_cagra_index(dataset, data, params.cagra_params.as_ref().unwrap(), column, name, uuid).await; // To change the unwrap part to if Some()
+    if let Some(cagra_params) = params.cagra_params() {
+        cagra_params.validate()?;
+        return cagra::build_cagra_index(dataset, column, uuid, cagra_params).await;
     }
 
     let stages = &params.stages;
@@ -323,7 +317,7 @@ pub(crate) async fn build_vector_index(
             });
         }
     }
-    
+
     let temp_dir = tempdir()?;
     let temp_dir_path = Path::from_filesystem_path(temp_dir.path())?;
     let shuffler = IvfShuffler::new(temp_dir_path, ivf_params.num_partitions);
@@ -367,7 +361,6 @@ pub(crate) async fn build_vector_index(
             }
         }
     } else if is_ivf_pq(stages) {
-        eprintln!("ivf pq vector");
         let len = stages.len();
         let StageParams::PQ(pq_params) = &stages[len - 1] else {
             return Err(Error::Index {
@@ -566,6 +559,24 @@ pub(crate) async fn open_vector_index(
                     location: location!(),
                 });
             }
+            Some(Stage::Cagra(_)) => {
+                if last_stage.is_some() {
+                    return Err(Error::Index {
+                        message: format!("Invalid vector index stages: {:?}", vec_idx.stages),
+                        location: location!(),
+                    });
+                };
+                // The graph and the vectors are stored in the index file, not in the stages
+                let cagra = cagra::CagraIndex::open(
+                    dataset.object_store.clone(),
+                    dataset.indices_dir(),
+                    uuid.to_owned(),
+                    &dataset.session.file_metadata_cache,
+                    fri.clone(),
+                )
+                .await?;
+                last_stage = Some(Arc::new(cagra));
+            }
             _ => {}
         }
     }
@@ -1,96 +1,234 @@
-use crate::index::vector::VectorIndexParams;
-use crate::Dataset;
-use crate::Result;
-use crate::index::prefilter::PreFilter;
-use crate::index::vector::IvfIndexPartitionStatistics;
-use crate::index::vector::IvfIndexStatistics;
-use roaring::RoaringBitmap;
-use lance_io::local::to_local_path;
-use lance_io::traits::Reader;
- use lance_index::vector::Query;
-use lance_index::pb;
-use lance_index::Index;
-use lance_index::pb::VectorIndex;
-use lance_index::vector::cagra::CagraBuildParams;
-use lance_index::IndexType;
-use snafu::location;
-use lance_index::vector::hnsw::HNSWIndex;
-use lance_index::vector::sq::ScalarQuantizer;
-use lance_index::vector::pq::ProductQuantizer;
-use lance_index::pb::{Cagra, VectorIndexStage};
-use lance_index::pb::vector_index_stage::Stage;
-use lance_index::metrics::MetricsCollector;
-use lance_index::metrics::NoOpMetricsCollector;
-use lance_core::Error;
-use lance_linalg::distance::MetricType;
-use std::sync::Arc;
-use arrow_array::{Array, FixedSizeListArray};
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! CAGRA graph index.
+//!
+//! The index file stores the row ids, the raw vectors (as `f32`) and the
+//! fixed-degree neighbor list of every vector. The whole index is loaded
+//! into memory when it's opened.
+//!
+
+use std::any::Any;
 use std::collections::HashMap;
-use lance_io::traits::WriteExt;
+use std::sync::Arc;
+
+use arrow::array::AsArray;
+use arrow::compute::concat_batches;
+use arrow_array::{
+    Array, ArrayRef, FixedSizeListArray, Float32Array, RecordBatch, UInt32Array, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema};
 use async_trait::async_trait;
-use std::any::Any;
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use deepsize::DeepSizeOf;
-use super::pq::PQIndex;
-use arrow_array::types::Float16Type;
-use arrow_array::RecordBatch;
-use arrow_array::types::Float32Type;
-use arrow_array::types::Float64Type;
-use arrow_array::cast::AsArray;
-use arrow_schema::DataType;
-use arrow_schema::Schema;
-use arrow::datatypes::UInt8Type;
-use lance_index::vector::VectorIndex as LanceIndexVectorIndex;
+use futures::{stream, TryStreamExt};
+use itertools::Itertools;
+use lance_arrow::RecordBatchExt;
+use lance_core::cache::FileMetadataCache;
+use lance_core::utils::tokio::spawn_cpu;
+use lance_core::{Error, Result, ROW_ID, ROW_ID_FIELD};
+use lance_encoding::decoder::{DecoderPlugins, FilterExpression};
+use lance_file::v2::reader::{FileReader, FileReaderOptions};
+use lance_file::v2::writer::FileWriter;
+use lance_index::frag_reuse::FragReuseIndex;
+use lance_index::metrics::MetricsCollector;
+use lance_index::vector::cagra::{
+    build_cagra_graph, neighbors_field, CagraBuildParams, CagraGraph, CagraSearchParams,
+    CAGRA_METADATA_KEY, CAGRA_NEIGHBORS_COL, CAGRA_TYPE,
+};
+use lance_index::vector::flat::index::FlatQuantizer;
+use lance_index::vector::flat::storage::{FlatFloatStorage, FLAT_COLUMN};
+use lance_index::vector::graph::{OrderedFloat, OrderedNode, VisitedGenerator};
+use lance_index::vector::ivf::storage::IvfModel;
+use lance_index::vector::quantizer::{QuantizationType, Quantizer};
+use lance_index::vector::storage::{DistCalculator, VectorStore};
 use lance_index::vector::v3::subindex::SubIndexType;
+use lance_index::vector::{Query, VectorIndex, VECTOR_RESULT_SCHEMA};
+use lance_index::{Index, IndexMetadata, IndexType, INDEX_FILE_NAME, INDEX_METADATA_SCHEMA_KEY};
+use lance_io::object_store::ObjectStore;
+use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
+use lance_io::traits::Reader;
+use lance_io::utils::CachedFileSize;
+use lance_io::ReadBatchParams;
+use lance_linalg::distance::DistanceType;
+use object_store::path::Path;
+use prost::Message;
+use roaring::RoaringBitmap;
 use serde::Serialize;
+use snafu::location;
 use tracing::instrument;
-use arrow_array::UInt32Array;
-use datafusion::execution::SendableRecordBatchStream;
-use lance_index::vector::ivf::storage::IvfModel;
-use lance_index::vector::quantizer::Quantizer;
-use lance_index::vector::quantizer::QuantizationType;
 
-use crate::{
-    index::{INDEX_FILE_NAME}
-};
+use crate::dataset::Dataset;
+use crate::index::pb::{self, vector_index_stage::Stage};
+use crate::index::prefilter::PreFilter;
 
-#[derive(Debug)]
+/// Switch to flat search if the prefilter selects less than this
+/// percentage of the rows.
+const FLAT_SEARCH_PERCENTAGE: usize = 10;
+
+/// CAGRA graph index.
+#[derive(Debug, Clone)]
 pub struct CagraIndex {
     uuid: String,
 
-    index: String,
-}
+    graph: Arc<CagraGraph>,
 
-impl CagraIndex {
-    pub fn new(
-        uuid: String,
-        index: String,
-    ) -> Self {
-        Self {
-            uuid,
-            index
-        }
-    }
+    /// Row ids and raw vectors, the node id in the graph is the offset in the storage.
+    storage: Arc<FlatFloatStorage>,
+
+    distance_type: DistanceType,
+
+    /// The parameters the graph was built with.
+    params: CagraBuildParams,
+
+    // CAGRA has no partitions, this is an empty model to satisfy [VectorIndex::ivf_model].
+    ivf: IvfModel,
 }
 
 #[derive(Serialize)]
 pub struct CagraIndexStatistics {
     pub index_type: String,
-    // uuid: String,
-    // uri: String,
-    // metric_type: String,
-    // num_partitions: usize,
-    // sub_index: serde_json::Value,
-    // partitions: Vec<IvfIndexPartitionStatistics>,
-    // centroids: Vec<Vec<f32>>,
-    // loss: Option<f64>,
+    pub uuid: String,
+    pub metric_type: String,
+    pub num_rows: usize,
+    pub dimension: usize,
+    pub graph_degree: usize,
+    pub intermediate_graph_degree: u32,
+    pub build_algo: String,
 }
 
-// Used for calculating heap memory - something like this
 impl DeepSizeOf for CagraIndex {
     fn deep_size_of_children(&self, context: &mut deepsize::Context) -> usize {
         self.uuid.deep_size_of_children(context)
-            + self.index.deep_size_of_children(context)
-        // Skipping session since it is a weak ref
+            + self.graph.deep_size_of_children(context)
+            + self.storage.deep_size_of_children(context)
+    }
+}
+
+impl CagraIndex {
+    pub fn try_new(
+        uuid: String,
+        graph: CagraGraph,
+        storage: FlatFloatStorage,
+        distance_type: DistanceType,
+        params: CagraBuildParams,
+    ) -> Result<Self> {
+        if graph.len() != storage.len() {
+            return Err(Error::Index {
+                message: format!(
+                    "CAGRA graph has {} nodes but there are {} vectors",
+                    graph.len(),
+                    storage.len()
+                ),
+                location: location!(),
+            });
+        }
+        Ok(Self {
+            uuid,
+            graph: Arc::new(graph),
+            storage: Arc::new(storage),
+            distance_type,
+            params,
+            ivf: IvfModel::empty(),
+        })
+    }
+
+    /// Open the CAGRA index from the index directory.
+    pub(crate) async fn open(
+        object_store: Arc<ObjectStore>,
+        index_dir: Path,
+        uuid: String,
+        file_metadata_cache: &FileMetadataCache,
+        fri: Option<Arc<FragReuseIndex>>,
+    ) -> Result<Self> {
+        let scheduler_config = SchedulerConfig::max_bandwidth(&object_store);
+        let scheduler = ScanScheduler::new(object_store, scheduler_config);
+        let reader = FileReader::try_open(
+            scheduler
+                .open_file(
+                    &index_dir.child(uuid.as_str()).child(INDEX_FILE_NAME),
+                    &CachedFileSize::unknown(),
+                )
+                .await?,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            file_metadata_cache,
+            FileReaderOptions::default(),
+        )
+        .await?;
+        let index_metadata: IndexMetadata = serde_json::from_str(
+            reader
+                .schema()
+                .metadata
+                .get(INDEX_METADATA_SCHEMA_KEY)
+                .ok_or(Error::Index {
+                    message: format!("{} not found", INDEX_METADATA_SCHEMA_KEY),
+                    location: location!(),
+                })?,
+        )?;
+        let distance_type = DistanceType::try_from(index_metadata.distance_type.as_str())?;
+
+        let cagra_pos = reader
+            .schema()
+            .metadata
+            .get(CAGRA_METADATA_KEY)
+            .ok_or(Error::Index {
+                message: format!("{} not found", CAGRA_METADATA_KEY),
+                location: location!(),
+            })?
+            .parse()
+            .map_err(|e| Error::Index {
+                message: format!("Failed to decode CAGRA position: {}", e),
+                location: location!(),
+            })?;
+        let index_pb = pb::VectorIndex::decode(reader.read_global_buffer(cagra_pos).await?)?;
+        let params = match index_pb.stages.as_slice() {
+            [pb::VectorIndexStage {
+                stage: Some(Stage::Cagra(cagra_pb)),
+            }] => CagraBuildParams::from(cagra_pb),
+            stages => {
+                return Err(Error::Index {
+                    message: format!("Invalid CAGRA index stages: {:?}", stages),
+                    location: location!(),
+                });
+            }
+        };
+
+        let schema = Arc::new(reader.schema().as_ref().into());
+        let batches = reader
+            .read_stream(
+                ReadBatchParams::RangeFull,
+                u32::MAX,
+                1,
+                FilterExpression::no_filter(),
+            )?
+            .try_collect::<Vec<_>>()
+            .await?;
+        let batch = concat_batches(&schema, batches.iter())?;
+
+        let graph = CagraGraph::try_from_array(batch[CAGRA_NEIGHBORS_COL].as_fixed_size_list())?;
+        let storage = FlatFloatStorage::try_from_batch(
+            batch.project_by_schema(&Schema::new(vec![
+                ROW_ID_FIELD.clone(),
+                Field::new(FLAT_COLUMN, batch[FLAT_COLUMN].data_type().clone(), true),
+            ]))?,
+            distance_type,
+            fri,
+        )?;
+        Self::try_new(uuid, graph, storage, distance_type, params)
+    }
+
+    fn flat_search(
+        &self,
+        dist_calc: &impl DistCalculator,
+        k: usize,
+        node_ids: impl Iterator<Item = u32>,
+    ) -> Vec<OrderedNode> {
+        node_ids
+            .map(|id| OrderedNode::new(id, OrderedFloat(dist_calc.distance(id))))
+            .k_smallest(k)
+            .collect()
     }
 }
 
@@ -104,7 +242,7 @@ impl Index for CagraIndex {
         self
     }
 
-    fn as_vector_index(self: Arc<Self>) -> Result<Arc<dyn LanceIndexVectorIndex>> {
+    fn as_vector_index(self: Arc<Self>) -> Result<Arc<dyn VectorIndex>> {
         Ok(self)
     }
 
@@ -113,63 +251,128 @@ impl Index for CagraIndex {
     }
 
     async fn prewarm(&self) -> Result<()> {
-        // TODO: We should prewarm the IVF index by loading the partitions into memory
+        // The whole index is loaded into memory when it's opened.
         Ok(())
     }
 
     fn statistics(&self) -> Result<serde_json::Value> {
         Ok(serde_json::to_value(CagraIndexStatistics {
-            index_type: self.index_type().to_string()
+            index_type: self.index_type().to_string(),
+            uuid: self.uuid.clone(),
+            metric_type: self.distance_type.to_string(),
+            num_rows: self.storage.len(),
+            dimension: self.storage.dim(),
+            graph_degree: self.graph.degree(),
+            intermediate_graph_degree: self.params.cagra_intermediate_graph_degree,
+            build_algo: self.params.cagra_build_algo.clone(),
         })?)
     }
 
-    // Collect the ids of data fragments used by an index; Not sure if it's needed yet
     async fn calculate_included_frags(&self) -> Result<RoaringBitmap> {
         let mut frag_ids = RoaringBitmap::default();
-        // Options:
-        //  1. Compute frag ids from row ids (add row ids as a field in CagraIndex struct)
-        //  2. Have frag ids already in the CagraIndex struct and just clone and pass them here
-        //  3. Return empty frags if not needed
+        for row_id in self.storage.row_ids() {
+            frag_ids.insert((row_id >> 32) as u32);
+        }
         Ok(frag_ids)
     }
 }
 
 #[async_trait]
-impl LanceIndexVectorIndex for CagraIndex {
-    #[instrument(level = "debug", skip_all, name = "IVFIndex::search")]
+impl VectorIndex for CagraIndex {
+    #[instrument(level = "debug", skip_all, name = "CagraIndex::search")]
     async fn search(
         &self,
-        _query: &Query,
-        _pre_filter: Arc<dyn PreFilter>,
-        _metrics: &dyn MetricsCollector,
+        query: &Query,
+        pre_filter: Arc<dyn PreFilter>,
+        metrics: &dyn MetricsCollector,
     ) -> Result<RecordBatch> {
-        unimplemented!("search is not yet implemented for Cagra");
-    }
+        pre_filter.wait_for_ready().await?;
+        let key = match query.key.data_type() {
+            DataType::Float32 => query.key.clone(),
+            _ => arrow::compute::cast(&query.key, &DataType::Float32)?,
+        };
+        let dist_calc = self.storage.dist_calculator(key);
+        let num_rows = self.storage.len();
+
+        let mut bitset_generator = VisitedGenerator::new(0);
+        let bitset = if pre_filter.is_empty() {
+            None
+        } else {
+            let indices = pre_filter.filter_row_ids(Box::new(self.storage.row_ids()));
+            let mut bitset = bitset_generator.generate(num_rows);
+            for id in indices {
+                bitset.insert(id as u32);
+            }
+            Some(bitset)
+        };
+        let remained = bitset.as_ref().map(|b| b.count_ones()).unwrap_or(num_rows);
+
+        let results = if remained * 100 < num_rows * FLAT_SEARCH_PERCENTAGE {
+            let bitset = bitset.expect("the prefilter bitset must be set for flat search");
+            metrics.record_comparisons(remained);
+            self.flat_search(
+                &dist_calc,
+                query.k,
+                (0..num_rows as u32).filter(|id| bitset.contains(*id)),
+            )
+        } else {
+            let mut visited_generator = VisitedGenerator::new(num_rows);
+            let mut visited = visited_generator.generate(num_rows);
+            let results = self.graph.search(
+                &dist_calc,
+                query.k,
+                &CagraSearchParams::from(query),
+                bitset.as_ref(),
+                &mut visited,
+            );
+            metrics.record_comparisons(visited.count_ones());
+            results
+        };
 
-    // Getting partitions from empty vector since cagra won't have partitions
-    fn find_partitions(&self, query: &Query) -> Result<UInt32Array> {
-        //Ok(arrow_array::PrimitiveArray::<UInt32Type>::from(vec![]))
-        unimplemented!("Not supported for Cagra");
+        let lower_bound = query.lower_bound.unwrap_or(f32::MIN);
+        let upper_bound = query.upper_bound.unwrap_or(f32::MAX);
+        let (row_ids, dists): (Vec<_>, Vec<_>) = results
+            .into_iter()
+            .filter(|r| lower_bound <= r.dist.0 && r.dist.0 < upper_bound)
+            .map(|r| (self.storage.row_id(r.id), r.dist.0))
+            .unzip();
+        Ok(RecordBatch::try_new(
+            VECTOR_RESULT_SCHEMA.clone(),
+            vec![
+                Arc::new(Float32Array::from(dists)),
+                Arc::new(UInt64Array::from(row_ids)),
+            ],
+        )?)
     }
 
-    async fn search_in_partition(
-        &self,
-        _: usize,
-        _: &Query,
-        _: Arc<dyn PreFilter>,
-        _: &dyn MetricsCollector,
-    ) -> Result<RecordBatch> {
-        eprintln!("cagar in serach partition");
-
-        let schema = Arc::new(Schema::empty());
-        let batch = RecordBatch::new_empty(schema);
-        Ok(batch)
+    fn find_partitions(&self, _: &Query) -> Result<UInt32Array> {
+        // CAGRA is searched as a single partition.
+        Ok(UInt32Array::from(vec![0]))
     }
 
     fn total_partitions(&self) -> usize {
         1
     }
 
+    async fn search_in_partition(
+        &self,
+        partition_id: usize,
+        query: &Query,
+        pre_filter: Arc<dyn PreFilter>,
+        metrics: &dyn MetricsCollector,
+    ) -> Result<RecordBatch> {
+        if partition_id != 0 {
+            return Err(Error::Index {
+                message: format!(
+                    "partition id {} is out of range, CAGRA index has only 1 partition",
+                    partition_id
+                ),
+                location: location!(),
+            });
+        }
+        self.search(query, pre_filter, metrics).await
+    }
+
     fn is_loadable(&self) -> bool {
         false
     }
@@ -183,187 +386,420 @@ impl LanceIndexVectorIndex for CagraIndex {
         _reader: Arc<dyn Reader>,
         _offset: usize,
         _length: usize,
-    ) -> Result<Box<dyn LanceIndexVectorIndex>> {
-        // Err(Error::Index {
-        //     message: "Flat index does not support load".to_string(),
-        //     location: location!(),
-        // })
-        unimplemented!("load is not yet implemented for Cagra")
+    ) -> Result<Box<dyn VectorIndex>> {
+        // The whole index is already in memory, and the graph and vectors are shared.
+        Ok(Box::new(self.clone()))
     }
 
-    async fn to_batch_stream(&self, _with_vector: bool) -> Result<SendableRecordBatchStream> {
-        unimplemented!("to_batch_stream is not yet implemented for Cagra")
+    async fn to_batch_stream(&self, with_vector: bool) -> Result<SendableRecordBatchStream> {
+        let batch = self.storage.to_batches()?.next().ok_or(Error::Internal {
+            message: "CAGRA index storage is empty".to_string(),
+            location: location!(),
+        })?;
+        let batch = if with_vector {
+            batch
+        } else {
+            batch.project_by_schema(&Schema::new(vec![ROW_ID_FIELD.clone()]))?
+        };
+        let schema = batch.schema();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            stream::once(std::future::ready(Ok(batch))),
+        )))
     }
 
     fn num_rows(&self) -> u64 {
-        0 // TODO: Return actual number of rows indexed
+        self.storage.len() as u64
     }
 
-    fn row_ids(&self) -> Box<dyn Iterator<Item = &u64>> {
-        todo!("this method is for only IVF_HNSW_* index");
+    fn row_ids(&self) -> Box<dyn Iterator<Item = &'_ u64> + '_> {
+        Box::new(self.storage.row_ids())
     }
 
     async fn remap(&mut self, _mapping: &HashMap<u64, Option<u64>>) -> Result<()> {
-        // This will be needed if we want to clean up IVF to allow more than just
-        // one layer (e.g. IVF -> IVF -> PQ).  We need to pass on the call to
-        // remap to the lower layers.
+        Err(Error::Index {
+            message: "Remapping CAGRA index is not supported, please rebuild the index".to_string(),
+            location: location!(),
+        })
+    }
 
-        // Currently, remapping for IVF is implemented in remap_index_file which
-        // mirrors some of the other IVF routines like build_ivf_pq_index
+    async fn remap_to(
+        self: Arc<Self>,
+        _store: ObjectStore,
+        _mapping: &HashMap<u64, Option<u64>>,
+        _column: String,
+        _index_dir: Path,
+    ) -> Result<()> {
         Err(Error::Index {
-            message: "Remapping Cagra in this way not supported".to_string(),
+            message: "Remapping CAGRA index is not supported, please rebuild the index".to_string(),
             location: location!(),
         })
     }
 
+    fn metric_type(&self) -> DistanceType {
+        self.distance_type
+    }
+
     fn ivf_model(&self) -> &IvfModel {
-        panic!("Cagra does not use an IVF model")
+        &self.ivf
     }
 
     fn quantizer(&self) -> Quantizer {
-        panic!("Cagra does not use a quantizer")
+        Quantizer::Flat(FlatQuantizer::new(self.storage.dim(), self.distance_type))
     }
 
-    /// the index type of this vector index.
     fn sub_index_type(&self) -> (SubIndexType, QuantizationType) {
-        unimplemented!("Don't think this applys to cagra")
+        // The graph is searched over the raw vectors.
+        (SubIndexType::Flat, QuantizationType::Flat)
     }
+}
 
-    fn metric_type(&self) -> MetricType {
-        unimplemented!("Need to edit cagra index stuct to include this field");
+/// Build the CAGRA index on the column and write it to the index directory.
+#[instrument(level = "debug", skip(dataset, params))]
+pub(crate) async fn build_cagra_index(
+    dataset: &Dataset,
+    column: &str,
+    uuid: &str,
+    params: &CagraBuildParams,
+) -> Result<()> {
+    let distance_type = params.distance_type()?;
+    let field = dataset.schema().field(column).ok_or(Error::Index {
+        message: format!("Column {} does not exist in schema", column),
+        location: location!(),
+    })?;
+
+    let mut scanner = dataset.scan();
+    scanner.project(&[column])?.with_row_id();
+    if field.nullable {
+        scanner.filter_expr(datafusion_expr::col(column).is_not_null());
     }
+    let batch = scanner.try_into_batch().await?;
 
+    let vectors = batch[column].as_fixed_size_list_opt().ok_or(Error::Index {
+        message: format!(
+            "CAGRA index can only be built on fixed size list, column {} is {}",
+            column,
+            batch[column].data_type()
+        ),
+        location: location!(),
+    })?;
+    let vectors: FixedSizeListArray = match vectors.value_type() {
+        DataType::Float32 => vectors.clone(),
+        DataType::Float16 | DataType::Float64 => arrow::compute::cast(
+            vectors,
+            &DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                vectors.value_length(),
+            ),
+        )?
+        .as_fixed_size_list()
+        .clone(),
+        value_type => {
+            return Err(Error::Index {
+                message: format!(
+                    "CAGRA index can only be built on floating point vectors, got {}",
+                    value_type
+                ),
+                location: location!(),
+            });
+        }
+    };
+    let row_ids = batch[ROW_ID].clone();
+
+    let build_params = params.clone();
+    let graph_vectors = vectors.clone();
+    let graph = spawn_cpu(move || build_cagra_graph(&graph_vectors, &build_params)).await?;
+
+    let dim = vectors.value_length();
+    let vectors: ArrayRef = Arc::new(vectors);
+    let batch = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![
+            ROW_ID_FIELD.clone(),
+            Field::new(FLAT_COLUMN, vectors.data_type().clone(), true),
+            neighbors_field(graph.degree()),
+        ])),
+        vec![row_ids, vectors, Arc::new(graph.to_array()?)],
+    )?;
+
+    let index_path = dataset.indices_dir().child(uuid).child(INDEX_FILE_NAME);
+    let mut writer = FileWriter::try_new(
+        dataset.object_store().create(&index_path).await?,
+        batch.schema_ref().as_ref().try_into()?,
+        Default::default(),
+    )?;
+    writer.write_batch(&batch).await?;
+    let index_metadata = IndexMetadata {
+        index_type: CAGRA_TYPE.to_string(),
+        distance_type: distance_type.to_string(),
+    };
+    writer.add_schema_metadata(
+        INDEX_METADATA_SCHEMA_KEY,
+        serde_json::to_string(&index_metadata)?,
+    );
+    // the graph degree could be less than the requested one for small datasets
+    let cagra_pb = pb::Cagra {
+        graph_degree: graph.degree() as u32,
+        ..pb::Cagra::from(params)
+    };
+    let index_pb = pb::VectorIndex {
+        spec_version: 1,
+        dimension: dim as u32,
+        stages: vec![pb::VectorIndexStage {
+            stage: Some(Stage::Cagra(cagra_pb)),
+        }],
+        metric_type: pb::VectorMetricType::from(distance_type).into(),
+    };
+    let cagra_pos = writer
+        .add_global_buffer(index_pb.encode_to_vec().into())
+        .await?;
+    writer.add_schema_metadata(CAGRA_METADATA_KEY, cagra_pos.to_string());
+    writer.finish().await?;
 
+    Ok(())
 }
 
-#[derive(Debug)]
-pub struct CagraIndexMetaData {
-    // Index name
-    name: String,
-
-    // Column we built the index for
-    column: String,
-
-    // Version of dataset where index was built
-    dataset_version: u64,
-
-    // Metric used the build the algorithm
-    metric: String,
-
-    // Algorithm used to build index
-    algo: String,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::RecordBatchIterator;
+    use lance_arrow::FixedSizeListArrayExt;
+    use lance_index::{DatasetIndexExt, IndexType};
+    use lance_testing::datagen::generate_random_array;
+    use tempfile::tempdir;
+
+    use crate::dataset::WriteParams;
+    use crate::index::vector::{open_vector_index, VectorIndexParams};
+    use crate::index::DatasetIndexInternalExt;
+    use lance_index::metrics::NoOpMetricsCollector;
+
+    const DIM: usize = 16;
+
+    async fn create_dataset(uri: &str, num_rows: usize) -> (Dataset, FixedSizeListArray) {
+        let vectors = FixedSizeListArray::try_new_from_values(
+            generate_random_array(num_rows * DIM),
+            DIM as i32,
+        )
+        .unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("vector", vectors.data_type().clone(), false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from_iter_values(0..num_rows as u32)),
+                Arc::new(vectors.clone()),
+            ],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let dataset = Dataset::write(reader, uri, Some(WriteParams::default()))
+            .await
+            .unwrap();
+        (dataset, vectors)
+    }
 
-    // Dimension of one embedding
-    dimension: u32
+    fn cagra_params() -> VectorIndexParams {
+        VectorIndexParams::cagra("sqeuclidean".to_string(), 32, 16, "nn_descent".to_string())
+            .unwrap()
+    }
 
-}
+    #[test]
+    fn test_cagra_params_metric() {
+        let params =
+            VectorIndexParams::with_cagra_params(DistanceType::Cosine, CagraBuildParams::default());
+        assert_eq!(params.metric_type, DistanceType::Cosine);
+        assert_eq!(
+            params.cagra_params().unwrap().distance_type().unwrap(),
+            DistanceType::Cosine
+        );
+
+        let params =
+            VectorIndexParams::cagra("inner_product".to_string(), 32, 16, "ivf".to_string())
+                .unwrap();
+        assert_eq!(params.metric_type, DistanceType::Dot);
+        assert!(
+            VectorIndexParams::cagra("unknown".to_string(), 32, 16, "ivf".to_string()).is_err()
+        );
+    }
 
-impl CagraIndexMetaData {
-    pub fn new(
-        name: String,
-        column: String,
-        dataset_version: u64,
-        metric: String,
-        algo: String,
-        dimension: u32
-    ) -> Self {
-        Self {
-            name,
-            column,
-            dataset_version,
-            metric,
-            algo,
-            dimension
-        }
+    #[tokio::test]
+    async fn test_build_and_search_cagra() {
+        let test_dir = tempdir().unwrap();
+        let uri = test_dir.path().to_str().unwrap();
+        let num_rows = 1000;
+        let (mut dataset, vectors) = create_dataset(uri, num_rows).await;
+        dataset
+            .create_index(&["vector"], IndexType::Vector, None, &cagra_params(), true)
+            .await
+            .unwrap();
+
+        // reopen the dataset so the index is loaded from the disk
+        let dataset = Dataset::open(uri).await.unwrap();
+        let indices = dataset.load_indices().await.unwrap();
+        assert_eq!(indices.len(), 1);
+        let index = dataset
+            .open_vector_index(
+                "vector",
+                &indices[0].uuid.to_string(),
+                &NoOpMetricsCollector,
+            )
+            .await
+            .unwrap();
+        assert_eq!(index.index_type(), IndexType::Cagra);
+        assert_eq!(index.num_rows(), num_rows as u64);
+
+        let k = 10;
+        let query = vectors.value(42);
+        let results = dataset
+            .scan()
+            .nearest("vector", query.as_ref(), k)
+            .unwrap()
+            .project(&["id"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(results.num_rows(), k);
+        // the query vector itself is the closest one
+        assert_eq!(
+            results["id"]
+                .as_primitive::<arrow_array::types::UInt32Type>()
+                .value(0),
+            42
+        );
+
+        let plan = dataset
+            .scan()
+            .nearest("vector", query.as_ref(), k)
+            .unwrap()
+            .explain_plan(true)
+            .await
+            .unwrap();
+        assert!(plan.contains("ANNSubIndex"), "{}", plan);
     }
-}
 
-/// Convert a CagraIndexMetaData to protobuf payload
-impl TryFrom<&CagraIndexMetaData> for pb::Index {
-    type Error = Error;
+    #[tokio::test]
+    async fn test_reload_cagra_index() {
+        let test_dir = tempdir().unwrap();
+        let uri = test_dir.path().to_str().unwrap();
+        let num_rows = 300;
+        let (mut dataset, _) = create_dataset(uri, num_rows).await;
+        dataset
+            .create_index(&["vector"], IndexType::Vector, None, &cagra_params(), true)
+            .await
+            .unwrap();
+
+        let dataset = Dataset::open(uri).await.unwrap();
+        let stats: serde_json::Value =
+            serde_json::from_str(&dataset.index_statistics("vector_idx").await.unwrap()).unwrap();
+        assert_eq!(stats["index_type"], "CAGRA");
+        assert_eq!(stats["num_indexed_rows"], num_rows);
+        let index_stats = &stats["indices"][0];
+        assert_eq!(index_stats["metric_type"], "l2");
+        assert_eq!(index_stats["num_rows"], num_rows);
+        assert_eq!(index_stats["dimension"], DIM);
+        assert_eq!(index_stats["graph_degree"], 16);
+        assert_eq!(index_stats["intermediate_graph_degree"], 32);
+        assert_eq!(index_stats["build_algo"], "nn_descent");
+
+        // the index is served from the session cache once it's opened
+        let uuid = dataset.load_indices().await.unwrap()[0].uuid.to_string();
+        let index = dataset
+            .open_vector_index("vector", &uuid, &NoOpMetricsCollector)
+            .await
+            .unwrap();
+        let cached = dataset
+            .open_vector_index("vector", &uuid, &NoOpMetricsCollector)
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&index, &cached));
+
+        let reader: Arc<dyn Reader> = dataset
+            .object_store()
+            .open(&dataset.indices_dir().child(uuid).child(INDEX_FILE_NAME))
+            .await
+            .unwrap()
+            .into();
+        let loaded = index.load(reader, 0, 0).await.unwrap();
+        assert_eq!(loaded.num_rows(), num_rows as u64);
+        assert_eq!(loaded.metric_type(), DistanceType::L2);
+    }
 
-    fn try_from(idx: &CagraIndexMetaData) -> Result<Self> {
-        let cagra_stage = VectorIndexStage {
-            stage: Some(Stage::Cagra(Cagra {
-                build_algo: idx.algo.clone(),
-                metric: idx.metric.clone()
-            }))
+    #[tokio::test]
+    async fn test_open_cagra_stage() {
+        let test_dir = tempdir().unwrap();
+        let uri = test_dir.path().to_str().unwrap();
+        let num_rows = 300;
+        let (mut dataset, _) = create_dataset(uri, num_rows).await;
+        let params = cagra_params();
+        dataset
+            .create_index(&["vector"], IndexType::Vector, None, &params, true)
+            .await
+            .unwrap();
+
+        // the CAGRA stage opens the graph from the index file
+        let dataset = Dataset::open(uri).await.unwrap();
+        let uuid = dataset.load_indices().await.unwrap()[0].uuid.to_string();
+        let reader: Arc<dyn Reader> = dataset
+            .object_store()
+            .open(
+                &dataset
+                    .indices_dir()
+                    .child(uuid.as_str())
+                    .child(INDEX_FILE_NAME),
+            )
+            .await
+            .unwrap()
+            .into();
+        let vec_idx = pb::VectorIndex {
+            spec_version: 1,
+            dimension: DIM as u32,
+            stages: vec![pb::VectorIndexStage {
+                stage: Some(Stage::Cagra(pb::Cagra::from(
+                    params.cagra_params().unwrap(),
+                ))),
+            }],
+            metric_type: pb::VectorMetricType::L2.into(),
         };
-
-        let metric_conversion = HashMap::from([
-            ("sqeuclidean".to_string(), MetricType::L2),
-            ("inner_product".to_string(), MetricType::Dot)
-
-        ]);
-
-        let index = metric_conversion.get(idx.metric.as_str()).cloned().expect("Metric must be 'sqeuclidean' or 'inner product' ");
-
-        Ok(Self {
-            name: idx.name.clone(),
-            columns: vec![idx.column.clone()],
-            dataset_version: idx.dataset_version,
-            index_type: pb::IndexType::Vector.into(),
-            implementation: Some(pb::index::Implementation::VectorIndex(pb::VectorIndex {
-                spec_version: 1,
-                dimension: idx.dimension, //array.shape()[1]
-                stages: vec![cagra_stage],
-                metric_type: match index {
-                    MetricType::L2 => pb::VectorMetricType::L2.into(), // Mimic euclidean, closest for sequclidean
-                    MetricType::Dot => pb::VectorMetricType::Dot.into(), // Mimic inner_product
-                    _ => {
-                        return Err(Error::Index { 
-                            message: "unsupported cagra distance metric".to_string(),
-                            location: location!(),
-                        })
-                    }
-                },
-            })),
-        })
+        let index = open_vector_index(Arc::new(dataset), &uuid, &vec_idx, reader, None)
+            .await
+            .unwrap();
+        assert_eq!(index.index_type(), IndexType::Cagra);
+        assert_eq!(index.num_rows(), num_rows as u64);
+        assert_eq!(index.metric_type(), DistanceType::L2);
     }
-}
 
-pub fn extract_dimension(data: &Arc<dyn arrow_array::Array>) -> u32 {
-    if let Some(list_array) = data.as_any().downcast_ref::<FixedSizeListArray>() {
-        let dim = list_array.len();
-        return dim as u32;
-    } else {
-        return 0
+    #[tokio::test]
+    async fn test_search_cagra_with_prefilter() {
+        let test_dir = tempdir().unwrap();
+        let uri = test_dir.path().to_str().unwrap();
+        let (mut dataset, vectors) = create_dataset(uri, 1000).await;
+        dataset
+            .create_index(&["vector"], IndexType::Vector, None, &cagra_params(), true)
+            .await
+            .unwrap();
+
+        for filter in ["id % 2 = 0", "id < 50"] {
+            let results = dataset
+                .scan()
+                .nearest("vector", vectors.value(7).as_ref(), 10)
+                .unwrap()
+                .prefilter(true)
+                .filter(filter)
+                .unwrap()
+                .project(&["id"])
+                .unwrap()
+                .try_into_batch()
+                .await
+                .unwrap();
+            assert_eq!(results.num_rows(), 10);
+            let ids = results["id"].as_primitive::<arrow_array::types::UInt32Type>();
+            match filter {
+                "id % 2 = 0" => assert!(ids.values().iter().all(|id| id % 2 == 0)),
+                _ => assert!(ids.values().iter().all(|id| *id < 50)),
+            }
+        }
     }
 }
-
-pub async fn save_cagra_index(
-    dataset: &Dataset,
-    data: &Arc<dyn arrow_array::Array>,
-    params: &CagraBuildParams,
-    column: &str,
-    index_name: &str,
-    uuid: &str,
-) -> Result<()> {
-    let object_store = dataset.object_store();
-    let index_dir = dataset.indices_dir();
-    let dataset_version = dataset.version().version;
-
-    let path = index_dir.child(uuid).child(INDEX_FILE_NAME);
-    let mut writer = object_store.create(&path).await?;
-
-    let mut file = tokio::fs::File::open("/workspace/cagra_index.bin").await?; //eventually this should not be hardcoded
-    tokio::io::copy(&mut file, &mut writer).await?;
-
-
-    let metadata = CagraIndexMetaData {
-        name: index_name.to_string(),
-        column: column.to_string(),
-        dataset_version,
-        metric: params.cagra_metric.to_string(), // Change later to not be hardcoded
-        algo: params.cagra_build_algo.to_string(), // Change later to not be hardcoded
-        dimension: extract_dimension(data)
-    };
-
-    let metadata = pb::Index::try_from(&metadata)?;
-    let pos = writer.write_protobuf(&metadata).await?;
-    //writer.write_magics(pos, 0, 1, MAGIC).await?; //Still not sure about this line
-
-    writer.shutdown().await?;
-
-    Ok(())
-}
\ No newline at end of file
@@ -259,6 +259,13 @@ pub(crate) async fn optimize_vector_indices(
         });
     }
 
+    if existing_indices[0].index_type() == IndexType::Cagra {
+        return Err(Error::Index {
+            message: "optimizing vector index: CAGRA index does not support incremental indexing, please rebuild the index".to_string(),
+            location: location!(),
+        });
+    }
+
     // try cast to v1 IVFIndex,
     // fallback to v2 IVFIndex if it's not v1 IVFIndex
     if !existing_indices[0].as_any().is::<IVFIndex>() {
@@ -60,7 +60,7 @@ use crate::io::deletion::read_dataset_deletion_file;
 use crate::session::Session;
 use crate::Dataset;
 
-mod conflict_resolver;
+pub(crate) mod conflict_resolver;
 #[cfg(all(feature = "dynamodb_tests", test))]
 mod dynamodb;
 #[cfg(test)]
@@ -574,7 +574,7 @@ pub(crate) async fn do_commit_detached_transaction(
                 Transaction::restore_old_manifest(
                     object_store,
                     commit_handler,
-                    &dataset.base,
+                    &dataset.manifest_base(),
                     version,
                     write_config,
                     &transaction_file,
@@ -604,7 +604,7 @@ pub(crate) async fn do_commit_detached_transaction(
         let result = write_manifest_file(
             object_store,
             commit_handler,
-            &dataset.base,
+            &dataset.manifest_base(),
             &mut manifest,
             if indices.is_empty() {
                 None
@@ -790,7 +790,7 @@ pub(crate) async fn commit_transaction(
                 Transaction::restore_old_manifest(
                     object_store,
                     commit_handler,
-                    &dataset.base,
+                    &dataset.manifest_base(),
                     version,
                     write_config,
                     &transaction_file,
@@ -827,7 +827,7 @@ pub(crate) async fn commit_transaction(
         let result = write_manifest_file(
             object_store,
             commit_handler,
-            &dataset.base,
+            &dataset.manifest_base(),
             &mut manifest,
             if indices.is_empty() {
                 None
@@ -842,7 +842,8 @@ pub(crate) async fn commit_transaction(
         match result {
             Ok(manifest_location) => {
                 // Cache both the transaction file and manifest
-                let cache_path = transaction_file_cache_path(&dataset.base, target_version);
+                let cache_path =
+                    transaction_file_cache_path(&dataset.manifest_base(), target_version);
                 dataset
                     .session()
                     .file_metadata_cache
@@ -853,7 +854,7 @@ pub(crate) async fn commit_transaction(
                 );
                 if !indices.is_empty() {
                     dataset.session().index_cache.insert_metadata(
-                        dataset.base.as_ref(),
+                        dataset.manifest_base().as_ref(),
                         target_version,
                         Arc::new(indices),
                     );
@@ -1390,6 +1391,7 @@ mod tests {
                 deletion_file: None,
                 row_id_meta: None,
                 physical_rows: None,
+                partition_values: vec![],
             },
             Fragment {
                 id: 1,
@@ -1400,6 +1402,7 @@ mod tests {
                 deletion_file: None,
                 row_id_meta: None,
                 physical_rows: None,
+                partition_values: vec![],
             },
         ];
 
@@ -1431,6 +1434,7 @@ mod tests {
                 deletion_file: None,
                 row_id_meta: None,
                 physical_rows: None,
+                partition_values: vec![],
             },
             Fragment {
                 id: 1,
@@ -1441,6 +1445,7 @@ mod tests {
                 deletion_file: None,
                 row_id_meta: None,
                 physical_rows: None,
+                partition_values: vec![],
             },
         ];
         assert_eq!(manifest.fragments.as_ref(), &expected_fragments);
@@ -5,9 +5,11 @@
 //!
 //! WARNING: Internal API with no stability guarantees.
 
+mod aggregate;
 mod filter;
 pub mod filtered_read;
 pub mod fts;
+mod fusion;
 pub(crate) mod knn;
 mod optimizer;
 mod projection;
@@ -20,8 +22,10 @@ mod take;
 pub mod testing;
 pub mod utils;
 
+pub use aggregate::PrecomputedAggregateExec;
 pub use filter::LanceFilterExec;
-pub use knn::{ANNIvfPartitionExec, ANNIvfSubIndexExec, KNNVectorDistanceExec};
+pub use fusion::{HybridFusionExec, Reranker, RELEVANCE_SCORE_COL};
+pub use knn::{ANNIvfPartitionExec, ANNIvfSubIndexExec, KNNVectorDistanceExec, QUERY_INDEX_COLUMN};
 pub use lance_datafusion::planner::Planner;
 pub use lance_index::scalar::expression::FilterPlan;
 pub use optimizer::get_physical_optimizer;
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PlanProperties, Statistics,
+};
+use datafusion_physical_expr::EquivalenceProperties;
+
+/// An aggregate that was answered without scanning any data.
+///
+/// Each aggregate is computed up front from dataset metadata (e.g. fragment row
+/// counts) or from a scalar index (e.g. the min / max of a btree) and this node
+/// emits the single resulting row.  `sources` describes where each value came
+/// from and is only used for display.
+#[derive(Debug)]
+pub struct PrecomputedAggregateExec {
+    batch: RecordBatch,
+    sources: Vec<String>,
+    properties: PlanProperties,
+}
+
+impl PrecomputedAggregateExec {
+    pub fn new(batch: RecordBatch, sources: Vec<String>) -> Self {
+        let properties = PlanProperties::new(
+            EquivalenceProperties::new(batch.schema()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+        Self {
+            batch,
+            sources,
+            properties,
+        }
+    }
+
+    fn describe(&self, separator: &str) -> String {
+        self.batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(&self.sources)
+            .map(|(field, source)| format!("{}={}", field.name(), source))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+impl DisplayAs for PrecomputedAggregateExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "PrecomputedAggregate: {}", self.describe(", "))
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "PrecomputedAggregate\n{}", self.describe("\n"))
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for PrecomputedAggregateExec {
+    fn name(&self) -> &str {
+        "PrecomputedAggregateExec"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.batch.schema()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !children.is_empty() {
+            return Err(DataFusionError::Internal(
+                "PrecomputedAggregateExec does not have children".into(),
+            ));
+        }
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<datafusion::execution::TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "PrecomputedAggregateExec has a single partition but partition {} was requested",
+                partition
+            )));
+        }
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.batch.schema(),
+            futures::stream::iter(vec![Ok(self.batch.clone())]),
+        )))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&self.batch.schema()))
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.properties
+    }
+}
@@ -5,16 +5,22 @@ use std::iter::Peekable;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{ops::Range, sync::Arc};
 
-use arrow_schema::SchemaRef;
+use arrow_schema::{DataType, Schema as ArrowSchema, SchemaRef};
 use async_recursion::async_recursion;
+use datafusion::common::{Column, DFSchema};
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::execution::{SendableRecordBatchStream, TaskContext};
-use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
+use datafusion::logical_expr::interval_arithmetic::{Interval, NullableInterval};
+use datafusion::logical_expr::Expr;
+use datafusion::optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext};
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
     execution_plan::{Boundedness, EmissionType},
     DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties,
 };
+use datafusion::scalar::ScalarValue;
 use datafusion_physical_expr::{EquivalenceProperties, Partitioning, PhysicalExpr};
 use datafusion_physical_plan::metrics::{BaselineMetrics, Count, Time};
 use futures::stream::BoxStream;
@@ -27,6 +33,7 @@ use lance_datafusion::utils::{
     ExecutionPlanMetricsSetExt, FRAGMENTS_SCANNED_METRIC, RANGES_SCANNED_METRIC,
     ROWS_SCANNED_METRIC, TASK_WAIT_TIME_METRIC,
 };
+use lance_file::v2::reader::PageStatistics;
 use lance_index::scalar::expression::{FilterPlan, IndexExprResult, ScalarIndexExpr};
 use lance_index::{DatasetIndexExt, ScalarIndexCriteria};
 use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
@@ -413,15 +420,15 @@ impl FilteredReadStream {
         let mut _filtered_range_offset = Some(0);
 
         let planner = Planner::new(output_schema);
-        let refine_filter = options
-            .filter_plan
-            .refine_expr
-            .map(|refine_expr| planner.create_physical_expr(&refine_expr))
+        let refine_expr = options.filter_plan.refine_expr;
+        let full_expr = options.filter_plan.full_expr;
+        let refine_filter = refine_expr
+            .as_ref()
+            .map(|refine_expr| planner.create_physical_expr(refine_expr))
             .transpose()?;
-        let full_filter = options
-            .filter_plan
-            .full_expr
-            .map(|full_expr| planner.create_physical_expr(&full_expr))
+        let full_filter = full_expr
+            .as_ref()
+            .map(|full_expr| planner.create_physical_expr(full_expr))
             .transpose()?;
 
         for (
@@ -447,6 +454,7 @@ impl FilteredReadStream {
             // By default we assume we will need to apply the full filter
             // This will get refined if we have an exact match
             let mut filter = &full_filter;
+            let mut filter_expr = &full_expr;
 
             let mut to_read: Vec<Range<u64>> = if let Some(evaluated_index) = evaluated_index {
                 if evaluated_index
@@ -463,6 +471,7 @@ impl FilteredReadStream {
 
                             // Also, with an exact match, we only need to apply the refine filter
                             filter = &refine_filter;
+                            filter_expr = &refine_expr;
 
                             let valid_ranges = row_id_sequence.mask_to_offset_ranges(row_id_mask);
                             if let Some(deletion_vector) = &deletion_vector {
@@ -542,6 +551,21 @@ impl FilteredReadStream {
                 to_read = Self::trim_ranges(to_read, range_start..range_end, range_before_filter);
             }
 
+            // This must happen after trimming since the scan range counts rows before the
+            // filter is applied
+            if let Some(filter_expr) = filter_expr {
+                if !to_read.is_empty() {
+                    to_read = Self::prune_with_page_statistics(
+                        dataset,
+                        &fragment,
+                        filter_expr,
+                        to_read,
+                        &scan_scheduler,
+                    )
+                    .await?;
+                }
+            }
+
             if !to_read.is_empty() {
                 global_metrics
                     .rows_scanned
@@ -573,7 +597,7 @@ impl FilteredReadStream {
                 });
             } else {
                 log::trace!(
-                    "Skipping fragment {} because it was outside the scan range",
+                    "Skipping fragment {} because it was outside the scan range or no pages could match the filter",
                     fragment.id()
                 );
             }
@@ -582,6 +606,140 @@ impl FilteredReadStream {
         Ok(scoped_fragments)
     }
 
+    /// Removes any rows from `to_read` that are in pages which cannot match `filter`
+    ///
+    /// Page statistics are only available for non-nested columns in 2.1+ files.  If statistics
+    /// are not available for a column then no guarantees are made about the column.
+    async fn prune_with_page_statistics(
+        dataset: &Dataset,
+        fragment: &FileFragment,
+        filter: &Expr,
+        to_read: Vec<Range<u64>>,
+        scan_scheduler: &Arc<ScanScheduler>,
+    ) -> Result<Vec<Range<u64>>> {
+        let column_names = filter
+            .column_refs()
+            .into_iter()
+            .map(|column| column.name.as_str())
+            .filter(|name| {
+                dataset
+                    .schema()
+                    .field(name)
+                    .is_some_and(|field| !field.data_type().is_nested())
+            })
+            .collect::<Vec<_>>();
+        if column_names.is_empty() {
+            return Ok(to_read);
+        }
+        let filter_schema = dataset.schema().project(&column_names)?;
+
+        let reader = fragment
+            .open(
+                &filter_schema,
+                FragReadConfig::default().with_scan_scheduler(scan_scheduler.clone()),
+            )
+            .await?;
+        let mut column_stats = Vec::with_capacity(filter_schema.fields.len());
+        for field in &filter_schema.fields {
+            if let Some(page_stats) = reader.page_statistics(field)? {
+                column_stats.push((field, page_stats));
+            }
+        }
+        if column_stats.is_empty() {
+            return Ok(to_read);
+        }
+
+        let df_schema = Arc::new(DFSchema::try_from(ArrowSchema::from(&filter_schema))?);
+        let props = ExecutionProps::new();
+        let mut simplifier =
+            ExprSimplifier::new(SimplifyContext::new(&props).with_schema(df_schema));
+
+        // Split the fragment at every page boundary of every column so that each zone lies
+        // within a single page of each column
+        let mut boundaries = column_stats
+            .iter()
+            .flat_map(|(_, pages)| pages.iter().map(|page| page.rows.end))
+            .collect::<Vec<_>>();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let mut page_indices = vec![0; column_stats.len()];
+        let mut may_match: Vec<Range<u64>> = Vec::new();
+        let mut zone_start = 0;
+        for zone_end in boundaries {
+            let mut guarantees = Vec::with_capacity(column_stats.len());
+            for ((field, pages), page_idx) in column_stats.iter().zip(page_indices.iter_mut()) {
+                while pages
+                    .get(*page_idx)
+                    .is_some_and(|page| page.rows.end <= zone_start)
+                {
+                    *page_idx += 1;
+                }
+                let guarantee = pages
+                    .get(*page_idx)
+                    .and_then(|page| Self::page_guarantee(page, &field.data_type()));
+                if let Some(guarantee) = guarantee {
+                    let column = Column::new_unqualified(field.name.clone());
+                    guarantees.push((Expr::Column(column), guarantee));
+                }
+            }
+            simplifier = simplifier.with_guarantees(guarantees);
+            let can_skip = matches!(
+                simplifier.simplify(filter.clone()),
+                Ok(Expr::Literal(ScalarValue::Boolean(Some(false))))
+            );
+            if !can_skip {
+                match may_match.last_mut() {
+                    Some(last) if last.end == zone_start => last.end = zone_end,
+                    _ => may_match.push(zone_start..zone_end),
+                }
+            }
+            zone_start = zone_end;
+        }
+        // Any rows not covered by the page statistics may match
+        match may_match.last_mut() {
+            Some(last) if last.end == zone_start => last.end = u64::MAX,
+            _ => may_match.push(zone_start..u64::MAX),
+        }
+
+        Ok(Self::intersect_ranges(to_read, &may_match))
+    }
+
+    fn page_guarantee(page: &PageStatistics, data_type: &DataType) -> Option<NullableInterval> {
+        if page.null_count == page.rows.end - page.rows.start {
+            return Some(NullableInterval::Null {
+                datatype: data_type.clone(),
+            });
+        }
+        let (min, max) = page.bounds.clone()?;
+        let values = Interval::try_new(min, max).ok()?;
+        if page.null_count == 0 {
+            Some(NullableInterval::NotNull { values })
+        } else {
+            Some(NullableInterval::MaybeNull { values })
+        }
+    }
+
+    /// Intersects two sorted lists of non-overlapping ranges
+    fn intersect_ranges(ranges: Vec<Range<u64>>, other: &[Range<u64>]) -> Vec<Range<u64>> {
+        let mut intersected = Vec::with_capacity(ranges.len());
+        let mut other_iter = other.iter().peekable();
+        for range in ranges {
+            while let Some(other_range) = other_iter.peek() {
+                let start = range.start.max(other_range.start);
+                let end = range.end.min(other_range.end);
+                if start < end {
+                    intersected.push(start..end);
+                }
+                if other_range.end > range.end {
+                    break;
+                }
+                other_iter.next();
+            }
+        }
+        intersected
+    }
+
     fn filter_deleted_rows(
         ranges: Vec<Range<u64>>,
         deletion_vector: &Arc<DeletionVector>,
@@ -1161,6 +1319,10 @@ impl ExecutionPlan for FilteredReadExec {
     ) -> DataFusionResult<SendableRecordBatchStream> {
         Ok(self.obtain_stream(partition))
     }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
 }
 
 #[cfg(test)]
@@ -1173,6 +1335,7 @@ mod tests {
     use itertools::Itertools;
     use lance_core::datatypes::OnMissing;
     use lance_datagen::{array, r#gen, BatchCount, RowCount};
+    use lance_file::version::LanceFileVersion;
     use lance_index::{
         optimize::OptimizeOptions,
         scalar::{expression::PlannerIndexExt, ScalarIndexParams},
@@ -1529,6 +1692,55 @@ mod tests {
         fixture.test_plan(options, &u32s(vec![250..400])).await;
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_filter_page_statistics() {
+        for (version, expected_rows_scanned) in
+            [(LanceFileVersion::V2_0, 400), (LanceFileVersion::V2_1, 100)]
+        {
+            let tmp_path = tempfile::tempdir().unwrap();
+            let data = gen()
+                .col("x", array::step::<UInt32Type>())
+                .into_reader_rows(RowCount::from(100), BatchCount::from(4));
+            let dataset = Dataset::write(
+                data,
+                tmp_path.path().to_str().unwrap(),
+                Some(WriteParams {
+                    max_rows_per_file: 100,
+                    data_storage_version: Some(version),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+            let dataset = Arc::new(dataset);
+
+            let planner = Planner::new(Arc::new(dataset.schema().into()));
+            let filter = planner.parse_filter("x >= 350").unwrap();
+            let index_info = dataset.scalar_index_info().await.unwrap();
+            let filter_plan = planner
+                .create_filter_plan(filter, &index_info, false)
+                .unwrap();
+            let options =
+                FilteredReadOptions::basic_full_read(&dataset).with_filter_plan(filter_plan);
+            let plan = FilteredReadExec::try_new(dataset.clone(), options).unwrap();
+
+            let stream = plan.execute(0, Arc::new(TaskContext::default())).unwrap();
+            let schema = stream.schema();
+            let batches = stream.try_collect::<Vec<_>>().await.unwrap();
+            let batch = concat_batches(&schema, &batches).unwrap();
+            assert_eq!(batch.column(0).as_ref(), u32s(vec![350..400]).as_ref());
+
+            // 2.1 files have page statistics and so only the last fragment is read
+            let rows_scanned = plan
+                .metrics()
+                .unwrap()
+                .sum_by_name(ROWS_SCANNED_METRIC)
+                .unwrap()
+                .as_usize();
+            assert_eq!(rows_scanned, expected_rows_scanned);
+        }
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_filter_scalar_index() {
         let fixture = Arc::new(TestFixture::new().await);
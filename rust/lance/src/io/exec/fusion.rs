@@ -0,0 +1,364 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Fusion of vector search and full text search results into a single ranking
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::AsArray;
+use arrow::datatypes::{Float32Type, UInt64Type};
+use arrow_array::{Float32Array, RecordBatch, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use datafusion::common::Statistics;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    collect, DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties,
+};
+use datafusion_physical_expr::{EquivalenceProperties, Partitioning};
+use futures::stream;
+use lance_core::{ROW_ID, ROW_ID_FIELD};
+use lance_index::scalar::inverted::SCORE_COL;
+use lance_index::vector::DIST_COL;
+
+/// The column holding the fused relevance of a hybrid search result, higher is better
+pub const RELEVANCE_SCORE_COL: &str = "_relevance_score";
+
+/// How the results of the vector search and the full text search of a hybrid
+/// search are combined into a single ranking
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reranker {
+    /// Reciprocal rank fusion
+    ///
+    /// Each result scores `1 / (k + rank)` in each search it was found by, where
+    /// rank starts at 1.  This only looks at the order of the results, so the
+    /// distances and scores don't have to be comparable.
+    ReciprocalRankFusion { k: f32 },
+    /// Weighted sum of the normalized distance and score
+    ///
+    /// The distances and the scores are each min-max normalized to `[0, 1]`
+    /// (distances are flipped so that closer is higher) and combined as
+    /// `vector_weight * distance + (1 - vector_weight) * score`.  A result that
+    /// was only found by one of the searches gets 0 for the other.
+    Linear { vector_weight: f32 },
+}
+
+impl Default for Reranker {
+    fn default() -> Self {
+        Self::ReciprocalRankFusion { k: 60.0 }
+    }
+}
+
+impl std::fmt::Display for Reranker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReciprocalRankFusion { k } => write!(f, "rrf(k={})", k),
+            Self::Linear { vector_weight } => write!(f, "linear(vector_weight={})", vector_weight),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FusedResult {
+    distance: Option<f32>,
+    score: Option<f32>,
+    relevance: f32,
+}
+
+impl Reranker {
+    /// Fuse the `(row id, distance)` results of a vector search with the
+    /// `(row id, score)` results of a full text search.
+    fn fuse(
+        &self,
+        mut vector_results: Vec<(u64, f32)>,
+        mut text_results: Vec<(u64, f32)>,
+    ) -> HashMap<u64, FusedResult> {
+        // Best results first
+        vector_results.sort_by(|a, b| a.1.total_cmp(&b.1));
+        text_results.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let mut fused = HashMap::<u64, FusedResult>::new();
+        for (row_id, distance) in &vector_results {
+            fused.entry(*row_id).or_default().distance = Some(*distance);
+        }
+        for (row_id, score) in &text_results {
+            fused.entry(*row_id).or_default().score = Some(*score);
+        }
+
+        match self {
+            Self::ReciprocalRankFusion { k } => {
+                for results in [&vector_results, &text_results] {
+                    for (rank, (row_id, _)) in results.iter().enumerate() {
+                        fused.get_mut(row_id).unwrap().relevance += 1.0 / (k + rank as f32 + 1.0);
+                    }
+                }
+            }
+            Self::Linear { vector_weight } => {
+                for (row_id, normalized) in normalize(&vector_results, true) {
+                    fused.get_mut(&row_id).unwrap().relevance += vector_weight * normalized;
+                }
+                for (row_id, normalized) in normalize(&text_results, false) {
+                    fused.get_mut(&row_id).unwrap().relevance += (1.0 - vector_weight) * normalized;
+                }
+            }
+        }
+        fused
+    }
+}
+
+/// Min-max normalize the values to `[0, 1]`, flipping them if lower is better
+fn normalize(results: &[(u64, f32)], lower_is_better: bool) -> Vec<(u64, f32)> {
+    let (min, max) = results
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), (_, v)| {
+            (min.min(*v), max.max(*v))
+        });
+    results
+        .iter()
+        .map(|(row_id, value)| {
+            let normalized = if max > min {
+                (value - min) / (max - min)
+            } else {
+                1.0
+            };
+            let normalized = if lower_is_better && max > min {
+                1.0 - normalized
+            } else {
+                normalized
+            };
+            (*row_id, normalized)
+        })
+        .collect()
+}
+
+/// Combines the results of a vector search (`_rowid`, `_distance`) and a full
+/// text search (`_rowid`, `_score`) with a [`Reranker`].
+///
+/// The output has the `_rowid`, `_distance`, `_score` and `_relevance_score` of
+/// every row found by either search, ordered by descending relevance.  The
+/// distance (score) is null for rows not found by the vector (text) search.
+#[derive(Debug)]
+pub struct HybridFusionExec {
+    vector_input: Arc<dyn ExecutionPlan>,
+    text_input: Arc<dyn ExecutionPlan>,
+    reranker: Reranker,
+    properties: PlanProperties,
+}
+
+lazy_static::lazy_static! {
+    static ref FUSION_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        ROW_ID_FIELD.clone(),
+        Field::new(DIST_COL, DataType::Float32, true),
+        Field::new(SCORE_COL, DataType::Float32, true),
+        Field::new(RELEVANCE_SCORE_COL, DataType::Float32, false),
+    ]));
+}
+
+impl HybridFusionExec {
+    pub fn new(
+        vector_input: Arc<dyn ExecutionPlan>,
+        text_input: Arc<dyn ExecutionPlan>,
+        reranker: Reranker,
+    ) -> Self {
+        let properties = PlanProperties::new(
+            EquivalenceProperties::new(FUSION_SCHEMA.clone()),
+            Partitioning::RoundRobinBatch(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+        Self {
+            vector_input,
+            text_input,
+            reranker,
+            properties,
+        }
+    }
+
+    async fn collect_results(
+        input: Arc<dyn ExecutionPlan>,
+        column: &str,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<Vec<(u64, f32)>> {
+        let batches = collect(input, context).await?;
+        let mut results = Vec::new();
+        for batch in batches {
+            let row_ids = batch
+                .column_by_name(ROW_ID)
+                .ok_or_else(|| missing_column(ROW_ID))?
+                .as_primitive::<UInt64Type>();
+            let values = batch
+                .column_by_name(column)
+                .ok_or_else(|| missing_column(column))?
+                .as_primitive::<Float32Type>();
+            results.extend(
+                row_ids
+                    .values()
+                    .iter()
+                    .zip(values.iter())
+                    .filter_map(|(row_id, value)| value.map(|value| (*row_id, value))),
+            );
+        }
+        Ok(results)
+    }
+
+    async fn fuse(
+        vector_input: Arc<dyn ExecutionPlan>,
+        text_input: Arc<dyn ExecutionPlan>,
+        reranker: Reranker,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<RecordBatch> {
+        let (vector_results, text_results) = futures::try_join!(
+            Self::collect_results(vector_input, DIST_COL, context.clone()),
+            Self::collect_results(text_input, SCORE_COL, context),
+        )?;
+
+        let mut fused = reranker
+            .fuse(vector_results, text_results)
+            .into_iter()
+            .collect::<Vec<_>>();
+        fused.sort_by(|(a_id, a), (b_id, b)| {
+            b.relevance
+                .total_cmp(&a.relevance)
+                .then_with(|| a_id.cmp(b_id))
+        });
+
+        Ok(RecordBatch::try_new(
+            FUSION_SCHEMA.clone(),
+            vec![
+                Arc::new(UInt64Array::from_iter_values(
+                    fused.iter().map(|(row_id, _)| *row_id),
+                )),
+                Arc::new(Float32Array::from_iter(
+                    fused.iter().map(|(_, result)| result.distance),
+                )),
+                Arc::new(Float32Array::from_iter(
+                    fused.iter().map(|(_, result)| result.score),
+                )),
+                Arc::new(Float32Array::from_iter_values(
+                    fused.iter().map(|(_, result)| result.relevance),
+                )),
+            ],
+        )?)
+    }
+}
+
+fn missing_column(column: &str) -> DataFusionError {
+    DataFusionError::Internal(format!(
+        "HybridFusionExec input is missing the {} column",
+        column
+    ))
+}
+
+impl DisplayAs for HybridFusionExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "HybridFusion: reranker={}", self.reranker)
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "HybridFusion\nreranker={}", self.reranker)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for HybridFusionExec {
+    fn name(&self) -> &str {
+        "HybridFusionExec"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        FUSION_SCHEMA.clone()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.vector_input, &self.text_input]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 2 {
+            return Err(DataFusionError::Internal(
+                "HybridFusionExec: invalid number of children".into(),
+            ));
+        }
+        let text_input = children.pop().unwrap();
+        let vector_input = children.pop().unwrap();
+        Ok(Arc::new(Self::new(vector_input, text_input, self.reranker)))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "HybridFusionExec has a single partition but partition {} was requested",
+                partition
+            )));
+        }
+        let batch = Self::fuse(
+            self.vector_input.clone(),
+            self.text_input.clone(),
+            self.reranker,
+            context,
+        );
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            FUSION_SCHEMA.clone(),
+            stream::once(batch),
+        )))
+    }
+
+    fn statistics(&self) -> DataFusionResult<Statistics> {
+        Ok(Statistics::new_unknown(&FUSION_SCHEMA))
+    }
+
+    fn properties(&self) -> &PlanProperties {
+        &self.properties
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let reranker = Reranker::ReciprocalRankFusion { k: 1.0 };
+        let fused = reranker.fuse(
+            vec![(1, 0.5), (2, 0.1), (3, 0.9)],
+            vec![(3, 2.0), (4, 10.0)],
+        );
+        assert_eq!(fused.len(), 4);
+        // Row 2 is the closest vector, row 4 the best text match
+        assert_eq!(fused[&2].relevance, 1.0 / 2.0);
+        assert_eq!(fused[&4].relevance, 1.0 / 2.0);
+        assert_eq!(fused[&1].relevance, 1.0 / 3.0);
+        assert_eq!(fused[&3].relevance, 1.0 / 4.0 + 1.0 / 3.0);
+        assert_eq!(fused[&3].distance, Some(0.9));
+        assert_eq!(fused[&3].score, Some(2.0));
+        assert_eq!(fused[&4].distance, None);
+    }
+
+    #[test]
+    fn test_linear_fusion() {
+        let reranker = Reranker::Linear {
+            vector_weight: 0.25,
+        };
+        let fused = reranker.fuse(vec![(1, 0.0), (2, 1.0), (3, 0.5)], vec![(3, 4.0), (4, 2.0)]);
+        assert_eq!(fused[&1].relevance, 0.25);
+        assert_eq!(fused[&2].relevance, 0.0);
+        assert_eq!(fused[&3].relevance, 0.25 * 0.5 + 0.75);
+        assert_eq!(fused[&4].relevance, 0.0);
+    }
+}
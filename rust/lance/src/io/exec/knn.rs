@@ -290,8 +290,23 @@ lazy_static::lazy_static! {
         Field::new(PART_ID_COLUMN, DataType::List(Field::new("item", DataType::UInt32, false).into()), false),
         Field::new(INDEX_UUID_COLUMN, DataType::Utf8, false),
     ]));
+
+    pub static ref KNN_BATCH_INDEX_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(QUERY_INDEX_COLUMN, DataType::UInt32, false),
+        Field::new(DIST_COL, DataType::Float32, true),
+        ROW_ID_FIELD.clone(),
+    ]));
+
+    pub static ref KNN_BATCH_PARTITION_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(QUERY_INDEX_COLUMN, DataType::UInt32, false),
+        Field::new(PART_ID_COLUMN, DataType::List(Field::new("item", DataType::UInt32, false).into()), false),
+        Field::new(INDEX_UUID_COLUMN, DataType::Utf8, false),
+    ]));
 }
 
+/// The column identifying which query of a batched vector search a result belongs to
+pub const QUERY_INDEX_COLUMN: &str = "query_index";
+
 pub fn new_knn_exec(
     dataset: Arc<Dataset>,
     indices: &[Index],
@@ -315,6 +330,37 @@ pub fn new_knn_exec(
     Ok(Arc::new(sub_index))
 }
 
+/// Like [`new_knn_exec`] but searches for the nearest neighbors of each of `keys`.
+///
+/// The partitions are ranked for every query and then each partition is loaded once
+/// and searched for all of the queries that probe it.  The prefilter is shared by all
+/// of the queries.
+pub fn new_batch_knn_exec(
+    dataset: Arc<Dataset>,
+    indices: &[Index],
+    query: &Query,
+    keys: Vec<ArrayRef>,
+    prefilter_source: PreFilterSource,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let ivf_node = ANNIvfPartitionExec::try_new_batch(
+        dataset.clone(),
+        indices.iter().map(|idx| idx.uuid.to_string()).collect_vec(),
+        query.clone(),
+        keys.clone(),
+    )?;
+
+    let sub_index = ANNIvfSubIndexExec::try_new_batch(
+        Arc::new(ivf_node),
+        dataset,
+        indices.to_vec(),
+        query.clone(),
+        keys,
+        prefilter_source,
+    )?;
+
+    Ok(Arc::new(sub_index))
+}
+
 /// [ExecutionPlan] to execute the find the closest IVF partitions.
 ///
 /// It searches the partition IDs using the input query.
@@ -338,6 +384,9 @@ pub fn new_knn_exec(
 ///    "__index_uuid": String,
 /// }
 /// ```
+///
+/// When searching a batch of queries each batch has one row per query and a
+/// leading `query_index` column.
 #[derive(Debug)]
 pub struct ANNIvfPartitionExec {
     pub dataset: Arc<Dataset>,
@@ -345,6 +394,9 @@ pub struct ANNIvfPartitionExec {
     /// The vector query to execute.
     pub query: Query,
 
+    /// The query vectors of a batched search, `query.key` is ignored if set.
+    pub batch_keys: Option<Vec<ArrayRef>>,
+
     /// The UUIDs of the indices to search.
     pub index_uuids: Vec<String>,
 
@@ -375,11 +427,34 @@ impl ANNIvfPartitionExec {
         Ok(Self {
             dataset,
             query,
+            batch_keys: None,
             index_uuids,
             properties,
             metrics: ExecutionPlanMetricsSet::new(),
         })
     }
+
+    /// Find the partitions to search for each of `keys`.
+    pub fn try_new_batch(
+        dataset: Arc<Dataset>,
+        index_uuids: Vec<String>,
+        query: Query,
+        keys: Vec<ArrayRef>,
+    ) -> Result<Self> {
+        let mut exec = Self::try_new(dataset, index_uuids, query)?;
+        exec.properties = PlanProperties::new(
+            EquivalenceProperties::new(KNN_BATCH_PARTITION_SCHEMA.clone()),
+            Partitioning::RoundRobinBatch(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        );
+        exec.batch_keys = Some(keys);
+        Ok(exec)
+    }
+
+    fn num_queries(&self) -> usize {
+        self.batch_keys.as_ref().map(|keys| keys.len()).unwrap_or(1)
+    }
 }
 
 impl DisplayAs for ANNIvfPartitionExec {
@@ -393,7 +468,11 @@ impl DisplayAs for ANNIvfPartitionExec {
                     self.query.minimum_nprobes,
                     self.query.maximum_nprobes,
                     self.index_uuids.len()
-                )
+                )?;
+                if self.batch_keys.is_some() {
+                    write!(f, ", queries={}", self.num_queries())?;
+                }
+                Ok(())
             }
             DisplayFormatType::TreeRender => {
                 write!(
@@ -403,7 +482,11 @@ impl DisplayAs for ANNIvfPartitionExec {
                     self.query.minimum_nprobes,
                     self.query.maximum_nprobes,
                     self.index_uuids.len()
-                )
+                )?;
+                if self.batch_keys.is_some() {
+                    write!(f, "\nqueries={}", self.num_queries())?;
+                }
+                Ok(())
             }
         }
     }
@@ -419,12 +502,12 @@ impl ExecutionPlan for ANNIvfPartitionExec {
     }
 
     fn schema(&self) -> SchemaRef {
-        KNN_PARTITION_SCHEMA.clone()
+        self.properties.eq_properties.schema().clone()
     }
 
     fn statistics(&self) -> DataFusionResult<Statistics> {
         Ok(Statistics {
-            num_rows: Precision::Exact(self.query.minimum_nprobes),
+            num_rows: Precision::Exact(self.query.minimum_nprobes * self.num_queries()),
             ..Statistics::new_unknown(self.schema().as_ref())
         })
     }
@@ -460,6 +543,8 @@ impl ExecutionPlan for ANNIvfPartitionExec {
         _context: Arc<datafusion::execution::TaskContext>,
     ) -> DataFusionResult<SendableRecordBatchStream> {
         let query = self.query.clone();
+        let batch_keys = self.batch_keys.clone();
+        let schema = self.schema();
         let ds = self.dataset.clone();
         let metrics = Arc::new(AnnPartitionMetrics::new(&self.metrics, partition));
 
@@ -469,6 +554,8 @@ impl ExecutionPlan for ANNIvfPartitionExec {
         let stream = stream::iter(self.index_uuids.clone())
             .map(move |uuid| {
                 let query = query.clone();
+                let batch_keys = batch_keys.clone();
+                let schema = schema.clone();
                 let ds = ds.clone();
                 let metrics = metrics.clone();
                 async move {
@@ -477,27 +564,37 @@ impl ExecutionPlan for ANNIvfPartitionExec {
                         .await?;
 
                     let _timer = metrics.baseline_metrics.elapsed_compute().timer();
-                    let mut query = query.clone();
-                    if index.metric_type() == DistanceType::Cosine {
-                        let key = normalize_arrow(&query.key)?;
-                        query.key = key;
-                    };
+                    let keys = batch_keys.unwrap_or_else(|| vec![query.key.clone()]);
+                    let mut list_builder = ListBuilder::new(UInt32Builder::new())
+                        .with_field(Field::new("item", DataType::UInt32, false));
+                    for key in &keys {
+                        let mut query = query.clone();
+                        query.key = key.clone();
+                        if index.metric_type() == DistanceType::Cosine {
+                            let key = normalize_arrow(&query.key)?;
+                            query.key = key;
+                        };
 
-                    metrics.partitions_ranked.add(index.total_partitions());
+                        metrics.partitions_ranked.add(index.total_partitions());
 
-                    let partitions = index.find_partitions(&query).map_err(|e| {
-                        DataFusionError::Execution(format!("Failed to find partitions: {}", e))
-                    })?;
+                        let partitions = index.find_partitions(&query).map_err(|e| {
+                            DataFusionError::Execution(format!("Failed to find partitions: {}", e))
+                        })?;
+                        list_builder.append_value(partitions.iter());
+                    }
 
-                    let mut list_builder = ListBuilder::new(UInt32Builder::new())
-                        .with_field(Field::new("item", DataType::UInt32, false));
-                    list_builder.append_value(partitions.iter());
-                    let partition_col = list_builder.finish();
-                    let uuid_col = StringArray::from(vec![uuid.as_str()]);
-                    let batch = RecordBatch::try_new(
-                        KNN_PARTITION_SCHEMA.clone(),
-                        vec![Arc::new(partition_col), Arc::new(uuid_col)],
-                    )?;
+                    let partition_col = Arc::new(list_builder.finish()) as ArrayRef;
+                    let uuid_col =
+                        Arc::new(StringArray::from(vec![uuid.as_str(); keys.len()])) as ArrayRef;
+                    let columns = if schema.field_with_name(QUERY_INDEX_COLUMN).is_ok() {
+                        let query_index_col =
+                            Arc::new(UInt32Array::from_iter_values(0..keys.len() as u32))
+                                as ArrayRef;
+                        vec![query_index_col, partition_col, uuid_col]
+                    } else {
+                        vec![partition_col, uuid_col]
+                    };
+                    let batch = RecordBatch::try_new(schema, columns)?;
                     metrics.baseline_metrics.record_output(batch.num_rows());
                     Ok::<_, DataFusionError>(batch)
                 }
@@ -553,6 +650,9 @@ pub struct ANNIvfSubIndexExec {
     /// Vector Query.
     query: Query,
 
+    /// The query vectors of a batched search, `query.key` is ignored if set.
+    batch_keys: Option<Vec<ArrayRef>>,
+
     /// Prefiltering input
     prefilter_source: PreFilterSource,
 
@@ -590,11 +690,48 @@ impl ANNIvfSubIndexExec {
             dataset,
             indices,
             query,
+            batch_keys: None,
             prefilter_source,
             properties,
             metrics: ExecutionPlanMetricsSet::new(),
         })
     }
+
+    /// Search for the nearest neighbors of each of `keys`.
+    ///
+    /// The input must be an [`ANNIvfPartitionExec`] for the same batch of queries.  The
+    /// output has the `query_index`, `_distance` and `_rowid` of the `k` nearest
+    /// neighbors of each query, ordered by query and then by distance.
+    ///
+    /// Unlike a single query, exactly `minimum_nprobes` partitions are searched for each
+    /// query.
+    pub fn try_new_batch(
+        input: Arc<dyn ExecutionPlan>,
+        dataset: Arc<Dataset>,
+        indices: Vec<Index>,
+        query: Query,
+        keys: Vec<ArrayRef>,
+        prefilter_source: PreFilterSource,
+    ) -> Result<Self> {
+        if input.schema().field_with_name(QUERY_INDEX_COLUMN).is_err() {
+            return Err(Error::Index {
+                message: format!(
+                    "ANNSubIndexExec node: input schema does not have \"{}\" column",
+                    QUERY_INDEX_COLUMN
+                ),
+                location: location!(),
+            });
+        }
+        let mut exec = Self::try_new(input, dataset, indices, query, prefilter_source)?;
+        exec.properties = PlanProperties::new(
+            EquivalenceProperties::new(KNN_BATCH_INDEX_SCHEMA.clone()),
+            Partitioning::RoundRobinBatch(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+        exec.batch_keys = Some(keys);
+        Ok(exec)
+    }
 }
 
 impl DisplayAs for ANNIvfSubIndexExec {
@@ -607,7 +744,11 @@ impl DisplayAs for ANNIvfSubIndexExec {
                     self.indices[0].name,
                     self.query.k * self.query.refine_factor.unwrap_or(1) as usize,
                     self.indices.len()
-                )
+                )?;
+                if let Some(keys) = &self.batch_keys {
+                    write!(f, ", queries={}", keys.len())?;
+                }
+                Ok(())
             }
             DisplayFormatType::TreeRender => {
                 write!(
@@ -616,7 +757,11 @@ impl DisplayAs for ANNIvfSubIndexExec {
                     self.indices[0].name,
                     self.query.k * self.query.refine_factor.unwrap_or(1) as usize,
                     self.indices.len()
-                )
+                )?;
+                if let Some(keys) = &self.batch_keys {
+                    write!(f, "\nqueries={}", keys.len())?;
+                }
+                Ok(())
             }
         }
     }
@@ -677,6 +822,149 @@ impl ANNIvfEarlySearchResults {
 }
 
 impl ANNIvfSubIndexExec {
+    fn prefilter(
+        &self,
+        partition: usize,
+        context: Arc<datafusion::execution::context::TaskContext>,
+    ) -> DataFusionResult<Arc<DatasetPreFilter>> {
+        let prefilter_loader = match &self.prefilter_source {
+            PreFilterSource::FilteredRowIds(src_node) => {
+                let stream = src_node.execute(partition, context)?;
+                Some(Box::new(FilteredRowIdsToPrefilter(stream)) as Box<dyn FilterLoader>)
+            }
+            PreFilterSource::ScalarIndexQuery(src_node) => {
+                let stream = src_node.execute(partition, context)?;
+                Some(Box::new(SelectionVectorToPrefilter(stream)) as Box<dyn FilterLoader>)
+            }
+            PreFilterSource::None => None,
+        };
+
+        Ok(Arc::new(DatasetPreFilter::new(
+            self.dataset.clone(),
+            &self.indices,
+            prefilter_loader,
+        )))
+    }
+
+    /// Search a batch of queries, see [`Self::try_new_batch`]
+    fn execute_batch(
+        &self,
+        keys: Vec<ArrayRef>,
+        partition: usize,
+        context: Arc<datafusion::execution::context::TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let input_stream = self.input.execute(partition, context.clone())?;
+        let schema = self.schema();
+        let query = self.query.clone();
+        let ds = self.dataset.clone();
+        let pre_filter = self.prefilter(partition, context)?;
+        let metrics = Arc::new(AnnIndexMetrics::new(&self.metrics, partition));
+
+        let stream = stream::once(async move {
+            // Group the queries by the partition they probe, so each partition is only
+            // loaded once
+            let mut probes = HashMap::<(String, u32), Vec<usize>>::new();
+            let input_batches = input_stream.try_collect::<Vec<_>>().await?;
+            for batch in input_batches {
+                let query_index = batch[QUERY_INDEX_COLUMN].as_primitive::<UInt32Type>();
+                let part_ids = batch[PART_ID_COLUMN].as_list::<i32>();
+                let index_uuids = batch[INDEX_UUID_COLUMN].as_string::<i32>();
+                for row in 0..batch.num_rows() {
+                    let part_ids = part_ids.value(row);
+                    let part_ids = part_ids.as_primitive::<UInt32Type>();
+                    for part_id in part_ids.values().iter().take(query.minimum_nprobes) {
+                        probes
+                            .entry((index_uuids.value(row).to_string(), *part_id))
+                            .or_default()
+                            .push(query_index.value(row) as usize);
+                    }
+                }
+            }
+            metrics.partitions_searched.add(probes.len());
+
+            let results = stream::iter(probes)
+                .map(|((index_uuid, part_id), query_indices)| {
+                    let ds = ds.clone();
+                    let query = query.clone();
+                    let keys = &keys;
+                    let pre_filter = pre_filter.clone();
+                    let metrics = metrics.clone();
+                    async move {
+                        let index = ds
+                            .open_vector_index(&query.column, &index_uuid, &metrics.index_metrics)
+                            .await?;
+                        let _timer = metrics.baseline_metrics.elapsed_compute().timer();
+                        let mut results = Vec::with_capacity(query_indices.len());
+                        for query_index in query_indices {
+                            let mut query = query.clone();
+                            query.key = keys[query_index].clone();
+                            if index.metric_type() == DistanceType::Cosine {
+                                let key = normalize_arrow(&query.key)?;
+                                query.key = key;
+                            };
+                            let batch = index
+                                .search_in_partition(
+                                    part_id as usize,
+                                    &query,
+                                    pre_filter.clone(),
+                                    &metrics.index_metrics,
+                                )
+                                .map_err(|e| {
+                                    DataFusionError::Execution(format!(
+                                        "Failed to calculate KNN: {}",
+                                        e
+                                    ))
+                                })
+                                .await?;
+                            results.push((query_index, batch));
+                        }
+                        DataFusionResult::Ok(results)
+                    }
+                })
+                .buffer_unordered(get_num_compute_intensive_cpus())
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            // Keep the k nearest neighbors of each query, across all partitions and deltas
+            let mut neighbors = vec![Vec::<(f32, u64)>::new(); keys.len()];
+            for (query_index, batch) in results.into_iter().flatten() {
+                let distances = batch[DIST_COL].as_primitive::<Float32Type>();
+                let row_ids = batch[ROW_ID].as_primitive::<UInt64Type>();
+                neighbors[query_index].extend(
+                    distances
+                        .values()
+                        .iter()
+                        .copied()
+                        .zip(row_ids.values().iter().copied()),
+                );
+            }
+            let mut query_index_builder = Vec::new();
+            let mut distance_builder = Vec::new();
+            let mut row_id_builder = Vec::new();
+            for (query_index, mut neighbors) in neighbors.into_iter().enumerate() {
+                neighbors.sort_by(|a, b| a.0.total_cmp(&b.0));
+                neighbors.truncate(query.k);
+                for (distance, row_id) in neighbors {
+                    query_index_builder.push(query_index as u32);
+                    distance_builder.push(distance);
+                    row_id_builder.push(row_id);
+                }
+            }
+            let batch = RecordBatch::try_new(
+                KNN_BATCH_INDEX_SCHEMA.clone(),
+                vec![
+                    Arc::new(UInt32Array::from(query_index_builder)),
+                    Arc::new(Float32Array::from(distance_builder)),
+                    Arc::new(UInt64Array::from(row_id_builder)),
+                ],
+            )?;
+            metrics.baseline_metrics.record_output(batch.num_rows());
+            metrics.baseline_metrics.done();
+            Ok(batch)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+
     fn late_search(
         index: Arc<dyn VectorIndex>,
         query: Query,
@@ -855,7 +1143,7 @@ impl ExecutionPlan for ANNIvfSubIndexExec {
     }
 
     fn schema(&self) -> arrow_schema::SchemaRef {
-        KNN_INDEX_SCHEMA.clone()
+        self.properties.eq_properties.schema().clone()
     }
 
     fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
@@ -899,6 +1187,7 @@ impl ExecutionPlan for ANNIvfSubIndexExec {
                 dataset: self.dataset.clone(),
                 indices: self.indices.clone(),
                 query: self.query.clone(),
+                batch_keys: self.batch_keys.clone(),
                 prefilter_source,
                 properties: self.properties.clone(),
                 metrics: ExecutionPlanMetricsSet::new(),
@@ -916,13 +1205,16 @@ impl ExecutionPlan for ANNIvfSubIndexExec {
         partition: usize,
         context: Arc<datafusion::execution::context::TaskContext>,
     ) -> DataFusionResult<datafusion::physical_plan::SendableRecordBatchStream> {
+        if let Some(keys) = &self.batch_keys {
+            return self.execute_batch(keys.clone(), partition, context);
+        }
+
         let input_stream = self.input.execute(partition, context.clone())?;
         let schema = self.schema();
         let query = self.query.clone();
         let ds = self.dataset.clone();
         let column = self.query.column.clone();
         let indices = self.indices.clone();
-        let prefilter_source = self.prefilter_source.clone();
         let metrics = Arc::new(AnnIndexMetrics::new(&self.metrics, partition));
 
         // Per-delta-index stream:
@@ -951,23 +1243,7 @@ impl ExecutionPlan for ANNIvfSubIndexExec {
                 async move { DataFusionResult::Ok(stream::iter(plan)) }
             })
             .try_flatten();
-        let prefilter_loader = match &prefilter_source {
-            PreFilterSource::FilteredRowIds(src_node) => {
-                let stream = src_node.execute(partition, context)?;
-                Some(Box::new(FilteredRowIdsToPrefilter(stream)) as Box<dyn FilterLoader>)
-            }
-            PreFilterSource::ScalarIndexQuery(src_node) => {
-                let stream = src_node.execute(partition, context)?;
-                Some(Box::new(SelectionVectorToPrefilter(stream)) as Box<dyn FilterLoader>)
-            }
-            PreFilterSource::None => None,
-        };
-
-        let pre_filter = Arc::new(DatasetPreFilter::new(
-            ds.clone(),
-            &indices,
-            prefilter_loader,
-        ));
+        let pre_filter = self.prefilter(partition, context)?;
 
         let state = Arc::new(ANNIvfEarlySearchResults::new(indices.len(), query.k));
 
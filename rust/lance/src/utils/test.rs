@@ -252,6 +252,7 @@ impl TestDatasetGenerator {
             deletion_file: None,
             row_id_meta: None,
             physical_rows: Some(batch.num_rows()),
+            partition_values: vec![],
         }
     }
 }